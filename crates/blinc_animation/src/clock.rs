@@ -0,0 +1,210 @@
+//! Time sources for the animation scheduler
+//!
+//! The scheduler measures frame deltas with a [`Clock`] instead of reading
+//! `Instant::now()` directly, so time can be controlled:
+//!
+//! - [`RealClock`] - wall-clock time (the default)
+//! - [`ManualClock`] - time that only moves when advanced, for tests and
+//!   headless runs
+//! - [`ScaledClock`] - another clock sped up or slowed down, for slow motion
+//!
+//! A global time scale ([`set_time_scale`]) additionally scales every
+//! scheduler's frame delta, which slows all animations down for debugging.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//! use blinc_animation::{AnimationScheduler, ManualClock};
+//!
+//! let clock = Arc::new(ManualClock::new());
+//! let scheduler = AnimationScheduler::with_clock(clock.clone());
+//!
+//! clock.advance(Duration::from_millis(16));
+//! scheduler.tick(); // steps animations by exactly 16ms
+//! ```
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A source of monotonic time
+pub trait Clock: Send + Sync {
+    /// Time elapsed since the clock's origin
+    fn now(&self) -> Duration;
+}
+
+/// Shared clock handle
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Wall-clock time, measured from when the clock was created
+#[derive(Debug, Clone, Copy)]
+pub struct RealClock {
+    origin: Instant,
+}
+
+impl RealClock {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+        }
+    }
+}
+
+impl Default for RealClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for RealClock {
+    fn now(&self) -> Duration {
+        self.origin.elapsed()
+    }
+}
+
+/// Time that only moves when told to
+///
+/// Share it with `Arc` between the scheduler and whatever drives it.
+#[derive(Debug, Default)]
+pub struct ManualClock {
+    now: Mutex<Duration>,
+}
+
+impl ManualClock {
+    /// Create a clock at time zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move time forward
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+
+    /// Jump to a point in time
+    ///
+    /// Moving backwards is allowed; the scheduler treats it as no time passing.
+    pub fn set(&self, now: Duration) {
+        *self.now.lock().unwrap() = now;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Another clock running at a different rate
+///
+/// Changing the scale keeps time continuous: only time elapsed after the
+/// change runs at the new rate.
+pub struct ScaledClock {
+    source: SharedClock,
+    state: Mutex<ScaleState>,
+}
+
+struct ScaleState {
+    scale: f32,
+    /// Source time at the last scale change
+    source_base: Duration,
+    /// Scaled time at the last scale change
+    scaled_base: Duration,
+}
+
+impl ScaledClock {
+    /// Run `source` at `scale` times its rate (0.25 for quarter-speed slow motion)
+    pub fn new(source: SharedClock, scale: f32) -> Self {
+        let now = source.now();
+        Self {
+            source,
+            state: Mutex::new(ScaleState {
+                scale: scale.max(0.0),
+                source_base: now,
+                scaled_base: now,
+            }),
+        }
+    }
+
+    /// Current rate
+    pub fn scale(&self) -> f32 {
+        self.state.lock().unwrap().scale
+    }
+
+    /// Change the rate from now on
+    pub fn set_scale(&self, scale: f32) {
+        let source_now = self.source.now();
+        let mut state = self.state.lock().unwrap();
+        state.scaled_base = scaled(&state, source_now);
+        state.source_base = source_now;
+        state.scale = scale.max(0.0);
+    }
+}
+
+fn scaled(state: &ScaleState, source_now: Duration) -> Duration {
+    let elapsed = source_now.saturating_sub(state.source_base);
+    let nanos = (elapsed.as_nanos() as f64 * state.scale as f64).round();
+    state.scaled_base + Duration::from_nanos(nanos as u64)
+}
+
+impl Clock for ScaledClock {
+    fn now(&self) -> Duration {
+        let source_now = self.source.now();
+        scaled(&self.state.lock().unwrap(), source_now)
+    }
+}
+
+impl std::fmt::Debug for ScaledClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScaledClock")
+            .field("scale", &self.scale())
+            .finish()
+    }
+}
+
+/// Global multiplier for animation time, stored as `f32` bits (1.0)
+static TIME_SCALE: AtomicU32 = AtomicU32::new(0x3f80_0000);
+
+/// Scale the frame delta of every scheduler
+///
+/// `0.1` plays all animations at a tenth of their speed, `0.0` freezes them.
+pub fn set_time_scale(scale: f32) {
+    TIME_SCALE.store(scale.max(0.0).to_bits(), Ordering::Relaxed);
+}
+
+/// Current global time scale (1.0 by default)
+pub fn time_scale() -> f32 {
+    f32::from_bits(TIME_SCALE.load(Ordering::Relaxed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manual_clock() {
+        let clock = ManualClock::new();
+        assert_eq!(clock.now(), Duration::ZERO);
+        clock.advance(Duration::from_millis(16));
+        clock.advance(Duration::from_millis(16));
+        assert_eq!(clock.now(), Duration::from_millis(32));
+        clock.set(Duration::from_secs(1));
+        assert_eq!(clock.now(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_scaled_clock_is_continuous() {
+        let source = Arc::new(ManualClock::new());
+        let clock = ScaledClock::new(source.clone(), 0.5);
+
+        source.advance(Duration::from_millis(100));
+        assert_eq!(clock.now(), Duration::from_millis(50));
+
+        clock.set_scale(2.0);
+        assert_eq!(clock.now(), Duration::from_millis(50));
+        source.advance(Duration::from_millis(100));
+        assert_eq!(clock.now(), Duration::from_millis(250));
+    }
+}
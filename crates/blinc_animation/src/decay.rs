@@ -0,0 +1,409 @@
+//! Inertial (decay) animation
+//!
+//! Models a value thrown with some velocity that slows down under friction,
+//! like a flung scroll view, map or dial. Optional bounds turn the overshoot
+//! into a rubber-band spring back to the nearest edge.
+//!
+//! Velocity hands off in both directions: a decay starts from a gesture's
+//! release velocity, and [`DecayAnimation::into_spring`] passes the remaining
+//! velocity on to a spring (e.g. to snap to the nearest detent).
+
+use std::marker::PhantomData;
+
+use blinc_core::Point;
+
+use crate::spring::{Spring, SpringConfig, TypedSpring};
+use crate::values::Animatable;
+
+/// Resistance applied when dragging past an edge
+///
+/// Maps how far the pointer is past the edge (`overshoot`) to how far the
+/// content should follow, approaching but never reaching `dimension` (the
+/// viewport extent along the axis). Sign is preserved.
+pub fn rubber_band(overshoot: f32, dimension: f32) -> f32 {
+    const COEFFICIENT: f32 = 0.55;
+
+    if dimension <= 0.0 {
+        return 0.0;
+    }
+    let distance = overshoot.abs();
+    let banded = (1.0 - 1.0 / (distance * COEFFICIENT / dimension + 1.0)) * dimension;
+    banded.copysign(overshoot)
+}
+
+/// Inertial motion of a single value slowed by friction
+///
+/// Velocity falls off exponentially, `v(t) = v0 * e^(-friction * t)`, and is
+/// integrated exactly so the result does not depend on the frame rate.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut decay = DecayAnimation::new(offset, release_velocity)
+///     .bounds(-max_scroll, 0.0);
+///
+/// while !decay.is_settled() {
+///     decay.step(dt);
+///     set_offset(decay.value());
+/// }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct DecayAnimation {
+    value: f32,
+    velocity: f32,
+    friction: f32,
+    rest_velocity: f32,
+    bounds: Option<(f32, f32)>,
+    bounce: SpringConfig,
+    /// Spring pulling the value back inside its bounds
+    spring: Option<Spring>,
+}
+
+impl DecayAnimation {
+    /// Friction close to a native scroll view's normal deceleration
+    pub const DEFAULT_FRICTION: f32 = 2.0;
+
+    /// Speed (units per second) below which the motion stops
+    pub const DEFAULT_REST_VELOCITY: f32 = 0.5;
+
+    /// Start at `initial` moving at `velocity` (units per second)
+    pub fn new(initial: f32, velocity: f32) -> Self {
+        Self {
+            value: initial,
+            velocity,
+            friction: Self::DEFAULT_FRICTION,
+            rest_velocity: Self::DEFAULT_REST_VELOCITY,
+            bounds: None,
+            bounce: SpringConfig::new(300.0, 30.0, 1.0),
+            spring: None,
+        }
+    }
+
+    /// Set the friction coefficient (per second; higher stops sooner)
+    pub fn friction(mut self, friction: f32) -> Self {
+        self.friction = friction.max(0.0);
+        self
+    }
+
+    /// Set the speed below which the motion is considered at rest
+    pub fn rest_velocity(mut self, rest_velocity: f32) -> Self {
+        self.rest_velocity = rest_velocity.abs();
+        self
+    }
+
+    /// Keep the value within `min..=max`, rubber-banding back when it leaves
+    pub fn bounds(mut self, min: f32, max: f32) -> Self {
+        self.bounds = Some((min.min(max), min.max(max)));
+        self
+    }
+
+    /// Spring used to pull the value back inside its bounds
+    pub fn bounce(mut self, config: SpringConfig) -> Self {
+        self.bounce = config;
+        self
+    }
+
+    /// Current value
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Current velocity (units per second)
+    pub fn velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    /// Where the value comes to rest
+    ///
+    /// Friction alone stops at `value + velocity / friction`; with bounds the
+    /// rest point is clamped to them. Useful for picking a snap point before
+    /// the motion ends.
+    pub fn projected(&self) -> f32 {
+        if let Some(spring) = &self.spring {
+            return spring.target();
+        }
+        let rest = if self.friction > 0.0 {
+            self.value + self.velocity / self.friction
+        } else if self.velocity == 0.0 {
+            self.value
+        } else {
+            f32::INFINITY.copysign(self.velocity)
+        };
+        match self.bounds {
+            Some((min, max)) => rest.clamp(min, max),
+            None => rest,
+        }
+    }
+
+    /// Whether the value is outside its bounds and springing back
+    pub fn is_rubber_banding(&self) -> bool {
+        self.spring.is_some_and(|spring| !spring.is_settled())
+    }
+
+    /// Whether the motion has stopped
+    pub fn is_settled(&self) -> bool {
+        match &self.spring {
+            Some(spring) => spring.is_settled(),
+            None => self.velocity.abs() < self.rest_velocity && self.in_bounds(),
+        }
+    }
+
+    /// Advance the simulation by `dt` seconds
+    pub fn step(&mut self, dt: f32) {
+        if self.spring.is_none() && !self.in_bounds() {
+            self.engage_bounds();
+        }
+
+        if let Some(spring) = &mut self.spring {
+            spring.step(dt);
+            self.value = spring.value();
+            self.velocity = spring.velocity();
+            return;
+        }
+
+        if self.is_settled() {
+            self.velocity = 0.0;
+            return;
+        }
+
+        if self.friction > 0.0 {
+            let decay = (-self.friction * dt).exp();
+            self.value += self.velocity * (1.0 - decay) / self.friction;
+            self.velocity *= decay;
+        } else {
+            self.value += self.velocity * dt;
+        }
+
+        if !self.in_bounds() {
+            self.engage_bounds();
+        }
+    }
+
+    /// Hand the current value and velocity to a spring heading for `target`
+    pub fn into_spring(self, target: f32, config: SpringConfig) -> Spring {
+        let mut spring = Spring::new(config, self.value).with_velocity(self.velocity);
+        spring.set_target(target);
+        spring
+    }
+
+    fn in_bounds(&self) -> bool {
+        self.bounds
+            .map_or(true, |(min, max)| self.value >= min && self.value <= max)
+    }
+
+    /// Switch to a spring towards the nearest edge, keeping the velocity
+    fn engage_bounds(&mut self) {
+        let Some((min, max)) = self.bounds else {
+            return;
+        };
+        let mut spring = Spring::new(self.bounce, self.value).with_velocity(self.velocity);
+        spring.set_target(self.value.clamp(min, max));
+        self.spring = Some(spring);
+    }
+}
+
+/// Inertial motion of any [`Animatable`] value
+///
+/// Each component decays on its own with a shared friction, so a map flung
+/// diagonally keeps its direction and each axis rubber-bands independently.
+#[derive(Clone, Debug)]
+pub struct TypedDecay<T: Animatable> {
+    axes: Vec<DecayAnimation>,
+    _value: PhantomData<T>,
+}
+
+impl<T: Animatable> TypedDecay<T> {
+    /// Start at `initial` moving at a per-component `velocity` (units per second)
+    pub fn new(initial: T, velocity: T) -> Self {
+        Self {
+            axes: initial
+                .components()
+                .into_iter()
+                .zip(velocity.components())
+                .map(|(value, velocity)| DecayAnimation::new(value, velocity))
+                .collect(),
+            _value: PhantomData,
+        }
+    }
+
+    /// Set the friction coefficient of every component
+    pub fn friction(self, friction: f32) -> Self {
+        self.map_axes(|axis| axis.friction(friction))
+    }
+
+    /// Set the speed below which each component is considered at rest
+    pub fn rest_velocity(self, rest_velocity: f32) -> Self {
+        self.map_axes(|axis| axis.rest_velocity(rest_velocity))
+    }
+
+    /// Keep each component within the matching components of `min` and `max`
+    pub fn bounds(mut self, min: T, max: T) -> Self {
+        for ((axis, min), max) in self
+            .axes
+            .iter_mut()
+            .zip(min.components())
+            .zip(max.components())
+        {
+            *axis = axis.bounds(min, max);
+        }
+        self
+    }
+
+    /// Spring used to pull components back inside their bounds
+    pub fn bounce(self, config: SpringConfig) -> Self {
+        self.map_axes(|axis| axis.bounce(config))
+    }
+
+    /// Current value
+    pub fn value(&self) -> T {
+        self.collect(DecayAnimation::value)
+    }
+
+    /// Current per-component velocity (units per second)
+    pub fn velocity(&self) -> T {
+        self.collect(DecayAnimation::velocity)
+    }
+
+    /// Where the value comes to rest (see [`DecayAnimation::projected`])
+    pub fn projected(&self) -> T {
+        self.collect(DecayAnimation::projected)
+    }
+
+    /// Whether any component is outside its bounds and springing back
+    pub fn is_rubber_banding(&self) -> bool {
+        self.axes.iter().any(DecayAnimation::is_rubber_banding)
+    }
+
+    /// Whether every component has stopped
+    pub fn is_settled(&self) -> bool {
+        self.axes.iter().all(DecayAnimation::is_settled)
+    }
+
+    /// Advance the simulation by `dt` seconds
+    pub fn step(&mut self, dt: f32) {
+        for axis in &mut self.axes {
+            axis.step(dt);
+        }
+    }
+
+    /// Hand the current value and velocity to a spring heading for `target`
+    pub fn into_spring(self, target: T, config: SpringConfig) -> TypedSpring<T> {
+        let mut spring = TypedSpring::new(config, self.value()).with_velocity(self.velocity());
+        spring.set_target(target);
+        spring
+    }
+
+    fn map_axes(mut self, f: impl Fn(DecayAnimation) -> DecayAnimation) -> Self {
+        for axis in &mut self.axes {
+            *axis = f(*axis);
+        }
+        self
+    }
+
+    fn collect(&self, f: impl Fn(&DecayAnimation) -> f32) -> T {
+        T::from_components(&self.axes.iter().map(f).collect::<Vec<_>>())
+    }
+}
+
+/// Decay for 2D positions (panning, flinging)
+pub type PointDecay = TypedDecay<Point>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(decay: &mut DecayAnimation, seconds: f32) {
+        for _ in 0..(seconds * 60.0) as usize {
+            decay.step(1.0 / 60.0);
+        }
+    }
+
+    #[test]
+    fn test_decay_comes_to_rest_at_projection() {
+        let mut decay = DecayAnimation::new(0.0, 1000.0);
+        assert!((decay.projected() - 500.0).abs() < 1e-3);
+
+        run(&mut decay, 6.0);
+        assert!(decay.is_settled());
+        assert!((decay.value() - 500.0).abs() < 1.0);
+        assert_eq!(decay.velocity(), 0.0);
+    }
+
+    #[test]
+    fn test_decay_is_frame_rate_independent() {
+        let mut coarse = DecayAnimation::new(0.0, 800.0).friction(4.0);
+        let mut fine = coarse;
+        coarse.step(0.5);
+        for _ in 0..50 {
+            fine.step(0.01);
+        }
+        assert!((coarse.value() - fine.value()).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_decay_rubber_bands_back_to_bounds() {
+        let mut decay = DecayAnimation::new(90.0, 2000.0).bounds(0.0, 100.0);
+        assert_eq!(decay.projected(), 100.0);
+
+        let mut peak: f32 = 0.0;
+        for _ in 0..240 {
+            decay.step(1.0 / 60.0);
+            peak = peak.max(decay.value());
+        }
+
+        assert!(
+            peak > 100.0,
+            "should overshoot the edge before bouncing back"
+        );
+        assert!(decay.is_settled());
+        assert!((decay.value() - 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_decay_starting_out_of_bounds_springs_back() {
+        let mut decay = DecayAnimation::new(-40.0, 0.0).bounds(0.0, 100.0);
+        assert!(!decay.is_settled());
+
+        decay.step(1.0 / 60.0);
+        assert!(decay.is_rubber_banding());
+        run(&mut decay, 3.0);
+        assert!(decay.value().abs() < 0.01);
+    }
+
+    #[test]
+    fn test_decay_hands_velocity_to_spring() {
+        let mut decay = DecayAnimation::new(0.0, 1500.0);
+        decay.step(0.1);
+        let velocity = decay.velocity();
+
+        let spring = decay.into_spring(400.0, SpringConfig::snappy());
+        assert_eq!(spring.velocity(), velocity);
+        assert_eq!(spring.value(), decay.value());
+        assert_eq!(spring.target(), 400.0);
+    }
+
+    #[test]
+    fn test_point_decay_per_axis_bounds() {
+        let mut decay = PointDecay::new(Point::new(0.0, 0.0), Point::new(600.0, -600.0))
+            .bounds(Point::new(-1000.0, -100.0), Point::new(1000.0, 100.0));
+
+        for _ in 0..300 {
+            decay.step(1.0 / 60.0);
+        }
+
+        assert!(decay.is_settled());
+        let p = decay.value();
+        // x coasts freely, y is caught by its bound
+        assert!((p.x - 300.0).abs() < 1.0);
+        assert!((p.y + 100.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_rubber_band_resistance() {
+        assert_eq!(rubber_band(0.0, 400.0), 0.0);
+        let small = rubber_band(10.0, 400.0);
+        assert!(small > 0.0 && small < 10.0);
+        assert!(rubber_band(10_000.0, 400.0) < 400.0);
+        assert_eq!(rubber_band(-50.0, 400.0), -rubber_band(50.0, 400.0));
+    }
+}
@@ -1,7 +1,16 @@
 //! Easing functions for animations
+//!
+//! Covers the CSS timing functions (`cubic-bezier()`, `steps()`, `linear()`),
+//! the classic polynomial, sine, expo, circ, back, elastic and bounce
+//! families, and a spring curve derived from a [`SpringConfig`].
+
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use crate::spring::SpringConfig;
 
 /// Easing function type
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub enum Easing {
     #[default]
     Linear,
@@ -17,10 +26,66 @@ pub enum Easing {
     EaseInQuart,
     EaseOutQuart,
     EaseInOutQuart,
+    EaseInSine,
+    EaseOutSine,
+    EaseInOutSine,
+    EaseInExpo,
+    EaseOutExpo,
+    EaseInOutExpo,
+    EaseInCirc,
+    EaseOutCirc,
+    EaseInOutCirc,
+    /// Pulls back slightly before moving
+    EaseInBack,
+    /// Overshoots slightly before settling
+    EaseOutBack,
+    EaseInOutBack,
+    EaseInElastic,
+    EaseOutElastic,
+    EaseInOutElastic,
+    EaseInBounce,
+    EaseOutBounce,
+    EaseInOutBounce,
     CubicBezier(f32, f32, f32, f32),
+    /// CSS `steps(n, <position>)`: jumps between `n` flat steps
+    Steps(u32, StepPosition),
+    /// CSS `linear(...)`: straight segments through `(input, output)` points
+    ///
+    /// Inputs must be ascending. Progress outside the first and last inputs
+    /// extends the first or last segment.
+    Piecewise(Arc<[(f32, f32)]>),
+    /// A spring settling from 0 to 1, stretched so it settles at `t = 1`
+    ///
+    /// Use [`Easing::spring_duration_ms`] as the animation duration to keep
+    /// the spring's natural speed.
+    Spring(SpringConfig),
+}
+
+/// Where the jumps of a [`Easing::Steps`] function happen
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StepPosition {
+    /// First jump at the start (`jump-start`, `start`)
+    JumpStart,
+    /// Last jump at the end (`jump-end`, `end`)
+    #[default]
+    JumpEnd,
+    /// No jump at either end (`jump-none`)
+    JumpNone,
+    /// Jumps at both ends (`jump-both`)
+    JumpBoth,
 }
 
 impl Easing {
+    /// Build a [`Easing::Piecewise`] function from `(input, output)` points
+    pub fn piecewise(points: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        Easing::Piecewise(points.into_iter().collect())
+    }
+
+    /// Time a spring takes to settle from 0 to 1, in milliseconds
+    pub fn spring_duration_ms(config: SpringConfig) -> u32 {
+        (spring_settle_time(&config) * 1000.0).ceil() as u32
+    }
+
     /// Apply the easing function to a progress value (0.0 to 1.0)
     pub fn apply(&self, t: f32) -> f32 {
         match self {
@@ -61,11 +126,227 @@ impl Easing {
                     1.0 - (-2.0 * t + 2.0).powi(4) / 2.0
                 }
             }
+            Easing::EaseInSine => 1.0 - (t * PI / 2.0).cos(),
+            Easing::EaseOutSine => (t * PI / 2.0).sin(),
+            Easing::EaseInOutSine => -((PI * t).cos() - 1.0) / 2.0,
+            Easing::EaseInExpo => {
+                if t <= 0.0 {
+                    0.0
+                } else {
+                    2.0_f32.powf(10.0 * t - 10.0)
+                }
+            }
+            Easing::EaseOutExpo => {
+                if t >= 1.0 {
+                    1.0
+                } else {
+                    1.0 - 2.0_f32.powf(-10.0 * t)
+                }
+            }
+            Easing::EaseInOutExpo => {
+                if t <= 0.0 {
+                    0.0
+                } else if t >= 1.0 {
+                    1.0
+                } else if t < 0.5 {
+                    2.0_f32.powf(20.0 * t - 10.0) / 2.0
+                } else {
+                    (2.0 - 2.0_f32.powf(-20.0 * t + 10.0)) / 2.0
+                }
+            }
+            Easing::EaseInCirc => 1.0 - (1.0 - t * t).max(0.0).sqrt(),
+            Easing::EaseOutCirc => (1.0 - (t - 1.0).powi(2)).max(0.0).sqrt(),
+            Easing::EaseInOutCirc => {
+                if t < 0.5 {
+                    (1.0 - (1.0 - (2.0 * t).powi(2)).max(0.0).sqrt()) / 2.0
+                } else {
+                    ((1.0 - (-2.0 * t + 2.0).powi(2)).max(0.0).sqrt() + 1.0) / 2.0
+                }
+            }
+            Easing::EaseInBack => BACK_C3 * t * t * t - BACK_C1 * t * t,
+            Easing::EaseOutBack => 1.0 + BACK_C3 * (t - 1.0).powi(3) + BACK_C1 * (t - 1.0).powi(2),
+            Easing::EaseInOutBack => {
+                const C2: f32 = BACK_C1 * 1.525;
+                if t < 0.5 {
+                    ((2.0 * t).powi(2) * ((C2 + 1.0) * 2.0 * t - C2)) / 2.0
+                } else {
+                    ((2.0 * t - 2.0).powi(2) * ((C2 + 1.0) * (2.0 * t - 2.0) + C2) + 2.0) / 2.0
+                }
+            }
+            Easing::EaseInElastic => {
+                if t <= 0.0 || t >= 1.0 {
+                    t.clamp(0.0, 1.0)
+                } else {
+                    -(2.0_f32.powf(10.0 * t - 10.0)) * ((10.0 * t - 10.75) * ELASTIC_C4).sin()
+                }
+            }
+            Easing::EaseOutElastic => {
+                if t <= 0.0 || t >= 1.0 {
+                    t.clamp(0.0, 1.0)
+                } else {
+                    2.0_f32.powf(-10.0 * t) * ((10.0 * t - 0.75) * ELASTIC_C4).sin() + 1.0
+                }
+            }
+            Easing::EaseInOutElastic => {
+                const C5: f32 = 2.0 * PI / 4.5;
+                if t <= 0.0 || t >= 1.0 {
+                    t.clamp(0.0, 1.0)
+                } else if t < 0.5 {
+                    -(2.0_f32.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * C5).sin()) / 2.0
+                } else {
+                    2.0_f32.powf(-20.0 * t + 10.0) * ((20.0 * t - 11.125) * C5).sin() / 2.0 + 1.0
+                }
+            }
+            Easing::EaseInBounce => 1.0 - bounce_out(1.0 - t),
+            Easing::EaseOutBounce => bounce_out(t),
+            Easing::EaseInOutBounce => {
+                if t < 0.5 {
+                    (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0
+                } else {
+                    (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0
+                }
+            }
             Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier_ease(t, *x1, *y1, *x2, *y2),
+            Easing::Steps(steps, position) => steps_ease(t, *steps, *position),
+            Easing::Piecewise(points) => piecewise_ease(t, points),
+            Easing::Spring(config) => spring_ease(t, config),
         }
     }
 }
 
+const BACK_C1: f32 = 1.70158;
+const BACK_C3: f32 = BACK_C1 + 1.0;
+const ELASTIC_C4: f32 = 2.0 * PI / 3.0;
+
+/// Bounce-out curve: four parabolic arcs of decreasing height
+fn bounce_out(t: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
+
+/// Step easing as defined by CSS Easing Functions Level 1
+fn steps_ease(t: f32, steps: u32, position: StepPosition) -> f32 {
+    let steps = steps.max(1) as f32;
+    let mut step = (t * steps).floor();
+    if matches!(position, StepPosition::JumpStart | StepPosition::JumpBoth) {
+        step += 1.0;
+    }
+    let jumps = match position {
+        StepPosition::JumpNone => (steps - 1.0).max(1.0),
+        StepPosition::JumpBoth => steps + 1.0,
+        _ => steps,
+    };
+    if t >= 0.0 {
+        step = step.max(0.0);
+    }
+    if t <= 1.0 {
+        step = step.min(jumps);
+    }
+    step / jumps
+}
+
+/// Piecewise-linear easing through `(input, output)` points
+fn piecewise_ease(t: f32, points: &[(f32, f32)]) -> f32 {
+    let segment = |(x0, y0): (f32, f32), (x1, y1): (f32, f32)| {
+        if x1 == x0 {
+            y1
+        } else {
+            y0 + (y1 - y0) * (t - x0) / (x1 - x0)
+        }
+    };
+
+    match points {
+        [] => t,
+        [(_, y)] => *y,
+        [first, second, ..] if t < first.0 => segment(*first, *second),
+        [.., before, last] if t >= last.0 => {
+            if t == last.0 {
+                last.1
+            } else {
+                segment(*before, *last)
+            }
+        }
+        _ => {
+            // Last point at or before t (later points win on equal inputs)
+            let i = points.iter().rposition(|p| p.0 <= t).unwrap_or(0);
+            segment(points[i], points[i + 1])
+        }
+    }
+}
+
+/// Progress of a spring released from rest at 0 towards 1, `time` seconds in
+fn spring_position(config: &SpringConfig, time: f32) -> f32 {
+    let omega = (config.stiffness / config.mass).sqrt();
+    let zeta = config.damping / (2.0 * (config.stiffness * config.mass).sqrt());
+
+    if zeta < 1.0 {
+        let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+        let envelope = (-zeta * omega * time).exp();
+        1.0 - envelope
+            * ((omega_d * time).cos() + (zeta * omega / omega_d) * (omega_d * time).sin())
+    } else if zeta == 1.0 {
+        1.0 - (-omega * time).exp() * (1.0 + omega * time)
+    } else {
+        let root = (zeta * zeta - 1.0).sqrt();
+        let r1 = -omega * (zeta - root);
+        let r2 = -omega * (zeta + root);
+        1.0 - (r2 * (r1 * time).exp() - r1 * (r2 * time).exp()) / (r2 - r1)
+    }
+}
+
+/// Seconds until a spring from 0 to 1 stays within 0.1% of its target
+fn spring_settle_time(config: &SpringConfig) -> f32 {
+    const SETTLE: f32 = 0.001;
+
+    if config.stiffness <= 0.0 || config.mass <= 0.0 || config.damping <= 0.0 {
+        return 1.0;
+    }
+    let omega = (config.stiffness / config.mass).sqrt();
+    let zeta = config.damping / (2.0 * (config.stiffness * config.mass).sqrt());
+
+    // Slowest exponential decay rate of the motion
+    let rate = if zeta < 1.0 {
+        zeta * omega
+    } else {
+        omega * (zeta - (zeta * zeta - 1.0).sqrt())
+    };
+    let mut time = -SETTLE.ln() / rate;
+    if (zeta - 1.0).abs() < 0.05 {
+        // Near critical damping the decay carries a (1 + wt) factor
+        for _ in 0..4 {
+            time = (-SETTLE.ln() + (1.0 + omega * time).ln()) / rate;
+        }
+    }
+    time
+}
+
+/// Spring easing: the spring's motion compressed into `0..=1`
+fn spring_ease(t: f32, config: &SpringConfig) -> f32 {
+    if t <= 0.0 {
+        return 0.0;
+    }
+    if t >= 1.0 {
+        return 1.0;
+    }
+    if config.stiffness <= 0.0 || config.mass <= 0.0 || config.damping <= 0.0 {
+        return t;
+    }
+    spring_position(config, t * spring_settle_time(config))
+}
+
 /// Cubic bezier easing calculation (matches CSS spec / browser implementations).
 ///
 /// Uses Newton-Raphson with binary-search fallback for robustness.
@@ -138,3 +419,126 @@ fn bezier_slope(t: f64, p1: f64, p2: f64) -> f64 {
     let c = 3.0 * p1;
     (3.0 * a * t + 2.0 * b) * t + c
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_NAMED: [Easing; 31] = [
+        Easing::Linear,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+        Easing::EaseInQuad,
+        Easing::EaseOutQuad,
+        Easing::EaseInOutQuad,
+        Easing::EaseInCubic,
+        Easing::EaseOutCubic,
+        Easing::EaseInOutCubic,
+        Easing::EaseInQuart,
+        Easing::EaseOutQuart,
+        Easing::EaseInOutQuart,
+        Easing::EaseInSine,
+        Easing::EaseOutSine,
+        Easing::EaseInOutSine,
+        Easing::EaseInExpo,
+        Easing::EaseOutExpo,
+        Easing::EaseInOutExpo,
+        Easing::EaseInCirc,
+        Easing::EaseOutCirc,
+        Easing::EaseInOutCirc,
+        Easing::EaseInBack,
+        Easing::EaseOutBack,
+        Easing::EaseInOutBack,
+        Easing::EaseInElastic,
+        Easing::EaseOutElastic,
+        Easing::EaseInOutElastic,
+        Easing::EaseInBounce,
+        Easing::EaseOutBounce,
+        Easing::EaseInOutBounce,
+    ];
+
+    #[test]
+    fn test_named_easings_hit_endpoints() {
+        for easing in &ALL_NAMED {
+            assert!(easing.apply(0.0).abs() < 1e-3, "{easing:?} at 0");
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-3, "{easing:?} at 1");
+        }
+        // In/out pairs meet halfway
+        assert!((Easing::EaseInOutSine.apply(0.5) - 0.5).abs() < 1e-6);
+        assert!((Easing::EaseInOutBounce.apply(0.5) - 0.5).abs() < 1e-6);
+        // Back and elastic leave the 0..1 range
+        assert!(Easing::EaseInBack.apply(0.2) < 0.0);
+        assert!(Easing::EaseOutBack.apply(0.8) > 1.0);
+        assert!(Easing::EaseOutElastic.apply(0.1) > 1.0);
+    }
+
+    #[test]
+    fn test_steps() {
+        let end = Easing::Steps(4, StepPosition::JumpEnd);
+        assert_eq!(end.apply(0.0), 0.0);
+        assert_eq!(end.apply(0.3), 0.25);
+        assert_eq!(end.apply(0.99), 0.75);
+        assert_eq!(end.apply(1.0), 1.0);
+
+        let start = Easing::Steps(4, StepPosition::JumpStart);
+        assert_eq!(start.apply(0.0), 0.25);
+        assert_eq!(start.apply(0.8), 1.0);
+
+        let none = Easing::Steps(5, StepPosition::JumpNone);
+        assert_eq!(none.apply(0.0), 0.0);
+        assert_eq!(none.apply(0.5), 0.5);
+        assert_eq!(none.apply(1.0), 1.0);
+
+        let both = Easing::Steps(3, StepPosition::JumpBoth);
+        assert_eq!(both.apply(0.0), 0.25);
+        assert_eq!(both.apply(0.5), 0.5);
+        assert_eq!(both.apply(1.0), 1.0);
+    }
+
+    #[test]
+    fn test_piecewise() {
+        let easing = Easing::piecewise([(0.0, 0.0), (0.5, 0.8), (1.0, 1.0)]);
+        assert_eq!(easing.apply(0.25), 0.4);
+        assert_eq!(easing.apply(0.5), 0.8);
+        assert!((easing.apply(0.75) - 0.9).abs() < 1e-6);
+        assert_eq!(easing.apply(1.0), 1.0);
+
+        // Equal inputs jump to the later output
+        let jump = Easing::piecewise([(0.0, 0.0), (0.5, 0.0), (0.5, 1.0), (1.0, 1.0)]);
+        assert_eq!(jump.apply(0.4), 0.0);
+        assert_eq!(jump.apply(0.5), 1.0);
+
+        // Outside the points the end segments are extended
+        assert_eq!(Easing::piecewise([(0.0, 0.0), (1.0, 0.5)]).apply(2.0), 1.0);
+    }
+
+    #[test]
+    fn test_spring_easing() {
+        let wobbly = Easing::Spring(SpringConfig::wobbly());
+        assert_eq!(wobbly.apply(0.0), 0.0);
+        assert_eq!(wobbly.apply(1.0), 1.0);
+        // Underdamped springs overshoot, and are close to rest near the end
+        let peak = (1..100)
+            .map(|i| wobbly.apply(i as f32 / 100.0))
+            .fold(0.0_f32, f32::max);
+        assert!(peak > 1.0);
+        assert!((wobbly.apply(0.99) - 1.0).abs() < 0.01);
+
+        // Overdamped springs approach without overshooting
+        let heavy = Easing::Spring(SpringConfig::new(100.0, 40.0, 1.0));
+        assert!((1..100).all(|i| heavy.apply(i as f32 / 100.0) <= 1.0));
+
+        // The stretched curve matches the simulated spring's timing
+        let config = SpringConfig::stiff();
+        let duration = Easing::spring_duration_ms(config);
+        assert!(duration > 100 && duration < 2000);
+        let mut spring = crate::spring::Spring::new(config, 0.0);
+        spring.set_target(1.0);
+        for _ in 0..10 {
+            spring.step(0.01);
+        }
+        let eased = Easing::Spring(config).apply(100.0 / duration as f32);
+        assert!((eased - spring.value()).abs() < 0.01);
+    }
+}
@@ -0,0 +1,241 @@
+//! Gesture-scrubbed timelines
+//!
+//! A [`GestureTimeline`] takes its progress from a drag instead of the clock.
+//! While the pointer is down the timeline follows it exactly; on release a
+//! spring carries the release velocity on to either end of the timeline.
+
+use crate::decay::DecayAnimation;
+use crate::easing::Easing;
+use crate::scheduler::{AnimatedValue, SchedulerHandle};
+use crate::spring::SpringConfig;
+use crate::timeline::{Timeline, TimelineEntryId};
+
+/// A timeline scrubbed by a drag that completes or reverts on release
+///
+/// `distance` is how far (in pixels, along the drag axis) the pointer moves
+/// to go from progress 0 to 1. Use a negative distance for drags towards
+/// negative coordinates (e.g. swiping up or left).
+///
+/// On release the motion is projected forward as if it coasted to a stop;
+/// the timeline completes when that lands past the threshold and reverts
+/// otherwise, so a quick flick completes even from a short drag.
+///
+/// # Example
+///
+/// ```ignore
+/// // Swipe-to-dismiss: drag 300px right to fade and slide the card out
+/// let mut dismiss = GestureTimeline::new(ctx.animation_handle(), 300.0);
+/// let offset = dismiss.add(0, 1000, 0.0, 300.0);
+/// let opacity = dismiss.add(0, 1000, 1.0, 0.0);
+///
+/// // In drag handlers
+/// dismiss.update(event.drag_delta_x);
+/// if dismiss.end(velocity_x) {
+///     // will finish dismissing
+/// }
+///
+/// div()
+///     .translate_x(dismiss.get(offset).unwrap_or(0.0))
+///     .opacity(dismiss.get(opacity).unwrap_or(1.0))
+/// ```
+pub struct GestureTimeline {
+    timeline: Timeline,
+    handle: SchedulerHandle,
+    progress: AnimatedValue,
+    config: SpringConfig,
+    distance: f32,
+    threshold: f32,
+    /// Progress when the current drag began (`None` when not dragging)
+    origin: Option<f32>,
+}
+
+impl GestureTimeline {
+    /// Create a timeline at progress 0, scrubbed over `distance` pixels
+    pub fn new(handle: SchedulerHandle, distance: f32) -> Self {
+        let config = SpringConfig::snappy();
+        Self {
+            timeline: Timeline::new(),
+            progress: AnimatedValue::new(handle.clone(), 0.0, config),
+            handle,
+            config,
+            distance,
+            threshold: 0.5,
+            origin: None,
+        }
+    }
+
+    /// Spring used to complete or revert after release
+    pub fn spring(mut self, config: SpringConfig) -> Self {
+        self.config = config;
+        self.progress = AnimatedValue::new(self.handle.clone(), self.progress(), config);
+        self
+    }
+
+    /// Projected progress past which a release completes (default 0.5)
+    pub fn threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Add an animation to the timeline at a given offset
+    pub fn add(
+        &mut self,
+        offset_ms: i32,
+        duration_ms: u32,
+        start_value: f32,
+        end_value: f32,
+    ) -> TimelineEntryId {
+        self.timeline
+            .add(offset_ms, duration_ms, start_value, end_value)
+    }
+
+    /// Add an animation with a specific easing function
+    pub fn add_with_easing(
+        &mut self,
+        offset_ms: i32,
+        duration_ms: u32,
+        start_value: f32,
+        end_value: f32,
+        easing: Easing,
+    ) -> TimelineEntryId {
+        self.timeline
+            .add_with_easing(offset_ms, duration_ms, start_value, end_value, easing)
+    }
+
+    /// Start a drag, interrupting any spring in flight
+    pub fn begin(&mut self) {
+        let progress = self.progress();
+        self.progress.set_immediate(progress);
+        self.origin = Some(progress);
+    }
+
+    /// Follow the drag's total translation since it began
+    ///
+    /// Starts a drag if one is not in progress.
+    pub fn update(&mut self, translation: f32) {
+        if self.origin.is_none() {
+            self.begin();
+        }
+        let origin = self.origin.unwrap_or_default();
+        let progress = if self.distance == 0.0 {
+            origin
+        } else {
+            origin + translation / self.distance
+        };
+        self.progress.set_immediate(progress.clamp(0.0, 1.0));
+    }
+
+    /// Release with the drag's velocity (pixels per second)
+    ///
+    /// Springs to the end that the projected motion favours and returns
+    /// `true` when that is completion.
+    pub fn end(&mut self, velocity: f32) -> bool {
+        self.origin = None;
+        let velocity = if self.distance == 0.0 {
+            0.0
+        } else {
+            velocity / self.distance
+        };
+        let projected = DecayAnimation::new(self.progress(), velocity).projected();
+        let complete = projected >= self.threshold;
+        self.progress
+            .set_target_with_velocity(if complete { 1.0 } else { 0.0 }, velocity);
+        complete
+    }
+
+    /// Spring to the end of the timeline
+    pub fn complete(&mut self) {
+        self.origin = None;
+        self.progress.set_target(1.0);
+    }
+
+    /// Spring back to the start of the timeline
+    pub fn revert(&mut self) {
+        self.origin = None;
+        self.progress.set_target(0.0);
+    }
+
+    /// Jump to a progress (0.0 to 1.0) without animating
+    pub fn set_progress(&mut self, progress: f32) {
+        self.origin = None;
+        self.progress.set_immediate(progress.clamp(0.0, 1.0));
+    }
+
+    /// Current progress (0.0 to 1.0)
+    pub fn progress(&self) -> f32 {
+        self.progress.get().clamp(0.0, 1.0)
+    }
+
+    /// Get the current value for a timeline entry
+    pub fn get(&self, entry_id: TimelineEntryId) -> Option<f32> {
+        self.timeline.value_at(entry_id, self.progress())
+    }
+
+    /// Whether a drag is in progress
+    pub fn is_dragging(&self) -> bool {
+        self.origin.is_some()
+    }
+
+    /// Whether the timeline is springing after a release
+    pub fn is_animating(&self) -> bool {
+        self.progress.is_animating()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::scheduler::AnimationScheduler;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn settle(clock: &ManualClock, scheduler: &AnimationScheduler) {
+        for _ in 0..120 {
+            clock.advance(Duration::from_millis(16));
+            scheduler.tick();
+        }
+    }
+
+    #[test]
+    fn test_gesture_timeline_follows_drag() {
+        let scheduler = AnimationScheduler::new();
+        let mut gesture = GestureTimeline::new(scheduler.handle(), 200.0);
+        let height = gesture.add(0, 1000, 100.0, 300.0);
+
+        gesture.update(50.0);
+        assert!(gesture.is_dragging());
+        assert!((gesture.progress() - 0.25).abs() < 1e-6);
+        assert_eq!(gesture.get(height), Some(150.0));
+
+        // Clamped at the ends
+        gesture.update(500.0);
+        assert_eq!(gesture.get(height), Some(300.0));
+    }
+
+    #[test]
+    fn test_gesture_timeline_release() {
+        let clock = Arc::new(ManualClock::new());
+        let scheduler = AnimationScheduler::with_clock(clock.clone());
+        let mut gesture = GestureTimeline::new(scheduler.handle(), -300.0);
+        let entry = gesture.add(0, 1000, 0.0, 1.0);
+
+        // Slow release short of halfway reverts
+        gesture.update(-90.0);
+        assert!(!gesture.end(-10.0));
+        assert!(gesture.is_animating());
+        settle(&clock, &scheduler);
+        assert!(gesture.progress() < 0.001);
+
+        // A short but fast flick completes
+        gesture.update(-60.0);
+        assert!(gesture.end(-900.0));
+        settle(&clock, &scheduler);
+        assert!((gesture.get(entry).unwrap() - 1.0).abs() < 0.001);
+
+        // Grabbing again resumes from where it rests
+        gesture.begin();
+        gesture.update(150.0);
+        assert!((gesture.progress() - 0.5).abs() < 0.01);
+    }
+}
@@ -252,6 +252,8 @@ pub struct KeyframeProperties {
     // --- Text properties ---
     /// Font size in pixels
     pub font_size: Option<f32>,
+    /// Variable font axis values as (4-byte OpenType tag, value) pairs
+    pub font_variations: Option<Vec<([u8; 4], f32)>>,
 
     // --- Skew ---
     /// Skew X in degrees
@@ -266,6 +268,10 @@ pub struct KeyframeProperties {
     // --- Stacking ---
     /// z-index (f32 for smooth interpolation, rounded on apply)
     pub z_index: Option<f32>,
+
+    // --- Motion path ---
+    /// Distance along the element's offset path, in percent of its length
+    pub offset_distance: Option<f32>,
 }
 
 impl KeyframeProperties {
@@ -372,6 +378,22 @@ impl KeyframeProperties {
         self
     }
 
+    /// Builder: set the distance along the offset path (percent)
+    pub fn with_offset_distance(mut self, percent: f32) -> Self {
+        self.offset_distance = Some(percent);
+        self
+    }
+
+    /// Builder: set a variable font axis (e.g. `b"wght"`)
+    pub fn with_font_variation(mut self, tag: [u8; 4], value: f32) -> Self {
+        let axes = self.font_variations.get_or_insert_with(Vec::new);
+        match axes.iter_mut().find(|(t, _)| *t == tag) {
+            Some(axis) => axis.1 = value,
+            None => axes.push((tag, value)),
+        }
+        self
+    }
+
     /// Interpolate between two property sets
     pub fn lerp(&self, other: &Self, t: f32) -> Self {
         Self {
@@ -461,6 +483,7 @@ impl KeyframeProperties {
             inset_left: lerp_opt(self.inset_left, other.inset_left, t),
             // Text properties
             font_size: lerp_opt(self.font_size, other.font_size, t),
+            font_variations: lerp_opt_axes(&self.font_variations, &other.font_variations, t),
             // Skew
             skew_x: lerp_opt(self.skew_x, other.skew_x, t),
             skew_y: lerp_opt(self.skew_y, other.skew_y, t),
@@ -468,6 +491,8 @@ impl KeyframeProperties {
             transform_origin: lerp_opt_array2(self.transform_origin, other.transform_origin, t),
             // Stacking
             z_index: lerp_opt(self.z_index, other.z_index, t),
+            // Motion path
+            offset_distance: lerp_opt(self.offset_distance, other.offset_distance, t),
         }
     }
 
@@ -525,6 +550,32 @@ fn lerp_opt(a: Option<f32>, b: Option<f32>, t: f32) -> Option<f32> {
     }
 }
 
+/// Helper to interpolate tagged axis lists, matching axes by tag
+///
+/// An axis present on only one side holds its value, like [`lerp_opt`].
+fn lerp_opt_axes(
+    a: &Option<Vec<([u8; 4], f32)>>,
+    b: &Option<Vec<([u8; 4], f32)>>,
+    t: f32,
+) -> Option<Vec<([u8; 4], f32)>> {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let mut axes: Vec<([u8; 4], f32)> = a
+                .iter()
+                .map(|&(tag, from)| {
+                    let to = b.iter().find(|(t, _)| *t == tag).map(|&(_, v)| v);
+                    (tag, lerp_opt(Some(from), to, t).unwrap_or(from))
+                })
+                .collect();
+            axes.extend(b.iter().filter(|(tag, _)| !a.iter().any(|(t, _)| t == tag)));
+            Some(axes)
+        }
+        (Some(a), None) => Some(a.clone()),
+        (None, Some(b)) => Some(b.clone()),
+        (None, None) => None,
+    }
+}
+
 /// Helper to interpolate optional [f32; 2] arrays
 fn lerp_opt_array2(a: Option<[f32; 2]>, b: Option<[f32; 2]>, t: f32) -> Option<[f32; 2]> {
     match (a, b) {
@@ -965,7 +1016,7 @@ impl KeyframeTrackBuilder {
         self.points.push(KeyframePoint {
             time_ms,
             value,
-            easing: self.default_easing,
+            easing: self.default_easing.clone(),
         });
         self
     }
@@ -985,11 +1036,11 @@ impl KeyframeTrackBuilder {
     /// This applies to keyframes added after this call, and updates
     /// existing keyframes that haven't had explicit easing set.
     pub fn ease(mut self, easing: Easing) -> Self {
-        self.default_easing = easing;
         // Update existing points that use the default
         for point in &mut self.points {
-            point.easing = easing;
+            point.easing = easing.clone();
         }
+        self.default_easing = easing;
         self
     }
 
@@ -1068,7 +1119,7 @@ impl KeyframeTrackBuilder {
                 Keyframe {
                     time,
                     value: p.value,
-                    easing: p.easing,
+                    easing: p.easing.clone(),
                 }
             })
             .collect();
@@ -6,39 +6,60 @@
 //!
 //! - **Spring Physics**: RK4-integrated springs with stiffness, damping, mass
 //! - **Keyframe Animations**: Timed sequences with easing functions
+//! - **Easing Catalog**: CSS `steps()` and `linear()`, elastic, back, bounce and spring curves
 //! - **Multi-Property Keyframes**: Animate multiple properties simultaneously
 //! - **Timelines**: Orchestrate multiple animations with offsets
+//! - **Gesture Timelines**: Timelines scrubbed by a drag that complete or revert on release
 //! - **Typed Animations**: Generic animations for Vec3, Color, and custom types
+//! - **Typed Springs**: Springs over points, sizes, rects, colors and transforms
+//! - **Decay**: Inertial motion with friction, bounds and rubber-banding
+//! - **Path Morphing**: Interpolate between arbitrary vector paths
+//! - **Motion Paths**: Move along a path by progress (CSS `offset-path`)
 //! - **Interruptible**: Animations inherit velocity when interrupted
 //! - **Animation Presets**: Common entry/exit animations
 //! - **AnimationContext**: Platform-agnostic animation management trait
+//! - **Injectable Clock**: Manual and scaled time for deterministic tests and slow motion
 
+pub mod clock;
 pub mod context;
+pub mod decay;
 pub mod easing;
+pub mod gesture;
 pub mod keyframe;
+pub mod path;
 pub mod presets;
 pub mod scheduler;
 pub mod spring;
 pub mod timeline;
 pub mod values;
 
+pub use clock::{
+    set_time_scale, time_scale, Clock, ManualClock, RealClock, ScaledClock, SharedClock,
+};
 pub use context::{
     AnimationContext, AnimationContextExt, SharedAnimatedTimeline, SharedAnimatedValue,
 };
-pub use easing::Easing;
+pub use decay::{rubber_band, DecayAnimation, PointDecay, TypedDecay};
+pub use easing::{Easing, StepPosition};
+pub use gesture::GestureTimeline;
 pub use keyframe::{
     FillMode, Keyframe, KeyframeAnimation, KeyframePoint, KeyframeProperties, KeyframeTrack,
     KeyframeTrackBuilder, MultiKeyframe, MultiKeyframeAnimation, PlayDirection,
 };
+pub use path::{MotionPath, MotionSample, OffsetRotate, PathMorph};
 pub use presets::AnimationPreset;
 pub use scheduler::{
     get_scheduler, is_scheduler_initialized, set_global_scheduler, try_get_scheduler,
     AnimatedKeyframe, AnimatedTimeline, AnimatedValue, AnimationScheduler, ConfigureResult,
     KeyframeId, SchedulerHandle, SpringId, TickCallback, TickCallbackId, TimelineId,
 };
-pub use spring::{Spring, SpringConfig};
+pub use spring::{
+    ColorSpring, PointSpring, RectSpring, SizeSpring, Spring, SpringConfig, TransformSpring,
+    TypedSpring,
+};
 pub use timeline::{StaggerBuilder, Timeline, TimelineEntryId};
 pub use values::{
-    ColorAnimation, ColorKeyframe, FloatAnimation, FloatKeyframe, Interpolate,
-    SphericalInterpolate, TypedKeyframe, TypedKeyframeAnimation, Vec3Animation, Vec3Keyframe,
+    Animatable, ColorAnimation, ColorKeyframe, FloatAnimation, FloatKeyframe, Interpolate,
+    PathAnimation, PathKeyframe, SphericalInterpolate, TypedKeyframe, TypedKeyframeAnimation,
+    Vec3Animation, Vec3Keyframe,
 };
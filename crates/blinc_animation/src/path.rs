@@ -0,0 +1,737 @@
+//! Path morphing and motion paths
+//!
+//! [`Path`] implements [`Interpolate`], so shapes can morph in keyframe
+//! animations. Both paths are first normalized to the same structure:
+//!
+//! - every command becomes a cubic bezier (lines, quadratics and arcs included)
+//! - subpaths are paired in order; a subpath without a partner grows out of
+//!   (or shrinks into) the center of its counterpart
+//! - the longest segments are split until paired subpaths have the same
+//!   number of segments
+//! - paired closed subpaths are rotated so their start points line up, and
+//!   single closed shapes are turned to wind the same way
+//!
+//! Use [`PathMorph`] to normalize once and sample many times.
+//!
+//! [`MotionPath`] places things along a path by progress, like CSS
+//! `offset-path` with `offset-distance` and `offset-rotate`.
+
+use blinc_core::{Affine2D, Path, PathCommand, Point, Vec2};
+
+use crate::values::Interpolate;
+
+/// Control polygon length per flattened step of a motion path
+const FLATTEN_STEP: f32 = 4.0;
+
+/// Most lines a single curve is flattened into
+const MAX_FLATTEN_STEPS: usize = 64;
+
+// ============================================================================
+// Normalized Geometry
+// ============================================================================
+
+/// A subpath with every segment as a cubic bezier `[control1, control2, end]`
+#[derive(Clone, Debug)]
+struct Contour {
+    start: Point,
+    segments: Vec<[Point; 3]>,
+    closed: bool,
+}
+
+impl Contour {
+    fn new(start: Point) -> Self {
+        Self {
+            start,
+            segments: Vec::new(),
+            closed: false,
+        }
+    }
+
+    fn end(&self) -> Point {
+        self.segments
+            .last()
+            .map_or(self.start, |segment| segment[2])
+    }
+
+    /// On-curve point `index`, where `0` is the start
+    fn anchor(&self, index: usize) -> Point {
+        match index {
+            0 => self.start,
+            i => self.segments[i - 1][2],
+        }
+    }
+
+    fn line_to(&mut self, to: Point) {
+        let from = self.end();
+        self.segments
+            .push([mix(from, to, 1.0 / 3.0), mix(from, to, 2.0 / 3.0), to]);
+    }
+
+    fn quad_to(&mut self, control: Point, to: Point) {
+        let from = self.end();
+        self.segments.push([
+            mix(from, control, 2.0 / 3.0),
+            mix(to, control, 2.0 / 3.0),
+            to,
+        ]);
+    }
+
+    /// Average of the on-curve points (counting a closed start once)
+    fn center(&self) -> Point {
+        let first = usize::from(self.closed);
+        let count = self.segments.len() + 1 - first;
+        let (x, y) = (first..=self.segments.len())
+            .map(|i| self.anchor(i))
+            .fold((0.0, 0.0), |(x, y), p| (x + p.x, y + p.y));
+        Point::new(x / count as f32, y / count as f32)
+    }
+
+    /// A contour collapsed to this one's center, with as many segments
+    fn collapsed(&self) -> Self {
+        let center = self.center();
+        Self {
+            start: center,
+            segments: vec![[center; 3]; self.segments.len()],
+            closed: self.closed,
+        }
+    }
+
+    /// Split the segment with the longest control polygon in half
+    fn split_longest(&mut self) {
+        let Some((index, _)) = self
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(i, segment)| (i, polygon_length(self.anchor(i), segment)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+        else {
+            return;
+        };
+        let (first, second) = split_cubic(self.anchor(index), &self.segments[index], 0.5);
+        self.segments[index] = first;
+        self.segments.insert(index + 1, second);
+    }
+
+    /// Twice the signed area of the on-curve polygon
+    fn signed_area(&self) -> f32 {
+        let count = self.segments.len() + 1;
+        (0..count)
+            .map(|i| {
+                let a = self.anchor(i);
+                let b = self.anchor((i + 1) % count);
+                a.x * b.y - b.x * a.y
+            })
+            .sum()
+    }
+
+    /// The same contour traced in the opposite direction
+    fn reversed(&self) -> Self {
+        let segments = (0..self.segments.len())
+            .rev()
+            .map(|i| {
+                let [control1, control2, _] = self.segments[i];
+                [control2, control1, self.anchor(i)]
+            })
+            .collect();
+        Self {
+            start: self.end(),
+            segments,
+            closed: self.closed,
+        }
+    }
+
+    /// A closed contour starting at on-curve point `offset`
+    fn rotated(&self, offset: usize) -> Self {
+        let mut segments = self.segments.clone();
+        segments.rotate_left(offset);
+        Self {
+            start: self.anchor(offset),
+            segments,
+            closed: self.closed,
+        }
+    }
+
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        let close = |a: Point, b: Point| (a.x - b.x).abs() < epsilon && (a.y - b.y).abs() < epsilon;
+        self.closed == other.closed
+            && self.segments.len() == other.segments.len()
+            && close(self.start, other.start)
+            && self
+                .segments
+                .iter()
+                .zip(&other.segments)
+                .all(|(a, b)| a.iter().zip(b).all(|(p, q)| close(*p, *q)))
+    }
+}
+
+fn mix(a: Point, b: Point, t: f32) -> Point {
+    Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+    ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt()
+}
+
+fn polygon_length(from: Point, segment: &[Point; 3]) -> f32 {
+    distance(from, segment[0]) + distance(segment[0], segment[1]) + distance(segment[1], segment[2])
+}
+
+fn cubic_point(from: Point, segment: &[Point; 3], t: f32) -> Point {
+    let [c1, c2, to] = *segment;
+    let u = 1.0 - t;
+    let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+    Point::new(
+        a * from.x + b * c1.x + c * c2.x + d * to.x,
+        a * from.y + b * c1.y + c * c2.y + d * to.y,
+    )
+}
+
+/// Split a cubic at `t` (de Casteljau)
+fn split_cubic(from: Point, segment: &[Point; 3], t: f32) -> ([Point; 3], [Point; 3]) {
+    let [c1, c2, to] = *segment;
+    let ab = mix(from, c1, t);
+    let bc = mix(c1, c2, t);
+    let cd = mix(c2, to, t);
+    let abc = mix(ab, bc, t);
+    let bcd = mix(bc, cd, t);
+    let middle = mix(abc, bcd, t);
+    ([ab, abc, middle], [bcd, cd, to])
+}
+
+/// Cubics approximating an SVG arc (rotation in radians), empty when the
+/// arc degenerates to a line
+fn arc_to_cubics(
+    from: Point,
+    radii: Vec2,
+    rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: Point,
+) -> Vec<[Point; 3]> {
+    let (mut rx, mut ry) = (radii.x.abs(), radii.y.abs());
+    if (from.x == to.x && from.y == to.y) || rx == 0.0 || ry == 0.0 {
+        return Vec::new();
+    }
+
+    // Endpoint to center parameterization (SVG implementation notes, F.6.5)
+    let (sin_phi, cos_phi) = rotation.sin_cos();
+    let dx = (from.x - to.x) / 2.0;
+    let dy = (from.y - to.y) / 2.0;
+    let x1 = cos_phi * dx + sin_phi * dy;
+    let y1 = -sin_phi * dx + cos_phi * dy;
+
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let numerator = (rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1).max(0.0);
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let root = if denominator > 0.0 {
+        (numerator / denominator).sqrt()
+    } else {
+        0.0
+    };
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let cx1 = sign * root * rx * y1 / ry;
+    let cy1 = -sign * root * ry * x1 / rx;
+    let cx = cos_phi * cx1 - sin_phi * cy1 + (from.x + to.x) / 2.0;
+    let cy = sin_phi * cx1 + cos_phi * cy1 + (from.y + to.y) / 2.0;
+
+    let theta = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+    let mut delta = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx) - theta;
+    if sweep && delta < 0.0 {
+        delta += std::f32::consts::TAU;
+    } else if !sweep && delta > 0.0 {
+        delta -= std::f32::consts::TAU;
+    }
+
+    // At most a quarter turn per cubic
+    let count = (delta.abs() / std::f32::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = delta / count as f32;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let point = |angle: f32| {
+        let (sin, cos) = angle.sin_cos();
+        let (x, y) = (rx * cos, ry * sin);
+        (
+            Point::new(
+                cx + cos_phi * x - sin_phi * y,
+                cy + sin_phi * x + cos_phi * y,
+            ),
+            // Derivative, for the control points
+            Point::new(
+                -cos_phi * rx * sin - sin_phi * ry * cos,
+                -sin_phi * rx * sin + cos_phi * ry * cos,
+            ),
+        )
+    };
+
+    let mut cubics = Vec::with_capacity(count);
+    for i in 0..count {
+        let (a, da) = point(theta + step * i as f32);
+        let (b, db) = point(theta + step * (i + 1) as f32);
+        let end = if i + 1 == count { to } else { b };
+        cubics.push([
+            Point::new(a.x + k * da.x, a.y + k * da.y),
+            Point::new(b.x - k * db.x, b.y - k * db.y),
+            end,
+        ]);
+    }
+    cubics
+}
+
+/// Break a path into cubic contours, dropping subpaths with no segments
+fn contours(path: &Path) -> Vec<Contour> {
+    let mut contours = Vec::new();
+    let mut current: Option<Contour> = None;
+    let mut pen = Point::ZERO;
+
+    for command in path.commands() {
+        match command {
+            PathCommand::MoveTo(p) => {
+                contours.extend(current.take().filter(|c| !c.segments.is_empty()));
+                current = Some(Contour::new(*p));
+                pen = *p;
+            }
+            PathCommand::LineTo(p) => {
+                current.get_or_insert_with(|| Contour::new(pen)).line_to(*p);
+                pen = *p;
+            }
+            PathCommand::QuadTo { control, end } => {
+                current
+                    .get_or_insert_with(|| Contour::new(pen))
+                    .quad_to(*control, *end);
+                pen = *end;
+            }
+            PathCommand::CubicTo {
+                control1,
+                control2,
+                end,
+            } => {
+                current
+                    .get_or_insert_with(|| Contour::new(pen))
+                    .segments
+                    .push([*control1, *control2, *end]);
+                pen = *end;
+            }
+            PathCommand::ArcTo {
+                radii,
+                rotation,
+                large_arc,
+                sweep,
+                end,
+            } => {
+                let contour = current.get_or_insert_with(|| Contour::new(pen));
+                let cubics = arc_to_cubics(pen, *radii, *rotation, *large_arc, *sweep, *end);
+                if cubics.is_empty() {
+                    contour.line_to(*end);
+                } else {
+                    contour.segments.extend(cubics);
+                }
+                pen = *end;
+            }
+            PathCommand::Close => {
+                if let Some(mut contour) = current.take() {
+                    if distance(contour.end(), contour.start) > 1e-4 {
+                        contour.line_to(contour.start);
+                    }
+                    contour.closed = true;
+                    pen = contour.start;
+                    if !contour.segments.is_empty() {
+                        contours.push(contour);
+                    }
+                }
+            }
+        }
+    }
+    contours.extend(current.filter(|c| !c.segments.is_empty()));
+    contours
+}
+
+// ============================================================================
+// Morphing
+// ============================================================================
+
+/// Two paths normalized for morphing
+///
+/// Normalizing splits curves and pairs subpaths, so build one morph and call
+/// [`at`](Self::at) every frame rather than calling [`Interpolate::lerp`] on
+/// the paths directly.
+///
+/// # Example
+///
+/// ```ignore
+/// let morph = PathMorph::new(&play_icon, &pause_icon);
+/// canvas(move |ctx, _| {
+///     ctx.fill_path(&morph.at(progress.get()), Color::WHITE.into());
+/// })
+/// ```
+#[derive(Clone, Debug)]
+pub struct PathMorph {
+    pairs: Vec<(Contour, Contour)>,
+}
+
+impl PathMorph {
+    /// Normalize `from` and `to` into matching cubic segments
+    pub fn new(from: &Path, to: &Path) -> Self {
+        let from = contours(from);
+        let to = contours(to);
+        let single = from.len() == 1 && to.len() == 1;
+        let pairs = (0..from.len().max(to.len()))
+            .map(|i| match (from.get(i), to.get(i)) {
+                (Some(a), Some(b)) => pair(a.clone(), b.clone(), single),
+                (Some(a), None) => (a.clone(), a.collapsed()),
+                (None, Some(b)) => (b.collapsed(), b.clone()),
+                (None, None) => unreachable!(),
+            })
+            .collect();
+        Self { pairs }
+    }
+
+    /// The shape `t` of the way from the first path to the second
+    pub fn at(&self, t: f32) -> Path {
+        let mut path = Path::new();
+        for (a, b) in &self.pairs {
+            let start = mix(a.start, b.start, t);
+            path = path.move_to(start.x, start.y);
+            for (p, q) in a.segments.iter().zip(&b.segments) {
+                let c1 = mix(p[0], q[0], t);
+                let c2 = mix(p[1], q[1], t);
+                let end = mix(p[2], q[2], t);
+                path = path.cubic_to(c1.x, c1.y, c2.x, c2.y, end.x, end.y);
+            }
+            if if t < 0.5 { a.closed } else { b.closed } {
+                path = path.close();
+            }
+        }
+        path
+    }
+}
+
+/// Give two contours the same segment count and line up closed ones
+///
+/// Reversing a subpath can change how it fills against its siblings, so
+/// winding is only matched when both paths are a single shape.
+fn pair(mut a: Contour, mut b: Contour, match_winding: bool) -> (Contour, Contour) {
+    while a.segments.len() < b.segments.len() {
+        a.split_longest();
+    }
+    while b.segments.len() < a.segments.len() {
+        b.split_longest();
+    }
+
+    if a.closed && b.closed {
+        if match_winding && (a.signed_area() > 0.0) != (b.signed_area() > 0.0) {
+            b = b.reversed();
+        }
+        let count = b.segments.len();
+        let offset = (0..count)
+            .map(|offset| {
+                let cost: f32 = (0..count)
+                    .map(|i| {
+                        let (p, q) = (a.anchor(i), b.anchor((i + offset) % count));
+                        (p.x - q.x).powi(2) + (p.y - q.y).powi(2)
+                    })
+                    .sum();
+                (offset, cost)
+            })
+            .min_by(|x, y| x.1.total_cmp(&y.1))
+            .map_or(0, |(offset, _)| offset);
+        b = b.rotated(offset);
+    }
+    (a, b)
+}
+
+impl Interpolate for Path {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        PathMorph::new(self, other).at(t)
+    }
+
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        let (a, b) = (contours(self), contours(other));
+        a.len() == b.len() && a.iter().zip(&b).all(|(x, y)| x.approx_eq(y, epsilon))
+    }
+}
+
+// ============================================================================
+// Motion Paths
+// ============================================================================
+
+/// How something following a [`MotionPath`] is turned (CSS `offset-rotate`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OffsetRotate {
+    /// Face along the path, plus an angle in degrees (`auto`, `auto 90deg`;
+    /// `reverse` is `Auto(180.0)`)
+    Auto(f32),
+    /// A fixed angle in degrees
+    Fixed(f32),
+}
+
+impl Default for OffsetRotate {
+    fn default() -> Self {
+        OffsetRotate::Auto(0.0)
+    }
+}
+
+/// A point on a [`MotionPath`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MotionSample {
+    /// Position on the path
+    pub position: Point,
+    /// Direction of travel in degrees (clockwise from +x)
+    pub angle: f32,
+}
+
+/// A path to move along by progress, like CSS `offset-path`
+///
+/// Curves are flattened once on construction, and progress is measured by
+/// length along the path. Progress wraps around closed paths and is clamped
+/// on open ones. Gaps between subpaths are skipped.
+///
+/// # Example
+///
+/// ```ignore
+/// let orbit = MotionPath::new(&Path::circle(Point::new(100.0, 100.0), 80.0));
+/// let entry = timeline.add(0, 2000, 0.0, 1.0);
+///
+/// let sample = orbit.sample(timeline.get(entry).unwrap_or(0.0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct MotionPath {
+    /// Flattened lines as `(from, to, distance to from, length)`
+    lines: Vec<(Point, Point, f32, f32)>,
+    length: f32,
+    closed: bool,
+    /// Position of an empty path
+    origin: Point,
+}
+
+impl MotionPath {
+    /// Flatten `path` for sampling
+    pub fn new(path: &Path) -> Self {
+        let contours = contours(path);
+        let mut lines = Vec::new();
+        let mut length = 0.0;
+        for contour in &contours {
+            let mut from = contour.start;
+            for (i, segment) in contour.segments.iter().enumerate() {
+                let steps = ((polygon_length(contour.anchor(i), segment) / FLATTEN_STEP).ceil()
+                    as usize)
+                    .clamp(1, MAX_FLATTEN_STEPS);
+                for step in 1..=steps {
+                    let to = cubic_point(contour.anchor(i), segment, step as f32 / steps as f32);
+                    let line = distance(from, to);
+                    if line > 1e-6 {
+                        lines.push((from, to, length, line));
+                        length += line;
+                        from = to;
+                    }
+                }
+            }
+        }
+
+        let origin = match path.commands().first() {
+            Some(PathCommand::MoveTo(p)) => *p,
+            _ => Point::ZERO,
+        };
+        Self {
+            lines,
+            length,
+            closed: contours.len() == 1 && contours[0].closed,
+            origin,
+        }
+    }
+
+    /// Total length
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+
+    /// Whether progress wraps around
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Point at `progress` of the way along (0 to 1)
+    pub fn sample(&self, progress: f32) -> MotionSample {
+        self.sample_at_length(progress * self.length)
+    }
+
+    /// Point `distance` along the path
+    pub fn sample_at_length(&self, distance: f32) -> MotionSample {
+        let Some(last) = self.lines.last() else {
+            return MotionSample {
+                position: self.origin,
+                angle: 0.0,
+            };
+        };
+
+        let distance = if self.closed {
+            distance.rem_euclid(self.length)
+        } else {
+            distance.clamp(0.0, self.length)
+        };
+        let index = self
+            .lines
+            .partition_point(|line| line.2 + line.3 < distance);
+        let (from, to, start, length) = self.lines.get(index).unwrap_or(last);
+        MotionSample {
+            position: mix(*from, *to, ((distance - start) / length).clamp(0.0, 1.0)),
+            angle: (to.y - from.y).atan2(to.x - from.x).to_degrees(),
+        }
+    }
+
+    /// Placement at `progress` for something whose own coordinates have
+    /// `anchor` as the point that sits on the path
+    ///
+    /// Compose this outside the element's own transform.
+    pub fn transform(&self, progress: f32, rotate: OffsetRotate, anchor: Point) -> Affine2D {
+        let sample = self.sample(progress);
+        let angle = match rotate {
+            OffsetRotate::Auto(extra) => sample.angle + extra,
+            OffsetRotate::Fixed(angle) => angle,
+        };
+        Affine2D::translation(sample.position.x, sample.position.y)
+            .then(&Affine2D::rotation(angle.to_radians()))
+            .then(&Affine2D::translation(-anchor.x, -anchor.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f32, y: f32, size: f32) -> Path {
+        Path::new()
+            .move_to(x, y)
+            .line_to(x + size, y)
+            .line_to(x + size, y + size)
+            .line_to(x, y + size)
+            .close()
+    }
+
+    #[test]
+    fn test_morph_normalizes_paths() {
+        let circle = Path::circle(Point::new(50.0, 50.0), 50.0);
+        let triangle = Path::new()
+            .move_to(50.0, 0.0)
+            .line_to(100.0, 100.0)
+            .line_to(0.0, 100.0)
+            .close();
+
+        // Ends match the inputs' shapes
+        let morph = PathMorph::new(&circle, &triangle);
+        let start = morph.at(0.0).bounds();
+        assert!((start.width() - 100.0).abs() < 1e-3 && (start.x()).abs() < 1e-3);
+        let end = MotionPath::new(&morph.at(1.0));
+        let expected = MotionPath::new(&triangle);
+        assert!((end.length() - expected.length()).abs() < 1e-2);
+
+        // Every frame has one closed subpath of equal segment count
+        let mid = morph.at(0.5);
+        let cubics = mid
+            .commands()
+            .iter()
+            .filter(|c| matches!(c, PathCommand::CubicTo { .. }))
+            .count();
+        assert_eq!(cubics, 4);
+        assert!(matches!(mid.commands().last(), Some(PathCommand::Close)));
+
+        // Through Interpolate, halfway between two squares is the middle square
+        let half = square(0.0, 0.0, 10.0).lerp(&square(20.0, 20.0, 30.0), 0.5);
+        assert!(half.approx_eq(
+            &square(10.0, 10.0, 20.0).lerp(&square(10.0, 10.0, 20.0), 0.0),
+            1e-3
+        ));
+    }
+
+    #[test]
+    fn test_morph_pairs_subpaths() {
+        // The extra subpath grows out of its own center
+        let one = square(0.0, 0.0, 10.0);
+        let two = Path::from_commands(
+            square(0.0, 0.0, 10.0)
+                .commands()
+                .iter()
+                .chain(square(50.0, 50.0, 10.0).commands())
+                .cloned()
+                .collect(),
+        );
+        let start = PathMorph::new(&one, &two).at(0.0);
+        let moves: Vec<Point> = start
+            .commands()
+            .iter()
+            .filter_map(|c| match c {
+                PathCommand::MoveTo(p) => Some(*p),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(moves.len(), 2);
+        assert!((moves[1].x - 55.0).abs() < 1e-3 && (moves[1].y - 55.0).abs() < 1e-3);
+
+        // A rotated start point is lined up instead of twisting
+        let shifted = Path::new()
+            .move_to(10.0, 10.0)
+            .line_to(0.0, 10.0)
+            .line_to(0.0, 0.0)
+            .line_to(10.0, 0.0)
+            .close();
+        let morph = PathMorph::new(&one, &shifted);
+        assert!(morph.at(0.5).approx_eq(&morph.at(0.0), 1e-3));
+    }
+
+    #[test]
+    fn test_motion_path_sampling() {
+        let line = MotionPath::new(
+            &Path::new()
+                .move_to(0.0, 0.0)
+                .line_to(100.0, 0.0)
+                .line_to(100.0, 100.0),
+        );
+        assert!((line.length() - 200.0).abs() < 1e-3);
+        assert!(!line.is_closed());
+
+        let quarter = line.sample(0.25);
+        assert!((quarter.position.x - 50.0).abs() < 1e-3 && quarter.angle.abs() < 1e-3);
+        let three_quarters = line.sample(0.75);
+        assert!((three_quarters.position.y - 50.0).abs() < 1e-3);
+        assert!((three_quarters.angle - 90.0).abs() < 1e-3);
+        // Open paths clamp
+        assert!((line.sample(2.0).position.y - 100.0).abs() < 1e-3);
+
+        // Closed paths wrap
+        let loop_path = MotionPath::new(&square(0.0, 0.0, 100.0));
+        assert!(loop_path.is_closed());
+        let wrapped = loop_path.sample(1.125);
+        assert!((wrapped.position.x - 50.0).abs() < 1e-3 && wrapped.position.y.abs() < 1e-3);
+
+        // Curves are measured by arc length
+        let circle = MotionPath::new(&Path::circle(Point::ZERO, 50.0));
+        assert!((circle.length() - std::f32::consts::TAU * 50.0).abs() < 0.5);
+
+        // The anchor lands on the path, turned to face along it
+        let placement = line.transform(0.75, OffsetRotate::Auto(0.0), Point::new(5.0, 5.0));
+        let anchor = placement.transform_point(Point::new(5.0, 5.0));
+        assert!((anchor.x - 100.0).abs() < 1e-3 && (anchor.y - 50.0).abs() < 1e-3);
+        let ahead = placement.transform_point(Point::new(15.0, 5.0));
+        assert!((ahead.y - 60.0).abs() < 1e-3);
+        let fixed = line.transform(0.75, OffsetRotate::Fixed(0.0), Point::new(5.0, 5.0));
+        assert!((fixed.transform_point(Point::new(15.0, 5.0)).x - 110.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_arcs_become_cubics() {
+        let arc = Path::new().move_to(0.0, 0.0).arc_to(
+            Vec2::new(50.0, 50.0),
+            0.0,
+            false,
+            true,
+            100.0,
+            0.0,
+        );
+        let motion = MotionPath::new(&arc);
+        // Half circle of radius 50, bulging toward -y for a clockwise sweep
+        assert!((motion.length() - std::f32::consts::PI * 50.0).abs() < 0.5);
+        assert!((motion.sample(0.5).position.y + 50.0).abs() < 0.1);
+    }
+}
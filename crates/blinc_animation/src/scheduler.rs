@@ -6,6 +6,7 @@
 //! - `AnimatedKeyframe` - Keyframe-based timed animations
 //! - `AnimatedTimeline` - Timeline orchestration of multiple animations
 
+use crate::clock::{time_scale, RealClock, SharedClock};
 use crate::easing::Easing;
 use crate::keyframe::{Keyframe, KeyframeAnimation};
 use crate::spring::{Spring, SpringConfig};
@@ -125,10 +126,21 @@ struct SchedulerInner {
     keyframes: SlotMap<KeyframeId, KeyframeAnimation>,
     timelines: SlotMap<TimelineId, Timeline>,
     tick_callbacks: SlotMap<TickCallbackId, TickCallback>,
-    last_frame: Instant,
+    clock: SharedClock,
+    last_frame: Duration,
     target_fps: u32,
 }
 
+impl SchedulerInner {
+    /// Seconds since the last frame, scaled by the global time scale
+    fn frame_delta(&mut self) -> f32 {
+        let now = self.clock.now();
+        let dt = now.saturating_sub(self.last_frame).as_secs_f32();
+        self.last_frame = now;
+        dt * time_scale()
+    }
+}
+
 /// Callback type for waking up the main thread from the animation thread
 ///
 /// This is called when there are active animations that need to be rendered.
@@ -167,13 +179,23 @@ pub struct AnimationScheduler {
 
 impl AnimationScheduler {
     pub fn new() -> Self {
+        Self::with_clock(Arc::new(RealClock::new()))
+    }
+
+    /// Create a scheduler that measures frame time with `clock`
+    ///
+    /// With a [`ManualClock`](crate::ManualClock), each `tick()` steps
+    /// animations by exactly the time the clock was advanced.
+    pub fn with_clock(clock: SharedClock) -> Self {
+        let last_frame = clock.now();
         Self {
             inner: Arc::new(Mutex::new(SchedulerInner {
                 springs: SlotMap::with_key(),
                 keyframes: SlotMap::with_key(),
                 timelines: SlotMap::with_key(),
                 tick_callbacks: SlotMap::with_key(),
-                last_frame: Instant::now(),
+                clock,
+                last_frame,
                 target_fps: 120,
             })),
             stop_flag: Arc::new(AtomicBool::new(false)),
@@ -236,10 +258,8 @@ impl AnimationScheduler {
                 // Tick animations and check if any are active
                 let (has_active, tick_callbacks_to_call, dt) = {
                     let mut inner = inner.lock().unwrap();
-                    let now = Instant::now();
-                    let dt = (now - inner.last_frame).as_secs_f32();
+                    let dt = inner.frame_delta();
                     let dt_ms = dt * 1000.0;
-                    inner.last_frame = now;
 
                     // Update all springs
                     for (_, spring) in inner.springs.iter_mut() {
@@ -376,6 +396,20 @@ impl AnimationScheduler {
         }
     }
 
+    /// Replace the clock that measures frame time
+    ///
+    /// The next tick measures from the new clock's current time.
+    pub fn set_clock(&self, clock: SharedClock) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.last_frame = clock.now();
+        inner.clock = clock;
+    }
+
+    /// The clock that measures frame time
+    pub fn clock(&self) -> SharedClock {
+        Arc::clone(&self.inner.lock().unwrap().clock)
+    }
+
     pub fn set_target_fps(&mut self, fps: u32) {
         self.inner.lock().unwrap().target_fps = fps;
     }
@@ -385,10 +419,8 @@ impl AnimationScheduler {
     /// Returns true if any animations are still active (need another tick).
     pub fn tick(&self) -> bool {
         let mut inner = self.inner.lock().unwrap();
-        let now = Instant::now();
-        let dt = (now - inner.last_frame).as_secs_f32();
+        let dt = inner.frame_delta();
         let dt_ms = dt * 1000.0;
-        inner.last_frame = now;
 
         // Update all springs
         for (_, spring) in inner.springs.iter_mut() {
@@ -695,6 +727,13 @@ pub struct SchedulerHandle {
 }
 
 impl SchedulerHandle {
+    /// Current time of the scheduler's clock
+    pub fn now(&self) -> Option<Duration> {
+        self.inner
+            .upgrade()
+            .map(|inner| inner.lock().unwrap().clock.now())
+    }
+
     // =========================================================================
     // Spring Operations
     // =========================================================================
@@ -705,7 +744,7 @@ impl SchedulerHandle {
             let mut guard = inner.lock().unwrap();
             // Reset last_frame to now to prevent huge dt on first tick
             // This ensures new springs start animating smoothly from their current frame
-            guard.last_frame = std::time::Instant::now();
+            guard.last_frame = guard.clock.now();
             guard.springs.insert(spring)
         })
     }
@@ -719,6 +758,15 @@ impl SchedulerHandle {
         }
     }
 
+    /// Replace a spring's velocity (units per second)
+    pub fn set_spring_velocity(&self, id: SpringId, velocity: f32) {
+        if let Some(inner) = self.inner.upgrade() {
+            if let Some(spring) = inner.lock().unwrap().springs.get_mut(id) {
+                spring.set_velocity(velocity);
+            }
+        }
+    }
+
     /// Get current spring value
     pub fn get_spring_value(&self, id: SpringId) -> Option<f32> {
         self.inner
@@ -1040,6 +1088,24 @@ impl AnimatedValue {
         }
     }
 
+    /// Set the target value, starting from the given velocity (units per second)
+    ///
+    /// Use this to hand off a gesture's release velocity (or a decay's
+    /// remaining velocity) so the spring continues the motion instead of
+    /// starting from rest.
+    pub fn set_target_with_velocity(&mut self, target: f32, velocity: f32) {
+        self.target = target;
+
+        if let Some(id) = self.spring_id {
+            self.handle.set_spring_target(id, target);
+            self.handle.set_spring_velocity(id, velocity);
+        } else {
+            let mut spring = Spring::new(self.config, self.current).with_velocity(velocity);
+            spring.set_target(target);
+            self.spring_id = self.handle.register_spring(spring);
+        }
+    }
+
     /// Get the current animated value
     pub fn get(&self) -> f32 {
         if let Some(id) = self.spring_id {
@@ -1143,7 +1209,7 @@ pub struct AnimatedKeyframe {
     /// Delay before animation starts (ms)
     delay_ms: u32,
     /// Time when animation started (for delay tracking)
-    start_time: Option<std::time::Instant>,
+    start_time: Option<Duration>,
 }
 
 impl AnimatedKeyframe {
@@ -1235,7 +1301,7 @@ impl AnimatedKeyframe {
 
         // Track start time for delay
         if self.delay_ms > 0 {
-            self.start_time = Some(std::time::Instant::now());
+            self.start_time = self.handle.now();
         } else {
             self.start_time = None;
         }
@@ -1283,7 +1349,8 @@ impl AnimatedKeyframe {
     fn check_and_update(&mut self) -> bool {
         // Handle delay
         if let Some(start_time) = self.start_time {
-            let elapsed = start_time.elapsed().as_millis() as u32;
+            let now = self.handle.now().unwrap_or(start_time);
+            let elapsed = now.saturating_sub(start_time).as_millis() as u32;
             if elapsed < self.delay_ms {
                 return true; // Still in delay period
             }
@@ -1668,6 +1735,15 @@ impl AnimatedTimeline {
         }
     }
 
+    /// Seek to a fraction of the duration (0.0 to 1.0)
+    pub fn set_progress(&self, progress: f32) {
+        if let Some(id) = self.timeline_id {
+            self.handle.with_timeline(id, |timeline| {
+                timeline.set_progress(progress);
+            });
+        }
+    }
+
     /// Get the current value for a timeline entry
     pub fn get(&self, entry_id: crate::timeline::TimelineEntryId) -> Option<f32> {
         if let Some(id) = self.timeline_id {
@@ -1749,6 +1825,53 @@ impl Drop for AnimatedTimeline {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::ManualClock;
+
+    #[test]
+    fn test_manual_clock_is_deterministic() {
+        let clock = Arc::new(ManualClock::new());
+        let scheduler = AnimationScheduler::with_clock(clock.clone());
+        let handle = scheduler.handle();
+
+        let mut anim = AnimatedKeyframe::new(handle, 1000)
+            .keyframe(0.0, 0.0, Easing::Linear)
+            .keyframe(1.0, 100.0, Easing::Linear);
+        anim.start();
+
+        clock.advance(Duration::from_millis(250));
+        scheduler.tick();
+        assert_eq!(anim.get(), 25.0);
+
+        // No time passes without the clock advancing
+        scheduler.tick();
+        assert_eq!(anim.get(), 25.0);
+
+        // Springs step identically across runs
+        let run = || {
+            let clock = Arc::new(ManualClock::new());
+            let scheduler = AnimationScheduler::with_clock(clock.clone());
+            let id = scheduler.add_spring(Spring::new(SpringConfig::wobbly(), 0.0));
+            scheduler.set_spring_target(id, 100.0);
+            (0..10)
+                .map(|_| {
+                    clock.advance(Duration::from_millis(16));
+                    scheduler.tick();
+                    scheduler.get_spring_value(id).unwrap()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn test_set_clock() {
+        let scheduler = AnimationScheduler::new();
+        let clock = Arc::new(ManualClock::new());
+        clock.set(Duration::from_secs(5));
+        scheduler.set_clock(clock.clone());
+        assert_eq!(scheduler.clock().now(), Duration::from_secs(5));
+        assert_eq!(scheduler.handle().now(), Some(Duration::from_secs(5)));
+    }
 
     #[test]
     fn test_scheduler_tick() {
@@ -1788,6 +1911,28 @@ mod tests {
         assert!(value.get() > 0.0);
     }
 
+    #[test]
+    fn test_animated_value_velocity_handoff() {
+        let clock = Arc::new(ManualClock::new());
+        let scheduler = AnimationScheduler::with_clock(clock.clone());
+
+        // Released at the target while still moving: the spring carries on past it
+        let mut value = AnimatedValue::new(scheduler.handle(), 50.0, SpringConfig::snappy());
+        value.set_target_with_velocity(50.0, 800.0);
+        assert!(value.is_animating());
+
+        clock.advance(Duration::from_millis(16));
+        scheduler.tick();
+        assert!(value.get() > 50.0);
+
+        for _ in 0..120 {
+            clock.advance(Duration::from_millis(16));
+            scheduler.tick();
+        }
+        assert!(!value.is_animating());
+        assert!((value.get() - 50.0).abs() < 0.01);
+    }
+
     #[test]
     fn test_animated_keyframe() {
         let scheduler = AnimationScheduler::new();
@@ -3,8 +3,14 @@
 //! RK4-integrated spring physics for smooth, natural animations.
 //! Supports preset configurations and custom spring parameters.
 
+use std::marker::PhantomData;
+
+use blinc_core::{Affine2D, Color, Point, Rect, Size};
+
+use crate::values::Animatable;
+
 /// Configuration for a spring animation
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct SpringConfig {
     pub stiffness: f32,
     pub damping: f32,
@@ -128,6 +134,17 @@ impl Spring {
         self.target = target;
     }
 
+    /// Start with a velocity (units per second), e.g. handed off from a gesture
+    pub fn with_velocity(mut self, velocity: f32) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    /// Replace the current velocity (units per second)
+    pub fn set_velocity(&mut self, velocity: f32) {
+        self.velocity = velocity;
+    }
+
     /// Check if the spring has settled (within epsilon of target with minimal velocity)
     pub fn is_settled(&self) -> bool {
         // Use small epsilons that work for both pixel-based values (scroll)
@@ -176,6 +193,107 @@ impl Spring {
     }
 }
 
+/// A spring over any [`Animatable`] value
+///
+/// Each component runs its own [`Spring`] with a shared config, so a flung
+/// point or rect keeps a separate velocity per axis.
+///
+/// # Example
+///
+/// ```ignore
+/// // Hand a drag's release velocity to the spring that snaps a sheet into place
+/// let mut spring = TypedSpring::new(SpringConfig::snappy(), drag.position())
+///     .with_velocity(drag.velocity());
+/// spring.set_target(Point::new(0.0, snap_y));
+/// ```
+#[derive(Clone, Debug)]
+pub struct TypedSpring<T: Animatable> {
+    springs: Vec<Spring>,
+    _value: PhantomData<T>,
+}
+
+impl<T: Animatable> TypedSpring<T> {
+    /// Create a spring at rest at `initial`
+    pub fn new(config: SpringConfig, initial: T) -> Self {
+        Self {
+            springs: initial
+                .components()
+                .into_iter()
+                .map(|c| Spring::new(config, c))
+                .collect(),
+            _value: PhantomData,
+        }
+    }
+
+    /// Start with a per-component velocity (units per second)
+    pub fn with_velocity(mut self, velocity: T) -> Self {
+        self.set_velocity(velocity);
+        self
+    }
+
+    /// Current value
+    pub fn value(&self) -> T {
+        T::from_components(&self.springs.iter().map(Spring::value).collect::<Vec<_>>())
+    }
+
+    /// Current per-component velocity (units per second)
+    pub fn velocity(&self) -> T {
+        T::from_components(
+            &self
+                .springs
+                .iter()
+                .map(Spring::velocity)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Value being animated towards
+    pub fn target(&self) -> T {
+        T::from_components(&self.springs.iter().map(Spring::target).collect::<Vec<_>>())
+    }
+
+    /// Retarget, keeping the current velocity
+    pub fn set_target(&mut self, target: T) {
+        for (spring, c) in self.springs.iter_mut().zip(target.components()) {
+            spring.set_target(c);
+        }
+    }
+
+    /// Replace the per-component velocity (units per second)
+    pub fn set_velocity(&mut self, velocity: T) {
+        for (spring, v) in self.springs.iter_mut().zip(velocity.components()) {
+            spring.set_velocity(v);
+        }
+    }
+
+    /// Whether every component has settled
+    pub fn is_settled(&self) -> bool {
+        self.springs.iter().all(Spring::is_settled)
+    }
+
+    /// Step every component by `dt` seconds
+    pub fn step(&mut self, dt: f32) {
+        for spring in &mut self.springs {
+            spring.step(dt);
+        }
+    }
+}
+
+/// Spring for 2D positions
+pub type PointSpring = TypedSpring<Point>;
+
+/// Spring for sizes
+pub type SizeSpring = TypedSpring<Size>;
+
+/// Spring for rectangles (position and size together)
+pub type RectSpring = TypedSpring<Rect>;
+
+/// Spring for colors
+pub type ColorSpring = TypedSpring<Color>;
+
+/// Spring for 2D transforms (element-wise)
+pub type TransformSpring = TypedSpring<Affine2D>;
+
 // =============================================================================
 // ZRTL Plugin Exports
 // =============================================================================
@@ -214,6 +332,7 @@ mod ffi {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::values::Interpolate;
 
     #[test]
     fn test_spring_settles_to_target() {
@@ -273,6 +392,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_spring_with_velocity_overshoots() {
+        // A flick towards the target carries the spring past it
+        let mut spring = Spring::new(SpringConfig::wobbly(), 0.0).with_velocity(2000.0);
+        spring.set_target(100.0);
+
+        let mut peak: f32 = 0.0;
+        for _ in 0..240 {
+            spring.step(1.0 / 60.0);
+            peak = peak.max(spring.value());
+        }
+
+        assert!(peak > 110.0);
+        assert!(spring.is_settled());
+    }
+
+    #[test]
+    fn test_typed_spring_per_component_velocity() {
+        let mut spring = PointSpring::new(SpringConfig::stiff(), Point::new(0.0, 0.0))
+            .with_velocity(Point::new(0.0, 500.0));
+        spring.set_target(Point::new(100.0, 0.0));
+
+        spring.step(1.0 / 60.0);
+        let v = spring.velocity();
+        // x is pulled towards its target, y keeps its own fling
+        assert!(v.x > 0.0);
+        assert!(v.y > 0.0);
+        assert!(spring.value().y > 0.0);
+
+        for _ in 0..180 {
+            spring.step(1.0 / 60.0);
+        }
+        assert!(spring.is_settled());
+        let p = spring.value();
+        assert!((p.x - 100.0).abs() < 0.01 && p.y.abs() < 0.01);
+    }
+
+    #[test]
+    fn test_typed_spring_rect() {
+        let mut spring = RectSpring::new(SpringConfig::snappy(), Rect::new(0.0, 0.0, 10.0, 10.0));
+        spring.set_target(Rect::new(20.0, 40.0, 100.0, 50.0));
+        assert_eq!(spring.target(), Rect::new(20.0, 40.0, 100.0, 50.0));
+
+        for _ in 0..240 {
+            spring.step(1.0 / 60.0);
+        }
+        assert!(spring
+            .value()
+            .approx_eq(&Rect::new(20.0, 40.0, 100.0, 50.0), 0.01));
+    }
+
     #[test]
     fn test_spring_different_mass() {
         // Test with heavier mass - should still settle, just slower
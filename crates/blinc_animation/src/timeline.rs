@@ -151,6 +151,13 @@ impl Timeline {
         self.current_time = time_ms.clamp(0.0, self.duration_ms as f32);
     }
 
+    /// Seek to a fraction of the duration (0.0 to 1.0)
+    ///
+    /// Lets input such as scrolling or dragging drive a paused timeline.
+    pub fn set_progress(&mut self, progress: f32) {
+        self.seek(progress.clamp(0.0, 1.0) * self.duration_ms as f32);
+    }
+
     /// Set loop count (-1 for infinite, 0 to disable, positive for specific count)
     pub fn set_loop(&mut self, count: i32) {
         self.loop_count = count;
@@ -255,10 +262,22 @@ impl Timeline {
 
     /// Get the current value for an animation entry
     pub fn value(&self, id: TimelineEntryId) -> Option<f32> {
+        self.value_at_time(id, self.current_time)
+    }
+
+    /// Get the value an entry would have at a fraction of the duration (0.0 to 1.0)
+    ///
+    /// Samples without moving the playhead, so one timeline can be shared by
+    /// several progress sources.
+    pub fn value_at(&self, id: TimelineEntryId, progress: f32) -> Option<f32> {
+        self.value_at_time(id, progress.clamp(0.0, 1.0) * self.duration_ms as f32)
+    }
+
+    fn value_at_time(&self, id: TimelineEntryId, time_ms: f32) -> Option<f32> {
         let entry = self.entries.get(id)?;
 
         // Calculate local time relative to entry offset
-        let local_time = self.current_time - entry.offset_ms as f32;
+        let local_time = time_ms - entry.offset_ms as f32;
 
         // Before entry starts
         if local_time < 0.0 {
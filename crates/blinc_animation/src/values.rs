@@ -3,7 +3,7 @@
 //! Provides traits and implementations for values that can be animated,
 //! including linear interpolation for vectors and colors.
 
-use blinc_core::{Color, Vec3};
+use blinc_core::{Affine2D, Color, CornerRadius, Point, Rect, Size, Vec2, Vec3};
 
 /// Trait for values that can be linearly interpolated
 pub trait Interpolate: Clone {
@@ -14,6 +14,18 @@ pub trait Interpolate: Clone {
     fn approx_eq(&self, other: &Self, epsilon: f32) -> bool;
 }
 
+/// Values made of independent `f32` components
+///
+/// Springs and decays animate each component on its own, so a value keeps a
+/// separate velocity per component (e.g. `x` and `y` of a flung point).
+pub trait Animatable: Interpolate {
+    /// Flatten the value into its components
+    fn components(&self) -> Vec<f32>;
+
+    /// Rebuild a value from components in the order of [`components`](Self::components)
+    fn from_components(components: &[f32]) -> Self;
+}
+
 /// Trait for values that use spherical interpolation (quaternions)
 pub trait SphericalInterpolate: Clone {
     /// Spherically interpolate between self and other by factor t (0.0 to 1.0)
@@ -74,6 +86,205 @@ impl Interpolate for Color {
     }
 }
 
+// ============================================================================
+// Geometry Implementations
+// ============================================================================
+
+impl Interpolate for Vec2 {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Vec2::new(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+        )
+    }
+
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        (self.x - other.x).abs() < epsilon && (self.y - other.y).abs() < epsilon
+    }
+}
+
+impl Interpolate for Point {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Point::new(
+            self.x + (other.x - self.x) * t,
+            self.y + (other.y - self.y) * t,
+        )
+    }
+
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        (self.x - other.x).abs() < epsilon && (self.y - other.y).abs() < epsilon
+    }
+}
+
+impl Interpolate for Size {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Size::new(
+            self.width + (other.width - self.width) * t,
+            self.height + (other.height - self.height) * t,
+        )
+    }
+
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        (self.width - other.width).abs() < epsilon && (self.height - other.height).abs() < epsilon
+    }
+}
+
+impl Interpolate for Rect {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Rect {
+            origin: self.origin.lerp(&other.origin, t),
+            size: self.size.lerp(&other.size, t),
+        }
+    }
+
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        self.origin.approx_eq(&other.origin, epsilon) && self.size.approx_eq(&other.size, epsilon)
+    }
+}
+
+impl Interpolate for CornerRadius {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        CornerRadius::new(
+            self.top_left.lerp(&other.top_left, t),
+            self.top_right.lerp(&other.top_right, t),
+            self.bottom_right.lerp(&other.bottom_right, t),
+            self.bottom_left.lerp(&other.bottom_left, t),
+        )
+    }
+
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        (self.top_left - other.top_left).abs() < epsilon
+            && (self.top_right - other.top_right).abs() < epsilon
+            && (self.bottom_right - other.bottom_right).abs() < epsilon
+            && (self.bottom_left - other.bottom_left).abs() < epsilon
+    }
+}
+
+/// Element-wise, which is exact for translations and scales and a good
+/// approximation for small rotations
+impl Interpolate for Affine2D {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let mut elements = self.elements;
+        for (e, o) in elements.iter_mut().zip(other.elements) {
+            *e += (o - *e) * t;
+        }
+        Affine2D { elements }
+    }
+
+    fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        self.elements
+            .iter()
+            .zip(other.elements)
+            .all(|(e, o)| (e - o).abs() < epsilon)
+    }
+}
+
+// ============================================================================
+// Animatable Implementations
+// ============================================================================
+
+impl Animatable for f32 {
+    fn components(&self) -> Vec<f32> {
+        vec![*self]
+    }
+
+    fn from_components(c: &[f32]) -> Self {
+        c[0]
+    }
+}
+
+impl Animatable for Vec2 {
+    fn components(&self) -> Vec<f32> {
+        vec![self.x, self.y]
+    }
+
+    fn from_components(c: &[f32]) -> Self {
+        Vec2::new(c[0], c[1])
+    }
+}
+
+impl Animatable for Vec3 {
+    fn components(&self) -> Vec<f32> {
+        vec![self.x, self.y, self.z]
+    }
+
+    fn from_components(c: &[f32]) -> Self {
+        Vec3::new(c[0], c[1], c[2])
+    }
+}
+
+impl Animatable for Point {
+    fn components(&self) -> Vec<f32> {
+        vec![self.x, self.y]
+    }
+
+    fn from_components(c: &[f32]) -> Self {
+        Point::new(c[0], c[1])
+    }
+}
+
+impl Animatable for Size {
+    fn components(&self) -> Vec<f32> {
+        vec![self.width, self.height]
+    }
+
+    fn from_components(c: &[f32]) -> Self {
+        Size::new(c[0], c[1])
+    }
+}
+
+impl Animatable for Rect {
+    fn components(&self) -> Vec<f32> {
+        vec![
+            self.origin.x,
+            self.origin.y,
+            self.size.width,
+            self.size.height,
+        ]
+    }
+
+    fn from_components(c: &[f32]) -> Self {
+        Rect::new(c[0], c[1], c[2], c[3])
+    }
+}
+
+impl Animatable for Color {
+    fn components(&self) -> Vec<f32> {
+        vec![self.r, self.g, self.b, self.a]
+    }
+
+    fn from_components(c: &[f32]) -> Self {
+        Color::rgba(c[0], c[1], c[2], c[3])
+    }
+}
+
+impl Animatable for CornerRadius {
+    fn components(&self) -> Vec<f32> {
+        vec![
+            self.top_left,
+            self.top_right,
+            self.bottom_right,
+            self.bottom_left,
+        ]
+    }
+
+    fn from_components(c: &[f32]) -> Self {
+        CornerRadius::new(c[0], c[1], c[2], c[3])
+    }
+}
+
+impl Animatable for Affine2D {
+    fn components(&self) -> Vec<f32> {
+        self.elements.to_vec()
+    }
+
+    fn from_components(c: &[f32]) -> Self {
+        let mut elements = [0.0; 6];
+        elements.copy_from_slice(&c[..6]);
+        Affine2D { elements }
+    }
+}
+
 // ============================================================================
 // Generic Keyframe Animation for any Interpolate type
 // ============================================================================
@@ -282,9 +493,15 @@ pub type FloatKeyframe = TypedKeyframe<f32>;
 /// Vec3 keyframe
 pub type Vec3Keyframe = TypedKeyframe<Vec3>;
 
+/// Keyframe animation for vector paths (shape morphing)
+pub type PathAnimation = TypedKeyframeAnimation<blinc_core::Path>;
+
 /// Color keyframe
 pub type ColorKeyframe = TypedKeyframe<Color>;
 
+/// Path keyframe
+pub type PathKeyframe = TypedKeyframe<blinc_core::Path>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
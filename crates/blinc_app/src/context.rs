@@ -9,11 +9,14 @@ use blinc_gpu::{
     GpuPaintContext, GpuPrimitive, GpuRenderer, ImageRenderingContext, PrimitiveBatch,
     TextAlignment, TextAnchor, TextRenderingContext,
 };
-use blinc_layout::div::{FontFamily, FontWeight, GenericFont, TextAlign, TextVerticalAlign};
+use blinc_layout::div::{
+    FontFamily, FontWeight, GenericFont, ParagraphStyle, TextAlign, TextVerticalAlign,
+};
 use blinc_layout::prelude::*;
 use blinc_layout::render_state::Overlay;
 use blinc_layout::renderer::ElementType;
 use blinc_svg::RasterizedSvg;
+use blinc_text::LayoutOptions;
 use lru::LruCache;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
@@ -61,6 +64,9 @@ pub struct RenderContext {
     scratch_images: Vec<ImageElement>,
     canvas_image_eviction_warns: u32,
     canvas_image_missing_warns: u32,
+    // Last glyph atlas stats sent to the recorder (grayscale, color)
+    #[cfg(feature = "recorder")]
+    last_atlas_stats: Option<(blinc_text::AtlasStats, blinc_text::AtlasStats)>,
 }
 
 struct CachedTexture {
@@ -108,6 +114,37 @@ struct TextElement {
     css_affine: Option<[f32; 6]>,
     /// Text shadow (offset_x, offset_y, blur, color) from CSS text-shadow property
     text_shadow: Option<blinc_core::Shadow>,
+    /// Paragraph layout: white-space, ellipsis, clamping, hyphenation
+    paragraph: ParagraphStyle,
+    /// OpenType features and variable font axes
+    font_settings: blinc_core::FontSettings,
+}
+
+impl TextElement {
+    /// Layout options for rendering this text
+    ///
+    /// Mirrors `TextContext::prepare_text_with_style`, plus the element's
+    /// paragraph settings.
+    fn layout_options(
+        &self,
+        anchor: TextAnchor,
+        alignment: TextAlignment,
+        width: Option<f32>,
+        wrap: bool,
+    ) -> LayoutOptions {
+        let mut options = LayoutOptions {
+            anchor,
+            alignment,
+            max_width: width,
+            ..Default::default()
+        };
+        if !wrap {
+            options.line_break = blinc_text::LineBreakMode::None;
+        }
+        crate::text_measurer::apply_paragraph_style(&mut options, &self.paragraph);
+        options.font_settings = self.font_settings.clone();
+        options
+    }
 }
 
 /// Image element data for rendering
@@ -233,9 +270,44 @@ impl RenderContext {
             scratch_images: Vec::with_capacity(32),   // Pre-allocate for image elements
             canvas_image_eviction_warns: 0,
             canvas_image_missing_warns: 0,
+            #[cfg(feature = "recorder")]
+            last_atlas_stats: None,
         }
     }
 
+    /// Advance the glyph atlases to a new frame and report their stats to the
+    /// recorder when they changed since the last report.
+    fn begin_text_frame(&mut self) {
+        self.text_ctx.begin_frame();
+        #[cfg(feature = "recorder")]
+        self.report_atlas_stats();
+    }
+
+    #[cfg(feature = "recorder")]
+    fn report_atlas_stats(&mut self) {
+        if !blinc_layout::recorder_bridge::is_recording() {
+            return;
+        }
+        let stats = self.text_ctx.atlas_stats();
+        if self.last_atlas_stats == Some(stats) {
+            return;
+        }
+        self.last_atlas_stats = Some(stats);
+
+        let convert =
+            |s: blinc_text::AtlasStats| blinc_layout::recorder_bridge::RecorderAtlasStats {
+                pages: s.pages,
+                max_pages: s.max_pages,
+                glyphs: s.glyphs as u64,
+                utilization: s.utilization,
+                evictions: s.evictions,
+                defragmentations: s.defragmentations,
+                allocation_failures: s.allocation_failures,
+                uploaded_bytes: s.uploaded_bytes,
+            };
+        blinc_layout::recorder_bridge::record_atlas_stats(convert(stats.0), convert(stats.1));
+    }
+
     /// Load font data into the text rendering registry
     ///
     /// This adds fonts that will be available for text rendering.
@@ -254,6 +326,8 @@ impl RenderContext {
         height: u32,
         target: &wgpu::TextureView,
     ) -> Result<()> {
+        self.begin_text_frame();
+
         // Get scale factor for HiDPI rendering
         let scale_factor = tree.scale_factor();
 
@@ -322,6 +396,9 @@ impl RenderContext {
                 TextAlign::Left => TextAlignment::Left,
                 TextAlign::Center => TextAlignment::Center,
                 TextAlign::Right => TextAlignment::Right,
+                TextAlign::Start => TextAlignment::Start,
+                TextAlign::End => TextAlignment::End,
+                TextAlign::Justify => TextAlignment::Justify,
             };
 
             // Vertical alignment:
@@ -370,16 +447,13 @@ impl RenderContext {
                 None
             };
 
-            match self.text_ctx.prepare_text_with_style(
+            match self.text_ctx.prepare_text_with_layout_options_and_style(
                 &text.content,
                 text.x,
                 y_pos,
                 text.font_size,
                 text.color,
-                anchor,
-                alignment,
-                Some(wrap_width),
-                text.wrap,
+                &text.layout_options(anchor, alignment, Some(wrap_width), text.wrap),
                 font_name,
                 generic,
                 font_weight,
@@ -449,7 +523,8 @@ impl RenderContext {
                 (self.text_ctx.atlas_view(), self.text_ctx.color_atlas_view())
             {
                 bg_batch.primitives.append(&mut css_transformed_text_prims);
-                self.renderer.set_glyph_atlas(atlas, color_atlas);
+                self.renderer
+                    .set_glyph_atlas(atlas, color_atlas, self.text_ctx.atlas_generation());
             }
         }
 
@@ -772,6 +847,7 @@ impl RenderContext {
                 atlas_view,
                 color_atlas_view,
                 self.text_ctx.sampler(),
+                self.text_ctx.atlas_generation(),
             );
         }
     }
@@ -1934,7 +2010,7 @@ impl RenderContext {
                         height: scaled_height,
                         font_size: scaled_font_size,
                         color: render_node.props.text_color.unwrap_or(text_data.color),
-                        align: text_data.align,
+                        align: render_node.props.text_align.unwrap_or(text_data.align),
                         weight: text_data.weight,
                         italic: text_data.italic,
                         v_align: text_data.v_align,
@@ -1949,6 +2025,38 @@ impl RenderContext {
                         underline: text_data.underline,
                         css_affine: inherited_css_affine,
                         text_shadow: render_node.props.text_shadow,
+                        paragraph: ParagraphStyle {
+                            white_space: render_node
+                                .props
+                                .white_space
+                                .or(text_data.paragraph.white_space),
+                            overflow: render_node
+                                .props
+                                .text_overflow
+                                .or(text_data.paragraph.overflow),
+                            line_clamp: render_node
+                                .props
+                                .line_clamp
+                                .or(text_data.paragraph.line_clamp),
+                            hyphens: render_node.props.hyphens.or(text_data.paragraph.hyphens),
+                            justify: render_node
+                                .props
+                                .text_justify
+                                .or(text_data.paragraph.justify),
+                            lang: text_data.paragraph.lang.clone(),
+                        },
+                        font_settings: {
+                            // CSS features and (possibly animated) axes override
+                            // the element's own settings tag by tag
+                            let mut settings = text_data.font_settings.clone();
+                            for feature in render_node.props.font_features.iter().flatten() {
+                                settings.set_feature(*feature);
+                            }
+                            for variation in render_node.props.font_variations.iter().flatten() {
+                                settings.set_variation(*variation);
+                            }
+                            settings
+                        },
                     });
                 }
                 ElementType::Svg(svg_data) => {
@@ -2229,6 +2337,8 @@ impl RenderContext {
                             underline,
                             css_affine: inherited_css_affine,
                             text_shadow: render_node.props.text_shadow,
+                            paragraph: ParagraphStyle::default(),
+                            font_settings: blinc_core::FontSettings::default(),
                         });
 
                         x_offset += segment_width;
@@ -2303,6 +2413,21 @@ impl RenderContext {
             inherited_css_affine
         };
 
+        // Shared-element flights and offset paths move the whole subtree,
+        // after its CSS transform
+        let child_css_affine = match tree.get_placement_transform(node, bounds.width, bounds.height)
+        {
+            Some(placement) => {
+                use blinc_core::Affine2D;
+                let absolute = Affine2D::translation(abs_x, abs_y)
+                    .then(&placement)
+                    .then(&Affine2D::translation(-abs_x, -abs_y));
+                let inner = child_css_affine.unwrap_or(Affine2D::IDENTITY.elements);
+                Some(absolute.then(&Affine2D { elements: inner }).elements)
+            }
+            None => child_css_affine,
+        };
+
         for child_id in tree.layout().children(node) {
             self.collect_elements_recursive(
                 tree,
@@ -2396,6 +2521,8 @@ impl RenderContext {
         height: u32,
         target: &wgpu::TextureView,
     ) -> Result<()> {
+        self.begin_text_frame();
+
         let scale_factor = tree.scale_factor();
 
         // Create a single paint context for all layers with text rendering support
@@ -2462,6 +2589,9 @@ impl RenderContext {
                 TextAlign::Left => TextAlignment::Left,
                 TextAlign::Center => TextAlignment::Center,
                 TextAlign::Right => TextAlignment::Right,
+                TextAlign::Start => TextAlignment::Start,
+                TextAlign::End => TextAlignment::End,
+                TextAlign::Justify => TextAlignment::Justify,
             };
 
             // Apply motion opacity to text color
@@ -2527,22 +2657,21 @@ impl RenderContext {
                 ];
                 let shadow_x = text.x + shadow.offset_x * scale_factor;
                 let shadow_y = y_pos + shadow.offset_y * scale_factor;
-                if let Ok(mut shadow_glyphs) = self.text_ctx.prepare_text_with_style(
-                    &text.content,
-                    shadow_x,
-                    shadow_y,
-                    text.font_size,
-                    shadow_color,
-                    anchor,
-                    alignment,
-                    wrap_width,
-                    needs_wrap,
-                    font_name,
-                    generic,
-                    font_weight,
-                    text.italic,
-                    layout_height,
-                ) {
+                if let Ok(mut shadow_glyphs) =
+                    self.text_ctx.prepare_text_with_layout_options_and_style(
+                        &text.content,
+                        shadow_x,
+                        shadow_y,
+                        text.font_size,
+                        shadow_color,
+                        &text.layout_options(anchor, alignment, wrap_width, needs_wrap),
+                        font_name,
+                        generic,
+                        font_weight,
+                        text.italic,
+                        layout_height,
+                    )
+                {
                     if let Some(clip) = text.clip_bounds {
                         for glyph in &mut shadow_glyphs {
                             glyph.clip_bounds = clip;
@@ -2577,16 +2706,13 @@ impl RenderContext {
                 }
             }
 
-            match self.text_ctx.prepare_text_with_style(
+            match self.text_ctx.prepare_text_with_layout_options_and_style(
                 &text.content,
                 text.x,
                 y_pos,
                 text.font_size,
                 color,
-                anchor,
-                alignment,
-                wrap_width,
-                needs_wrap,
+                &text.layout_options(anchor, alignment, wrap_width, needs_wrap),
                 font_name,
                 generic,
                 font_weight,
@@ -2666,7 +2792,8 @@ impl RenderContext {
                 (self.text_ctx.atlas_view(), self.text_ctx.color_atlas_view())
             {
                 batch.primitives.append(&mut css_transformed_text_prims);
-                self.renderer.set_glyph_atlas(atlas, color_atlas);
+                self.renderer
+                    .set_glyph_atlas(atlas, color_atlas, self.text_ctx.atlas_generation());
             }
         }
 
@@ -3101,6 +3228,9 @@ impl RenderContext {
                 TextAlign::Left => TextAlignment::Left,
                 TextAlign::Center => TextAlignment::Center,
                 TextAlign::Right => TextAlignment::Right,
+                TextAlign::Start => TextAlignment::Start,
+                TextAlign::End => TextAlignment::End,
+                TextAlign::Justify => TextAlignment::Justify,
             };
 
             // Apply motion opacity to text color
@@ -3144,16 +3274,13 @@ impl RenderContext {
                 None
             };
 
-            if let Ok(glyphs) = self.text_ctx.prepare_text_with_style(
+            if let Ok(glyphs) = self.text_ctx.prepare_text_with_layout_options_and_style(
                 &text.content,
                 text.x,
                 y_pos,
                 text.font_size,
                 color,
-                anchor,
-                alignment,
-                wrap_width,
-                needs_wrap,
+                &text.layout_options(anchor, alignment, wrap_width, needs_wrap),
                 font_name,
                 generic,
                 font_weight,
@@ -3213,7 +3340,8 @@ impl RenderContext {
                 (self.text_ctx.atlas_view(), self.text_ctx.color_atlas_view())
             {
                 batch.primitives.append(&mut css_transformed_text_prims);
-                self.renderer.set_glyph_atlas(atlas, color_atlas);
+                self.renderer
+                    .set_glyph_atlas(atlas, color_atlas, self.text_ctx.atlas_generation());
             }
         }
 
@@ -7,6 +7,9 @@ use crate::headless_report::HeadlessReport;
 use crate::headless_runtime::{HeadlessRunConfig, HeadlessRuntime};
 use crate::headless_scenario::{HeadlessScenario, ScenarioStep};
 use anyhow::{bail, Result};
+use blinc_animation::{AnimationScheduler, ManualClock};
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Temporal context passed into diagnostics probes.
 #[derive(Debug, Clone, Copy, Default)]
@@ -76,6 +79,62 @@ pub fn run_loaded_scenario_with_probe<F>(
 where
     F: FnMut(&ProbeContext) -> DiagnosticsSnapshot,
 {
+    run_loaded(scenario, runtime_cfg, None, probe)
+}
+
+/// Execute scenario JSON, driving `scheduler` with logical frame time.
+///
+/// The scheduler's clock is replaced with a manual clock that advances by
+/// each frame's logical milliseconds before the scheduler ticks, so probes
+/// see the same animation values on every run.
+pub fn run_scenario_with_scheduler<F>(
+    input: &str,
+    runtime_cfg: HeadlessRunConfig,
+    scheduler: &AnimationScheduler,
+    mut probe: F,
+) -> Result<RunOutcome>
+where
+    F: FnMut(&ProbeContext) -> DiagnosticsSnapshot,
+{
+    let scenario = HeadlessScenario::from_json(input)?;
+    run_loaded_scenario_with_scheduler(&scenario, runtime_cfg, scheduler, &mut probe)
+}
+
+/// Execute a pre-loaded scenario, driving `scheduler` with logical frame time.
+pub fn run_loaded_scenario_with_scheduler<F>(
+    scenario: &HeadlessScenario,
+    runtime_cfg: HeadlessRunConfig,
+    scheduler: &AnimationScheduler,
+    probe: &mut F,
+) -> Result<RunOutcome>
+where
+    F: FnMut(&ProbeContext) -> DiagnosticsSnapshot,
+{
+    let clock = Arc::new(ManualClock::new());
+    scheduler.set_clock(clock.clone());
+    run_loaded(
+        scenario,
+        runtime_cfg,
+        Some((scheduler, clock.as_ref())),
+        probe,
+    )
+}
+
+fn run_loaded<F>(
+    scenario: &HeadlessScenario,
+    runtime_cfg: HeadlessRunConfig,
+    animations: Option<(&AnimationScheduler, &ManualClock)>,
+    probe: &mut F,
+) -> Result<RunOutcome>
+where
+    F: FnMut(&ProbeContext) -> DiagnosticsSnapshot,
+{
+    let tick_animations = |ms: u64| {
+        if let Some((scheduler, clock)) = animations {
+            clock.advance(Duration::from_millis(ms));
+            scheduler.tick();
+        }
+    };
     let mut elapsed_frames: u64 = 0;
     let mut elapsed_ms: u64 = 0;
     let mut latest_snapshot: Option<DiagnosticsSnapshot> = None;
@@ -98,6 +157,7 @@ where
                     || {
                         let step_ms = remaining_ms.min(runtime_cfg.tick_ms);
                         remaining_ms = remaining_ms.saturating_sub(step_ms);
+                        tick_animations(step_ms);
                         step_ms
                     },
                 )?;
@@ -112,7 +172,10 @@ where
                     &mut elapsed_ms,
                     &mut latest_snapshot,
                     probe,
-                    || runtime_cfg.tick_ms,
+                    || {
+                        tick_animations(runtime_cfg.tick_ms);
+                        runtime_cfg.tick_ms
+                    },
                 )?;
             }
             ScenarioStep::AssertExists { id } => {
@@ -1,6 +1,9 @@
 //! Headless runtime primitives for diagnostics execution.
 
 use anyhow::{bail, Result};
+use blinc_animation::{AnimationScheduler, ManualClock};
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Configuration for deterministic headless frame execution.
 #[derive(Debug, Clone, Copy)]
@@ -69,4 +72,26 @@ impl HeadlessRuntime {
 
         Ok(())
     }
+
+    /// Run a fixed frame budget, ticking `scheduler` on logical time.
+    ///
+    /// The scheduler's clock is replaced with a manual clock that advances
+    /// by `tick_ms` between frames, and the scheduler ticks before each
+    /// frame callback.
+    pub fn run_with_scheduler<F>(
+        cfg: HeadlessRunConfig,
+        scheduler: &AnimationScheduler,
+        mut on_frame: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&HeadlessContext),
+    {
+        let clock = Arc::new(ManualClock::new());
+        scheduler.set_clock(clock.clone());
+        Self::run(cfg, |ctx| {
+            clock.set(Duration::from_millis(ctx.elapsed_ms));
+            scheduler.tick();
+            on_frame(ctx);
+        })
+    }
 }
@@ -1063,6 +1063,42 @@ fn headless_runtime_runs_fixed_frame_budget() {
     assert_eq!(frames, 3);
 }
 
+#[test]
+fn headless_runner_drives_animations_on_logical_time() {
+    use crate::headless_assert::DiagnosticsSnapshot;
+    use crate::headless_runner::run_scenario_with_scheduler;
+    use crate::headless_runtime::{HeadlessRunConfig, HeadlessRuntime};
+    use blinc_animation::{AnimatedKeyframe, AnimationScheduler, Easing};
+
+    let scheduler = AnimationScheduler::new();
+    let mut anim = AnimatedKeyframe::new(scheduler.handle(), 1000)
+        .keyframe(0.0, 0.0, Easing::Linear)
+        .keyframe(1.0, 100.0, Easing::Linear);
+    anim.start();
+
+    // Frames at 0, 16 and 32ms
+    let cfg = HeadlessRunConfig {
+        max_frames: 3,
+        ..HeadlessRunConfig::default()
+    };
+    HeadlessRuntime::run_with_scheduler(cfg, &scheduler, |_| {}).expect("run should succeed");
+    assert!((anim.get() - 3.2).abs() < 1e-4);
+
+    let mut values = Vec::new();
+    run_scenario_with_scheduler(
+        r#"{"steps": [{"type":"wait","ms":100}]}"#,
+        HeadlessRunConfig::default(),
+        &scheduler,
+        |ctx| {
+            values.push((ctx.elapsed_ms, anim.get()));
+            DiagnosticsSnapshot::default()
+        },
+    )
+    .expect("runner should return outcome");
+    assert_eq!(values.last().map(|v| v.0), Some(100));
+    assert!((anim.get() - 13.2).abs() < 1e-4);
+}
+
 #[test]
 fn parses_wait_and_assert_steps() {
     use crate::headless_scenario::{HeadlessScenario, ScenarioStep};
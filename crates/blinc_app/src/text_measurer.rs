@@ -5,9 +5,46 @@
 
 use blinc_layout::text_measure::{TextLayoutOptions, TextMeasurer, TextMetrics};
 use blinc_layout::GenericFont as LayoutGenericFont;
+use blinc_layout::{Hyphens, ParagraphStyle, TextJustify, TextOverflow, WhiteSpace};
 use blinc_text::{FontFace, FontRegistry, GenericFont, LayoutOptions, TextLayoutEngine};
 use std::sync::{Arc, Mutex};
 
+/// Apply a text element's paragraph settings to `blinc_text` layout options
+///
+/// Shared by measurement and rendering so both lay out the same lines.
+pub(crate) fn apply_paragraph_style(options: &mut LayoutOptions, paragraph: &ParagraphStyle) {
+    if let Some(white_space) = paragraph.white_space {
+        options.white_space = match white_space {
+            WhiteSpace::Normal => blinc_text::WhiteSpace::Normal,
+            WhiteSpace::NoWrap => blinc_text::WhiteSpace::NoWrap,
+            WhiteSpace::Pre => blinc_text::WhiteSpace::Pre,
+            WhiteSpace::PreWrap => blinc_text::WhiteSpace::PreWrap,
+            WhiteSpace::PreLine => blinc_text::WhiteSpace::PreLine,
+        };
+    }
+    if let Some(overflow) = paragraph.overflow {
+        options.overflow = match overflow {
+            TextOverflow::Clip => blinc_text::TextOverflow::Clip,
+            TextOverflow::Ellipsis => blinc_text::TextOverflow::Ellipsis,
+        };
+    }
+    if let Some(hyphens) = paragraph.hyphens {
+        options.hyphens = match hyphens {
+            Hyphens::None => blinc_text::Hyphens::None,
+            Hyphens::Manual => blinc_text::Hyphens::Manual,
+            Hyphens::Auto => blinc_text::Hyphens::Auto,
+        };
+    }
+    if let Some(justify) = paragraph.justify {
+        options.justify = match justify {
+            TextJustify::InterWord => blinc_text::TextJustify::InterWord,
+            TextJustify::InterCharacter => blinc_text::TextJustify::InterCharacter,
+        };
+    }
+    options.max_lines = paragraph.line_clamp.map(|n| n as usize);
+    options.language = paragraph.lang.clone();
+}
+
 struct MeasureFallbackWalker {
     font_size: f32,
     letter_spacing: f32,
@@ -236,13 +273,18 @@ impl TextMeasurer for FontTextMeasurer {
             // No wrapping for single-line measurement
             (None, blinc_text::LineBreakMode::None)
         };
-        let layout_opts = LayoutOptions {
+        let mut layout_opts = LayoutOptions {
             line_height: options.line_height,
             letter_spacing: options.letter_spacing,
             max_width,
             line_break,
             ..LayoutOptions::default()
         };
+        apply_paragraph_style(&mut layout_opts, &options.paragraph);
+        // Variation axes change advances; resolve them the way the renderer does
+        layout_opts.font_settings = options.font_settings.clone();
+        layout_opts.font_settings.variations =
+            font.resolve_variations(&layout_opts.font_settings, options.font_weight);
 
         let layout_engine = self.layout_engine.lock().unwrap();
         let layout = layout_engine.layout(text, &font, font_size, &layout_opts);
@@ -38,8 +38,8 @@ use blinc_layout::overlay_state::{get_overlay_manager, OverlayContext};
 use blinc_layout::prelude::*;
 use blinc_layout::widgets::overlay::{overlay_manager, OverlayManager, OverlayManagerExt};
 use blinc_platform::{
-    ControlFlow, Event, EventLoop, InputEvent, Key, KeyState, LifecycleEvent, MouseEvent, Platform,
-    TouchEvent, Window, WindowConfig, WindowEvent,
+    ControlFlow, Event, EventLoop, ImeEvent, InputEvent, Key, KeyState, LifecycleEvent, MouseEvent,
+    Platform, TouchEvent, Window, WindowConfig, WindowEvent,
 };
 
 use crate::app::BlincApp;
@@ -523,6 +523,11 @@ pub struct WindowedContext {
     /// This is the width you should use when building UI. It automatically
     /// accounts for DPI scaling, so elements sized to `ctx.width` will
     /// fill the window regardless of display scale factor.
+    ///
+    /// Resizing the window restyles and relayouts the existing tree without
+    /// calling the UI builder again, so values derived from `width` only
+    /// update on the next rebuild. Use `w_full()`, flex or `vw` units for
+    /// elements that must track the window size.
     pub width: f32,
     /// Current window height in logical pixels (for UI layout)
     pub height: f32,
@@ -560,6 +565,8 @@ pub struct WindowedContext {
     /// CSS stylesheet for automatic style application (hover, animations, base styles)
     /// Multiple stylesheets cascade — later rules override earlier ones.
     pub stylesheet: Option<Arc<blinc_layout::css_parser::Stylesheet>>,
+    /// Watcher for hot-reloaded stylesheet and theme token files
+    stylesheet_watcher: Option<blinc_layout::hot_reload::StylesheetWatcher>,
 }
 
 impl WindowedContext {
@@ -603,6 +610,7 @@ impl WindowedContext {
             element_registry,
             ready_callbacks,
             stylesheet: None,
+            stylesheet_watcher: None,
         }
     }
 
@@ -643,6 +651,7 @@ impl WindowedContext {
             element_registry,
             ready_callbacks,
             stylesheet: None,
+            stylesheet_watcher: None,
         }
     }
 
@@ -683,6 +692,7 @@ impl WindowedContext {
             element_registry,
             ready_callbacks,
             stylesheet: None,
+            stylesheet_watcher: None,
         }
     }
 
@@ -723,6 +733,7 @@ impl WindowedContext {
             element_registry,
             ready_callbacks,
             stylesheet: None,
+            stylesheet_watcher: None,
         }
     }
 
@@ -1763,6 +1774,8 @@ impl WindowedContext {
     /// Load and add a `.css` file to the application stylesheet.
     ///
     /// Multiple calls cascade — later rules override earlier ones.
+    /// The file is read once; use [`watch_css`](Self::watch_css) during
+    /// development to pick up edits without restarting.
     pub fn load_css(&mut self, path: &str) {
         match blinc_layout::css_parser::Stylesheet::from_file(path) {
             Ok(sheet) => self.add_stylesheet(sheet),
@@ -1776,6 +1789,10 @@ impl WindowedContext {
     ///
     /// Multiple calls cascade — later rules override earlier ones.
     pub fn add_stylesheet(&mut self, sheet: blinc_layout::css_parser::Stylesheet) {
+        // Keep the watcher's layers in cascade order with watched files
+        if let Some(watcher) = self.stylesheet_watcher.as_mut() {
+            watcher.add_stylesheet(sheet.clone());
+        }
         match self.stylesheet.as_mut() {
             Some(existing) => {
                 // Cascade: merge into existing (Arc::make_mut for COW)
@@ -1806,16 +1823,9 @@ impl WindowedContext {
     /// div().id("card").w(200.0).h(100.0)
     /// ```
     pub fn set_style(&mut self, id: &str, style: blinc_layout::element_style::ElementStyle) {
-        match self.stylesheet.as_mut() {
-            Some(existing) => {
-                Arc::make_mut(existing).insert(id, style);
-            }
-            None => {
-                let mut sheet = blinc_layout::css_parser::Stylesheet::new();
-                sheet.insert(id, style);
-                self.stylesheet = Some(Arc::new(sheet));
-            }
-        }
+        let mut sheet = blinc_layout::css_parser::Stylesheet::new();
+        sheet.insert(id, style);
+        self.add_stylesheet(sheet);
     }
 
     /// Set a state-specific style for an element by ID.
@@ -1837,16 +1847,66 @@ impl WindowedContext {
         state: blinc_layout::css_parser::ElementState,
         style: blinc_layout::element_style::ElementStyle,
     ) {
-        match self.stylesheet.as_mut() {
-            Some(existing) => {
-                Arc::make_mut(existing).insert_with_state(id, state, style);
-            }
-            None => {
-                let mut sheet = blinc_layout::css_parser::Stylesheet::new();
-                sheet.insert_with_state(id, state, style);
-                self.stylesheet = Some(Arc::new(sheet));
+        let mut sheet = blinc_layout::css_parser::Stylesheet::new();
+        sheet.insert_with_state(id, state, style);
+        self.add_stylesheet(sheet);
+    }
+
+    /// Load a `.css` file and reload it whenever it changes on disk.
+    ///
+    /// Like [`load_css`](Self::load_css), but the file is re-parsed on change
+    /// and the live tree is restyled in place — element state, scroll
+    /// positions and signals are kept. While the file has problems, the last
+    /// good version stays active and the diagnostics are shown in an overlay.
+    pub fn watch_css(&mut self, path: impl AsRef<std::path::Path>) {
+        self.ensure_stylesheet_watcher().watch_stylesheet(path);
+        self.sync_watched_stylesheet();
+    }
+
+    /// Load a theme token file and reload it whenever it changes on disk.
+    ///
+    /// The file's `:root` variables override theme color tokens by name
+    /// (`--primary: #7c3aed;`) and are available to stylesheets via `var()`.
+    pub fn watch_theme_tokens(&mut self, path: impl AsRef<std::path::Path>) {
+        self.ensure_stylesheet_watcher().watch_theme_tokens(path);
+        self.sync_watched_stylesheet();
+    }
+
+    /// Get the stylesheet watcher, seeding it with the current stylesheet
+    fn ensure_stylesheet_watcher(&mut self) -> &mut blinc_layout::hot_reload::StylesheetWatcher {
+        let current = self.stylesheet.clone();
+        self.stylesheet_watcher.get_or_insert_with(|| {
+            let mut watcher = blinc_layout::hot_reload::StylesheetWatcher::new();
+            if let Some(sheet) = current {
+                watcher.add_stylesheet((*sheet).clone());
             }
+            watcher
+        })
+    }
+
+    fn sync_watched_stylesheet(&mut self) {
+        if let Some(watcher) = &self.stylesheet_watcher {
+            self.stylesheet = Some(Arc::new(watcher.stylesheet().clone()));
+        }
+    }
+
+    /// Check watched files for changes, updating `stylesheet` on reload
+    fn poll_stylesheet_reload(&mut self) -> Option<blinc_layout::hot_reload::StylesheetReload> {
+        let reload = self.stylesheet_watcher.as_mut()?.poll()?;
+        if reload.needs_restyle() {
+            self.sync_watched_stylesheet();
         }
+        Some(reload)
+    }
+
+    /// Build the stylesheet diagnostics layer (empty when no watched file has problems)
+    fn stylesheet_diagnostics_layer(&self) -> Div {
+        let diagnostics = self
+            .stylesheet_watcher
+            .as_ref()
+            .map(|watcher| watcher.diagnostics())
+            .unwrap_or_default();
+        blinc_layout::hot_reload::diagnostics_layer(&diagnostics)
     }
 }
 
@@ -2112,6 +2172,8 @@ impl WindowedApp {
         let mut needs_rebuild = true;
         // Track if we need to relayout (e.g., after resize even if tree unchanged)
         let mut needs_relayout = false;
+        // Window was resized: re-resolve media/container queries and relayout in place
+        let mut viewport_resized = false;
         // Shared dirty flag for element refs
         let ref_dirty_flag: RefDirtyFlag = Arc::new(AtomicBool::new(false));
         // Shared reactive graph for signal-based state management
@@ -2208,6 +2270,20 @@ impl WindowedApp {
             });
         }
 
+        // Caret rectangles reported by focused text widgets, applied to the
+        // window after input dispatch (`None` disables the IME on blur)
+        #[allow(clippy::type_complexity)]
+        let ime_caret: Arc<Mutex<Option<Option<blinc_layout::widgets::ImeCaretRect>>>> =
+            Arc::new(Mutex::new(None));
+        {
+            let ime_caret = Arc::clone(&ime_caret);
+            blinc_layout::widgets::set_ime_caret_callback(move |rect| {
+                *ime_caret.lock().unwrap() = Some(rect);
+            });
+        }
+        // Whether an IME composition is open; keystrokes belong to the IME meanwhile
+        let mut ime_composing = false;
+
         // Connect theme animation to the animation scheduler
         // This enables smooth color transitions when switching between light/dark mode
         blinc_theme::ThemeState::get().set_scheduler(&animations);
@@ -2338,8 +2414,8 @@ impl WindowedApp {
                                 config.width = width;
                                 config.height = height;
                                 surf.configure(blinc_app.device(), config);
-                                needs_rebuild = true;
-                                needs_relayout = true;
+                                // Restyled and relaid out in place; no tree rebuild
+                                viewport_resized = true;
 
                                 // Dispatch RESIZE event to elements (use logical dimensions)
                                 if let (Some(ref mut windowed_ctx), Some(ref tree)) =
@@ -2414,6 +2490,9 @@ impl WindowedApp {
                             drag_delta_x: f32,
                             drag_delta_y: f32,
                             key_char: Option<char>,
+                            /// Preedit or committed text for COMPOSITION_* events
+                            composition_text: Option<String>,
+                            composition_cursor: Option<(usize, usize)>,
                             key_code: u32,
                             shift: bool,
                             ctrl: bool,
@@ -2437,6 +2516,8 @@ impl WindowedApp {
                                     drag_delta_x: 0.0,
                                     drag_delta_y: 0.0,
                                     key_char: None,
+                                    composition_text: None,
+                                    composition_cursor: None,
                                     key_code: 0,
                                     shift: false,
                                     ctrl: false,
@@ -2731,6 +2812,44 @@ impl WindowedApp {
                                         Key::Down => 40,
                                         Key::Home => 36,
                                         Key::End => 35,
+                                        // Letters use their uppercase ASCII code, for shortcuts
+                                        // like Ctrl/Cmd+Z (see `blinc_core::events::KeyCode`)
+                                        Key::A => 65,
+                                        Key::B => 66,
+                                        Key::C => 67,
+                                        Key::D => 68,
+                                        Key::E => 69,
+                                        Key::F => 70,
+                                        Key::G => 71,
+                                        Key::H => 72,
+                                        Key::I => 73,
+                                        Key::J => 74,
+                                        Key::K => 75,
+                                        Key::L => 76,
+                                        Key::M => 77,
+                                        Key::N => 78,
+                                        Key::O => 79,
+                                        Key::P => 80,
+                                        Key::Q => 81,
+                                        Key::R => 82,
+                                        Key::S => 83,
+                                        Key::T => 84,
+                                        Key::U => 85,
+                                        Key::V => 86,
+                                        Key::W => 87,
+                                        Key::X => 88,
+                                        Key::Y => 89,
+                                        Key::Z => 90,
+                                        Key::Num0 => 48,
+                                        Key::Num1 => 49,
+                                        Key::Num2 => 50,
+                                        Key::Num3 => 51,
+                                        Key::Num4 => 52,
+                                        Key::Num5 => 53,
+                                        Key::Num6 => 54,
+                                        Key::Num7 => 55,
+                                        Key::Num8 => 56,
+                                        Key::Num9 => 57,
                                         _ => 0,
                                     };
 
@@ -2757,8 +2876,9 @@ impl WindowedApp {
                                             // For character-producing keys, dispatch TEXT_INPUT
                                             // We use broadcast dispatch so any focused text input can receive it
                                             if let Some(c) = key_char {
-                                                // Don't send text input if ctrl/cmd is held (shortcuts)
-                                                if !mods.ctrl && !mods.meta {
+                                                // Don't send text input if ctrl/cmd is held (shortcuts),
+                                                // or while the IME is composing (it owns the keystrokes)
+                                                if !mods.ctrl && !mods.meta && !ime_composing {
                                                     keyboard_events.push(PendingEvent {
                                                         event_type: blinc_core::events::event_types::TEXT_INPUT,
                                                         key_char: Some(c),
@@ -2797,6 +2917,32 @@ impl WindowedApp {
                                         }
                                     }
                                 },
+                                InputEvent::Ime(ime_event) => {
+                                    let (event_type, composition_text, composition_cursor) =
+                                        match ime_event {
+                                            ImeEvent::Start => {
+                                                ime_composing = true;
+                                                (blinc_core::events::event_types::COMPOSITION_START, None, None)
+                                            }
+                                            ImeEvent::Update { text, cursor } => {
+                                                (blinc_core::events::event_types::COMPOSITION_UPDATE, Some(text), cursor)
+                                            }
+                                            ImeEvent::Commit(text) => {
+                                                ime_composing = false;
+                                                (blinc_core::events::event_types::COMPOSITION_COMMIT, Some(text), None)
+                                            }
+                                            ImeEvent::Cancel => {
+                                                ime_composing = false;
+                                                (blinc_core::events::event_types::COMPOSITION_CANCEL, None, None)
+                                            }
+                                        };
+                                    keyboard_events.push(PendingEvent {
+                                        event_type,
+                                        composition_text,
+                                        composition_cursor,
+                                        ..Default::default()
+                                    });
+                                }
                                 InputEvent::Touch(touch_event) => match touch_event {
                                     TouchEvent::Started { x, y, .. } => {
                                         let lx = x / scale;
@@ -3026,7 +3172,24 @@ impl WindowedApp {
                             // Text inputs track their own focus state internally via `s.visual.is_focused()`,
                             // so broadcasting to all handlers is safe - only the focused one will process.
                             for event in keyboard_events {
-                                if event.event_type == blinc_core::events::event_types::TEXT_INPUT {
+                                use blinc_core::events::event_types::{
+                                    COMPOSITION_CANCEL, COMPOSITION_COMMIT, COMPOSITION_START,
+                                    COMPOSITION_UPDATE,
+                                };
+                                if matches!(
+                                    event.event_type,
+                                    COMPOSITION_START
+                                        | COMPOSITION_UPDATE
+                                        | COMPOSITION_COMMIT
+                                        | COMPOSITION_CANCEL
+                                ) {
+                                    // Broadcast to all text widgets; only the focused one composes
+                                    tree.broadcast_composition_event(
+                                        event.event_type,
+                                        event.composition_text.as_deref(),
+                                        event.composition_cursor,
+                                    );
+                                } else if event.event_type == blinc_core::events::event_types::TEXT_INPUT {
                                     if let Some(c) = event.key_char {
                                         // Broadcast to all text input handlers
                                         // Each handler checks its own focus state internally
@@ -3051,6 +3214,22 @@ impl WindowedApp {
                                 }
                             }
 
+                            // Forward caret moves from focused text widgets to the platform IME
+                            if let Some(caret) = ime_caret.lock().unwrap().take() {
+                                match caret {
+                                    Some(rect) => {
+                                        window.set_ime_allowed(true);
+                                        window.set_ime_cursor_area(
+                                            rect.x,
+                                            rect.y,
+                                            rect.width,
+                                            rect.height,
+                                        );
+                                    }
+                                    None => window.set_ime_allowed(false),
+                                }
+                            }
+
                             // If scroll momentum ended, notify scroll physics
                             if scroll_ended {
                                 tree.on_scroll_end();
@@ -3175,7 +3354,11 @@ impl WindowedApp {
                             // Animation visual updates (backdrop opacity, motion transforms) are handled
                             // by the motion system and render-time interpolation, not content rebuilds.
                             // Rebuilding during animation breaks event handlers because node IDs change.
-                            let overlay_content_dirty = windowed_ctx.overlay_manager.is_dirty();
+                            // Visible overlays are sized to the viewport, so a resize
+                            // rebuilds the overlay subtree (not the whole tree)
+                            let overlay_content_dirty = windowed_ctx.overlay_manager.is_dirty()
+                                || (viewport_resized
+                                    && windowed_ctx.overlay_manager.has_visible_overlays());
 
                             if overlay_content_dirty {
                                 tracing::debug!(
@@ -3249,6 +3432,74 @@ impl WindowedApp {
                                 window.request_redraw();
                             }
 
+                            // Re-evaluate @media blocks when the environment changed (resize,
+                            // theme switch, reduced motion): the live tree is restyled in place
+                            // and relaid out, which also re-checks @container rules against the
+                            // new sizes.
+                            if let Some(ref mut tree) = render_tree {
+                                let media_ctx =
+                                    MediaContext::from_globals(windowed_ctx.scale_factor as f32);
+                                let restyled = tree.set_media_context(media_ctx);
+                                if restyled || viewport_resized {
+                                    tracing::debug!(
+                                        "Viewport or media query results changed, recomputing layout"
+                                    );
+                                    tree.compute_layout(windowed_ctx.width, windowed_ctx.height);
+                                    if tree.apply_container_queries() {
+                                        tree.compute_layout(windowed_ctx.width, windowed_ctx.height);
+                                    }
+                                    window.request_redraw();
+                                }
+                            }
+                            viewport_resized = false;
+
+                            // Hot reload watched stylesheet files: restyle the live
+                            // tree in place so element and app state survive.
+                            if let Some(reload) = windowed_ctx.poll_stylesheet_reload() {
+                                if reload.needs_restyle() {
+                                    if let (Some(tree), Some(stylesheet)) =
+                                        (render_tree.as_mut(), windowed_ctx.stylesheet.as_ref())
+                                    {
+                                        tree.reload_stylesheet(stylesheet.clone(), &reload.diff);
+                                        tree.compute_layout(windowed_ctx.width, windowed_ctx.height);
+                                        if tree.apply_container_queries() {
+                                            tree.compute_layout(windowed_ctx.width, windowed_ctx.height);
+                                        }
+                                    }
+                                    window.request_redraw();
+                                }
+                                // The diagnostics overlay is its own layer: swap its
+                                // contents with a subtree rebuild, not a UI rebuild
+                                if reload.diagnostics_changed {
+                                    if let Some(layer_node) = element_registry
+                                        .get(blinc_layout::hot_reload::DIAGNOSTICS_LAYER_ID)
+                                    {
+                                        blinc_layout::queue_subtree_rebuild(
+                                            layer_node,
+                                            windowed_ctx.stylesheet_diagnostics_layer(),
+                                        );
+                                    } else {
+                                        needs_rebuild = true;
+                                    }
+                                    window.request_redraw();
+                                }
+                            }
+
+                            // Route synthetic events queued by ElementHandle (UI tests,
+                            // accessibility actions) through the event router, against
+                            // the same tree their node ids were resolved from.
+                            let synthetic_events = blinc_layout::take_pending_synthetic_events();
+                            if !synthetic_events.is_empty() {
+                                if let Some(tree) = render_tree.as_mut() {
+                                    for (node_id, event) in &synthetic_events {
+                                        windowed_ctx
+                                            .event_router
+                                            .dispatch_synthetic(tree, *node_id, event);
+                                    }
+                                }
+                                window.request_redraw();
+                            }
+
                             // =========================================================
                             // PHASE 2: Build/rebuild tree only for structural changes
                             // This must happen BEFORE tick() so motion animations are available
@@ -3275,21 +3526,24 @@ impl WindowedApp {
                                 // Compose user UI with overlay layer using a regular Div container
                                 // We use position:relative with the overlay absolutely positioned on top.
                                 let overlay_layer = windowed_ctx.overlay_manager.build_overlay_layer();
+                                // Sized relative to the viewport so resizes need no rebuild
                                 let ui = div()
-                                    .w(windowed_ctx.width)
-                                    .h(windowed_ctx.height)
+                                    .w_full()
+                                    .h_full()
                                     .relative() // positioning context for overlay
                                     .child(user_ui)
-                                    .child(overlay_layer);
+                                    .child(overlay_layer)
+                                    .child(windowed_ctx.stylesheet_diagnostics_layer());
 
                                 // Use incremental update if we have an existing tree
-                                // BUT: Skip incremental update during resize - do full rebuild instead
+                                // BUT: Skip incremental update when a full relayout was requested
+                                // (theme or global state change) - do full rebuild instead
                                 // This ensures parent constraints properly propagate to all children
                                 if let Some(ref mut existing_tree) = render_tree {
                                     if needs_relayout {
-                                        // Window resize: bypass incremental update, do full rebuild
+                                        // Relayout requested: bypass incremental update, do full rebuild
                                         // This ensures proper constraint propagation from parents to children
-                                        tracing::debug!("Window resize: full tree rebuild (bypassing incremental update)");
+                                        tracing::debug!("Relayout requested: full tree rebuild (bypassing incremental update)");
 
                                         // Clear layout bounds storages before rebuild
                                         existing_tree.clear_layout_bounds_storages();
@@ -3310,6 +3564,12 @@ impl WindowedApp {
                                         // Set DPI scale factor for HiDPI rendering
                                         tree.set_scale_factor(windowed_ctx.scale_factor as f32);
 
+                                        // Media context must be set before the stylesheet so
+                                        // @media blocks resolve against the new viewport
+                                        tree.set_media_context(MediaContext::from_globals(
+                                            windowed_ctx.scale_factor as f32,
+                                        ));
+
                                         // Set CSS stylesheet for automatic style application
                                         if let Some(ref stylesheet) = windowed_ctx.stylesheet {
                                             tree.set_stylesheet_arc(stylesheet.clone());
@@ -3322,6 +3582,10 @@ impl WindowedApp {
 
                                         // Compute layout with new viewport dimensions
                                         tree.compute_layout(windowed_ctx.width, windowed_ctx.height);
+                                        // @container rules depend on the computed sizes
+                                        if tree.apply_container_queries() {
+                                            tree.compute_layout(windowed_ctx.width, windowed_ctx.height);
+                                        }
 
                                         // Initialize motion animations for any nodes wrapped in motion() containers
                                         tree.initialize_motion_animations(rs);
@@ -3361,6 +3625,9 @@ impl WindowedApp {
                                                 tracing::debug!("Incremental update: LayoutChanged - recomputing layout");
                                                 existing_tree.apply_stylesheet_layout_overrides();
                                                 existing_tree.compute_layout(windowed_ctx.width, windowed_ctx.height);
+                                                if existing_tree.apply_container_queries() {
+                                                    existing_tree.compute_layout(windowed_ctx.width, windowed_ctx.height);
+                                                }
                                             }
                                             UpdateResult::ChildrenChanged => {
                                                 // Children changed - subtrees were rebuilt in place
@@ -3398,6 +3665,12 @@ impl WindowedApp {
                                     // Set DPI scale factor for HiDPI rendering
                                     tree.set_scale_factor(windowed_ctx.scale_factor as f32);
 
+                                    // Media context must be set before the stylesheet so
+                                    // @media blocks resolve against the current viewport
+                                    tree.set_media_context(MediaContext::from_globals(
+                                        windowed_ctx.scale_factor as f32,
+                                    ));
+
                                     // Set CSS stylesheet for automatic style application
                                     if let Some(ref stylesheet) = windowed_ctx.stylesheet {
                                         tree.set_stylesheet_arc(stylesheet.clone());
@@ -3410,6 +3683,10 @@ impl WindowedApp {
 
                                     // Compute layout in logical pixels
                                     tree.compute_layout(windowed_ctx.width, windowed_ctx.height);
+                                    // @container rules depend on the computed sizes
+                                    if tree.apply_container_queries() {
+                                        tree.compute_layout(windowed_ctx.width, windowed_ctx.height);
+                                    }
 
                                     // Initialize motion animations for any nodes wrapped in motion() containers
                                     tree.initialize_motion_animations(rs);
@@ -4078,6 +4355,7 @@ mod tests {
             element_registry,
             ready_callbacks,
             stylesheet: None,
+            stylesheet_watcher: None,
         }
     }
 
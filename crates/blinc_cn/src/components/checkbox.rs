@@ -190,6 +190,10 @@ impl Checkbox {
                 visual
             });
 
+        // Report the checked state for `:checked` selector matching
+        let checked_state_for_probe = config.checked_state.clone();
+        checkbox = checkbox.checked_when(move || checked_state_for_probe.get());
+
         // Add click handler to toggle the state (only if not disabled)
         checkbox = checkbox.on_click(move |_| {
             if disabled {
@@ -264,6 +268,10 @@ impl ElementBuilder for Checkbox {
     fn layout_style(&self) -> Option<&taffy::Style> {
         self.inner.layout_style()
     }
+
+    fn checked_probe(&self) -> Option<blinc_layout::selector::CheckedProbe> {
+        self.inner.checked_probe()
+    }
 }
 
 /// Internal configuration for building a Checkbox
@@ -418,6 +426,10 @@ impl ElementBuilder for CheckboxBuilder {
     fn layout_style(&self) -> Option<&taffy::Style> {
         self.get_or_build().layout_style()
     }
+
+    fn checked_probe(&self) -> Option<blinc_layout::selector::CheckedProbe> {
+        self.get_or_build().checked_probe()
+    }
 }
 
 /// Create a checkbox with state from context
@@ -186,6 +186,10 @@ impl Switch {
             switch = switch.opacity(0.5);
         }
 
+        // Report the on state for `:checked` selector matching
+        let on_state_for_probe = config.on_state.clone();
+        switch = switch.checked_when(move || on_state_for_probe.get());
+
         // Add click handler to toggle the state
         switch = switch.on_click(move |_| {
             if disabled {
@@ -257,6 +261,10 @@ impl ElementBuilder for Switch {
     fn layout_style(&self) -> Option<&taffy::Style> {
         self.inner.layout_style()
     }
+
+    fn checked_probe(&self) -> Option<blinc_layout::selector::CheckedProbe> {
+        self.inner.checked_probe()
+    }
 }
 
 /// Internal configuration for building a Switch
@@ -425,6 +433,10 @@ impl ElementBuilder for SwitchBuilder {
     fn layout_style(&self) -> Option<&taffy::Style> {
         self.get_or_build().layout_style()
     }
+
+    fn checked_probe(&self) -> Option<blinc_layout::selector::CheckedProbe> {
+        self.get_or_build().checked_probe()
+    }
 }
 
 /// Create a switch with state from context
@@ -321,6 +321,8 @@ pub struct BlincContextState {
     scroll_callback: RwLock<Option<ScrollCallback>>,
     /// Current viewport size (width, height)
     viewport_size: RwLock<(f32, f32)>,
+    /// Whether the user prefers reduced motion (CSS `prefers-reduced-motion`)
+    prefers_reduced_motion: RwLock<bool>,
     /// Currently focused element ID
     focused_element: RwLock<Option<String>>,
     /// Type-erased element registry for query API
@@ -359,6 +361,7 @@ impl BlincContextState {
             focus_callback: RwLock::new(None),
             scroll_callback: RwLock::new(None),
             viewport_size: RwLock::new((0.0, 0.0)),
+            prefers_reduced_motion: RwLock::new(false),
             focused_element: RwLock::new(None),
             element_registry: RwLock::new(None),
             motion_state_callback: RwLock::new(None),
@@ -390,6 +393,7 @@ impl BlincContextState {
             focus_callback: RwLock::new(None),
             scroll_callback: RwLock::new(None),
             viewport_size: RwLock::new((0.0, 0.0)),
+            prefers_reduced_motion: RwLock::new(false),
             focused_element: RwLock::new(None),
             element_registry: RwLock::new(None),
             motion_state_callback: RwLock::new(None),
@@ -625,6 +629,20 @@ impl BlincContextState {
         *self.viewport_size.read().unwrap()
     }
 
+    /// Set whether the user prefers reduced motion
+    ///
+    /// Platforms call this from the OS accessibility setting; apps may also
+    /// expose it as an in-app preference. Evaluated by `prefers-reduced-motion`
+    /// media queries.
+    pub fn set_prefers_reduced_motion(&self, reduced: bool) {
+        *self.prefers_reduced_motion.write().unwrap() = reduced;
+    }
+
+    /// Whether the user prefers reduced motion
+    pub fn prefers_reduced_motion(&self) -> bool {
+        *self.prefers_reduced_motion.read().unwrap()
+    }
+
     // =========================================================================
     // Focus Management
     // =========================================================================
@@ -1015,6 +1033,7 @@ mod tests {
             focus_callback: RwLock::new(None),
             scroll_callback: RwLock::new(None),
             viewport_size: RwLock::new((0.0, 0.0)),
+            prefers_reduced_motion: RwLock::new(false),
             focused_element: RwLock::new(None),
             element_registry: RwLock::new(None),
             motion_state_callback: RwLock::new(None),
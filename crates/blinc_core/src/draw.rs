@@ -297,6 +297,8 @@ pub struct TextStyle {
     pub letter_spacing: f32,
     /// Line height multiplier
     pub line_height: f32,
+    /// OpenType features and variable font axes
+    pub font_settings: FontSettings,
 }
 
 impl Default for TextStyle {
@@ -310,6 +312,7 @@ impl Default for TextStyle {
             baseline: TextBaseline::Alphabetic,
             letter_spacing: 0.0,
             line_height: 1.2,
+            font_settings: FontSettings::default(),
         }
     }
 }
@@ -340,6 +343,163 @@ impl TextStyle {
         self.family = family.into();
         self
     }
+
+    /// Set an OpenType feature (e.g. `("tnum", 1)`, `("liga", 0)`)
+    pub fn with_feature(mut self, tag: &str, value: u32) -> Self {
+        self.font_settings = self.font_settings.with_feature(tag, value);
+        self
+    }
+
+    /// Set a variable font axis (e.g. `("wght", 650.0)`)
+    pub fn with_variation(mut self, tag: &str, value: f32) -> Self {
+        self.font_settings = self.font_settings.with_variation(tag, value);
+        self
+    }
+
+    /// Use tabular (fixed-width) figures, so changing numbers don't shift
+    pub fn tabular_nums(self) -> Self {
+        self.with_feature("tnum", 1)
+    }
+}
+
+/// Convert a 1-4 character OpenType tag to bytes (padded with spaces)
+///
+/// Returns `None` for empty, overlong or non-ASCII tags.
+pub fn font_tag(tag: &str) -> Option<[u8; 4]> {
+    if tag.is_empty() || tag.len() > 4 || !tag.bytes().all(|b| (0x20..0x7F).contains(&b)) {
+        return None;
+    }
+    let mut bytes = [b' '; 4];
+    bytes[..tag.len()].copy_from_slice(tag.as_bytes());
+    Some(bytes)
+}
+
+/// An OpenType feature setting (CSS `font-feature-settings`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FontFeature {
+    /// Feature tag (e.g. `tnum`, `smcp`, `ss01`)
+    pub tag: [u8; 4],
+    /// 0 = off, 1 = on; alternates (`salt`, `cv01`) take an index
+    pub value: u32,
+}
+
+impl FontFeature {
+    /// Create a feature setting; invalid tags yield `None`
+    pub fn new(tag: &str, value: u32) -> Option<Self> {
+        font_tag(tag).map(|tag| Self { tag, value })
+    }
+
+    /// Tag as a string
+    pub fn tag_str(&self) -> &str {
+        std::str::from_utf8(&self.tag).unwrap_or("????")
+    }
+}
+
+/// A variable font axis value (CSS `font-variation-settings`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FontVariation {
+    /// Axis tag (e.g. `wght`, `wdth`, `opsz`, or a custom uppercase axis)
+    pub tag: [u8; 4],
+    /// Axis value in the axis's own units
+    pub value: f32,
+}
+
+impl FontVariation {
+    /// Create an axis setting; invalid tags yield `None`
+    pub fn new(tag: &str, value: f32) -> Option<Self> {
+        font_tag(tag).map(|tag| Self { tag, value })
+    }
+
+    /// Tag as a string
+    pub fn tag_str(&self) -> &str {
+        std::str::from_utf8(&self.tag).unwrap_or("????")
+    }
+}
+
+/// OpenType features and variation axes applied when shaping and rasterizing
+///
+/// Later settings for the same tag replace earlier ones.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FontSettings {
+    /// Feature settings, in application order
+    pub features: Vec<FontFeature>,
+    /// Variation axis values
+    pub variations: Vec<FontVariation>,
+}
+
+impl FontSettings {
+    /// Whether no features or axes are set
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty() && self.variations.is_empty()
+    }
+
+    /// Set a feature, replacing any earlier value for the tag
+    pub fn with_feature(mut self, tag: &str, value: u32) -> Self {
+        if let Some(feature) = FontFeature::new(tag, value) {
+            self.set_feature(feature);
+        }
+        self
+    }
+
+    /// Set a variation axis, replacing any earlier value for the tag
+    pub fn with_variation(mut self, tag: &str, value: f32) -> Self {
+        if let Some(variation) = FontVariation::new(tag, value) {
+            self.set_variation(variation);
+        }
+        self
+    }
+
+    /// Set a feature, replacing any earlier value for the tag
+    pub fn set_feature(&mut self, feature: FontFeature) {
+        self.features.retain(|f| f.tag != feature.tag);
+        self.features.push(feature);
+    }
+
+    /// Set a variation axis, replacing any earlier value for the tag
+    pub fn set_variation(&mut self, variation: FontVariation) {
+        self.variations.retain(|v| v.tag != variation.tag);
+        self.variations.push(variation);
+    }
+
+    /// Value of a variation axis, if set
+    pub fn variation(&self, tag: &str) -> Option<f32> {
+        let tag = font_tag(tag)?;
+        self.variations
+            .iter()
+            .find(|v| v.tag == tag)
+            .map(|v| v.value)
+    }
+
+    /// Combine with `other`, whose settings win for tags set in both
+    pub fn merge(&self, other: &FontSettings) -> FontSettings {
+        let mut merged = self.clone();
+        for feature in &other.features {
+            merged.set_feature(*feature);
+        }
+        for variation in &other.variations {
+            merged.set_variation(*variation);
+        }
+        merged
+    }
+
+    /// Stable hash of the variation axes, for glyph cache keys
+    ///
+    /// Features only affect glyph selection, not outlines, so they are not
+    /// included. Returns 0 when no axes are set.
+    pub fn variation_key(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        if self.variations.is_empty() {
+            return 0;
+        }
+        let mut sorted = self.variations.clone();
+        sorted.sort_by_key(|v| v.tag);
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for v in sorted {
+            v.tag.hash(&mut hasher);
+            v.value.to_bits().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -2300,6 +2460,37 @@ mod tests {
         assert_eq!(style.family, "Arial");
     }
 
+    #[test]
+    fn test_font_settings() {
+        let style = TextStyle::new(12.0)
+            .tabular_nums()
+            .with_feature("liga", 0)
+            .with_variation("wght", 500.0)
+            .with_variation("wght", 650.0)
+            .with_feature("toolong", 1);
+
+        let settings = &style.font_settings;
+        assert_eq!(settings.features.len(), 2);
+        assert_eq!(settings.features[0].tag_str(), "tnum");
+        assert_eq!(settings.variation("wght"), Some(650.0));
+        assert_eq!(settings.variations.len(), 1);
+
+        let merged = settings.merge(&FontSettings::default().with_feature("tnum", 0));
+        assert_eq!(merged.features.last().unwrap().value, 0);
+        assert_eq!(merged.features.len(), 2);
+
+        assert_eq!(FontSettings::default().variation_key(), 0);
+        assert_ne!(
+            settings.variation_key(),
+            FontSettings::default()
+                .with_variation("wght", 400.0)
+                .variation_key()
+        );
+        assert_eq!(font_tag("kern"), Some(*b"kern"));
+        assert_eq!(font_tag("cv1"), Some(*b"cv1 "));
+        assert_eq!(font_tag(""), None);
+    }
+
     #[test]
     fn test_draw_context_ext() {
         let mut ctx = RecordingContext::new(Size::new(800.0, 600.0));
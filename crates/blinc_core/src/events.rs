@@ -26,6 +26,14 @@ pub mod event_types {
     pub const KEY_UP: EventType = 21;
     /// Text input event (for character input, IME composition)
     pub const TEXT_INPUT: EventType = 22;
+    /// IME composition started
+    pub const COMPOSITION_START: EventType = 23;
+    /// IME preedit text or preedit cursor changed
+    pub const COMPOSITION_UPDATE: EventType = 24;
+    /// IME composition committed final text
+    pub const COMPOSITION_COMMIT: EventType = 25;
+    /// IME composition cancelled without committing
+    pub const COMPOSITION_CANCEL: EventType = 26;
     pub const SCROLL: EventType = 30;
     /// Scroll gesture ended (for deceleration/momentum)
     pub const SCROLL_END: EventType = 31;
@@ -47,10 +47,10 @@ pub mod store;
 pub mod value;
 
 pub use draw::{
-    BlurQuality, DrawCommand, DrawContext, DrawContextExt, FontWeight, ImageId, ImageOptions,
-    LayerConfig, LayerEffect, LineCap, LineJoin, MaterialId, MeshId, MeshInstance, Path,
-    PathCommand, RecordingContext, SdfBuilder, ShapeId, Stroke, TextAlign, TextBaseline, TextStyle,
-    Transform,
+    font_tag, BlurQuality, DrawCommand, DrawContext, DrawContextExt, FontFeature, FontSettings,
+    FontVariation, FontWeight, ImageId, ImageOptions, LayerConfig, LayerEffect, LineCap, LineJoin,
+    MaterialId, MeshId, MeshInstance, Path, PathCommand, RecordingContext, SdfBuilder, ShapeId,
+    Stroke, TextAlign, TextBaseline, TextStyle, Transform,
 };
 pub use events::{Event, EventData, EventDispatcher, EventType, KeyCode, Modifiers};
 pub use fsm::{FsmId, FsmRuntime, StateId, StateMachine, Transition};
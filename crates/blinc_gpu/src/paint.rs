@@ -2219,6 +2219,7 @@ impl<'a> DrawContext for GpuPaintContext<'a> {
             line_break: blinc_text::LineBreakMode::None, // no wrap for canvas text
             letter_spacing: style.letter_spacing,
             line_height: style.line_height,
+            font_settings: style.font_settings.clone(),
             ..Default::default()
         };
 
@@ -489,10 +489,11 @@ impl GpuPrimitive {
     /// in the same pass as shapes, enabling proper z-ordering.
     ///
     /// The glyph's UV bounds are stored in `gradient_params` and the color in `color`.
-    /// For color emoji, `flags[0]` is 1.0 (stored in `type_info[1]`).
+    /// For color emoji, `flags[0]` is 1.0. Both the color flag (bit 0) and
+    /// the atlas page from `flags[1]` (remaining bits) go in `type_info[1]`.
     pub fn from_glyph(glyph: &GpuGlyph) -> Self {
-        // Use type_info[1] to store is_color flag (1 = color emoji, 0 = grayscale)
         let is_color_flag = if glyph.flags[0] > 0.5 { 1u32 } else { 0u32 };
+        let page = glyph.flags[1].max(0.0) as u32;
         Self {
             bounds: glyph.bounds,
             corner_radius: [0.0; 4],
@@ -514,7 +515,7 @@ impl GpuPrimitive {
             filter_b: [1.0, 1.0, 1.0, 0.0],
             type_info: [
                 PrimitiveType::Text as u32,
-                is_color_flag,
+                is_color_flag | (page << 1),
                 ClipType::None as u32,
                 0,
             ],
@@ -918,8 +919,9 @@ pub struct GpuGlyph {
     pub color: [f32; 4],
     /// Clip bounds (x, y, width, height) - set to large values for no clip
     pub clip_bounds: [f32; 4],
-    /// Flags: [is_color, unused, unused, unused]
+    /// Flags: [is_color, atlas_page, unused, unused]
     /// is_color: 1.0 for color emoji (use color atlas), 0.0 for grayscale (use main atlas)
+    /// atlas_page: texture array layer holding the glyph
     pub flags: [f32; 4],
 }
 
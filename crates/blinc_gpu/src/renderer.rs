@@ -330,6 +330,9 @@ struct CachedTextResources {
     atlas_view_ptr: *const wgpu::TextureView,
     /// Pointer to color atlas view when bind group was created (for invalidation)
     color_atlas_view_ptr: *const wgpu::TextureView,
+    /// Atlas generation when bind group was created, since a view recreated
+    /// in place (atlas grew a page) keeps its address
+    atlas_generation: u64,
 }
 
 /// Active glyph atlas pointers for SDF bind group (set per-frame).
@@ -344,6 +347,7 @@ struct CachedTextResources {
 struct ActiveGlyphAtlas {
     atlas_view_ptr: *const wgpu::TextureView,
     color_atlas_view_ptr: *const wgpu::TextureView,
+    atlas_generation: u64,
 }
 
 /// Cached resources for SDF 3D raymarching viewports
@@ -1345,13 +1349,19 @@ impl GpuRenderer {
         let buffers = Self::create_buffers(&device, &config);
 
         // Create placeholder glyph atlas textures (1x1 transparent)
-        // These are used when no text is rendered, satisfying the bind group layout
+        // These are used when no text is rendered, satisfying the bind group layout.
+        // Glyph atlases are texture arrays (one layer per atlas page); GL backends
+        // treat single-layer textures as plain 2D, so allocate two layers.
+        let array_view = wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::D2Array),
+            ..Default::default()
+        };
         let placeholder_glyph_atlas = device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Placeholder Glyph Atlas"),
             size: wgpu::Extent3d {
                 width: 1,
                 height: 1,
-                depth_or_array_layers: 1,
+                depth_or_array_layers: 2,
             },
             mip_level_count: 1,
             sample_count: 1,
@@ -1360,15 +1370,14 @@ impl GpuRenderer {
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         });
-        let placeholder_glyph_atlas_view =
-            placeholder_glyph_atlas.create_view(&wgpu::TextureViewDescriptor::default());
+        let placeholder_glyph_atlas_view = placeholder_glyph_atlas.create_view(&array_view);
 
         let placeholder_color_glyph_atlas = device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Placeholder Color Glyph Atlas"),
             size: wgpu::Extent3d {
                 width: 1,
                 height: 1,
-                depth_or_array_layers: 1,
+                depth_or_array_layers: 2,
             },
             mip_level_count: 1,
             sample_count: 1,
@@ -1378,7 +1387,7 @@ impl GpuRenderer {
             view_formats: &[],
         });
         let placeholder_color_glyph_atlas_view =
-            placeholder_color_glyph_atlas.create_view(&wgpu::TextureViewDescriptor::default());
+            placeholder_color_glyph_atlas.create_view(&array_view);
 
         // Create sampler for glyph atlases
         let glyph_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
@@ -1522,7 +1531,7 @@ impl GpuRenderer {
                     visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Texture {
                         sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                        view_dimension: wgpu::TextureViewDimension::D2,
+                        view_dimension: wgpu::TextureViewDimension::D2Array,
                         multisampled: false,
                     },
                     count: None,
@@ -1540,7 +1549,7 @@ impl GpuRenderer {
                     visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Texture {
                         sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                        view_dimension: wgpu::TextureViewDimension::D2,
+                        view_dimension: wgpu::TextureViewDimension::D2Array,
                         multisampled: false,
                     },
                     count: None,
@@ -1667,7 +1676,7 @@ impl GpuRenderer {
                     visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Texture {
                         sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                        view_dimension: wgpu::TextureViewDimension::D2,
+                        view_dimension: wgpu::TextureViewDimension::D2Array,
                         multisampled: false,
                     },
                     count: None,
@@ -1685,7 +1694,7 @@ impl GpuRenderer {
                     visibility: wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Texture {
                         sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                        view_dimension: wgpu::TextureViewDimension::D2,
+                        view_dimension: wgpu::TextureViewDimension::D2Array,
                         multisampled: false,
                     },
                     count: None,
@@ -3129,8 +3138,8 @@ impl GpuRenderer {
     /// `self.bind_groups.sdf`, so ALL render paths automatically get the atlas
     /// without needing to thread it through every method.
     ///
-    /// Uses pointer comparison to avoid recreating the bind group when the atlas
-    /// hasn't changed between frames.
+    /// Uses pointer and `atlas_generation` comparison to avoid recreating the
+    /// bind group when the atlas hasn't changed between frames.
     ///
     /// SAFETY: The raw pointers stored in `active_glyph_atlas` must remain valid
     /// for the duration of the frame. This is guaranteed because they point to
@@ -3139,13 +3148,16 @@ impl GpuRenderer {
         &mut self,
         atlas_view: &wgpu::TextureView,
         color_atlas_view: &wgpu::TextureView,
+        atlas_generation: u64,
     ) {
         let atlas_ptr = atlas_view as *const wgpu::TextureView;
         let color_ptr = color_atlas_view as *const wgpu::TextureView;
 
         let need_rebuild = match &self.active_glyph_atlas {
             Some(active) => {
-                active.atlas_view_ptr != atlas_ptr || active.color_atlas_view_ptr != color_ptr
+                active.atlas_view_ptr != atlas_ptr
+                    || active.color_atlas_view_ptr != color_ptr
+                    || active.atlas_generation != atlas_generation
             }
             None => true,
         };
@@ -3154,6 +3166,7 @@ impl GpuRenderer {
             self.active_glyph_atlas = Some(ActiveGlyphAtlas {
                 atlas_view_ptr: atlas_ptr,
                 color_atlas_view_ptr: color_ptr,
+                atlas_generation,
             });
             self.rebind_sdf_bind_group();
         }
@@ -5448,8 +5461,9 @@ impl GpuRenderer {
         primitives: &[GpuPrimitive],
         atlas_view: &wgpu::TextureView,
         color_atlas_view: &wgpu::TextureView,
+        atlas_generation: u64,
     ) {
-        self.set_glyph_atlas(atlas_view, color_atlas_view);
+        self.set_glyph_atlas(atlas_view, color_atlas_view, atlas_generation);
         self.render_primitives_overlay(target, primitives);
     }
 
@@ -6000,6 +6014,7 @@ impl GpuRenderer {
     /// * `atlas_view` - The grayscale glyph atlas texture view
     /// * `color_atlas_view` - The color (RGBA) glyph atlas texture view for emoji
     /// * `atlas_sampler` - The sampler for the atlases
+    /// * `atlas_generation` - Changes whenever the atlas textures are recreated
     pub fn render_text(
         &mut self,
         target: &wgpu::TextureView,
@@ -6007,6 +6022,7 @@ impl GpuRenderer {
         atlas_view: &wgpu::TextureView,
         color_atlas_view: &wgpu::TextureView,
         atlas_sampler: &wgpu::Sampler,
+        atlas_generation: u64,
     ) {
         if glyphs.is_empty() {
             return;
@@ -6025,13 +6041,15 @@ impl GpuRenderer {
             .write_buffer(&self.buffers._glyphs, 0, bytemuck::cast_slice(glyphs));
 
         // Check if we need to recreate the text bind group
-        // Invalidate if either atlas view pointer changed (texture was recreated)
+        // Invalidate if either atlas view pointer or the atlas generation changed
+        // (texture was recreated)
         let atlas_view_ptr = atlas_view as *const wgpu::TextureView;
         let color_atlas_view_ptr = color_atlas_view as *const wgpu::TextureView;
         let need_new_bind_group = match &self.cached_text {
             Some(cached) => {
                 cached.atlas_view_ptr != atlas_view_ptr
                     || cached.color_atlas_view_ptr != color_atlas_view_ptr
+                    || cached.atlas_generation != atlas_generation
             }
             None => true,
         };
@@ -6067,6 +6085,7 @@ impl GpuRenderer {
                 bind_group,
                 atlas_view_ptr,
                 color_atlas_view_ptr,
+                atlas_generation,
             });
         }
 
@@ -99,9 +99,9 @@ struct Primitive {
 @group(0) @binding(0) var<uniform> uniforms: Uniforms;
 @group(0) @binding(1) var<storage, read> primitives: array<Primitive>;
 // Glyph atlas textures for unified text rendering
-@group(0) @binding(2) var glyph_atlas: texture_2d<f32>;
+@group(0) @binding(2) var glyph_atlas: texture_2d_array<f32>;
 @group(0) @binding(3) var glyph_sampler: sampler;
-@group(0) @binding(4) var color_glyph_atlas: texture_2d<f32>;
+@group(0) @binding(4) var color_glyph_atlas: texture_2d_array<f32>;
 // Auxiliary data buffer for variable-length per-primitive data
 // (3D group shape descriptors, polygon clip vertices, etc.)
 @group(0) @binding(5) var<storage, read> aux_data: array<vec4<f32>>;
@@ -1195,9 +1195,11 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         case PRIM_TEXT: {
             // Text glyph - sample from glyph atlas
             // UV bounds are stored in gradient_params: (u_min, v_min, u_max, v_max)
-            // fill_type stores is_color flag (1 = color emoji, 0 = grayscale)
+            // fill_type bit 0 is the is_color flag (1 = color emoji, 0 = grayscale),
+            // the remaining bits are the atlas page (texture array layer)
             let uv_bounds = prim.gradient_params;
-            let is_color = fill_type == 1u;
+            let is_color = (fill_type & 1u) == 1u;
+            let atlas_page = i32(fill_type >> 1u);
 
             // Calculate UV within the glyph quad
             // Use sp (inverse-transformed point) so rotated/skewed text samples correctly
@@ -1209,10 +1211,10 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
             var text_result: vec4<f32>;
             if is_color {
                 // Color emoji - sample RGBA directly from color atlas
-                text_result = textureSample(color_glyph_atlas, glyph_sampler, atlas_uv);
+                text_result = textureSample(color_glyph_atlas, glyph_sampler, atlas_uv, atlas_page);
             } else {
                 // Grayscale text - sample coverage from R channel, apply color tint
-                let coverage = textureSample(glyph_atlas, glyph_sampler, atlas_uv).r;
+                let coverage = textureSample(glyph_atlas, glyph_sampler, atlas_uv, atlas_page).r;
                 // Apply gamma correction for crisp text rendering
                 let gamma_coverage = pow(coverage, 0.7);
                 text_result = vec4<f32>(prim.color.rgb, prim.color.a * gamma_coverage);
@@ -1428,6 +1430,7 @@ struct VertexOutput {
     @location(2) world_pos: vec2<f32>,
     @location(3) @interpolate(flat) clip_bounds: vec4<f32>,
     @location(4) @interpolate(flat) is_color: f32,
+    @location(5) @interpolate(flat) atlas_page: i32,
 }
 
 struct TextUniforms {
@@ -1444,16 +1447,17 @@ struct GlyphInstance {
     color: vec4<f32>,
     // Clip bounds (x, y, width, height) - set to large values for no clip
     clip_bounds: vec4<f32>,
-    // Flags: [is_color, unused, unused, unused]
+    // Flags: [is_color, atlas_page, unused, unused]
     // is_color: 1.0 = color emoji (use color_atlas), 0.0 = grayscale (use glyph_atlas)
+    // atlas_page: texture array layer holding the glyph
     flags: vec4<f32>,
 }
 
 @group(0) @binding(0) var<uniform> uniforms: TextUniforms;
 @group(0) @binding(1) var<storage, read> glyphs: array<GlyphInstance>;
-@group(0) @binding(2) var glyph_atlas: texture_2d<f32>;
+@group(0) @binding(2) var glyph_atlas: texture_2d_array<f32>;
 @group(0) @binding(3) var glyph_sampler: sampler;
-@group(0) @binding(4) var color_atlas: texture_2d<f32>;
+@group(0) @binding(4) var color_atlas: texture_2d_array<f32>;
 
 @vertex
 fn vs_main(
@@ -1501,6 +1505,7 @@ fn vs_main(
     out.world_pos = pos;
     out.clip_bounds = glyph.clip_bounds;
     out.is_color = glyph.flags.x;
+    out.atlas_page = i32(glyph.flags.y);
 
     return out;
 }
@@ -1540,12 +1545,12 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     // Check if this is a color emoji glyph
     if in.is_color > 0.5 {
         // Color emoji: sample RGBA from color atlas, use texture color directly
-        let emoji_color = textureSample(color_atlas, glyph_sampler, in.uv);
+        let emoji_color = textureSample(color_atlas, glyph_sampler, in.uv, in.atlas_page);
         // Apply clip alpha only - keep original emoji colors
         return vec4<f32>(emoji_color.rgb, emoji_color.a * clip_alpha);
     } else {
         // Grayscale text: sample coverage from glyph atlas, apply tint color
-        let coverage = textureSample(glyph_atlas, glyph_sampler, in.uv).r;
+        let coverage = textureSample(glyph_atlas, glyph_sampler, in.uv, in.atlas_page).r;
 
         // Use coverage directly with slight gamma correction for cleaner edges
         // The rasterizer provides good coverage values - we just need to
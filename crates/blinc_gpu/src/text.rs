@@ -63,41 +63,22 @@ impl TextRenderingContext {
         let renderer = TextRenderer::new();
 
         // Grayscale atlas for regular text
-        let (gray_width, gray_height) = renderer.atlas_dimensions();
-        let atlas_texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Glyph Atlas Texture"),
-            size: wgpu::Extent3d {
-                width: gray_width,
-                height: gray_height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::R8Unorm,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
-        let atlas_view = atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let (atlas_texture, atlas_view) = create_atlas_texture(
+            &device,
+            "Glyph Atlas Texture",
+            wgpu::TextureFormat::R8Unorm,
+            renderer.atlas_dimensions(),
+            renderer.atlas().page_count(),
+        );
 
         // RGBA color atlas for emoji
-        let (color_width, color_height) = renderer.color_atlas_dimensions();
-        let color_atlas_texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Color Glyph Atlas Texture"),
-            size: wgpu::Extent3d {
-                width: color_width,
-                height: color_height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
-        let color_atlas_view =
-            color_atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let (color_atlas_texture, color_atlas_view) = create_atlas_texture(
+            &device,
+            "Color Glyph Atlas Texture",
+            wgpu::TextureFormat::Rgba8UnormSrgb,
+            renderer.color_atlas_dimensions(),
+            renderer.color_atlas().page_count(),
+        );
 
         Self {
             renderer,
@@ -450,7 +431,7 @@ impl TextRenderingContext {
                 // Default: no clip (will be set by caller if needed)
                 clip_bounds: [-10000.0, -10000.0, 100000.0, 100000.0],
                 // Set is_color flag for emoji glyphs
-                flags: [if g.is_color { 1.0 } else { 0.0 }, 0.0, 0.0, 0.0],
+                flags: [if g.is_color { 1.0 } else { 0.0 }, g.page as f32, 0.0, 0.0],
             })
             .collect();
 
@@ -536,7 +517,7 @@ impl TextRenderingContext {
                 uv_bounds: g.uv_bounds,
                 color: g.color,
                 clip_bounds: [-10000.0, -10000.0, 100000.0, 100000.0],
-                flags: [if g.is_color { 1.0 } else { 0.0 }, 0.0, 0.0, 0.0],
+                flags: [if g.is_color { 1.0 } else { 0.0 }, g.page as f32, 0.0, 0.0],
             })
             .collect();
 
@@ -611,6 +592,14 @@ impl TextRenderingContext {
         self.color_atlas_view.as_ref()
     }
 
+    /// Generation of the atlas textures
+    ///
+    /// Changes whenever either atlas may have recreated its texture and view,
+    /// so bind groups referencing them must be rebuilt.
+    pub fn atlas_generation(&self) -> u64 {
+        self.renderer.atlas().generation() + self.renderer.color_atlas().generation()
+    }
+
     /// Get the sampler
     pub fn sampler(&self) -> &wgpu::Sampler {
         &self.sampler
@@ -624,115 +613,152 @@ impl TextRenderingContext {
         self.renderer.font_registry()
     }
 
-    /// Update the GPU atlas texture from the TextRenderer's atlas
-    fn update_atlas_texture(&mut self) {
-        let (width, height) = self.renderer.atlas_dimensions();
-        let pixels = self.renderer.atlas_pixels();
-
-        // Create or recreate texture if size changed
-        let needs_create = match &self.atlas_texture {
-            Some(tex) => tex.width() != width || tex.height() != height,
-            None => true,
-        };
+    /// Advance the glyph atlases to a new frame
+    ///
+    /// Call once per frame before preparing text. Glyphs that go unused for
+    /// a number of frames become eligible for eviction when the atlas fills up.
+    pub fn begin_frame(&mut self) {
+        self.renderer.begin_frame();
+    }
 
-        if needs_create {
-            let texture = self.device.create_texture(&wgpu::TextureDescriptor {
-                label: Some("Glyph Atlas Texture"),
-                size: wgpu::Extent3d {
-                    width,
-                    height,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::R8Unorm,
-                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-                view_formats: &[],
-            });
-
-            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-            self.atlas_texture = Some(texture);
-            self.atlas_view = Some(view);
-        }
+    /// Usage statistics of the grayscale and color glyph atlases
+    pub fn atlas_stats(&self) -> (blinc_text::AtlasStats, blinc_text::AtlasStats) {
+        self.renderer.atlas_stats()
+    }
 
-        // Upload pixel data
-        if let Some(texture) = &self.atlas_texture {
-            self.queue.write_texture(
-                wgpu::ImageCopyTexture {
-                    texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d::ZERO,
-                    aspect: wgpu::TextureAspect::All,
-                },
-                pixels,
-                wgpu::ImageDataLayout {
-                    offset: 0,
-                    bytes_per_row: Some(width),
-                    rows_per_image: Some(height),
-                },
-                wgpu::Extent3d {
-                    width,
-                    height,
-                    depth_or_array_layers: 1,
-                },
-            );
-        }
+    /// Update the GPU atlas texture from the TextRenderer's atlas
+    fn update_atlas_texture(&mut self) {
+        sync_atlas_texture(
+            &self.device,
+            &self.queue,
+            self.renderer.atlas(),
+            &mut self.atlas_texture,
+            &mut self.atlas_view,
+            "Glyph Atlas Texture",
+            wgpu::TextureFormat::R8Unorm,
+        );
     }
 
     /// Update the GPU color atlas texture from the TextRenderer's color atlas
     fn update_color_atlas_texture(&mut self) {
-        let (width, height) = self.renderer.color_atlas_dimensions();
-        let pixels = self.renderer.color_atlas_pixels();
+        sync_atlas_texture(
+            &self.device,
+            &self.queue,
+            self.renderer.color_atlas(),
+            &mut self.color_atlas_texture,
+            &mut self.color_atlas_view,
+            "Color Glyph Atlas Texture",
+            wgpu::TextureFormat::Rgba8UnormSrgb, // RGBA for color emoji
+        );
+    }
+}
 
-        // Create or recreate texture if size changed
-        let needs_create = match &self.color_atlas_texture {
-            Some(tex) => tex.width() != width || tex.height() != height,
-            None => true,
-        };
+/// Create a glyph atlas texture array with one layer per atlas page
+///
+/// GL backends treat single-layer textures as plain 2D, so at least two
+/// layers are allocated.
+fn create_atlas_texture(
+    device: &wgpu::Device,
+    label: &str,
+    format: wgpu::TextureFormat,
+    (width, height): (u32, u32),
+    pages: u32,
+) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(label),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: pages.max(2),
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor {
+        dimension: Some(wgpu::TextureViewDimension::D2Array),
+        ..Default::default()
+    });
+    (texture, view)
+}
 
-        if needs_create {
-            let texture = self.device.create_texture(&wgpu::TextureDescriptor {
-                label: Some("Color Glyph Atlas Texture"),
-                size: wgpu::Extent3d {
-                    width,
-                    height,
-                    depth_or_array_layers: 1,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8UnormSrgb, // RGBA for color emoji
-                usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-                view_formats: &[],
-            });
-
-            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-            self.color_atlas_texture = Some(texture);
-            self.color_atlas_view = Some(view);
+/// Upload an atlas to its GPU texture array
+///
+/// When the atlas has grown past the texture's layers (or the page size
+/// changed) the texture is recreated and every page uploaded; otherwise only
+/// the dirty region of each page is written.
+fn sync_atlas_texture<const BPP: usize>(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    atlas: &blinc_text::PagedGlyphAtlas<BPP>,
+    texture: &mut Option<wgpu::Texture>,
+    view: &mut Option<wgpu::TextureView>,
+    label: &str,
+    format: wgpu::TextureFormat,
+) {
+    let (width, height) = atlas.dimensions();
+    let pages = atlas.page_count();
+
+    let needs_create = match texture.as_ref() {
+        Some(tex) => {
+            tex.width() != width || tex.height() != height || tex.depth_or_array_layers() < pages
         }
-
-        // Upload pixel data (RGBA = 4 bytes per pixel)
-        if let Some(texture) = &self.color_atlas_texture {
-            self.queue.write_texture(
-                wgpu::ImageCopyTexture {
-                    texture,
-                    mip_level: 0,
-                    origin: wgpu::Origin3d::ZERO,
-                    aspect: wgpu::TextureAspect::All,
-                },
-                pixels,
-                wgpu::ImageDataLayout {
-                    offset: 0,
-                    bytes_per_row: Some(width * 4), // 4 bytes per pixel for RGBA
-                    rows_per_image: Some(height),
-                },
-                wgpu::Extent3d {
+        None => true,
+    };
+
+    let regions: Vec<blinc_text::DirtyRegion> = if needs_create {
+        let (new_texture, new_view) =
+            create_atlas_texture(device, label, format, (width, height), pages);
+        *texture = Some(new_texture);
+        *view = Some(new_view);
+        (0..pages)
+            .map(|page| blinc_text::DirtyRegion {
+                page,
+                region: blinc_text::AtlasRegion {
+                    x: 0,
+                    y: 0,
                     width,
                     height,
-                    depth_or_array_layers: 1,
                 },
-            );
-        }
+            })
+            .collect()
+    } else {
+        atlas.dirty_regions()
+    };
+
+    let Some(texture) = texture.as_ref() else {
+        return;
+    };
+    for dirty in regions {
+        let Some(pixels) = atlas.page_pixels(dirty.page) else {
+            continue;
+        };
+        let region = dirty.region;
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: region.x,
+                    y: region.y,
+                    z: dirty.page,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            pixels,
+            wgpu::ImageDataLayout {
+                offset: ((region.y * width + region.x) as usize * BPP) as u64,
+                bytes_per_row: Some(width * BPP as u32),
+                rows_per_image: None,
+            },
+            wgpu::Extent3d {
+                width: region.width,
+                height: region.height,
+                depth_or_array_layers: 1,
+            },
+        );
     }
 }
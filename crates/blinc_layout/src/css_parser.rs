@@ -51,6 +51,7 @@
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
+use blinc_animation::{Easing, MotionPath, OffsetRotate, SpringConfig, StepPosition};
 use blinc_core::{
     Brush, ClipLength, ClipPath, Color, CornerRadius, Gradient, GradientSpace, GradientStop,
     ImageBrush, Point, Shadow, Transform,
@@ -74,7 +75,8 @@ use crate::element_style::{
     ElementStyle, SpacingRect, StyleAlign, StyleDisplay, StyleFlexDirection, StyleJustify,
     StyleOverflow, StylePosition,
 };
-use crate::units::Length;
+use crate::media_query::{ContainerQuery, ContainerType, MediaContext, MediaQueryList};
+use crate::units::{CalcExpr, Length, LengthSlot};
 
 /// Custom parser result type using VerboseError for better diagnostics
 type ParseResult<'a, O> = IResult<&'a str, O, VerboseError<&'a str>>;
@@ -799,7 +801,7 @@ impl CssKeyframes {
 
         for kf in &self.keyframes {
             let props = Self::style_to_keyframe_properties(&kf.style);
-            animation = animation.keyframe(kf.position, props, easing);
+            animation = animation.keyframe(kf.position, props, easing.clone());
         }
 
         animation
@@ -933,6 +935,11 @@ impl CssKeyframes {
             props.font_size = Some(fs);
         }
 
+        // Variable font axes
+        if let Some(axes) = &style.font_variations {
+            props.font_variations = Some(axes.iter().map(|v| (v.tag, v.value)).collect());
+        }
+
         // Corner radius
         if let Some(cr) = &style.corner_radius {
             props.corner_radius =
@@ -1066,6 +1073,11 @@ impl CssKeyframes {
             props.transform_origin = Some(to);
         }
 
+        // Motion path position
+        if let Some(distance) = style.offset_distance {
+            props.offset_distance = Some(distance);
+        }
+
         props
     }
 
@@ -1176,7 +1188,7 @@ impl Default for CssAnimation {
 }
 
 /// Animation timing function
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub enum AnimationTiming {
     Linear,
     #[default]
@@ -1184,6 +1196,9 @@ pub enum AnimationTiming {
     EaseIn,
     EaseOut,
     EaseInOut,
+    /// Any other timing function: `cubic-bezier()`, `steps()`, `linear()`,
+    /// `spring()` or a named easing such as `ease-out-back`
+    Function(Easing),
 }
 
 impl AnimationTiming {
@@ -1194,7 +1209,7 @@ impl AnimationTiming {
             "ease-in" => Some(AnimationTiming::EaseIn),
             "ease-out" => Some(AnimationTiming::EaseOut),
             "ease-in-out" => Some(AnimationTiming::EaseInOut),
-            _ => None,
+            _ => parse_easing_function(s).map(AnimationTiming::Function),
         }
     }
 
@@ -1205,14 +1220,14 @@ impl AnimationTiming {
     /// - ease-in:     cubic-bezier(0.42, 0.0, 1.0, 1.0)
     /// - ease-out:    cubic-bezier(0.0, 0.0, 0.58, 1.0)
     /// - ease-in-out: cubic-bezier(0.42, 0.0, 0.58, 1.0)
-    pub fn to_easing(&self) -> blinc_animation::Easing {
-        use blinc_animation::Easing;
+    pub fn to_easing(&self) -> Easing {
         match self {
             AnimationTiming::Linear => Easing::Linear,
             AnimationTiming::Ease => Easing::CubicBezier(0.25, 0.1, 0.25, 1.0),
             AnimationTiming::EaseIn => Easing::CubicBezier(0.42, 0.0, 1.0, 1.0),
             AnimationTiming::EaseOut => Easing::CubicBezier(0.0, 0.0, 0.58, 1.0),
             AnimationTiming::EaseInOut => Easing::CubicBezier(0.42, 0.0, 0.58, 1.0),
+            AnimationTiming::Function(easing) => easing.clone(),
         }
     }
 }
@@ -1269,6 +1284,56 @@ impl AnimationFillMode {
     }
 }
 
+/// An `@media` block: rules that apply while the query matches
+#[derive(Clone, Debug)]
+pub struct MediaRule {
+    /// The media query list from the rule prelude
+    pub query: MediaQueryList,
+    /// Rules nested inside the block
+    pub stylesheet: Stylesheet,
+}
+
+/// An `@container` block: rules that apply to descendants of a matching container
+#[derive(Clone, Debug)]
+pub struct ContainerRule {
+    /// The container condition from the rule prelude
+    pub query: ContainerQuery,
+    /// Rules nested inside the block
+    pub stylesheet: Stylesheet,
+}
+
+/// Rule-level differences between two versions of a stylesheet
+///
+/// Selectors are reported as written (`#id`, `#id:hover`) for simple rules and
+/// in debug form for complex selectors.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StylesheetDiff {
+    /// Selectors only present in the new stylesheet
+    pub added: Vec<String>,
+    /// Selectors only present in the old stylesheet
+    pub removed: Vec<String>,
+    /// Selectors present in both with different declarations
+    pub changed: Vec<String>,
+    /// Whether any `:root` variable was added, removed or changed
+    pub variables_changed: bool,
+    /// Whether any `@keyframes` block was added, removed or changed
+    pub keyframes_changed: bool,
+    /// Whether any `@media` or `@container` block changed
+    pub conditional_rules_changed: bool,
+}
+
+impl StylesheetDiff {
+    /// Check if the two stylesheets are equivalent
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && !self.variables_changed
+            && !self.keyframes_changed
+            && !self.conditional_rules_changed
+    }
+}
+
 /// A parsed stylesheet containing styles keyed by element ID
 #[derive(Clone, Default, Debug)]
 pub struct Stylesheet {
@@ -1280,6 +1345,10 @@ pub struct Stylesheet {
     variables: HashMap<String, String>,
     /// Keyframe animations defined with @keyframes
     keyframes: HashMap<String, CssKeyframes>,
+    /// Conditional rules defined with @media
+    media_rules: Vec<MediaRule>,
+    /// Conditional rules defined with @container
+    container_rules: Vec<ContainerRule>,
 }
 
 impl Stylesheet {
@@ -1328,18 +1397,7 @@ impl Stylesheet {
                     });
                 }
 
-                let mut stylesheet = Stylesheet::new();
-                stylesheet.variables = parsed.variables;
-                for (id, style) in parsed.rules {
-                    stylesheet.styles.insert(id, style);
-                }
-                stylesheet.complex_rules = parsed.complex_rules;
-                for keyframes in parsed.keyframes {
-                    stylesheet
-                        .keyframes
-                        .insert(keyframes.name.clone(), keyframes);
-                }
-
+                let stylesheet = Stylesheet::from_parsed(parsed);
                 CssParseResult { stylesheet, errors }
             }
             Err(e) => {
@@ -1401,18 +1459,7 @@ impl Stylesheet {
                     });
                 }
 
-                let mut stylesheet = Stylesheet::new();
-                stylesheet.variables = parsed.variables;
-                for (id, style) in parsed.rules {
-                    stylesheet.styles.insert(id, style);
-                }
-                stylesheet.complex_rules = parsed.complex_rules;
-                for keyframes in parsed.keyframes {
-                    stylesheet
-                        .keyframes
-                        .insert(keyframes.name.clone(), keyframes);
-                }
-
+                let stylesheet = Stylesheet::from_parsed(parsed);
                 CssParseResult { stylesheet, errors }
             }
             Err(e) => {
@@ -1427,6 +1474,24 @@ impl Stylesheet {
         }
     }
 
+    /// Build a stylesheet from the intermediate parser output
+    fn from_parsed(parsed: ParsedStylesheet) -> Self {
+        let mut stylesheet = Stylesheet::new();
+        stylesheet.variables = parsed.variables;
+        for (id, style) in parsed.rules {
+            stylesheet.styles.insert(id, style);
+        }
+        stylesheet.complex_rules = parsed.complex_rules;
+        for keyframes in parsed.keyframes {
+            stylesheet
+                .keyframes
+                .insert(keyframes.name.clone(), keyframes);
+        }
+        stylesheet.media_rules = parsed.media_rules;
+        stylesheet.container_rules = parsed.container_rules;
+        stylesheet
+    }
+
     /// Parse CSS text into a stylesheet
     ///
     /// Parse errors are logged via tracing at DEBUG level with full context.
@@ -1584,7 +1649,10 @@ impl Stylesheet {
 
     /// Check if the stylesheet is empty
     pub fn is_empty(&self) -> bool {
-        self.styles.is_empty() && self.complex_rules.is_empty()
+        self.styles.is_empty()
+            && self.complex_rules.is_empty()
+            && self.media_rules.is_empty()
+            && self.container_rules.is_empty()
     }
 
     /// Get all complex selector rules
@@ -1612,6 +1680,89 @@ impl Stylesheet {
         for (key, kf) in other.keyframes {
             self.keyframes.insert(key, kf);
         }
+        self.media_rules.extend(other.media_rules);
+        self.container_rules.extend(other.container_rules);
+    }
+
+    /// Compare this stylesheet against a newer version of itself
+    ///
+    /// Used by hot reload to decide whether a re-parsed file actually changed
+    /// anything and to report which selectors were touched.
+    pub fn diff(&self, new: &Stylesheet) -> StylesheetDiff {
+        fn style_key(style: &ElementStyle) -> String {
+            // ElementStyle has no PartialEq; its Debug output is deterministic
+            format!("{:?}", style)
+        }
+
+        let mut diff = StylesheetDiff::default();
+
+        for (key, style) in &self.styles {
+            match new.styles.get(key) {
+                None => diff.removed.push(format!("#{}", key)),
+                Some(new_style) if style_key(style) != style_key(new_style) => {
+                    diff.changed.push(format!("#{}", key))
+                }
+                Some(_) => {}
+            }
+        }
+        for key in new.styles.keys() {
+            if !self.styles.contains_key(key) {
+                diff.added.push(format!("#{}", key));
+            }
+        }
+
+        // Complex selectors may repeat, so compare the rule lists per selector
+        let group = |rules: &[(ComplexSelector, ElementStyle)]| {
+            let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+            for (selector, style) in rules {
+                groups
+                    .entry(format!("{:?}", selector))
+                    .or_default()
+                    .push(style_key(style));
+            }
+            groups
+        };
+        let old_complex = group(&self.complex_rules);
+        let new_complex = group(&new.complex_rules);
+        for (selector, styles) in &old_complex {
+            match new_complex.get(selector) {
+                None => diff.removed.push(selector.clone()),
+                Some(new_styles) if styles != new_styles => diff.changed.push(selector.clone()),
+                Some(_) => {}
+            }
+        }
+        for selector in new_complex.keys() {
+            if !old_complex.contains_key(selector) {
+                diff.added.push(selector.clone());
+            }
+        }
+
+        diff.variables_changed = self.variables != new.variables;
+        diff.keyframes_changed = self.keyframes.len() != new.keyframes.len()
+            || self.keyframes.iter().any(|(name, kf)| {
+                new.keyframes.get(name).map_or(true, |new_kf| {
+                    format!("{:?}", kf) != format!("{:?}", new_kf)
+                })
+            });
+        diff.conditional_rules_changed = self.media_rules.len() != new.media_rules.len()
+            || self.container_rules.len() != new.container_rules.len()
+            || self.media_rules.iter().zip(&new.media_rules).any(|(a, b)| {
+                format!("{:?}", a.query) != format!("{:?}", b.query)
+                    || !a.stylesheet.diff(&b.stylesheet).is_empty()
+            })
+            || self
+                .container_rules
+                .iter()
+                .zip(&new.container_rules)
+                .any(|(a, b)| {
+                    format!("{:?}", a.query) != format!("{:?}", b.query)
+                        || !a.stylesheet.diff(&b.stylesheet).is_empty()
+                });
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+        diff
     }
 
     /// Load and parse a `.css` file from disk
@@ -1629,6 +1780,112 @@ impl Stylesheet {
         Self::parse(&css)
     }
 
+    // =========================================================================
+    // Media and Container Queries
+    // =========================================================================
+
+    /// Get all `@media` blocks
+    pub fn media_rules(&self) -> &[MediaRule] {
+        &self.media_rules
+    }
+
+    /// Get all `@container` blocks
+    pub fn container_rules(&self) -> &[ContainerRule] {
+        &self.container_rules
+    }
+
+    /// Check if the stylesheet (or any nested block) contains `@media` rules
+    pub fn has_media_rules(&self) -> bool {
+        !self.media_rules.is_empty()
+            || self
+                .container_rules
+                .iter()
+                .any(|rule| rule.stylesheet.has_media_rules())
+    }
+
+    /// Record which `@media` blocks match, depth-first
+    ///
+    /// Two contexts with equal signatures resolve to the same stylesheet, so
+    /// callers can skip re-resolution (and restyling) when it is unchanged.
+    pub fn media_signature(&self, ctx: &MediaContext) -> Vec<bool> {
+        let mut signature = Vec::new();
+        self.collect_media_signature(ctx, &mut signature);
+        signature
+    }
+
+    fn collect_media_signature(&self, ctx: &MediaContext, out: &mut Vec<bool>) {
+        for rule in &self.media_rules {
+            let matches = rule.query.matches(ctx);
+            out.push(matches);
+            if matches {
+                rule.stylesheet.collect_media_signature(ctx, out);
+            }
+        }
+        for rule in &self.container_rules {
+            rule.stylesheet.collect_media_signature(ctx, out);
+        }
+    }
+
+    /// Flatten `@media` blocks that match `ctx` into a plain stylesheet
+    ///
+    /// Matching blocks cascade over the top-level rules property by property,
+    /// in source order. `@container` blocks are kept (with their own nested
+    /// `@media` blocks resolved) since they depend on layout.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let css = r#"
+    ///     #sidebar { width: 280px; }
+    ///     @media (max-width: 600px) {
+    ///         #sidebar { width: 100%; }
+    ///     }
+    /// "#;
+    /// let sheet = Stylesheet::parse_with_errors(css).stylesheet;
+    /// let narrow = sheet.resolve_media(&MediaContext::new(400.0, 800.0));
+    /// ```
+    pub fn resolve_media(&self, ctx: &MediaContext) -> Stylesheet {
+        let mut resolved = Stylesheet {
+            styles: self.styles.clone(),
+            complex_rules: self.complex_rules.clone(),
+            variables: self.variables.clone(),
+            keyframes: self.keyframes.clone(),
+            media_rules: Vec::new(),
+            container_rules: self
+                .container_rules
+                .iter()
+                .map(|rule| ContainerRule {
+                    query: rule.query.clone(),
+                    stylesheet: rule.stylesheet.resolve_media(ctx),
+                })
+                .collect(),
+        };
+        for rule in &self.media_rules {
+            if rule.query.matches(ctx) {
+                resolved.cascade(rule.stylesheet.resolve_media(ctx));
+            }
+        }
+        resolved
+    }
+
+    /// Cascade another stylesheet over this one, merging styles per property
+    ///
+    /// Unlike [`merge`](Self::merge), a simple rule in `other` only overrides
+    /// the properties it sets instead of replacing the whole style.
+    fn cascade(&mut self, other: Stylesheet) {
+        for (key, style) in other.styles {
+            let merged = match self.styles.get(&key) {
+                Some(existing) => existing.merge(&style),
+                None => style,
+            };
+            self.styles.insert(key, merged);
+        }
+        self.complex_rules.extend(other.complex_rules);
+        self.variables.extend(other.variables);
+        self.keyframes.extend(other.keyframes);
+        self.container_rules.extend(other.container_rules);
+    }
+
     // =========================================================================
     // CSS Variables (Custom Properties)
     // =========================================================================
@@ -2072,6 +2329,51 @@ fn parse_complex_selector(input: &str) -> ParseResult<ComplexSelector> {
     Ok((remaining, ComplexSelector { segments }))
 }
 
+/// Parse a selector list such as `#inbox .row:checked, #sidebar > .item`
+///
+/// This is the runtime entry point used by element queries; it accepts the
+/// same selector syntax as stylesheet rules.
+#[allow(clippy::result_large_err)]
+pub fn parse_selectors(input: &str) -> Result<Vec<ComplexSelector>, ParseError> {
+    let mut selectors = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    let mut parts = Vec::new();
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&input[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&input[start..]);
+
+    for part in parts {
+        let part = part.trim();
+        let parsed = match parse_complex_selector(part) {
+            Ok((rest, selector)) if rest.trim().is_empty() => Some(selector),
+            _ => None,
+        };
+        let Some(selector) = parsed else {
+            let offset = part.as_ptr() as usize - input.as_ptr() as usize;
+            let mut error = ParseError::new(
+                Severity::Error,
+                format!("Invalid selector '{}'", part),
+                1,
+                offset + 1,
+            );
+            error.fragment = part.to_string();
+            return Err(error);
+        };
+        selectors.push(selector);
+    }
+    Ok(selectors)
+}
+
 /// Parse a compound selector: one or more simple selector parts with no combinator.
 /// e.g. `#id.class:hover:first-child`
 /// Find the index of the matching closing parenthesis for the opening paren at input[0].
@@ -2580,12 +2882,14 @@ where
     }
 }
 
-/// Result of parsing a stylesheet - rules, variables, and keyframes
+/// Result of parsing a stylesheet - rules, variables, keyframes and conditional blocks
 struct ParsedStylesheet {
     rules: Vec<(String, ElementStyle)>,
     complex_rules: Vec<(ComplexSelector, ElementStyle)>,
     variables: HashMap<String, String>,
     keyframes: Vec<CssKeyframes>,
+    media_rules: Vec<MediaRule>,
+    container_rules: Vec<ContainerRule>,
 }
 
 /// Parse an entire stylesheet with error collection
@@ -2595,12 +2899,28 @@ fn parse_stylesheet_with_errors<'a>(
     variables: &HashMap<String, String>,
 ) -> ParseResult<'a, ParsedStylesheet> {
     let (input, _) = ws(css)?;
+    let (remaining, parsed) = parse_block_items(css, input, errors, variables);
+    let (input, _) = ws(remaining)?;
+    Ok((input, parsed))
+}
 
+/// Parse rules, `:root` blocks, `@keyframes` and conditional blocks until the
+/// input ends or a `}` closes the enclosing block
+///
+/// `css` is the full original source, used for error positions.
+fn parse_block_items<'a>(
+    css: &'a str,
+    input: &'a str,
+    errors: &mut Vec<ParseError>,
+    variables: &HashMap<String, String>,
+) -> (&'a str, ParsedStylesheet) {
     // Parse blocks one at a time to collect errors
     let mut rules = Vec::new();
     let mut complex_rules = Vec::new();
     let mut parsed_variables = variables.clone();
     let mut parsed_keyframes = Vec::new();
+    let mut media_rules = Vec::new();
+    let mut container_rules = Vec::new();
     let mut remaining = input;
 
     loop {
@@ -2649,6 +2969,22 @@ fn parse_stylesheet_with_errors<'a>(
             }
         }
 
+        // Try to parse @media / @container blocks
+        if trimmed.starts_with("@media") || trimmed.starts_with("@container") {
+            match conditional_block(css, trimmed, errors, &parsed_variables) {
+                Some((rest, block)) => {
+                    match block {
+                        Some(ConditionalBlock::Media(rule)) => media_rules.push(rule),
+                        Some(ConditionalBlock::Container(rule)) => container_rules.push(rule),
+                        None => {}
+                    }
+                    remaining = rest;
+                    continue;
+                }
+                None => break,
+            }
+        }
+
         // Try to parse a rule (complex selector or simple #id selector)
         // Supports comma-separated selector lists: #a, #b { ... }
         match css_rule_complex_or_simple(css, errors, &parsed_variables)(trimmed) {
@@ -2673,16 +3009,110 @@ fn parse_stylesheet_with_errors<'a>(
         }
     }
 
-    let (input, _) = ws(remaining)?;
-    Ok((
-        input,
+    (
+        remaining,
         ParsedStylesheet {
             rules,
             complex_rules,
             variables: parsed_variables,
             keyframes: parsed_keyframes,
+            media_rules,
+            container_rules,
         },
-    ))
+    )
+}
+
+/// A parsed `@media` or `@container` block
+enum ConditionalBlock {
+    Media(MediaRule),
+    Container(ContainerRule),
+}
+
+/// Find the `}` that closes a block whose `{` has already been consumed
+fn find_block_end(input: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in input.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parse an `@media` or `@container` block with its nested rules
+///
+/// Returns the input after the block and the parsed block, or `None` for the
+/// block when its condition is invalid (the block is skipped with a warning,
+/// like browsers treat unknown media queries as `not all`). Returns `None`
+/// overall if the block is unterminated.
+fn conditional_block<'a>(
+    css: &'a str,
+    input: &'a str,
+    errors: &mut Vec<ParseError>,
+    variables: &HashMap<String, String>,
+) -> Option<(&'a str, Option<ConditionalBlock>)> {
+    let (is_media, after_keyword) = match input.strip_prefix("@media") {
+        Some(rest) => (true, rest),
+        None => (false, input.strip_prefix("@container")?),
+    };
+    let open = after_keyword.find('{')?;
+    let prelude = after_keyword[..open].trim();
+    let body = &after_keyword[open + 1..];
+    let (line, column, _) = calculate_position(css, input);
+
+    let Some(close) = find_block_end(body) else {
+        errors.push(ParseError::new(
+            Severity::Error,
+            format!(
+                "Unterminated @{} block",
+                if is_media { "media" } else { "container" }
+            ),
+            line,
+            column,
+        ));
+        return None;
+    };
+    let after_block = &body[close + 1..];
+
+    let (rest, parsed) = parse_block_items(css, body, errors, variables);
+    if !rest.trim_start().starts_with('}') {
+        // A nested rule failed to parse; skip what's left of the block
+        let (line, column, fragment) = calculate_position(css, rest.trim_start());
+        let mut err = ParseError::new(
+            Severity::Warning,
+            "Skipped unparsable content in conditional block",
+            line,
+            column,
+        );
+        err.fragment = fragment;
+        errors.push(err);
+    }
+    let stylesheet = Stylesheet::from_parsed(parsed);
+
+    let block = if is_media {
+        MediaQueryList::parse(prelude)
+            .map(|query| ConditionalBlock::Media(MediaRule { query, stylesheet }))
+    } else {
+        ContainerQuery::parse(prelude)
+            .map(|query| ConditionalBlock::Container(ContainerRule { query, stylesheet }))
+    };
+    if block.is_none() {
+        errors.push(ParseError::new(
+            Severity::Warning,
+            format!(
+                "Invalid @{} condition '{}', block ignored",
+                if is_media { "media" } else { "container" },
+                prelude
+            ),
+            line,
+            column,
+        ));
+    }
+
+    Some((after_block, block))
 }
 
 /// Result of parsing a CSS rule — either a simple #id rule or a complex selector rule
@@ -2924,6 +3354,10 @@ fn resolve_var_references(value: &str, variables: &HashMap<String, String>) -> S
 // ============================================================================
 
 fn apply_property(style: &mut ElementStyle, name: &str, value: &str) {
+    if apply_contextual_length(style, name, value) {
+        return;
+    }
+
     match name {
         "background" | "background-color" => {
             if let Some(brush) = parse_brush(value) {
@@ -2956,6 +3390,30 @@ fn apply_property(style: &mut ElementStyle, name: &str, value: &str) {
         "text-align" => {
             style.text_align = parse_text_align(value);
         }
+        "white-space" => {
+            style.white_space = parse_white_space(value);
+        }
+        "text-overflow" => {
+            style.text_overflow = parse_text_overflow(value);
+        }
+        "line-clamp" | "-webkit-line-clamp" => {
+            style.line_clamp = parse_line_clamp(value);
+        }
+        "hyphens" | "-webkit-hyphens" => {
+            style.hyphens = parse_hyphens(value);
+        }
+        "text-justify" => {
+            style.text_justify = parse_text_justify(value);
+        }
+        "font-feature-settings" => {
+            style.font_features = parse_font_feature_settings(value);
+        }
+        "font-variant-numeric" => {
+            style.font_variant_numeric = parse_font_variant_numeric(value);
+        }
+        "font-variation-settings" => {
+            style.font_variations = parse_font_variation_settings(value);
+        }
         "letter-spacing" => {
             if let Some(px) = parse_length_value(value) {
                 style.letter_spacing = Some(px);
@@ -3259,6 +3717,23 @@ fn apply_property(style: &mut ElementStyle, name: &str, value: &str) {
                 style.clip_path = Some(cp);
             }
         }
+        "offset-path" => {
+            if value.trim().eq_ignore_ascii_case("none") {
+                style.offset_path = None;
+            } else if let Some(path) = parse_offset_path(value) {
+                style.offset_path = Some(Arc::new(MotionPath::new(&path)));
+            }
+        }
+        "offset-distance" => {
+            if let Some(distance) = parse_offset_distance(value) {
+                style.offset_distance = Some(distance);
+            }
+        }
+        "offset-rotate" => {
+            if let Some(rotate) = parse_offset_rotate(value) {
+                style.offset_rotate = Some(rotate);
+            }
+        }
         // =====================================================================
         // Layout Properties
         // =====================================================================
@@ -3537,6 +4012,10 @@ fn apply_property_with_errors(
 ) {
     let (line, column, _) = calculate_position(original_css, current_input);
 
+    if apply_contextual_length(style, name, value) {
+        return;
+    }
+
     match name {
         "background" | "background-color" => {
             if let Some(brush) = parse_brush(value) {
@@ -3589,6 +4068,62 @@ fn apply_property_with_errors(
                 errors.push(ParseError::invalid_value(name, value, line, column));
             }
         }
+        "white-space" => {
+            if let Some(v) = parse_white_space(value) {
+                style.white_space = Some(v);
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
+        "text-overflow" => {
+            if let Some(v) = parse_text_overflow(value) {
+                style.text_overflow = Some(v);
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
+        "line-clamp" | "-webkit-line-clamp" => {
+            if let Some(v) = parse_line_clamp(value) {
+                style.line_clamp = Some(v);
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
+        "hyphens" | "-webkit-hyphens" => {
+            if let Some(v) = parse_hyphens(value) {
+                style.hyphens = Some(v);
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
+        "text-justify" => {
+            if let Some(v) = parse_text_justify(value) {
+                style.text_justify = Some(v);
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
+        "font-feature-settings" => {
+            if let Some(v) = parse_font_feature_settings(value) {
+                style.font_features = Some(v);
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
+        "font-variant-numeric" => {
+            if let Some(v) = parse_font_variant_numeric(value) {
+                style.font_variant_numeric = Some(v);
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
+        "font-variation-settings" => {
+            if let Some(v) = parse_font_variation_settings(value) {
+                style.font_variations = Some(v);
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
         "letter-spacing" => {
             if let Some(px) = parse_length_value(value) {
                 style.letter_spacing = Some(px);
@@ -3972,6 +4507,29 @@ fn apply_property_with_errors(
                 errors.push(ParseError::invalid_value(name, value, line, column));
             }
         }
+        "offset-path" => {
+            if value.trim().eq_ignore_ascii_case("none") {
+                style.offset_path = None;
+            } else if let Some(path) = parse_offset_path(value) {
+                style.offset_path = Some(Arc::new(MotionPath::new(&path)));
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
+        "offset-distance" => {
+            if let Some(distance) = parse_offset_distance(value) {
+                style.offset_distance = Some(distance);
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
+        "offset-rotate" => {
+            if let Some(rotate) = parse_offset_rotate(value) {
+                style.offset_rotate = Some(rotate);
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
         // =====================================================================
         // Layout Properties
         // =====================================================================
@@ -4275,6 +4833,42 @@ fn apply_property_with_errors(
                 errors.push(ParseError::invalid_value(name, value, line, column));
             }
         }
+        "container-type" => {
+            if let Some(ct) = ContainerType::parse(value) {
+                style.container_type = Some(ct);
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
+        "container-name" => {
+            let value = value.trim();
+            if value == "none" {
+                style.container_name = None;
+            } else if !value.is_empty()
+                && value
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+            {
+                style.container_name = Some(value.to_string());
+            } else {
+                errors.push(ParseError::invalid_value(name, value, line, column));
+            }
+        }
+        "container" => {
+            // Shorthand: `container: <name> [/ <type>]`
+            let (container_name, container_type) = match value.split_once('/') {
+                Some((n, t)) => (n.trim(), ContainerType::parse(t)),
+                None => (value.trim(), Some(ContainerType::Normal)),
+            };
+            match container_type {
+                Some(ct) if !container_name.is_empty() => {
+                    style.container_name =
+                        (container_name != "none").then(|| container_name.to_string());
+                    style.container_type = Some(ct);
+                }
+                _ => errors.push(ParseError::invalid_value(name, value, line, column)),
+            }
+        }
         _ => {
             // Unknown property - collect as warning
             errors.push(ParseError::unknown_property(name, line, column));
@@ -4338,16 +4932,9 @@ fn parse_url_value(value: &str) -> Option<String> {
     Some(path.to_string())
 }
 
-/// Parse theme(token-name) for colors
-fn parse_theme_color<'a, E: NomParseError<&'a str>>(input: &'a str) -> IResult<&'a str, Color, E> {
-    let (input, _) = ws(input)?;
-    let (input, _) = tag_no_case("theme")(input)?;
-    let (input, _) = ws(input)?;
-    let (input, token_name) =
-        delimited(char('('), take_while1(|c: char| c != ')'), char(')'))(input)?;
-
-    let token_name = token_name.trim();
-    let token = match token_name.to_lowercase().as_str() {
+/// Map a `theme()` color token name (e.g. `text-primary`) to its token
+pub(crate) fn theme_color_token(name: &str) -> Option<ColorToken> {
+    let token = match name.to_lowercase().as_str() {
         // Brand colors
         "primary" => ColorToken::Primary,
         "primary-hover" => ColorToken::PrimaryHover,
@@ -4381,13 +4968,26 @@ fn parse_theme_color<'a, E: NomParseError<&'a str>>(input: &'a str) -> IResult<&
         "border-hover" => ColorToken::BorderHover,
         "border-focus" => ColorToken::BorderFocus,
         "border-error" => ColorToken::BorderError,
-        _ => {
-            debug!(token = token_name, "Unknown theme color token");
-            return Err(nom::Err::Error(E::from_error_kind(
-                input,
-                nom::error::ErrorKind::Tag,
-            )));
-        }
+        _ => return None,
+    };
+    Some(token)
+}
+
+/// Parse theme(token-name) for colors
+fn parse_theme_color<'a, E: NomParseError<&'a str>>(input: &'a str) -> IResult<&'a str, Color, E> {
+    let (input, _) = ws(input)?;
+    let (input, _) = tag_no_case("theme")(input)?;
+    let (input, _) = ws(input)?;
+    let (input, token_name) =
+        delimited(char('('), take_while1(|c: char| c != ')'), char(')'))(input)?;
+
+    let token_name = token_name.trim();
+    let Some(token) = theme_color_token(token_name) else {
+        debug!(token = token_name, "Unknown theme color token");
+        return Err(nom::Err::Error(E::from_error_kind(
+            input,
+            nom::error::ErrorKind::Tag,
+        )));
     };
 
     Ok((input, ThemeState::get().color(token)))
@@ -4863,34 +5463,312 @@ fn parse_translate_transform<'a, E: NomParseError<&'a str>>(
 /// - `px` - pixels (e.g., "16px")
 /// - `sp` - spacing units, 4px grid (e.g., "4sp" = 16px)
 /// - `%` - percentage (e.g., "50%")
+/// - `em`, `rem` - font-relative units (e.g., "1.5rem")
+/// - `vw`, `vh`, `vmin`, `vmax` - viewport units (e.g., "100vh")
+/// - `calc()`, `min()`, `max()`, `clamp()` - math mixing any of the above
 /// - unitless - treated as pixels for backwards compatibility
 fn parse_css_length(input: &str) -> Option<Length> {
     let input = input.trim();
+    let tokens = tokenize_length_expr(input)?;
+    let mut parser = LengthExprParser { tokens, pos: 0 };
+    let value = parser.factor()?;
+    if parser.pos != parser.tokens.len() {
+        return None;
+    }
+    value.into_length()
+}
+
+/// Token of a length expression (`calc(100% - 2 * 8px)`)
+#[derive(Debug, Clone, PartialEq)]
+enum LengthToken {
+    /// Number with optional unit (`16px`, `50%`, `2`)
+    Dimension(f32, String),
+    /// Math function name followed by `(`
+    Function(String),
+    /// `+`, `-`, `*` or `/`
+    Operator(char),
+    OpenParen,
+    CloseParen,
+    Comma,
+}
+
+fn tokenize_length_expr(input: &str) -> Option<Vec<LengthToken>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        // A sign starts a number only where an operand is expected
+        let expects_operand = matches!(
+            tokens.last(),
+            None | Some(LengthToken::Operator(_))
+                | Some(LengthToken::OpenParen)
+                | Some(LengthToken::Function(_))
+                | Some(LengthToken::Comma)
+        );
+        let starts_number = c.is_ascii_digit()
+            || c == '.'
+            || ((c == '-' || c == '+')
+                && expects_operand
+                && chars
+                    .get(i + 1)
+                    .is_some_and(|n| n.is_ascii_digit() || *n == '.'));
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if starts_number {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            let unit_start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphabetic() || chars[i] == '%') {
+                i += 1;
+            }
+            let unit: String = chars[unit_start..i].iter().collect();
+            tokens.push(LengthToken::Dimension(
+                number.parse().ok()?,
+                unit.to_ascii_lowercase(),
+            ));
+        } else if c.is_ascii_alphabetic() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphabetic() || chars[i] == '-') {
+                i += 1;
+            }
+            let name: String = chars[start..i].iter().collect();
+            if chars.get(i) != Some(&'(') {
+                return None;
+            }
+            i += 1;
+            tokens.push(LengthToken::Function(name.to_ascii_lowercase()));
+        } else {
+            tokens.push(match c {
+                '+' | '-' | '*' | '/' => LengthToken::Operator(c),
+                '(' => LengthToken::OpenParen,
+                ')' => LengthToken::CloseParen,
+                ',' => LengthToken::Comma,
+                _ => return None,
+            });
+            i += 1;
+        }
+    }
 
-    // Try percentage first
-    if let Some(pct_str) = input.strip_suffix('%') {
-        return pct_str.trim().parse::<f32>().ok().map(Length::Pct);
+    Some(tokens)
+}
+
+/// Intermediate value while parsing a length expression
+#[derive(Debug, Clone)]
+enum LengthOperand {
+    /// Unitless number (a multiplier, or pixels at the top level)
+    Number(f32),
+    Length(Length),
+}
+
+impl LengthOperand {
+    fn into_length(self) -> Option<Length> {
+        match self {
+            LengthOperand::Number(n) => Some(Length::Px(n)),
+            LengthOperand::Length(len) => Some(len),
+        }
+    }
+}
+
+/// Recursive-descent parser for CSS math functions over lengths
+struct LengthExprParser {
+    tokens: Vec<LengthToken>,
+    pos: usize,
+}
+
+impl LengthExprParser {
+    fn peek(&self) -> Option<&LengthToken> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<LengthToken> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, token: LengthToken) -> Option<()> {
+        (self.next()? == token).then_some(())
+    }
+
+    /// `sum := product (('+' | '-') product)*`
+    fn sum(&mut self) -> Option<LengthOperand> {
+        let mut left = self.product()?;
+        while let Some(LengthToken::Operator(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            let right = self.product()?;
+            left = match (left, right) {
+                (LengthOperand::Number(a), LengthOperand::Number(b)) => {
+                    LengthOperand::Number(if op == '+' { a + b } else { a - b })
+                }
+                (a, b) => {
+                    let (a, b) = (a.into_length()?, b.into_length()?);
+                    LengthOperand::Length(if op == '+' { a + b } else { a - b })
+                }
+            };
+        }
+        Some(left)
     }
 
-    // Try spacing units (4px grid)
-    if let Some(sp_str) = input.strip_suffix("sp") {
-        return sp_str.trim().parse::<f32>().ok().map(Length::Sp);
+    /// `product := factor (('*' | '/') factor)*`
+    fn product(&mut self) -> Option<LengthOperand> {
+        let mut left = self.factor()?;
+        while let Some(LengthToken::Operator(op @ ('*' | '/'))) = self.peek().cloned() {
+            self.pos += 1;
+            let right = self.factor()?;
+            left = match (left, right, op) {
+                (LengthOperand::Number(a), LengthOperand::Number(b), '*') => {
+                    LengthOperand::Number(a * b)
+                }
+                (LengthOperand::Number(a), LengthOperand::Number(b), _) if b != 0.0 => {
+                    LengthOperand::Number(a / b)
+                }
+                (LengthOperand::Length(len), LengthOperand::Number(n), '*')
+                | (LengthOperand::Number(n), LengthOperand::Length(len), '*') => {
+                    LengthOperand::Length(len * n)
+                }
+                (LengthOperand::Length(len), LengthOperand::Number(n), '/') if n != 0.0 => {
+                    LengthOperand::Length(len / n)
+                }
+                // length * length, x / length and division by zero are invalid
+                _ => return None,
+            };
+        }
+        Some(left)
+    }
+
+    /// `factor := dimension | '(' sum ')' | function '(' args ')'`
+    fn factor(&mut self) -> Option<LengthOperand> {
+        match self.next()? {
+            LengthToken::Dimension(value, unit) => {
+                let len = match unit.as_str() {
+                    "" => return Some(LengthOperand::Number(value)),
+                    "px" => Length::Px(value),
+                    "sp" => Length::Sp(value),
+                    "%" => Length::Pct(value),
+                    "em" => Length::Em(value),
+                    "rem" => Length::Rem(value),
+                    "vw" => Length::Vw(value),
+                    "vh" => Length::Vh(value),
+                    "vmin" => Length::Vmin(value),
+                    "vmax" => Length::Vmax(value),
+                    _ => return None,
+                };
+                Some(LengthOperand::Length(len))
+            }
+            LengthToken::OpenParen => {
+                let value = self.sum()?;
+                self.expect(LengthToken::CloseParen)?;
+                Some(value)
+            }
+            LengthToken::Function(name) => {
+                let mut args = vec![self.sum()?];
+                while self.peek() == Some(&LengthToken::Comma) {
+                    self.pos += 1;
+                    args.push(self.sum()?);
+                }
+                self.expect(LengthToken::CloseParen)?;
+
+                if name == "calc" {
+                    return if args.len() == 1 { args.pop() } else { None };
+                }
+                let mut lengths = args
+                    .into_iter()
+                    .map(LengthOperand::into_length)
+                    .collect::<Option<Vec<_>>>()?;
+                let expr = match name.as_str() {
+                    "min" => CalcExpr::Min(lengths),
+                    "max" => CalcExpr::Max(lengths),
+                    "clamp" if lengths.len() == 3 => {
+                        let max = lengths.pop()?;
+                        let value = lengths.pop()?;
+                        let min = lengths.pop()?;
+                        CalcExpr::Clamp(min, value, max)
+                    }
+                    _ => return None,
+                };
+                Some(LengthOperand::Length(Length::from(expr)))
+            }
+            _ => None,
+        }
     }
+}
 
-    // Try pixels (explicit or implicit)
-    let num_str = input.strip_suffix("px").unwrap_or(input).trim();
-    num_str.parse::<f32>().ok().map(Length::Px)
+/// Record contextual lengths (em, rem, viewport units, math functions)
+///
+/// Returns `true` when the value contained a contextual length and was fully
+/// handled. Plain values clear earlier contextual declarations of the same
+/// property and fall through to the regular property parser.
+fn apply_contextual_length(style: &mut ElementStyle, name: &str, value: &str) -> bool {
+    let slots = LengthSlot::for_css_property(name);
+    if slots.is_empty() {
+        return false;
+    }
+
+    let lengths: Option<Vec<Length>> = split_whitespace_respecting_parens(value)
+        .iter()
+        .map(|part| {
+            if part.eq_ignore_ascii_case("auto") {
+                Some(Length::Auto)
+            } else {
+                parse_css_length(part)
+            }
+        })
+        .collect();
+    let Some(lengths) = lengths.filter(|l| l.iter().any(Length::is_contextual)) else {
+        style
+            .contextual_lengths
+            .retain(|(slot, _)| !slots.contains(slot));
+        return false;
+    };
+
+    // Shorthand expansion: top right bottom left, or row column for gap
+    let expanded = match (slots.len(), lengths.as_slice()) {
+        (1, [a]) => vec![a.clone()],
+        (2, [a]) => vec![a.clone(), a.clone()],
+        (2, [a, b]) => vec![a.clone(), b.clone()],
+        (4, [a]) => vec![a.clone(), a.clone(), a.clone(), a.clone()],
+        (4, [a, b]) => vec![a.clone(), b.clone(), a.clone(), b.clone()],
+        (4, [a, b, c]) => vec![a.clone(), b.clone(), c.clone(), b.clone()],
+        (4, [a, b, c, d]) => vec![a.clone(), b.clone(), c.clone(), d.clone()],
+        _ => return false,
+    };
+    for (&slot, len) in slots.iter().zip(expanded) {
+        style.set_length(slot, len);
+    }
+    true
 }
 
-/// Parse a length value with optional px/sp suffix using nom
+/// Parse a length value with optional unit suffix using nom
 fn parse_length<'a, E: NomParseError<&'a str>>(input: &'a str) -> IResult<&'a str, Length, E> {
     let (input, value) = float(input)?;
-    // Try to match a unit suffix
-    let (input, unit) = opt(alt((tag_no_case("px"), tag_no_case("sp"), tag("%"))))(input)?;
-
-    let length = match unit {
-        Some("sp") | Some("SP") => Length::Sp(value),
+    // Try to match a unit suffix. `em` is not accepted here: nom's float
+    // parser reads the `e` as an exponent marker.
+    let (input, unit) = opt(alt((
+        tag_no_case("px"),
+        tag_no_case("sp"),
+        tag("%"),
+        tag_no_case("rem"),
+        tag_no_case("vmin"),
+        tag_no_case("vmax"),
+        tag_no_case("vw"),
+        tag_no_case("vh"),
+    )))(input)?;
+
+    let length = match unit.map(str::to_ascii_lowercase).as_deref() {
+        Some("sp") => Length::Sp(value),
         Some("%") => Length::Pct(value),
+        Some("rem") => Length::Rem(value),
+        Some("vmin") => Length::Vmin(value),
+        Some("vmax") => Length::Vmax(value),
+        Some("vw") => Length::Vw(value),
+        Some("vh") => Length::Vh(value),
         _ => Length::Px(value), // px or unitless
     };
 
@@ -4935,8 +5813,9 @@ fn parse_render_layer<'a, E: NomParseError<&'a str>>(
 /// - `animation: fade-in 300ms ease-out 100ms`
 /// - `animation: fade-in 300ms ease-out 0ms infinite`
 /// - `animation: slide-in 0.5s ease-in-out 0s 1 normal forwards`
+/// - `animation: pop 600ms cubic-bezier(0.34, 1.56, 0.64, 1) both`
 fn parse_animation(value: &str) -> Option<CssAnimation> {
-    let parts: Vec<&str> = value.split_whitespace().collect();
+    let parts = split_whitespace_respecting_parens(value);
     if parts.is_empty() {
         return None;
     }
@@ -4945,7 +5824,8 @@ fn parse_animation(value: &str) -> Option<CssAnimation> {
     let mut duration_set = false;
     let mut delay_set = false;
 
-    for part in parts {
+    for part in &parts {
+        let part = part.as_str();
         // Try parsing as timing function
         if let Some(timing) = AnimationTiming::from_str(part) {
             anim.timing = timing;
@@ -5021,6 +5901,186 @@ fn parse_animation_fill_mode(input: &str) -> Option<AnimationFillMode> {
     }
 }
 
+/// Parse a timing function beyond the five CSS keywords
+///
+/// Supports:
+/// - `cubic-bezier(x1, y1, x2, y2)`
+/// - `steps(n)`, `steps(n, jump-start | jump-end | jump-none | jump-both | start | end)`,
+///   `step-start`, `step-end`
+/// - `linear(0, 0.25 40%, 1)` (CSS piecewise-linear easing)
+/// - `spring()`, `spring(stiffness, damping[, mass])`, `spring(wobbly)` and other presets
+/// - `ease-{in,out,in-out}-{quad,cubic,quart,sine,expo,circ,back,elastic,bounce}`
+pub fn parse_easing_function(input: &str) -> Option<Easing> {
+    let input = input.trim().to_lowercase();
+
+    let Some((name, args)) = input.split_once('(') else {
+        return named_easing(&input);
+    };
+    let args = args.strip_suffix(')')?;
+    let args = split_gradient_parts(args);
+    let number = |arg: &String| arg.parse::<f32>().ok().filter(|v| v.is_finite());
+
+    match name.trim() {
+        "cubic-bezier" => {
+            let [x1, y1, x2, y2] = args.as_slice() else {
+                return None;
+            };
+            let (x1, y1, x2, y2) = (number(x1)?, number(y1)?, number(x2)?, number(y2)?);
+            ((0.0..=1.0).contains(&x1) && (0.0..=1.0).contains(&x2))
+                .then_some(Easing::CubicBezier(x1, y1, x2, y2))
+        }
+        "steps" => {
+            let (count, position) = match args.as_slice() {
+                [count] => (count, StepPosition::JumpEnd),
+                [count, position] => (
+                    count,
+                    match position.as_str() {
+                        "jump-start" | "start" => StepPosition::JumpStart,
+                        "jump-end" | "end" => StepPosition::JumpEnd,
+                        "jump-none" => StepPosition::JumpNone,
+                        "jump-both" => StepPosition::JumpBoth,
+                        _ => return None,
+                    },
+                ),
+                _ => return None,
+            };
+            let count = count.parse::<u32>().ok()?;
+            let min = if position == StepPosition::JumpNone {
+                2
+            } else {
+                1
+            };
+            (count >= min).then_some(Easing::Steps(count, position))
+        }
+        "linear" => parse_linear_easing(&args),
+        "spring" => {
+            let config = match args.as_slice() {
+                [] => SpringConfig::default(),
+                [preset] if number(preset).is_none() => match preset.as_str() {
+                    "gentle" => SpringConfig::gentle(),
+                    "wobbly" => SpringConfig::wobbly(),
+                    "stiff" => SpringConfig::stiff(),
+                    "snappy" => SpringConfig::snappy(),
+                    "molasses" => SpringConfig::molasses(),
+                    _ => return None,
+                },
+                [stiffness, damping] => {
+                    SpringConfig::new(number(stiffness)?, number(damping)?, 1.0)
+                }
+                [stiffness, damping, mass] => {
+                    SpringConfig::new(number(stiffness)?, number(damping)?, number(mass)?)
+                }
+                _ => return None,
+            };
+            (config.stiffness > 0.0 && config.damping > 0.0 && config.mass > 0.0)
+                .then_some(Easing::Spring(config))
+        }
+        _ => None,
+    }
+}
+
+/// Named easings outside the CSS keywords (`step-start`, `ease-out-back`, ...)
+fn named_easing(name: &str) -> Option<Easing> {
+    Some(match name {
+        "step-start" => Easing::Steps(1, StepPosition::JumpStart),
+        "step-end" => Easing::Steps(1, StepPosition::JumpEnd),
+        "ease-in-quad" => Easing::EaseInQuad,
+        "ease-out-quad" => Easing::EaseOutQuad,
+        "ease-in-out-quad" => Easing::EaseInOutQuad,
+        "ease-in-cubic" => Easing::EaseInCubic,
+        "ease-out-cubic" => Easing::EaseOutCubic,
+        "ease-in-out-cubic" => Easing::EaseInOutCubic,
+        "ease-in-quart" => Easing::EaseInQuart,
+        "ease-out-quart" => Easing::EaseOutQuart,
+        "ease-in-out-quart" => Easing::EaseInOutQuart,
+        "ease-in-sine" => Easing::EaseInSine,
+        "ease-out-sine" => Easing::EaseOutSine,
+        "ease-in-out-sine" => Easing::EaseInOutSine,
+        "ease-in-expo" => Easing::EaseInExpo,
+        "ease-out-expo" => Easing::EaseOutExpo,
+        "ease-in-out-expo" => Easing::EaseInOutExpo,
+        "ease-in-circ" => Easing::EaseInCirc,
+        "ease-out-circ" => Easing::EaseOutCirc,
+        "ease-in-out-circ" => Easing::EaseInOutCirc,
+        "ease-in-back" => Easing::EaseInBack,
+        "ease-out-back" => Easing::EaseOutBack,
+        "ease-in-out-back" => Easing::EaseInOutBack,
+        "ease-in-elastic" => Easing::EaseInElastic,
+        "ease-out-elastic" => Easing::EaseOutElastic,
+        "ease-in-out-elastic" => Easing::EaseInOutElastic,
+        "ease-in-bounce" => Easing::EaseInBounce,
+        "ease-out-bounce" => Easing::EaseOutBounce,
+        "ease-in-out-bounce" => Easing::EaseInOutBounce,
+        _ => return None,
+    })
+}
+
+/// Parse the stops of a CSS `linear()` function
+///
+/// Each stop is an output with up to two input percentages. Missing inputs
+/// are filled in as the spec describes: the first defaults to 0%, the last
+/// to 100%, inputs never decrease, and runs without one are spaced evenly.
+fn parse_linear_easing(args: &[String]) -> Option<Easing> {
+    let mut stops: Vec<(f32, Option<f32>)> = Vec::new();
+    for arg in args {
+        let mut parts = arg.split_whitespace();
+        let output = parts.next()?.parse::<f32>().ok()?;
+        let inputs = parts
+            .map(|part| {
+                let pct = part.strip_suffix('%')?.parse::<f32>().ok()?;
+                Some(pct / 100.0)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        match inputs.as_slice() {
+            [] => stops.push((output, None)),
+            [input] => stops.push((output, Some(*input))),
+            [from, to] => {
+                stops.push((output, Some(*from)));
+                stops.push((output, Some(*to)));
+            }
+            _ => return None,
+        }
+    }
+    if stops.len() < 2 {
+        return None;
+    }
+
+    let last = stops.len() - 1;
+    stops[0].1.get_or_insert(0.0);
+    let mut largest = f32::NEG_INFINITY;
+    for (i, stop) in stops.iter_mut().enumerate() {
+        if let Some(input) = &mut stop.1 {
+            *input = input.max(largest);
+            largest = *input;
+        } else if i == last {
+            stop.1 = Some(largest.max(1.0));
+        }
+    }
+
+    // Space runs of missing inputs evenly between their neighbours
+    let mut i = 1;
+    while i < last {
+        if stops[i].1.is_some() {
+            i += 1;
+            continue;
+        }
+        let end = (i..=last).find(|&j| stops[j].1.is_some())?;
+        let from = stops[i - 1].1?;
+        let to = stops[end].1?;
+        let gaps = (end - i + 1) as f32;
+        for (k, stop) in stops[i..end].iter_mut().enumerate() {
+            stop.1 = Some(from + (to - from) * (k + 1) as f32 / gaps);
+        }
+        i = end;
+    }
+
+    Some(Easing::piecewise(
+        stops
+            .into_iter()
+            .map(|(output, input)| (input.unwrap_or_default(), output)),
+    ))
+}
+
 // ============================================================================
 // Transition Parsing
 // ============================================================================
@@ -5031,6 +6091,7 @@ fn parse_animation_fill_mode(input: &str) -> Option<AnimationFillMode> {
 /// - `transition: all 300ms ease`
 /// - `transition: opacity 200ms ease-in-out`
 /// - `transition: opacity 200ms ease, clip-path 500ms ease-out 100ms`
+/// - `transition: transform 400ms steps(4, jump-end)`
 /// - `transition: none`
 fn parse_transition(value: &str) -> Option<CssTransitionSet> {
     let value = value.trim();
@@ -5039,8 +6100,8 @@ fn parse_transition(value: &str) -> Option<CssTransitionSet> {
     }
 
     let mut transitions = Vec::new();
-    for segment in value.split(',') {
-        if let Some(t) = parse_single_transition(segment.trim()) {
+    for segment in split_gradient_parts(value) {
+        if let Some(t) = parse_single_transition(&segment) {
             transitions.push(t);
         } else {
             return None;
@@ -5056,7 +6117,7 @@ fn parse_transition(value: &str) -> Option<CssTransitionSet> {
 
 /// Parse a single transition: `property duration [timing] [delay]`
 fn parse_single_transition(value: &str) -> Option<CssTransition> {
-    let parts: Vec<&str> = value.split_whitespace().collect();
+    let parts = split_whitespace_respecting_parens(value);
     if parts.is_empty() {
         return None;
     }
@@ -5067,7 +6128,8 @@ fn parse_single_transition(value: &str) -> Option<CssTransition> {
     let mut delay_ms = 0u32;
     let mut duration_set = false;
 
-    for part in parts {
+    for part in &parts {
+        let part = part.as_str();
         // Try as timing function
         if let Some(t) = AnimationTiming::from_str(part) {
             timing = t;
@@ -5583,14 +6645,135 @@ fn parse_text_decoration(value: &str) -> Option<crate::element_style::TextDecora
 fn parse_text_align(value: &str) -> Option<crate::div::TextAlign> {
     use crate::div::TextAlign;
     match value.trim().to_lowercase().as_str() {
-        "left" | "start" => Some(TextAlign::Left),
+        "left" => Some(TextAlign::Left),
         "center" => Some(TextAlign::Center),
-        "right" | "end" => Some(TextAlign::Right),
+        "right" => Some(TextAlign::Right),
+        "start" => Some(TextAlign::Start),
+        "end" => Some(TextAlign::End),
+        "justify" => Some(TextAlign::Justify),
         _ => None,
     }
 }
 
-fn parse_color(input: &str) -> Option<Color> {
+fn parse_white_space(value: &str) -> Option<crate::div::WhiteSpace> {
+    use crate::div::WhiteSpace;
+    match value.trim().to_lowercase().as_str() {
+        "normal" => Some(WhiteSpace::Normal),
+        "nowrap" => Some(WhiteSpace::NoWrap),
+        "pre" => Some(WhiteSpace::Pre),
+        "pre-wrap" => Some(WhiteSpace::PreWrap),
+        "pre-line" => Some(WhiteSpace::PreLine),
+        _ => None,
+    }
+}
+
+fn parse_text_overflow(value: &str) -> Option<crate::div::TextOverflow> {
+    use crate::div::TextOverflow;
+    match value.trim().to_lowercase().as_str() {
+        "clip" => Some(TextOverflow::Clip),
+        "ellipsis" => Some(TextOverflow::Ellipsis),
+        _ => None,
+    }
+}
+
+fn parse_line_clamp(value: &str) -> Option<u32> {
+    value.trim().parse::<u32>().ok().filter(|&n| n > 0)
+}
+
+fn parse_hyphens(value: &str) -> Option<crate::div::Hyphens> {
+    use crate::div::Hyphens;
+    match value.trim().to_lowercase().as_str() {
+        "none" => Some(Hyphens::None),
+        "manual" => Some(Hyphens::Manual),
+        "auto" => Some(Hyphens::Auto),
+        _ => None,
+    }
+}
+
+fn parse_text_justify(value: &str) -> Option<crate::div::TextJustify> {
+    use crate::div::TextJustify;
+    match value.trim().to_lowercase().as_str() {
+        "auto" | "inter-word" => Some(TextJustify::InterWord),
+        "inter-character" | "distribute" => Some(TextJustify::InterCharacter),
+        _ => None,
+    }
+}
+
+/// Split a `"tag" value` entry into its OpenType tag and the remainder
+fn parse_quoted_tag(entry: &str) -> Option<([u8; 4], &str)> {
+    let entry = entry.trim();
+    let quote = entry.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let rest = &entry[1..];
+    let end = rest.find(quote)?;
+    let tag = &rest[..end];
+    if tag.len() != 4 {
+        return None;
+    }
+    Some((blinc_core::font_tag(tag)?, rest[end + 1..].trim()))
+}
+
+/// Parse `font-feature-settings`: `normal` or `"tnum" 1, "liga" off, "smcp"`
+fn parse_font_feature_settings(value: &str) -> Option<Vec<blinc_core::FontFeature>> {
+    if value.trim().eq_ignore_ascii_case("normal") {
+        return Some(Vec::new());
+    }
+    value
+        .split(',')
+        .map(|entry| {
+            let (tag, rest) = parse_quoted_tag(entry)?;
+            let value = match rest.to_lowercase().as_str() {
+                "" | "on" => 1,
+                "off" => 0,
+                n => n.parse::<u32>().ok()?,
+            };
+            Some(blinc_core::FontFeature { tag, value })
+        })
+        .collect()
+}
+
+/// Parse `font-variation-settings`: `normal` or `"wght" 650, "wdth" 80`
+fn parse_font_variation_settings(value: &str) -> Option<Vec<blinc_core::FontVariation>> {
+    if value.trim().eq_ignore_ascii_case("normal") {
+        return Some(Vec::new());
+    }
+    value
+        .split(',')
+        .map(|entry| {
+            let (tag, rest) = parse_quoted_tag(entry)?;
+            let value = rest.parse::<f32>().ok().filter(|v| v.is_finite())?;
+            Some(blinc_core::FontVariation { tag, value })
+        })
+        .collect()
+}
+
+/// Parse `font-variant-numeric` keywords into the equivalent OpenType features
+fn parse_font_variant_numeric(value: &str) -> Option<Vec<blinc_core::FontFeature>> {
+    if value.trim().eq_ignore_ascii_case("normal") {
+        return Some(Vec::new());
+    }
+    value
+        .split_whitespace()
+        .map(|keyword| {
+            let tag = match keyword.to_lowercase().as_str() {
+                "lining-nums" => b"lnum",
+                "oldstyle-nums" => b"onum",
+                "proportional-nums" => b"pnum",
+                "tabular-nums" => b"tnum",
+                "diagonal-fractions" => b"frac",
+                "stacked-fractions" => b"afrc",
+                "ordinal" => b"ordn",
+                "slashed-zero" => b"zero",
+                _ => return None,
+            };
+            Some(blinc_core::FontFeature {
+                tag: *tag,
+                value: 1,
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn parse_color(input: &str) -> Option<Color> {
     let input = input.trim();
 
     // Try hex color
@@ -6457,20 +7640,8 @@ pub fn parse_clip_path(value: &str) -> Option<ClipPath> {
     }
 }
 
-/// Flatten SVG path commands into a list of (x, y) vertices
-///
-/// Supports: M/m, L/l, H/h, V/v, C/c, S/s, Q/q, T/t, A/a (approximated), Z/z
-/// Cubic/quadratic curves are subdivided into line segments for polygon clipping.
-fn flatten_svg_path(d: &str) -> Option<Vec<(f32, f32)>> {
-    let mut vertices = Vec::new();
-    let mut cx = 0.0_f32;
-    let mut cy = 0.0_f32;
-    let mut start_x = 0.0_f32;
-    let mut start_y = 0.0_f32;
-    let mut last_cp_x = 0.0_f32;
-    let mut last_cp_y = 0.0_f32;
-    let mut last_cmd = ' ';
-
+/// Split SVG path data into commands with their numeric arguments
+fn tokenize_svg_path(d: &str) -> Vec<(char, Vec<f32>)> {
     // Tokenize: split on command letters, keeping the letter
     let mut tokens: Vec<(char, Vec<f32>)> = Vec::new();
     let mut current_cmd = ' ';
@@ -6511,6 +7682,24 @@ fn flatten_svg_path(d: &str) -> Option<Vec<(f32, f32)>> {
     if current_cmd != ' ' {
         tokens.push((current_cmd, nums));
     }
+    tokens
+}
+
+/// Flatten SVG path commands into a list of (x, y) vertices
+///
+/// Supports: M/m, L/l, H/h, V/v, C/c, S/s, Q/q, T/t, A/a (approximated), Z/z
+/// Cubic/quadratic curves are subdivided into line segments for polygon clipping.
+fn flatten_svg_path(d: &str) -> Option<Vec<(f32, f32)>> {
+    let mut vertices = Vec::new();
+    let mut cx = 0.0_f32;
+    let mut cy = 0.0_f32;
+    let mut start_x = 0.0_f32;
+    let mut start_y = 0.0_f32;
+    let mut last_cp_x = 0.0_f32;
+    let mut last_cp_y = 0.0_f32;
+    let mut last_cmd = ' ';
+
+    let tokens = tokenize_svg_path(d);
 
     for (cmd, nums) in &tokens {
         let is_rel = cmd.is_ascii_lowercase();
@@ -6690,6 +7879,164 @@ fn flatten_svg_path(d: &str) -> Option<Vec<(f32, f32)>> {
     }
 }
 
+/// Parse SVG path data into a [`blinc_core::Path`]
+///
+/// Supports: M/m, L/l, H/h, V/v, C/c, S/s, Q/q, T/t, A/a, Z/z. Curves and
+/// arcs are kept as curves; extra argument groups repeat the command (an
+/// extra group after a moveto is a lineto).
+pub fn parse_svg_path(d: &str) -> Option<blinc_core::Path> {
+    let mut path = blinc_core::Path::new();
+    let mut cx = 0.0_f32;
+    let mut cy = 0.0_f32;
+    let mut start_x = 0.0_f32;
+    let mut start_y = 0.0_f32;
+    // Last control point and whether it came from a cubic ('C') or quadratic ('Q')
+    let mut last_cp: Option<(char, f32, f32)> = None;
+
+    for (cmd, nums) in tokenize_svg_path(d) {
+        let upper = cmd.to_ascii_uppercase();
+        let arity = match upper {
+            'M' | 'L' | 'T' => 2,
+            'H' | 'V' => 1,
+            'S' | 'Q' => 4,
+            'C' => 6,
+            'A' => 7,
+            'Z' => {
+                path = path.close();
+                cx = start_x;
+                cy = start_y;
+                last_cp = None;
+                continue;
+            }
+            _ => return None,
+        };
+        if nums.is_empty() || nums.len() % arity != 0 {
+            return None;
+        }
+
+        for (group, args) in nums.chunks(arity).enumerate() {
+            let (base_x, base_y) = if cmd.is_ascii_lowercase() {
+                (cx, cy)
+            } else {
+                (0.0, 0.0)
+            };
+            // Reflect the previous control point for smooth curves
+            let reflected = |kind: char| match last_cp {
+                Some((k, x, y)) if k == kind => (2.0 * cx - x, 2.0 * cy - y),
+                _ => (cx, cy),
+            };
+            let mut control = None;
+
+            match upper {
+                'M' if group == 0 => {
+                    cx = base_x + args[0];
+                    cy = base_y + args[1];
+                    start_x = cx;
+                    start_y = cy;
+                    path = path.move_to(cx, cy);
+                }
+                'M' | 'L' => {
+                    cx = base_x + args[0];
+                    cy = base_y + args[1];
+                    path = path.line_to(cx, cy);
+                }
+                'H' => {
+                    cx = base_x + args[0];
+                    path = path.line_to(cx, cy);
+                }
+                'V' => {
+                    cy = base_y + args[0];
+                    path = path.line_to(cx, cy);
+                }
+                'C' | 'S' => {
+                    let (x1, y1, rest) = if upper == 'C' {
+                        (base_x + args[0], base_y + args[1], &args[2..])
+                    } else {
+                        let (x1, y1) = reflected('C');
+                        (x1, y1, args)
+                    };
+                    let (x2, y2) = (base_x + rest[0], base_y + rest[1]);
+                    cx = base_x + rest[2];
+                    cy = base_y + rest[3];
+                    path = path.cubic_to(x1, y1, x2, y2, cx, cy);
+                    control = Some(('C', x2, y2));
+                }
+                'Q' | 'T' => {
+                    let (qx, qy, rest) = if upper == 'Q' {
+                        (base_x + args[0], base_y + args[1], &args[2..])
+                    } else {
+                        let (qx, qy) = reflected('Q');
+                        (qx, qy, args)
+                    };
+                    cx = base_x + rest[0];
+                    cy = base_y + rest[1];
+                    path = path.quad_to(qx, qy, cx, cy);
+                    control = Some(('Q', qx, qy));
+                }
+                _ => {
+                    cx = base_x + args[5];
+                    cy = base_y + args[6];
+                    path = path.arc_to(
+                        blinc_core::Vec2::new(args[0].abs(), args[1].abs()),
+                        args[2].to_radians(),
+                        args[3] != 0.0,
+                        args[4] != 0.0,
+                        cx,
+                        cy,
+                    );
+                }
+            }
+            last_cp = control;
+        }
+    }
+
+    (!path.is_empty()).then_some(path)
+}
+
+/// Parse an `offset-path` value: `path("<svg path data>")`
+fn parse_offset_path(value: &str) -> Option<blinc_core::Path> {
+    let value = value.trim();
+    let inner = value
+        .strip_prefix("path(")
+        .or_else(|| value.strip_prefix("PATH("))?
+        .strip_suffix(')')?
+        .trim();
+    let data = inner
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .or_else(|| inner.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')))?;
+    parse_svg_path(data)
+}
+
+/// Parse an `offset-distance` value as a percentage of the path length
+fn parse_offset_distance(value: &str) -> Option<f32> {
+    let value = value.trim();
+    if value == "0" {
+        return Some(0.0);
+    }
+    value.strip_suffix('%')?.trim().parse::<f32>().ok()
+}
+
+/// Parse an `offset-rotate` value: `auto`, `reverse`, `<angle>`, or
+/// `auto <angle>` / `reverse <angle>` in either order
+fn parse_offset_rotate(value: &str) -> Option<OffsetRotate> {
+    let mut auto = None;
+    let mut angle = None;
+    for part in value.split_whitespace() {
+        match part.to_ascii_lowercase().as_str() {
+            "auto" if auto.is_none() => auto = Some(0.0),
+            "reverse" if auto.is_none() => auto = Some(180.0),
+            other if angle.is_none() => angle = Some(parse_angle_value(other)?),
+            _ => return None,
+        }
+    }
+    match (auto, angle) {
+        (Some(base), angle) => Some(OffsetRotate::Auto(base + angle.unwrap_or(0.0))),
+        (None, Some(angle)) => Some(OffsetRotate::Fixed(angle)),
+        (None, None) => None,
+    }
+}
+
 /// Recursively subdivide a cubic bezier curve into line segments
 #[allow(clippy::too_many_arguments)]
 fn subdivide_cubic(
@@ -8043,6 +9390,65 @@ mod tests {
         assert_eq!(animation.keyframes()[2].properties.opacity, Some(1.0));
     }
 
+    #[test]
+    fn test_offset_path_properties() {
+        use blinc_animation::{Easing, OffsetRotate};
+
+        let css = r#"
+            #rider {
+                offset-path: path("M 0 0 h 100 q 50 0 50 50 A 50 50 0 0 1 100 100 z");
+                offset-distance: 25%;
+                offset-rotate: reverse 10deg;
+            }
+            #fixed { offset-rotate: 45deg; }
+            @keyframes travel {
+                from { offset-distance: 0%; }
+                to { offset-distance: 100%; }
+            }
+        "#;
+        let result = Stylesheet::parse_with_errors(css);
+        assert!(result.errors.is_empty(), "{:?}", result.errors);
+
+        let style = result.stylesheet.get("rider").unwrap();
+        let path = style.offset_path.as_ref().unwrap();
+        assert!(path.is_closed());
+        assert!(path.length() > 250.0);
+        assert_eq!(style.offset_distance, Some(25.0));
+        assert_eq!(style.offset_rotate, Some(OffsetRotate::Auto(190.0)));
+        assert_eq!(
+            result.stylesheet.get("fixed").unwrap().offset_rotate,
+            Some(OffsetRotate::Fixed(45.0))
+        );
+
+        let keyframes = result.stylesheet.get_keyframes("travel").unwrap();
+        let animation = keyframes.to_multi_keyframe_animation(1000, Easing::Linear);
+        assert_eq!(animation.sample_at(0.5).offset_distance, Some(50.0));
+
+        let result = Stylesheet::parse_with_errors("#bad { offset-path: circle(50%); }");
+        assert_eq!(result.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_svg_path() {
+        use blinc_core::PathCommand;
+
+        // Implicit lineto after moveto, relative commands and smooth curves
+        let path = parse_svg_path("m10 10 20 0 v20 c0 10 -10 10 -10 10 s-10 0 -10 -10 Z").unwrap();
+        let commands = path.commands();
+        assert_eq!(commands.len(), 6);
+        assert!(matches!(commands[1], PathCommand::LineTo(p) if p == Point::new(30.0, 10.0)));
+        assert!(matches!(commands[2], PathCommand::LineTo(p) if p == Point::new(30.0, 30.0)));
+        assert!(matches!(
+            commands[4],
+            PathCommand::CubicTo { control1, end, .. }
+                if control1 == Point::new(20.0, 40.0) && end == Point::new(10.0, 30.0)
+        ));
+        assert!(matches!(commands[5], PathCommand::Close));
+
+        assert!(parse_svg_path("M 0 0 L 10").is_none());
+        assert!(parse_svg_path("").is_none());
+    }
+
     #[test]
     fn test_keyframes_with_variables() {
         let css = r#"
@@ -8665,6 +10071,126 @@ mod tests {
         assert_eq!(len.to_px(), 24.0);
     }
 
+    #[test]
+    fn test_parse_css_length_relative_units() {
+        assert_eq!(parse_css_length("1.5rem"), Some(Length::Rem(1.5)));
+        assert_eq!(parse_css_length("2em"), Some(Length::Em(2.0)));
+        assert_eq!(parse_css_length("100vh"), Some(Length::Vh(100.0)));
+        assert_eq!(parse_css_length("50vw"), Some(Length::Vw(50.0)));
+        assert_eq!(parse_css_length("10vmin"), Some(Length::Vmin(10.0)));
+        assert_eq!(parse_css_length("10vmax"), Some(Length::Vmax(10.0)));
+        assert_eq!(parse_css_length("10furlongs"), None);
+    }
+
+    #[test]
+    fn test_parse_css_length_math_functions() {
+        use crate::units::LengthContext;
+
+        let ctx = LengthContext::new(1000.0, 800.0).with_percent_base(500.0);
+        let resolve = |css: &str| parse_css_length(css).and_then(|len| len.resolve(&ctx));
+
+        assert_eq!(resolve("calc(100vh - 64px)"), Some(736.0));
+        assert_eq!(resolve("calc(100% - 2 * 10px)"), Some(480.0));
+        assert_eq!(resolve("calc((100% - 20px) / 2)"), Some(240.0));
+        assert_eq!(resolve("calc(-1 * 1rem)"), Some(-16.0));
+        assert_eq!(resolve("min(50vw, 300px)"), Some(300.0));
+        assert_eq!(resolve("max(10vw, 2rem, 50px)"), Some(100.0));
+        assert_eq!(resolve("clamp(14px, 2vw, 22px)"), Some(20.0));
+        assert_eq!(resolve("calc(min(50%, 200px) + 1em)"), Some(216.0));
+
+        // Invalid expressions
+        assert!(parse_css_length("calc(10px * 2px)").is_none());
+        assert!(parse_css_length("calc(10px / 0)").is_none());
+        assert!(parse_css_length("clamp(1px, 2px)").is_none());
+        assert!(parse_css_length("calc(10px").is_none());
+    }
+
+    #[test]
+    fn test_parse_selectors() {
+        let selectors = parse_selectors("#inbox .row:checked, :is(.a, .b) > span").unwrap();
+        assert_eq!(selectors.len(), 2);
+        assert_eq!(selectors[0].segments.len(), 2);
+        assert_eq!(
+            selectors[0].segments[0].1,
+            Some(Combinator::Descendant),
+            "descendant combinator between #inbox and .row"
+        );
+        assert_eq!(selectors[1].segments[0].1, Some(Combinator::Child));
+
+        let err = parse_selectors("#inbox, ").unwrap_err();
+        assert_eq!(err.severity, Severity::Error);
+        assert!(parse_selectors("#a >").is_err());
+    }
+
+    #[test]
+    fn test_stylesheet_diff() {
+        let old = Stylesheet::parse(
+            ":root { --accent: #ff0000; } \
+             #a { opacity: 0.5; } #a:hover { opacity: 0.8; } .card { opacity: 1.0; }",
+        )
+        .unwrap();
+        assert!(old.diff(&old.clone()).is_empty());
+
+        let new = Stylesheet::parse(
+            ":root { --accent: #00ff00; } \
+             #a { opacity: 0.6; } #b { opacity: 1.0; } .card { opacity: 1.0; }",
+        )
+        .unwrap();
+        let diff = old.diff(&new);
+        assert_eq!(diff.changed, vec!["#a".to_string()]);
+        assert_eq!(diff.added, vec!["#b".to_string()]);
+        assert_eq!(diff.removed, vec!["#a:hover".to_string()]);
+        assert!(diff.variables_changed);
+        assert!(!diff.keyframes_changed);
+        assert!(!diff.conditional_rules_changed);
+
+        let with_media = Stylesheet::parse(
+            "#a { opacity: 0.5; } @media (min-width: 600px) { #a { opacity: 1.0; } }",
+        )
+        .unwrap();
+        let media_changed = Stylesheet::parse(
+            "#a { opacity: 0.5; } @media (min-width: 600px) { #a { opacity: 0.9; } }",
+        )
+        .unwrap();
+        assert!(with_media.diff(&media_changed).conditional_rules_changed);
+    }
+
+    #[test]
+    fn test_contextual_length_properties() {
+        use crate::units::LengthSlot;
+
+        let css = r#"
+            #sidebar { height: calc(100vh - 64px); width: 240px; }
+            #title { font-size: clamp(14px, 2vw, 22px); }
+            #card { padding: 1rem 2vw; width: 50vw; width: 300px; }
+        "#;
+        let result = Stylesheet::parse_with_errors(css);
+        assert!(!result.has_errors(), "{:?}", result.errors);
+
+        let sidebar = result.stylesheet.get("sidebar").unwrap();
+        assert_eq!(sidebar.contextual_lengths.len(), 1);
+        assert_eq!(sidebar.contextual_lengths[0].0, LengthSlot::Height);
+        assert!(sidebar.width.is_some());
+
+        let title = result.stylesheet.get("title").unwrap();
+        assert_eq!(title.contextual_lengths[0].0, LengthSlot::FontSize);
+
+        // Shorthand expands to all four sides; the later plain width wins
+        let card = result.stylesheet.get("card").unwrap();
+        let slots: Vec<LengthSlot> = card.contextual_lengths.iter().map(|(s, _)| *s).collect();
+        assert_eq!(
+            slots,
+            vec![
+                LengthSlot::PaddingTop,
+                LengthSlot::PaddingRight,
+                LengthSlot::PaddingBottom,
+                LengthSlot::PaddingLeft,
+            ]
+        );
+        assert_eq!(card.contextual_lengths[1].1, Length::Vw(2.0));
+        assert!(card.width.is_some());
+    }
+
     #[test]
     fn test_border_radius_with_sp() {
         let css = "#card { border-radius: 2sp; }"; // 2 * 4 = 8px
@@ -8837,6 +10363,146 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_parse_easing_functions() {
+        use blinc_animation::{Easing, SpringConfig, StepPosition};
+
+        assert_eq!(
+            parse_easing_function("cubic-bezier(0.34, 1.56, 0.64, 1)"),
+            Some(Easing::CubicBezier(0.34, 1.56, 0.64, 1.0))
+        );
+        assert_eq!(parse_easing_function("cubic-bezier(1.5, 0, 0, 1)"), None);
+
+        assert_eq!(
+            parse_easing_function("steps(4)"),
+            Some(Easing::Steps(4, StepPosition::JumpEnd))
+        );
+        assert_eq!(
+            parse_easing_function("steps(3, jump-both)"),
+            Some(Easing::Steps(3, StepPosition::JumpBoth))
+        );
+        assert_eq!(
+            parse_easing_function("step-start"),
+            Some(Easing::Steps(1, StepPosition::JumpStart))
+        );
+        assert_eq!(parse_easing_function("steps(1, jump-none)"), None);
+
+        // Missing inputs: first 0%, last 100%, runs spaced evenly, never decreasing
+        assert_eq!(
+            parse_easing_function("linear(0, 0.5, 0.8 75%, 1)"),
+            Some(Easing::piecewise([
+                (0.0, 0.0),
+                (0.375, 0.5),
+                (0.75, 0.8),
+                (1.0, 1.0)
+            ]))
+        );
+        assert_eq!(
+            parse_easing_function("linear(0, 0.5 25% 75%, 1 50%)"),
+            Some(Easing::piecewise([
+                (0.0, 0.0),
+                (0.25, 0.5),
+                (0.75, 0.5),
+                (0.75, 1.0)
+            ]))
+        );
+        assert_eq!(parse_easing_function("linear(0.5)"), None);
+
+        assert_eq!(
+            parse_easing_function("spring(wobbly)"),
+            Some(Easing::Spring(SpringConfig::wobbly()))
+        );
+        assert_eq!(
+            parse_easing_function("spring(300, 20)"),
+            Some(Easing::Spring(SpringConfig::new(300.0, 20.0, 1.0)))
+        );
+        assert_eq!(parse_easing_function("spring(300, 0)"), None);
+
+        assert_eq!(
+            parse_easing_function("ease-out-back"),
+            Some(Easing::EaseOutBack)
+        );
+        assert_eq!(
+            parse_easing_function("ease-in-out-elastic"),
+            Some(Easing::EaseInOutElastic)
+        );
+        assert_eq!(parse_easing_function("ease-sideways"), None);
+    }
+
+    #[test]
+    fn test_timing_functions_in_animation_and_transition() {
+        use blinc_animation::{Easing, KeyframeTrackBuilder, StepPosition};
+
+        let css = r#"
+            #pop { animation: pop 600ms cubic-bezier(0.34, 1.56, 0.64, 1) both; }
+            #tick { animation-timing-function: steps(4, jump-start); }
+            #fade {
+                transition: opacity 200ms linear(0, 0.9 40%, 1), transform 400ms ease-out-bounce;
+            }
+        "#;
+        let result = Stylesheet::parse_with_errors(css);
+        assert!(!result.has_errors());
+
+        let pop = result
+            .stylesheet
+            .get("pop")
+            .unwrap()
+            .animation
+            .clone()
+            .unwrap();
+        assert_eq!(pop.name, "pop");
+        assert_eq!(pop.duration_ms, 600);
+        assert_eq!(pop.fill_mode, AnimationFillMode::Both);
+        assert_eq!(
+            pop.timing.to_easing(),
+            Easing::CubicBezier(0.34, 1.56, 0.64, 1.0)
+        );
+
+        let tick = result
+            .stylesheet
+            .get("tick")
+            .unwrap()
+            .animation
+            .clone()
+            .unwrap();
+        assert_eq!(
+            tick.timing,
+            AnimationTiming::Function(Easing::Steps(4, StepPosition::JumpStart))
+        );
+
+        let fade = result
+            .stylesheet
+            .get("fade")
+            .unwrap()
+            .transition
+            .clone()
+            .unwrap();
+        assert_eq!(fade.transitions.len(), 2);
+        assert_eq!(fade.transitions[0].property, "opacity");
+        assert_eq!(
+            fade.transitions[0].timing.to_easing(),
+            Easing::piecewise([(0.0, 0.0), (0.4, 0.9), (1.0, 1.0)])
+        );
+        assert_eq!(
+            fade.transitions[1].timing.to_easing(),
+            Easing::EaseOutBounce
+        );
+
+        // Parsed timing functions drive keyframe tracks
+        let mut track = KeyframeTrackBuilder::new()
+            .at(0, 0.0)
+            .at(1000, 100.0)
+            .ease(tick.timing.to_easing())
+            .build();
+        track.start();
+        track.tick(100.0);
+        assert_eq!(track.value(), 25.0);
+
+        let invalid =
+            Stylesheet::parse_with_errors("#bad { transition-timing-function: steps(0); }");
+        assert_eq!(invalid.errors.len(), 1);
+    }
+
     #[test]
     fn test_animation_direction_to_play_direction() {
         use blinc_animation::PlayDirection;
@@ -8969,4 +10635,178 @@ mod tests {
         let style = result.stylesheet.get("b").unwrap();
         assert_eq!(style.animation.as_ref().unwrap().iteration_count, 3);
     }
+
+    #[test]
+    fn test_media_rule_resolution() {
+        let css = r#"
+            #sidebar { width: 280px; opacity: 0.9; }
+            @media (max-width: 600px) {
+                #sidebar { width: 100%; }
+                .nav { display: none; }
+            }
+            @media (prefers-color-scheme: dark) {
+                #sidebar { opacity: 0.5; }
+            }
+        "#;
+        let result = Stylesheet::parse_with_errors(css);
+        assert!(!result.has_errors());
+        let sheet = result.stylesheet;
+        assert_eq!(sheet.media_rules().len(), 2);
+        assert!(sheet.has_media_rules());
+
+        let wide = sheet.resolve_media(&MediaContext::new(1024.0, 768.0));
+        let style = wide.get("sidebar").unwrap();
+        assert!(matches!(
+            style.width,
+            Some(crate::element_style::StyleDimension::Length(w)) if w == 280.0
+        ));
+        assert!(wide.complex_rules().is_empty());
+
+        let narrow = sheet.resolve_media(&MediaContext::new(400.0, 768.0));
+        let style = narrow.get("sidebar").unwrap();
+        assert!(matches!(
+            style.width,
+            Some(crate::element_style::StyleDimension::Percent(_))
+        ));
+        // Properties not set in the media block survive the cascade
+        assert_eq!(style.opacity, Some(0.9));
+        assert_eq!(narrow.complex_rules().len(), 1);
+
+        let dark =
+            MediaContext::new(1024.0, 768.0).with_color_scheme(blinc_theme::ColorScheme::Dark);
+        assert_eq!(
+            sheet.resolve_media(&dark).get("sidebar").unwrap().opacity,
+            Some(0.5)
+        );
+        assert_ne!(
+            sheet.media_signature(&dark),
+            sheet.media_signature(&MediaContext::new(1024.0, 768.0))
+        );
+    }
+
+    #[test]
+    fn test_container_rule_parsing() {
+        let css = r#"
+            #sidebar { container: sidebar / inline-size; }
+            @container sidebar (min-width: 300px) {
+                .card { flex-direction: row; }
+            }
+            #after { opacity: 0.5; }
+        "#;
+        let result = Stylesheet::parse_with_errors(css);
+        assert!(!result.has_errors());
+        let sheet = result.stylesheet;
+
+        let style = sheet.get("sidebar").unwrap();
+        assert_eq!(style.container_name.as_deref(), Some("sidebar"));
+        assert_eq!(style.container_type, Some(ContainerType::InlineSize));
+
+        assert_eq!(sheet.container_rules().len(), 1);
+        let rule = &sheet.container_rules()[0];
+        assert_eq!(rule.query.name.as_deref(), Some("sidebar"));
+        assert_eq!(rule.stylesheet.complex_rules().len(), 1);
+
+        // Rules after the block still parse
+        assert_eq!(sheet.get("after").unwrap().opacity, Some(0.5));
+    }
+
+    #[test]
+    fn test_invalid_media_query_is_skipped() {
+        let css = r#"
+            @media (hover: hover) { #a { opacity: 0.5; } }
+            #b { opacity: 0.7; }
+        "#;
+        let result = Stylesheet::parse_with_errors(css);
+        assert!(result.has_warnings());
+        assert!(result.stylesheet.media_rules().is_empty());
+        assert_eq!(result.stylesheet.get("b").unwrap().opacity, Some(0.7));
+    }
+
+    #[test]
+    fn test_paragraph_text_properties() {
+        use crate::div::{Hyphens, TextAlign, TextJustify, TextOverflow, WhiteSpace};
+
+        let css = r#"
+            #title { white-space: nowrap; text-overflow: ellipsis; }
+            #body {
+                text-align: justify;
+                text-justify: inter-character;
+                hyphens: auto;
+                -webkit-line-clamp: 3;
+                white-space: pre-line;
+            }
+        "#;
+        let result = Stylesheet::parse_with_errors(css);
+        assert!(!result.has_errors());
+
+        let title = result.stylesheet.get("title").unwrap();
+        assert_eq!(title.white_space, Some(WhiteSpace::NoWrap));
+        assert_eq!(title.text_overflow, Some(TextOverflow::Ellipsis));
+
+        let body = result.stylesheet.get("body").unwrap();
+        assert_eq!(body.text_align, Some(TextAlign::Justify));
+        assert_eq!(body.text_justify, Some(TextJustify::InterCharacter));
+        assert_eq!(body.hyphens, Some(Hyphens::Auto));
+        assert_eq!(body.line_clamp, Some(3));
+        assert_eq!(body.white_space, Some(WhiteSpace::PreLine));
+
+        let result = Stylesheet::parse_with_errors("#x { line-clamp: zero; }");
+        assert!(result.has_warnings());
+    }
+
+    #[test]
+    fn test_font_feature_and_variation_settings() {
+        use blinc_animation::Easing;
+
+        let css = r#"
+            #price {
+                font-variant-numeric: tabular-nums slashed-zero;
+                font-feature-settings: "liga" off, "ss01", "cv05" 2;
+                font-variation-settings: "wght" 650, 'wdth' 80.5;
+            }
+            #plain { font-feature-settings: normal; }
+            @keyframes embolden {
+                from { font-variation-settings: "wght" 400; }
+                to { font-variation-settings: "wght" 800; }
+            }
+        "#;
+        let result = Stylesheet::parse_with_errors(css);
+        assert!(!result.has_errors());
+        assert!(!result.has_warnings());
+
+        let price = result.stylesheet.get("price").unwrap();
+        let numeric = price.font_variant_numeric.as_ref().unwrap();
+        assert_eq!(numeric[0].tag_str(), "tnum");
+        assert_eq!(numeric[1].tag_str(), "zero");
+        let features = price.font_features.as_ref().unwrap();
+        assert_eq!(
+            features
+                .iter()
+                .map(|f| (f.tag_str(), f.value))
+                .collect::<Vec<_>>(),
+            vec![("liga", 0), ("ss01", 1), ("cv05", 2)]
+        );
+        let axes = price.font_variations.as_ref().unwrap();
+        assert_eq!(axes[0].tag, *b"wght");
+        assert_eq!(axes[0].value, 650.0);
+        assert_eq!(axes[1].value, 80.5);
+
+        let plain = result.stylesheet.get("plain").unwrap();
+        assert_eq!(plain.font_features, Some(Vec::new()));
+
+        // Variation axes interpolate through keyframes
+        let keyframes = result.stylesheet.get_keyframes("embolden").unwrap();
+        let animation = keyframes.to_multi_keyframe_animation(1000, Easing::Linear);
+        let mid = animation.sample_at(0.5);
+        assert_eq!(mid.font_variations, Some(vec![(*b"wght", 600.0)]));
+
+        for bad in [
+            "#x { font-feature-settings: tnum; }",
+            "#x { font-feature-settings: \"toolong\" 1; }",
+            "#x { font-variation-settings: \"wght\" heavy; }",
+            "#x { font-variant-numeric: fancy-nums; }",
+        ] {
+            assert!(Stylesheet::parse_with_errors(bad).has_warnings(), "{bad}");
+        }
+    }
 }
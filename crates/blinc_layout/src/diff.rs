@@ -645,9 +645,18 @@ fn hash_element(element: &dyn ElementBuilder, hasher: &mut impl Hasher) {
         0u8.hash(hasher); // Marker that style is absent
     }
 
+    // Hash contextual lengths (they resolve into the layout style at layout time)
+    for (slot, len) in element.contextual_lengths() {
+        slot.hash(hasher);
+        hash_length(len, hasher);
+    }
+
     // Hash render props (visual properties)
     hash_render_props(&element.render_props(), hasher);
 
+    // Hash the element ID so keyed siblings with identical content keep their own identity
+    element.element_id().hash(hasher);
+
     // Hash type-specific data
     if let Some(text_info) = element.text_render_info() {
         text_info.content.hash(hasher);
@@ -978,6 +987,35 @@ fn hash_option_material(material: &Option<Material>, hasher: &mut impl Hasher) {
     }
 }
 
+fn hash_length(len: &crate::units::Length, hasher: &mut impl Hasher) {
+    use crate::units::{CalcExpr, Length};
+
+    std::mem::discriminant(len).hash(hasher);
+    match len {
+        Length::Px(v)
+        | Length::Sp(v)
+        | Length::Pct(v)
+        | Length::Em(v)
+        | Length::Rem(v)
+        | Length::Vw(v)
+        | Length::Vh(v)
+        | Length::Vmin(v)
+        | Length::Vmax(v) => hash_f32(*v, hasher),
+        Length::Auto => {}
+        Length::Calc(expr) => {
+            std::mem::discriminant(expr.as_ref()).hash(hasher);
+            match expr.as_ref() {
+                CalcExpr::Product(_, n) | CalcExpr::Quotient(_, n) => hash_f32(*n, hasher),
+                CalcExpr::Min(values) | CalcExpr::Max(values) => values.len().hash(hasher),
+                _ => {}
+            }
+            for operand in expr.operands() {
+                hash_length(operand, hasher);
+            }
+        }
+    }
+}
+
 fn hash_style(style: &Style, hasher: &mut impl Hasher) {
     // Display & position
     std::mem::discriminant(&style.display).hash(hasher);
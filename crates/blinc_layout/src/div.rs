@@ -31,6 +31,7 @@ use crate::element::{
 };
 use crate::element_style::ElementStyle;
 use crate::tree::{LayoutNodeId, LayoutTree};
+use crate::units::{Length, LengthSlot};
 
 // ============================================================================
 // ElementRef - Generic reference binding for external access
@@ -405,6 +406,10 @@ pub struct Div {
     pub(crate) blend_3d: Option<f32>,
     /// CSS clip-path shape function
     pub(crate) clip_path: Option<ClipPath>,
+    /// Motion path placement (offset-path, offset-distance, offset-rotate)
+    pub(crate) offset_path: Option<std::sync::Arc<blinc_animation::MotionPath>>,
+    pub(crate) offset_distance: Option<f32>,
+    pub(crate) offset_rotate: Option<blinc_animation::OffsetRotate>,
     /// Fixed positioning (stays in place when ancestors scroll)
     pub(crate) is_fixed: bool,
     /// Sticky positioning (sticks when scrolled past threshold)
@@ -425,11 +430,18 @@ pub struct Div {
     pub(crate) layout_animation: Option<crate::layout_animation::LayoutAnimationConfig>,
     /// Visual animation configuration (new FLIP-style system, read-only layout)
     pub(crate) visual_animation: Option<crate::visual_animation::VisualAnimationConfig>,
+    /// Shared-element identity and transition spring
+    pub(crate) shared_id: Option<String>,
+    pub(crate) shared_spring: Option<blinc_animation::SpringConfig>,
     /// Ancestor stateful context key for automatic key derivation
     ///
     /// When set, motion containers and layout animations will use this key
     /// as a prefix for auto-generated stable keys.
     pub(crate) stateful_context_key: Option<String>,
+    /// Lengths resolved at layout time (em, rem, viewport units, calc)
+    pub(crate) contextual_lengths: Vec<(LengthSlot, Length)>,
+    /// Live checked state for `:checked` selector matching
+    pub(crate) checked_probe: Option<crate::selector::CheckedProbe>,
 }
 
 impl Default for Div {
@@ -474,6 +486,9 @@ impl Div {
             op_3d: None,
             blend_3d: None,
             clip_path: None,
+            offset_path: None,
+            offset_distance: None,
+            offset_rotate: None,
             outline_width: 0.0,
             outline_color: None,
             outline_offset: 0.0,
@@ -484,7 +499,11 @@ impl Div {
             scroll_physics: None,
             layout_animation: None,
             visual_animation: None,
+            shared_id: None,
+            shared_spring: None,
             stateful_context_key: None,
+            contextual_lengths: Vec::new(),
+            checked_probe: None,
         }
     }
 
@@ -526,6 +545,9 @@ impl Div {
             op_3d: None,
             blend_3d: None,
             clip_path: None,
+            offset_path: None,
+            offset_distance: None,
+            offset_rotate: None,
             outline_width: 0.0,
             outline_color: None,
             outline_offset: 0.0,
@@ -536,7 +558,11 @@ impl Div {
             scroll_physics: None,
             layout_animation: None,
             visual_animation: None,
+            shared_id: None,
+            shared_spring: None,
             stateful_context_key: None,
+            contextual_lengths: Vec::new(),
+            checked_probe: None,
         }
     }
 
@@ -575,6 +601,15 @@ impl Div {
         &self.classes
     }
 
+    /// Report a checked state for `:checked` selector matching
+    ///
+    /// The probe is read on each style pass, so toggle widgets built from a
+    /// plain div (switches, radio buttons) match without a rebuild.
+    pub fn checked_when(mut self, probe: impl Fn() -> bool + Send + Sync + 'static) -> Self {
+        self.checked_probe = Some(std::sync::Arc::new(probe));
+        self
+    }
+
     /// Set the stateful context key for automatic key derivation
     ///
     /// This is typically set automatically by `stateful()` callbacks.
@@ -681,6 +716,35 @@ impl Div {
         self
     }
 
+    /// Give this element an identity shared with elements in other layouts
+    ///
+    /// When the element carrying an id unmounts and another element with the
+    /// same id mounts in the same frame (or shortly after), the new element
+    /// animates from the old one's bounds, corner radius and opacity to its
+    /// own, drawn above the rest of the UI while in flight. Ids should be
+    /// unique among mounted elements.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // List item
+    /// div().shared_id(format!("card-{id}")).h(72.0).rounded(12.0)
+    ///
+    /// // Detail view shown instead of the list
+    /// div().shared_id(format!("card-{id}")).h_full().rounded(0.0)
+    /// ```
+    pub fn shared_id(mut self, id: impl Into<String>) -> Self {
+        self.shared_id = Some(id.into());
+        self
+    }
+
+    /// Spring used when this element flies in from a shared element
+    /// (default: snappy)
+    pub fn shared_spring(mut self, config: blinc_animation::SpringConfig) -> Self {
+        self.shared_spring = Some(config);
+        self
+    }
+
     /// Wrap this Div in a Motion container with automatic key derivation
     ///
     /// If this Div is inside a stateful context (has `stateful_context_key` set),
@@ -1165,6 +1229,15 @@ impl Div {
         if let Some(ref cp) = style.clip_path {
             self.clip_path = Some(cp.clone());
         }
+        if let Some(ref path) = style.offset_path {
+            self.offset_path = Some(path.clone());
+        }
+        if let Some(v) = style.offset_distance {
+            self.offset_distance = Some(v);
+        }
+        if let Some(v) = style.offset_rotate {
+            self.offset_rotate = Some(v);
+        }
     }
 
     /// Merge properties from another Div into this one
@@ -1241,12 +1314,28 @@ impl Div {
         if other.visual_animation.is_some() {
             self.visual_animation = other.visual_animation;
         }
+        if other.shared_id.is_some() {
+            self.shared_id = other.shared_id;
+        }
+        if other.shared_spring.is_some() {
+            self.shared_spring = other.shared_spring;
+        }
 
         // Merge layout animation config (deprecated) - take other's if set
         if other.layout_animation.is_some() {
             self.layout_animation = other.layout_animation;
         }
 
+        if other.checked_probe.is_some() {
+            self.checked_probe = other.checked_probe;
+        }
+
+        // Merge contextual lengths slot by slot
+        for (slot, len) in other.contextual_lengths {
+            self.contextual_lengths.retain(|(s, _)| *s != slot);
+            self.contextual_lengths.push((slot, len));
+        }
+
         // Merge 3D properties
         if other.rotate_x.is_some() {
             self.rotate_x = other.rotate_x;
@@ -1287,6 +1376,15 @@ impl Div {
         if other.clip_path.is_some() {
             self.clip_path = other.clip_path;
         }
+        if other.offset_path.is_some() {
+            self.offset_path = other.offset_path;
+        }
+        if other.offset_distance.is_some() {
+            self.offset_distance = other.offset_distance;
+        }
+        if other.offset_rotate.is_some() {
+            self.offset_rotate = other.offset_rotate;
+        }
 
         // Note: event_handlers are NOT merged - they're set on the base element
     }
@@ -2028,30 +2126,109 @@ impl Div {
     /// div().padding(px(16.0))  // 16 raw pixels
     /// div().padding(sp(4.0))   // 4 spacing units = 16px
     /// ```
-    pub fn padding(mut self, len: crate::units::Length) -> Self {
-        let val: LengthPercentage = len.into();
-        self.style.padding = Rect {
-            left: val,
-            right: val,
-            top: val,
-            bottom: val,
-        };
-        self
+    pub fn padding(self, len: crate::units::Length) -> Self {
+        self.lengths(
+            &[
+                LengthSlot::PaddingTop,
+                LengthSlot::PaddingRight,
+                LengthSlot::PaddingBottom,
+                LengthSlot::PaddingLeft,
+            ],
+            len,
+        )
     }
 
     /// Set horizontal padding using a semantic Length value
-    pub fn padding_x(mut self, len: crate::units::Length) -> Self {
-        let val: LengthPercentage = len.into();
-        self.style.padding.left = val;
-        self.style.padding.right = val;
-        self
+    pub fn padding_x(self, len: crate::units::Length) -> Self {
+        self.lengths(&[LengthSlot::PaddingLeft, LengthSlot::PaddingRight], len)
     }
 
     /// Set vertical padding using a semantic Length value
-    pub fn padding_y(mut self, len: crate::units::Length) -> Self {
-        let val: LengthPercentage = len.into();
-        self.style.padding.top = val;
-        self.style.padding.bottom = val;
+    pub fn padding_y(self, len: crate::units::Length) -> Self {
+        self.lengths(&[LengthSlot::PaddingTop, LengthSlot::PaddingBottom], len)
+    }
+
+    /// Set width using a semantic Length value
+    ///
+    /// Relative units and math are resolved at layout time:
+    /// ```rust,ignore
+    /// div().width(vw(50.0))
+    /// div().width(pct(100.0) - px(240.0))   // calc(100% - 240px)
+    /// div().width(rem(20.0).min(pct(90.0))) // min(20rem, 90%)
+    /// ```
+    pub fn width(self, len: crate::units::Length) -> Self {
+        self.lengths(&[LengthSlot::Width], len)
+    }
+
+    /// Set height using a semantic Length value
+    ///
+    /// ```rust,ignore
+    /// div().height(vh(100.0) - px(64.0))    // calc(100vh - 64px)
+    /// ```
+    pub fn height(self, len: crate::units::Length) -> Self {
+        self.lengths(&[LengthSlot::Height], len)
+    }
+
+    /// Set min-width using a semantic Length value
+    pub fn min_width(self, len: crate::units::Length) -> Self {
+        self.lengths(&[LengthSlot::MinWidth], len)
+    }
+
+    /// Set min-height using a semantic Length value
+    pub fn min_height(self, len: crate::units::Length) -> Self {
+        self.lengths(&[LengthSlot::MinHeight], len)
+    }
+
+    /// Set max-width using a semantic Length value
+    pub fn max_width(self, len: crate::units::Length) -> Self {
+        self.lengths(&[LengthSlot::MaxWidth], len)
+    }
+
+    /// Set max-height using a semantic Length value
+    pub fn max_height(self, len: crate::units::Length) -> Self {
+        self.lengths(&[LengthSlot::MaxHeight], len)
+    }
+
+    /// Set margin on all sides using a semantic Length value
+    pub fn margin(self, len: crate::units::Length) -> Self {
+        self.lengths(
+            &[
+                LengthSlot::MarginTop,
+                LengthSlot::MarginRight,
+                LengthSlot::MarginBottom,
+                LengthSlot::MarginLeft,
+            ],
+            len,
+        )
+    }
+
+    /// Set horizontal margin using a semantic Length value
+    pub fn margin_x(self, len: crate::units::Length) -> Self {
+        self.lengths(&[LengthSlot::MarginLeft, LengthSlot::MarginRight], len)
+    }
+
+    /// Set vertical margin using a semantic Length value
+    pub fn margin_y(self, len: crate::units::Length) -> Self {
+        self.lengths(&[LengthSlot::MarginTop, LengthSlot::MarginBottom], len)
+    }
+
+    /// Set gap between children using a semantic Length value
+    pub fn gap_len(self, len: crate::units::Length) -> Self {
+        self.lengths(&[LengthSlot::RowGap, LengthSlot::ColumnGap], len)
+    }
+
+    /// Set a Length on one or more style slots
+    ///
+    /// Contextual lengths are remembered so the render tree can resolve them
+    /// against viewport, font size and parent size before each layout.
+    fn lengths(mut self, slots: &[LengthSlot], len: crate::units::Length) -> Self {
+        for &slot in slots {
+            slot.apply_length(&mut self.style, len.clone());
+            self.contextual_lengths.retain(|(s, _)| *s != slot);
+            if len.is_contextual() {
+                self.contextual_lengths.push((slot, len.clone()));
+            }
+        }
         self
     }
 
@@ -3309,6 +3486,79 @@ pub enum TextAlign {
     Center,
     /// Align text to the right
     Right,
+    /// Align to the start edge of the text direction (left in LTR, right in RTL)
+    Start,
+    /// Align to the end edge of the text direction (right in LTR, left in RTL)
+    End,
+    /// Stretch wrapped lines to fill the width (last line start-aligned)
+    Justify,
+}
+
+/// How justified text distributes extra space
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextJustify {
+    /// Widen the spaces between words (default)
+    #[default]
+    InterWord,
+    /// Spread space between every character
+    InterCharacter,
+}
+
+/// Hyphenation mode (CSS `hyphens`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Hyphens {
+    /// Never hyphenate
+    None,
+    /// Break only at soft hyphens (U+00AD) (default)
+    #[default]
+    Manual,
+    /// Hyphenate automatically using the text's language
+    Auto,
+}
+
+/// White-space handling (CSS `white-space`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WhiteSpace {
+    /// Collapse spaces and newlines, wrap lines
+    Normal,
+    /// Collapse spaces and newlines, never wrap
+    NoWrap,
+    /// Preserve spaces and newlines, never wrap
+    Pre,
+    /// Preserve spaces and newlines, wrap lines (default)
+    #[default]
+    PreWrap,
+    /// Collapse spaces but keep newlines, wrap lines
+    PreLine,
+}
+
+/// How overflowing text is shown (CSS `text-overflow`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextOverflow {
+    /// Clip at the element bounds (default)
+    #[default]
+    Clip,
+    /// End overflowing lines with an ellipsis
+    Ellipsis,
+}
+
+/// Paragraph-level text layout settings
+///
+/// `None` fields fall back to the text element's defaults.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParagraphStyle {
+    /// White-space collapsing and wrapping
+    pub white_space: Option<WhiteSpace>,
+    /// Overflow handling for lines wider than the container
+    pub overflow: Option<TextOverflow>,
+    /// Maximum number of lines; the last line ends in an ellipsis
+    pub line_clamp: Option<u32>,
+    /// Hyphenation mode
+    pub hyphens: Option<Hyphens>,
+    /// Justification mode for `TextAlign::Justify`
+    pub justify: Option<TextJustify>,
+    /// Language tag for hyphenation patterns (e.g. "en-US")
+    pub lang: Option<String>,
 }
 
 /// Font weight options
@@ -3482,6 +3732,10 @@ pub struct TextRenderInfo {
     pub strikethrough: bool,
     /// Whether text has underline decoration
     pub underline: bool,
+    /// Paragraph layout: white-space, ellipsis, clamping, hyphenation
+    pub paragraph: ParagraphStyle,
+    /// OpenType features and variable font axes
+    pub font_settings: blinc_core::FontSettings,
 }
 
 /// A span within styled text (for rich_text element)
@@ -3756,6 +4010,23 @@ pub trait ElementBuilder {
         &[]
     }
 
+    /// Get a live probe for the element's checked state
+    ///
+    /// Toggle widgets (checkboxes, switches) return a probe so `:checked`
+    /// selectors can match them without a rebuild when the value changes.
+    fn checked_probe(&self) -> Option<crate::selector::CheckedProbe> {
+        None
+    }
+
+    /// Get lengths that must be resolved at layout time
+    ///
+    /// Font-relative, viewport and `calc()`-style lengths depend on values
+    /// only known at layout time. The render tree resolves them before each
+    /// layout pass and writes the pixel values into the layout style.
+    fn contextual_lengths(&self) -> &[(LengthSlot, Length)] {
+        &[]
+    }
+
     /// Get the bound ScrollRef for programmatic scroll control
     ///
     /// Only scroll containers return a ScrollRef. This is used by the renderer
@@ -3897,6 +4168,14 @@ impl ElementBuilder for std::sync::Arc<dyn ElementBuilder> {
         self.as_ref().element_classes()
     }
 
+    fn checked_probe(&self) -> Option<crate::selector::CheckedProbe> {
+        self.as_ref().checked_probe()
+    }
+
+    fn contextual_lengths(&self) -> &[(LengthSlot, Length)] {
+        self.as_ref().contextual_lengths()
+    }
+
     fn bound_scroll_ref(&self) -> Option<&crate::selector::ScrollRef> {
         self.as_ref().bound_scroll_ref()
     }
@@ -3965,6 +4244,9 @@ impl ElementBuilder for Div {
             op_3d: self.op_3d,
             blend_3d: self.blend_3d,
             clip_path: self.clip_path.clone(),
+            offset_path: self.offset_path.clone(),
+            offset_distance: self.offset_distance,
+            offset_rotate: self.offset_rotate,
             outline_color: self.outline_color,
             outline_width: self.outline_width,
             outline_offset: self.outline_offset,
@@ -3972,6 +4254,8 @@ impl ElementBuilder for Div {
             is_sticky: self.is_sticky,
             sticky_top: self.sticky_top,
             z_index: self.z_index,
+            shared_id: self.shared_id.clone(),
+            shared_spring: self.shared_spring,
             ..Default::default()
         }
     }
@@ -4000,6 +4284,14 @@ impl ElementBuilder for Div {
         &self.classes
     }
 
+    fn checked_probe(&self) -> Option<crate::selector::CheckedProbe> {
+        self.checked_probe.clone()
+    }
+
+    fn contextual_lengths(&self) -> &[(LengthSlot, Length)] {
+        &self.contextual_lengths
+    }
+
     fn layout_animation_config(&self) -> Option<crate::layout_animation::LayoutAnimationConfig> {
         self.layout_animation.clone()
     }
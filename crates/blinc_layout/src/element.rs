@@ -577,7 +577,7 @@ impl MaterialShadow {
 }
 
 /// Computed layout bounds for an element after layout computation
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct ElementBounds {
     /// X position relative to parent
     pub x: f32,
@@ -988,6 +988,10 @@ pub struct RenderProps {
     /// Used with suspended animations to start the animation after content is mounted
     pub motion_on_ready_callback:
         Option<std::sync::Arc<dyn Fn(ElementBounds) + Send + Sync + 'static>>,
+    /// Identity shared with elements in other layouts for hero transitions
+    pub shared_id: Option<String>,
+    /// Spring for shared-element transitions into this element
+    pub shared_spring: Option<blinc_animation::SpringConfig>,
     /// Whether this is a Stack layer that increments z_layer for proper z-ordering
     /// When true, entering this node increments the DrawContext's z_layer
     pub is_stack_layer: bool,
@@ -1025,6 +1029,20 @@ pub struct RenderProps {
     pub text_align: Option<crate::div::TextAlign>,
     /// Letter spacing override in pixels
     pub letter_spacing: Option<f32>,
+    /// White-space handling override
+    pub white_space: Option<crate::div::WhiteSpace>,
+    /// Text overflow override
+    pub text_overflow: Option<crate::div::TextOverflow>,
+    /// Line clamp override
+    pub line_clamp: Option<u32>,
+    /// Hyphenation mode override
+    pub hyphens: Option<crate::div::Hyphens>,
+    /// Justification mode override
+    pub text_justify: Option<crate::div::TextJustify>,
+    /// OpenType feature override (applied after the element's own features)
+    pub font_features: Option<Vec<blinc_core::FontFeature>>,
+    /// Variable font axis override (per axis, over the element's own axes)
+    pub font_variations: Option<Vec<blinc_core::FontVariation>>,
     /// SVG fill color override
     pub fill: Option<[f32; 4]>,
     /// SVG stroke color override
@@ -1033,6 +1051,12 @@ pub struct RenderProps {
     pub stroke_width: Option<f32>,
     /// Transform origin as percentages [x%, y%] (default 50%, 50% = center)
     pub transform_origin: Option<[f32; 2]>,
+    /// Motion path the element is placed on (CSS `offset-path`)
+    pub offset_path: Option<std::sync::Arc<blinc_animation::MotionPath>>,
+    /// Distance along the offset path in percent (CSS `offset-distance`)
+    pub offset_distance: Option<f32>,
+    /// Rotation along the offset path (CSS `offset-rotate`, default `auto`)
+    pub offset_rotate: Option<blinc_animation::OffsetRotate>,
     /// Layer effects applied to this element (blur, drop shadow, glow, color matrix)
     /// Effects are applied during layer composition when the element is rendered
     pub layer_effects: Vec<LayerEffect>,
@@ -1106,6 +1130,8 @@ impl Default for RenderProps {
             motion_should_replay: false,
             motion_is_suspended: false,
             motion_on_ready_callback: None,
+            shared_id: None,
+            shared_spring: None,
             is_stack_layer: false,
             cursor: None,
             pointer_events_none: false,
@@ -1138,10 +1164,20 @@ impl Default for RenderProps {
             line_height: None,
             text_align: None,
             letter_spacing: None,
+            white_space: None,
+            text_overflow: None,
+            line_clamp: None,
+            hyphens: None,
+            text_justify: None,
+            font_features: None,
+            font_variations: None,
             fill: None,
             stroke: None,
             stroke_width: None,
             transform_origin: None,
+            offset_path: None,
+            offset_distance: None,
+            offset_rotate: None,
             visible: true,
         }
     }
@@ -1214,6 +1250,20 @@ impl RenderProps {
         self
     }
 
+    /// Placement on the offset path, in the element's own coordinates
+    ///
+    /// Puts the transform origin on the path at `offset_distance` and turns
+    /// it per `offset_rotate`. Applies outside the element's transform.
+    pub fn offset_path_transform(&self, width: f32, height: f32) -> Option<blinc_core::Affine2D> {
+        let path = self.offset_path.as_ref()?;
+        let [origin_x, origin_y] = self.transform_origin.unwrap_or([50.0, 50.0]);
+        Some(path.transform(
+            self.offset_distance.unwrap_or(0.0) / 100.0,
+            self.offset_rotate.unwrap_or_default(),
+            blinc_core::Point::new(width * origin_x / 100.0, height * origin_y / 100.0),
+        ))
+    }
+
     /// Set transform
     pub fn with_transform(mut self, transform: Transform) -> Self {
         self.transform = Some(transform);
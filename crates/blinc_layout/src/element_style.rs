@@ -29,6 +29,9 @@
 
 use blinc_core::{Brush, ClipPath, Color, CornerRadius, Shadow, Transform};
 
+use crate::media_query::ContainerType;
+use crate::units::{Length, LengthSlot};
+
 /// CSS filter functions applied to an element
 ///
 /// Each field corresponds to a CSS filter function.
@@ -272,12 +275,34 @@ pub struct ElementStyle {
     pub text_align: Option<crate::div::TextAlign>,
     /// Letter spacing in pixels
     pub letter_spacing: Option<f32>,
+    /// White-space handling (normal, nowrap, pre, pre-wrap, pre-line)
+    pub white_space: Option<crate::div::WhiteSpace>,
+    /// Text overflow handling (clip, ellipsis)
+    pub text_overflow: Option<crate::div::TextOverflow>,
+    /// Maximum number of text lines (last line ends in an ellipsis)
+    pub line_clamp: Option<u32>,
+    /// Hyphenation mode (none, manual, auto)
+    pub hyphens: Option<crate::div::Hyphens>,
+    /// Justification mode for `text-align: justify`
+    pub text_justify: Option<crate::div::TextJustify>,
+    /// OpenType features (`font-feature-settings`)
+    pub font_features: Option<Vec<blinc_core::FontFeature>>,
+    /// Numeric glyph variants (`font-variant-numeric`), as OpenType features
+    pub font_variant_numeric: Option<Vec<blinc_core::FontFeature>>,
+    /// Variable font axis values (`font-variation-settings`)
+    pub font_variations: Option<Vec<blinc_core::FontVariation>>,
     /// Skew X angle in degrees
     pub skew_x: Option<f32>,
     /// Skew Y angle in degrees
     pub skew_y: Option<f32>,
     /// Transform origin as percentages [x%, y%] (default 50%, 50% = center)
     pub transform_origin: Option<[f32; 2]>,
+    /// Motion path to place the element on (CSS `offset-path`)
+    pub offset_path: Option<std::sync::Arc<blinc_animation::MotionPath>>,
+    /// Distance along the offset path in percent (CSS `offset-distance`)
+    pub offset_distance: Option<f32>,
+    /// Rotation along the offset path (CSS `offset-rotate`)
+    pub offset_rotate: Option<blinc_animation::OffsetRotate>,
     /// CSS animation configuration (animation: name duration timing delay iteration-count direction fill-mode)
     pub animation: Option<CssAnimation>,
     /// CSS transition configuration (transition: property duration timing delay)
@@ -423,6 +448,22 @@ pub struct ElementStyle {
     pub z_index: Option<i32>,
     /// CSS visibility (visible or hidden — hidden keeps layout space but doesn't render)
     pub visibility: Option<StyleVisibility>,
+
+    // =========================================================================
+    // Container Queries
+    // =========================================================================
+    /// Container type exposed to `@container` queries (CSS `container-type`)
+    pub container_type: Option<ContainerType>,
+    /// Container name targeted by `@container name (...)` (CSS `container-name`)
+    pub container_name: Option<String>,
+
+    // =========================================================================
+    // Contextual Lengths
+    // =========================================================================
+    /// Lengths resolved at layout time (em, rem, vw, vh, calc, min, max, clamp)
+    ///
+    /// Take precedence over the plain pixel field for the same property.
+    pub contextual_lengths: Vec<(LengthSlot, Length)>,
 }
 
 impl ElementStyle {
@@ -597,6 +638,31 @@ impl ElementStyle {
         self.rotate(degrees * std::f32::consts::PI / 180.0)
     }
 
+    // =========================================================================
+    // Motion Path
+    // =========================================================================
+
+    /// Place the element on a path (CSS `offset-path`)
+    ///
+    /// Path coordinates are relative to the element's layout position, and
+    /// the element's transform origin is what sits on the path.
+    pub fn offset_path(mut self, path: &blinc_core::Path) -> Self {
+        self.offset_path = Some(std::sync::Arc::new(blinc_animation::MotionPath::new(path)));
+        self
+    }
+
+    /// Set the distance along the offset path, in percent of its length
+    pub fn offset_distance(mut self, percent: f32) -> Self {
+        self.offset_distance = Some(percent);
+        self
+    }
+
+    /// Set how the element turns along the offset path
+    pub fn offset_rotate(mut self, rotate: blinc_animation::OffsetRotate) -> Self {
+        self.offset_rotate = Some(rotate);
+        self
+    }
+
     // =========================================================================
     // 3D Transform
     // =========================================================================
@@ -1073,6 +1139,67 @@ impl ElementStyle {
         self
     }
 
+    // =========================================================================
+    // Container Queries
+    // =========================================================================
+
+    /// Declare this element as a named query container
+    ///
+    /// Descendants can then be styled with `@container name (...) { ... }`.
+    pub fn container(mut self, name: impl Into<String>, container_type: ContainerType) -> Self {
+        self.container_name = Some(name.into());
+        self.container_type = Some(container_type);
+        self
+    }
+
+    /// Set the container type without a name
+    pub fn container_type(mut self, container_type: ContainerType) -> Self {
+        self.container_type = Some(container_type);
+        self
+    }
+
+    // =========================================================================
+    // Contextual Lengths
+    // =========================================================================
+
+    /// Set a length resolved at layout time
+    ///
+    /// ```rust,ignore
+    /// ElementStyle::new().length(LengthSlot::Height, vh(100.0) - px(64.0))
+    /// ```
+    pub fn length(mut self, slot: LengthSlot, len: Length) -> Self {
+        self.set_length(slot, len);
+        self
+    }
+
+    /// Set a length resolved at layout time, replacing any previous value
+    pub fn set_length(&mut self, slot: LengthSlot, len: Length) {
+        self.contextual_lengths.retain(|(s, _)| *s != slot);
+        self.contextual_lengths.push((slot, len));
+    }
+
+    /// Check if a plain (non-contextual) field sets the property behind a slot
+    pub fn sets_plain_length(&self, slot: LengthSlot) -> bool {
+        use LengthSlot::*;
+        match slot {
+            Width => self.width.is_some(),
+            Height => self.height.is_some(),
+            MinWidth => self.min_width.is_some(),
+            MinHeight => self.min_height.is_some(),
+            MaxWidth => self.max_width.is_some(),
+            MaxHeight => self.max_height.is_some(),
+            FlexBasis => false,
+            PaddingTop | PaddingRight | PaddingBottom | PaddingLeft => self.padding.is_some(),
+            MarginTop | MarginRight | MarginBottom | MarginLeft => self.margin.is_some(),
+            RowGap | ColumnGap => self.gap.is_some(),
+            Top => self.top.is_some(),
+            Right => self.right.is_some(),
+            Bottom => self.bottom.is_some(),
+            Left => self.left.is_some(),
+            FontSize => self.font_size.is_some(),
+        }
+    }
+
     // =========================================================================
     // Merging
     // =========================================================================
@@ -1099,9 +1226,32 @@ impl ElementStyle {
             line_height: other.line_height.or(self.line_height),
             text_align: other.text_align.or(self.text_align),
             letter_spacing: other.letter_spacing.or(self.letter_spacing),
+            white_space: other.white_space.or(self.white_space),
+            text_overflow: other.text_overflow.or(self.text_overflow),
+            line_clamp: other.line_clamp.or(self.line_clamp),
+            hyphens: other.hyphens.or(self.hyphens),
+            text_justify: other.text_justify.or(self.text_justify),
+            font_features: other
+                .font_features
+                .clone()
+                .or_else(|| self.font_features.clone()),
+            font_variant_numeric: other
+                .font_variant_numeric
+                .clone()
+                .or_else(|| self.font_variant_numeric.clone()),
+            font_variations: other
+                .font_variations
+                .clone()
+                .or_else(|| self.font_variations.clone()),
             skew_x: other.skew_x.or(self.skew_x),
             skew_y: other.skew_y.or(self.skew_y),
             transform_origin: other.transform_origin.or(self.transform_origin),
+            offset_path: other
+                .offset_path
+                .clone()
+                .or_else(|| self.offset_path.clone()),
+            offset_distance: other.offset_distance.or(self.offset_distance),
+            offset_rotate: other.offset_rotate.or(self.offset_rotate),
             animation: other.animation.clone().or_else(|| self.animation.clone()),
             transition: other.transition.clone().or_else(|| self.transition.clone()),
             // 3D
@@ -1165,6 +1315,23 @@ impl ElementStyle {
             left: other.left.or(self.left),
             z_index: other.z_index.or(self.z_index),
             visibility: other.visibility.or(self.visibility),
+            // Container queries
+            container_type: other.container_type.or(self.container_type),
+            container_name: other
+                .container_name
+                .clone()
+                .or_else(|| self.container_name.clone()),
+            // A property set by `other` (plain or contextual) replaces ours
+            contextual_lengths: self
+                .contextual_lengths
+                .iter()
+                .filter(|(slot, _)| {
+                    !other.sets_plain_length(*slot)
+                        && !other.contextual_lengths.iter().any(|(s, _)| s == slot)
+                })
+                .chain(other.contextual_lengths.iter())
+                .cloned()
+                .collect(),
         }
     }
 
@@ -1212,6 +1379,7 @@ impl ElementStyle {
             || self.bottom.is_some()
             || self.left.is_some()
             || self.visibility.is_some()
+            || !self.contextual_lengths.is_empty()
     }
 
     /// Check if no property is set
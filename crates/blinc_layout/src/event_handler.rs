@@ -80,6 +80,10 @@ pub struct EventContext {
     pub pinch_scale: f32,
     /// Character for TEXT_INPUT events
     pub key_char: Option<char>,
+    /// Preedit text for COMPOSITION_UPDATE, final text for COMPOSITION_COMMIT
+    pub composition_text: Option<String>,
+    /// Caret or selection inside the preedit text, in chars
+    pub composition_cursor: Option<(usize, usize)>,
     /// Key code for KEY_DOWN/KEY_UP events (platform-specific)
     pub key_code: u32,
     /// Whether shift modifier is held
@@ -115,6 +119,8 @@ impl EventContext {
             pinch_center_y: 0.0,
             pinch_scale: 1.0,
             key_char: None,
+            composition_text: None,
+            composition_cursor: None,
             key_code: 0,
             shift: false,
             ctrl: false,
@@ -185,6 +191,17 @@ impl EventContext {
         self
     }
 
+    /// Set composition text and preedit cursor (for COMPOSITION_* events)
+    pub fn with_composition(
+        mut self,
+        text: impl Into<String>,
+        cursor: Option<(usize, usize)>,
+    ) -> Self {
+        self.composition_text = Some(text.into());
+        self.composition_cursor = cursor;
+        self
+    }
+
     /// Set key code (for KEY_DOWN/KEY_UP events)
     pub fn with_key_code(mut self, code: u32) -> Self {
         self.key_code = code;
@@ -33,12 +33,16 @@
 //! router.on_mouse_up(&tree, 100.0, 200.0, MouseButton::Left);
 //! ```
 
+use std::cell::RefCell;
 use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::{LazyLock, Mutex};
 
 use blinc_core::events::event_types;
 
 use crate::element::ElementBounds;
 use crate::renderer::RenderTree;
+use crate::selector::ElementEvent;
 use crate::tree::LayoutNodeId;
 
 #[cfg(feature = "recorder")]
@@ -89,6 +93,36 @@ pub struct HitTestResult {
 /// Callback for element events
 pub type EventCallback = Box<dyn FnMut(LayoutNodeId, u32)>;
 
+/// Number of intermediate pointer moves used by synthetic drags
+const SYNTHETIC_DRAG_STEPS: usize = 4;
+
+/// Synthetic events waiting to be routed on the next frame
+#[allow(clippy::incompatible_msrv)]
+static PENDING_SYNTHETIC_EVENTS: LazyLock<Mutex<Vec<(LayoutNodeId, ElementEvent)>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Queue a synthetic event for a node
+///
+/// Called by `ElementHandle::dispatch_event()`. The event loop drains the
+/// queue each frame and routes every entry through
+/// [`EventRouter::dispatch_synthetic`].
+pub fn queue_synthetic_event(node_id: LayoutNodeId, event: ElementEvent) {
+    PENDING_SYNTHETIC_EVENTS
+        .lock()
+        .unwrap()
+        .push((node_id, event));
+    crate::stateful::request_redraw();
+}
+
+/// Take all queued synthetic events (called by the event loop)
+pub fn take_pending_synthetic_events() -> Vec<(LayoutNodeId, ElementEvent)> {
+    std::mem::take(&mut *PENDING_SYNTHETIC_EVENTS.lock().unwrap())
+}
+
+/// Serializes tests that queue and drain the global synthetic event queue
+#[cfg(test)]
+pub(crate) static SYNTHETIC_QUEUE_TEST_LOCK: Mutex<()> = Mutex::new(());
+
 /// Routes platform input events to layout elements
 ///
 /// Maintains state for:
@@ -1564,6 +1598,366 @@ impl EventRouter {
         }
     }
 
+    // =========================================================================
+    // Synthetic Input
+    // =========================================================================
+
+    /// Dispatch a synthetic event to a node
+    ///
+    /// Drives the same `on_mouse_*` / `on_key_*` / focus / scroll entry points
+    /// as platform input, then delivers the resulting events to the tree's
+    /// handlers the way the event loop does. Hover and press tracking, focus,
+    /// bubbling and `Stateful` transitions therefore behave exactly as they do
+    /// for real input. Pointer events are hit tested, so an element covered by
+    /// another one will not receive them.
+    ///
+    /// Any event callback installed on the router is restored afterwards.
+    /// Returns the `(node, event_type)` pairs that were delivered.
+    pub fn dispatch_synthetic(
+        &mut self,
+        tree: &mut RenderTree,
+        node: LayoutNodeId,
+        event: &ElementEvent,
+    ) -> Vec<(LayoutNodeId, u32)> {
+        let Some(bounds) = self.node_screen_bounds(tree, node) else {
+            tracing::debug!(
+                "dispatch_synthetic: {:?} has no layout, dropping {:?}",
+                node,
+                event
+            );
+            return Vec::new();
+        };
+        let center = (
+            bounds.x + bounds.width * 0.5,
+            bounds.y + bounds.height * 0.5,
+        );
+        let local = |x: f32, y: f32| (bounds.x + x, bounds.y + y);
+
+        let sink: Rc<RefCell<Vec<(LayoutNodeId, u32)>>> = Rc::default();
+        let previous_callback = self.event_callback.take();
+        let collector = Rc::clone(&sink);
+        self.set_event_callback(move |node, event_type| {
+            collector.borrow_mut().push((node, event_type));
+        });
+
+        let mut delivered = Vec::new();
+        match *event {
+            ElementEvent::Activate => {
+                delivered.extend(self.synthetic_click(tree, &sink, center, MouseButton::Left));
+            }
+            ElementEvent::Click { x, y } => {
+                delivered.extend(self.synthetic_click(tree, &sink, local(x, y), MouseButton::Left));
+            }
+            ElementEvent::PointerMove { x, y } => {
+                delivered.extend(self.synthetic_move(tree, &sink, local(x, y)));
+            }
+            ElementEvent::PointerDown { x, y, button } => {
+                delivered.extend(self.synthetic_move(tree, &sink, local(x, y)));
+                delivered.extend(self.synthetic_press(tree, &sink, local(x, y), button));
+            }
+            ElementEvent::PointerUp { x, y, button } => {
+                delivered.extend(self.synthetic_release(tree, &sink, local(x, y), button));
+            }
+            ElementEvent::MouseEnter => {
+                delivered.extend(self.synthetic_move(tree, &sink, center));
+            }
+            ElementEvent::MouseLeave => {
+                // Treated as the pointer leaving the window
+                self.on_mouse_leave();
+                delivered.extend(self.deliver_synthetic(tree, &sink, (0.0, 0.0)));
+            }
+            ElementEvent::Focus => {
+                self.synthetic_focus(tree, node);
+                delivered.extend(self.deliver_synthetic(tree, &sink, (0.0, 0.0)));
+            }
+            ElementEvent::Blur => {
+                if self.focused == Some(node) {
+                    self.set_focus(None);
+                }
+                delivered.extend(self.deliver_synthetic(tree, &sink, (0.0, 0.0)));
+            }
+            ElementEvent::KeyDown { key, modifiers } => {
+                delivered.extend(self.synthetic_key_down(tree, &sink, node, key, modifiers, None));
+            }
+            ElementEvent::KeyUp { key, modifiers } => {
+                self.synthetic_focus(tree, node);
+                self.on_key_up_with_modifiers(
+                    key,
+                    modifiers.shift,
+                    modifiers.ctrl,
+                    modifiers.alt,
+                    modifiers.meta,
+                );
+                delivered.extend(self.deliver_synthetic(tree, &sink, (0.0, 0.0)));
+            }
+            ElementEvent::TextInput(ch) => {
+                let modifiers = self.modifiers;
+                delivered.extend(self.synthetic_key_down(
+                    tree,
+                    &sink,
+                    node,
+                    0,
+                    modifiers,
+                    Some(ch),
+                ));
+                self.on_key_up_with_modifiers(
+                    0,
+                    modifiers.shift,
+                    modifiers.ctrl,
+                    modifiers.alt,
+                    modifiers.meta,
+                );
+                delivered.extend(self.deliver_synthetic(tree, &sink, (0.0, 0.0)));
+            }
+            ElementEvent::Scroll { delta_x, delta_y } => {
+                delivered.extend(self.synthetic_move(tree, &sink, center));
+                if let Some(hit) = self.on_scroll_nested(tree, delta_x, delta_y) {
+                    tree.dispatch_scroll_chain(
+                        hit.node,
+                        &hit.ancestors,
+                        center.0,
+                        center.1,
+                        delta_x,
+                        delta_y,
+                    );
+                    delivered.push((hit.node, event_types::SCROLL));
+                }
+            }
+            ElementEvent::DragTo { target } => {
+                if let Some(to) = self.node_screen_bounds(tree, target) {
+                    let end = (to.x + to.width * 0.5, to.y + to.height * 0.5);
+                    delivered.extend(self.synthetic_move(tree, &sink, center));
+                    delivered.extend(self.synthetic_press(tree, &sink, center, MouseButton::Left));
+                    for step in 1..=SYNTHETIC_DRAG_STEPS {
+                        let t = step as f32 / SYNTHETIC_DRAG_STEPS as f32;
+                        let point = (
+                            center.0 + (end.0 - center.0) * t,
+                            center.1 + (end.1 - center.1) * t,
+                        );
+                        delivered.extend(self.synthetic_move(tree, &sink, point));
+                    }
+                    delivered.extend(self.synthetic_release(tree, &sink, end, MouseButton::Left));
+                }
+            }
+            ElementEvent::Custom(event_type) => {
+                tree.dispatch_event_full(
+                    node,
+                    event_type,
+                    center.0,
+                    center.1,
+                    center.0 - bounds.x,
+                    center.1 - bounds.y,
+                    bounds.x,
+                    bounds.y,
+                    bounds.width,
+                    bounds.height,
+                    0.0,
+                    0.0,
+                    1.0,
+                );
+                delivered.push((node, event_type));
+            }
+        }
+
+        self.event_callback = previous_callback;
+        delivered
+    }
+
+    /// Absolute on-screen bounds of a node
+    ///
+    /// Applies ancestor scroll offsets the same way hit testing does, so the
+    /// returned rectangle is where the node is actually drawn.
+    pub fn node_screen_bounds(
+        &self,
+        tree: &RenderTree,
+        node: LayoutNodeId,
+    ) -> Option<ElementBounds> {
+        let path = Self::node_path(tree, node);
+        let mut offset = (0.0, 0.0);
+        let mut cumulative_scroll = (0.0, 0.0);
+
+        for (depth, &current) in path.iter().enumerate() {
+            let is_fixed = depth > 0
+                && tree
+                    .get_render_node(current)
+                    .map(|n| n.props.is_fixed)
+                    .unwrap_or(false);
+            if is_fixed {
+                offset.0 -= cumulative_scroll.0;
+                offset.1 -= cumulative_scroll.1;
+                cumulative_scroll = (0.0, 0.0);
+            }
+
+            let bounds = tree.layout().get_bounds(current, offset)?;
+            if current == node {
+                return Some(bounds);
+            }
+
+            let scroll_offset = tree.get_scroll_offset(current);
+            offset = (bounds.x + scroll_offset.0, bounds.y + scroll_offset.1);
+            cumulative_scroll = if tree.is_scroll_container(current) {
+                scroll_offset
+            } else {
+                (
+                    cumulative_scroll.0 + scroll_offset.0,
+                    cumulative_scroll.1 + scroll_offset.1,
+                )
+            };
+        }
+
+        None
+    }
+
+    /// Chain of nodes from the root down to `node` (inclusive)
+    fn node_path(tree: &RenderTree, node: LayoutNodeId) -> Vec<LayoutNodeId> {
+        let mut path = vec![node];
+        let mut current = node;
+        while let Some(parent) = tree.layout().parent(current) {
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    fn synthetic_click(
+        &mut self,
+        tree: &mut RenderTree,
+        sink: &RefCell<Vec<(LayoutNodeId, u32)>>,
+        point: (f32, f32),
+        button: MouseButton,
+    ) -> Vec<(LayoutNodeId, u32)> {
+        let mut delivered = self.synthetic_move(tree, sink, point);
+        delivered.extend(self.synthetic_press(tree, sink, point, button));
+        delivered.extend(self.synthetic_release(tree, sink, point, button));
+        delivered
+    }
+
+    fn synthetic_move(
+        &mut self,
+        tree: &mut RenderTree,
+        sink: &RefCell<Vec<(LayoutNodeId, u32)>>,
+        (x, y): (f32, f32),
+    ) -> Vec<(LayoutNodeId, u32)> {
+        self.on_mouse_move(tree, x, y);
+        let drag_delta = self.drag_delta();
+        self.deliver_synthetic(tree, sink, drag_delta)
+    }
+
+    fn synthetic_press(
+        &mut self,
+        tree: &mut RenderTree,
+        sink: &RefCell<Vec<(LayoutNodeId, u32)>>,
+        (x, y): (f32, f32),
+        button: MouseButton,
+    ) -> Vec<(LayoutNodeId, u32)> {
+        // Mirrors the event loop: pressing anywhere blurs text inputs, and the
+        // pressed input re-focuses itself from its own handler
+        crate::widgets::blur_all_text_inputs();
+        self.on_mouse_down(tree, x, y, button);
+        self.deliver_synthetic(tree, sink, (0.0, 0.0))
+    }
+
+    fn synthetic_release(
+        &mut self,
+        tree: &mut RenderTree,
+        sink: &RefCell<Vec<(LayoutNodeId, u32)>>,
+        (x, y): (f32, f32),
+        button: MouseButton,
+    ) -> Vec<(LayoutNodeId, u32)> {
+        let drag_delta = self.drag_delta();
+        self.on_mouse_up(tree, x, y, button);
+        self.deliver_synthetic(tree, sink, drag_delta)
+    }
+
+    fn synthetic_focus(&mut self, tree: &RenderTree, node: LayoutNodeId) {
+        if self.focused != Some(node) {
+            let ancestors = Self::node_path(tree, node);
+            self.set_focus_with_ancestors(Some(node), ancestors);
+        }
+    }
+
+    fn synthetic_key_down(
+        &mut self,
+        tree: &mut RenderTree,
+        sink: &RefCell<Vec<(LayoutNodeId, u32)>>,
+        node: LayoutNodeId,
+        key: u32,
+        modifiers: InputModifiers,
+        ch: Option<char>,
+    ) -> Vec<(LayoutNodeId, u32)> {
+        self.synthetic_focus(tree, node);
+        self.on_key_down_with_modifiers(
+            key,
+            modifiers.shift,
+            modifiers.ctrl,
+            modifiers.alt,
+            modifiers.meta,
+        );
+        let delivered = self.deliver_synthetic(tree, sink, (0.0, 0.0));
+
+        // Text and special keys are broadcast, matching the event loop: text
+        // inputs track their own focus and ignore events when unfocused
+        let InputModifiers {
+            shift,
+            ctrl,
+            alt,
+            meta,
+        } = modifiers;
+        if let Some(ch) = ch {
+            if !ctrl && !meta {
+                tree.broadcast_text_input_event(ch, shift, ctrl, alt, meta);
+            }
+        }
+        if key != 0 {
+            tree.broadcast_key_event(event_types::KEY_DOWN, key, shift, ctrl, alt, meta);
+        }
+        delivered
+    }
+
+    /// Deliver events collected by the synthetic callback to the tree
+    fn deliver_synthetic(
+        &mut self,
+        tree: &mut RenderTree,
+        sink: &RefCell<Vec<(LayoutNodeId, u32)>>,
+        drag_delta: (f32, f32),
+    ) -> Vec<(LayoutNodeId, u32)> {
+        let events = std::mem::take(&mut *sink.borrow_mut());
+        for &(node, event_type) in &events {
+            // Scroll is dispatched through the nested scroll chain instead
+            if event_type == event_types::SCROLL {
+                continue;
+            }
+            let (bx, by, bw, bh) = self.get_node_bounds(node).unwrap_or_else(|| {
+                self.node_screen_bounds(tree, node)
+                    .map(|b| (b.x, b.y, b.width, b.height))
+                    .unwrap_or_default()
+            });
+            let (drag_x, drag_y) =
+                if event_type == event_types::DRAG || event_type == event_types::DRAG_END {
+                    drag_delta
+                } else {
+                    (0.0, 0.0)
+                };
+            tree.dispatch_event_full(
+                node,
+                event_type,
+                self.mouse_x,
+                self.mouse_y,
+                self.mouse_x - bx,
+                self.mouse_y - by,
+                bx,
+                by,
+                bw,
+                bh,
+                drag_x,
+                drag_y,
+                1.0,
+            );
+        }
+        events
+    }
+
     /// Emit an event via the callback
     fn emit_event(&mut self, node: LayoutNodeId, event_type: u32) {
         tracing::debug!(
@@ -2025,4 +2419,199 @@ mod tests {
 
         assert_eq!(clicks.lock().unwrap().as_slice(), &[10]);
     }
+
+    #[test]
+    fn test_synthetic_click_bubbles_and_hovers() {
+        use std::sync::{Arc, Mutex};
+
+        let clicks: Arc<Mutex<Vec<&str>>> = Arc::new(Mutex::new(Vec::new()));
+        let (parent_clicks, child_clicks) = (Arc::clone(&clicks), Arc::clone(&clicks));
+
+        let ui = div().w(400.0).h(300.0).child(
+            div()
+                .id("card")
+                .w(200.0)
+                .h(200.0)
+                .on_click(move |_| parent_clicks.lock().unwrap().push("card"))
+                .child(
+                    div()
+                        .id("button")
+                        .w(100.0)
+                        .h(40.0)
+                        .on_click(move |_| child_clicks.lock().unwrap().push("button")),
+                ),
+        );
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.compute_layout(400.0, 300.0);
+        let button = tree.element_registry().get("button").unwrap();
+
+        let mut router = EventRouter::new();
+        let delivered = router.dispatch_synthetic(&mut tree, button, &ElementEvent::Activate);
+
+        assert_eq!(clicks.lock().unwrap().as_slice(), &["button", "card"]);
+        assert!(router.is_hovered(button));
+        assert_eq!(router.focused(), Some(button));
+        assert!(delivered.contains(&(button, event_types::POINTER_ENTER)));
+
+        router.dispatch_synthetic(&mut tree, button, &ElementEvent::MouseLeave);
+        assert!(!router.is_hovered(button));
+    }
+
+    #[test]
+    fn test_synthetic_keyboard_focuses_target() {
+        use std::sync::{Arc, Mutex};
+
+        let typed: Arc<Mutex<String>> = Arc::new(Mutex::new(String::new()));
+        let keys: Arc<Mutex<Vec<(u32, bool)>>> = Arc::new(Mutex::new(Vec::new()));
+        let (typed_clone, keys_clone) = (Arc::clone(&typed), Arc::clone(&keys));
+
+        let ui = div().w(400.0).h(300.0).child(
+            div()
+                .id("field")
+                .w(200.0)
+                .h(30.0)
+                .on_text_input(move |ctx| {
+                    if let Some(ch) = ctx.key_char {
+                        typed_clone.lock().unwrap().push(ch);
+                    }
+                })
+                .on_key_down(move |ctx| {
+                    if ctx.key_code != 0 {
+                        keys_clone.lock().unwrap().push((ctx.key_code, ctx.ctrl));
+                    }
+                }),
+        );
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.compute_layout(400.0, 300.0);
+        let field = tree.element_registry().get("field").unwrap();
+
+        let mut router = EventRouter::new();
+        for ch in "hi".chars() {
+            router.dispatch_synthetic(&mut tree, field, &ElementEvent::TextInput(ch));
+        }
+        let ctrl = InputModifiers {
+            ctrl: true,
+            ..Default::default()
+        };
+        router.dispatch_synthetic(
+            &mut tree,
+            field,
+            &ElementEvent::KeyDown {
+                key: 8,
+                modifiers: ctrl,
+            },
+        );
+
+        assert_eq!(router.focused(), Some(field));
+        assert_eq!(typed.lock().unwrap().as_str(), "hi");
+        assert_eq!(keys.lock().unwrap().as_slice(), &[(8, true)]);
+    }
+
+    #[test]
+    fn test_type_text_into_text_widgets() {
+        use crate::selector::ElementHandle;
+        use std::sync::Arc;
+
+        let _queue = SYNTHETIC_QUEUE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if blinc_theme::ThemeState::try_get().is_none() {
+            blinc_theme::ThemeState::init_default();
+        }
+        let name = text_input_state();
+        let notes = text_area_state();
+        let ui = div()
+            .w(400.0)
+            .h(300.0)
+            .flex_col()
+            .child(text_input(&name).id("name"))
+            .child(text_area(&notes).id("notes"));
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.compute_layout(400.0, 300.0);
+        let mut router = EventRouter::new();
+
+        // Neither widget has been clicked, so typing must focus it first
+        let mut type_into = |id: &str, text: &str| {
+            ElementHandle::<()>::new(id, Arc::clone(tree.element_registry())).type_text(text);
+            for (node, event) in take_pending_synthetic_events() {
+                router.dispatch_synthetic(&mut tree, node, &event);
+            }
+        };
+        type_into("name", "Ada");
+        type_into("notes", "hi");
+
+        assert_eq!(name.lock().unwrap().value, "Ada");
+        assert_eq!(notes.lock().unwrap().value(), "hi");
+        // Focus moved on to the text area
+        assert!(!name.lock().unwrap().visual.is_focused());
+    }
+
+    #[test]
+    fn test_synthetic_drag_to() {
+        use std::sync::{Arc, Mutex};
+
+        let drags: Arc<Mutex<Vec<(f32, f32)>>> = Arc::new(Mutex::new(Vec::new()));
+        let ended = Arc::new(Mutex::new(false));
+        let (drags_clone, ended_clone) = (Arc::clone(&drags), Arc::clone(&ended));
+
+        let ui = div()
+            .w(400.0)
+            .h(300.0)
+            .flex_row()
+            .child(
+                div()
+                    .id("source")
+                    .w(100.0)
+                    .h(100.0)
+                    .on_drag(move |ctx| {
+                        drags_clone
+                            .lock()
+                            .unwrap()
+                            .push((ctx.drag_delta_x, ctx.drag_delta_y))
+                    })
+                    .on_drag_end(move |_| *ended_clone.lock().unwrap() = true),
+            )
+            .child(div().id("target").w(100.0).h(100.0));
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.compute_layout(400.0, 300.0);
+        let source = tree.element_registry().get("source").unwrap();
+        let target = tree.element_registry().get("target").unwrap();
+
+        let mut router = EventRouter::new();
+        router.dispatch_synthetic(&mut tree, source, &ElementEvent::DragTo { target });
+
+        let drags = drags.lock().unwrap();
+        assert!(!drags.is_empty());
+        assert_eq!(*drags.last().unwrap(), (100.0, 0.0));
+        assert!(*ended.lock().unwrap());
+        assert!(!router.is_dragging());
+        assert!(router.is_hovered(target));
+    }
+
+    #[test]
+    fn test_synthetic_dispatch_restores_callback() {
+        let ui = div()
+            .w(400.0)
+            .h(300.0)
+            .child(div().id("a").w(100.0).h(100.0));
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.compute_layout(400.0, 300.0);
+        let a = tree.element_registry().get("a").unwrap();
+
+        let events: Rc<RefCell<Vec<u32>>> = Rc::new(RefCell::new(Vec::new()));
+        let events_clone = Rc::clone(&events);
+        let mut router = EventRouter::new();
+        router.set_event_callback(move |_node, event| events_clone.borrow_mut().push(event));
+
+        router.dispatch_synthetic(&mut tree, a, &ElementEvent::MouseEnter);
+        assert!(events.borrow().is_empty());
+
+        router.on_mouse_down(&tree, 50.0, 50.0, MouseButton::Left);
+        assert!(events.borrow().contains(&event_types::POINTER_DOWN));
+    }
 }
@@ -0,0 +1,683 @@
+//! Stylesheet hot reload
+//!
+//! Watches `.css` files on disk and re-parses them when they change, so style
+//! tweaks show up in a running app without a restart.
+//!
+//! The watcher keeps an ordered list of layers (inline stylesheets and watched
+//! files) and recomposes them into a single [`Stylesheet`] whenever a file
+//! changes. A file with errors keeps contributing its last good version, and
+//! all diagnostics are exposed through [`StylesheetWatcher::diagnostics`] so
+//! they can be shown in-app with [`error_overlay`].
+//!
+//! Theme token files are plain CSS files whose `:root` variables override
+//! theme color tokens by name:
+//!
+//! ```css
+//! :root {
+//!     --primary: #7c3aed;
+//!     --surface: #fafafa;
+//! }
+//! ```
+//!
+//! # Usage
+//!
+//! ```rust,ignore
+//! use blinc_layout::hot_reload::StylesheetWatcher;
+//!
+//! let mut watcher = StylesheetWatcher::new();
+//! watcher.watch_theme_tokens("theme.css");
+//! watcher.watch_stylesheet("app.css");
+//!
+//! // Once per frame:
+//! if let Some(reload) = watcher.poll() {
+//!     if reload.needs_restyle() {
+//!         tree.reload_stylesheet(Arc::new(watcher.stylesheet().clone()), &reload.diff);
+//!     }
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use blinc_core::Color;
+use blinc_theme::{ColorToken, ThemeState};
+
+use crate::css_parser::{
+    parse_color, theme_color_token, ParseError, Severity, Stylesheet, StylesheetDiff,
+};
+use crate::div::{div, Div};
+use crate::text::text;
+
+/// Default interval between file modification checks
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// What a watched file contains
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WatchKind {
+    /// Regular stylesheet rules
+    Stylesheet,
+    /// `:root` variables that override theme color tokens
+    ThemeTokens,
+}
+
+/// A diagnostic attributed to the file it came from
+#[derive(Clone, Debug)]
+pub struct SourceError {
+    /// Path of the watched file
+    pub path: PathBuf,
+    /// The parse (or read) error
+    pub error: ParseError,
+}
+
+impl std::fmt::Display for SourceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+/// Outcome of a reload triggered by a file change
+#[derive(Clone, Debug)]
+pub struct StylesheetReload {
+    /// Files whose contents changed on disk
+    pub changed_files: Vec<PathBuf>,
+    /// Differences between the previous and the new composed stylesheet
+    pub diff: StylesheetDiff,
+    /// Whether the reported diagnostics changed (overlay needs updating)
+    pub diagnostics_changed: bool,
+}
+
+impl StylesheetReload {
+    /// Check if the live tree needs to be restyled
+    pub fn needs_restyle(&self) -> bool {
+        !self.diff.is_empty()
+    }
+}
+
+/// A file on disk contributing to the composed stylesheet
+struct WatchedFile {
+    path: PathBuf,
+    kind: WatchKind,
+    /// Modification time seen on the last check
+    modified: Option<SystemTime>,
+    /// File contents, `None` if the last read failed
+    source: Option<String>,
+    /// Last version that parsed without errors
+    last_good: Option<Stylesheet>,
+    /// Read or parse diagnostics from the last reload
+    diagnostics: Vec<ParseError>,
+}
+
+impl WatchedFile {
+    fn new(path: PathBuf, kind: WatchKind) -> Self {
+        let mut file = Self {
+            path,
+            kind,
+            modified: None,
+            source: None,
+            last_good: None,
+            diagnostics: Vec::new(),
+        };
+        file.modified = file.current_mtime();
+        file.read();
+        file
+    }
+
+    fn current_mtime(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.path)
+            .and_then(|m| m.modified())
+            .ok()
+    }
+
+    fn read(&mut self) {
+        match std::fs::read_to_string(&self.path) {
+            Ok(css) => {
+                self.source = Some(css);
+                self.diagnostics.clear();
+            }
+            Err(e) => {
+                // Editors that save atomically briefly remove the file; keep the
+                // last good version and report until it reappears.
+                self.source = None;
+                self.diagnostics = vec![ParseError::new(
+                    Severity::Error,
+                    format!("Failed to read CSS file '{}': {}", self.path.display(), e),
+                    0,
+                    0,
+                )];
+            }
+        }
+    }
+
+    /// Re-parse the cached source, keeping the last good version on errors
+    fn reparse(&mut self, variables: &HashMap<String, String>) {
+        let Some(source) = &self.source else {
+            return;
+        };
+        let result = Stylesheet::parse_with_errors_and_variables(source, variables);
+        result.log_diagnostics();
+        if result.has_errors() {
+            tracing::warn!(
+                "Keeping previous version of '{}' until parse errors are fixed",
+                self.path.display()
+            );
+        } else {
+            self.last_good = Some(result.stylesheet);
+        }
+        self.diagnostics = result.errors;
+    }
+}
+
+/// A layer of the composed stylesheet, in cascade order
+enum Layer {
+    /// A stylesheet added in code (never reloaded)
+    Inline(Stylesheet),
+    /// A stylesheet or theme token file watched on disk
+    File(WatchedFile),
+}
+
+/// Watches stylesheet and theme token files and recomposes them on change
+///
+/// The watcher is poll-based: call [`poll`](Self::poll) once per frame and it
+/// checks file modification times at most every
+/// [`poll_interval`](Self::with_poll_interval).
+pub struct StylesheetWatcher {
+    layers: Vec<Layer>,
+    composed: Stylesheet,
+    /// Theme color overrides currently applied from token files
+    token_overrides: HashMap<ColorToken, Color>,
+    poll_interval: Duration,
+    last_poll: Option<Instant>,
+}
+
+impl Default for StylesheetWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StylesheetWatcher {
+    /// Create an empty watcher with the default poll interval
+    pub fn new() -> Self {
+        Self {
+            layers: Vec::new(),
+            composed: Stylesheet::new(),
+            token_overrides: HashMap::new(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            last_poll: None,
+        }
+    }
+
+    /// Set how often [`poll`](Self::poll) checks files for changes
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Add a stylesheet that is not backed by a file
+    ///
+    /// Layers cascade in the order they are added, so rules added here
+    /// override earlier watched files and are overridden by later ones.
+    pub fn add_stylesheet(&mut self, sheet: Stylesheet) {
+        match self.layers.last_mut() {
+            Some(Layer::Inline(existing)) => existing.merge(sheet.clone()),
+            _ => self.layers.push(Layer::Inline(sheet.clone())),
+        }
+        self.composed.merge(sheet);
+    }
+
+    /// Watch a `.css` file, loading it immediately
+    pub fn watch_stylesheet(&mut self, path: impl AsRef<Path>) {
+        self.watch(path.as_ref(), WatchKind::Stylesheet);
+    }
+
+    /// Watch a theme token file, loading it immediately
+    ///
+    /// `:root` variables named after theme color tokens (`--primary`,
+    /// `--text-secondary`, ...) are applied as theme color overrides. All of
+    /// the file's variables are also visible to later stylesheets via `var()`.
+    pub fn watch_theme_tokens(&mut self, path: impl AsRef<Path>) {
+        self.watch(path.as_ref(), WatchKind::ThemeTokens);
+    }
+
+    fn watch(&mut self, path: &Path, kind: WatchKind) {
+        let already_watched = self
+            .layers
+            .iter()
+            .any(|layer| matches!(layer, Layer::File(file) if file.path == path));
+        if already_watched {
+            return;
+        }
+        tracing::debug!("Watching {:?} file '{}'", kind, path.display());
+        self.layers
+            .push(Layer::File(WatchedFile::new(path.to_path_buf(), kind)));
+        self.recompose();
+    }
+
+    /// Paths of all watched files
+    pub fn watched_paths(&self) -> impl Iterator<Item = &Path> {
+        self.layers.iter().filter_map(|layer| match layer {
+            Layer::File(file) => Some(file.path.as_path()),
+            Layer::Inline(_) => None,
+        })
+    }
+
+    /// The composed stylesheet of all layers
+    pub fn stylesheet(&self) -> &Stylesheet {
+        &self.composed
+    }
+
+    /// Errors and warnings reported by the last reload of each file
+    pub fn diagnostics(&self) -> Vec<SourceError> {
+        self.layers
+            .iter()
+            .filter_map(|layer| match layer {
+                Layer::File(file) => Some(file),
+                Layer::Inline(_) => None,
+            })
+            .flat_map(|file| {
+                file.diagnostics
+                    .iter()
+                    .filter(|e| e.severity != Severity::Info)
+                    .map(|error| SourceError {
+                        path: file.path.clone(),
+                        error: error.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    /// Check if any watched file currently has errors
+    ///
+    /// Files with errors keep contributing their last good version.
+    pub fn has_errors(&self) -> bool {
+        self.layers.iter().any(|layer| {
+            matches!(layer, Layer::File(file)
+                if file.diagnostics.iter().any(|e| e.severity == Severity::Error))
+        })
+    }
+
+    /// Check for changes if the poll interval has elapsed
+    ///
+    /// Returns `None` when nothing changed on disk.
+    pub fn poll(&mut self) -> Option<StylesheetReload> {
+        let now = Instant::now();
+        if let Some(last) = self.last_poll {
+            if now.duration_since(last) < self.poll_interval {
+                return None;
+            }
+        }
+        self.last_poll = Some(now);
+        self.check()
+    }
+
+    /// Check all watched files for changes right away
+    pub fn check(&mut self) -> Option<StylesheetReload> {
+        let old_diagnostics = self.diagnostic_strings();
+        let mut changed_files = Vec::new();
+        for layer in &mut self.layers {
+            let Layer::File(file) = layer else {
+                continue;
+            };
+            let modified = file.current_mtime();
+            if modified != file.modified {
+                file.modified = modified;
+                file.read();
+                changed_files.push(file.path.clone());
+            }
+        }
+        if changed_files.is_empty() {
+            return None;
+        }
+
+        let old = std::mem::take(&mut self.composed);
+        self.recompose();
+        let diff = old.diff(&self.composed);
+        let diagnostics_changed = self.diagnostic_strings() != old_diagnostics;
+
+        tracing::info!(
+            "Reloaded {} stylesheet file(s): {} added, {} removed, {} changed",
+            changed_files.len(),
+            diff.added.len(),
+            diff.removed.len(),
+            diff.changed.len()
+        );
+
+        Some(StylesheetReload {
+            changed_files,
+            diff,
+            diagnostics_changed,
+        })
+    }
+
+    fn diagnostic_strings(&self) -> Vec<String> {
+        self.diagnostics().iter().map(ToString::to_string).collect()
+    }
+
+    /// Re-parse every file and rebuild the composed stylesheet
+    fn recompose(&mut self) {
+        // Token files go first so stylesheets see the overridden theme colors
+        for layer in &mut self.layers {
+            if let Layer::File(file) = layer {
+                if file.kind == WatchKind::ThemeTokens {
+                    file.reparse(&HashMap::new());
+                }
+            }
+        }
+        self.apply_theme_tokens();
+
+        let mut variables = ThemeState::try_get()
+            .map(|t| t.to_css_variable_map())
+            .unwrap_or_default();
+        let mut composed = Stylesheet::new();
+        for layer in &mut self.layers {
+            let sheet = match layer {
+                Layer::Inline(sheet) => sheet,
+                Layer::File(file) => {
+                    if file.kind == WatchKind::Stylesheet {
+                        file.reparse(&variables);
+                    }
+                    match &file.last_good {
+                        Some(sheet) => sheet,
+                        None => continue,
+                    }
+                }
+            };
+            for (name, value) in sheet.variables() {
+                variables.insert(name.clone(), value.clone());
+            }
+            composed.merge(sheet.clone());
+        }
+        self.composed = composed;
+    }
+
+    /// Sync theme color overrides with the variables of all token files
+    fn apply_theme_tokens(&mut self) {
+        let mut overrides = HashMap::new();
+        for layer in &self.layers {
+            let Layer::File(file) = layer else {
+                continue;
+            };
+            let Some(sheet) = file.last_good.as_ref() else {
+                continue;
+            };
+            if file.kind != WatchKind::ThemeTokens {
+                continue;
+            }
+            for (name, value) in sheet.variables() {
+                match (theme_color_token(name), parse_color(value)) {
+                    (Some(token), Some(color)) => {
+                        overrides.insert(token, color);
+                    }
+                    (Some(_), None) => {
+                        tracing::warn!("Invalid color for theme token --{}: {}", name, value)
+                    }
+                    (None, _) => {}
+                }
+            }
+        }
+
+        if let Some(theme) = ThemeState::try_get() {
+            for token in self.token_overrides.keys() {
+                if !overrides.contains_key(token) {
+                    theme.remove_color_override(*token);
+                }
+            }
+            for (token, color) in &overrides {
+                if self.token_overrides.get(token) != Some(color) {
+                    theme.set_color_override(*token, *color);
+                }
+            }
+        }
+        self.token_overrides = overrides;
+    }
+}
+
+/// Element ID of the layer built by [`diagnostics_layer`]
+pub const DIAGNOSTICS_LAYER_ID: &str = "__blinc_stylesheet_diagnostics__";
+
+/// Build the layer hosting the stylesheet diagnostics overlay
+///
+/// The layer covers its parent, ignores pointer events and is empty when
+/// there is nothing to report. Its stable ID lets the app swap the contents
+/// with a subtree rebuild when diagnostics change, without rebuilding the UI.
+pub fn diagnostics_layer(errors: &[SourceError]) -> Div {
+    let mut layer = div()
+        .id(DIAGNOSTICS_LAYER_ID)
+        .absolute()
+        .left(0.0)
+        .top(0.0)
+        .w_full()
+        .h_full()
+        .stack_layer()
+        .pointer_events_none();
+    if !errors.is_empty() {
+        layer = layer.child(error_overlay(errors));
+    }
+    layer
+}
+
+/// Build an overlay panel listing stylesheet diagnostics
+///
+/// The panel is absolutely positioned along the bottom edge of its parent and
+/// ignores pointer events so the app underneath stays usable.
+pub fn error_overlay(errors: &[SourceError]) -> Div {
+    let mut panel = div()
+        .absolute()
+        .left(12.0)
+        .right(12.0)
+        .bottom(12.0)
+        .flex_col()
+        .gap(1.0)
+        .p(3.0)
+        .rounded(8.0)
+        .bg(Color::rgba(0.16, 0.04, 0.05, 0.94))
+        .border(1.0, Color::rgba(0.94, 0.33, 0.31, 1.0))
+        .pointer_events_none()
+        .z_index(i32::MAX)
+        .child(
+            text(format!(
+                "{} stylesheet problem{}",
+                errors.len(),
+                if errors.len() == 1 { "" } else { "s" }
+            ))
+            .size(14.0)
+            .bold()
+            .color(Color::rgba(1.0, 0.55, 0.52, 1.0)),
+        );
+
+    for source_error in errors {
+        let error = &source_error.error;
+        let location = if error.line > 0 {
+            format!(
+                "{}:{}:{}",
+                source_error.path.display(),
+                error.line,
+                error.column
+            )
+        } else {
+            source_error.path.display().to_string()
+        };
+        panel = panel.child(
+            text(format!(
+                "{} {}  {}",
+                error.severity, location, error.message
+            ))
+            .size(12.0)
+            .monospace()
+            .color(Color::rgba(0.98, 0.9, 0.9, 1.0)),
+        );
+        if !error.fragment.is_empty() {
+            panel = panel.child(
+                text(format!("    near \"{}\"", error.fragment.trim()))
+                    .size(12.0)
+                    .monospace()
+                    .color(Color::rgba(0.75, 0.65, 0.65, 1.0)),
+            );
+        }
+    }
+
+    panel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Per-test scratch directory, removed again when dropped
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(test: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "blinc-hot-reload-{}-{}",
+                std::process::id(),
+                test
+            ));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn css(&self, name: &str, css: &str) -> PathBuf {
+            let path = self.0.join(name);
+            std::fs::write(&path, css).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Rewrite a file and force a distinct modification time
+    fn rewrite(path: &Path, css: &str) {
+        std::fs::write(path, css).unwrap();
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        let bumped = SystemTime::now() + Duration::from_secs(5);
+        file.set_modified(bumped).unwrap();
+    }
+
+    #[test]
+    fn test_reload_reports_diff() {
+        let tmp = TempDir::new("diff");
+        let path = tmp.css(
+            "diff.css",
+            "#card { opacity: 0.5; } #title { opacity: 1.0; }",
+        );
+        let mut watcher = StylesheetWatcher::new();
+        watcher.watch_stylesheet(&path);
+        assert!(watcher.stylesheet().get("card").is_some());
+        assert!(watcher.check().is_none());
+
+        rewrite(&path, "#card { opacity: 0.8; } #footer { opacity: 1.0; }");
+        let reload = watcher.check().expect("file changed");
+        assert!(reload.needs_restyle());
+        assert_eq!(reload.diff.changed, vec!["#card".to_string()]);
+        assert_eq!(reload.diff.added, vec!["#footer".to_string()]);
+        assert_eq!(reload.diff.removed, vec!["#title".to_string()]);
+        assert_eq!(watcher.stylesheet().get("card").unwrap().opacity, Some(0.8));
+    }
+
+    #[test]
+    fn test_errors_keep_last_good_version() {
+        let tmp = TempDir::new("errors");
+        let path = tmp.css("errors.css", "#card { opacity: 0.5; }");
+        let mut watcher = StylesheetWatcher::new();
+        watcher.watch_stylesheet(&path);
+        assert!(watcher.diagnostics().is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+        let reload = watcher.check().expect("file removed");
+        assert!(reload.diagnostics_changed);
+        assert!(!reload.needs_restyle());
+        assert!(watcher.has_errors());
+        assert_eq!(watcher.stylesheet().get("card").unwrap().opacity, Some(0.5));
+
+        rewrite(&path, "#card { opacity: 0.9; }");
+        let reload = watcher.check().expect("file restored");
+        assert!(reload.diagnostics_changed);
+        assert!(!watcher.has_errors());
+        assert_eq!(watcher.stylesheet().get("card").unwrap().opacity, Some(0.9));
+    }
+
+    #[test]
+    fn test_warnings_are_reported() {
+        let tmp = TempDir::new("warnings");
+        let path = tmp.css("warnings.css", "#card { opacity: 0.5; }");
+        let mut watcher = StylesheetWatcher::new();
+        watcher.watch_stylesheet(&path);
+
+        rewrite(&path, "#card { opacity: 0.5; colour: red; }");
+        let reload = watcher.check().expect("file changed");
+        assert!(reload.diagnostics_changed);
+        assert!(!watcher.has_errors());
+        let diagnostics = watcher.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].path, path);
+        assert_eq!(diagnostics[0].error.severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_layers_cascade_in_order() {
+        let tmp = TempDir::new("cascade");
+        let path = tmp.css("cascade.css", "#card { opacity: 0.5; }");
+        let mut watcher = StylesheetWatcher::new();
+        watcher.add_stylesheet(Stylesheet::parse("#card { opacity: 0.1; }").unwrap());
+        watcher.watch_stylesheet(&path);
+        watcher.add_stylesheet(Stylesheet::parse("#other { opacity: 0.2; }").unwrap());
+        assert_eq!(watcher.stylesheet().get("card").unwrap().opacity, Some(0.5));
+
+        rewrite(&path, "#title { opacity: 0.7; }");
+        watcher.check().expect("file changed");
+        // Inline rule shows through once the file stops overriding it
+        assert_eq!(watcher.stylesheet().get("card").unwrap().opacity, Some(0.1));
+        assert!(watcher.stylesheet().get("other").is_some());
+    }
+
+    #[test]
+    fn test_theme_token_variables_visible_to_stylesheets() {
+        let tmp = TempDir::new("tokens");
+        let tokens = tmp.css("tokens.css", ":root { --card-opacity: 0.25; }");
+        let sheet = tmp.css("themed.css", "#card { opacity: var(--card-opacity); }");
+        let mut watcher = StylesheetWatcher::new();
+        watcher.watch_theme_tokens(&tokens);
+        watcher.watch_stylesheet(&sheet);
+        assert_eq!(
+            watcher.stylesheet().get("card").unwrap().opacity,
+            Some(0.25)
+        );
+
+        rewrite(&tokens, ":root { --card-opacity: 0.75; }");
+        let reload = watcher.check().expect("file changed");
+        assert!(reload.diff.variables_changed);
+        assert_eq!(
+            watcher.stylesheet().get("card").unwrap().opacity,
+            Some(0.75)
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_layer_keeps_stable_id() {
+        use crate::div::ElementBuilder;
+
+        let empty = diagnostics_layer(&[]);
+        assert_eq!(empty.element_id(), Some(DIAGNOSTICS_LAYER_ID));
+        assert!(empty.children_builders().is_empty());
+
+        let errors: Vec<SourceError> =
+            Stylesheet::parse_with_errors("#card { opacity: 0.5; colour: red; }")
+                .errors
+                .into_iter()
+                .map(|error| SourceError {
+                    path: PathBuf::from("card.css"),
+                    error,
+                })
+                .collect();
+        assert!(!errors.is_empty());
+        let layer = diagnostics_layer(&errors);
+        assert_eq!(layer.element_id(), Some(DIAGNOSTICS_LAYER_ID));
+        assert_eq!(layer.children_builders().len(), 1);
+    }
+}
@@ -52,6 +52,8 @@ pub mod renderer;
 #[allow(dead_code)]
 pub mod rich_text;
 pub mod scroll;
+pub mod scroll_timeline;
+pub mod shared_element;
 pub mod stack;
 #[allow(dead_code, deprecated)]
 pub mod stateful;
@@ -60,6 +62,7 @@ pub mod styled_text;
 pub mod svg;
 pub mod syntax;
 pub mod text;
+pub mod text_buffer;
 pub mod text_measure;
 pub mod text_selection;
 pub mod tree;
@@ -73,6 +76,12 @@ pub mod widgets;
 #[allow(dead_code)]
 pub mod markdown;
 
+// Math typesetting
+pub mod math;
+
+// Lottie animation playback
+pub mod lottie;
+
 // Selector API for programmatic element access
 #[allow(dead_code)]
 pub mod selector;
@@ -88,6 +97,12 @@ pub mod recorder_bridge;
 #[allow(dead_code)]
 pub mod css_parser;
 
+// @media and @container query evaluation
+pub mod media_query;
+
+// Stylesheet and theme token hot reload
+pub mod hot_reload;
+
 // Stable unique key generation for components
 pub mod key;
 
@@ -106,7 +121,10 @@ pub use diff::{
     ReconcileActions,
 };
 pub use event_handler::{EventCallback, EventContext, EventHandlers, HandlerRegistry};
-pub use event_router::{EventRouter, HitTestResult, MouseButton};
+pub use event_router::{
+    queue_synthetic_event, take_pending_synthetic_events, EventRouter, HitTestResult,
+    InputModifiers, MouseButton,
+};
 pub use interactive::{DirtyTracker, InteractiveContext, NodeState};
 pub use style::LayoutStyle;
 pub use tree::{LayoutNodeId, LayoutTree, TextMeasureContext};
@@ -118,8 +136,9 @@ pub use element::{
 
 // Builder API
 pub use div::{
-    div, Div, ElementBuilder, ElementTypeId, FontFamily, FontWeight, GenericFont, ImageRenderInfo,
-    StyledTextRenderInfo, StyledTextSpanInfo, TextAlign, TextVerticalAlign,
+    div, Div, ElementBuilder, ElementTypeId, FontFamily, FontWeight, GenericFont, Hyphens,
+    ImageRenderInfo, ParagraphStyle, StyledTextRenderInfo, StyledTextSpanInfo, TextAlign,
+    TextJustify, TextOverflow, TextVerticalAlign, WhiteSpace,
 };
 // Stack container (overlayed children)
 pub use stack::{stack, Stack};
@@ -169,7 +188,7 @@ pub use animated::{AnimatedProperties, AnimationBuilder};
 pub use layout_animation::{LayoutAnimation, LayoutAnimationConfig, LayoutAnimationState};
 
 // CSS-like units
-pub use units::{pct, px, sp, Length, Unit};
+pub use units::{em, pct, px, rem, sp, vh, vmax, vmin, vw, CalcExpr, Length, LengthSlot, Unit};
 
 // Motion container for entry/exit animations
 pub use motion::{
@@ -180,6 +199,9 @@ pub use motion::{
     SharedAnimatedValue, SlideDirection, StaggerConfig, StaggerDirection,
 };
 
+// Rope text buffer
+pub use text_buffer::TextBuffer;
+
 // Text measurement
 pub use text_measure::{
     measure_text, measure_text_with_options, set_text_measurer, TextLayoutOptions, TextMeasurer,
@@ -188,8 +210,8 @@ pub use text_measure::{
 
 // Text selection (clipboard support)
 pub use text_selection::{
-    clear_selection, get_selected_text, global_selection, set_selection, SelectionSource,
-    SharedTextSelection, TextSelection,
+    clear_selection, get_selected_text, global_selection, selection_spans, set_selection,
+    BidiCaretMap, SelectionSource, SharedTextSelection, TextSelection,
 };
 
 /// Prelude module - import everything commonly needed
@@ -197,8 +219,8 @@ pub mod prelude {
     #![allow(deprecated)]
 
     pub use crate::div::{
-        div, Div, ElementBuilder, ElementTypeId, FontFamily, FontWeight, GenericFont,
-        ImageRenderInfo, TextAlign, TextVerticalAlign,
+        div, Div, ElementBuilder, ElementTypeId, FontFamily, FontWeight, GenericFont, Hyphens,
+        ImageRenderInfo, TextAlign, TextJustify, TextOverflow, TextVerticalAlign, WhiteSpace,
     };
     // Stack container (overlayed children)
     pub use crate::stack::{stack, Stack};
@@ -210,7 +232,7 @@ pub mod prelude {
     // Event handlers
     pub use crate::event_handler::{EventCallback, EventContext, EventHandlers, HandlerRegistry};
     // Event routing
-    pub use crate::event_router::{EventRouter, HitTestResult, MouseButton};
+    pub use crate::event_router::{EventRouter, HitTestResult, InputModifiers, MouseButton};
     // Image element
     pub use crate::image::{
         emoji, emoji_sized, image, img, Image, ImageFilter, LoadingStrategy, ObjectFit,
@@ -335,15 +357,23 @@ pub mod prelude {
     };
 
     // Code block widget with syntax highlighting
-    pub use crate::widgets::{code, pre, Code, CodeConfig};
+    pub use crate::widgets::{
+        code, code_editor, code_state, pre, Code, CodeConfig, Diagnostic, FoldMode, SearchQuery,
+    };
+
+    // Rich text editor
+    pub use crate::widgets::{
+        rich_editor, rich_editor_state, rich_editor_state_from_markdown, BlockType, Mark,
+        RichDocument, RichEditor, RichEditorConfig, RichEditorState, SharedRichEditorState,
+    };
 
     // CSS-like units for layout dimensions
-    pub use crate::units::{pct, px, sp, Length, Unit};
+    pub use crate::units::{em, pct, px, rem, sp, vh, vmax, vmin, vw, Length, Unit};
 
     // Syntax highlighting
     pub use crate::syntax::{
-        JsonHighlighter, PlainHighlighter, RustHighlighter, SyntaxConfig, SyntaxHighlighter,
-        TokenHit, TokenRule, TokenType,
+        GrammarHighlighter, JsonHighlighter, PlainHighlighter, RustHighlighter, SyntaxConfig,
+        SyntaxHighlighter, SyntaxTheme, TokenHit, TokenRule, TokenType,
     };
 
     // Canvas element
@@ -364,7 +394,8 @@ pub mod prelude {
     // Re-export animation types from blinc_animation for convenience
     pub use blinc_animation::{
         AnimatedKeyframe, AnimatedTimeline, AnimatedValue, AnimationPreset, Easing,
-        KeyframeProperties, MultiKeyframeAnimation, SchedulerHandle, SpringConfig,
+        KeyframeProperties, MotionPath, MultiKeyframeAnimation, OffsetRotate, PathMorph,
+        SchedulerHandle, SpringConfig,
     };
 
     // Motion container for entry/exit animations
@@ -406,9 +437,14 @@ pub mod prelude {
         OverlayManager, OverlayManagerExt, OverlayPosition, OverlayState, ToastBuilder,
     };
 
+    // Math typesetting
+    pub use crate::lottie::{lottie, Lottie, LottieAnimation, LottieError, LottiePlayer};
+    pub use crate::math::{display_math, math, Math, MathError};
+
     // Markdown rendering
     pub use crate::markdown::{
         markdown, markdown_light, markdown_with_config, MarkdownConfig, MarkdownRenderer,
+        TableOfContents, TocEntry,
     };
 
     // Additional markdown widgets
@@ -424,17 +460,26 @@ pub mod prelude {
         ScrollBehavior, ScrollBlock, ScrollInline, ScrollOptions, ScrollRef, SharedElementRegistry,
     };
 
+    // Scroll-linked timelines
+    pub use crate::scroll_timeline::{ScrollTimeline, ViewRange};
+
     // Overlay context singleton
     pub use crate::overlay_state::{get_overlay_manager, OverlayContext};
 
     // CSS parser for loading stylesheets
     pub use crate::css_parser::{
         AnimationDirection, AnimationFillMode, AnimationTiming, Combinator, ComplexSelector,
-        CompoundSelector, CssAnimation, CssKeyframe, CssKeyframes, CssParseResult, CssSelector,
-        ElementState as CssElementState, ParseError as CssParseError, SelectorPart,
-        Severity as CssSeverity, StructuralPseudo, Stylesheet,
+        CompoundSelector, ContainerRule, CssAnimation, CssKeyframe, CssKeyframes, CssParseResult,
+        CssSelector, ElementState as CssElementState, MediaRule, ParseError as CssParseError,
+        SelectorPart, Severity as CssSeverity, StructuralPseudo, Stylesheet, StylesheetDiff,
     };
 
+    // Media and container queries
+    pub use crate::media_query::{ContainerQuery, ContainerType, MediaContext, MediaQueryList};
+
+    // Stylesheet hot reload
+    pub use crate::hot_reload::{StylesheetReload, StylesheetWatcher};
+
     // Stable unique key generation for components
     pub use crate::key::{reset_call_counters, InstanceKey};
 }
@@ -0,0 +1,552 @@
+//! Shape geometry: bezier contours, primitives and trim paths
+
+use std::f32::consts::{FRAC_PI_2, PI};
+
+use blinc_core::{Affine2D, Path, Point};
+
+use super::value::Lerp;
+
+/// Circle approximation constant for cubic beziers
+const KAPPA: f32 = 0.552_284_8;
+
+/// A Lottie bezier shape with tangents relative to their vertices
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct Bezier {
+    pub closed: bool,
+    pub vertices: Vec<[f32; 2]>,
+    pub in_tangents: Vec<[f32; 2]>,
+    pub out_tangents: Vec<[f32; 2]>,
+}
+
+impl Lerp for Bezier {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        if self.vertices.len() != to.vertices.len() {
+            return if t < 1.0 { self.clone() } else { to.clone() };
+        }
+        let mix = |a: &[[f32; 2]], b: &[[f32; 2]]| {
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t])
+                .collect()
+        };
+        Bezier {
+            closed: self.closed,
+            vertices: mix(&self.vertices, &to.vertices),
+            in_tangents: mix(&self.in_tangents, &to.in_tangents),
+            out_tangents: mix(&self.out_tangents, &to.out_tangents),
+        }
+    }
+}
+
+impl Bezier {
+    /// Absolute cubic segments
+    pub(super) fn to_contour(&self) -> Contour {
+        let mut contour = Contour {
+            start: self.vertices.first().map_or(Point::ZERO, |v| pt(*v)),
+            segments: Vec::with_capacity(self.vertices.len()),
+            closed: self.closed,
+        };
+        let count = self.vertices.len();
+        let edges = if self.closed {
+            count
+        } else {
+            count.saturating_sub(1)
+        };
+        for i in 0..edges {
+            let from = self.vertices[i];
+            let to = self.vertices[(i + 1) % count];
+            let out = self.out_tangents[i];
+            let into = self.in_tangents[(i + 1) % count];
+            contour.segments.push([
+                pt([from[0] + out[0], from[1] + out[1]]),
+                pt([to[0] + into[0], to[1] + into[1]]),
+                pt(to),
+            ]);
+        }
+        contour
+    }
+}
+
+fn pt(p: [f32; 2]) -> Point {
+    Point::new(p[0], p[1])
+}
+
+/// A subpath of absolute cubic segments: `[control1, control2, end]`
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Contour {
+    pub start: Point,
+    pub segments: Vec<[Point; 3]>,
+    pub closed: bool,
+}
+
+impl Contour {
+    fn new(start: Point) -> Self {
+        Self {
+            start,
+            segments: Vec::new(),
+            closed: false,
+        }
+    }
+
+    fn end(&self) -> Point {
+        self.segments.last().map_or(self.start, |s| s[2])
+    }
+
+    fn line_to(&mut self, to: Point) {
+        let from = self.end();
+        self.segments.push([
+            lerp_point(from, to, 1.0 / 3.0),
+            lerp_point(from, to, 2.0 / 3.0),
+            to,
+        ]);
+    }
+
+    fn cubic_to(&mut self, c1: Point, c2: Point, to: Point) {
+        self.segments.push([c1, c2, to]);
+    }
+
+    fn close(mut self) -> Self {
+        if self.end() != self.start {
+            self.line_to(self.start);
+        }
+        self.closed = true;
+        self
+    }
+
+    /// Apply an affine transform to every point
+    pub(super) fn transform(&self, m: &Affine2D) -> Contour {
+        Contour {
+            start: m.transform_point(self.start),
+            segments: self
+                .segments
+                .iter()
+                .map(|s| s.map(|p| m.transform_point(p)))
+                .collect(),
+            closed: self.closed,
+        }
+    }
+
+    /// The same contour traversed in the opposite direction
+    pub(super) fn reversed(&self) -> Contour {
+        let mut points = Vec::with_capacity(self.segments.len());
+        let mut from = self.start;
+        for segment in &self.segments {
+            points.push((from, segment[0], segment[1]));
+            from = segment[2];
+        }
+        Contour {
+            start: from,
+            segments: points
+                .into_iter()
+                .rev()
+                .map(|(start, c1, c2)| [c2, c1, start])
+                .collect(),
+            closed: self.closed,
+        }
+    }
+
+    /// Signed area of the control polygon (positive when clockwise on screen)
+    pub(super) fn signed_area(&self) -> f32 {
+        let points = self.flatten(4);
+        let mut area = 0.0;
+        for (i, a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            area += a.x * b.y - b.x * a.y;
+        }
+        area / 2.0
+    }
+
+    /// Polyline approximation with `steps` points per segment
+    pub(super) fn flatten(&self, steps: usize) -> Vec<Point> {
+        let mut points = vec![self.start];
+        let mut from = self.start;
+        for &[c1, c2, to] in &self.segments {
+            for step in 1..=steps {
+                points.push(cubic_point(from, c1, c2, to, step as f32 / steps as f32));
+            }
+            from = to;
+        }
+        if self.closed && points.len() > 1 && points.last() == Some(&self.start) {
+            points.pop();
+        }
+        points
+    }
+
+    fn segment_lengths(&self) -> Vec<f32> {
+        let mut from = self.start;
+        self.segments
+            .iter()
+            .map(|&[c1, c2, to]| {
+                let length = cubic_length(from, c1, c2, to);
+                from = to;
+                length
+            })
+            .collect()
+    }
+
+    /// The part of the contour between two distances along it
+    fn extract(&self, lengths: &[f32], from: f32, to: f32) -> Option<Contour> {
+        if to <= from {
+            return None;
+        }
+        let mut result: Option<Contour> = None;
+        let mut offset = 0.0;
+        let mut start = self.start;
+        for (&[c1, c2, end], &length) in self.segments.iter().zip(lengths) {
+            let seg_from = offset;
+            let seg_to = offset + length;
+            offset = seg_to;
+            if seg_to > from && seg_from < to && length > 0.0 {
+                let t0 = cubic_t_at(start, c1, c2, end, ((from - seg_from) / length).max(0.0));
+                let t1 = cubic_t_at(start, c1, c2, end, ((to - seg_from) / length).min(1.0));
+                let [p0, q1, q2, p3] = sub_cubic([start, c1, c2, end], t0, t1);
+                result
+                    .get_or_insert_with(|| Contour::new(p0))
+                    .cubic_to(q1, q2, p3);
+            }
+            start = end;
+        }
+        result
+    }
+}
+
+/// Build a path from contours
+pub(super) fn to_path(contours: &[Contour]) -> Path {
+    let mut path = Path::new();
+    for contour in contours {
+        if contour.segments.is_empty() {
+            continue;
+        }
+        path = path.move_to(contour.start.x, contour.start.y);
+        for [c1, c2, to] in &contour.segments {
+            path = path.cubic_to(c1.x, c1.y, c2.x, c2.y, to.x, to.y);
+        }
+        if contour.closed {
+            path = path.close();
+        }
+    }
+    path
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Primitives
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Rectangle centered on `center`, with rounded corners
+pub(super) fn rect(center: [f32; 2], size: [f32; 2], roundness: f32) -> Contour {
+    let (hw, hh) = (size[0] / 2.0, size[1] / 2.0);
+    let (left, top) = (center[0] - hw, center[1] - hh);
+    let (right, bottom) = (center[0] + hw, center[1] + hh);
+    let r = roundness.clamp(0.0, hw.min(hh));
+
+    // Lottie rectangles start at the top right corner and run clockwise
+    if r <= 0.0 {
+        let mut contour = Contour::new(Point::new(right, top));
+        contour.line_to(Point::new(right, bottom));
+        contour.line_to(Point::new(left, bottom));
+        contour.line_to(Point::new(left, top));
+        return contour.close();
+    }
+
+    let k = r * (1.0 - KAPPA);
+    let mut contour = Contour::new(Point::new(right, top + r));
+    contour.line_to(Point::new(right, bottom - r));
+    contour.cubic_to(
+        Point::new(right, bottom - k),
+        Point::new(right - k, bottom),
+        Point::new(right - r, bottom),
+    );
+    contour.line_to(Point::new(left + r, bottom));
+    contour.cubic_to(
+        Point::new(left + k, bottom),
+        Point::new(left, bottom - k),
+        Point::new(left, bottom - r),
+    );
+    contour.line_to(Point::new(left, top + r));
+    contour.cubic_to(
+        Point::new(left, top + k),
+        Point::new(left + k, top),
+        Point::new(left + r, top),
+    );
+    contour.line_to(Point::new(right - r, top));
+    contour.cubic_to(
+        Point::new(right - k, top),
+        Point::new(right, top + k),
+        Point::new(right, top + r),
+    );
+    contour.close()
+}
+
+/// Ellipse centered on `center`, starting at the top and running clockwise
+pub(super) fn ellipse(center: [f32; 2], size: [f32; 2]) -> Contour {
+    let (cx, cy) = (center[0], center[1]);
+    let (rx, ry) = (size[0] / 2.0, size[1] / 2.0);
+    let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+    let mut contour = Contour::new(Point::new(cx, cy - ry));
+    contour.cubic_to(
+        Point::new(cx + kx, cy - ry),
+        Point::new(cx + rx, cy - ky),
+        Point::new(cx + rx, cy),
+    );
+    contour.cubic_to(
+        Point::new(cx + rx, cy + ky),
+        Point::new(cx + kx, cy + ry),
+        Point::new(cx, cy + ry),
+    );
+    contour.cubic_to(
+        Point::new(cx - kx, cy + ry),
+        Point::new(cx - rx, cy + ky),
+        Point::new(cx - rx, cy),
+    );
+    contour.cubic_to(
+        Point::new(cx - rx, cy - ky),
+        Point::new(cx - kx, cy - ry),
+        Point::new(cx, cy - ry),
+    );
+    contour.closed = true;
+    contour
+}
+
+/// Star (`inner_radius` is `Some`) or regular polygon
+///
+/// Corner roundness is not applied; points are joined with straight edges.
+pub(super) fn star(
+    center: [f32; 2],
+    points: f32,
+    rotation_deg: f32,
+    outer_radius: f32,
+    inner_radius: Option<f32>,
+) -> Contour {
+    let count = points.round().max(3.0) as usize;
+    let vertices = if inner_radius.is_some() {
+        count * 2
+    } else {
+        count
+    };
+    let step = 2.0 * PI / vertices as f32;
+    let start = rotation_deg.to_radians() - FRAC_PI_2;
+
+    let vertex = |i: usize| {
+        let radius = match inner_radius {
+            Some(inner) if i % 2 == 1 => inner,
+            _ => outer_radius,
+        };
+        let angle = start + step * i as f32;
+        Point::new(
+            center[0] + radius * angle.cos(),
+            center[1] + radius * angle.sin(),
+        )
+    };
+
+    let mut contour = Contour::new(vertex(0));
+    for i in 1..vertices {
+        contour.line_to(vertex(i));
+    }
+    contour.close()
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Trim paths
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Keep the part of the contours between `start` and `end` (0 to 1)
+///
+/// `offset` shifts the trimmed range by a fraction of the length and wraps
+/// around. With `sequential`, the range spans all contours laid end to end;
+/// otherwise it applies to each contour on its own. Each piece is returned
+/// with the index of the contour it came from.
+pub(super) fn trim(
+    contours: &[Contour],
+    start: f32,
+    end: f32,
+    offset: f32,
+    sequential: bool,
+) -> Vec<(usize, Contour)> {
+    let (mut start, mut end) = (start.clamp(0.0, 1.0), end.clamp(0.0, 1.0));
+    if start > end {
+        std::mem::swap(&mut start, &mut end);
+    }
+    if end - start >= 1.0 {
+        return contours.iter().cloned().enumerate().collect();
+    }
+    if end <= start {
+        return Vec::new();
+    }
+
+    let shift = offset.rem_euclid(1.0);
+    let (start, end) = (start + shift, end + shift);
+    // Ranges within [0, 1] after wrapping
+    let ranges: Vec<(f32, f32)> = if end > 1.0 {
+        if start >= 1.0 {
+            vec![(start - 1.0, end - 1.0)]
+        } else {
+            vec![(start, 1.0), (0.0, end - 1.0)]
+        }
+    } else {
+        vec![(start, end)]
+    };
+
+    let lengths: Vec<Vec<f32>> = contours.iter().map(Contour::segment_lengths).collect();
+    let totals: Vec<f32> = lengths.iter().map(|l| l.iter().sum()).collect();
+    let total: f32 = totals.iter().sum();
+
+    let mut result = Vec::new();
+    let mut offset = 0.0;
+    for (index, ((contour, lengths), &length)) in
+        contours.iter().zip(&lengths).zip(&totals).enumerate()
+    {
+        for &(from, to) in &ranges {
+            let piece = if sequential {
+                let from = (from * total - offset).max(0.0);
+                let to = (to * total - offset).min(length);
+                contour.extract(lengths, from, to)
+            } else {
+                contour.extract(lengths, from * length, to * length)
+            };
+            result.extend(piece.map(|piece| (index, piece)));
+        }
+        offset += length;
+    }
+    result
+}
+
+fn lerp_point(a: Point, b: Point, t: f32) -> Point {
+    Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
+
+fn cubic_point(p0: Point, p1: Point, p2: Point, p3: Point, t: f32) -> Point {
+    let a = lerp_point(p0, p1, t);
+    let b = lerp_point(p1, p2, t);
+    let c = lerp_point(p2, p3, t);
+    let d = lerp_point(a, b, t);
+    let e = lerp_point(b, c, t);
+    lerp_point(d, e, t)
+}
+
+/// Samples per segment for arc length estimates
+const LENGTH_SAMPLES: usize = 16;
+
+fn cubic_length(p0: Point, p1: Point, p2: Point, p3: Point) -> f32 {
+    let mut length = 0.0;
+    let mut previous = p0;
+    for i in 1..=LENGTH_SAMPLES {
+        let point = cubic_point(p0, p1, p2, p3, i as f32 / LENGTH_SAMPLES as f32);
+        length += (point.x - previous.x).hypot(point.y - previous.y);
+        previous = point;
+    }
+    length
+}
+
+/// Curve parameter at a fraction of the segment's arc length
+fn cubic_t_at(p0: Point, p1: Point, p2: Point, p3: Point, fraction: f32) -> f32 {
+    if fraction <= 0.0 {
+        return 0.0;
+    }
+    if fraction >= 1.0 {
+        return 1.0;
+    }
+    let mut cumulative = [0.0f32; LENGTH_SAMPLES + 1];
+    let mut previous = p0;
+    for (i, slot) in cumulative.iter_mut().enumerate().skip(1) {
+        let point = cubic_point(p0, p1, p2, p3, i as f32 / LENGTH_SAMPLES as f32);
+        *slot = (point.x - previous.x).hypot(point.y - previous.y);
+        previous = point;
+    }
+    for i in 1..=LENGTH_SAMPLES {
+        cumulative[i] += cumulative[i - 1];
+    }
+    let target = fraction * cumulative[LENGTH_SAMPLES];
+    let i = cumulative
+        .iter()
+        .position(|&length| length >= target)
+        .unwrap_or(LENGTH_SAMPLES)
+        .max(1);
+    let span = cumulative[i] - cumulative[i - 1];
+    let local = if span > 0.0 {
+        (target - cumulative[i - 1]) / span
+    } else {
+        0.0
+    };
+    ((i - 1) as f32 + local) / LENGTH_SAMPLES as f32
+}
+
+/// The part of a cubic between parameters `t0` and `t1`
+fn sub_cubic(c: [Point; 4], t0: f32, t1: f32) -> [Point; 4] {
+    let (left, _) = split_cubic(c, t1);
+    if t1 <= 0.0 {
+        return [c[0]; 4];
+    }
+    let (_, right) = split_cubic(left, t0 / t1);
+    right
+}
+
+fn split_cubic(c: [Point; 4], t: f32) -> ([Point; 4], [Point; 4]) {
+    let a = lerp_point(c[0], c[1], t);
+    let b = lerp_point(c[1], c[2], t);
+    let d = lerp_point(c[2], c[3], t);
+    let e = lerp_point(a, b, t);
+    let f = lerp_point(b, d, t);
+    let g = lerp_point(e, f, t);
+    ([c[0], a, e, g], [g, f, d, c[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn length(contours: &[Contour]) -> f32 {
+        contours
+            .iter()
+            .map(|c| c.segment_lengths().iter().sum::<f32>())
+            .sum()
+    }
+
+    #[test]
+    fn test_primitives() {
+        let square = rect([50.0, 50.0], [100.0, 100.0], 0.0);
+        assert_eq!(square.segments.len(), 4);
+        assert!((length(std::slice::from_ref(&square)) - 400.0).abs() < 1e-2);
+        assert!(square.signed_area() > 0.0);
+        assert!(square.reversed().signed_area() < 0.0);
+
+        let circle = ellipse([0.0, 0.0], [20.0, 20.0]);
+        let circumference = 2.0 * PI * 10.0;
+        assert!((length(&[circle]) - circumference).abs() < 0.1);
+
+        let star = star([0.0, 0.0], 5.0, 0.0, 10.0, Some(5.0));
+        assert_eq!(star.segments.len(), 10);
+        assert!((star.start.y + 10.0).abs() < 1e-4);
+    }
+
+    fn pieces(trimmed: Vec<(usize, Contour)>) -> Vec<Contour> {
+        trimmed.into_iter().map(|(_, contour)| contour).collect()
+    }
+
+    #[test]
+    fn test_trim() {
+        let line = Bezier {
+            closed: false,
+            vertices: vec![[0.0, 0.0], [100.0, 0.0]],
+            in_tangents: vec![[0.0, 0.0]; 2],
+            out_tangents: vec![[0.0, 0.0]; 2],
+        }
+        .to_contour();
+
+        let half = pieces(trim(std::slice::from_ref(&line), 0.25, 0.75, 0.0, false));
+        assert_eq!(half.len(), 1);
+        assert!((half[0].start.x - 25.0).abs() < 0.5);
+        assert!((half[0].end().x - 75.0).abs() < 0.5);
+
+        // Offsets wrap around the end
+        let wrapped = pieces(trim(std::slice::from_ref(&line), 0.0, 0.5, 0.75, false));
+        assert_eq!(wrapped.len(), 2);
+        assert!((length(&wrapped) - 50.0).abs() < 0.5);
+
+        // Sequential trimming spans both lines
+        let moved = line.transform(&Affine2D::translation(0.0, 10.0));
+        let first_half = trim(&[line, moved], 0.0, 0.5, 0.0, true);
+        assert_eq!(first_half.len(), 1);
+        assert_eq!(first_half[0].0, 0);
+        assert!((length(&pieces(first_half)) - 100.0).abs() < 0.5);
+    }
+}
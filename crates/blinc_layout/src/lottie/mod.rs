@@ -0,0 +1,327 @@
+//! Lottie animation playback
+//!
+//! Plays Lottie (Bodymovin JSON) files exported from After Effects and other
+//! motion design tools. Frames are drawn as `DrawContext` paths on a canvas
+//! and the playhead runs on the animation scheduler.
+//!
+//! Supported features:
+//!
+//! - shape layers with groups, paths, rectangles, ellipses, stars and polygons
+//! - fills, strokes (caps, joins, dashes), linear and radial gradients
+//! - trim paths, simultaneous and sequential
+//! - layer and group transforms (anchor, position, scale, rotation, skew,
+//!   opacity), parenting, and motion paths with spatial tangents
+//! - keyframes with bezier easing and hold keyframes
+//! - masks (add, subtract, intersect, inverted) as polygon clips
+//! - precomps with time stretch and time remapping, and solid layers
+//!
+//! Image and text layers, track mattes, merge paths and repeaters are not
+//! drawn.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use blinc_layout::lottie::{lottie, LottieAnimation, LottiePlayer};
+//!
+//! let animation = LottieAnimation::from_json(include_str!("success.json"))?;
+//! let player = LottiePlayer::new(ctx.animation_handle(), animation);
+//! player.set_segment(0.0, 30.0);
+//! player.play();
+//!
+//! div().child(lottie(&player).size(96.0, 96.0))
+//! ```
+
+mod geometry;
+mod model;
+mod player;
+mod render;
+mod value;
+
+use std::ops::{Deref, DerefMut};
+
+use blinc_core::DrawContext;
+
+use crate::canvas::canvas;
+use crate::div::{div, Div, ElementBuilder, ElementTypeId};
+use crate::element::RenderProps;
+use crate::tree::{LayoutNodeId, LayoutTree};
+
+pub use model::{LottieAnimation, LottieError};
+pub use player::LottiePlayer;
+
+/// Element drawing the current frame of a [`LottiePlayer`]
+///
+/// Sized to the composition by default. The animation is scaled to fit the
+/// element and centered.
+pub struct Lottie {
+    inner: Div,
+    player: LottiePlayer,
+}
+
+impl Lottie {
+    /// Draw `player`'s animation
+    pub fn new(player: &LottiePlayer) -> Self {
+        let animation = player.animation();
+        let frames = player.clone();
+        let surface = canvas(move |ctx: &mut dyn DrawContext, bounds| {
+            animation.render(ctx, frames.frame(), bounds.width, bounds.height);
+        })
+        .w_full()
+        .h_full();
+
+        let animation = player.animation();
+        Self {
+            inner: div()
+                .w(animation.width())
+                .h(animation.height())
+                .flex_shrink_0()
+                .child(surface),
+            player: player.clone(),
+        }
+    }
+
+    /// Set the width
+    pub fn w(mut self, px: f32) -> Self {
+        self.inner = std::mem::take(&mut self.inner).w(px);
+        self
+    }
+
+    /// Set the height
+    pub fn h(mut self, px: f32) -> Self {
+        self.inner = std::mem::take(&mut self.inner).h(px);
+        self
+    }
+
+    /// Set width and height
+    pub fn size(self, width: f32, height: f32) -> Self {
+        self.w(width).h(height)
+    }
+
+    /// The player driving this element
+    pub fn player(&self) -> &LottiePlayer {
+        &self.player
+    }
+}
+
+impl Deref for Lottie {
+    type Target = Div;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for Lottie {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl ElementBuilder for Lottie {
+    fn build(&self, tree: &mut LayoutTree) -> LayoutNodeId {
+        self.inner.build(tree)
+    }
+
+    fn render_props(&self) -> RenderProps {
+        self.inner.render_props()
+    }
+
+    fn children_builders(&self) -> &[Box<dyn ElementBuilder>] {
+        self.inner.children_builders()
+    }
+
+    fn element_type_id(&self) -> ElementTypeId {
+        ElementTypeId::Div
+    }
+
+    fn semantic_type_name(&self) -> Option<&'static str> {
+        Some("lottie")
+    }
+
+    fn event_handlers(&self) -> Option<&crate::event_handler::EventHandlers> {
+        ElementBuilder::event_handlers(&self.inner)
+    }
+
+    fn layout_style(&self) -> Option<&taffy::Style> {
+        self.inner.layout_style()
+    }
+}
+
+/// Draw the current frame of `player`
+pub fn lottie(player: &LottiePlayer) -> Lottie {
+    Lottie::new(player)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use blinc_animation::{AnimationScheduler, ManualClock};
+    use blinc_core::{Brush, ClipShape, DrawCommand, RecordingContext, Size};
+
+    /// 100x100 at 10fps, 20 frames: a red square sliding right, drawn over
+    /// a trimmed stroke and a masked precomp
+    const SAMPLE: &str = r##"{
+        "v": "5.7.0", "fr": 10, "ip": 0, "op": 20, "w": 100, "h": 100,
+        "assets": [{"id": "comp_1", "layers": [
+            {"ty": 1, "ind": 1, "ip": 0, "op": 20, "st": 0, "sc": "#00ff00",
+             "sw": 50, "sh": 50, "ks": {}}
+        ]}],
+        "layers": [
+            {"ty": 4, "ind": 1, "ip": 0, "op": 20, "st": 0,
+             "ks": {"p": {"a": 1, "k": [
+                {"t": 0, "s": [10, 10], "o": {"x": 0, "y": 0}, "i": {"x": 1, "y": 1}},
+                {"t": 10, "s": [60, 10]}
+             ]}},
+             "shapes": [{"ty": "gr", "it": [
+                {"ty": "rc", "p": {"a": 0, "k": [10, 10]}, "s": {"a": 0, "k": [20, 20]},
+                 "r": {"a": 0, "k": 0}},
+                {"ty": "fl", "c": {"a": 0, "k": [1, 0, 0, 1]}, "o": {"a": 0, "k": 100}},
+                {"ty": "tr", "o": {"a": 0, "k": 100}}
+             ]}]},
+            {"ty": 4, "ind": 2, "ip": 0, "op": 20, "st": 0, "ks": {},
+             "shapes": [
+                {"ty": "sh", "ks": {"a": 0, "k": {"c": false, "v": [[0, 90], [100, 90]],
+                  "i": [[0, 0], [0, 0]], "o": [[0, 0], [0, 0]]}}},
+                {"ty": "st", "c": {"a": 0, "k": [0, 0, 1]}, "o": {"a": 0, "k": 100},
+                 "w": {"a": 0, "k": 4}, "lc": 2, "lj": 2},
+                {"ty": "tm", "s": {"a": 0, "k": 0}, "e": {"a": 0, "k": 50},
+                 "o": {"a": 0, "k": 0}, "m": 1}
+             ]},
+            {"ty": 0, "ind": 3, "ip": 0, "op": 20, "st": 0, "refId": "comp_1",
+             "w": 50, "h": 50, "hasMask": true,
+             "ks": {"o": {"a": 0, "k": 50}},
+             "masksProperties": [{"mode": "a", "inv": false, "o": {"a": 0, "k": 100},
+               "pt": {"a": 0, "k": {"c": true, "v": [[0, 0], [25, 0], [25, 25], [0, 25]],
+                 "i": [[0, 0], [0, 0], [0, 0], [0, 0]], "o": [[0, 0], [0, 0], [0, 0], [0, 0]]}}}]}
+        ]
+    }"##;
+
+    fn render(animation: &LottieAnimation, frame: f32) -> Vec<DrawCommand> {
+        let mut ctx = RecordingContext::new(Size::new(100.0, 100.0));
+        animation.render(&mut ctx, frame, 100.0, 100.0);
+        ctx.take_commands()
+    }
+
+    fn fill_bounds(commands: &[DrawCommand], color: blinc_core::Color) -> Option<blinc_core::Rect> {
+        commands.iter().find_map(|command| match command {
+            DrawCommand::FillPath {
+                path,
+                brush: Brush::Solid(c),
+            } if *c == color => Some(path.bounds()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_render_frames() {
+        let animation = LottieAnimation::from_json(SAMPLE).unwrap();
+        assert_eq!(animation.duration_ms(), 2000.0);
+        let red = blinc_core::Color::rgba(1.0, 0.0, 0.0, 1.0);
+
+        // Linear position keyframes: 10 -> 60 over frames 0..10
+        let start = fill_bounds(&render(&animation, 0.0), red).unwrap();
+        assert!((start.x() - 10.0).abs() < 1e-3 && (start.width() - 20.0).abs() < 1e-3);
+        let middle = fill_bounds(&render(&animation, 5.0), red).unwrap();
+        assert!((middle.x() - 35.0).abs() < 1e-3);
+
+        let commands = render(&animation, 5.0);
+        // The stroke is trimmed to the first half of the line
+        let stroke = commands
+            .iter()
+            .find_map(|command| match command {
+                DrawCommand::StrokePath { path, stroke, .. } => Some((path.bounds(), stroke)),
+                _ => None,
+            })
+            .unwrap();
+        assert!((stroke.0.width() - 50.0).abs() < 0.5);
+        assert_eq!(stroke.1.width, 4.0);
+
+        // The precomp is drawn at half opacity inside its mask
+        assert!(commands
+            .iter()
+            .any(|command| matches!(command, DrawCommand::PushOpacity(o) if *o == 0.5)));
+        // Precomp bounds, and the mask flattened at eight points per edge
+        for corners in [4, 32] {
+            assert!(commands.iter().any(|command| matches!(
+                command,
+                DrawCommand::PushClip(ClipShape::Polygon(points)) if points.len() == corners
+            )));
+        }
+        let green = blinc_core::Color::rgba(0.0, 1.0, 0.0, 1.0);
+        assert!(fill_bounds(&commands, green).is_some());
+
+        // Layers draw bottom to top: precomp, stroke, then the square
+        let order: Vec<usize> = commands
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| {
+                matches!(
+                    c,
+                    DrawCommand::FillPath { .. } | DrawCommand::StrokePath { .. }
+                )
+            })
+            .map(|(i, _)| i)
+            .collect();
+        assert!(matches!(
+            commands[*order.last().unwrap()],
+            DrawCommand::FillPath { brush: Brush::Solid(c), .. } if c == red
+        ));
+    }
+
+    #[test]
+    fn test_player_follows_scheduler() {
+        let clock = Arc::new(ManualClock::new());
+        let scheduler = AnimationScheduler::with_clock(clock.clone());
+        let animation = LottieAnimation::from_json(SAMPLE).unwrap();
+        let player = LottiePlayer::new(scheduler.handle(), animation);
+
+        let advance = |ms| {
+            clock.advance(Duration::from_millis(ms));
+            scheduler.tick();
+        };
+
+        assert_eq!(player.frame(), 0.0);
+        assert!(!player.is_playing());
+        advance(500);
+        assert_eq!(player.frame(), 0.0);
+
+        // 10fps: 500ms is five frames
+        player.play();
+        advance(500);
+        assert!((player.frame() - 5.0).abs() < 1e-3);
+
+        player.pause();
+        advance(500);
+        assert!((player.frame() - 5.0).abs() < 1e-3);
+
+        player.seek(12.0);
+        assert!((player.frame() - 12.0).abs() < 1e-3);
+        assert!((player.progress() - 0.6).abs() < 1e-3);
+
+        // Segments play in either direction and stop at their end
+        player.set_segment(15.0, 5.0);
+        player.play();
+        advance(500);
+        assert!((player.frame() - 10.0).abs() < 1e-3);
+        advance(1000);
+        assert!((player.frame() - 5.0).abs() < 1e-3);
+        assert!(!player.is_playing());
+
+        // Looping wraps around
+        player.set_looping(true);
+        player.set_speed(2.0);
+        player.play();
+        advance(300);
+        assert!((player.frame() - 9.0).abs() < 1e-3);
+        advance(300);
+        assert!((player.frame() - 15.0).abs() < 1e-3);
+        assert!(player.is_playing());
+
+        player.clear_segment();
+        player.stop();
+        assert_eq!(player.segment(), (0.0, 20.0));
+        assert_eq!(player.frame(), 0.0);
+    }
+}
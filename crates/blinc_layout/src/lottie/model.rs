@@ -0,0 +1,635 @@
+//! Lottie document model and JSON parsing
+
+use std::collections::HashMap;
+use std::fmt;
+
+use blinc_core::{Affine2D, Color, LineCap, LineJoin};
+use serde_json::Value;
+
+use super::geometry::Bezier;
+use super::value::{animated, scalar, shape, vector, Animated};
+
+/// Error loading a Lottie file
+#[derive(Debug)]
+pub enum LottieError {
+    /// The input is not valid JSON
+    Json(serde_json::Error),
+    /// The JSON is not a Lottie animation
+    Invalid(String),
+}
+
+impl fmt::Display for LottieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LottieError::Json(err) => write!(f, "invalid Lottie JSON: {err}"),
+            LottieError::Invalid(msg) => write!(f, "invalid Lottie animation: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for LottieError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LottieError::Json(err) => Some(err),
+            LottieError::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for LottieError {
+    fn from(err: serde_json::Error) -> Self {
+        LottieError::Json(err)
+    }
+}
+
+/// A parsed Lottie (Bodymovin) animation
+///
+/// Parse once and share between players with `Arc`; the animation itself is
+/// immutable and renders any frame on demand.
+#[derive(Clone, Debug)]
+pub struct LottieAnimation {
+    pub(super) width: f32,
+    pub(super) height: f32,
+    pub(super) frame_rate: f32,
+    pub(super) in_point: f32,
+    pub(super) out_point: f32,
+    pub(super) layers: Vec<Layer>,
+    pub(super) assets: HashMap<String, Vec<Layer>>,
+}
+
+impl LottieAnimation {
+    /// Parse a Lottie JSON document
+    pub fn from_json(json: &str) -> Result<Self, LottieError> {
+        let value: Value = serde_json::from_str(json)?;
+        Self::from_value(&value)
+    }
+
+    /// Build from an already parsed JSON value
+    pub fn from_value(value: &Value) -> Result<Self, LottieError> {
+        if !value.is_object() {
+            return Err(LottieError::Invalid("expected an object".into()));
+        }
+        let number = |key: &str| -> Result<f32, LottieError> {
+            value
+                .get(key)
+                .and_then(Value::as_f64)
+                .map(|n| n as f32)
+                .ok_or_else(|| LottieError::Invalid(format!("missing `{key}`")))
+        };
+
+        let frame_rate = number("fr")?;
+        if frame_rate <= 0.0 {
+            return Err(LottieError::Invalid("`fr` must be positive".into()));
+        }
+        let layers = value
+            .get("layers")
+            .and_then(Value::as_array)
+            .ok_or_else(|| LottieError::Invalid("missing `layers`".into()))?;
+
+        let assets = value
+            .get("assets")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|asset| {
+                let id = asset.get("id")?.as_str()?;
+                let layers = asset.get("layers")?.as_array()?;
+                Some((id.to_string(), parse_layers(layers)))
+            })
+            .collect();
+
+        Ok(Self {
+            width: number("w")?,
+            height: number("h")?,
+            frame_rate,
+            in_point: number("ip")?,
+            out_point: number("op")?,
+            layers: parse_layers(layers),
+            assets,
+        })
+    }
+
+    /// Composition width
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// Composition height
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    /// Frames per second
+    pub fn frame_rate(&self) -> f32 {
+        self.frame_rate
+    }
+
+    /// First frame
+    pub fn in_point(&self) -> f32 {
+        self.in_point
+    }
+
+    /// Frame the animation ends at (exclusive)
+    pub fn out_point(&self) -> f32 {
+        self.out_point
+    }
+
+    /// Number of frames
+    pub fn frame_count(&self) -> f32 {
+        (self.out_point - self.in_point).max(0.0)
+    }
+
+    /// Duration in milliseconds
+    pub fn duration_ms(&self) -> f32 {
+        self.frame_count() / self.frame_rate * 1000.0
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Layers
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Debug)]
+pub(super) struct Layer {
+    pub index: Option<i64>,
+    pub parent: Option<i64>,
+    pub in_point: f32,
+    pub out_point: f32,
+    pub start_time: f32,
+    pub stretch: f32,
+    pub transform: Transform,
+    pub masks: Vec<Mask>,
+    /// Hidden, or the source of a track matte (never drawn directly)
+    pub hidden: bool,
+    pub content: LayerContent,
+}
+
+#[derive(Clone, Debug)]
+pub(super) enum LayerContent {
+    /// Null layers only carry a transform for their children
+    Null,
+    Shape(Vec<ShapeItem>),
+    Solid {
+        color: Color,
+        width: f32,
+        height: f32,
+    },
+    Precomp {
+        asset: String,
+        width: f32,
+        height: f32,
+        /// Local time in seconds, replacing the layer's own timing
+        time_remap: Option<Animated<f32>>,
+    },
+}
+
+impl Layer {
+    /// Frame within the layer's own timeline
+    pub(super) fn local_frame(&self, frame: f32) -> f32 {
+        (frame - self.start_time) / self.stretch
+    }
+
+    pub(super) fn is_visible_at(&self, frame: f32) -> bool {
+        !self.hidden && frame >= self.in_point && frame < self.out_point
+    }
+}
+
+fn parse_layers(layers: &[Value]) -> Vec<Layer> {
+    layers.iter().filter_map(parse_layer).collect()
+}
+
+fn parse_layer(layer: &Value) -> Option<Layer> {
+    let number = |key: &str| layer.get(key).and_then(Value::as_f64).map(|n| n as f32);
+    let flag = |key: &str| {
+        layer
+            .get(key)
+            .is_some_and(|v| v.as_bool().unwrap_or(v.as_f64() == Some(1.0)))
+    };
+
+    let content = match layer.get("ty")?.as_i64()? {
+        0 => LayerContent::Precomp {
+            asset: layer.get("refId")?.as_str()?.to_string(),
+            width: number("w").unwrap_or(0.0),
+            height: number("h").unwrap_or(0.0),
+            time_remap: layer.get("tm").map(|tm| animated(Some(tm), scalar, 0.0)),
+        },
+        1 => LayerContent::Solid {
+            color: layer
+                .get("sc")
+                .and_then(Value::as_str)
+                .and_then(hex_color)
+                .unwrap_or(Color::BLACK),
+            width: number("sw").unwrap_or(0.0),
+            height: number("sh").unwrap_or(0.0),
+        },
+        4 => LayerContent::Shape(parse_shapes(layer.get("shapes"))),
+        // Null layers; images and text are not supported and draw nothing
+        _ => LayerContent::Null,
+    };
+
+    let masks = layer
+        .get("masksProperties")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(parse_mask)
+        .collect();
+
+    Some(Layer {
+        index: layer.get("ind").and_then(Value::as_i64),
+        parent: layer.get("parent").and_then(Value::as_i64),
+        in_point: number("ip").unwrap_or(0.0),
+        out_point: number("op").unwrap_or(f32::MAX),
+        start_time: number("st").unwrap_or(0.0),
+        stretch: number("sr").filter(|sr| *sr != 0.0).unwrap_or(1.0),
+        transform: Transform::parse(layer.get("ks")),
+        masks,
+        hidden: flag("hd") || flag("td"),
+        content,
+    })
+}
+
+fn hex_color(hex: &str) -> Option<Color> {
+    let hex = hex.trim_start_matches('#');
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some(Color::rgba(
+        channel(0)? as f32 / 255.0,
+        channel(2)? as f32 / 255.0,
+        channel(4)? as f32 / 255.0,
+        1.0,
+    ))
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Transforms
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Debug)]
+pub(super) enum Position {
+    Combined(Animated<Vec<f32>>),
+    Split(Animated<f32>, Animated<f32>),
+}
+
+/// Layer (`ks`) or group (`tr`) transform
+#[derive(Clone, Debug)]
+pub(super) struct Transform {
+    anchor: Animated<Vec<f32>>,
+    position: Position,
+    scale: Animated<Vec<f32>>,
+    rotation: Animated<f32>,
+    skew: Animated<f32>,
+    skew_axis: Animated<f32>,
+    opacity: Animated<f32>,
+}
+
+impl Transform {
+    fn parse(value: Option<&Value>) -> Self {
+        let prop = |key: &str| value.and_then(|v| v.get(key));
+        let position = match prop("p") {
+            Some(p) if p.get("s").and_then(Value::as_bool) == Some(true) => Position::Split(
+                animated(p.get("x"), scalar, 0.0),
+                animated(p.get("y"), scalar, 0.0),
+            ),
+            p => Position::Combined(animated(p, vector, vec![0.0, 0.0])),
+        };
+        Self {
+            anchor: animated(prop("a"), vector, vec![0.0, 0.0]),
+            position,
+            scale: animated(prop("s"), vector, vec![100.0, 100.0]),
+            // 3D layers store the z rotation in `rz`
+            rotation: animated(prop("r").or_else(|| prop("rz")), scalar, 0.0),
+            skew: animated(prop("sk"), scalar, 0.0),
+            skew_axis: animated(prop("sa"), scalar, 0.0),
+            opacity: animated(prop("o"), scalar, 100.0),
+        }
+    }
+
+    /// Matrix mapping local coordinates to the parent's
+    pub(super) fn matrix(&self, frame: f32) -> Affine2D {
+        let anchor = self.anchor.at(frame);
+        let [px, py] = match &self.position {
+            Position::Combined(p) => p.point_at(frame),
+            Position::Split(x, y) => [x.at(frame), y.at(frame)],
+        };
+        let scale = self.scale.at(frame);
+        let sx = scale.first().copied().unwrap_or(100.0) / 100.0;
+        let sy = scale.get(1).copied().unwrap_or(sx * 100.0) / 100.0;
+
+        let mut m = Affine2D::translation(px, py)
+            .then(&Affine2D::rotation(self.rotation.at(frame).to_radians()));
+        let skew = self.skew.at(frame);
+        if skew != 0.0 {
+            let axis = self.skew_axis.at(frame).to_radians();
+            m = m
+                .then(&Affine2D::rotation(-axis))
+                .then(&Affine2D::skew_x((-skew).to_radians()))
+                .then(&Affine2D::rotation(axis));
+        }
+        m.then(&Affine2D::scale(sx, sy))
+            .then(&Affine2D::translation(
+                -anchor.first().copied().unwrap_or(0.0),
+                -anchor.get(1).copied().unwrap_or(0.0),
+            ))
+    }
+
+    /// Opacity from 0 to 1
+    pub(super) fn opacity(&self, frame: f32) -> f32 {
+        (self.opacity.at(frame) / 100.0).clamp(0.0, 1.0)
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Masks
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum MaskMode {
+    Add,
+    Subtract,
+    Intersect,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct Mask {
+    pub mode: MaskMode,
+    pub inverted: bool,
+    pub shape: Animated<Bezier>,
+    pub opacity: Animated<f32>,
+}
+
+fn parse_mask(mask: &Value) -> Option<Mask> {
+    let mode = match mask.get("mode").and_then(Value::as_str).unwrap_or("a") {
+        "a" => MaskMode::Add,
+        "s" => MaskMode::Subtract,
+        "i" => MaskMode::Intersect,
+        // "n" (none) and the unsupported lighten/darken/difference modes
+        _ => return None,
+    };
+    Some(Mask {
+        mode,
+        inverted: mask.get("inv").and_then(Value::as_bool).unwrap_or(false),
+        shape: animated(mask.get("pt"), shape, Bezier::default()),
+        opacity: animated(mask.get("o"), scalar, 100.0),
+    })
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Shapes
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[derive(Clone, Debug)]
+pub(super) enum ShapeItem {
+    Group {
+        items: Vec<ShapeItem>,
+        transform: Option<Transform>,
+    },
+    Path {
+        shape: Animated<Bezier>,
+        reversed: bool,
+    },
+    Rect {
+        position: Animated<Vec<f32>>,
+        size: Animated<Vec<f32>>,
+        roundness: Animated<f32>,
+        reversed: bool,
+    },
+    Ellipse {
+        position: Animated<Vec<f32>>,
+        size: Animated<Vec<f32>>,
+        reversed: bool,
+    },
+    Star {
+        polygon: bool,
+        position: Animated<Vec<f32>>,
+        points: Animated<f32>,
+        rotation: Animated<f32>,
+        outer_radius: Animated<f32>,
+        inner_radius: Animated<f32>,
+        reversed: bool,
+    },
+    Fill {
+        color: Animated<Vec<f32>>,
+        opacity: Animated<f32>,
+    },
+    Stroke {
+        color: Animated<Vec<f32>>,
+        opacity: Animated<f32>,
+        style: StrokeStyle,
+    },
+    GradientFill {
+        gradient: GradientDef,
+        opacity: Animated<f32>,
+    },
+    GradientStroke {
+        gradient: GradientDef,
+        opacity: Animated<f32>,
+        style: StrokeStyle,
+    },
+    Trim {
+        start: Animated<f32>,
+        end: Animated<f32>,
+        offset: Animated<f32>,
+        /// Trim all shapes laid end to end instead of each on its own
+        sequential: bool,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct StrokeStyle {
+    pub width: Animated<f32>,
+    pub cap: LineCap,
+    pub join: LineJoin,
+    pub miter_limit: f32,
+    /// Alternating dash and gap lengths
+    pub dashes: Vec<Animated<f32>>,
+    pub dash_offset: Animated<f32>,
+}
+
+#[derive(Clone, Debug)]
+pub(super) struct GradientDef {
+    pub radial: bool,
+    pub start: Animated<Vec<f32>>,
+    pub end: Animated<Vec<f32>>,
+    /// Focal point offset toward `end`, in percent of the radius
+    pub highlight_length: Animated<f32>,
+    /// Focal point angle in degrees
+    pub highlight_angle: Animated<f32>,
+    /// Number of color stops in `colors`
+    pub stop_count: usize,
+    /// `[offset, r, g, b]` per color stop, then `[offset, alpha]` pairs
+    pub colors: Animated<Vec<f32>>,
+}
+
+fn parse_shapes(value: Option<&Value>) -> Vec<ShapeItem> {
+    value
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|item| item.get("hd").and_then(Value::as_bool) != Some(true))
+        .filter_map(parse_shape)
+        .collect()
+}
+
+fn parse_shape(item: &Value) -> Option<ShapeItem> {
+    let prop = |key: &str| item.get(key);
+    // Direction 3 runs the shape counter-clockwise
+    let reversed = item.get("d").and_then(Value::as_i64) == Some(3);
+
+    let shape = match item.get("ty")?.as_str()? {
+        "gr" => {
+            let items = item.get("it").and_then(Value::as_array);
+            let transform = items
+                .into_iter()
+                .flatten()
+                .find(|it| it.get("ty").and_then(Value::as_str) == Some("tr"))
+                .map(|tr| Transform::parse(Some(tr)));
+            ShapeItem::Group {
+                items: parse_shapes(prop("it")),
+                transform,
+            }
+        }
+        "sh" => ShapeItem::Path {
+            shape: animated(prop("ks"), shape, Bezier::default()),
+            reversed,
+        },
+        "rc" => ShapeItem::Rect {
+            position: animated(prop("p"), vector, vec![0.0, 0.0]),
+            size: animated(prop("s"), vector, vec![0.0, 0.0]),
+            roundness: animated(prop("r"), scalar, 0.0),
+            reversed,
+        },
+        "el" => ShapeItem::Ellipse {
+            position: animated(prop("p"), vector, vec![0.0, 0.0]),
+            size: animated(prop("s"), vector, vec![0.0, 0.0]),
+            reversed,
+        },
+        "sr" => ShapeItem::Star {
+            polygon: item.get("sy").and_then(Value::as_i64) == Some(2),
+            position: animated(prop("p"), vector, vec![0.0, 0.0]),
+            points: animated(prop("pt"), scalar, 5.0),
+            rotation: animated(prop("r"), scalar, 0.0),
+            outer_radius: animated(prop("or"), scalar, 0.0),
+            inner_radius: animated(prop("ir"), scalar, 0.0),
+            reversed,
+        },
+        "fl" => ShapeItem::Fill {
+            color: animated(prop("c"), vector, vec![0.0, 0.0, 0.0, 1.0]),
+            opacity: animated(prop("o"), scalar, 100.0),
+        },
+        "st" => ShapeItem::Stroke {
+            color: animated(prop("c"), vector, vec![0.0, 0.0, 0.0, 1.0]),
+            opacity: animated(prop("o"), scalar, 100.0),
+            style: parse_stroke_style(item),
+        },
+        "gf" => ShapeItem::GradientFill {
+            gradient: parse_gradient(item),
+            opacity: animated(prop("o"), scalar, 100.0),
+        },
+        "gs" => ShapeItem::GradientStroke {
+            gradient: parse_gradient(item),
+            opacity: animated(prop("o"), scalar, 100.0),
+            style: parse_stroke_style(item),
+        },
+        "tm" => ShapeItem::Trim {
+            start: animated(prop("s"), scalar, 0.0),
+            end: animated(prop("e"), scalar, 100.0),
+            offset: animated(prop("o"), scalar, 0.0),
+            sequential: item.get("m").and_then(Value::as_i64) == Some(2),
+        },
+        // Transforms are read by their group; merge paths, repeaters and
+        // other modifiers are not supported
+        _ => return None,
+    };
+    Some(shape)
+}
+
+fn parse_stroke_style(item: &Value) -> StrokeStyle {
+    let cap = match item.get("lc").and_then(Value::as_i64) {
+        Some(2) => LineCap::Round,
+        Some(3) => LineCap::Square,
+        _ => LineCap::Butt,
+    };
+    let join = match item.get("lj").and_then(Value::as_i64) {
+        Some(2) => LineJoin::Round,
+        Some(3) => LineJoin::Bevel,
+        _ => LineJoin::Miter,
+    };
+
+    let mut dashes = Vec::new();
+    let mut dash_offset = Animated::Static(0.0);
+    for dash in item
+        .get("d")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let value = animated(dash.get("v"), scalar, 0.0);
+        match dash.get("n").and_then(Value::as_str) {
+            Some("o") => dash_offset = value,
+            Some(_) => dashes.push(value),
+            None => {}
+        }
+    }
+
+    StrokeStyle {
+        width: animated(item.get("w"), scalar, 1.0),
+        cap,
+        join,
+        miter_limit: item
+            .get("ml")
+            .and_then(Value::as_f64)
+            .map_or(4.0, |ml| ml as f32),
+        dashes,
+        dash_offset,
+    }
+}
+
+fn parse_gradient(item: &Value) -> GradientDef {
+    let colors = item.get("g");
+    GradientDef {
+        radial: item.get("t").and_then(Value::as_i64) == Some(2),
+        start: animated(item.get("s"), vector, vec![0.0, 0.0]),
+        end: animated(item.get("e"), vector, vec![0.0, 0.0]),
+        highlight_length: animated(item.get("h"), scalar, 0.0),
+        highlight_angle: animated(item.get("a"), scalar, 0.0),
+        stop_count: colors
+            .and_then(|g| g.get("p"))
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as usize,
+        colors: animated(colors.and_then(|g| g.get("k")), vector, Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            LottieAnimation::from_json("{"),
+            Err(LottieError::Json(_))
+        ));
+        assert!(matches!(
+            LottieAnimation::from_json(r#"{"fr": 30, "w": 10, "h": 10, "ip": 0, "op": 10}"#),
+            Err(LottieError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_transform_matrix() {
+        let transform = Transform::parse(Some(&serde_json::json!({
+            "a": {"a": 0, "k": [10, 10]},
+            "p": {"a": 0, "k": [100, 50]},
+            "s": {"a": 0, "k": [200, 200]},
+            "r": {"a": 0, "k": 90}
+        })));
+        let m = transform.matrix(0.0);
+        // The anchor lands on the position
+        let anchor = m.transform_point(blinc_core::Point::new(10.0, 10.0));
+        assert!((anchor.x - 100.0).abs() < 1e-3 && (anchor.y - 50.0).abs() < 1e-3);
+        // One unit right of the anchor is scaled by 2 and rotated clockwise
+        let right = m.transform_point(blinc_core::Point::new(11.0, 10.0));
+        assert!((right.x - 100.0).abs() < 1e-3 && (right.y - 52.0).abs() < 1e-3);
+    }
+}
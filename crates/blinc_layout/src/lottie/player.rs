@@ -0,0 +1,227 @@
+//! Playback control bound to the animation scheduler
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use blinc_animation::{AnimatedTimeline, SchedulerHandle, TimelineEntryId};
+
+use super::model::LottieAnimation;
+
+/// Plays a [`LottieAnimation`] on the animation scheduler
+///
+/// The playhead is a scheduler timeline, so it advances with every other
+/// animation (including under a manual clock) and keeps frames coming while
+/// playing. Clones share the same playhead.
+///
+/// # Example
+///
+/// ```ignore
+/// let animation = LottieAnimation::from_json(include_str!("loader.json"))?;
+/// let player = LottiePlayer::new(ctx.animation_handle(), animation);
+/// player.set_looping(true);
+/// player.play();
+///
+/// div().child(lottie(&player).size(120.0, 120.0))
+/// ```
+#[derive(Clone)]
+pub struct LottiePlayer {
+    inner: Rc<RefCell<PlayerState>>,
+}
+
+struct PlayerState {
+    animation: Arc<LottieAnimation>,
+    handle: SchedulerHandle,
+    /// `None` when the scheduler has been dropped
+    timeline: Option<(AnimatedTimeline, TimelineEntryId)>,
+    /// Frames played, in playback order (`end` may be before `start`)
+    segment: (f32, f32),
+    looping: bool,
+    speed: f32,
+    /// Playhead when there is no timeline
+    frame: f32,
+}
+
+impl LottiePlayer {
+    /// Create a paused player at the first frame
+    pub fn new(handle: SchedulerHandle, animation: impl Into<Arc<LottieAnimation>>) -> Self {
+        let animation = animation.into();
+        let segment = (animation.in_point(), animation.out_point());
+        let mut state = PlayerState {
+            animation,
+            handle,
+            timeline: None,
+            segment,
+            looping: false,
+            speed: 1.0,
+            frame: segment.0,
+        };
+        state.rebuild(segment.0, false);
+        Self {
+            inner: Rc::new(RefCell::new(state)),
+        }
+    }
+
+    /// The animation being played
+    pub fn animation(&self) -> Arc<LottieAnimation> {
+        self.inner.borrow().animation.clone()
+    }
+
+    /// Start or resume playback
+    ///
+    /// A player that finished its segment starts over.
+    pub fn play(&self) {
+        let mut state = self.inner.borrow_mut();
+        let finished = state
+            .timeline
+            .as_ref()
+            .is_some_and(|(timeline, _)| timeline.progress() >= 1.0);
+        if finished {
+            let start = state.segment.0;
+            state.rebuild(start, true);
+        } else if let Some((timeline, _)) = &state.timeline {
+            timeline.resume();
+        }
+    }
+
+    /// Pause at the current frame
+    pub fn pause(&self) {
+        if let Some((timeline, _)) = &self.inner.borrow().timeline {
+            timeline.pause();
+        }
+    }
+
+    /// Pause and return to the start of the segment
+    pub fn stop(&self) {
+        let mut state = self.inner.borrow_mut();
+        let start = state.segment.0;
+        state.rebuild(start, false);
+    }
+
+    /// Jump to a frame within the segment, keeping the play state
+    pub fn seek(&self, frame: f32) {
+        let mut state = self.inner.borrow_mut();
+        let playing = state.is_playing();
+        state.rebuild(frame, playing);
+    }
+
+    /// Jump to a point in the segment (0 to 1), keeping the play state
+    pub fn seek_progress(&self, progress: f32) {
+        let (start, end) = self.segment();
+        self.seek(start + (end - start) * progress.clamp(0.0, 1.0));
+    }
+
+    /// Play only the frames from `start` to `end`, starting at `start`
+    ///
+    /// `end` may be before `start` to play backwards. Frames are clamped to
+    /// the animation's range.
+    pub fn set_segment(&self, start: f32, end: f32) {
+        let mut state = self.inner.borrow_mut();
+        let (first, last) = (state.animation.in_point(), state.animation.out_point());
+        let start = start.clamp(first, last);
+        state.segment = (start, end.clamp(first, last));
+        let playing = state.is_playing();
+        state.rebuild(start, playing);
+    }
+
+    /// Play the whole animation again after [`set_segment`](Self::set_segment)
+    pub fn clear_segment(&self) {
+        let (first, last) = {
+            let animation = self.animation();
+            (animation.in_point(), animation.out_point())
+        };
+        self.set_segment(first, last);
+    }
+
+    /// Restart the segment whenever it ends
+    pub fn set_looping(&self, looping: bool) {
+        let mut state = self.inner.borrow_mut();
+        state.looping = looping;
+        if let Some((timeline, _)) = &mut state.timeline {
+            timeline.set_loop(if looping { -1 } else { 1 });
+        }
+    }
+
+    /// Playback rate (1.0 is the animation's own frame rate)
+    pub fn set_speed(&self, speed: f32) {
+        let mut state = self.inner.borrow_mut();
+        state.speed = speed.max(0.0);
+        let speed = state.speed;
+        if let Some((timeline, _)) = &mut state.timeline {
+            timeline.set_playback_rate(speed);
+        }
+    }
+
+    /// Current frame
+    pub fn frame(&self) -> f32 {
+        self.inner.borrow().frame()
+    }
+
+    /// Position within the segment (0 to 1)
+    pub fn progress(&self) -> f32 {
+        let state = self.inner.borrow();
+        let (start, end) = state.segment;
+        if end == start {
+            return 1.0;
+        }
+        ((state.frame() - start) / (end - start)).clamp(0.0, 1.0)
+    }
+
+    /// Frames being played
+    pub fn segment(&self) -> (f32, f32) {
+        self.inner.borrow().segment
+    }
+
+    /// Whether the playhead is moving
+    pub fn is_playing(&self) -> bool {
+        self.inner.borrow().is_playing()
+    }
+
+    /// Whether playback restarts at the end of the segment
+    pub fn is_looping(&self) -> bool {
+        self.inner.borrow().looping
+    }
+}
+
+impl PlayerState {
+    fn frame(&self) -> f32 {
+        self.timeline
+            .as_ref()
+            .and_then(|(timeline, entry)| timeline.get(*entry))
+            .unwrap_or(self.frame)
+    }
+
+    fn is_playing(&self) -> bool {
+        self.timeline
+            .as_ref()
+            .is_some_and(|(timeline, _)| timeline.is_playing())
+    }
+
+    /// Replace the timeline with one for the current segment, positioned at `frame`
+    fn rebuild(&mut self, frame: f32, playing: bool) {
+        let (start, end) = self.segment;
+        let (low, high) = (start.min(end), start.max(end));
+        let frame = frame.clamp(low, high);
+        self.frame = frame;
+
+        // Drop the old timeline first so it is unregistered
+        self.timeline = None;
+        if !self.handle.is_alive() {
+            return;
+        }
+
+        let frames = (end - start).abs();
+        let duration_ms = (frames / self.animation.frame_rate() * 1000.0).round() as u32;
+        let mut timeline = AnimatedTimeline::new(self.handle.clone());
+        let entry = timeline.add(0, duration_ms.max(1), start, end);
+        timeline.set_loop(if self.looping { -1 } else { 1 });
+        timeline.set_playback_rate(self.speed);
+        if frames > 0.0 {
+            timeline.seek((frame - start).abs() / frames * duration_ms as f32);
+        }
+        if playing {
+            timeline.resume();
+        }
+        self.timeline = Some((timeline, entry));
+    }
+}
@@ -0,0 +1,546 @@
+//! Drawing frames through `DrawContext`
+
+use std::ops::Range;
+
+use blinc_core::{
+    Affine2D, Brush, ClipShape, Color, DrawContext, Gradient, GradientStop, Point, Rect, Stroke,
+};
+
+use super::geometry::{self, Contour};
+use super::model::{
+    GradientDef, Layer, LayerContent, LottieAnimation, MaskMode, ShapeItem, StrokeStyle,
+};
+
+/// Precomps nested deeper than this are not drawn (guards against cycles)
+const MAX_DEPTH: usize = 16;
+
+/// Points per bezier segment when flattening mask outlines into polygons
+const MASK_STEPS: usize = 8;
+
+impl LottieAnimation {
+    /// Draw `frame` scaled to fit a `width` x `height` box, centered
+    ///
+    /// Frames are clamped to the animation's range; fractional frames
+    /// interpolate between keyframes.
+    pub fn render(&self, ctx: &mut dyn DrawContext, frame: f32, width: f32, height: f32) {
+        if self.width <= 0.0 || self.height <= 0.0 || width <= 0.0 || height <= 0.0 {
+            return;
+        }
+        let scale = (width / self.width).min(height / self.height);
+        let x = (width - self.width * scale) / 2.0;
+        let y = (height - self.height * scale) / 2.0;
+        let fit = Affine2D::translation(x, y).then(&Affine2D::scale(scale, scale));
+
+        // The out point is exclusive; hold the last frame instead of going blank
+        let last = (self.out_point - 1e-3).max(self.in_point);
+        let frame = frame.clamp(self.in_point, last);
+
+        ctx.push_clip(ClipShape::rect(Rect::new(
+            x,
+            y,
+            self.width * scale,
+            self.height * scale,
+        )));
+        let mut renderer = Renderer {
+            animation: self,
+            ctx,
+            viewport: Rect::new(0.0, 0.0, width, height),
+        };
+        renderer.layers(&self.layers, frame, fit, 0);
+        renderer.ctx.pop_clip();
+    }
+}
+
+struct Renderer<'a> {
+    animation: &'a LottieAnimation,
+    ctx: &'a mut dyn DrawContext,
+    /// Area covered by inverted and subtracted masks
+    viewport: Rect,
+}
+
+/// A fill or stroke over a range of shape slots
+struct Draw {
+    slots: Range<usize>,
+    brush: Brush,
+    stroke: Option<Stroke>,
+}
+
+impl Renderer<'_> {
+    /// Draw layers bottom to top (Lottie lists the topmost layer first)
+    fn layers(&mut self, layers: &[Layer], frame: f32, parent: Affine2D, depth: usize) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        for layer in layers.iter().rev() {
+            if !layer.is_visible_at(frame) {
+                continue;
+            }
+            let local = layer.local_frame(frame);
+            let opacity = layer.transform.opacity(local);
+            if opacity <= 0.0 {
+                continue;
+            }
+            let matrix = parent.then(&layer_matrix(layers, layer, frame, 0));
+
+            if opacity < 1.0 {
+                self.ctx.push_opacity(opacity);
+            }
+            let clips = self.push_masks(layer, local, &matrix);
+
+            match &layer.content {
+                LayerContent::Null => {}
+                LayerContent::Shape(items) => self.shapes(items, local, &matrix),
+                LayerContent::Solid {
+                    color,
+                    width,
+                    height,
+                } => {
+                    let rect = geometry::rect([width / 2.0, height / 2.0], [*width, *height], 0.0);
+                    let path = geometry::to_path(&[rect.transform(&matrix)]);
+                    self.ctx.fill_path(&path, Brush::Solid(*color));
+                }
+                LayerContent::Precomp {
+                    asset,
+                    width,
+                    height,
+                    time_remap,
+                } => {
+                    if let Some(layers) = self.animation.assets.get(asset) {
+                        let inner = match time_remap {
+                            Some(seconds) => seconds.at(local) * self.animation.frame_rate,
+                            None => local,
+                        };
+                        let bounds =
+                            geometry::rect([width / 2.0, height / 2.0], [*width, *height], 0.0);
+                        self.ctx
+                            .push_clip(ClipShape::Polygon(bounds.transform(&matrix).flatten(1)));
+                        self.layers(layers, inner, matrix, depth + 1);
+                        self.ctx.pop_clip();
+                    }
+                }
+            }
+
+            for _ in 0..clips {
+                self.ctx.pop_clip();
+            }
+            if opacity < 1.0 {
+                self.ctx.pop_opacity();
+            }
+        }
+    }
+
+    /// Clip to the layer's masks, returning the number of clips pushed
+    ///
+    /// Added masks are merged into one polygon, subtracted and inverted masks
+    /// are cut out of it, and intersected masks are pushed as further clips.
+    fn push_masks(&mut self, layer: &Layer, frame: f32, matrix: &Affine2D) -> usize {
+        let mut adds = Vec::new();
+        let mut holes = Vec::new();
+        let mut intersects = Vec::new();
+        for mask in &layer.masks {
+            if mask.opacity.at(frame) <= 0.0 {
+                continue;
+            }
+            let contour = mask.shape.at(frame).to_contour().transform(matrix);
+            if contour.segments.is_empty() {
+                continue;
+            }
+            match (mask.mode, mask.inverted) {
+                (MaskMode::Add, false) => adds.push(contour),
+                (MaskMode::Add, true)
+                | (MaskMode::Subtract, false)
+                | (MaskMode::Intersect, true) => holes.push(contour),
+                (MaskMode::Subtract, true) | (MaskMode::Intersect, false) => {
+                    intersects.push(contour)
+                }
+            }
+        }
+
+        let mut pushed = 0;
+        if !adds.is_empty() || !holes.is_empty() {
+            let outlines = if adds.is_empty() {
+                vec![geometry::rect(
+                    [
+                        self.viewport.x() + self.viewport.width() / 2.0,
+                        self.viewport.y() + self.viewport.height() / 2.0,
+                    ],
+                    [self.viewport.width(), self.viewport.height()],
+                    0.0,
+                )]
+            } else {
+                adds
+            };
+            self.ctx
+                .push_clip(ClipShape::Polygon(keyhole_polygon(&outlines, &holes)));
+            pushed += 1;
+        }
+        for contour in intersects {
+            self.ctx
+                .push_clip(ClipShape::Polygon(keyhole_polygon(&[contour], &[])));
+            pushed += 1;
+        }
+        pushed
+    }
+
+    fn shapes(&mut self, items: &[ShapeItem], frame: f32, matrix: &Affine2D) {
+        let mut slots = Vec::new();
+        let mut draws = Vec::new();
+        collect(items, frame, matrix, 1.0, &mut slots, &mut draws);
+
+        // Items listed first are drawn on top
+        for draw in draws.iter().rev() {
+            let contours: Vec<Contour> = slots[draw.slots.clone()]
+                .iter()
+                .flatten()
+                .cloned()
+                .collect();
+            if contours.is_empty() {
+                continue;
+            }
+            let path = geometry::to_path(&contours);
+            match &draw.stroke {
+                Some(stroke) => self.ctx.stroke_path(&path, stroke, draw.brush.clone()),
+                None => self.ctx.fill_path(&path, draw.brush.clone()),
+            }
+        }
+    }
+}
+
+/// Layer matrix including its parent chain, relative to the containing composition
+fn layer_matrix(layers: &[Layer], layer: &Layer, frame: f32, depth: usize) -> Affine2D {
+    let own = layer.transform.matrix(layer.local_frame(frame));
+    let parent = layer
+        .parent
+        .filter(|_| depth < MAX_DEPTH)
+        .and_then(|parent| layers.iter().find(|l| l.index == Some(parent)));
+    match parent {
+        Some(parent) => layer_matrix(layers, parent, frame, depth + 1).then(&own),
+        None => own,
+    }
+}
+
+/// Gather geometry into slots (one per shape) and paints into draws
+///
+/// A paint covers every shape before it in its group, including shapes in
+/// nested groups. Trim paths rewrite the slots they cover, so paints see
+/// the trimmed geometry wherever they appear.
+fn collect(
+    items: &[ShapeItem],
+    frame: f32,
+    matrix: &Affine2D,
+    opacity: f32,
+    slots: &mut Vec<Vec<Contour>>,
+    draws: &mut Vec<Draw>,
+) {
+    let group_start = slots.len();
+    let shape = |slots: &mut Vec<Vec<Contour>>, contour: Contour, reversed: bool| {
+        let contour = if reversed {
+            contour.reversed()
+        } else {
+            contour
+        };
+        slots.push(vec![contour.transform(matrix)]);
+    };
+
+    for item in items {
+        match item {
+            ShapeItem::Group { items, transform } => {
+                let (matrix, opacity) = match transform {
+                    Some(t) => (matrix.then(&t.matrix(frame)), opacity * t.opacity(frame)),
+                    None => (*matrix, opacity),
+                };
+                if opacity > 0.0 {
+                    collect(items, frame, &matrix, opacity, slots, draws);
+                }
+            }
+            ShapeItem::Path {
+                shape: bezier,
+                reversed,
+            } => {
+                shape(slots, bezier.at(frame).to_contour(), *reversed);
+            }
+            ShapeItem::Rect {
+                position,
+                size,
+                roundness,
+                reversed,
+            } => {
+                let contour = geometry::rect(
+                    xy(&position.at(frame)),
+                    xy(&size.at(frame)),
+                    roundness.at(frame),
+                );
+                shape(slots, contour, *reversed);
+            }
+            ShapeItem::Ellipse {
+                position,
+                size,
+                reversed,
+            } => {
+                let contour = geometry::ellipse(xy(&position.at(frame)), xy(&size.at(frame)));
+                shape(slots, contour, *reversed);
+            }
+            ShapeItem::Star {
+                polygon,
+                position,
+                points,
+                rotation,
+                outer_radius,
+                inner_radius,
+                reversed,
+            } => {
+                let contour = geometry::star(
+                    xy(&position.at(frame)),
+                    points.at(frame),
+                    rotation.at(frame),
+                    outer_radius.at(frame),
+                    (!polygon).then(|| inner_radius.at(frame)),
+                );
+                shape(slots, contour, *reversed);
+            }
+            ShapeItem::Fill {
+                color,
+                opacity: fill_opacity,
+            } => {
+                let alpha = opacity * percent(fill_opacity.at(frame));
+                draws.push(Draw {
+                    slots: group_start..slots.len(),
+                    brush: Brush::Solid(color_from(&color.at(frame), alpha)),
+                    stroke: None,
+                });
+            }
+            ShapeItem::Stroke {
+                color,
+                opacity: stroke_opacity,
+                style,
+            } => {
+                let alpha = opacity * percent(stroke_opacity.at(frame));
+                if let Some(stroke) = stroke_from(style, frame, matrix) {
+                    draws.push(Draw {
+                        slots: group_start..slots.len(),
+                        brush: Brush::Solid(color_from(&color.at(frame), alpha)),
+                        stroke: Some(stroke),
+                    });
+                }
+            }
+            ShapeItem::GradientFill {
+                gradient,
+                opacity: fill_opacity,
+            } => {
+                let alpha = opacity * percent(fill_opacity.at(frame));
+                draws.push(Draw {
+                    slots: group_start..slots.len(),
+                    brush: Brush::Gradient(gradient_from(gradient, frame, matrix, alpha)),
+                    stroke: None,
+                });
+            }
+            ShapeItem::GradientStroke {
+                gradient,
+                opacity: stroke_opacity,
+                style,
+            } => {
+                let alpha = opacity * percent(stroke_opacity.at(frame));
+                if let Some(stroke) = stroke_from(style, frame, matrix) {
+                    draws.push(Draw {
+                        slots: group_start..slots.len(),
+                        brush: Brush::Gradient(gradient_from(gradient, frame, matrix, alpha)),
+                        stroke: Some(stroke),
+                    });
+                }
+            }
+            ShapeItem::Trim {
+                start,
+                end,
+                offset,
+                sequential,
+            } => {
+                let covered = &mut slots[group_start..];
+                let (owners, contours): (Vec<usize>, Vec<Contour>) = covered
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(slot, contours)| contours.iter().map(move |c| (slot, c.clone())))
+                    .unzip();
+                let trimmed = geometry::trim(
+                    &contours,
+                    percent(start.at(frame)),
+                    percent(end.at(frame)),
+                    offset.at(frame) / 360.0,
+                    *sequential,
+                );
+                covered.iter_mut().for_each(Vec::clear);
+                for (index, contour) in trimmed {
+                    covered[owners[index]].push(contour);
+                }
+            }
+        }
+    }
+}
+
+fn percent(value: f32) -> f32 {
+    (value / 100.0).clamp(0.0, 1.0)
+}
+
+fn xy(value: &[f32]) -> [f32; 2] {
+    [
+        value.first().copied().unwrap_or(0.0),
+        value.get(1).copied().unwrap_or(0.0),
+    ]
+}
+
+fn color_from(rgba: &[f32], opacity: f32) -> Color {
+    let channel = |i: usize| rgba.get(i).copied().unwrap_or(0.0).clamp(0.0, 1.0);
+    let alpha = rgba.get(3).copied().unwrap_or(1.0).clamp(0.0, 1.0);
+    Color::rgba(channel(0), channel(1), channel(2), alpha * opacity)
+}
+
+/// Uniform scale factor of a matrix, for stroke widths and radii
+fn matrix_scale(m: &Affine2D) -> f32 {
+    let [a, b, c, d, _, _] = m.elements;
+    (a * d - b * c).abs().sqrt()
+}
+
+fn stroke_from(style: &StrokeStyle, frame: f32, matrix: &Affine2D) -> Option<Stroke> {
+    let scale = matrix_scale(matrix);
+    let width = style.width.at(frame) * scale;
+    if width <= 0.0 {
+        return None;
+    }
+
+    let mut stroke = Stroke::new(width).with_cap(style.cap).with_join(style.join);
+    stroke.miter_limit = style.miter_limit;
+
+    let mut dashes: Vec<f32> = style
+        .dashes
+        .iter()
+        .map(|dash| dash.at(frame).max(0.0) * scale)
+        .collect();
+    if dashes.iter().sum::<f32>() > 0.0 {
+        // An odd pattern repeats to make dash/gap pairs, as in SVG
+        if dashes.len() % 2 == 1 {
+            dashes.extend_from_within(..);
+        }
+        stroke = stroke.with_dash(dashes, style.dash_offset.at(frame) * scale);
+    }
+    Some(stroke)
+}
+
+fn gradient_from(def: &GradientDef, frame: f32, matrix: &Affine2D, opacity: f32) -> Gradient {
+    let values = def.colors.at(frame);
+    let color_values = def.stop_count * 4;
+    let alphas: Vec<(f32, f32)> = values
+        .get(color_values..)
+        .unwrap_or_default()
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+
+    let stops: Vec<GradientStop> = values
+        .get(..color_values)
+        .unwrap_or(&values)
+        .chunks_exact(4)
+        .map(|stop| {
+            let alpha = alpha_at(&alphas, stop[0]);
+            GradientStop::new(
+                stop[0],
+                Color::rgba(stop[1], stop[2], stop[3], alpha * opacity),
+            )
+        })
+        .collect();
+
+    let start = xy(&def.start.at(frame));
+    let end = xy(&def.end.at(frame));
+    let start_point = matrix.transform_point(Point::new(start[0], start[1]));
+    let end_point = matrix.transform_point(Point::new(end[0], end[1]));
+
+    if !def.radial {
+        return Gradient::linear_with_stops(start_point, end_point, stops);
+    }
+
+    let radius = (end_point.x - start_point.x).hypot(end_point.y - start_point.y);
+    let highlight = percent(def.highlight_length.at(frame).abs()) * radius;
+    let gradient = Gradient::radial_with_stops(start_point, radius, stops);
+    if highlight <= 0.0 {
+        return gradient;
+    }
+
+    let angle = (end_point.y - start_point.y).atan2(end_point.x - start_point.x)
+        + def.highlight_angle.at(frame).to_radians();
+    let focal = Point::new(
+        start_point.x + highlight * angle.cos(),
+        start_point.y + highlight * angle.sin(),
+    );
+    match gradient {
+        Gradient::Radial {
+            center,
+            radius,
+            stops,
+            space,
+            spread,
+            ..
+        } => Gradient::Radial {
+            center,
+            radius,
+            focal: Some(focal),
+            stops,
+            space,
+            spread,
+        },
+        other => other,
+    }
+}
+
+/// Alpha at `offset` from `(offset, alpha)` stops, linearly interpolated
+fn alpha_at(alphas: &[(f32, f32)], offset: f32) -> f32 {
+    let Some(&(first_offset, first_alpha)) = alphas.first() else {
+        return 1.0;
+    };
+    if offset <= first_offset {
+        return first_alpha;
+    }
+    for pair in alphas.windows(2) {
+        let ((a_offset, a), (b_offset, b)) = (pair[0], pair[1]);
+        if offset <= b_offset {
+            let t = if b_offset > a_offset {
+                (offset - a_offset) / (b_offset - a_offset)
+            } else {
+                1.0
+            };
+            return a + (b - a) * t;
+        }
+    }
+    alphas.last().map_or(1.0, |&(_, alpha)| alpha)
+}
+
+/// A single polygon covering the union of `outlines` minus `holes`
+///
+/// Polygon clips are one outline, so separate outlines and holes are joined
+/// to the first vertex with zero-width bridges. Outlines run clockwise and
+/// holes counter-clockwise, so the result is the same under either fill rule
+/// as long as the shapes don't overlap.
+fn keyhole_polygon(outlines: &[Contour], holes: &[Contour]) -> Vec<Point> {
+    let oriented = |contour: &Contour, clockwise: bool| {
+        let contour = if (contour.signed_area() > 0.0) == clockwise {
+            contour.clone()
+        } else {
+            contour.reversed()
+        };
+        contour.flatten(MASK_STEPS)
+    };
+
+    let mut rings = outlines
+        .iter()
+        .map(|c| oriented(c, true))
+        .chain(holes.iter().map(|c| oriented(c, false)))
+        .filter(|ring| !ring.is_empty());
+    let Some(mut polygon) = rings.next() else {
+        return Vec::new();
+    };
+    let anchor = polygon[0];
+    for ring in rings {
+        polygon.push(anchor);
+        polygon.push(ring[0]);
+        polygon.extend_from_slice(&ring[1..]);
+        polygon.push(ring[0]);
+    }
+    polygon
+}
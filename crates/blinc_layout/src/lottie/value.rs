@@ -0,0 +1,360 @@
+//! Animated properties and keyframe evaluation
+
+use serde_json::Value;
+
+use super::geometry::Bezier;
+
+/// A value that can be interpolated between keyframes
+pub(super) trait Lerp: Clone {
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for Vec<f32> {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self.iter()
+            .zip(to.iter())
+            .map(|(a, b)| a + (b - a) * t)
+            .collect()
+    }
+}
+
+/// One keyframe of an animated property
+#[derive(Clone, Debug)]
+pub(super) struct Keyframe<T> {
+    /// Frame the keyframe starts at
+    time: f32,
+    /// Value at `time`
+    start: T,
+    /// Value at the next keyframe (legacy files store it inline as `e`)
+    end: Option<T>,
+    /// Hold the start value until the next keyframe
+    hold: bool,
+    /// Outgoing bezier handle of the easing curve
+    ease_out: [f32; 2],
+    /// Incoming bezier handle of the easing curve
+    ease_in: [f32; 2],
+    /// Spatial tangents for motion paths, relative to the start and end values
+    tangents: Option<([f32; 2], [f32; 2])>,
+}
+
+/// A property that is either static or keyframed
+#[derive(Clone, Debug)]
+pub(super) enum Animated<T> {
+    Static(T),
+    Keyframes(Vec<Keyframe<T>>),
+}
+
+impl<T: Lerp> Animated<T> {
+    /// Value at `frame`
+    pub(super) fn at(&self, frame: f32) -> T {
+        let keyframes = match self {
+            Animated::Static(value) => return value.clone(),
+            Animated::Keyframes(keyframes) => keyframes,
+        };
+
+        let first = &keyframes[0];
+        if frame <= first.time || keyframes.len() == 1 {
+            return first.start.clone();
+        }
+
+        // Index of the last keyframe at or before `frame`
+        let index = keyframes
+            .iter()
+            .rposition(|kf| kf.time <= frame)
+            .unwrap_or(0);
+        let current = &keyframes[index];
+        let Some(next) = keyframes.get(index + 1) else {
+            return current.start.clone();
+        };
+
+        if current.hold || next.time <= current.time {
+            return current.start.clone();
+        }
+
+        let end = current.end.as_ref().unwrap_or(&next.start);
+        let linear = (frame - current.time) / (next.time - current.time);
+        let t = cubic_bezier_ease(current.ease_out, current.ease_in, linear);
+        current.start.lerp(end, t)
+    }
+
+    /// Whether the property changes over time
+    pub(super) fn is_animated(&self) -> bool {
+        matches!(self, Animated::Keyframes(keyframes) if keyframes.len() > 1)
+    }
+}
+
+impl Animated<Vec<f32>> {
+    /// Value at `frame`, following the motion path for spatial properties
+    pub(super) fn point_at(&self, frame: f32) -> [f32; 2] {
+        if let Animated::Keyframes(keyframes) = self {
+            let index = keyframes.iter().rposition(|kf| kf.time <= frame);
+            if let Some(index) = index {
+                let current = &keyframes[index];
+                if let (Some(next), Some((out_tangent, in_tangent)), false) =
+                    (keyframes.get(index + 1), current.tangents, current.hold)
+                {
+                    let has_path = out_tangent != [0.0, 0.0] || in_tangent != [0.0, 0.0];
+                    if has_path && next.time > current.time {
+                        let end = current.end.as_ref().unwrap_or(&next.start);
+                        let from = xy(&current.start);
+                        let to = xy(end);
+                        let linear = (frame - current.time) / (next.time - current.time);
+                        let t = cubic_bezier_ease(current.ease_out, current.ease_in, linear);
+                        return cubic_point(
+                            from,
+                            [from[0] + out_tangent[0], from[1] + out_tangent[1]],
+                            [to[0] + in_tangent[0], to[1] + in_tangent[1]],
+                            to,
+                            t,
+                        );
+                    }
+                }
+            }
+        }
+        xy(&self.at(frame))
+    }
+}
+
+fn xy(value: &[f32]) -> [f32; 2] {
+    [
+        value.first().copied().unwrap_or(0.0),
+        value.get(1).copied().unwrap_or(0.0),
+    ]
+}
+
+fn cubic_point(p0: [f32; 2], p1: [f32; 2], p2: [f32; 2], p3: [f32; 2], t: f32) -> [f32; 2] {
+    let mt = 1.0 - t;
+    let a = mt * mt * mt;
+    let b = 3.0 * mt * mt * t;
+    let c = 3.0 * mt * t * t;
+    let d = t * t * t;
+    [
+        a * p0[0] + b * p1[0] + c * p2[0] + d * p3[0],
+        a * p0[1] + b * p1[1] + c * p2[1] + d * p3[1],
+    ]
+}
+
+/// Evaluate a CSS-style cubic bezier easing curve at `x`
+///
+/// The curve runs from (0, 0) to (1, 1) with handles `p1` and `p2`.
+pub(super) fn cubic_bezier_ease(p1: [f32; 2], p2: [f32; 2], x: f32) -> f32 {
+    let x = x.clamp(0.0, 1.0);
+    if p1[0] == p1[1] && p2[0] == p2[1] {
+        return x;
+    }
+
+    let bezier = |a: f32, b: f32, t: f32| {
+        let mt = 1.0 - t;
+        3.0 * mt * mt * t * a + 3.0 * mt * t * t * b + t * t * t
+    };
+    let slope = |a: f32, b: f32, t: f32| {
+        let mt = 1.0 - t;
+        3.0 * mt * mt * a + 6.0 * mt * t * (b - a) + 3.0 * t * t * (1.0 - b)
+    };
+
+    // Newton iterations, falling back to bisection when the slope is flat
+    let mut t = x;
+    for _ in 0..8 {
+        let error = bezier(p1[0], p2[0], t) - x;
+        if error.abs() < 1e-5 {
+            return bezier(p1[1], p2[1], t);
+        }
+        let d = slope(p1[0], p2[0], t);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        t = (t - error / d).clamp(0.0, 1.0);
+    }
+
+    let (mut lo, mut hi) = (0.0f32, 1.0f32);
+    t = x;
+    for _ in 0..32 {
+        let value = bezier(p1[0], p2[0], t);
+        if (value - x).abs() < 1e-5 {
+            break;
+        }
+        if value < x {
+            lo = t;
+        } else {
+            hi = t;
+        }
+        t = (lo + hi) / 2.0;
+    }
+    bezier(p1[1], p2[1], t)
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Parsing
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Parse an animated property (`{"a": 0|1, "k": …}`)
+///
+/// Missing or malformed properties fall back to `default`.
+pub(super) fn animated<T: Lerp>(
+    value: Option<&Value>,
+    parse: fn(&Value) -> Option<T>,
+    default: T,
+) -> Animated<T> {
+    let Some(k) = value.and_then(|v| v.get("k")) else {
+        return Animated::Static(default);
+    };
+
+    let keyframes = k
+        .as_array()
+        .filter(|items| items.first().is_some_and(|first| first.get("t").is_some()));
+    let Some(keyframes) = keyframes else {
+        return Animated::Static(parse(k).unwrap_or(default));
+    };
+
+    let parsed: Vec<Keyframe<T>> = keyframes
+        .iter()
+        .filter_map(|kf| {
+            let time = kf.get("t")?.as_f64()? as f32;
+            // The last keyframe of some exporters only carries a time
+            let start = kf.get("s").and_then(parse);
+            Some((time, start, kf))
+        })
+        .scan(None::<T>, |previous_end, (time, start, kf)| {
+            // Legacy files put the final value in the previous keyframe's `e`
+            let start = start
+                .or_else(|| previous_end.clone())
+                .unwrap_or_else(|| default.clone());
+            let end = kf.get("e").and_then(parse);
+            *previous_end = end.clone();
+            Some(Keyframe {
+                time,
+                start,
+                end,
+                hold: kf.get("h").and_then(Value::as_f64).unwrap_or(0.0) != 0.0,
+                ease_out: handle(kf.get("o")).unwrap_or([0.0, 0.0]),
+                ease_in: handle(kf.get("i")).unwrap_or([1.0, 1.0]),
+                tangents: match (point(kf.get("to")), point(kf.get("ti"))) {
+                    (Some(to), Some(ti)) => Some((to, ti)),
+                    _ => None,
+                },
+            })
+        })
+        .collect();
+
+    if parsed.is_empty() {
+        Animated::Static(default)
+    } else {
+        Animated::Keyframes(parsed)
+    }
+}
+
+/// Easing handle `{"x": [..] | n, "y": [..] | n}`, using the first dimension
+fn handle(value: Option<&Value>) -> Option<[f32; 2]> {
+    let value = value?;
+    Some([scalar(value.get("x")?)?, scalar(value.get("y")?)?])
+}
+
+fn point(value: Option<&Value>) -> Option<[f32; 2]> {
+    let items = value?.as_array()?;
+    Some([
+        items.first()?.as_f64()? as f32,
+        items.get(1)?.as_f64()? as f32,
+    ])
+}
+
+/// A number, or the first element of an array of numbers
+pub(super) fn scalar(value: &Value) -> Option<f32> {
+    match value {
+        Value::Number(n) => n.as_f64().map(|n| n as f32),
+        Value::Array(items) => items.first().and_then(Value::as_f64).map(|n| n as f32),
+        _ => None,
+    }
+}
+
+/// An array of numbers, or a single number
+pub(super) fn vector(value: &Value) -> Option<Vec<f32>> {
+    match value {
+        Value::Number(n) => n.as_f64().map(|n| vec![n as f32]),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_f64().map(|n| n as f32))
+            .collect(),
+        _ => None,
+    }
+}
+
+/// A bezier shape, bare or wrapped in a one-element array (as in keyframes)
+pub(super) fn shape(value: &Value) -> Option<Bezier> {
+    let value = match value {
+        Value::Array(items) => items.first()?,
+        other => other,
+    };
+    let points = |key: &str| -> Option<Vec<[f32; 2]>> {
+        value
+            .get(key)?
+            .as_array()?
+            .iter()
+            .map(|p| point(Some(p)))
+            .collect()
+    };
+
+    let vertices = points("v")?;
+    let count = vertices.len();
+    let tangents = |key| {
+        let mut tangents = points(key).unwrap_or_default();
+        tangents.resize(count, [0.0, 0.0]);
+        tangents
+    };
+    Some(Bezier {
+        closed: value.get("c").and_then(Value::as_bool).unwrap_or(false),
+        in_tangents: tangents("i"),
+        out_tangents: tangents("o"),
+        vertices,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_static_and_keyframed_values() {
+        let opacity = animated(Some(&json!({"a": 0, "k": 50})), scalar, 100.0);
+        assert_eq!(opacity.at(10.0), 50.0);
+        assert!(!opacity.is_animated());
+
+        let linear = json!({"a": 1, "k": [
+            {"t": 0, "s": [0], "o": {"x": [0.0], "y": [0.0]}, "i": {"x": [1.0], "y": [1.0]}},
+            {"t": 10, "s": [100], "h": 1},
+            {"t": 20, "s": [50]}
+        ]});
+        let value = animated(Some(&linear), scalar, 0.0);
+        assert!(value.is_animated());
+        assert_eq!(value.at(-5.0), 0.0);
+        assert!((value.at(5.0) - 50.0).abs() < 1e-3);
+        // Hold keyframe
+        assert_eq!(value.at(15.0), 100.0);
+        assert_eq!(value.at(25.0), 50.0);
+
+        // Legacy keyframes with inline end values
+        let legacy = json!({"a": 1, "k": [
+            {"t": 0, "s": [0, 0], "e": [10, 20], "o": {"x": 0, "y": 0}, "i": {"x": 1, "y": 1}},
+            {"t": 10}
+        ]});
+        let value = animated(Some(&legacy), vector, vec![0.0, 0.0]);
+        assert_eq!(value.at(5.0), vec![5.0, 10.0]);
+        assert_eq!(value.at(10.0), vec![10.0, 20.0]);
+    }
+
+    #[test]
+    fn test_bezier_easing() {
+        // ease-in-out is symmetric around the midpoint
+        let mid = cubic_bezier_ease([0.42, 0.0], [0.58, 1.0], 0.5);
+        assert!((mid - 0.5).abs() < 1e-3);
+        let early = cubic_bezier_ease([0.42, 0.0], [0.58, 1.0], 0.25);
+        assert!(early < 0.25);
+        assert_eq!(cubic_bezier_ease([0.42, 0.0], [0.58, 1.0], 1.0), 1.0);
+    }
+}
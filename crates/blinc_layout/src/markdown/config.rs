@@ -1,5 +1,8 @@
 //! Configuration for markdown rendering
 
+use std::fmt;
+use std::sync::Arc;
+
 use blinc_core::Color;
 use blinc_theme::{ColorToken, ThemeState};
 
@@ -59,6 +62,16 @@ pub struct MarkdownConfig {
     pub list_marker_width: f32,
     /// List marker gap (space between marker and content)
     pub list_marker_gap: f32,
+
+    // Features
+    /// Typeset `$…$`, `$$…$$` and ```` ```math ```` blocks as math
+    pub math: bool,
+
+    // Navigation
+    /// Prefix for heading anchor IDs, to keep several documents on one page apart
+    pub anchor_prefix: String,
+    /// Handler for relative links; without one they open like external links
+    pub link_handler: Option<LinkHandler>,
 }
 
 impl Default for MarkdownConfig {
@@ -94,6 +107,10 @@ impl Default for MarkdownConfig {
             code_padding: 12.0,
             list_marker_width: 12.0,
             list_marker_gap: 4.0,
+
+            math: true,
+            anchor_prefix: String::new(),
+            link_handler: None,
         }
     }
 }
@@ -138,6 +155,10 @@ impl MarkdownConfig {
             code_padding: 8.0,
             list_marker_width: 12.0,
             list_marker_gap: 4.0,
+
+            math: true,
+            anchor_prefix: String::new(),
+            link_handler: None,
         }
     }
 
@@ -176,4 +197,38 @@ impl MarkdownConfig {
         self.blockquote_border = color;
         self
     }
+
+    /// Enable or disable math typesetting
+    pub fn math(mut self, enabled: bool) -> Self {
+        self.math = enabled;
+        self
+    }
+
+    /// Set the prefix for heading anchor IDs
+    pub fn anchor_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.anchor_prefix = prefix.into();
+        self
+    }
+
+    /// Handle clicks on relative links, such as `docs/install.md`
+    ///
+    /// In-document `#anchor` links always scroll to their heading, and links
+    /// with a scheme open in the system browser.
+    pub fn on_link<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.link_handler = Some(LinkHandler(Arc::new(handler)));
+        self
+    }
+}
+
+/// Handler for relative links in rendered markdown
+#[derive(Clone)]
+pub struct LinkHandler(pub Arc<dyn Fn(&str) + Send + Sync>);
+
+impl fmt::Debug for LinkHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LinkHandler")
+    }
 }
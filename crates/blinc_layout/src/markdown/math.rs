@@ -0,0 +1,191 @@
+//! `$…$` and `$$…$$` math in markdown source
+//!
+//! pulldown-cmark does not know about math, and its backslash escapes and
+//! emphasis rules would mangle TeX. Before parsing, math spans are rewritten
+//! into code spans whose content starts with a private-use marker, which the
+//! parser passes through untouched; the renderer typesets those instead of
+//! showing them as code. Multi-line `$$` blocks become ```` ```math ```` fences.
+
+use std::borrow::Cow;
+
+use super::stream::fence_marker;
+
+/// Marks a code span holding inline math
+pub(super) const INLINE_MATH: char = '\u{E000}';
+/// Marks a code span holding display math
+pub(super) const DISPLAY_MATH: char = '\u{E001}';
+
+/// Rewrite math spans in markdown source so they survive parsing
+pub(super) fn prepare_math(text: &str) -> Cow<'_, str> {
+    if !text.contains('$') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut out = String::with_capacity(text.len() + 16);
+    let mut fence: Option<(char, usize)> = None;
+    let mut display: Option<String> = None;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let shallow = line.len() - trimmed.len() <= 3;
+
+        if let Some(body) = display.as_mut() {
+            match trimmed.trim_end().strip_suffix("$$") {
+                Some(last) => {
+                    body.push_str(last);
+                    out.push_str("```math\n");
+                    out.push_str(body.trim());
+                    out.push_str("\n```\n");
+                    display = None;
+                }
+                None => body.push_str(line),
+            }
+            continue;
+        }
+
+        match (fence, shallow.then(|| fence_marker(trimmed)).flatten()) {
+            (None, Some(opened)) => {
+                fence = Some(opened);
+                out.push_str(line);
+                continue;
+            }
+            (Some((marker, len)), Some((m, l)))
+                if m == marker && l >= len && trimmed[l..].trim().is_empty() =>
+            {
+                fence = None;
+                out.push_str(line);
+                continue;
+            }
+            (Some(_), _) => {
+                out.push_str(line);
+                continue;
+            }
+            _ => {}
+        }
+
+        // A `$$` line whose block closes on a later line
+        if shallow {
+            if let Some(rest) = trimmed.strip_prefix("$$") {
+                if !rest.contains("$$") {
+                    display = Some(rest.to_string());
+                    continue;
+                }
+            }
+        }
+
+        rewrite_line(line, &mut out);
+    }
+
+    // An unclosed block is left as written
+    if let Some(body) = display {
+        out.push_str("$$");
+        out.push_str(&body);
+    }
+    Cow::Owned(out)
+}
+
+/// Rewrite the math spans of one line
+fn rewrite_line(line: &str, out: &mut String) {
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    let at = |i: usize| chars.get(i).map(|&(_, c)| c);
+    let byte = |i: usize| chars.get(i).map_or(line.len(), |&(b, _)| b);
+
+    let mut i = 0;
+    let mut copied = 0;
+    while i < chars.len() {
+        match chars[i].1 {
+            '\\' => i += 2,
+            '`' => {
+                // Skip code spans
+                let run = chars[i..].iter().take_while(|&&(_, c)| c == '`').count();
+                let close = (i + run..chars.len()).find(|&j| {
+                    at(j) == Some('`')
+                        && at(j - 1) != Some('`')
+                        && chars[j..].iter().take_while(|&&(_, c)| c == '`').count() == run
+                });
+                i = close.map_or(i + run, |j| j + run);
+            }
+            '$' if at(i + 1) == Some('$') => {
+                let close = (i + 2..chars.len().saturating_sub(1)).find(|&j| {
+                    at(j) == Some('$') && at(j + 1) == Some('$') && at(j - 1) != Some('\\')
+                });
+                match close {
+                    Some(j) if j > i + 2 => {
+                        out.push_str(&line[copied..byte(i)]);
+                        push_code_span(out, DISPLAY_MATH, line[byte(i + 2)..byte(j)].trim());
+                        i = j + 2;
+                        copied = byte(i);
+                    }
+                    _ => i += 2,
+                }
+            }
+            '$' => {
+                let opens = at(i + 1).is_some_and(|c| !c.is_whitespace());
+                let close = (i + 1..chars.len()).find(|&j| {
+                    at(j) == Some('$')
+                        && at(j - 1).is_some_and(|c| !c.is_whitespace() && c != '\\')
+                        && !at(j + 1).is_some_and(|c| c.is_ascii_digit())
+                });
+                match close {
+                    Some(j) if opens && j > i + 1 => {
+                        out.push_str(&line[copied..byte(i)]);
+                        push_code_span(out, INLINE_MATH, &line[byte(i + 1)..byte(j)]);
+                        i = j + 1;
+                        copied = byte(i);
+                    }
+                    _ => i += 1,
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    out.push_str(&line[copied.min(line.len())..]);
+}
+
+/// A code span holding `source` behind a marker
+fn push_code_span(out: &mut String, marker: char, source: &str) {
+    let longest = source.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest + 1);
+    let pad = if source.ends_with('`') { " " } else { "" };
+    out.push_str(&fence);
+    out.push_str(pad);
+    out.push(marker);
+    out.push_str(source);
+    out.push_str(pad);
+    out.push_str(&fence);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_and_display_spans() {
+        assert_eq!(
+            prepare_math(r"Euler: $e^{i\pi} = -1$, done"),
+            format!("Euler: `{INLINE_MATH}e^{{i\\pi}} = -1`, done")
+        );
+        assert_eq!(
+            prepare_math(r"so $$\frac{a}{b}$$ here"),
+            format!("so `{DISPLAY_MATH}\\frac{{a}}{{b}}` here")
+        );
+        // Prices, escapes and code are left alone
+        for text in [
+            "costs $5 and $10",
+            r"a \$ sign and $ x$",
+            "`$x$` in code",
+            "```\n$x$\n```\n",
+        ] {
+            assert_eq!(prepare_math(text), text);
+        }
+    }
+
+    #[test]
+    fn test_display_blocks_become_fences() {
+        let text = "Before\n\n$$\n\\begin{pmatrix}\na \\\\ b\n\\end{pmatrix}\n$$\nAfter";
+        assert_eq!(
+            prepare_math(text),
+            "Before\n\n```math\n\\begin{pmatrix}\na \\\\ b\n\\end{pmatrix}\n```\nAfter"
+        );
+        assert_eq!(prepare_math("$$\nx"), "$$\nx");
+    }
+}
@@ -22,9 +22,26 @@
 //! // Use in your layout
 //! div().flex_col().child(content)
 //! ```
+//!
+//! Text that arrives in pieces, such as a streamed chat response, can be
+//! rendered with [`MarkdownStream`], which only re-parses the block that is
+//! still open.
+//!
+//! `$…$` and `$$…$$` math, and ```` ```math ```` blocks, are typeset with
+//! [`crate::math`] unless [`MarkdownConfig::math`] is turned off.
+//!
+//! Headings get slugged anchor IDs (`## Getting Started` becomes
+//! `getting-started`), listed by [`MarkdownRenderer::render_with_toc`].
+//! `#anchor` links smooth-scroll the enclosing scroll container to their
+//! heading, and relative links go to [`MarkdownConfig::on_link`].
 
 mod config;
+mod math;
 mod renderer;
+mod stream;
+mod toc;
 
-pub use config::MarkdownConfig;
+pub use config::{LinkHandler, MarkdownConfig};
 pub use renderer::{markdown, markdown_light, markdown_with_config, MarkdownRenderer};
+pub use stream::MarkdownStream;
+pub use toc::{slugify, TableOfContents, TocEntry};
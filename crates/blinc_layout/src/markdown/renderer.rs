@@ -1,18 +1,26 @@
 //! Markdown to blinc layout renderer
 
+use std::borrow::Cow;
+
 use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 
 use crate::div::{div, Div, ElementBuilder};
+use crate::event_handler::EventContext;
 use crate::image::img;
+use crate::math::{display_math, math};
+use crate::selector::{scroll_into_view, ScrollBehavior, ScrollBlock, ScrollOptions};
 use crate::text::text;
+use crate::text_measure::measure_text;
 use crate::typography::{h1, h2, h3, h4, h5, h6};
 use crate::widgets::{
-    code, li, link, ol_start_with_config, ol_with_config, striped_tr, table, task_item,
+    code, li, link, ol_start_with_config, ol_with_config, open_url, striped_tr, table, task_item,
     task_item_with_config, tbody, td, th, thead, tr, ul_with_config, ListConfig, ListItem,
     OrderedList, TaskListItem, UnorderedList,
 };
 
-use super::config::MarkdownConfig;
+use super::config::{LinkHandler, MarkdownConfig};
+use super::math::{prepare_math, DISPLAY_MATH, INLINE_MATH};
+use super::toc::{slugify, Slugger, TableOfContents, TocEntry};
 
 // Re-export for HTML entity decoding
 use html_escape::decode_html_entities;
@@ -43,26 +51,56 @@ impl MarkdownRenderer {
 
     /// Render markdown text to a Div containing all the elements
     pub fn render(&self, markdown_text: &str) -> Div {
-        // Set up parser with GFM extensions and additional features
-        let mut options = Options::empty();
-        options.insert(Options::ENABLE_TABLES);
-        options.insert(Options::ENABLE_STRIKETHROUGH);
-        options.insert(Options::ENABLE_TASKLISTS);
-        options.insert(Options::ENABLE_FOOTNOTES);
-        options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
-        options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+        self.render_with_toc(markdown_text).0
+    }
 
-        let parser = Parser::new_ext(markdown_text, options);
+    /// Render markdown text, also returning its table of contents
+    ///
+    /// Each heading's element ID is its anchor, so `[Setup](#setup)` links
+    /// and [`scroll_into_view`] can reach it.
+    pub fn render_with_toc(&self, markdown_text: &str) -> (Div, TableOfContents) {
+        let source = if self.config.math {
+            prepare_math(markdown_text)
+        } else {
+            Cow::Borrowed(markdown_text)
+        };
+        let parser = Parser::new_ext(&source, parser_options());
         let events: Vec<Event<'_>> = parser.collect();
 
-        // Build the layout
         let mut renderer = RenderState::new(&self.config);
         renderer.render_events(&events);
+        let toc = std::mem::take(&mut renderer.toc);
+        (renderer.into_container(), toc)
+    }
 
-        renderer.into_container()
+    /// Table of contents of markdown text
+    ///
+    /// This renders the document; use [`render_with_toc`](Self::render_with_toc)
+    /// when the content is needed too.
+    pub fn table_of_contents(&self, markdown_text: &str) -> TableOfContents {
+        self.render_with_toc(markdown_text).1
     }
 }
 
+/// Parser options: GFM extensions and additional features
+pub(super) fn parser_options() -> Options {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options.insert(Options::ENABLE_YAML_STYLE_METADATA_BLOCKS);
+    options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+    options
+}
+
+/// Build the layout for already-parsed events
+pub(super) fn render_events(config: &MarkdownConfig, events: &[Event<'_>]) -> Div {
+    let mut renderer = RenderState::new(config);
+    renderer.render_events(events);
+    renderer.into_container()
+}
+
 impl Default for MarkdownRenderer {
     fn default() -> Self {
         Self::new()
@@ -161,6 +199,14 @@ struct RenderState<'a> {
     in_html_block: bool,
     /// HTML block content accumulator
     html_content: String,
+    /// Text of the current heading, including inline code
+    heading_text: String,
+    /// Explicit `{#id}` of the current heading
+    heading_id: Option<String>,
+    /// Headings rendered so far
+    toc: TableOfContents,
+    /// Keeps heading anchors unique
+    slugger: Slugger,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -204,6 +250,10 @@ impl<'a> RenderState<'a> {
             in_metadata_block: false,
             in_html_block: false,
             html_content: String::new(),
+            heading_text: String::new(),
+            heading_id: None,
+            toc: TableOfContents::default(),
+            slugger: Slugger::default(),
         }
     }
 
@@ -234,7 +284,9 @@ impl<'a> RenderState<'a> {
             Tag::Paragraph => {
                 self.stack.push(StackItem::Paragraph);
             }
-            Tag::Heading { level, .. } => {
+            Tag::Heading { level, id, .. } => {
+                self.heading_text.clear();
+                self.heading_id = id.as_ref().map(|id| id.to_string());
                 let level = match level {
                     HeadingLevel::H1 => 1,
                     HeadingLevel::H2 => 2,
@@ -607,14 +659,43 @@ impl<'a> RenderState<'a> {
         } else {
             // Decode HTML entities (e.g., &amp; -> &, &nbsp; -> non-breaking space)
             let decoded = decode_html_entities(text);
+            if self.in_heading() {
+                self.heading_text.push_str(&decoded);
+            }
             self.inline_text.push_str(&decoded);
         }
     }
 
     fn handle_inline_code(&mut self, code_text: &str) {
+        if self.in_heading() {
+            self.heading_text.push_str(code_text);
+        }
+
         // Flush any accumulated styled segments to elements first
         self.flush_segments_to_elements();
 
+        if self.config.math {
+            if let Some(source) = code_text.strip_prefix(INLINE_MATH) {
+                // End at the same depth below the baseline as body text
+                let size = self.config.body_size;
+                let depth = size - measure_text(source, size).ascender;
+                let formula = math(source)
+                    .size(size)
+                    .color(self.config.text_color)
+                    .baseline_depth(depth);
+                self.inline_elements.push(Box::new(formula));
+                return;
+            }
+            if let Some(source) = code_text.strip_prefix(DISPLAY_MATH) {
+                let line_break = div().w_full().h(0.0);
+                self.inline_elements.push(Box::new(line_break));
+                self.inline_elements
+                    .push(Box::new(self.display_math_block(source)));
+                self.inline_elements.push(Box::new(div().w_full().h(0.0)));
+                return;
+            }
+        }
+
         // Build inline code manually with matching size to body text for proper alignment
         // We need to set size BEFORE no_wrap() to ensure correct measurement
         let code_elem = text(code_text)
@@ -713,7 +794,8 @@ impl<'a> RenderState<'a> {
             if let Some(url) = &segment.link_url {
                 let link_elem = link(&segment.text, url)
                     .font_size(self.config.body_size)
-                    .text_color(segment.color);
+                    .text_color(segment.color)
+                    .on_click(self.link_router());
                 self.inline_elements.push(Box::new(link_elem));
                 continue;
             }
@@ -776,17 +858,23 @@ impl<'a> RenderState<'a> {
         // Flush any remaining inline text first
         self.flush_inline_text();
 
-        // Clear any inline elements (headings don't support inline code etc.)
+        // Clear any inline elements (headings render inline code as plain text)
         self.inline_elements.clear();
+        self.styled_segments.clear();
 
-        let segments = std::mem::take(&mut self.styled_segments);
-
-        if segments.is_empty() {
+        let text_content = std::mem::take(&mut self.heading_text);
+        let explicit_id = self.heading_id.take();
+        if text_content.trim().is_empty() {
             return;
         }
 
-        // Combine all segment text for the heading
-        let text_content: String = segments.iter().map(|s| s.text.as_str()).collect();
+        let slug = explicit_id.unwrap_or_else(|| slugify(&text_content));
+        let anchor = format!("{}{}", self.config.anchor_prefix, self.slugger.unique(slug));
+        self.toc.push(TocEntry {
+            level,
+            title: text_content.trim().to_string(),
+            anchor: anchor.clone(),
+        });
 
         // Use config font sizes and apply text color
         let (heading, size) = match level {
@@ -798,24 +886,56 @@ impl<'a> RenderState<'a> {
             _ => (h6(&text_content), self.config.h6_size),
         };
 
-        let heading = heading.size(size).color(self.config.text_color);
+        let heading = heading.size(size).color(self.config.text_color).id(anchor);
         self.add_to_current_context(heading);
     }
 
+    fn in_heading(&self) -> bool {
+        self.stack
+            .iter()
+            .any(|item| matches!(item, StackItem::Heading(_)))
+    }
+
+    /// Click handler for links: `#anchor` links scroll to their target,
+    /// relative links go to the configured handler, and others open in the
+    /// system browser
+    fn link_router(&self) -> impl Fn(&str, &EventContext) + Send + Sync + 'static {
+        let prefix = self.config.anchor_prefix.clone();
+        let handler = self.config.link_handler.clone();
+        move |url, _ctx| route_link(url, &prefix, handler.as_ref())
+    }
+
     fn flush_code_block(&mut self) {
         let content = std::mem::take(&mut self.code_content);
-        let _lang = self.code_language.take();
+        let lang = self.code_language.take();
+
+        if self.config.math && lang.as_deref() == Some("math") {
+            let block = self.display_math_block(&content);
+            self.add_to_current_context(block);
+            return;
+        }
 
-        // Create code block (without syntax highlighting for now)
         // Note: code() returns a Code struct that derefs to Div
         // We can't chain Div methods after Code methods due to Deref ownership rules
-        let code_block = code(&content)
+        let mut code_block = code(&content)
             .line_numbers(true)
             .font_size(self.config.code_size);
+        if let Some(lang) = lang {
+            code_block = code_block.language(&lang);
+        }
 
         self.add_to_current_context(code_block);
     }
 
+    /// Display math centered on its own line
+    fn display_math_block(&self, source: &str) -> Div {
+        div().w_full().flex_row().justify_center().py(1.0).child(
+            display_math(source)
+                .size(self.config.body_size)
+                .color(self.config.text_color),
+        )
+    }
+
     fn add_to_current_context(&mut self, element: impl ElementBuilder + 'static) {
         // Find the appropriate parent to add to
         for item in self.stack.iter_mut().rev() {
@@ -849,11 +969,11 @@ impl<'a> RenderState<'a> {
         // Flush any pending text first
         self.flush_segments_to_elements();
 
-        // Render as a link-styled element with pointer cursor
-        // TODO: In the future, this could scroll to the footnote definition
+        // Render as a link-styled element that scrolls to the definition
         let footnote_ref = link(format!("[{}]", label), format!("#footnote-{}", label))
             .font_size(self.config.body_size * 0.75) // Smaller, superscript-like
-            .text_color(self.config.link_color);
+            .text_color(self.config.link_color)
+            .on_click(self.link_router());
 
         self.inline_elements.push(Box::new(footnote_ref));
     }
@@ -1076,6 +1196,7 @@ impl<'a> RenderState<'a> {
             for (label, content) in self.footnote_defs {
                 // Create footnote row: number + content
                 let footnote_row = div()
+                    .id(format!("{}footnote-{}", self.config.anchor_prefix, label))
                     .flex_row()
                     .gap(8.0)
                     .items_start()
@@ -1096,6 +1217,36 @@ impl<'a> RenderState<'a> {
     }
 }
 
+fn route_link(url: &str, anchor_prefix: &str, handler: Option<&LinkHandler>) {
+    if let Some(anchor) = url.strip_prefix('#') {
+        scroll_into_view(
+            &format!("{anchor_prefix}{anchor}"),
+            ScrollOptions {
+                behavior: ScrollBehavior::Smooth,
+                block: ScrollBlock::Start,
+                ..ScrollOptions::default()
+            },
+        );
+        return;
+    }
+    match handler {
+        Some(handler) if !has_scheme(url) => (handler.0)(url),
+        _ => open_url(url),
+    }
+}
+
+/// Whether a URL starts with a scheme such as `https:` or `mailto:`
+fn has_scheme(url: &str) -> bool {
+    url.split_once(':').is_some_and(|(scheme, _)| {
+        // Single letters are Windows drive paths
+        scheme.len() > 1
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1118,6 +1269,64 @@ mod tests {
         assert!(!tree.is_empty());
     }
 
+    #[test]
+    fn test_table_of_contents() {
+        init_theme();
+        let md = "# Guide\n\nIntro\n\n## Using `markdown()`\n\n### Example\n\n### Example\n\n## Custom {#own-id}\n";
+        let renderer =
+            MarkdownRenderer::with_config(MarkdownConfig::default().anchor_prefix("doc-"));
+        let (content, toc) = renderer.render_with_toc(md);
+
+        let entries: Vec<_> = toc
+            .iter()
+            .map(|e| (e.level, e.title.as_str(), e.anchor.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                (1, "Guide", "doc-guide"),
+                (2, "Using markdown()", "doc-using-markdown"),
+                (3, "Example", "doc-example"),
+                (3, "Example", "doc-example-1"),
+                (2, "Custom", "doc-own-id"),
+            ]
+        );
+
+        let mut tree = LayoutTree::new();
+        content.build(&mut tree);
+        assert_eq!(renderer.table_of_contents(md), toc);
+    }
+
+    #[test]
+    fn test_link_routing() {
+        use std::sync::{Arc, Mutex};
+
+        assert!(has_scheme("https://example.com"));
+        assert!(has_scheme("mailto:someone@example.com"));
+        assert!(!has_scheme("docs/install.md"));
+        assert!(!has_scheme("C:/docs/readme.md"));
+
+        let routed = Arc::new(Mutex::new(Vec::new()));
+        let seen = routed.clone();
+        let config = MarkdownConfig::default().on_link(move |url| {
+            seen.lock().unwrap().push(url.to_string());
+        });
+        route_link("../guide.md#setup", "", config.link_handler.as_ref());
+        assert_eq!(*routed.lock().unwrap(), ["../guide.md#setup"]);
+    }
+
+    #[test]
+    fn test_math() {
+        init_theme();
+        let md = "Inline $x^2 + y_1$ and display:\n\n$$\n\\sum_{i=1}^n i = \\frac{n(n+1)}{2}\n$$\n";
+        let mut tree = LayoutTree::new();
+        markdown(md).build(&mut tree);
+        assert!(!tree.is_empty());
+
+        let plain = MarkdownRenderer::with_config(MarkdownConfig::default().math(false));
+        plain.render(md).build(&mut LayoutTree::new());
+    }
+
     #[test]
     fn test_bold_spacing_events() {
         // Test that pulldown-cmark preserves spaces around styled text
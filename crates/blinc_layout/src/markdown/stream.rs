@@ -0,0 +1,601 @@
+//! Incremental markdown rendering for text that arrives in pieces
+//!
+//! [`MarkdownStream`] is meant for content such as chat responses that are
+//! streamed token by token. Appended text only re-parses the trailing open
+//! block: once a later top-level block has started, the blocks before it are
+//! final, so their parsed events are kept and never parsed again.
+//!
+//! Every block is wrapped in a container with a stable element ID
+//! (`"{prefix}-block-{index}"`), so between rebuilds the completed blocks
+//! hash the same and [`diff`](crate::diff) reports them as unchanged.
+//!
+//! The open block is rendered provisionally to avoid flicker while it is
+//! still being written:
+//!
+//! - unterminated `**`, `*`, `_`, `~~` and inline code are closed, so text
+//!   shows its final style right away
+//! - a fence opener whose info string is still being typed renders as a plain
+//!   code block, and a half-typed closing fence is hidden
+//! - table rows are hidden until they are complete, and a table header until
+//!   its delimiter row has arrived
+//!
+//! Reference-style links and footnotes are resolved within the part of the
+//! text that was parsed together, so a definition that arrives after a block
+//! was completed does not apply to it.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use blinc_layout::markdown::MarkdownStream;
+//!
+//! let mut stream = MarkdownStream::new().id_prefix("reply-7");
+//! for token in ["# Ti", "tle\n\nSome **bo", "ld** text"] {
+//!     stream.push(token);
+//!     let content = stream.render();
+//! }
+//! stream.finish();
+//! ```
+
+use std::borrow::Cow;
+
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Parser, Tag};
+
+use crate::div::{div, Div};
+
+use super::config::MarkdownConfig;
+use super::math::prepare_math;
+use super::renderer::{parser_options, render_events};
+
+/// Incremental markdown renderer for appended text
+pub struct MarkdownStream {
+    config: MarkdownConfig,
+    id_prefix: String,
+    source: String,
+    /// Byte offset where the open tail starts
+    committed: usize,
+    /// Parsed events of each completed block
+    blocks: Vec<Vec<Event<'static>>>,
+    finished: bool,
+}
+
+impl MarkdownStream {
+    /// Create an empty stream with default configuration
+    pub fn new() -> Self {
+        Self::with_config(MarkdownConfig::default())
+    }
+
+    /// Create an empty stream with custom configuration
+    pub fn with_config(config: MarkdownConfig) -> Self {
+        Self {
+            config,
+            id_prefix: "md".to_string(),
+            source: String::new(),
+            committed: 0,
+            blocks: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// Set the prefix of the block element IDs
+    ///
+    /// Use a distinct prefix for each stream that is on screen at once.
+    pub fn id_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.id_prefix = prefix.into();
+        self
+    }
+
+    /// Append text, completing any blocks it closes
+    ///
+    /// Text pushed after [`finish`](Self::finish) starts the stream again
+    /// from where the finished text left off.
+    pub fn push(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.finished = false;
+        self.source.push_str(text);
+        self.commit(false);
+    }
+
+    /// Mark the text as complete, so the trailing block renders as written
+    pub fn finish(&mut self) {
+        self.commit(true);
+        self.finished = true;
+    }
+
+    /// Remove all text
+    pub fn clear(&mut self) {
+        self.source.clear();
+        self.committed = 0;
+        self.blocks.clear();
+        self.finished = false;
+    }
+
+    /// All text pushed so far
+    pub fn text(&self) -> &str {
+        &self.source
+    }
+
+    /// Number of completed blocks
+    pub fn completed_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Whether [`finish`](Self::finish) was called since the last push
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Render the completed blocks and the open tail
+    pub fn render(&self) -> Div {
+        let mut container = div().flex_col().gap(self.config.paragraph_spacing).w_full();
+        for (index, events) in self.blocks.iter().enumerate() {
+            container = container.child(self.block(index, events));
+        }
+
+        let tail = &self.source[self.committed..];
+        let tail = if self.finished {
+            Cow::Borrowed(tail)
+        } else {
+            provisional(tail)
+        };
+        let tail = if self.config.math {
+            Cow::Owned(prepare_math(&tail).into_owned())
+        } else {
+            tail
+        };
+        if !tail.trim().is_empty() {
+            let events: Vec<Event<'_>> = Parser::new_ext(&tail, parser_options()).collect();
+            if !events.is_empty() {
+                container = container.child(self.block(self.blocks.len(), &events));
+            }
+        }
+        container
+    }
+
+    fn block(&self, index: usize, events: &[Event<'_>]) -> Div {
+        render_events(&self.config, events).id(format!("{}-block-{}", self.id_prefix, index))
+    }
+
+    /// Parse the open tail and move its completed blocks into `blocks`
+    ///
+    /// With `all`, the trailing block is completed as well.
+    fn commit(&mut self, all: bool) {
+        let tail = &self.source[self.committed..];
+        let mut parsed = split_blocks(tail);
+
+        let end = if all {
+            tail.len()
+        } else {
+            // The last block may still grow; it starts the new tail
+            let Some((start, _)) = parsed.pop() else {
+                return;
+            };
+            if parsed.is_empty() {
+                return;
+            }
+            tail[..start].rfind('\n').map_or(0, |i| i + 1)
+        };
+        if self.config.math {
+            // Block boundaries come from the source as written, since math
+            // rewriting does not preserve offsets
+            parsed = split_blocks(&prepare_math(&tail[..end]));
+        }
+        self.committed += end;
+        self.blocks
+            .extend(parsed.into_iter().map(|(_, events)| events));
+    }
+}
+
+/// Parse `text` into top-level blocks, each with its start offset
+fn split_blocks(text: &str) -> Vec<(usize, Vec<Event<'static>>)> {
+    let mut blocks: Vec<(usize, Vec<Event<'static>>)> = Vec::new();
+    let mut depth = 0usize;
+    for (event, range) in Parser::new_ext(text, parser_options()).into_offset_iter() {
+        if depth == 0 {
+            blocks.push((range.start, Vec::new()));
+        }
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+        if let Some((_, events)) = blocks.last_mut() {
+            events.push(owned_event(event));
+        }
+    }
+    blocks
+}
+
+impl Default for MarkdownStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ============================================================================
+// Provisional rendering of the open tail
+// ============================================================================
+
+/// The open tail with unfinished constructs completed or held back
+fn provisional(tail: &str) -> Cow<'_, str> {
+    let (complete, partial) = match tail.rfind('\n') {
+        Some(i) => tail.split_at(i + 1),
+        None => ("", tail),
+    };
+    let fence = open_fence(complete);
+
+    if let Some((marker, len)) = fence {
+        // A closing fence that is still being typed would show up as code
+        let trimmed = partial.trim_start();
+        if !trimmed.is_empty() && trimmed.chars().all(|c| c == marker) && trimmed.len() < len {
+            return Cow::Borrowed(complete);
+        }
+        return Cow::Borrowed(tail);
+    }
+
+    let mut text = String::from(complete);
+    let trimmed = partial.trim_start();
+    if fence_marker(trimmed).is_some() {
+        // The info string is still being typed; open the block without it
+        let indent = &partial[..partial.len() - trimmed.len()];
+        let marker_len = trimmed
+            .chars()
+            .take_while(|&c| c == trimmed.as_bytes()[0] as char)
+            .count();
+        text.push_str(indent);
+        text.push_str(&trimmed[..marker_len]);
+        return Cow::Owned(text);
+    }
+    if !trimmed.starts_with('|') {
+        text.push_str(partial);
+    }
+
+    // A table header renders as a paragraph until its delimiter row arrives
+    let body = text.trim_end_matches('\n');
+    let last_line_start = body.rfind('\n').map_or(0, |i| i + 1);
+    let before = body[..last_line_start].trim_end_matches('\n');
+    let previous_line = &before[before.rfind('\n').map_or(0, |i| i + 1)..];
+    if body[last_line_start..].trim_start().starts_with('|')
+        && !previous_line.trim_start().starts_with('|')
+    {
+        text.truncate(last_line_start);
+    }
+
+    Cow::Owned(close_inline(&text))
+}
+
+/// Fence character and length of a line that opens or closes a fenced block
+pub(super) fn fence_marker(line: &str) -> Option<(char, usize)> {
+    let marker = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.chars().take_while(|&c| c == marker).count();
+    (len >= 3).then_some((marker, len))
+}
+
+/// The fence left open at the end of `text`, if any
+fn open_fence(text: &str) -> Option<(char, usize)> {
+    let mut open: Option<(char, usize)> = None;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        if line.len() - trimmed.len() > 3 {
+            continue;
+        }
+        match (open, fence_marker(trimmed)) {
+            (None, Some(fence)) => open = Some(fence),
+            (Some((marker, len)), Some((m, l)))
+                if m == marker && l >= len && trimmed[l..].trim().is_empty() =>
+            {
+                open = None
+            }
+            _ => {}
+        }
+    }
+    open
+}
+
+/// Close inline spans left open in the last paragraph of `text`
+fn close_inline(text: &str) -> String {
+    let start = text.trim_end().rfind("\n\n").map_or(0, |i| i + 2);
+    let chars: Vec<(usize, char)> = text[start..]
+        .char_indices()
+        .map(|(i, c)| (start + i, c))
+        .collect();
+
+    // Open delimiters with their byte offsets
+    let mut open: Vec<(String, usize)> = Vec::new();
+    let mut line_start = true;
+    let mut i = 0;
+    while i < chars.len() {
+        let (pos, c) = chars[i];
+        if line_start {
+            line_start = false;
+            // A new block on this line ends the spans of the previous one
+            let rest = text[pos..].trim_start();
+            if let Some(marker) = block_marker_len(rest) {
+                open.clear();
+                let skip = text[pos..].len() - rest.len();
+                while i < chars.len() && chars[i].0 < pos + skip + marker {
+                    i += 1;
+                }
+                continue;
+            }
+        }
+        match c {
+            '\n' => {
+                line_start = true;
+                i += 1;
+            }
+            '\\' => i += 2,
+            '`' => {
+                let run = run_len(&chars, i);
+                let closing = (i + run..chars.len()).find(|&j| {
+                    chars[j].1 == '`' && chars[j - 1].1 != '`' && run_len(&chars, j) == run
+                });
+                match closing {
+                    Some(j) => i = j + run,
+                    None => {
+                        // Everything after an unterminated code span is code
+                        open.push(("`".repeat(run), pos));
+                        break;
+                    }
+                }
+            }
+            '*' | '_' | '~' => {
+                let run = run_len(&chars, i);
+                if c == '~' && run < 2 {
+                    i += run;
+                    continue;
+                }
+                let delimiter = c.to_string().repeat(if c == '~' { 2 } else { run.min(3) });
+                let prev = i.checked_sub(1).map(|j| chars[j].1);
+                let next = chars.get(i + run).map(|&(_, c)| c);
+                // At the end of the text an opener may still be followed by more
+                let left = next.map_or(true, |c| !c.is_whitespace());
+                let right = prev.is_some_and(|c| !c.is_whitespace());
+                let intraword = c == '_'
+                    && prev.is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(char::is_alphanumeric);
+                if !intraword {
+                    let opener = open.iter().rposition(|(d, _)| *d == delimiter);
+                    match opener {
+                        Some(o) if right => open.truncate(o),
+                        _ if left => open.push((delimiter, pos)),
+                        _ => {}
+                    }
+                }
+                i += run;
+            }
+            _ => i += 1,
+        }
+    }
+
+    let mut result = text.trim_end().to_string();
+    // Drop openers with nothing after them yet
+    while let Some((delimiter, pos)) = open.last() {
+        if pos + delimiter.len() < result.len() {
+            break;
+        }
+        result.truncate(*pos);
+        result.truncate(result.trim_end().len());
+        open.pop();
+    }
+    for (delimiter, _) in open.iter().rev() {
+        result.push_str(delimiter);
+    }
+    result
+}
+
+/// Number of repeats of the character at `i`
+fn run_len(chars: &[(usize, char)], i: usize) -> usize {
+    let c = chars[i].1;
+    chars[i..].iter().take_while(|&&(_, d)| d == c).count()
+}
+
+/// Length of the marker when a line starts a heading, list item, quote or
+/// table row
+fn block_marker_len(line: &str) -> Option<usize> {
+    if line.starts_with(['>', '|']) {
+        return Some(1);
+    }
+    let marker = line.split_whitespace().next()?;
+    if !line[marker.len()..].starts_with(char::is_whitespace) {
+        return None;
+    }
+    let is_marker = marker.chars().all(|c| c == '#')
+        || matches!(marker, "-" | "*" | "+")
+        || marker
+            .strip_suffix(['.', ')'])
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    is_marker.then_some(marker.len())
+}
+
+// ============================================================================
+// Owned events
+// ============================================================================
+
+fn owned(s: CowStr<'_>) -> CowStr<'static> {
+    match s {
+        CowStr::Inlined(s) => CowStr::Inlined(s),
+        s => CowStr::Boxed(s.into_string().into_boxed_str()),
+    }
+}
+
+fn owned_tag(tag: Tag<'_>) -> Tag<'static> {
+    match tag {
+        Tag::Paragraph => Tag::Paragraph,
+        Tag::Heading {
+            level,
+            id,
+            classes,
+            attrs,
+        } => Tag::Heading {
+            level,
+            id: id.map(owned),
+            classes: classes.into_iter().map(owned).collect(),
+            attrs: attrs
+                .into_iter()
+                .map(|(k, v)| (owned(k), v.map(owned)))
+                .collect(),
+        },
+        Tag::BlockQuote => Tag::BlockQuote,
+        Tag::CodeBlock(CodeBlockKind::Indented) => Tag::CodeBlock(CodeBlockKind::Indented),
+        Tag::CodeBlock(CodeBlockKind::Fenced(info)) => {
+            Tag::CodeBlock(CodeBlockKind::Fenced(owned(info)))
+        }
+        Tag::HtmlBlock => Tag::HtmlBlock,
+        Tag::List(start) => Tag::List(start),
+        Tag::Item => Tag::Item,
+        Tag::FootnoteDefinition(label) => Tag::FootnoteDefinition(owned(label)),
+        Tag::Table(alignments) => Tag::Table(alignments),
+        Tag::TableHead => Tag::TableHead,
+        Tag::TableRow => Tag::TableRow,
+        Tag::TableCell => Tag::TableCell,
+        Tag::Emphasis => Tag::Emphasis,
+        Tag::Strong => Tag::Strong,
+        Tag::Strikethrough => Tag::Strikethrough,
+        Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        } => Tag::Link {
+            link_type,
+            dest_url: owned(dest_url),
+            title: owned(title),
+            id: owned(id),
+        },
+        Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        } => Tag::Image {
+            link_type,
+            dest_url: owned(dest_url),
+            title: owned(title),
+            id: owned(id),
+        },
+        Tag::MetadataBlock(kind) => Tag::MetadataBlock(kind),
+    }
+}
+
+fn owned_event(event: Event<'_>) -> Event<'static> {
+    match event {
+        Event::Start(tag) => Event::Start(owned_tag(tag)),
+        Event::End(tag) => Event::End(tag),
+        Event::Text(s) => Event::Text(owned(s)),
+        Event::Code(s) => Event::Code(owned(s)),
+        Event::Html(s) => Event::Html(owned(s)),
+        Event::InlineHtml(s) => Event::InlineHtml(owned(s)),
+        Event::FootnoteReference(s) => Event::FootnoteReference(owned(s)),
+        Event::SoftBreak => Event::SoftBreak,
+        Event::HardBreak => Event::HardBreak,
+        Event::Rule => Event::Rule,
+        Event::TaskListMarker(checked) => Event::TaskListMarker(checked),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::{diff, ChildDiff};
+    use blinc_theme::ThemeState;
+
+    fn init_theme() {
+        let _ = ThemeState::try_get().unwrap_or_else(|| {
+            ThemeState::init_default();
+            ThemeState::get()
+        });
+    }
+
+    #[test]
+    fn test_completed_blocks_are_kept() {
+        let mut stream = MarkdownStream::new();
+        stream.push("# Title\n\nFirst para");
+        assert_eq!(stream.completed_blocks(), 1);
+        stream.push("graph continues\n\n- item");
+        assert_eq!(stream.completed_blocks(), 2);
+        assert_eq!(&stream.source[stream.committed..], "- item");
+
+        stream.push(" one\n- item two");
+        assert_eq!(stream.completed_blocks(), 2);
+        stream.finish();
+        assert_eq!(stream.completed_blocks(), 3);
+        assert!(stream.is_finished());
+
+        stream.clear();
+        assert_eq!(stream.completed_blocks(), 0);
+        assert_eq!(stream.text(), "");
+    }
+
+    #[test]
+    fn test_math_blocks() {
+        let mut stream = MarkdownStream::new();
+        stream.push("$$\n\\frac{a}{b}\n$$\n\nNext");
+        assert_eq!(stream.completed_blocks(), 1);
+        assert!(matches!(
+            &stream.blocks[0][0],
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) if lang.as_ref() == "math"
+        ));
+    }
+
+    #[test]
+    fn test_open_fence_stays_open() {
+        let mut stream = MarkdownStream::new();
+        stream.push("```rust\nfn main() {}\n\nlet x = 1;\n");
+        assert_eq!(stream.completed_blocks(), 0);
+        stream.push("```\n\nafter");
+        assert_eq!(stream.completed_blocks(), 1);
+    }
+
+    #[test]
+    fn test_provisional_inline_closing() {
+        assert_eq!(close_inline("Some **bold"), "Some **bold**");
+        assert_eq!(close_inline("a *b **c"), "a *b **c***");
+        assert_eq!(close_inline("done **bold** text"), "done **bold** text");
+        assert_eq!(close_inline("call `foo(x"), "call `foo(x`");
+        assert_eq!(close_inline("`a*` and *b"), "`a*` and *b*");
+        assert_eq!(close_inline("snake_case ~~gone"), "snake_case ~~gone~~");
+        assert_eq!(close_inline("trailing **"), "trailing");
+        assert_eq!(close_inline("* item *em"), "* item *em*");
+        assert_eq!(close_inline("- *a\n- b"), "- *a\n- b");
+        assert_eq!(close_inline("*old*\n\nnew _x"), "*old*\n\nnew _x_");
+    }
+
+    #[test]
+    fn test_provisional_fences_and_tables() {
+        assert_eq!(provisional("text\n```ru"), "text\n```");
+        assert_eq!(provisional("```rust\ncode\n``"), "```rust\ncode\n");
+        assert_eq!(provisional("```rust\ncode *x"), "```rust\ncode *x");
+        assert_eq!(provisional("| a | b |\n"), "");
+        assert_eq!(provisional("| a | b |\n|--"), "");
+        assert_eq!(
+            provisional("| a | b |\n|---|---|\n| 1 | 2"),
+            "| a | b |\n|---|---|"
+        );
+    }
+
+    #[test]
+    fn test_completed_blocks_diff_unchanged() {
+        init_theme();
+        let mut stream = MarkdownStream::new().id_prefix("reply");
+        stream.push("Intro\n\n---\n\nMore **te");
+        let old = stream.render();
+        stream.push("xt** here");
+        let new = stream.render();
+
+        let result = diff(&old, &new);
+        assert!(matches!(
+            result.child_diffs[0],
+            ChildDiff::Unchanged { index: 0 }
+        ));
+        assert!(matches!(
+            result.child_diffs[1],
+            ChildDiff::Unchanged { index: 1 }
+        ));
+        assert!(matches!(
+            result.child_diffs[2],
+            ChildDiff::Modified { new_index: 2, .. }
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+//! Heading anchors and table of contents
+
+use std::collections::HashMap;
+
+use crate::selector::ScrollRef;
+
+/// A heading in a rendered markdown document
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TocEntry {
+    /// Heading level, 1 to 6
+    pub level: u8,
+    /// Heading text
+    pub title: String,
+    /// Element ID of the rendered heading
+    pub anchor: String,
+}
+
+/// Headings of a rendered markdown document, in document order
+///
+/// # Example
+///
+/// ```ignore
+/// let (content, toc) = MarkdownRenderer::new().render_with_toc(text);
+/// toc.track(&scroll_ref);
+///
+/// // While rendering the sidebar
+/// let current = toc.current(&scroll_ref).map(|entry| entry.anchor.clone());
+/// for entry in toc.iter() {
+///     // indent by entry.level, highlight when Some(&entry.anchor) == current.as_ref()
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TableOfContents {
+    entries: Vec<TocEntry>,
+}
+
+impl TableOfContents {
+    /// All headings
+    pub fn entries(&self) -> &[TocEntry] {
+        &self.entries
+    }
+
+    /// Iterate over the headings
+    pub fn iter(&self) -> std::slice::Iter<'_, TocEntry> {
+        self.entries.iter()
+    }
+
+    /// Number of headings
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the document has no headings
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Look up a heading by anchor ID
+    pub fn get(&self, anchor: &str) -> Option<&TocEntry> {
+        self.entries.iter().find(|entry| entry.anchor == anchor)
+    }
+
+    /// Track the headings' positions in the scroll container bound to `scroll_ref`
+    pub fn track(&self, scroll_ref: &ScrollRef) {
+        scroll_ref.track_anchors(self.entries.iter().map(|entry| entry.anchor.clone()));
+    }
+
+    /// The section scrolled to in a container passed to [`track`](Self::track)
+    pub fn current(&self, scroll_ref: &ScrollRef) -> Option<&TocEntry> {
+        self.get(&scroll_ref.current_anchor()?)
+    }
+
+    pub(super) fn push(&mut self, entry: TocEntry) {
+        self.entries.push(entry);
+    }
+}
+
+impl<'a> IntoIterator for &'a TableOfContents {
+    type Item = &'a TocEntry;
+    type IntoIter = std::slice::Iter<'a, TocEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter()
+    }
+}
+
+/// Anchor slug for heading text, as GitHub generates them
+///
+/// Letters and digits are lowercased, spaces become hyphens, and other
+/// punctuation is dropped: `"Getting Started!"` becomes `"getting-started"`.
+pub fn slugify(title: &str) -> String {
+    title
+        .trim()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            '-' | '_' => Some(c),
+            c if c.is_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Makes anchors unique within a document by numbering repeats
+#[derive(Default)]
+pub(super) struct Slugger {
+    seen: HashMap<String, usize>,
+}
+
+impl Slugger {
+    pub(super) fn unique(&mut self, slug: String) -> String {
+        match self.seen.get_mut(&slug) {
+            Some(count) => {
+                *count += 1;
+                let numbered = format!("{slug}-{count}");
+                self.seen.insert(numbered.clone(), 0);
+                numbered
+            }
+            None => {
+                self.seen.insert(slug.clone(), 0);
+                slug
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugs() {
+        assert_eq!(slugify("Getting Started!"), "getting-started");
+        assert_eq!(slugify("  API: `render_with_toc`  "), "api-render_with_toc");
+        assert_eq!(slugify("Ünïcode Ünits"), "ünïcode-ünits");
+
+        let mut slugger = Slugger::default();
+        assert_eq!(slugger.unique("example".into()), "example");
+        assert_eq!(slugger.unique("example".into()), "example-1");
+        assert_eq!(slugger.unique("example".into()), "example-2");
+        assert_eq!(slugger.unique("example-1".into()), "example-1-1");
+    }
+}
@@ -0,0 +1,917 @@
+//! Box layout for a parsed math list
+//!
+//! Follows the shape of TeX's math typesetting rules with simplified font
+//! parameters: widths come from the text measurer, heights from per-glyph
+//! estimates. Coordinates are relative to the left end of the baseline, with
+//! y growing downwards.
+
+use blinc_core::Path;
+
+use super::parse::{Accent, Class, Columns, Font, FracStyle, Limits, Node};
+use super::symbols;
+use crate::text_measure::measure_text_with_options;
+
+/// Height of the math axis (fraction bars, centre of operators) in em
+const AXIS: f32 = 0.25;
+
+/// Size style of a sub-formula
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum Style {
+    Display,
+    Text,
+    Script,
+    ScriptScript,
+}
+
+impl Style {
+    fn scale(self) -> f32 {
+        match self {
+            Style::Display | Style::Text => 1.0,
+            Style::Script => 0.7,
+            Style::ScriptScript => 0.5,
+        }
+    }
+
+    fn script(self) -> Style {
+        match self {
+            Style::Display | Style::Text => Style::Script,
+            _ => Style::ScriptScript,
+        }
+    }
+
+    fn fraction(self) -> Style {
+        match self {
+            Style::Display => Style::Text,
+            other => other.script(),
+        }
+    }
+
+    fn is_script(self) -> bool {
+        self >= Style::Script
+    }
+}
+
+/// Something drawn inside a [`MathBox`]
+#[derive(Clone, Debug)]
+pub(super) enum Item {
+    Glyph {
+        x: f32,
+        baseline: f32,
+        text: String,
+        size: f32,
+        font: Font,
+    },
+    Rule {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+    /// A path drawn at an offset, stroked with the given width or filled
+    Path {
+        x: f32,
+        y: f32,
+        path: Path,
+        stroke: Option<f32>,
+    },
+}
+
+/// A laid-out sub-formula
+#[derive(Clone, Debug, Default)]
+pub(super) struct MathBox {
+    pub width: f32,
+    /// Extent above the baseline
+    pub height: f32,
+    /// Extent below the baseline
+    pub depth: f32,
+    pub items: Vec<Item>,
+}
+
+impl MathBox {
+    fn empty(width: f32) -> Self {
+        Self {
+            width,
+            ..Self::default()
+        }
+    }
+
+    /// Add another box with its baseline origin at `(x, y)`
+    fn place(&mut self, other: MathBox, x: f32, y: f32) {
+        self.height = self.height.max(other.height - y);
+        self.depth = self.depth.max(other.depth + y);
+        self.width = self.width.max(x + other.width);
+        self.items
+            .extend(other.items.into_iter().map(|item| match item {
+                Item::Glyph {
+                    x: gx,
+                    baseline,
+                    text,
+                    size,
+                    font,
+                } => Item::Glyph {
+                    x: gx + x,
+                    baseline: baseline + y,
+                    text,
+                    size,
+                    font,
+                },
+                Item::Rule {
+                    x: rx,
+                    y: ry,
+                    width,
+                    height,
+                } => Item::Rule {
+                    x: rx + x,
+                    y: ry + y,
+                    width,
+                    height,
+                },
+                Item::Path {
+                    x: px,
+                    y: py,
+                    path,
+                    stroke,
+                } => Item::Path {
+                    x: px + x,
+                    y: py + y,
+                    path,
+                    stroke,
+                },
+            }));
+    }
+
+    /// Append a box to the right on the same baseline
+    fn push(&mut self, other: MathBox) {
+        let x = self.width;
+        self.width += other.width;
+        self.place(other, x, 0.0);
+    }
+}
+
+/// Lay out a math list at a font size
+pub(super) fn layout(nodes: &[Node], size: f32, display: bool) -> MathBox {
+    let style = if display { Style::Display } else { Style::Text };
+    Layout { size }.list(nodes, style)
+}
+
+struct Layout {
+    size: f32,
+}
+
+impl Layout {
+    fn em(&self, style: Style) -> f32 {
+        self.size * style.scale()
+    }
+
+    fn rule_thickness(&self, style: Style) -> f32 {
+        (self.em(style) * 0.05).max(1.0)
+    }
+
+    fn axis(&self, style: Style) -> f32 {
+        self.em(style) * AXIS
+    }
+
+    fn list(&self, nodes: &[Node], style: Style) -> MathBox {
+        let mut laid_out: Vec<(MathBox, Option<Class>)> =
+            nodes.iter().map(|node| self.node(node, style)).collect();
+        reclassify_binaries(&mut laid_out);
+
+        let mut result = MathBox::default();
+        let mut previous: Option<Class> = None;
+        for (child, class) in laid_out {
+            if let (Some(left), Some(right)) = (previous, class) {
+                let mu = spacing(left, right, style.is_script());
+                result.push(MathBox::empty(mu / 18.0 * self.em(style)));
+            }
+            result.push(child);
+            if class.is_some() {
+                previous = class;
+            }
+        }
+        result
+    }
+
+    fn node(&self, node: &Node, style: Style) -> (MathBox, Option<Class>) {
+        match node {
+            Node::Symbol { text, class, font } => {
+                (self.glyph(text, self.em(style), *font), Some(*class))
+            }
+            Node::Space(em) => (MathBox::empty(em * self.em(style)), None),
+            Node::Group(nodes) => (self.list(nodes, style), Some(Class::Ord)),
+            Node::Frac {
+                num,
+                den,
+                bar,
+                style: frac_style,
+            } => {
+                let style = match frac_style {
+                    FracStyle::Auto => style,
+                    FracStyle::Display => Style::Display,
+                    FracStyle::Text => Style::Text.max(style),
+                };
+                (self.fraction(num, den, *bar, style), Some(Class::Inner))
+            }
+            Node::Sqrt { index, body } => {
+                (self.sqrt(index.as_deref(), body, style), Some(Class::Ord))
+            }
+            Node::Scripts { base, sub, sup } => {
+                self.scripts(base, sub.as_deref(), sup.as_deref(), style)
+            }
+            Node::Operator { text, large, .. } => {
+                (self.operator(text, *large, style), Some(Class::Op))
+            }
+            Node::Delimited { left, right, body } => {
+                let body = self.list(body, style);
+                (self.delimited(left, right, body, style), Some(Class::Inner))
+            }
+            Node::SizedDelim { delim, size, class } => (
+                self.delimiter(delim, size * self.em(style), *class == Class::Close, style),
+                Some(*class),
+            ),
+            Node::Matrix {
+                rows,
+                columns,
+                left,
+                right,
+            } => {
+                let matrix = self.matrix(rows, *columns, style);
+                if left.is_empty() && right.is_empty() {
+                    (matrix, Some(Class::Ord))
+                } else {
+                    (
+                        self.delimited(left, right, matrix, style),
+                        Some(Class::Inner),
+                    )
+                }
+            }
+            Node::Accent { accent, body } => (self.accent(*accent, body, style), Some(Class::Ord)),
+        }
+    }
+
+    fn glyph(&self, text: &str, size: f32, font: Font) -> MathBox {
+        let width = measure_text_with_options(text, size, &super::glyph_options(font)).width;
+        let (height, depth) = symbols::extents(text);
+        MathBox {
+            width,
+            height: height * size,
+            depth: depth * size,
+            items: vec![Item::Glyph {
+                x: 0.0,
+                baseline: 0.0,
+                text: text.to_string(),
+                size,
+                font,
+            }],
+        }
+    }
+
+    fn fraction(&self, num: &[Node], den: &[Node], bar: bool, style: Style) -> MathBox {
+        let em = self.em(style);
+        let t = if bar { self.rule_thickness(style) } else { 0.0 };
+        let axis = self.axis(style);
+        let num = self.list(num, style.fraction());
+        let den = self.list(den, style.fraction());
+        let display = style == Style::Display;
+
+        let gap = if display {
+            3.0 * t.max(1.0)
+        } else {
+            t.max(1.0)
+        };
+        let pad = 0.12 * em;
+        let width = num.width.max(den.width) + 2.0 * pad;
+
+        let num_shift =
+            (axis + t / 2.0 + gap + num.depth).max(if display { 0.68 } else { 0.4 } * em);
+        let den_shift =
+            (den.height + gap + t / 2.0 - axis).max(if display { 0.69 } else { 0.35 } * em);
+
+        let mut result = MathBox::empty(width);
+        let num_x = (width - num.width) / 2.0;
+        let den_x = (width - den.width) / 2.0;
+        result.place(num, num_x, -num_shift);
+        result.place(den, den_x, den_shift);
+        if bar {
+            result.items.push(Item::Rule {
+                x: pad / 2.0,
+                y: -axis - t / 2.0,
+                width: width - pad,
+                height: t,
+            });
+        }
+        result
+    }
+
+    fn sqrt(&self, index: Option<&[Node]>, body: &[Node], style: Style) -> MathBox {
+        let em = self.em(style);
+        let t = self.rule_thickness(style);
+        let body = self.list(body, style);
+        let clearance = if style == Style::Display {
+            t + 0.15 * em
+        } else {
+            t + 0.1 * em
+        };
+
+        let top = -(body.height.max(0.45 * em) + clearance);
+        let bottom = body.depth.max(0.1 * em);
+        let sign_width = 0.55 * em;
+        let pad = 0.08 * em;
+
+        // Room for the index over the hook of the radical sign
+        let index_box = index.map(|nodes| self.list(nodes, Style::ScriptScript));
+        let hook = sign_width * 0.5;
+        let shift = index_box
+            .as_ref()
+            .map_or(0.0, |index| (index.width - hook).max(0.0));
+
+        let span = bottom - top;
+        let path = Path::new()
+            .move_to(shift, bottom - span * 0.42)
+            .line_to(shift + sign_width * 0.22, bottom - span * 0.5)
+            .line_to(shift + sign_width * 0.5, bottom)
+            .line_to(shift + sign_width, top)
+            .line_to(shift + sign_width + body.width + pad, top);
+
+        let mut result = MathBox::empty(0.0);
+        result.height = -top + t;
+        result.depth = bottom;
+        result.items.push(Item::Path {
+            x: 0.0,
+            y: 0.0,
+            path,
+            stroke: Some(t),
+        });
+        if let Some(index) = index_box {
+            let baseline = bottom - span * 0.55 - index.depth;
+            let x = shift + hook - index.width.min(shift + hook);
+            result.place(index, x, baseline);
+        }
+        let body_x = shift + sign_width;
+        let body_width = body.width;
+        result.place(body, body_x, 0.0);
+        result.width = body_x + body_width + pad;
+        result
+    }
+
+    fn scripts(
+        &self,
+        base: &Node,
+        sub: Option<&[Node]>,
+        sup: Option<&[Node]>,
+        style: Style,
+    ) -> (MathBox, Option<Class>) {
+        let em = self.em(style);
+        let (base_box, class) = self.node(base, style);
+
+        if let Node::Operator { limits, .. } = base {
+            let stacked = match limits {
+                Limits::Always => true,
+                Limits::Display => style == Style::Display,
+                Limits::Never => false,
+            };
+            if stacked {
+                return (self.limits(base_box, sub, sup, style), class);
+            }
+        }
+
+        let simple = matches!(base, Node::Symbol { text, .. } if text.chars().count() == 1);
+        let script_style = style.script();
+        let script_em = self.em(script_style);
+        let t = self.rule_thickness(style);
+
+        let sup_box = sup.map(|nodes| self.list(nodes, script_style));
+        let sub_box = sub.map(|nodes| self.list(nodes, script_style));
+
+        let mut up = if simple {
+            0.0
+        } else {
+            base_box.height - 0.39 * script_em
+        };
+        let mut down = if simple {
+            0.0
+        } else {
+            base_box.depth + 0.05 * script_em
+        };
+        if let Some(sup) = &sup_box {
+            let min = if style == Style::Display { 0.41 } else { 0.36 } * em;
+            up = up.max(min).max(sup.depth + 0.12 * em);
+        }
+        if let Some(sub) = &sub_box {
+            down = down.max(0.15 * em);
+            if sup_box.is_none() {
+                down = down.max(sub.height - 0.4 * em);
+            }
+        }
+        if let (Some(sup), Some(sub)) = (&sup_box, &sub_box) {
+            let gap = (up - sup.depth) - (sub.height - down);
+            if gap < 4.0 * t {
+                down += 4.0 * t - gap;
+            }
+        }
+
+        let x = base_box.width;
+        let mut result = MathBox::default();
+        result.push(base_box);
+        let mut script_width: f32 = 0.0;
+        if let Some(sup) = sup_box {
+            script_width = script_width.max(sup.width);
+            result.place(sup, x, -up);
+        }
+        if let Some(sub) = sub_box {
+            script_width = script_width.max(sub.width);
+            result.place(sub, x, down);
+        }
+        result.width = x + script_width + 0.05 * em;
+        (result, class)
+    }
+
+    /// Scripts set above and below an operator
+    fn limits(
+        &self,
+        op: MathBox,
+        sub: Option<&[Node]>,
+        sup: Option<&[Node]>,
+        style: Style,
+    ) -> MathBox {
+        let em = self.em(style);
+        let gap = 0.15 * em;
+        let script_style = style.script();
+        let sup_box = sup.map(|nodes| self.list(nodes, script_style));
+        let sub_box = sub.map(|nodes| self.list(nodes, script_style));
+
+        let width = op
+            .width
+            .max(sup_box.as_ref().map_or(0.0, |b| b.width))
+            .max(sub_box.as_ref().map_or(0.0, |b| b.width));
+        let mut result = MathBox::empty(width);
+        let op_height = op.height;
+        let op_depth = op.depth;
+        let op_x = (width - op.width) / 2.0;
+        result.place(op, op_x, 0.0);
+        if let Some(sup) = sup_box {
+            let y = -(op_height + gap + sup.depth);
+            let x = (width - sup.width) / 2.0;
+            result.place(sup, x, y);
+        }
+        if let Some(sub) = sub_box {
+            let y = op_depth + gap + sub.height;
+            let x = (width - sub.width) / 2.0;
+            result.place(sub, x, y);
+        }
+        result
+    }
+
+    fn operator(&self, text: &str, large: bool, style: Style) -> MathBox {
+        if !large {
+            return self.glyph(text, self.em(style), Font::Roman);
+        }
+        let scale = if style == Style::Display { 1.6 } else { 1.15 };
+        let size = self.em(style) * scale;
+        let axis = self.axis(style);
+        let mut glyph = self.glyph(text, size, Font::Roman);
+        // Centre the glyph on the axis; its ink sits about a third of its size
+        // above its baseline
+        let baseline = -axis + 0.35 * size;
+        let half = 0.45 * size;
+        glyph.height = 0.0;
+        glyph.depth = 0.0;
+        let mut result = MathBox::empty(glyph.width);
+        result.place(glyph, 0.0, baseline);
+        result.height = axis + half;
+        result.depth = half - axis;
+        result
+    }
+
+    fn delimited(&self, left: &str, right: &str, body: MathBox, style: Style) -> MathBox {
+        let axis = self.axis(style);
+        let extent = (body.height - axis).max(body.depth + axis);
+        let total = 2.0 * extent;
+        let mut result = MathBox::default();
+        result.push(self.delimiter(left, total, false, style));
+        result.push(body);
+        result.push(self.delimiter(right, total, true, style));
+        result
+    }
+
+    /// A delimiter covering `total` around the axis; a glyph when small enough
+    fn delimiter(&self, delim: &str, total: f32, closing: bool, style: Style) -> MathBox {
+        let em = self.em(style);
+        if delim.is_empty() {
+            return MathBox::empty(0.12 * em);
+        }
+        if total <= 1.2 * em {
+            return self.glyph(delim, em, Font::Roman);
+        }
+
+        let axis = self.axis(style);
+        let top = -axis - total / 2.0;
+        let bottom = -axis + total / 2.0;
+        let mid = -axis;
+        let h = total;
+        let w = (0.35 + 0.04 * total / em).min(0.6) * em;
+        let stroke = (0.06 * em).max(1.0);
+
+        // Shapes are drawn for the opening form and mirrored for the closing one
+        let mirror = closing && !matches!(delim, "|" | "‖" | "/");
+        let x = |v: f32| if mirror { w - v * w } else { v * w };
+        let path = match delim {
+            "(" | ")" => Path::new().move_to(x(0.85), top).cubic_to(
+                x(0.15),
+                top + h * 0.25,
+                x(0.15),
+                bottom - h * 0.25,
+                x(0.85),
+                bottom,
+            ),
+            "[" | "]" => Path::new()
+                .move_to(x(0.8), top)
+                .line_to(x(0.3), top)
+                .line_to(x(0.3), bottom)
+                .line_to(x(0.8), bottom),
+            "{" | "}" => Path::new()
+                .move_to(x(0.85), top)
+                .quad_to(x(0.45), top, x(0.45), top + h * 0.12)
+                .line_to(x(0.45), mid - h * 0.08)
+                .quad_to(x(0.45), mid, x(0.1), mid)
+                .quad_to(x(0.45), mid, x(0.45), mid + h * 0.08)
+                .line_to(x(0.45), bottom - h * 0.12)
+                .quad_to(x(0.45), bottom, x(0.85), bottom),
+            "⟨" | "⟩" => Path::new()
+                .move_to(x(0.8), top)
+                .line_to(x(0.2), mid)
+                .line_to(x(0.8), bottom),
+            "⌊" | "⌋" => Path::new()
+                .move_to(x(0.3), top)
+                .line_to(x(0.3), bottom)
+                .line_to(x(0.8), bottom),
+            "⌈" | "⌉" => Path::new()
+                .move_to(x(0.8), top)
+                .line_to(x(0.3), top)
+                .line_to(x(0.3), bottom),
+            "‖" => Path::new()
+                .move_to(0.35 * w, top)
+                .line_to(0.35 * w, bottom)
+                .move_to(0.65 * w, top)
+                .line_to(0.65 * w, bottom),
+            "/" => Path::new().move_to(0.9 * w, top).line_to(0.1 * w, bottom),
+            "|" => Path::new().move_to(0.5 * w, top).line_to(0.5 * w, bottom),
+            _ => return self.glyph(delim, em, Font::Roman),
+        };
+
+        MathBox {
+            width: w,
+            height: -top,
+            depth: bottom,
+            items: vec![Item::Path {
+                x: 0.0,
+                y: 0.0,
+                path,
+                stroke: Some(stroke),
+            }],
+        }
+    }
+
+    fn matrix(&self, rows: &[Vec<Vec<Node>>], columns: Columns, style: Style) -> MathBox {
+        let em = self.em(style);
+        let cell_style = style.max(Style::Text);
+        let cells: Vec<Vec<MathBox>> = rows
+            .iter()
+            .map(|row| row.iter().map(|cell| self.list(cell, cell_style)).collect())
+            .collect();
+
+        let column_count = cells.iter().map(Vec::len).max().unwrap_or(0);
+        let mut widths = vec![0.0f32; column_count];
+        for row in &cells {
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.width);
+            }
+        }
+        let column_gap = |i: usize| match columns {
+            Columns::RightLeft if i % 2 == 1 => 0.0,
+            Columns::RightLeft => 1.5 * em,
+            Columns::Left => 1.0 * em,
+            Columns::Center => 0.8 * em,
+        };
+        let row_gap = 0.3 * em;
+        let pad = 0.15 * em;
+
+        let metrics: Vec<(f32, f32)> = cells
+            .iter()
+            .map(|row| {
+                row.iter().fold((0.7 * em, 0.3 * em), |(h, d), cell| {
+                    (h.max(cell.height), d.max(cell.depth))
+                })
+            })
+            .collect();
+        let total: f32 = metrics.iter().map(|(h, d)| h + d).sum::<f32>()
+            + row_gap * metrics.len().saturating_sub(1) as f32;
+
+        let mut result = MathBox::default();
+        let mut y = -self.axis(style) - total / 2.0;
+        for (row, (h, d)) in cells.into_iter().zip(metrics) {
+            let baseline = y + h;
+            let mut x = pad;
+            for (i, cell) in row.into_iter().enumerate() {
+                let slack = widths[i] - cell.width;
+                let offset = match columns {
+                    Columns::Center => slack / 2.0,
+                    Columns::Left => 0.0,
+                    Columns::RightLeft if i % 2 == 0 => slack,
+                    Columns::RightLeft => 0.0,
+                };
+                result.place(cell, x + offset, baseline);
+                x += widths[i];
+                if i + 1 < column_count {
+                    x += column_gap(i);
+                }
+            }
+            y = baseline + d + row_gap;
+        }
+        result.width = pad * 2.0
+            + widths.iter().sum::<f32>()
+            + (0..column_count.saturating_sub(1))
+                .map(column_gap)
+                .sum::<f32>();
+        result.height = result.height.max(self.axis(style) + total / 2.0);
+        result.depth = result.depth.max(total / 2.0 - self.axis(style));
+        result
+    }
+
+    fn accent(&self, accent: Accent, body: &[Node], style: Style) -> MathBox {
+        let em = self.em(style);
+        let t = self.rule_thickness(style);
+        let body = self.list(body, style);
+        let width = body.width;
+        let top = -(body.height.max(0.45 * em) + 0.08 * em);
+        let centre = width / 2.0;
+
+        let mut result = MathBox::default();
+        let mut above = 0.0;
+        match accent {
+            Accent::Bar | Accent::Overline => {
+                let inset = if accent == Accent::Bar {
+                    0.1 * width
+                } else {
+                    0.0
+                };
+                result.items.push(Item::Rule {
+                    x: inset,
+                    y: top - t,
+                    width: width - 2.0 * inset,
+                    height: t,
+                });
+                above = t;
+            }
+            Accent::Underline => {
+                result.items.push(Item::Rule {
+                    x: 0.0,
+                    y: body.depth + 0.1 * em,
+                    width,
+                    height: t,
+                });
+                result.depth = body.depth + 0.1 * em + t;
+            }
+            Accent::Hat => {
+                let half = (width * 0.45).clamp(0.2 * em, 0.6 * em);
+                let rise = 0.15 * em;
+                result.items.push(Item::Path {
+                    x: 0.0,
+                    y: 0.0,
+                    path: Path::new()
+                        .move_to(centre - half, top)
+                        .line_to(centre, top - rise)
+                        .line_to(centre + half, top),
+                    stroke: Some(t),
+                });
+                above = rise;
+            }
+            Accent::Vec => {
+                let y = top - 0.06 * em;
+                let head = 0.12 * em;
+                let (left, right) = (0.05 * width, width.max(0.4 * em) - 0.05 * width);
+                result.items.push(Item::Path {
+                    x: 0.0,
+                    y: 0.0,
+                    path: Path::new()
+                        .move_to(left, y)
+                        .line_to(right, y)
+                        .move_to(right - head, y - head * 0.7)
+                        .line_to(right, y)
+                        .line_to(right - head, y + head * 0.7),
+                    stroke: Some(t),
+                });
+                above = 0.06 * em + head * 0.7;
+            }
+            Accent::Tilde => {
+                let half = (width * 0.45).clamp(0.2 * em, 0.6 * em);
+                let amp = 0.06 * em;
+                let y = top - amp;
+                result.items.push(Item::Path {
+                    x: 0.0,
+                    y: 0.0,
+                    path: Path::new().move_to(centre - half, y + amp).cubic_to(
+                        centre - half * 0.3,
+                        y - 2.0 * amp,
+                        centre + half * 0.3,
+                        y + 3.0 * amp,
+                        centre + half,
+                        y - amp,
+                    ),
+                    stroke: Some(t),
+                });
+                above = 2.0 * amp;
+            }
+            Accent::Dot | Accent::Ddot => {
+                let r = 0.05 * em;
+                let y = top - r;
+                let xs: &[f32] = if accent == Accent::Dot {
+                    &[0.0]
+                } else {
+                    &[-0.12, 0.12]
+                };
+                for dx in xs {
+                    result.items.push(Item::Path {
+                        x: 0.0,
+                        y: 0.0,
+                        path: Path::circle(blinc_core::Point::new(centre + dx * em, y), r),
+                        stroke: None,
+                    });
+                }
+                above = 2.0 * r;
+            }
+        }
+        result.height = result.height.max(-top + above);
+        result.place(body, 0.0, 0.0);
+        result.width = width;
+        result
+    }
+}
+
+/// Treat binary operators that have nothing to operate on as ordinary
+fn reclassify_binaries(atoms: &mut [(MathBox, Option<Class>)]) {
+    let mut previous: Option<Class> = None;
+    for i in 0..atoms.len() {
+        let Some(class) = atoms[i].1 else {
+            continue;
+        };
+        if class == Class::Bin {
+            let after_operand = matches!(previous, Some(Class::Ord | Class::Close | Class::Inner));
+            let next = atoms[i + 1..].iter().find_map(|(_, c)| *c);
+            let before_operand = matches!(
+                next,
+                Some(Class::Ord | Class::Op | Class::Open | Class::Inner)
+            );
+            if !after_operand || !before_operand {
+                atoms[i].1 = Some(Class::Ord);
+            }
+        }
+        previous = atoms[i].1;
+    }
+}
+
+/// Space between two atoms in mu (1/18 em), after TeX's spacing table
+fn spacing(left: Class, right: Class, script: bool) -> f32 {
+    use Class::*;
+    const THIN: f32 = 3.0;
+    const MED: f32 = 4.0;
+    const THICK: f32 = 5.0;
+    // Spaces that TeX drops in script styles
+    let (space, always) = match (left, right) {
+        (Ord, Op) | (Op, Ord) | (Op, Op) | (Close, Op) | (Inner, Op) => (THIN, true),
+        (Ord, Inner)
+        | (Op, Inner)
+        | (Close, Inner)
+        | (Inner, Ord)
+        | (Inner, Open)
+        | (Inner, Punct)
+        | (Inner, Inner)
+        | (Punct, _) => (THIN, false),
+        (Bin, _) | (_, Bin) => (MED, false),
+        (Rel, Rel) | (Rel, Close) | (Rel, Punct) => (0.0, true),
+        (Rel, _) | (_, Rel) => (THICK, false),
+        _ => (0.0, true),
+    };
+    if script && !always {
+        0.0
+    } else {
+        space
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse::parse;
+    use super::*;
+
+    fn boxed(source: &str, display: bool) -> MathBox {
+        layout(&parse(source).unwrap(), 20.0, display)
+    }
+
+    fn glyphs(b: &MathBox) -> Vec<(&str, f32, f32)> {
+        b.items
+            .iter()
+            .filter_map(|item| match item {
+                Item::Glyph {
+                    text,
+                    baseline,
+                    size,
+                    ..
+                } => Some((text.as_str(), *baseline, *size)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fraction_stacks_around_axis() {
+        let b = boxed(r"\frac{a}{b}", true);
+        let g = glyphs(&b);
+        let (_, num_y, _) = g[0];
+        let (_, den_y, _) = g[1];
+        assert!(num_y < -5.0 && den_y > 5.0, "{g:?}");
+        let rule = b
+            .items
+            .iter()
+            .find_map(|item| match item {
+                Item::Rule { y, height, .. } => Some(y + height / 2.0),
+                _ => None,
+            })
+            .unwrap();
+        assert!((rule + AXIS * 20.0).abs() < 0.01);
+
+        // Text-style fractions use smaller parts
+        let inline = boxed(r"\frac{a}{b}", false);
+        assert_eq!(glyphs(&inline)[0].2, 14.0);
+        assert!(inline.height < b.height);
+    }
+
+    #[test]
+    fn test_scripts_and_spacing() {
+        let b = boxed("x^2_i", false);
+        let g = glyphs(&b);
+        assert_eq!(g[1].2, 14.0);
+        assert!(g[1].1 < 0.0 && g[2].1 > 0.0, "{g:?}");
+
+        // Binary and relation spacing, and unary minus without it
+        let spaced = boxed("a+b=c", false);
+        let tight = boxed("abc", false);
+        let plus = glyphs(&boxed("+", false)).len();
+        assert_eq!(plus, 1);
+        assert!(spaced.width > tight.width + 2.0 * (4.0 + 5.0) / 18.0 * 20.0);
+        assert_eq!(spacing(Class::Bin, Class::Ord, true), 0.0);
+        let mut atoms = vec![
+            (MathBox::default(), Some(Class::Bin)),
+            (MathBox::default(), Some(Class::Ord)),
+        ];
+        reclassify_binaries(&mut atoms);
+        assert_eq!(atoms[0].1, Some(Class::Ord));
+    }
+
+    #[test]
+    fn test_large_operator_limits() {
+        let display = boxed(r"\sum_{i=0}^{n} i", true);
+        let inline = boxed(r"\sum_{i=0}^{n} i", false);
+        let g = glyphs(&display);
+        let sum_x = |b: &MathBox| match &b
+            .items
+            .iter()
+            .find(|item| matches!(item, Item::Glyph { text, .. } if text == "∑"))
+        {
+            Some(Item::Glyph { x, .. }) => *x,
+            _ => panic!(),
+        };
+        // Limits are centred over the operator in display style, beside it inline
+        let n = g.iter().find(|(t, _, _)| *t == "n").unwrap();
+        assert!(n.1 < -display.height / 2.0);
+        assert!(sum_x(&display) > 0.0);
+        assert!(display.height > inline.height);
+        assert!(inline.width < display.width + 20.0);
+    }
+
+    #[test]
+    fn test_radicals_and_delimiters() {
+        let b = boxed(r"\sqrt{x}", false);
+        assert!(matches!(
+            b.items[0],
+            Item::Path {
+                stroke: Some(_),
+                ..
+            }
+        ));
+        assert!(b.width > glyphs(&b)[0].2 * 0.5);
+
+        let small = boxed(r"\left( x \right)", false);
+        assert_eq!(glyphs(&small)[0].0, "(");
+        let tall = boxed(r"\left( \frac{a}{b} \right)", true);
+        let paths = tall
+            .items
+            .iter()
+            .filter(|item| matches!(item, Item::Path { .. }))
+            .count();
+        assert_eq!(paths, 2);
+
+        let m = boxed(r"\begin{bmatrix} 1 & 0 \\ 0 & 1 \end{bmatrix}", false);
+        assert_eq!(glyphs(&m).len(), 4);
+        assert!(m.height + m.depth > 2.0 * 20.0);
+    }
+}
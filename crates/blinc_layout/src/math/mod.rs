@@ -0,0 +1,378 @@
+//! Math typesetting
+//!
+//! Typesets a subset of TeX math into layout elements. Glyphs are text
+//! elements in the serif font, measured with the active text measurer;
+//! fraction bars, radicals, stretchy delimiters and accents are drawn as
+//! `DrawContext` paths on a canvas behind them.
+//!
+//! Supported input:
+//!
+//! - letters (italic), numbers, operators and punctuation with TeX spacing
+//! - `^`, `_` and primes, `\frac`, `\dfrac`, `\tfrac`, `\binom`, `\sqrt[n]{…}`
+//! - `\sum`, `\prod`, `\int` and other large operators, with `\limits` and
+//!   `\nolimits`, and named functions such as `\sin`, `\log`, `\lim`
+//! - Greek letters, relations, arrows, set and logic symbols
+//! - `\left … \right`, `\big` and friends
+//! - `matrix`, `pmatrix`, `bmatrix`, `Bmatrix`, `vmatrix`, `Vmatrix`,
+//!   `cases`, `aligned` and `gathered` environments
+//! - `\text`, `\mathrm`, `\mathbf`, `\mathit`, `\mathbb`, `\operatorname`
+//! - accents (`\hat`, `\bar`, `\vec`, `\tilde`, `\dot`, `\ddot`,
+//!   `\overline`, `\underline`) and spacing commands
+//!
+//! # Example
+//!
+//! ```ignore
+//! use blinc_layout::math::{display_math, math};
+//!
+//! div()
+//!     .child(math(r"e^{i\pi} + 1 = 0"))
+//!     .child(display_math(r"\int_0^\infty e^{-x^2}\,dx = \frac{\sqrt{\pi}}{2}"))
+//! ```
+
+mod layout;
+mod parse;
+mod symbols;
+
+use std::ops::{Deref, DerefMut};
+
+use blinc_core::{Brush, Color, DrawContext, LineCap, LineJoin, Rect, Stroke, Transform};
+use blinc_theme::{ColorToken, ThemeState};
+
+use crate::canvas::canvas;
+use crate::div::{div, Div, ElementBuilder, ElementTypeId, GenericFont};
+use crate::element::RenderProps;
+use crate::text::text;
+use crate::text_measure::{measure_text_with_options, TextLayoutOptions};
+use crate::tree::{LayoutNodeId, LayoutTree};
+
+use layout::{Item, MathBox};
+use parse::Font;
+
+pub use parse::MathError;
+
+/// Typeset TeX math element
+///
+/// Invalid input renders as its source in the theme's error color; the parse
+/// error is available from [`Math::error`].
+pub struct Math {
+    inner: Div,
+    source: String,
+    display: bool,
+    font_size: f32,
+    color: Color,
+    baseline_depth: Option<f32>,
+    error: Option<MathError>,
+    width: f32,
+    ascent: f32,
+    descent: f32,
+}
+
+impl Math {
+    /// Typeset math in text style
+    pub fn new(source: impl Into<String>) -> Self {
+        let mut math = Self {
+            inner: div(),
+            source: source.into(),
+            display: false,
+            font_size: 16.0,
+            color: ThemeState::get().color(ColorToken::TextPrimary),
+            baseline_depth: None,
+            error: None,
+            width: 0.0,
+            ascent: 0.0,
+            descent: 0.0,
+        };
+        math.rebuild();
+        math
+    }
+
+    /// Use display style: larger operators, limits above and below, and
+    /// full-size fractions
+    pub fn display(mut self, display: bool) -> Self {
+        self.display = display;
+        self.rebuild();
+        self
+    }
+
+    /// Set the font size in pixels
+    pub fn size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self.rebuild();
+        self
+    }
+
+    /// Set the color of glyphs and rules
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self.rebuild();
+        self
+    }
+
+    /// Make the element end this far below the baseline
+    ///
+    /// Flex rows align baselines by the bottom edge of their children, so
+    /// inline math sets this to the depth of the surrounding text. Anything
+    /// deeper overflows and is accounted for with a bottom margin.
+    pub fn baseline_depth(mut self, depth: f32) -> Self {
+        self.baseline_depth = Some(depth);
+        self.rebuild();
+        self
+    }
+
+    /// TeX source
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Parse error, if the source is not valid
+    pub fn error(&self) -> Option<&MathError> {
+        self.error.as_ref()
+    }
+
+    /// Width of the typeset formula
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// Extent of the formula above the baseline
+    pub fn ascent(&self) -> f32 {
+        self.ascent
+    }
+
+    /// Extent of the formula below the baseline
+    pub fn descent(&self) -> f32 {
+        self.descent
+    }
+
+    fn rebuild(&mut self) {
+        match parse::parse(&self.source) {
+            Ok(nodes) => {
+                self.error = None;
+                let math_box = layout::layout(&nodes, self.font_size, self.display);
+                self.width = math_box.width;
+                self.ascent = math_box.height;
+                self.descent = math_box.depth;
+                self.inner = self.render(math_box);
+            }
+            Err(error) => {
+                self.error = Some(error);
+                let color = ThemeState::get().color(ColorToken::Error);
+                self.inner = div().child(
+                    text(&self.source)
+                        .size(self.font_size)
+                        .monospace()
+                        .color(color)
+                        .line_height(1.0)
+                        .v_baseline()
+                        .no_wrap(),
+                );
+            }
+        }
+    }
+
+    fn render(&self, math_box: MathBox) -> Div {
+        let height = math_box.height;
+        let depth = self.baseline_depth.unwrap_or(math_box.depth);
+        let mut root = div()
+            .relative()
+            .w(math_box.width)
+            .h(height + depth)
+            .flex_shrink_0();
+        if math_box.depth > depth {
+            // mb() takes 4px units
+            root = root.mb((math_box.depth - depth) / 4.0);
+        }
+
+        let (glyphs, shapes): (Vec<Item>, Vec<Item>) = math_box
+            .items
+            .into_iter()
+            .partition(|item| matches!(item, Item::Glyph { .. }));
+
+        if !shapes.is_empty() {
+            let color = self.color;
+            let shapes = canvas(move |ctx: &mut dyn DrawContext, _| {
+                draw_shapes(ctx, &shapes, height, color)
+            })
+            .absolute()
+            .left(0.0)
+            .top(0.0)
+            .w(math_box.width)
+            .h(height + math_box.depth);
+            root = root.child(shapes);
+        }
+
+        for glyph in glyphs {
+            let Item::Glyph {
+                x,
+                baseline,
+                text: content,
+                size,
+                font,
+            } = glyph
+            else {
+                continue;
+            };
+            let ascender = measure_text_with_options(&content, size, &glyph_options(font)).ascender;
+            let mut glyph = text(content)
+                .size(size)
+                .serif()
+                .color(self.color)
+                .line_height(1.0);
+            if matches!(font, Font::Italic | Font::BoldItalic) {
+                glyph = glyph.italic();
+            }
+            if matches!(font, Font::Bold | Font::BoldItalic) {
+                glyph = glyph.bold();
+            }
+            root = root.child(
+                div()
+                    .absolute()
+                    .left(x)
+                    .top(height + baseline - ascender)
+                    .child(glyph.v_baseline().no_wrap()),
+            );
+        }
+        root
+    }
+}
+
+/// Measurement options matching the glyph text elements
+fn glyph_options(font: Font) -> TextLayoutOptions {
+    let mut options = TextLayoutOptions::new().with_generic_font(GenericFont::Serif);
+    if matches!(font, Font::Italic | Font::BoldItalic) {
+        options = options.italic();
+    }
+    if matches!(font, Font::Bold | Font::BoldItalic) {
+        options = options.bold();
+    }
+    options
+}
+
+/// Draw rules and paths with the baseline at `baseline`
+fn draw_shapes(ctx: &mut dyn DrawContext, shapes: &[Item], baseline: f32, color: Color) {
+    for shape in shapes {
+        match shape {
+            Item::Rule {
+                x,
+                y,
+                width,
+                height,
+            } => ctx.fill_rect(
+                Rect::new(*x, baseline + y, *width, *height),
+                0.0.into(),
+                Brush::Solid(color),
+            ),
+            Item::Path { x, y, path, stroke } => {
+                ctx.push_transform(Transform::translate(*x, baseline + y));
+                match stroke {
+                    Some(width) => ctx.stroke_path(
+                        path,
+                        &Stroke::new(*width)
+                            .with_cap(LineCap::Round)
+                            .with_join(LineJoin::Round),
+                        Brush::Solid(color),
+                    ),
+                    None => ctx.fill_path(path, Brush::Solid(color)),
+                }
+                ctx.pop_transform();
+            }
+            Item::Glyph { .. } => {}
+        }
+    }
+}
+
+impl Deref for Math {
+    type Target = Div;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for Math {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl ElementBuilder for Math {
+    fn build(&self, tree: &mut LayoutTree) -> LayoutNodeId {
+        self.inner.build(tree)
+    }
+
+    fn render_props(&self) -> RenderProps {
+        self.inner.render_props()
+    }
+
+    fn children_builders(&self) -> &[Box<dyn ElementBuilder>] {
+        self.inner.children_builders()
+    }
+
+    fn element_type_id(&self) -> ElementTypeId {
+        ElementTypeId::Div
+    }
+
+    fn semantic_type_name(&self) -> Option<&'static str> {
+        Some("math")
+    }
+
+    fn event_handlers(&self) -> Option<&crate::event_handler::EventHandlers> {
+        ElementBuilder::event_handlers(&self.inner)
+    }
+
+    fn layout_style(&self) -> Option<&taffy::Style> {
+        self.inner.layout_style()
+    }
+}
+
+/// Typeset inline (text style) math
+///
+/// # Example
+///
+/// ```ignore
+/// math(r"a^2 + b^2 = c^2").size(18.0)
+/// ```
+pub fn math(source: impl Into<String>) -> Math {
+    Math::new(source)
+}
+
+/// Typeset display style math
+pub fn display_math(source: impl Into<String>) -> Math {
+    Math::new(source).display(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn init_theme() {
+        let _ = ThemeState::try_get().unwrap_or_else(|| {
+            ThemeState::init_default();
+            ThemeState::get()
+        });
+    }
+
+    #[test]
+    fn test_math_builds_glyphs_and_shapes() {
+        init_theme();
+        let m = math(r"\frac{1}{\sqrt{x}}").size(20.0);
+        assert!(m.error().is_none());
+        // Canvas for the bar and radical, then one element per glyph
+        assert_eq!(m.children_builders().len(), 3);
+        assert!(m.ascent() > 0.0 && m.descent() > 0.0);
+
+        let mut tree = LayoutTree::new();
+        m.build(&mut tree);
+        assert!(!tree.is_empty());
+
+        let display = display_math(r"\frac{1}{\sqrt{x}}").size(20.0);
+        assert!(display.ascent() > m.ascent());
+    }
+
+    #[test]
+    fn test_math_error_falls_back_to_source() {
+        init_theme();
+        let m = math(r"\frac{a");
+        assert_eq!(m.error().unwrap().message, "missing '}'");
+        assert_eq!(m.children_builders().len(), 1);
+    }
+}
@@ -0,0 +1,831 @@
+//! TeX-subset parser producing a math list
+
+use std::fmt;
+
+use super::symbols;
+
+/// Spacing class of an atom, as in TeX's inter-atom spacing table
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Class {
+    Ord,
+    Op,
+    Bin,
+    Rel,
+    Open,
+    Close,
+    Punct,
+    Inner,
+}
+
+/// Font a symbol is set in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Font {
+    Italic,
+    Roman,
+    Bold,
+    BoldItalic,
+}
+
+/// Where an operator's scripts go
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Limits {
+    /// Above and below in display style, as scripts otherwise
+    Display,
+    /// Always above and below (`\limits`)
+    Always,
+    /// Always as scripts (`\nolimits`)
+    Never,
+}
+
+/// Style forced on the parts of a fraction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum FracStyle {
+    Auto,
+    Display,
+    Text,
+}
+
+/// Accent drawn over or under its body
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Accent {
+    Hat,
+    Bar,
+    Overline,
+    Underline,
+    Vec,
+    Tilde,
+    Dot,
+    Ddot,
+}
+
+/// How the cells of a matrix environment are aligned
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Columns {
+    Center,
+    Left,
+    /// Alternating right and left, for `aligned`
+    RightLeft,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Node {
+    Symbol {
+        text: String,
+        class: Class,
+        font: Font,
+    },
+    /// Horizontal space in em
+    Space(f32),
+    Group(Vec<Node>),
+    Frac {
+        num: Vec<Node>,
+        den: Vec<Node>,
+        bar: bool,
+        style: FracStyle,
+    },
+    Sqrt {
+        index: Option<Vec<Node>>,
+        body: Vec<Node>,
+    },
+    Scripts {
+        base: Box<Node>,
+        sub: Option<Vec<Node>>,
+        sup: Option<Vec<Node>>,
+    },
+    Operator {
+        text: String,
+        large: bool,
+        limits: Limits,
+    },
+    /// `\left … \right`; an empty delimiter is `.`
+    Delimited {
+        left: String,
+        right: String,
+        body: Vec<Node>,
+    },
+    /// `\big(` and friends, with the height in em
+    SizedDelim {
+        delim: String,
+        size: f32,
+        class: Class,
+    },
+    Matrix {
+        rows: Vec<Vec<Vec<Node>>>,
+        columns: Columns,
+        left: String,
+        right: String,
+    },
+    Accent {
+        accent: Accent,
+        body: Vec<Node>,
+    },
+}
+
+/// Error from parsing TeX math
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MathError {
+    /// What went wrong
+    pub message: String,
+    /// Byte offset in the source
+    pub offset: usize,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for MathError {}
+
+/// Parse TeX math source into a math list
+pub(super) fn parse(source: &str) -> Result<Vec<Node>, MathError> {
+    let mut parser = Parser {
+        source,
+        chars: source.char_indices().collect(),
+        pos: 0,
+    };
+    parser.parse_list(Stop::Eof)
+}
+
+/// What ends the list being parsed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Stop {
+    Eof,
+    Brace,
+    Bracket,
+    Right,
+    Cell,
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    chars: Vec<(usize, char)>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).map(|&(_, c)| c)
+    }
+
+    fn offset(&self) -> usize {
+        self.chars
+            .get(self.pos)
+            .map_or(self.source.len(), |&(i, _)| i)
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, MathError> {
+        Err(MathError {
+            message: message.into(),
+            offset: self.offset(),
+        })
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// The control word or symbol at the current position, without consuming it
+    fn peek_command(&self) -> Option<String> {
+        if self.peek() != Some('\\') {
+            return None;
+        }
+        let rest = &self.chars[self.pos + 1..];
+        let letters: String = rest
+            .iter()
+            .map(|&(_, c)| c)
+            .take_while(char::is_ascii_alphabetic)
+            .collect();
+        if letters.is_empty() {
+            rest.first().map(|&(_, c)| c.to_string())
+        } else {
+            Some(letters)
+        }
+    }
+
+    fn read_command(&mut self) -> Result<String, MathError> {
+        let Some(name) = self.peek_command() else {
+            return self.error("expected a command");
+        };
+        if name.is_empty() {
+            return self.error("expected a command name after \\");
+        }
+        self.pos += 1 + name.chars().count();
+        Ok(name)
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), MathError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            self.error(format!("expected '{c}'"))
+        }
+    }
+
+    fn parse_list(&mut self, stop: Stop) -> Result<Vec<Node>, MathError> {
+        let mut nodes: Vec<Node> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            let Some(c) = self.peek() else {
+                return match stop {
+                    Stop::Eof => Ok(nodes),
+                    Stop::Brace => self.error("missing '}'"),
+                    Stop::Bracket => self.error("missing ']'"),
+                    Stop::Right => self.error("missing \\right"),
+                    Stop::Cell => self.error("missing \\end"),
+                };
+            };
+            match c {
+                '}' => {
+                    if stop != Stop::Brace {
+                        return self.error("unexpected '}'");
+                    }
+                    self.pos += 1;
+                    return Ok(nodes);
+                }
+                ']' if stop == Stop::Bracket => {
+                    self.pos += 1;
+                    return Ok(nodes);
+                }
+                '&' => {
+                    if stop == Stop::Cell {
+                        return Ok(nodes);
+                    }
+                    return self.error("'&' outside of an environment");
+                }
+                '^' | '_' => {
+                    self.pos += 1;
+                    let script = self.parse_arg()?;
+                    self.attach(&mut nodes, c == '^', script)?;
+                }
+                '\'' => {
+                    let mut primes = String::new();
+                    while self.peek() == Some('\'') {
+                        primes.push('′');
+                        self.pos += 1;
+                    }
+                    let prime = Node::Symbol {
+                        text: primes,
+                        class: Class::Ord,
+                        font: Font::Roman,
+                    };
+                    self.attach(&mut nodes, true, vec![prime])?;
+                }
+                '\\' => match self.peek_command().as_deref() {
+                    Some("\\") => {
+                        if stop == Stop::Cell {
+                            return Ok(nodes);
+                        }
+                        // Line breaks outside of environments are ignored
+                        self.pos += 2;
+                    }
+                    Some("end") if stop == Stop::Cell => return Ok(nodes),
+                    Some("right") if stop == Stop::Right => return Ok(nodes),
+                    Some("limits") | Some("nolimits") => {
+                        let limits = if self.read_command()? == "limits" {
+                            Limits::Always
+                        } else {
+                            Limits::Never
+                        };
+                        match operator_mut(nodes.last_mut()) {
+                            Some(l) => *l = limits,
+                            None => return self.error("\\limits must follow an operator"),
+                        }
+                    }
+                    _ => nodes.push(self.parse_atom()?),
+                },
+                _ => nodes.push(self.parse_atom()?),
+            }
+        }
+    }
+
+    /// Attach a superscript or subscript to the last node
+    fn attach(
+        &mut self,
+        nodes: &mut Vec<Node>,
+        superscript: bool,
+        script: Vec<Node>,
+    ) -> Result<(), MathError> {
+        let last = match nodes.pop() {
+            Some(node @ Node::Scripts { .. }) => node,
+            Some(node) => Node::Scripts {
+                base: Box::new(node),
+                sub: None,
+                sup: None,
+            },
+            None => Node::Scripts {
+                base: Box::new(Node::Group(Vec::new())),
+                sub: None,
+                sup: None,
+            },
+        };
+        let Node::Scripts { base, sub, sup } = last else {
+            unreachable!()
+        };
+        let (sub, sup) = if superscript {
+            match sup {
+                // Primes followed by a superscript share it
+                Some(mut existing) if is_primes(&existing) => {
+                    existing.extend(script);
+                    (sub, Some(existing))
+                }
+                Some(_) => return self.error("double superscript"),
+                None => (sub, Some(script)),
+            }
+        } else {
+            if sub.is_some() {
+                return self.error("double subscript");
+            }
+            (Some(script), sup)
+        };
+        nodes.push(Node::Scripts { base, sub, sup });
+        Ok(())
+    }
+
+    /// A braced group or a single atom, as taken by commands and scripts
+    fn parse_arg(&mut self) -> Result<Vec<Node>, MathError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                self.parse_list(Stop::Brace)
+            }
+            Some('0'..='9') => {
+                let (_, c) = self.chars[self.pos];
+                self.pos += 1;
+                Ok(vec![Node::Symbol {
+                    text: c.to_string(),
+                    class: Class::Ord,
+                    font: Font::Roman,
+                }])
+            }
+            Some('}') | Some('^') | Some('_') | Some('&') | None => self.error("missing argument"),
+            _ => Ok(vec![self.parse_atom()?]),
+        }
+    }
+
+    /// Raw text of a braced group
+    fn parse_text_arg(&mut self) -> Result<String, MathError> {
+        self.expect('{')?;
+        let start = self.offset();
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    let text = self.source[start..self.offset()].to_string();
+                    self.pos += 1;
+                    return Ok(text);
+                }
+                '}' => depth -= 1,
+                '\\' => self.pos += 1,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        self.error("missing '}'")
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, MathError> {
+        let Some(c) = self.peek() else {
+            return self.error("unexpected end of input");
+        };
+        match c {
+            '{' => {
+                self.pos += 1;
+                Ok(Node::Group(self.parse_list(Stop::Brace)?))
+            }
+            '\\' => self.parse_command(),
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(c) = self.peek() {
+                    let digit_follows = self
+                        .chars
+                        .get(self.pos + 1)
+                        .is_some_and(|&(_, c)| c.is_ascii_digit());
+                    if c.is_ascii_digit() || (c == '.' && digit_follows) {
+                        number.push(c);
+                        self.pos += 1;
+                    } else {
+                        break;
+                    }
+                }
+                if number.is_empty() {
+                    self.pos += 1;
+                    number.push('.');
+                }
+                Ok(Node::Symbol {
+                    text: number,
+                    class: Class::Ord,
+                    font: Font::Roman,
+                })
+            }
+            '~' => {
+                self.pos += 1;
+                Ok(Node::Space(0.25))
+            }
+            c => {
+                self.pos += 1;
+                let text = match c {
+                    '-' => "−".to_string(),
+                    '*' => "∗".to_string(),
+                    c => c.to_string(),
+                };
+                let font = if c.is_alphabetic() {
+                    Font::Italic
+                } else {
+                    Font::Roman
+                };
+                Ok(Node::Symbol {
+                    text,
+                    class: symbols::char_class(c),
+                    font,
+                })
+            }
+        }
+    }
+
+    fn parse_command(&mut self) -> Result<Node, MathError> {
+        let start = self.pos;
+        let name = self.read_command()?;
+        let symbol = |text: &str, class: Class| Node::Symbol {
+            text: text.to_string(),
+            class,
+            font: Font::Roman,
+        };
+        let node = match name.as_str() {
+            "frac" | "dfrac" | "tfrac" | "cfrac" => {
+                let num = self.parse_arg()?;
+                let den = self.parse_arg()?;
+                let style = match name.as_str() {
+                    "dfrac" | "cfrac" => FracStyle::Display,
+                    "tfrac" => FracStyle::Text,
+                    _ => FracStyle::Auto,
+                };
+                Node::Frac {
+                    num,
+                    den,
+                    bar: true,
+                    style,
+                }
+            }
+            "binom" | "dbinom" | "tbinom" => {
+                let num = self.parse_arg()?;
+                let den = self.parse_arg()?;
+                let style = match name.as_str() {
+                    "dbinom" => FracStyle::Display,
+                    "tbinom" => FracStyle::Text,
+                    _ => FracStyle::Auto,
+                };
+                Node::Delimited {
+                    left: "(".to_string(),
+                    right: ")".to_string(),
+                    body: vec![Node::Frac {
+                        num,
+                        den,
+                        bar: false,
+                        style,
+                    }],
+                }
+            }
+            "sqrt" => {
+                self.skip_whitespace();
+                let index = if self.peek() == Some('[') {
+                    self.pos += 1;
+                    Some(self.parse_list(Stop::Bracket)?)
+                } else {
+                    None
+                };
+                let body = self.parse_arg()?;
+                Node::Sqrt { index, body }
+            }
+            "left" => {
+                let left = self.parse_delim()?;
+                let body = self.parse_list(Stop::Right)?;
+                self.read_command()?;
+                let right = self.parse_delim()?;
+                Node::Delimited { left, right, body }
+            }
+            "right" => return self.error("\\right without \\left"),
+            "end" => return self.error("\\end without \\begin"),
+            "begin" => self.parse_environment()?,
+            "big" | "Big" | "bigg" | "Bigg" | "bigl" | "Bigl" | "biggl" | "Biggl" | "bigr"
+            | "Bigr" | "biggr" | "Biggr" | "bigm" | "Bigm" | "biggm" | "Biggm" => {
+                let delim = self.parse_delim()?;
+                let size = match name.trim_end_matches(['l', 'r', 'm']) {
+                    "big" => 1.2,
+                    "Big" => 1.8,
+                    "bigg" => 2.4,
+                    _ => 3.0,
+                };
+                let class = match name.chars().last() {
+                    Some('l') => Class::Open,
+                    Some('r') => Class::Close,
+                    Some('m') => Class::Rel,
+                    _ => symbols::char_class(delim.chars().next().unwrap_or('.')),
+                };
+                Node::SizedDelim { delim, size, class }
+            }
+            "text" | "textrm" | "textbf" | "textit" | "mbox" => {
+                let text = self.parse_text_arg()?;
+                let font = match name.as_str() {
+                    "textbf" => Font::Bold,
+                    "textit" => Font::Italic,
+                    _ => Font::Roman,
+                };
+                Node::Symbol {
+                    text,
+                    class: Class::Ord,
+                    font,
+                }
+            }
+            "operatorname" => {
+                let limits = if self.peek() == Some('*') {
+                    self.pos += 1;
+                    Limits::Display
+                } else {
+                    Limits::Never
+                };
+                let text = self.parse_text_arg()?;
+                Node::Operator {
+                    text,
+                    large: false,
+                    limits,
+                }
+            }
+            "mathrm" | "mathbf" | "mathit" | "boldsymbol" | "bm" | "mathbb" => {
+                let mut body = self.parse_arg()?;
+                let font = match name.as_str() {
+                    "mathrm" => Font::Roman,
+                    "mathbf" => Font::Bold,
+                    "mathit" => Font::Italic,
+                    "mathbb" => Font::Roman,
+                    _ => Font::BoldItalic,
+                };
+                for node in &mut body {
+                    set_font(node, font, name == "mathbb");
+                }
+                Node::Group(body)
+            }
+            "hat" | "widehat" | "bar" | "overline" | "underline" | "vec" | "overrightarrow"
+            | "tilde" | "widetilde" | "dot" | "ddot" => {
+                let accent = match name.as_str() {
+                    "hat" | "widehat" => Accent::Hat,
+                    "bar" => Accent::Bar,
+                    "overline" => Accent::Overline,
+                    "underline" => Accent::Underline,
+                    "vec" | "overrightarrow" => Accent::Vec,
+                    "tilde" | "widetilde" => Accent::Tilde,
+                    "dot" => Accent::Dot,
+                    _ => Accent::Ddot,
+                };
+                let body = self.parse_arg()?;
+                Node::Accent { accent, body }
+            }
+            "displaystyle" | "textstyle" | "scriptstyle" | "scriptscriptstyle" => {
+                Node::Group(Vec::new())
+            }
+            "bmod" | "mod" => symbol("mod", Class::Bin),
+            "pmod" => {
+                let body = self.parse_arg()?;
+                let mut nodes = vec![Node::Space(1.0), symbol("(", Class::Open)];
+                nodes.push(Node::Operator {
+                    text: "mod".to_string(),
+                    large: false,
+                    limits: Limits::Never,
+                });
+                nodes.push(Node::Space(1.0 / 3.0));
+                nodes.extend(body);
+                nodes.push(symbol(")", Class::Close));
+                Node::Group(nodes)
+            }
+            name => {
+                if let Some(em) = symbols::space(name) {
+                    Node::Space(em)
+                } else if let Some((c, italic)) = symbols::greek(name) {
+                    Node::Symbol {
+                        text: c.to_string(),
+                        class: Class::Ord,
+                        font: if italic { Font::Italic } else { Font::Roman },
+                    }
+                } else if let Some((text, class)) = symbols::symbol(name) {
+                    symbol(text, class)
+                } else if let Some((text, limits)) = symbols::large_operator(name) {
+                    Node::Operator {
+                        text: text.to_string(),
+                        large: true,
+                        limits: if limits {
+                            Limits::Display
+                        } else {
+                            Limits::Never
+                        },
+                    }
+                } else if let Some(limits) = symbols::function(name) {
+                    Node::Operator {
+                        text: name.to_string(),
+                        large: false,
+                        limits: if limits {
+                            Limits::Display
+                        } else {
+                            Limits::Never
+                        },
+                    }
+                } else {
+                    self.pos = start;
+                    return self.error(format!("unknown command \\{name}"));
+                }
+            }
+        };
+        Ok(node)
+    }
+
+    /// A delimiter after `\left`, `\right` or `\big`; `.` is empty
+    fn parse_delim(&mut self) -> Result<String, MathError> {
+        self.skip_whitespace();
+        let Some(c) = self.peek() else {
+            return self.error("missing delimiter");
+        };
+        if c == '\\' {
+            let name = self.read_command()?;
+            return match symbols::symbol(&name) {
+                Some((text, Class::Open | Class::Close)) => Ok(text.to_string()),
+                Some((text @ ("|" | "‖"), _)) => Ok(text.to_string()),
+                _ => self.error(format!("\\{name} is not a delimiter")),
+            };
+        }
+        self.pos += 1;
+        match c {
+            '.' => Ok(String::new()),
+            '<' => Ok("⟨".to_string()),
+            '>' => Ok("⟩".to_string()),
+            '(' | ')' | '[' | ']' | '|' | '/' => Ok(c.to_string()),
+            _ => {
+                self.pos -= 1;
+                self.error(format!("'{c}' is not a delimiter"))
+            }
+        }
+    }
+
+    fn parse_environment(&mut self) -> Result<Node, MathError> {
+        let name = self.parse_text_arg()?;
+        let (left, right, columns) = match name.as_str() {
+            "matrix" | "smallmatrix" | "array" => ("", "", Columns::Center),
+            "pmatrix" => ("(", ")", Columns::Center),
+            "bmatrix" => ("[", "]", Columns::Center),
+            "Bmatrix" => ("{", "}", Columns::Center),
+            "vmatrix" => ("|", "|", Columns::Center),
+            "Vmatrix" => ("‖", "‖", Columns::Center),
+            "cases" => ("{", "", Columns::Left),
+            "aligned" | "align" | "align*" | "split" => ("", "", Columns::RightLeft),
+            "gathered" | "gather" | "gather*" => ("", "", Columns::Center),
+            _ => return self.error(format!("unknown environment {name}")),
+        };
+        if name == "array" {
+            // Column specification; cells are centered regardless
+            self.parse_text_arg()?;
+        }
+
+        let mut rows: Vec<Vec<Vec<Node>>> = Vec::new();
+        let mut row: Vec<Vec<Node>> = Vec::new();
+        loop {
+            let cell = self.parse_list(Stop::Cell)?;
+            row.push(cell);
+            if self.peek() == Some('&') {
+                self.pos += 1;
+                continue;
+            }
+            if self.read_command()? == "end" {
+                let end = self.parse_text_arg()?;
+                if end != name {
+                    return self.error(format!("\\begin{{{name}}} ended by \\end{{{end}}}"));
+                }
+                // A trailing \\ leaves an empty row
+                if !(row.len() == 1 && row[0].is_empty()) {
+                    rows.push(row);
+                }
+                break;
+            }
+            rows.push(std::mem::take(&mut row));
+        }
+
+        Ok(Node::Matrix {
+            rows,
+            columns,
+            left: left.to_string(),
+            right: right.to_string(),
+        })
+    }
+}
+
+/// Limits of the operator a `\limits` applies to
+fn operator_mut(node: Option<&mut Node>) -> Option<&mut Limits> {
+    match node? {
+        Node::Operator { limits, .. } => Some(limits),
+        Node::Scripts { base, .. } => operator_mut(Some(base)),
+        _ => None,
+    }
+}
+
+fn is_primes(nodes: &[Node]) -> bool {
+    nodes
+        .iter()
+        .all(|node| matches!(node, Node::Symbol { text, .. } if text.chars().all(|c| c == '′')))
+}
+
+/// Set the font of every symbol in a node, for `\mathrm` and friends
+fn set_font(node: &mut Node, font: Font, double_struck: bool) {
+    match node {
+        Node::Symbol { text, font: f, .. } => {
+            *f = font;
+            if double_struck {
+                *text = text.chars().map(symbols::double_struck).collect();
+            }
+        }
+        Node::Group(nodes) | Node::Accent { body: nodes, .. } => {
+            for node in nodes {
+                set_font(node, font, double_struck);
+            }
+        }
+        Node::Scripts { base, .. } => set_font(base, font, double_struck),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sym(text: &str, class: Class, font: Font) -> Node {
+        Node::Symbol {
+            text: text.to_string(),
+            class,
+            font,
+        }
+    }
+
+    #[test]
+    fn test_parse_scripts_and_symbols() {
+        let nodes = parse(r"x_1^2 + \alpha \leq 3.14").unwrap();
+        assert_eq!(
+            nodes,
+            vec![
+                Node::Scripts {
+                    base: Box::new(sym("x", Class::Ord, Font::Italic)),
+                    sub: Some(vec![sym("1", Class::Ord, Font::Roman)]),
+                    sup: Some(vec![sym("2", Class::Ord, Font::Roman)]),
+                },
+                sym("+", Class::Bin, Font::Roman),
+                sym("α", Class::Ord, Font::Italic),
+                sym("≤", Class::Rel, Font::Roman),
+                sym("3.14", Class::Ord, Font::Roman),
+            ]
+        );
+
+        let nodes = parse(r"f'(x) \sum\limits_{i=0}^n \mathbb{R}").unwrap();
+        assert!(matches!(&nodes[0], Node::Scripts { sup: Some(p), .. } if is_primes(p)));
+        assert!(matches!(
+            &nodes[4],
+            Node::Scripts { base, .. }
+                if matches!(**base, Node::Operator { limits: Limits::Always, .. })
+        ));
+        assert_eq!(
+            nodes[5],
+            Node::Group(vec![sym("ℝ", Class::Ord, Font::Roman)])
+        );
+    }
+
+    #[test]
+    fn test_parse_structures() {
+        let nodes = parse(r"\frac12 \sqrt[3]{x} \left( a \right.").unwrap();
+        assert!(matches!(&nodes[0], Node::Frac { num, den, bar: true, .. }
+            if num.len() == 1 && den.len() == 1));
+        assert!(matches!(&nodes[1], Node::Sqrt { index: Some(_), .. }));
+        assert!(matches!(&nodes[2], Node::Delimited { left, right, .. }
+            if left == "(" && right.is_empty()));
+
+        let nodes = parse(r"\begin{pmatrix} a & b \\ c & d \\ \end{pmatrix}").unwrap();
+        let Node::Matrix { rows, left, .. } = &nodes[0] else {
+            panic!("expected a matrix: {nodes:?}");
+        };
+        assert_eq!(left, "(");
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.len() == 2));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse(r"\frac{a}{b").unwrap_err().message, "missing '}'");
+        let err = parse(r"x + \foo").unwrap_err();
+        assert_eq!(err.message, "unknown command \\foo");
+        assert_eq!(err.offset, 4);
+        assert!(parse("x^1^2").is_err());
+        assert!(parse(r"\begin{matrix} a \end{pmatrix}").is_err());
+        assert!(parse(r"\left( x").is_err());
+        assert!(parse("a & b").is_err());
+    }
+}
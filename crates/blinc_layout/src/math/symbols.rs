@@ -0,0 +1,333 @@
+//! Symbol tables for TeX control words
+
+use super::parse::Class;
+
+/// Greek letters; lowercase ones are set in italic
+const GREEK: &[(&str, char)] = &[
+    ("alpha", 'α'),
+    ("beta", 'β'),
+    ("gamma", 'γ'),
+    ("delta", 'δ'),
+    ("epsilon", 'ϵ'),
+    ("varepsilon", 'ε'),
+    ("zeta", 'ζ'),
+    ("eta", 'η'),
+    ("theta", 'θ'),
+    ("vartheta", 'ϑ'),
+    ("iota", 'ι'),
+    ("kappa", 'κ'),
+    ("lambda", 'λ'),
+    ("mu", 'μ'),
+    ("nu", 'ν'),
+    ("xi", 'ξ'),
+    ("omicron", 'ο'),
+    ("pi", 'π'),
+    ("varpi", 'ϖ'),
+    ("rho", 'ρ'),
+    ("varrho", 'ϱ'),
+    ("sigma", 'σ'),
+    ("varsigma", 'ς'),
+    ("tau", 'τ'),
+    ("upsilon", 'υ'),
+    ("phi", 'ϕ'),
+    ("varphi", 'φ'),
+    ("chi", 'χ'),
+    ("psi", 'ψ'),
+    ("omega", 'ω'),
+    ("Gamma", 'Γ'),
+    ("Delta", 'Δ'),
+    ("Theta", 'Θ'),
+    ("Lambda", 'Λ'),
+    ("Xi", 'Ξ'),
+    ("Pi", 'Π'),
+    ("Sigma", 'Σ'),
+    ("Upsilon", 'Υ'),
+    ("Phi", 'Φ'),
+    ("Psi", 'Ψ'),
+    ("Omega", 'Ω'),
+];
+
+/// Upright symbols with their spacing class
+const SYMBOLS: &[(&str, &str, Class)] = &[
+    // Relations
+    ("leq", "≤", Class::Rel),
+    ("le", "≤", Class::Rel),
+    ("geq", "≥", Class::Rel),
+    ("ge", "≥", Class::Rel),
+    ("neq", "≠", Class::Rel),
+    ("ne", "≠", Class::Rel),
+    ("approx", "≈", Class::Rel),
+    ("equiv", "≡", Class::Rel),
+    ("sim", "∼", Class::Rel),
+    ("simeq", "≃", Class::Rel),
+    ("cong", "≅", Class::Rel),
+    ("propto", "∝", Class::Rel),
+    ("ll", "≪", Class::Rel),
+    ("gg", "≫", Class::Rel),
+    ("in", "∈", Class::Rel),
+    ("notin", "∉", Class::Rel),
+    ("ni", "∋", Class::Rel),
+    ("subset", "⊂", Class::Rel),
+    ("subseteq", "⊆", Class::Rel),
+    ("supset", "⊃", Class::Rel),
+    ("supseteq", "⊇", Class::Rel),
+    ("perp", "⊥", Class::Rel),
+    ("parallel", "∥", Class::Rel),
+    ("mid", "∣", Class::Rel),
+    ("to", "→", Class::Rel),
+    ("rightarrow", "→", Class::Rel),
+    ("leftarrow", "←", Class::Rel),
+    ("gets", "←", Class::Rel),
+    ("leftrightarrow", "↔", Class::Rel),
+    ("Rightarrow", "⇒", Class::Rel),
+    ("Leftarrow", "⇐", Class::Rel),
+    ("Leftrightarrow", "⇔", Class::Rel),
+    ("implies", "⟹", Class::Rel),
+    ("impliedby", "⟸", Class::Rel),
+    ("iff", "⟺", Class::Rel),
+    ("mapsto", "↦", Class::Rel),
+    ("longrightarrow", "⟶", Class::Rel),
+    ("longleftarrow", "⟵", Class::Rel),
+    ("uparrow", "↑", Class::Rel),
+    ("downarrow", "↓", Class::Rel),
+    // Binary operators
+    ("pm", "±", Class::Bin),
+    ("mp", "∓", Class::Bin),
+    ("times", "×", Class::Bin),
+    ("div", "÷", Class::Bin),
+    ("cdot", "⋅", Class::Bin),
+    ("ast", "∗", Class::Bin),
+    ("star", "⋆", Class::Bin),
+    ("circ", "∘", Class::Bin),
+    ("bullet", "∙", Class::Bin),
+    ("cup", "∪", Class::Bin),
+    ("cap", "∩", Class::Bin),
+    ("wedge", "∧", Class::Bin),
+    ("land", "∧", Class::Bin),
+    ("vee", "∨", Class::Bin),
+    ("lor", "∨", Class::Bin),
+    ("oplus", "⊕", Class::Bin),
+    ("ominus", "⊖", Class::Bin),
+    ("otimes", "⊗", Class::Bin),
+    ("setminus", "∖", Class::Bin),
+    // Ordinary symbols
+    ("infty", "∞", Class::Ord),
+    ("partial", "∂", Class::Ord),
+    ("nabla", "∇", Class::Ord),
+    ("forall", "∀", Class::Ord),
+    ("exists", "∃", Class::Ord),
+    ("nexists", "∄", Class::Ord),
+    ("emptyset", "∅", Class::Ord),
+    ("varnothing", "∅", Class::Ord),
+    ("ell", "ℓ", Class::Ord),
+    ("hbar", "ℏ", Class::Ord),
+    ("Re", "ℜ", Class::Ord),
+    ("Im", "ℑ", Class::Ord),
+    ("aleph", "ℵ", Class::Ord),
+    ("angle", "∠", Class::Ord),
+    ("triangle", "△", Class::Ord),
+    ("neg", "¬", Class::Ord),
+    ("lnot", "¬", Class::Ord),
+    ("prime", "′", Class::Ord),
+    ("top", "⊤", Class::Ord),
+    ("bot", "⊥", Class::Ord),
+    ("degree", "°", Class::Ord),
+    ("dots", "…", Class::Inner),
+    ("ldots", "…", Class::Inner),
+    ("cdots", "⋯", Class::Inner),
+    ("vdots", "⋮", Class::Ord),
+    ("ddots", "⋱", Class::Inner),
+    ("colon", ":", Class::Punct),
+    // Delimiters
+    ("langle", "⟨", Class::Open),
+    ("rangle", "⟩", Class::Close),
+    ("lfloor", "⌊", Class::Open),
+    ("rfloor", "⌋", Class::Close),
+    ("lceil", "⌈", Class::Open),
+    ("rceil", "⌉", Class::Close),
+    ("lvert", "|", Class::Open),
+    ("rvert", "|", Class::Close),
+    ("lVert", "‖", Class::Open),
+    ("rVert", "‖", Class::Close),
+    ("vert", "|", Class::Ord),
+    ("Vert", "‖", Class::Ord),
+    ("{", "{", Class::Open),
+    ("}", "}", Class::Close),
+    ("|", "‖", Class::Ord),
+    // Escaped characters
+    ("%", "%", Class::Ord),
+    ("$", "$", Class::Ord),
+    ("&", "&", Class::Ord),
+    ("#", "#", Class::Ord),
+    ("_", "_", Class::Ord),
+];
+
+/// Large operators; the flag is whether limits go above and below in
+/// display style
+const LARGE_OPERATORS: &[(&str, &str, bool)] = &[
+    ("sum", "∑", true),
+    ("prod", "∏", true),
+    ("coprod", "∐", true),
+    ("bigcup", "⋃", true),
+    ("bigcap", "⋂", true),
+    ("bigoplus", "⨁", true),
+    ("bigotimes", "⨂", true),
+    ("bigvee", "⋁", true),
+    ("bigwedge", "⋀", true),
+    ("int", "∫", false),
+    ("iint", "∬", false),
+    ("iiint", "∭", false),
+    ("oint", "∮", false),
+];
+
+/// Named functions set upright; the flag is whether limits go below in
+/// display style
+const FUNCTIONS: &[(&str, bool)] = &[
+    ("sin", false),
+    ("cos", false),
+    ("tan", false),
+    ("cot", false),
+    ("sec", false),
+    ("csc", false),
+    ("arcsin", false),
+    ("arccos", false),
+    ("arctan", false),
+    ("sinh", false),
+    ("cosh", false),
+    ("tanh", false),
+    ("coth", false),
+    ("log", false),
+    ("ln", false),
+    ("lg", false),
+    ("exp", false),
+    ("arg", false),
+    ("deg", false),
+    ("dim", false),
+    ("hom", false),
+    ("ker", false),
+    ("det", true),
+    ("gcd", true),
+    ("Pr", true),
+    ("lim", true),
+    ("liminf", true),
+    ("limsup", true),
+    ("max", true),
+    ("min", true),
+    ("sup", true),
+    ("inf", true),
+];
+
+/// Horizontal spaces in em
+const SPACES: &[(&str, f32)] = &[
+    (",", 3.0 / 18.0),
+    ("thinspace", 3.0 / 18.0),
+    (":", 4.0 / 18.0),
+    (">", 4.0 / 18.0),
+    ("medspace", 4.0 / 18.0),
+    (";", 5.0 / 18.0),
+    ("thickspace", 5.0 / 18.0),
+    ("!", -3.0 / 18.0),
+    ("negthinspace", -3.0 / 18.0),
+    (" ", 0.25),
+    ("quad", 1.0),
+    ("qquad", 2.0),
+];
+
+/// Greek letter for a control word, and whether it is set in italic
+pub(super) fn greek(name: &str) -> Option<(char, bool)> {
+    GREEK
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, c)| (c, c.is_lowercase()))
+}
+
+/// Symbol and spacing class for a control word
+pub(super) fn symbol(name: &str) -> Option<(&'static str, Class)> {
+    SYMBOLS
+        .iter()
+        .find(|(n, _, _)| *n == name)
+        .map(|&(_, s, class)| (s, class))
+}
+
+/// Large operator for a control word
+pub(super) fn large_operator(name: &str) -> Option<(&'static str, bool)> {
+    LARGE_OPERATORS
+        .iter()
+        .find(|(n, _, _)| *n == name)
+        .map(|&(_, s, limits)| (s, limits))
+}
+
+/// Whether a named function takes limits in display style
+pub(super) fn function(name: &str) -> Option<bool> {
+    FUNCTIONS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, limits)| limits)
+}
+
+/// Width of a spacing command in em
+pub(super) fn space(name: &str) -> Option<f32> {
+    SPACES.iter().find(|(n, _)| *n == name).map(|&(_, em)| em)
+}
+
+/// Double-struck form of a letter or digit, for `\mathbb`
+pub(super) fn double_struck(c: char) -> char {
+    let special = match c {
+        'C' => Some('ℂ'),
+        'H' => Some('ℍ'),
+        'N' => Some('ℕ'),
+        'P' => Some('ℙ'),
+        'Q' => Some('ℚ'),
+        'R' => Some('ℝ'),
+        'Z' => Some('ℤ'),
+        _ => None,
+    };
+    let offset = match c {
+        'A'..='Z' => Some(0x1D538 + (c as u32 - 'A' as u32)),
+        'a'..='z' => Some(0x1D552 + (c as u32 - 'a' as u32)),
+        '0'..='9' => Some(0x1D7D8 + (c as u32 - '0' as u32)),
+        _ => None,
+    };
+    special
+        .or_else(|| offset.and_then(char::from_u32))
+        .unwrap_or(c)
+}
+
+/// Spacing class of a single source character
+pub(super) fn char_class(c: char) -> Class {
+    match c {
+        '+' | '-' | '*' => Class::Bin,
+        '=' | '<' | '>' | ':' => Class::Rel,
+        ',' | ';' => Class::Punct,
+        '(' | '[' => Class::Open,
+        ')' | ']' | '!' | '?' => Class::Close,
+        _ => Class::Ord,
+    }
+}
+
+/// Approximate ink extents of text above and below the baseline, in em
+pub(super) fn extents(text: &str) -> (f32, f32) {
+    let mut height: f32 = 0.0;
+    let mut depth: f32 = 0.0;
+    for c in text.chars() {
+        let (h, d) = match c {
+            'a' | 'c' | 'e' | 'm' | 'n' | 'o' | 'r' | 's' | 'u' | 'v' | 'w' | 'x' | 'z' | 'ı'
+            | 'α' | 'ε' | 'ϵ' | 'ι' | 'κ' | 'ν' | 'ο' | 'π' | 'σ' | 'τ' | 'υ' | 'ω' | 'ϖ' => {
+                (0.45, 0.0)
+            }
+            'g' | 'p' | 'q' | 'y' | 'γ' | 'η' | 'μ' | 'ρ' | 'ϱ' | 'χ' | 'ς' => (0.45, 0.2),
+            'j' | 'f' | 'β' | 'ζ' | 'ξ' | 'φ' | 'ϕ' | 'ψ' => (0.7, 0.2),
+            '(' | ')' | '[' | ']' | '{' | '}' | '|' | '‖' | '⟨' | '⟩' | '⌊' | '⌋' | '⌈' | '⌉'
+            | '/' => (0.75, 0.25),
+            '+' | '−' | '=' | '×' | '÷' | '±' | '∓' | '⋅' | '∘' | '∙' | '∗' | '<' | '>' | '≤'
+            | '≥' | '≠' | '≈' | '≡' | '∼' | '→' | '←' | '⇒' | '⇔' => (0.6, 0.1),
+            ',' | ';' => (0.1, 0.2),
+            '.' | '…' | '_' => (0.1, 0.0),
+            '⋯' => (0.3, 0.0),
+            _ => (0.7, 0.0),
+        };
+        height = height.max(h);
+        depth = depth.max(d);
+    }
+    (height, depth)
+}
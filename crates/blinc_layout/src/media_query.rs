@@ -0,0 +1,762 @@
+//! Media queries and container queries
+//!
+//! Evaluates the conditions of `@media` and `@container` blocks parsed by
+//! [`css_parser`](crate::css_parser). Media queries are matched against a
+//! [`MediaContext`] describing the viewport and user preferences; container
+//! queries are matched against the computed size of the nearest ancestor
+//! declared as a container (`container-type` / `container-name`).
+//!
+//! # Supported Syntax
+//!
+//! - Media types: `all`, `screen`, `print` with `not` / `only` prefixes
+//! - Size features: `width`, `height`, `aspect-ratio` with `min-` / `max-`
+//!   prefixes or range syntax (`(width >= 600px)`)
+//! - `orientation: portrait | landscape`
+//! - `prefers-color-scheme: light | dark`
+//! - `prefers-reduced-motion: reduce | no-preference`
+//! - `resolution` with `dppx`, `x`, `dpi` and `dpcm` units
+//! - Comma-separated query lists (any query matching matches the list)
+//!
+//! # Example
+//!
+//! ```ignore
+//! use blinc_layout::media_query::{MediaContext, MediaQueryList};
+//!
+//! let query = MediaQueryList::parse("screen and (max-width: 600px)").unwrap();
+//! assert!(query.matches(&MediaContext::new(400.0, 800.0)));
+//! ```
+
+use blinc_theme::ColorScheme;
+
+/// Font size used to resolve `em` / `rem` lengths inside media conditions
+///
+/// Media queries are evaluated before any element style is known, so relative
+/// lengths resolve against the initial font size like in browsers.
+const MEDIA_BASE_FONT_SIZE: f32 = 16.0;
+
+// ============================================================================
+// Evaluation Context
+// ============================================================================
+
+/// Environment a media query is evaluated against
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MediaContext {
+    /// Viewport width in logical pixels
+    pub width: f32,
+    /// Viewport height in logical pixels
+    pub height: f32,
+    /// Device pixel ratio (physical pixels per logical pixel)
+    pub scale_factor: f32,
+    /// Active color scheme (`prefers-color-scheme`)
+    pub color_scheme: ColorScheme,
+    /// Whether the user asked for reduced motion (`prefers-reduced-motion`)
+    pub reduced_motion: bool,
+}
+
+impl Default for MediaContext {
+    fn default() -> Self {
+        Self::new(0.0, 0.0)
+    }
+}
+
+impl MediaContext {
+    /// Create a context for a viewport size with default preferences
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            scale_factor: 1.0,
+            color_scheme: ColorScheme::Light,
+            reduced_motion: false,
+        }
+    }
+
+    /// Build a context from the global application state
+    ///
+    /// Reads the viewport size and reduced-motion preference from
+    /// `BlincContextState` and the color scheme from `ThemeState`. Missing
+    /// globals fall back to the defaults of [`MediaContext::new`].
+    pub fn from_globals(scale_factor: f32) -> Self {
+        let mut ctx = Self::new(0.0, 0.0);
+        ctx.scale_factor = scale_factor;
+        if let Some(state) = blinc_core::BlincContextState::try_get() {
+            let (width, height) = state.viewport_size();
+            ctx.width = width;
+            ctx.height = height;
+            ctx.reduced_motion = state.prefers_reduced_motion();
+        }
+        if let Some(theme) = blinc_theme::ThemeState::try_get() {
+            ctx.color_scheme = theme.scheme();
+        }
+        ctx
+    }
+
+    /// Set the device pixel ratio
+    pub fn with_scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Set the color scheme
+    pub fn with_color_scheme(mut self, scheme: ColorScheme) -> Self {
+        self.color_scheme = scheme;
+        self
+    }
+
+    /// Set the reduced-motion preference
+    pub fn with_reduced_motion(mut self, reduced: bool) -> Self {
+        self.reduced_motion = reduced;
+        self
+    }
+}
+
+// ============================================================================
+// Query Types
+// ============================================================================
+
+/// Comparison used by a size or resolution feature
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeOp {
+    /// `feature: value` or `feature = value`
+    Eq,
+    /// `min-feature: value` or `feature >= value`
+    Ge,
+    /// `max-feature: value` or `feature <= value`
+    Le,
+    /// `feature > value`
+    Gt,
+    /// `feature < value`
+    Lt,
+}
+
+impl RangeOp {
+    fn compare(self, actual: f32, expected: f32) -> bool {
+        match self {
+            RangeOp::Eq => (actual - expected).abs() < 0.01,
+            RangeOp::Ge => actual >= expected,
+            RangeOp::Le => actual <= expected,
+            RangeOp::Gt => actual > expected,
+            RangeOp::Lt => actual < expected,
+        }
+    }
+
+    /// Mirror the operator for `value op feature` range syntax
+    fn flip(self) -> Self {
+        match self {
+            RangeOp::Eq => RangeOp::Eq,
+            RangeOp::Ge => RangeOp::Le,
+            RangeOp::Le => RangeOp::Ge,
+            RangeOp::Gt => RangeOp::Lt,
+            RangeOp::Lt => RangeOp::Gt,
+        }
+    }
+}
+
+/// Screen orientation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    /// Height is greater than or equal to width
+    Portrait,
+    /// Width is greater than height
+    Landscape,
+}
+
+/// Media type of a query
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MediaType {
+    /// Matches every device
+    All,
+    /// Matches on-screen rendering (always true in Blinc)
+    Screen,
+    /// Matches printed output (never true in Blinc)
+    Print,
+}
+
+/// A single media feature test, e.g. `(min-width: 600px)`
+#[derive(Clone, Debug, PartialEq)]
+pub enum MediaFeature {
+    /// Viewport (or container) width in pixels
+    Width(RangeOp, f32),
+    /// Viewport (or container) height in pixels
+    Height(RangeOp, f32),
+    /// Width divided by height
+    AspectRatio(RangeOp, f32),
+    /// Portrait or landscape
+    Orientation(Orientation),
+    /// Light or dark color scheme
+    PrefersColorScheme(ColorScheme),
+    /// `true` for `reduce`, `false` for `no-preference`
+    PrefersReducedMotion(bool),
+    /// Device pixel ratio in dppx
+    Resolution(RangeOp, f32),
+}
+
+impl MediaFeature {
+    /// Evaluate the feature against a context
+    pub fn matches(&self, ctx: &MediaContext) -> bool {
+        match self {
+            MediaFeature::Width(op, v) => op.compare(ctx.width, *v),
+            MediaFeature::Height(op, v) => op.compare(ctx.height, *v),
+            MediaFeature::AspectRatio(op, v) => {
+                ctx.height > 0.0 && op.compare(ctx.width / ctx.height, *v)
+            }
+            MediaFeature::Orientation(o) => {
+                let actual = if ctx.width > ctx.height {
+                    Orientation::Landscape
+                } else {
+                    Orientation::Portrait
+                };
+                actual == *o
+            }
+            MediaFeature::PrefersColorScheme(scheme) => ctx.color_scheme == *scheme,
+            MediaFeature::PrefersReducedMotion(reduce) => ctx.reduced_motion == *reduce,
+            MediaFeature::Resolution(op, v) => op.compare(ctx.scale_factor, *v),
+        }
+    }
+
+    /// Whether the feature reads the width of the viewport or container
+    pub fn uses_width(&self) -> bool {
+        matches!(
+            self,
+            MediaFeature::Width(..) | MediaFeature::AspectRatio(..) | MediaFeature::Orientation(_)
+        )
+    }
+
+    /// Whether the feature reads the height of the viewport or container
+    pub fn uses_height(&self) -> bool {
+        matches!(
+            self,
+            MediaFeature::Height(..) | MediaFeature::AspectRatio(..) | MediaFeature::Orientation(_)
+        )
+    }
+
+    /// Whether the feature is a size feature (valid inside `@container`)
+    pub fn is_size_feature(&self) -> bool {
+        self.uses_width() || self.uses_height()
+    }
+}
+
+/// A single media query: `[not|only] type and (feature) and (feature)`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaQuery {
+    /// Whether the whole query is negated with `not`
+    pub negated: bool,
+    /// Media type (`None` means `all`)
+    pub media_type: Option<MediaType>,
+    /// Features that must all match
+    pub features: Vec<MediaFeature>,
+}
+
+impl MediaQuery {
+    /// Evaluate the query against a context
+    pub fn matches(&self, ctx: &MediaContext) -> bool {
+        let type_matches = !matches!(self.media_type, Some(MediaType::Print));
+        let result = type_matches && self.features.iter().all(|f| f.matches(ctx));
+        result != self.negated
+    }
+}
+
+/// A comma-separated list of media queries, as written after `@media`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaQueryList {
+    /// Queries of the list; the list matches if any query matches
+    pub queries: Vec<MediaQuery>,
+}
+
+impl MediaQueryList {
+    /// Parse the prelude of an `@media` rule
+    ///
+    /// Returns `None` if any query in the list is malformed.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Some(Self {
+                queries: vec![MediaQuery {
+                    negated: false,
+                    media_type: Some(MediaType::All),
+                    features: Vec::new(),
+                }],
+            });
+        }
+        let queries = split_top_level(input, ',')
+            .into_iter()
+            .map(|q| parse_media_query(q.trim()))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self { queries })
+    }
+
+    /// Evaluate the list against a context
+    pub fn matches(&self, ctx: &MediaContext) -> bool {
+        self.queries.iter().any(|q| q.matches(ctx))
+    }
+}
+
+impl std::str::FromStr for MediaQueryList {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or(())
+    }
+}
+
+// ============================================================================
+// Container Queries
+// ============================================================================
+
+/// How an element exposes its size to `@container` queries
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContainerType {
+    /// Not a size container (can still be targeted by name)
+    #[default]
+    Normal,
+    /// Width and height can be queried
+    Size,
+    /// Only the inline (horizontal) size can be queried
+    InlineSize,
+}
+
+impl ContainerType {
+    /// Parse a `container-type` value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "normal" => Some(ContainerType::Normal),
+            "size" => Some(ContainerType::Size),
+            "inline-size" => Some(ContainerType::InlineSize),
+            _ => None,
+        }
+    }
+
+    /// Whether a query reading the given axes can be answered by this container
+    ///
+    /// `normal` containers expose no size, `inline-size` containers only
+    /// their width, and `size` containers both axes.
+    pub fn supports(self, width: bool, height: bool) -> bool {
+        match self {
+            ContainerType::Normal => !width && !height,
+            ContainerType::Size => true,
+            ContainerType::InlineSize => !height,
+        }
+    }
+}
+
+/// The condition of an `@container` rule: `[name] (feature) and (feature)`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContainerQuery {
+    /// Container name to match, or `None` for the nearest size container
+    pub name: Option<String>,
+    /// Whether the condition is negated with `not`
+    pub negated: bool,
+    /// Size features that must all match
+    pub features: Vec<MediaFeature>,
+}
+
+impl ContainerQuery {
+    /// Parse the prelude of an `@container` rule
+    ///
+    /// Only size features (`width`, `height`, `aspect-ratio`, `orientation`)
+    /// are accepted.
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut rest = input.trim();
+        let mut name = None;
+        if !rest.starts_with('(') && !rest.starts_with("not ") {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == '(')
+                .unwrap_or(rest.len());
+            let ident = &rest[..end];
+            if ident.is_empty() || !is_ident(ident) {
+                return None;
+            }
+            name = Some(ident.to_string());
+            rest = rest[end..].trim_start();
+        }
+
+        let mut negated = false;
+        if let Some(after) = rest.strip_prefix("not ") {
+            negated = true;
+            rest = after.trim_start();
+        }
+
+        let features = parse_feature_conjunction(rest)?;
+        if features.is_empty() || !features.iter().all(MediaFeature::is_size_feature) {
+            return None;
+        }
+
+        Some(Self {
+            name,
+            negated,
+            features,
+        })
+    }
+
+    /// Whether the query reads the container width
+    pub fn uses_width(&self) -> bool {
+        self.features.iter().any(MediaFeature::uses_width)
+    }
+
+    /// Whether the query reads the container height
+    pub fn uses_height(&self) -> bool {
+        self.features.iter().any(MediaFeature::uses_height)
+    }
+
+    /// Whether a container with this name and type can answer the query
+    pub fn accepts_container(&self, name: Option<&str>, container_type: ContainerType) -> bool {
+        if let Some(wanted) = &self.name {
+            if name != Some(wanted.as_str()) {
+                return false;
+            }
+        }
+        container_type.supports(self.uses_width(), self.uses_height())
+    }
+
+    /// Evaluate the query against a container's computed size
+    pub fn matches_size(&self, width: f32, height: f32) -> bool {
+        let ctx = MediaContext::new(width, height);
+        self.features.iter().all(|f| f.matches(&ctx)) != self.negated
+    }
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+fn is_ident(s: &str) -> bool {
+    s.chars()
+        .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Split on a separator that is not nested inside parentheses
+fn split_top_level(input: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in input.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c == sep && depth == 0 => {
+                parts.push(&input[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&input[start..]);
+    parts
+}
+
+fn parse_media_query(input: &str) -> Option<MediaQuery> {
+    let mut rest = input;
+    let mut negated = false;
+    if let Some(after) = rest.strip_prefix("not ") {
+        negated = true;
+        rest = after.trim_start();
+    } else if let Some(after) = rest.strip_prefix("only ") {
+        rest = after.trim_start();
+    }
+
+    let mut media_type = None;
+    if !rest.starts_with('(') {
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        media_type = Some(match rest[..end].to_ascii_lowercase().as_str() {
+            "all" => MediaType::All,
+            "screen" => MediaType::Screen,
+            "print" => MediaType::Print,
+            _ => return None,
+        });
+        rest = rest[end..].trim_start();
+        if rest.is_empty() {
+            return Some(MediaQuery {
+                negated,
+                media_type,
+                features: Vec::new(),
+            });
+        }
+        rest = rest.strip_prefix("and")?.trim_start();
+    } else if negated {
+        // `not (feature)` without a media type negates the condition
+        media_type = Some(MediaType::All);
+    }
+
+    let features = parse_feature_conjunction(rest)?;
+    Some(MediaQuery {
+        negated,
+        media_type,
+        features,
+    })
+}
+
+/// Parse `(feature) and (feature) ...`
+fn parse_feature_conjunction(input: &str) -> Option<Vec<MediaFeature>> {
+    let mut features = Vec::new();
+    let mut rest = input.trim();
+    while !rest.is_empty() {
+        let inner_start = rest.strip_prefix('(')?;
+        let close = inner_start.find(')')?;
+        parse_feature(&inner_start[..close], &mut features)?;
+        rest = inner_start[close + 1..].trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        rest = rest.strip_prefix("and")?.trim_start();
+        if rest.is_empty() {
+            return None;
+        }
+    }
+    Some(features)
+}
+
+/// Parse the inside of a parenthesized feature, pushing one or two features
+/// (a double-sided range like `400px <= width <= 800px` yields two)
+fn parse_feature(input: &str, out: &mut Vec<MediaFeature>) -> Option<()> {
+    let input = input.trim();
+
+    // Plain `name: value` syntax
+    if let Some((name, value)) = input.split_once(':') {
+        let name = name.trim().to_ascii_lowercase();
+        let value = value.trim();
+        let (op, base) = if let Some(base) = name.strip_prefix("min-") {
+            (RangeOp::Ge, base)
+        } else if let Some(base) = name.strip_prefix("max-") {
+            (RangeOp::Le, base)
+        } else {
+            (RangeOp::Eq, name.as_str())
+        };
+        out.push(build_feature(base, op, value)?);
+        return Some(());
+    }
+
+    // Boolean context: `(orientation)` etc. are not meaningful for our
+    // discrete features, but `(prefers-reduced-motion)` is common shorthand
+    if is_ident(input) {
+        return match input {
+            "prefers-reduced-motion" => {
+                out.push(MediaFeature::PrefersReducedMotion(true));
+                Some(())
+            }
+            _ => None,
+        };
+    }
+
+    // Range syntax: `width >= 600px`, `600px < width`, `400px <= width <= 800px`
+    let tokens = tokenize_range(input)?;
+    match tokens.as_slice() {
+        [RangeToken::Operand(a), RangeToken::Op(op), RangeToken::Operand(b)] => {
+            if is_ident(a) && !a.starts_with(|c: char| c.is_ascii_digit()) {
+                out.push(build_feature(a, *op, b)?);
+            } else {
+                out.push(build_feature(b, op.flip(), a)?);
+            }
+            Some(())
+        }
+        [RangeToken::Operand(lo), RangeToken::Op(op1), RangeToken::Operand(name), RangeToken::Op(op2), RangeToken::Operand(hi)] =>
+        {
+            out.push(build_feature(name, op1.flip(), lo)?);
+            out.push(build_feature(name, *op2, hi)?);
+            Some(())
+        }
+        _ => None,
+    }
+}
+
+enum RangeToken<'a> {
+    Operand(&'a str),
+    Op(RangeOp),
+}
+
+fn tokenize_range(input: &str) -> Option<Vec<RangeToken<'_>>> {
+    let mut tokens = Vec::new();
+    let bytes = input.as_bytes();
+    let mut i = 0;
+    let mut operand_start: Option<usize> = None;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let op = match (c, bytes.get(i + 1)) {
+            (b'>', Some(b'=')) => Some((RangeOp::Ge, 2)),
+            (b'<', Some(b'=')) => Some((RangeOp::Le, 2)),
+            (b'>', _) => Some((RangeOp::Gt, 1)),
+            (b'<', _) => Some((RangeOp::Lt, 1)),
+            (b'=', _) => Some((RangeOp::Eq, 1)),
+            _ => None,
+        };
+        if let Some((op, len)) = op {
+            if let Some(start) = operand_start.take() {
+                tokens.push(RangeToken::Operand(input[start..i].trim()));
+            }
+            tokens.push(RangeToken::Op(op));
+            i += len;
+            continue;
+        }
+        if operand_start.is_none() && !c.is_ascii_whitespace() {
+            operand_start = Some(i);
+        }
+        i += 1;
+    }
+    if let Some(start) = operand_start {
+        tokens.push(RangeToken::Operand(input[start..].trim()));
+    }
+    Some(tokens)
+}
+
+fn build_feature(name: &str, op: RangeOp, value: &str) -> Option<MediaFeature> {
+    let value = value.trim();
+    match name {
+        "width" => Some(MediaFeature::Width(op, parse_media_length(value)?)),
+        "height" => Some(MediaFeature::Height(op, parse_media_length(value)?)),
+        "aspect-ratio" => Some(MediaFeature::AspectRatio(op, parse_ratio(value)?)),
+        "resolution" => Some(MediaFeature::Resolution(op, parse_resolution(value)?)),
+        "orientation" if op == RangeOp::Eq => match value {
+            "portrait" => Some(MediaFeature::Orientation(Orientation::Portrait)),
+            "landscape" => Some(MediaFeature::Orientation(Orientation::Landscape)),
+            _ => None,
+        },
+        "prefers-color-scheme" if op == RangeOp::Eq => match value {
+            "light" => Some(MediaFeature::PrefersColorScheme(ColorScheme::Light)),
+            "dark" => Some(MediaFeature::PrefersColorScheme(ColorScheme::Dark)),
+            _ => None,
+        },
+        "prefers-reduced-motion" if op == RangeOp::Eq => match value {
+            "reduce" => Some(MediaFeature::PrefersReducedMotion(true)),
+            "no-preference" => Some(MediaFeature::PrefersReducedMotion(false)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn split_number_unit(value: &str) -> Option<(f32, &str)> {
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-' || c == '+'))
+        .unwrap_or(value.len());
+    let number = value[..end].parse::<f32>().ok()?;
+    Some((number, value[end..].trim()))
+}
+
+fn parse_media_length(value: &str) -> Option<f32> {
+    let (number, unit) = split_number_unit(value)?;
+    match unit {
+        "px" => Some(number),
+        "" if number == 0.0 => Some(0.0),
+        "em" | "rem" => Some(number * MEDIA_BASE_FONT_SIZE),
+        _ => None,
+    }
+}
+
+fn parse_ratio(value: &str) -> Option<f32> {
+    match value.split_once('/') {
+        Some((w, h)) => {
+            let w = w.trim().parse::<f32>().ok()?;
+            let h = h.trim().parse::<f32>().ok()?;
+            (h != 0.0).then(|| w / h)
+        }
+        None => value.parse::<f32>().ok(),
+    }
+}
+
+fn parse_resolution(value: &str) -> Option<f32> {
+    let (number, unit) = split_number_unit(value)?;
+    match unit {
+        "dppx" | "x" => Some(number),
+        "dpi" => Some(number / 96.0),
+        "dpcm" => Some(number * 2.54 / 96.0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_max_width() {
+        let q = MediaQueryList::parse("(min-width: 600px) and (max-width: 900px)").unwrap();
+        assert!(q.matches(&MediaContext::new(700.0, 500.0)));
+        assert!(!q.matches(&MediaContext::new(500.0, 500.0)));
+        assert!(!q.matches(&MediaContext::new(901.0, 500.0)));
+    }
+
+    #[test]
+    fn test_media_type_and_not() {
+        let screen = MediaQueryList::parse("screen and (max-width: 600px)").unwrap();
+        assert!(screen.matches(&MediaContext::new(400.0, 800.0)));
+
+        let print = MediaQueryList::parse("print").unwrap();
+        assert!(!print.matches(&MediaContext::new(400.0, 800.0)));
+
+        let not_print = MediaQueryList::parse("not print").unwrap();
+        assert!(not_print.matches(&MediaContext::new(400.0, 800.0)));
+
+        let not_narrow = MediaQueryList::parse("not all and (max-width: 600px)").unwrap();
+        assert!(!not_narrow.matches(&MediaContext::new(400.0, 800.0)));
+        assert!(not_narrow.matches(&MediaContext::new(800.0, 800.0)));
+    }
+
+    #[test]
+    fn test_query_list_is_disjunction() {
+        let q = MediaQueryList::parse("(max-width: 300px), (orientation: landscape)").unwrap();
+        assert!(q.matches(&MediaContext::new(200.0, 800.0)));
+        assert!(q.matches(&MediaContext::new(1000.0, 800.0)));
+        assert!(!q.matches(&MediaContext::new(500.0, 800.0)));
+    }
+
+    #[test]
+    fn test_range_syntax() {
+        let q = MediaQueryList::parse("(width >= 600px)").unwrap();
+        assert!(q.matches(&MediaContext::new(600.0, 0.0)));
+        assert!(!q.matches(&MediaContext::new(599.0, 0.0)));
+
+        let q = MediaQueryList::parse("(400px <= width < 800px)").unwrap();
+        assert!(q.matches(&MediaContext::new(400.0, 0.0)));
+        assert!(!q.matches(&MediaContext::new(800.0, 0.0)));
+
+        let q = MediaQueryList::parse("(500px > height)").unwrap();
+        assert!(q.matches(&MediaContext::new(0.0, 499.0)));
+    }
+
+    #[test]
+    fn test_preferences_and_resolution() {
+        let dark = MediaQueryList::parse("(prefers-color-scheme: dark)").unwrap();
+        let ctx = MediaContext::new(800.0, 600.0);
+        assert!(!dark.matches(&ctx));
+        assert!(dark.matches(&ctx.with_color_scheme(ColorScheme::Dark)));
+
+        let reduce = MediaQueryList::parse("(prefers-reduced-motion: reduce)").unwrap();
+        assert!(reduce.matches(&ctx.with_reduced_motion(true)));
+        assert!(!reduce.matches(&ctx));
+
+        let hidpi = MediaQueryList::parse("(min-resolution: 192dpi)").unwrap();
+        assert!(hidpi.matches(&ctx.with_scale_factor(2.0)));
+        assert!(!hidpi.matches(&ctx));
+    }
+
+    #[test]
+    fn test_em_lengths() {
+        let q = MediaQueryList::parse("(min-width: 40em)").unwrap();
+        assert!(q.matches(&MediaContext::new(640.0, 0.0)));
+        assert!(!q.matches(&MediaContext::new(639.0, 0.0)));
+    }
+
+    #[test]
+    fn test_invalid_queries() {
+        assert!(MediaQueryList::parse("(min-width: wide)").is_none());
+        assert!(MediaQueryList::parse("tv and").is_none());
+        assert!(MediaQueryList::parse("(hover: hover)").is_none());
+    }
+
+    #[test]
+    fn test_container_query() {
+        let q = ContainerQuery::parse("sidebar (min-width: 300px)").unwrap();
+        assert_eq!(q.name.as_deref(), Some("sidebar"));
+        assert!(q.matches_size(320.0, 10.0));
+        assert!(!q.matches_size(200.0, 10.0));
+        assert!(q.accepts_container(Some("sidebar"), ContainerType::InlineSize));
+        assert!(!q.accepts_container(Some("main"), ContainerType::InlineSize));
+        assert!(!q.accepts_container(Some("sidebar"), ContainerType::Normal));
+
+        let anon = ContainerQuery::parse("(height > 100px)").unwrap();
+        assert!(anon.name.is_none());
+        assert!(!anon.accepts_container(None, ContainerType::InlineSize));
+        assert!(anon.accepts_container(None, ContainerType::Size));
+
+        assert!(ContainerQuery::parse("(prefers-color-scheme: dark)").is_none());
+    }
+}
@@ -250,6 +250,22 @@ pub struct TimelineRotation {
     pub entry_id: blinc_animation::TimelineEntryId,
 }
 
+/// Timeline binding moving an element along a motion path
+///
+/// The timeline entry is the progress along the path (0.0 to 1.0). The
+/// element's center sits on the path.
+#[derive(Clone)]
+pub struct TimelinePath {
+    /// The path, in coordinates relative to the element's layout position
+    pub path: std::sync::Arc<blinc_animation::MotionPath>,
+    /// How the element turns along the path
+    pub rotate: blinc_animation::OffsetRotate,
+    /// The timeline containing the progress animation
+    pub timeline: blinc_animation::SharedAnimatedTimeline,
+    /// The entry ID for the progress value in the timeline
+    pub entry_id: blinc_animation::TimelineEntryId,
+}
+
 /// Motion bindings for continuous animation driven by AnimatedValue
 ///
 /// This struct holds references to animated values that are sampled every frame
@@ -272,6 +288,8 @@ pub struct MotionBindings {
     pub rotation_timeline: Option<TimelineRotation>,
     /// Animated opacity
     pub opacity: Option<SharedAnimatedValue>,
+    /// Timeline-driven position along a motion path
+    pub path_timeline: Option<TimelinePath>,
 }
 
 impl MotionBindings {
@@ -285,6 +303,7 @@ impl MotionBindings {
             && self.rotation.is_none()
             && self.rotation_timeline.is_none()
             && self.opacity.is_none()
+            && self.path_timeline.is_none()
     }
 
     /// Get the current translation from animated values
@@ -347,6 +366,25 @@ impl MotionBindings {
     pub fn get_opacity(&self) -> Option<f32> {
         self.opacity.as_ref().map(|v| v.lock().unwrap().get())
     }
+
+    /// Get the current placement on the bound motion path
+    ///
+    /// Returns a transform in the element's own coordinates that puts its
+    /// center on the path.
+    pub fn get_path_transform(&self, width: f32, height: f32) -> Option<blinc_core::Affine2D> {
+        let binding = self.path_timeline.as_ref()?;
+        let progress = binding
+            .timeline
+            .lock()
+            .ok()
+            .and_then(|timeline| timeline.get(binding.entry_id))
+            .unwrap_or(0.0);
+        Some(binding.path.transform(
+            progress,
+            binding.rotate,
+            blinc_core::Point::new(width / 2.0, height / 2.0),
+        ))
+    }
 }
 
 /// Motion container for animations
@@ -429,6 +467,8 @@ pub struct Motion {
     rotation_timeline: Option<TimelineRotation>,
     /// Animated opacity
     opacity: Option<SharedAnimatedValue>,
+    /// Timeline-driven position along a motion path
+    path_timeline: Option<TimelinePath>,
     /// DEPRECATED: Whether the overlay was closing when this motion was constructed
     ///
     /// This field is deprecated and always false. Motion exit is now triggered
@@ -518,6 +558,7 @@ pub fn motion() -> Motion {
         rotation: None,
         rotation_timeline: None,
         opacity: None,
+        path_timeline: None,
         // Motion exit is now triggered explicitly via MotionHandle.exit()
         // The is_exiting field is deprecated and always false
         is_exiting: false,
@@ -576,6 +617,7 @@ pub fn motion_derived(parent_key: &str) -> Motion {
         rotation: None,
         rotation_timeline: None,
         opacity: None,
+        path_timeline: None,
         // Motion exit is now triggered explicitly via MotionHandle.exit()
         // The is_exiting field is deprecated and always false
         is_exiting: false,
@@ -898,6 +940,42 @@ impl Motion {
         self
     }
 
+    /// Move along a path as a timeline entry goes from 0.0 to 1.0
+    ///
+    /// Path coordinates are relative to the element's layout position; the
+    /// element's center sits on the path.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let timeline = ctx.use_animated_timeline();
+    /// let entry_id = timeline.lock().unwrap().configure(|t| {
+    ///     let id = t.add(0, 3000, 0.0, 1.0);
+    ///     t.set_loop(-1);
+    ///     t.start();
+    ///     id
+    /// });
+    /// let orbit = Path::circle(Point::new(0.0, 0.0), 80.0);
+    /// motion()
+    ///     .follow_path_timeline(&orbit, OffsetRotate::Auto(0.0), timeline, entry_id)
+    ///     .child(satellite)
+    /// ```
+    pub fn follow_path_timeline(
+        mut self,
+        path: &blinc_core::Path,
+        rotate: blinc_animation::OffsetRotate,
+        timeline: blinc_animation::SharedAnimatedTimeline,
+        entry_id: blinc_animation::TimelineEntryId,
+    ) -> Self {
+        self.path_timeline = Some(TimelinePath {
+            path: std::sync::Arc::new(blinc_animation::MotionPath::new(path)),
+            rotate,
+            timeline,
+            entry_id,
+        });
+        self
+    }
+
     /// Check if any continuous animations are bound
     pub fn has_animated_bindings(&self) -> bool {
         self.translate_x.is_some()
@@ -908,6 +986,7 @@ impl Motion {
             || self.rotation.is_some()
             || self.rotation_timeline.is_some()
             || self.opacity.is_some()
+            || self.path_timeline.is_some()
     }
 
     /// Get the motion bindings for this element
@@ -928,6 +1007,7 @@ impl Motion {
             rotation: self.rotation.clone(),
             rotation_timeline: self.rotation_timeline.clone(),
             opacity: self.opacity.clone(),
+            path_timeline: self.path_timeline.clone(),
         })
     }
 
@@ -160,6 +160,41 @@ pub fn record_update(element_id: &str, category: blinc_core::UpdateCategory) {
     }
 }
 
+/// Glyph atlas statistics for one atlas, as reported to the recorder.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RecorderAtlasStats {
+    pub pages: u32,
+    pub max_pages: u32,
+    pub glyphs: u64,
+    pub utilization: f32,
+    pub evictions: u64,
+    pub defragmentations: u64,
+    pub allocation_failures: u64,
+    pub uploaded_bytes: u64,
+}
+
+/// Send glyph atlas statistics to the recorder if recording is enabled.
+pub fn record_atlas_stats(grayscale: RecorderAtlasStats, color: RecorderAtlasStats) {
+    if let Some(ctx) = BlincContextState::try_get() {
+        #[cfg(feature = "recorder")]
+        maybe_install_recorder_hooks(ctx);
+
+        if ctx.is_recording_events() {
+            #[cfg(feature = "recorder")]
+            {
+                let update = blinc_recorder::GlyphAtlasStatsUpdate {
+                    grayscale: to_glyph_atlas_stats(grayscale),
+                    color: to_glyph_atlas_stats(color),
+                };
+                ctx.record_event(Box::new(update) as Box<dyn Any + Send>);
+                return;
+            }
+            #[cfg(not(feature = "recorder"))]
+            ctx.record_event(Box::new((grayscale, color)) as Box<dyn Any + Send>);
+        }
+    }
+}
+
 /// Convert ChangeCategory from diff module to UpdateCategory for recording.
 pub fn change_category_to_update(
     change: &crate::diff::ChangeCategory,
@@ -485,6 +520,20 @@ pub(crate) fn to_recorded_event(event: RecorderEventData) -> blinc_recorder::Rec
     }
 }
 
+#[cfg(feature = "recorder")]
+fn to_glyph_atlas_stats(stats: RecorderAtlasStats) -> blinc_recorder::GlyphAtlasStats {
+    blinc_recorder::GlyphAtlasStats {
+        pages: stats.pages,
+        max_pages: stats.max_pages,
+        glyphs: stats.glyphs,
+        utilization: stats.utilization,
+        evictions: stats.evictions,
+        defragmentations: stats.defragmentations,
+        allocation_failures: stats.allocation_failures,
+        uploaded_bytes: stats.uploaded_bytes,
+    }
+}
+
 #[cfg(feature = "recorder")]
 pub(crate) fn to_tree_snapshot(snapshot: TreeSnapshotData) -> blinc_recorder::TreeSnapshot {
     let mut converted = blinc_recorder::TreeSnapshot::new(
@@ -7,12 +7,12 @@ use std::any::Any;
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex, Weak};
 
-use blinc_animation::AnimationScheduler;
+use blinc_animation::{AnimationScheduler, SpringConfig};
 use indexmap::IndexMap;
 
 use blinc_core::{
-    BlendMode, BlurQuality, Brush, ClipShape, Color, CornerRadius, DrawContext, GlassStyle,
-    LayerConfig, LayerEffect, Point, Rect, Shadow, Stroke, Transform, Vec2,
+    Affine2D, BlendMode, BlurQuality, Brush, ClipShape, Color, CornerRadius, DrawContext,
+    GlassStyle, LayerConfig, LayerEffect, Point, Rect, Shadow, Stroke, Transform, Vec2,
 };
 use taffy::prelude::*;
 use taffy::Overflow;
@@ -20,14 +20,17 @@ use taffy::Overflow;
 use crate::canvas::CanvasData;
 use crate::css_parser::{
     Combinator, ComplexSelector, CompoundSelector, ElementState, SelectorPart, StructuralPseudo,
-    Stylesheet,
+    Stylesheet, StylesheetDiff,
 };
 use crate::diff::{render_props_eq, ChangeCategory, DivHash};
 use crate::div::{ElementBuilder, ElementTypeId};
 use crate::element::{ElementBounds, GlassMaterial, Material, RenderLayer, RenderProps};
 use crate::layout_animation::{LayoutAnimationConfig, LayoutAnimationState};
-use crate::selector::{ElementRegistry, ScrollRef};
+use crate::media_query::{ContainerType, MediaContext};
+use crate::selector::{ElementHandle, ElementRegistry, ScrollRef};
+use crate::shared_element::{SharedElementMount, SharedElementSnapshot, SharedElements};
 use crate::tree::{LayoutNodeId, LayoutTree};
+use crate::units::{Axis, Length, LengthContext, LengthSlot, DEFAULT_FONT_SIZE};
 use crate::visual_animation::{AnimatedRenderBounds, VisualAnimation, VisualAnimationConfig};
 
 /// A computed glass panel ready for GPU rendering
@@ -119,6 +122,10 @@ pub struct TextData {
     pub strikethrough: bool,
     /// Whether text has underline decoration
     pub underline: bool,
+    /// Paragraph layout: white-space, ellipsis, clamping, hyphenation
+    pub paragraph: crate::div::ParagraphStyle,
+    /// OpenType features and variable font axes
+    pub font_settings: blinc_core::FontSettings,
 }
 
 /// A styled span within rich text
@@ -344,6 +351,15 @@ pub struct OnReadyEntry {
     pub triggered: bool,
 }
 
+/// `@container` rule targets resolved against the current tree and stylesheet
+#[derive(Default)]
+struct ContainerQueryIndex {
+    /// (rule index, target node, container answering the query)
+    targets: Vec<(usize, LayoutNodeId, LayoutNodeId)>,
+    /// Container sizes the active matches were evaluated at, if evaluated yet
+    sizes: Option<HashMap<LayoutNodeId, (f32, f32)>>,
+}
+
 /// RenderTree - bridges layout computation and rendering
 pub struct RenderTree {
     /// The underlying layout tree
@@ -439,6 +455,8 @@ pub struct RenderTree {
     /// Pre-computed animated render bounds for this frame
     /// Calculated after layout, used during rendering
     animated_render_bounds: HashMap<LayoutNodeId, AnimatedRenderBounds>,
+    /// Shared-element (hero) transitions, matched by shared id across layouts
+    shared_elements: SharedElements,
 
     // ========================================================================
     // CSS Animation/Transition System (shared with AnimationScheduler thread)
@@ -454,6 +472,35 @@ pub struct RenderTree {
     /// Nodes that were affected by complex selector state rules (e.g. .class:hover)
     /// Used to reset render props when the state rule no longer matches
     complex_state_affected: HashSet<LayoutNodeId>,
+
+    // ========================================================================
+    // Media and Container Queries
+    // ========================================================================
+    /// Stylesheet as provided, before `@media` blocks were resolved
+    /// `stylesheet` holds the resolved copy actually used for matching
+    source_stylesheet: Option<Arc<Stylesheet>>,
+    /// Environment `@media` blocks are evaluated against
+    media_context: MediaContext,
+    /// Which `@media` blocks matched when `stylesheet` was resolved
+    media_signature: Vec<bool>,
+    /// Render props captured before any stylesheet rule touched the node
+    /// Used to restyle in place when media or container query results change
+    unstyled_props: HashMap<LayoutNodeId, RenderProps>,
+    /// Taffy styles captured before any stylesheet layout override
+    unstyled_taffy_styles: HashMap<LayoutNodeId, taffy::Style>,
+    /// `@container` rules currently in effect, as (rule index, target node)
+    container_matches: Vec<(usize, LayoutNodeId)>,
+    /// Resolved `@container` targets, rebuilt when the tree or stylesheet changes
+    container_index: Option<ContainerQueryIndex>,
+
+    // ========================================================================
+    // Contextual Lengths (em, rem, viewport units, calc)
+    // ========================================================================
+    /// Contextual lengths declared by element builders
+    element_lengths: HashMap<LayoutNodeId, Vec<(LengthSlot, Length)>>,
+    /// Contextual lengths from the stylesheet; `None` marks a slot where a
+    /// plain stylesheet value overrides the builder's contextual length
+    style_lengths: HashMap<LayoutNodeId, Vec<(LengthSlot, Option<Length>)>>,
 }
 
 /// Result of an incremental update attempt
@@ -513,10 +560,22 @@ impl RenderTree {
             visual_animations: HashMap::new(),
             previous_visual_bounds: HashMap::new(),
             animated_render_bounds: HashMap::new(),
+            shared_elements: SharedElements::default(),
             // CSS animation/transition system (shared with scheduler thread)
             css_anim_store: Arc::new(Mutex::new(crate::render_state::CssAnimationStore::new())),
             hover_css_animations: HashSet::new(),
             complex_state_affected: HashSet::new(),
+            // Media and container queries
+            source_stylesheet: None,
+            media_context: MediaContext::from_globals(1.0),
+            media_signature: Vec::new(),
+            unstyled_props: HashMap::new(),
+            unstyled_taffy_styles: HashMap::new(),
+            container_matches: Vec::new(),
+            container_index: None,
+            // Contextual lengths
+            element_lengths: HashMap::new(),
+            style_lengths: HashMap::new(),
         }
     }
 
@@ -631,6 +690,7 @@ impl RenderTree {
         // Rebuild the layout tree
         self.layout_tree = LayoutTree::new();
         self.root = Some(self.build_element(element));
+        self.container_index = None;
 
         true
     }
@@ -656,6 +716,9 @@ impl RenderTree {
             return UpdateResult::NoChanges;
         }
 
+        // Nodes, ids or classes may change, so container targets are re-resolved
+        self.container_index = None;
+
         // Tree hash differs - analyze what kind of changes occurred
         // Walk the tree comparing per-node hashes to detect change categories
         let Some(root_id) = self.root else {
@@ -980,6 +1043,11 @@ impl RenderTree {
             self.layout_tree.set_style(node_id, style.clone());
         }
 
+        // Builder props replaced any stylesheet-applied values; drop the stale
+        // snapshots so the next stylesheet pass captures fresh ones
+        self.unstyled_props.remove(&node_id);
+        self.unstyled_taffy_styles.remove(&node_id);
+
         // Update stored hash
         let own_hash = DivHash::compute_element(element);
         let tree_hash = DivHash::compute_element_tree(element);
@@ -1008,6 +1076,9 @@ impl RenderTree {
         // Register layout bounds storage if element wants bounds updates
         self.register_element_bounds_storage(node_id, element);
 
+        // Track lengths that resolve at layout time (em, vw, calc, ...)
+        self.register_contextual_lengths(node_id, element);
+
         // Recursively update children
         let child_node_ids = self.layout_tree.children(node_id);
         let child_builders = element.children_builders();
@@ -1061,6 +1132,11 @@ impl RenderTree {
             self.layout_tree.set_style(node_id, style.clone());
         }
 
+        // Builder props replaced any stylesheet-applied values; drop the stale
+        // snapshots so the next stylesheet pass captures fresh ones
+        self.unstyled_props.remove(&node_id);
+        self.unstyled_taffy_styles.remove(&node_id);
+
         let own_hash = DivHash::compute_element(element);
         let tree_hash = DivHash::compute_element_tree(element);
         self.node_hashes.insert(node_id, (own_hash, tree_hash));
@@ -1086,6 +1162,9 @@ impl RenderTree {
         // Register layout bounds storage if element wants bounds updates
         self.register_element_bounds_storage(node_id, element);
 
+        // Track lengths that resolve at layout time (em, vw, calc, ...)
+        self.register_contextual_lengths(node_id, element);
+
         let child_node_ids = self.layout_tree.children(node_id);
         let child_builders = element.children_builders();
 
@@ -1205,6 +1284,9 @@ impl RenderTree {
         // Register layout bounds storage if element wants bounds updates
         self.register_element_bounds_storage(node_id, element);
 
+        // Track lengths that resolve at layout time (em, vw, calc, ...)
+        self.register_contextual_lengths(node_id, element);
+
         // Register layout animation config if element wants animated layout transitions
         if let Some(config) = element.layout_animation_config() {
             tracing::debug!(
@@ -1224,6 +1306,8 @@ impl RenderTree {
             self.register_visual_animation_config(node_id, config);
         }
 
+        self.element_registry.register_node(node_id);
+
         // Register element ID if present (for selector API)
         if let Some(id) = element.element_id() {
             self.element_registry.register(id, node_id);
@@ -1242,6 +1326,11 @@ impl RenderTree {
                 .register_element_type(node_id, type_name.to_string());
         }
 
+        // Register checked-state probe for :checked selector matching
+        if let Some(probe) = element.checked_probe() {
+            self.element_registry.register_checked(node_id, probe);
+        }
+
         // Bind ScrollRef if present (for scroll containers)
         if let Some(scroll_ref) = element.bound_scroll_ref() {
             self.register_scroll_ref(node_id, scroll_ref);
@@ -1328,6 +1417,8 @@ impl RenderTree {
                         ascender: info.ascender,
                         strikethrough: info.strikethrough,
                         underline: info.underline,
+                        paragraph: info.paragraph,
+                        font_settings: info.font_settings,
                     })
                 } else {
                     ElementType::Div
@@ -1438,6 +1529,9 @@ impl RenderTree {
         // Register layout bounds storage if element wants bounds updates
         self.register_element_bounds_storage(node_id, element);
 
+        // Track lengths that resolve at layout time (em, vw, calc, ...)
+        self.register_contextual_lengths(node_id, element);
+
         // Register layout animation config if element wants animated layout transitions
         if let Some(config) = element.layout_animation_config() {
             tracing::debug!(
@@ -1457,6 +1551,8 @@ impl RenderTree {
             self.register_visual_animation_config(node_id, config);
         }
 
+        self.element_registry.register_node(node_id);
+
         // Register element ID if present (for selector API)
         if let Some(id) = element.element_id() {
             self.element_registry.register(id, node_id);
@@ -1475,6 +1571,11 @@ impl RenderTree {
                 .register_element_type(node_id, type_name.to_string());
         }
 
+        // Register checked-state probe for :checked selector matching
+        if let Some(probe) = element.checked_probe() {
+            self.element_registry.register_checked(node_id, probe);
+        }
+
         // Bind ScrollRef if present (for scroll containers)
         if let Some(scroll_ref) = element.bound_scroll_ref() {
             self.register_scroll_ref(node_id, scroll_ref);
@@ -1640,6 +1741,8 @@ impl RenderTree {
                         ascender: info.ascender,
                         strikethrough: info.strikethrough,
                         underline: info.underline,
+                        paragraph: info.paragraph,
+                        font_settings: info.font_settings,
                     })
                 } else {
                     ElementType::Div
@@ -1750,6 +1853,9 @@ impl RenderTree {
         // Register layout bounds storage if element wants bounds updates
         self.register_element_bounds_storage(node_id, element);
 
+        // Track lengths that resolve at layout time (em, vw, calc, ...)
+        self.register_contextual_lengths(node_id, element);
+
         // Register layout animation config if element wants animated layout transitions
         if let Some(config) = element.layout_animation_config() {
             self.layout_animation_configs.insert(node_id, config);
@@ -1760,6 +1866,8 @@ impl RenderTree {
             self.register_visual_animation_config(node_id, config);
         }
 
+        self.element_registry.register_node(node_id);
+
         // Register element ID if present (for selector API)
         if let Some(id) = element.element_id() {
             self.element_registry.register(id, node_id);
@@ -1778,6 +1886,11 @@ impl RenderTree {
                 .register_element_type(node_id, type_name.to_string());
         }
 
+        // Register checked-state probe for :checked selector matching
+        if let Some(probe) = element.checked_probe() {
+            self.element_registry.register_checked(node_id, probe);
+        }
+
         // Bind ScrollRef if present (for scroll containers)
         if let Some(scroll_ref) = element.bound_scroll_ref() {
             self.register_scroll_ref(node_id, scroll_ref);
@@ -1825,6 +1938,8 @@ impl RenderTree {
                         ascender: info.ascender,
                         strikethrough: info.strikethrough,
                         underline: info.underline,
+                        paragraph: info.paragraph,
+                        font_settings: info.font_settings,
                     })
                 } else {
                     ElementType::Div
@@ -1927,6 +2042,8 @@ impl RenderTree {
                         ascender: info.ascender,
                         strikethrough: info.strikethrough,
                         underline: info.underline,
+                        paragraph: info.paragraph,
+                        font_settings: info.font_settings,
                     })
                 } else {
                     ElementType::Div
@@ -2011,6 +2128,9 @@ impl RenderTree {
     /// Compute layout for the given viewport size
     pub fn compute_layout(&mut self, width: f32, height: f32) {
         if let Some(root) = self.root {
+            // Step 0: Resolve em/rem/viewport/calc lengths against the viewport
+            let (_, uses_percentage) = self.resolve_contextual_lengths(width, height);
+
             // Step 1: Check for existing collapsing animations and apply their constraints
             // This ensures children are laid out at the larger (animated) size during collapse
             let style_overrides = self.apply_collapsing_animation_constraints();
@@ -2025,6 +2145,18 @@ impl RenderTree {
                 },
             );
 
+            // Percentages inside calc() refer to parent sizes from this layout;
+            // re-resolve and lay out again if they moved
+            if uses_percentage && self.resolve_contextual_lengths(width, height).0 {
+                self.layout_tree.compute_layout(
+                    root,
+                    Size {
+                        width: AvailableSpace::Definite(width),
+                        height: AvailableSpace::Definite(height),
+                    },
+                );
+            }
+
             // Step 3: Restore original styles (cleanup for next frame)
             self.restore_style_overrides(style_overrides);
 
@@ -2077,6 +2209,9 @@ impl RenderTree {
             // Pre-compute animated render bounds for all nodes
             // This propagates parent animation offsets to children.
             self.compute_animated_render_bounds();
+
+            // Start hero transitions for shared ids that moved to new elements
+            self.update_shared_elements();
         }
     }
 
@@ -2262,6 +2397,179 @@ impl RenderTree {
         }
     }
 
+    /// Remember an element's contextual lengths for layout-time resolution
+    fn register_contextual_lengths(&mut self, node_id: LayoutNodeId, element: &dyn ElementBuilder) {
+        let lengths = element.contextual_lengths();
+        if lengths.is_empty() {
+            self.element_lengths.remove(&node_id);
+        } else {
+            self.element_lengths.insert(node_id, lengths.to_vec());
+        }
+    }
+
+    /// Record contextual lengths declared by a stylesheet rule for a node
+    ///
+    /// Plain values in the rule mask contextual builder lengths of the same
+    /// property, so `#id { width: 200px }` beats `div().width(vw(50.0))`.
+    fn record_style_lengths(
+        &mut self,
+        node_id: LayoutNodeId,
+        es: &crate::element_style::ElementStyle,
+    ) {
+        let masked: Vec<LengthSlot> = self
+            .element_lengths
+            .get(&node_id)
+            .map(|lengths| {
+                lengths
+                    .iter()
+                    .map(|(slot, _)| *slot)
+                    .filter(|slot| es.sets_plain_length(*slot))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if masked.is_empty() && es.contextual_lengths.is_empty() {
+            return;
+        }
+
+        let entry = self.style_lengths.entry(node_id).or_default();
+        let updates = masked.into_iter().map(|slot| (slot, None)).chain(
+            es.contextual_lengths
+                .iter()
+                .map(|(slot, len)| (*slot, Some(len.clone()))),
+        );
+        for (slot, len) in updates {
+            entry.retain(|(s, _)| *s != slot);
+            entry.push((slot, len));
+        }
+    }
+
+    /// Resolve contextual lengths into the layout styles
+    ///
+    /// Font-relative units use the node's font size (the inherited one for
+    /// `font-size` itself, as in CSS), viewport units the given viewport, and
+    /// percentages inside math expressions the parent's content box from the
+    /// previous layout. Returns `(changed, uses_percentage)`.
+    fn resolve_contextual_lengths(
+        &mut self,
+        viewport_width: f32,
+        viewport_height: f32,
+    ) -> (bool, bool) {
+        if self.element_lengths.is_empty() && self.style_lengths.is_empty() {
+            return (false, false);
+        }
+
+        let mut node_ids: Vec<LayoutNodeId> = self.element_lengths.keys().copied().collect();
+        node_ids.extend(
+            self.style_lengths
+                .keys()
+                .filter(|id| !self.element_lengths.contains_key(id)),
+        );
+        // Ancestors first, so relative font sizes build on resolved parents
+        node_ids.sort_by_cached_key(|id| {
+            std::iter::successors(Some(*id), |id| self.layout_tree.parent(*id)).count()
+        });
+
+        let mut changed = false;
+        let mut uses_percentage = false;
+        let mut removed_nodes = Vec::new();
+        for node_id in node_ids {
+            // Builder lengths first, stylesheet lengths override per slot
+            let mut lengths: Vec<(LengthSlot, Option<Length>)> = self
+                .element_lengths
+                .get(&node_id)
+                .map(|l| {
+                    l.iter()
+                        .map(|(slot, len)| (*slot, Some(len.clone())))
+                        .collect()
+                })
+                .unwrap_or_default();
+            for (slot, len) in self.style_lengths.get(&node_id).into_iter().flatten() {
+                lengths.retain(|(s, _)| s != slot);
+                lengths.push((*slot, len.clone()));
+            }
+            // The font size first, since other em lengths refer to it
+            lengths.sort_by_key(|(slot, _)| *slot != LengthSlot::FontSize);
+
+            let inherited_font_size =
+                std::iter::successors(self.layout_tree.parent(node_id), |id| {
+                    self.layout_tree.parent(*id)
+                })
+                .find_map(|id| self.render_nodes.get(&id).and_then(|n| n.props.font_size))
+                .unwrap_or(DEFAULT_FONT_SIZE);
+            let mut font_size = self
+                .render_nodes
+                .get(&node_id)
+                .and_then(|n| n.props.font_size)
+                .unwrap_or(inherited_font_size);
+            let parent_box = match self.layout_tree.parent(node_id) {
+                Some(parent) => self.layout_tree.get_layout(parent).map(|layout| {
+                    (
+                        layout.size.width
+                            - layout.padding.left
+                            - layout.padding.right
+                            - layout.border.left
+                            - layout.border.right,
+                        layout.size.height
+                            - layout.padding.top
+                            - layout.padding.bottom
+                            - layout.border.top
+                            - layout.border.bottom,
+                    )
+                }),
+                None => Some((viewport_width, viewport_height)),
+            };
+
+            let Some(original_style) = self.layout_tree.get_style(node_id) else {
+                removed_nodes.push(node_id);
+                continue;
+            };
+            let mut style = original_style.clone();
+            for (slot, len) in lengths {
+                let Some(len) = len else {
+                    continue;
+                };
+                // `font-size: 2em` is twice the parent's size, not its own
+                let ctx = LengthContext::new(viewport_width, viewport_height).with_font_size(
+                    if slot == LengthSlot::FontSize {
+                        inherited_font_size
+                    } else {
+                        font_size
+                    },
+                );
+                let ctx = match (slot.percent_axis(), parent_box) {
+                    (Some(Axis::Horizontal), Some((w, _))) => ctx.with_percent_base(w),
+                    (Some(Axis::Vertical), Some((_, h))) => ctx.with_percent_base(h),
+                    _ => ctx,
+                };
+                uses_percentage |= len.uses_percentage();
+                let Some(px) = len.resolve(&ctx) else {
+                    continue;
+                };
+                if slot == LengthSlot::FontSize {
+                    font_size = px;
+                    if let Some(render_node) = self.render_nodes.get_mut(&node_id) {
+                        if render_node.props.font_size != Some(px) {
+                            render_node.props.font_size = Some(px);
+                            changed = true;
+                        }
+                    }
+                } else {
+                    slot.apply(&mut style, px);
+                }
+            }
+            // Only touch taffy when something moved, to keep its layout cache
+            if style != original_style {
+                self.layout_tree.set_style(node_id, style);
+                changed = true;
+            }
+        }
+        for node_id in removed_nodes {
+            self.element_lengths.remove(&node_id);
+            self.style_lengths.remove(&node_id);
+        }
+        (changed, uses_percentage)
+    }
+
     /// Update all registered layout bounds storages after layout computation
     ///
     /// When bounds change (width or height differ), the on_change callback is invoked.
@@ -2887,6 +3195,147 @@ impl RenderTree {
             .unwrap_or(false)
     }
 
+    // =========================================================================
+    // Shared-Element Transitions
+    // =========================================================================
+
+    /// Match shared ids against the previous layout and start transitions
+    ///
+    /// A shared id that now belongs to a different node than in the last
+    /// layout (or whose node unmounted shortly before) flies the new node in
+    /// from the old one's screen bounds. Called after layout computation.
+    fn update_shared_elements(&mut self) {
+        let mounts: Vec<SharedElementMount> = self
+            .render_nodes
+            .iter()
+            .filter_map(|(&node, render_node)| {
+                let id = render_node.props.shared_id.clone()?;
+                Some(SharedElementMount {
+                    id,
+                    node,
+                    snapshot: SharedElementSnapshot {
+                        bounds: self.screen_bounds(node)?,
+                        corner_radius: render_node.props.border_radius,
+                        opacity: render_node.props.opacity,
+                    },
+                    spring: render_node
+                        .props
+                        .shared_spring
+                        .unwrap_or_else(SpringConfig::snappy),
+                })
+            })
+            .collect();
+
+        let scheduler_handle = if let Some(arc) = self.animations.upgrade() {
+            arc.lock().unwrap().handle()
+        } else if let Some(handle) = crate::render_state::get_global_scheduler() {
+            handle
+        } else {
+            return;
+        };
+        let Some(now) = scheduler_handle.now() else {
+            return;
+        };
+
+        self.shared_elements.update(mounts, now, &scheduler_handle);
+    }
+
+    /// Screen position of a node: layout offsets plus ancestor scroll offsets
+    ///
+    /// Element and motion transforms are not included.
+    fn screen_position(&self, node: LayoutNodeId) -> Option<(f32, f32)> {
+        let bounds = self.layout_tree.get_bounds(node, (0.0, 0.0))?;
+        let (mut x, mut y) = (bounds.x, bounds.y);
+        let mut current = node;
+        while let Some(parent) = self.layout_tree.parent(current) {
+            let parent_bounds = self.layout_tree.get_bounds(parent, (0.0, 0.0))?;
+            let (scroll_x, scroll_y) = self.get_scroll_offset(parent);
+            x += parent_bounds.x + scroll_x;
+            y += parent_bounds.y + scroll_y;
+            current = parent;
+        }
+        Some((x, y))
+    }
+
+    /// Layout bounds of a node in screen space
+    fn screen_bounds(&self, node: LayoutNodeId) -> Option<ElementBounds> {
+        let bounds = self.layout_tree.get_bounds(node, (0.0, 0.0))?;
+        let (x, y) = self.screen_position(node)?;
+        Some(ElementBounds {
+            x,
+            y,
+            width: bounds.width,
+            height: bounds.height,
+        })
+    }
+
+    /// Get the placement of a node flying in from a shared element
+    ///
+    /// Moves and scales the node from its layout bounds onto the transition's
+    /// current bounds, in the node's own coordinates. `None` when the node is
+    /// not in flight.
+    pub fn get_shared_element_transform(&self, node_id: LayoutNodeId) -> Option<Affine2D> {
+        let transition = self.shared_elements.in_flight(node_id)?;
+        Some(transition.placement(&self.screen_bounds(node_id)?))
+    }
+
+    /// Check if a node is flying in from a shared element
+    pub fn is_shared_element_in_flight(&self, node_id: LayoutNodeId) -> bool {
+        self.shared_elements.in_flight(node_id).is_some()
+    }
+
+    /// Check if any shared-element transition is running
+    pub fn has_active_shared_transitions(&self) -> bool {
+        self.shared_elements.is_animating()
+    }
+
+    /// Corner radius and opacity of a node in flight, replacing its own
+    fn shared_element_style(&self, node: LayoutNodeId) -> Option<(CornerRadius, f32)> {
+        let transition = self.shared_elements.in_flight(node)?;
+        let radius = transition.local_corner_radius(&self.screen_bounds(node)?);
+        Some((radius, transition.current().opacity))
+    }
+
+    /// Draw shared elements in flight above the rest of the tree
+    ///
+    /// They are skipped during the normal traversal, so ancestors' clips,
+    /// opacity and transforms don't apply to them.
+    fn render_shared_elements_in_flight(
+        &self,
+        ctx: &mut dyn DrawContext,
+        render_state: &crate::render_state::RenderState,
+    ) {
+        for node in self.shared_elements.in_flight_nodes() {
+            // Content origin of the parent, where the node's layout offset applies
+            let origin = self
+                .layout_tree
+                .parent(node)
+                .and_then(|parent| {
+                    let (x, y) = self.screen_position(parent)?;
+                    let (scroll_x, scroll_y) = self.get_scroll_offset(parent);
+                    Some((x + scroll_x, y + scroll_y))
+                })
+                .unwrap_or((0.0, 0.0));
+            for layer in [
+                RenderLayer::Background,
+                RenderLayer::Glass,
+                RenderLayer::Foreground,
+            ] {
+                self.render_layer_with_motion(
+                    ctx,
+                    node,
+                    origin,
+                    layer,
+                    0,
+                    false,
+                    render_state,
+                    1.0,
+                    (0.0, 0.0),
+                );
+            }
+        }
+    }
+
     // =========================================================================
     // On-Ready Callbacks
     // =========================================================================
@@ -3021,6 +3470,96 @@ impl RenderTree {
         self.element_registry.get(id)
     }
 
+    /// Find the first element matching a CSS selector, in document order
+    ///
+    /// Accepts the stylesheet selector syntax, including selector lists,
+    /// combinators, structural pseudo-classes and `:checked`. Interaction
+    /// states (`:hover`, `:active`, `:focus`) never match here; use
+    /// [`query_selector_with_state`](Self::query_selector_with_state) for those.
+    ///
+    /// ```ignore
+    /// let row = tree.query_selector("#inbox .row:first-child");
+    /// ```
+    pub fn query_selector(&self, selector: &str) -> Option<ElementHandle> {
+        self.select(selector, None).into_iter().next()
+    }
+
+    /// Find all elements matching a CSS selector, in document order
+    ///
+    /// ```ignore
+    /// let checked_rows = tree.query_selector_all("#inbox .row:checked");
+    /// ```
+    pub fn query_selector_all(&self, selector: &str) -> Vec<ElementHandle> {
+        self.select(selector, None)
+    }
+
+    /// Like [`query_selector`](Self::query_selector), matching interaction
+    /// states against the router's current hover, press and focus state
+    pub fn query_selector_with_state(
+        &self,
+        selector: &str,
+        router: &crate::event_router::EventRouter,
+    ) -> Option<ElementHandle> {
+        self.select(selector, Some(router)).into_iter().next()
+    }
+
+    /// Like [`query_selector_all`](Self::query_selector_all), matching
+    /// interaction states against the router's current state
+    pub fn query_selector_all_with_state(
+        &self,
+        selector: &str,
+        router: &crate::event_router::EventRouter,
+    ) -> Vec<ElementHandle> {
+        self.select(selector, Some(router))
+    }
+
+    /// Run a selector list against the tree in document order
+    fn select(
+        &self,
+        selector: &str,
+        router: Option<&crate::event_router::EventRouter>,
+    ) -> Vec<ElementHandle> {
+        let selectors = match crate::css_parser::parse_selectors(selector) {
+            Ok(selectors) => selectors,
+            Err(e) => {
+                tracing::warn!("Invalid selector query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let (hovered_nodes, pressed_nodes, focused_node) = match router {
+            Some(router) => (
+                router.hovered_nodes().collect(),
+                router.pressed_target().into_iter().collect(),
+                router.focused(),
+            ),
+            None => (HashSet::new(), HashSet::new(), None),
+        };
+
+        let mut matches = Vec::new();
+        let mut stack: Vec<LayoutNodeId> = self.root.into_iter().collect();
+        while let Some(node_id) = stack.pop() {
+            let matched = selectors.iter().any(|s| {
+                self.complex_selector_matches(
+                    s,
+                    node_id,
+                    &hovered_nodes,
+                    &pressed_nodes,
+                    focused_node,
+                )
+            });
+            if matched {
+                matches.push(ElementHandle::from_node(
+                    node_id,
+                    Arc::clone(&self.element_registry),
+                ));
+            }
+            // Push in reverse so children are visited first-to-last
+            stack.extend(self.layout_tree.children(node_id).into_iter().rev());
+        }
+        matches
+    }
+
     /// Get a bound ScrollRef by node ID
     pub fn scroll_ref(&self, node_id: LayoutNodeId) -> Option<&ScrollRef> {
         self.scroll_refs.get(&node_id)
@@ -3096,67 +3635,144 @@ impl RenderTree {
                     element_id,
                     options,
                 } => {
-                    // Look up element bounds and scroll to make it visible
                     if let Some(target_node) = self.element_registry.get(&element_id) {
-                        // Get target element's bounds
-                        if let Some(target_bounds) = self.get_bounds(target_node) {
-                            // Get scroll container's bounds
-                            if let Some(container_bounds) = self.get_bounds(node_id) {
-                                // Calculate scroll offset to bring element into view
-                                // Element's position relative to scroll container
-                                let relative_y = target_bounds.y - container_bounds.y;
-                                let relative_x = target_bounds.x - container_bounds.x;
-
-                                // Scroll to center the element (or just make it visible)
-                                let viewport_height = physics.viewport_height;
-                                let viewport_width = physics.viewport_width;
-
-                                // Calculate target offsets
-                                // Center vertically
-                                let target_center_y =
-                                    relative_y + target_bounds.height / 2.0 - viewport_height / 2.0;
-                                let target_offset_y = (-target_center_y)
-                                    .clamp(physics.max_offset_y(), physics.min_offset_y());
-
-                                // Center horizontally
-                                let target_center_x =
-                                    relative_x + target_bounds.width / 2.0 - viewport_width / 2.0;
-                                let target_offset_x = (-target_center_x)
-                                    .clamp(physics.max_offset_x(), physics.min_offset_x());
-
-                                // Use smooth animation if requested
-                                if options.behavior == crate::selector::ScrollBehavior::Smooth {
-                                    physics.scroll_to_animated(target_offset_x, target_offset_y);
-                                } else {
-                                    // Instant scroll
-                                    physics.offset_y = target_offset_y;
-                                    if matches!(
-                                        physics.config.direction,
-                                        crate::scroll::ScrollDirection::Horizontal
-                                            | crate::scroll::ScrollDirection::Both
-                                    ) {
-                                        physics.offset_x = target_offset_x;
-                                    }
-                                }
-                            }
-                        }
+                        self.scroll_to_node(node_id, target_node, options, &mut physics);
                     }
                 }
             }
 
             // Update ScrollRef with current state
-            if let Some(scroll_ref) = self.scroll_refs.get(&node_id) {
-                scroll_ref.update_state(
-                    (physics.offset_x.abs(), physics.offset_y.abs()),
-                    (physics.content_width, physics.content_height),
-                    (physics.viewport_width, physics.viewport_height),
-                );
+            self.sync_scroll_ref(node_id, &physics);
+        }
+
+        // Scroll-into-view requests go to the nearest enclosing scroll container
+        for (element_id, options) in crate::selector::take_scroll_into_view_requests() {
+            let Some(target_node) = self.element_registry.get(&element_id) else {
+                continue;
+            };
+            let mut container = self.layout_tree.parent(target_node);
+            while let Some(node) = container {
+                if self.scroll_physics.contains_key(&node) {
+                    break;
+                }
+                container = self.layout_tree.parent(node);
             }
+            let Some(node_id) = container else {
+                continue;
+            };
+            let physics = self.scroll_physics[&node_id].clone();
+            let mut physics = physics.lock().unwrap();
+            self.scroll_to_node(node_id, target_node, options, &mut physics);
+            self.sync_scroll_ref(node_id, &physics);
+            any_modified = true;
         }
 
         any_modified
     }
 
+    /// Position of `node` within the content of its ancestor `container`
+    fn offset_within(&self, node: LayoutNodeId, container: LayoutNodeId) -> Option<(f32, f32)> {
+        let (mut x, mut y) = (0.0, 0.0);
+        let mut current = node;
+        while current != container {
+            let bounds = self.layout_tree.get_bounds(current, (0.0, 0.0))?;
+            x += bounds.x;
+            y += bounds.y;
+            current = self.layout_tree.parent(current)?;
+        }
+        Some((x, y))
+    }
+
+    /// Scroll `container` so that `target` is aligned as `options` ask
+    fn scroll_to_node(
+        &self,
+        container: LayoutNodeId,
+        target: LayoutNodeId,
+        options: crate::selector::ScrollOptions,
+        physics: &mut crate::scroll::ScrollPhysics,
+    ) {
+        let (Some((x, y)), Some(bounds)) = (
+            self.offset_within(target, container),
+            self.get_bounds(target),
+        ) else {
+            return;
+        };
+
+        // Offsets are negative when scrolled down or right
+        let target_offset_y = -options.block.scroll_position(
+            y,
+            bounds.height,
+            physics.viewport_height,
+            -physics.offset_y,
+        );
+        let target_offset_y = target_offset_y.clamp(physics.max_offset_y(), physics.min_offset_y());
+        let target_offset_x = -options.inline.scroll_position(
+            x,
+            bounds.width,
+            physics.viewport_width,
+            -physics.offset_x,
+        );
+        let target_offset_x = target_offset_x.clamp(physics.max_offset_x(), physics.min_offset_x());
+
+        if options.behavior == crate::selector::ScrollBehavior::Smooth {
+            physics.scroll_to_animated(target_offset_x, target_offset_y);
+        } else {
+            physics.offset_y = target_offset_y;
+            if matches!(
+                physics.config.direction,
+                crate::scroll::ScrollDirection::Horizontal | crate::scroll::ScrollDirection::Both
+            ) {
+                physics.offset_x = target_offset_x;
+            }
+        }
+    }
+
+    /// Copy scroll state, tracked view bounds and the current anchor to a bound ScrollRef
+    fn sync_scroll_ref(&self, node_id: LayoutNodeId, physics: &crate::scroll::ScrollPhysics) {
+        let Some(scroll_ref) = self.scroll_refs.get(&node_id) else {
+            return;
+        };
+        let scroll_y = physics.offset_y.abs();
+        scroll_ref.update_state(
+            (physics.offset_x.abs(), scroll_y),
+            (physics.content_width, physics.content_height),
+            (physics.viewport_width, physics.viewport_height),
+        );
+
+        for view in scroll_ref.views() {
+            let bounds = self.element_registry.get(&view).and_then(|node| {
+                let (x, y) = self.offset_within(node, node_id)?;
+                let bounds = self.get_bounds(node)?;
+                Some((x, y, bounds.width, bounds.height))
+            });
+            scroll_ref.set_view_bounds(&view, bounds);
+        }
+
+        let anchors = scroll_ref.anchors();
+        if anchors.is_empty() {
+            return;
+        }
+        // At the bottom, later anchors may never reach the top of the
+        // viewport; the last visible one is current then
+        let at_bottom = physics.offset_y <= physics.max_offset_y() + 1.0 && scroll_y > 0.0;
+        let line = if at_bottom {
+            scroll_y + physics.viewport_height
+        } else {
+            scroll_y + physics.viewport_height / 5.0
+        };
+        let current = anchors
+            .into_iter()
+            .filter_map(|anchor| {
+                let node = self.element_registry.get(&anchor)?;
+                let (_, y) = self.offset_within(node, node_id)?;
+                Some((anchor, y))
+            })
+            .filter(|(_, y)| *y <= line)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(anchor, _)| anchor);
+        scroll_ref.set_current_anchor(current);
+    }
+
     /// Dispatch an event to a node's handlers
     ///
     /// This automatically marks the tree as dirty after dispatching,
@@ -3396,6 +4012,27 @@ impl RenderTree {
             .broadcast(blinc_core::events::event_types::TEXT_INPUT, &ctx);
     }
 
+    /// Broadcast an IME composition event to ALL composition handlers
+    ///
+    /// Like text input, only the focused text widget acts on it. `text` is the
+    /// preedit for `COMPOSITION_UPDATE` and the final text for `COMPOSITION_COMMIT`.
+    pub fn broadcast_composition_event(
+        &mut self,
+        event_type: blinc_core::events::EventType,
+        text: Option<&str>,
+        cursor: Option<(usize, usize)>,
+    ) {
+        let mut ctx = crate::event_handler::EventContext::new(
+            event_type,
+            crate::tree::LayoutNodeId::default(), // Will be overwritten per-node
+        );
+        if let Some(text) = text {
+            ctx = ctx.with_composition(text, cursor);
+        }
+
+        self.handler_registry.broadcast(event_type, &ctx);
+    }
+
     /// Broadcast a key event to ALL key handlers
     ///
     /// This is used when the router's focused node ID may be stale after a tree rebuild.
@@ -3908,6 +4545,52 @@ impl RenderTree {
             .and_then(|b| b.get_transform())
     }
 
+    /// Get a node's placement on its motion path, in its own coordinates
+    ///
+    /// A path bound with [`Motion::follow_path_timeline`](crate::motion::Motion::follow_path_timeline)
+    /// takes precedence over a CSS `offset-path`.
+    pub fn get_offset_path_transform(
+        &self,
+        node_id: LayoutNodeId,
+        width: f32,
+        height: f32,
+    ) -> Option<blinc_core::Affine2D> {
+        self.motion_bindings
+            .get(&node_id)
+            .and_then(|b| b.get_path_transform(width, height))
+            .or_else(|| {
+                self.render_nodes
+                    .get(&node_id)
+                    .and_then(|n| n.props.offset_path_transform(width, height))
+            })
+    }
+
+    /// Get a node's placement relative to its layout position, in its own
+    /// coordinates: a shared-element flight, then its motion path
+    pub fn get_placement_transform(
+        &self,
+        node_id: LayoutNodeId,
+        width: f32,
+        height: f32,
+    ) -> Option<Affine2D> {
+        let shared = self.get_shared_element_transform(node_id);
+        let offset = self.get_offset_path_transform(node_id, width, height);
+        match (shared, offset) {
+            (Some(shared), Some(offset)) => Some(shared.then(&offset)),
+            (shared, offset) => shared.or(offset),
+        }
+    }
+
+    /// Transform placing a node: its layout position, then its placement
+    fn position_transform(&self, node: LayoutNodeId, bounds: &ElementBounds) -> Transform {
+        match self.get_placement_transform(node, bounds.width, bounds.height) {
+            Some(placement) => {
+                Transform::Affine2D(Affine2D::translation(bounds.x, bounds.y).then(&placement))
+            }
+            None => Transform::translate(bounds.x, bounds.y),
+        }
+    }
+
     /// Get the motion scale for a node (if it has motion bindings)
     ///
     /// Returns (scale_x, scale_y) if scale bindings are present.
@@ -4176,13 +4859,7 @@ impl RenderTree {
             }
 
             // Sync ScrollRef state with current physics (for scrollbar position updates)
-            if let Some(scroll_ref) = self.scroll_refs.get(&node_id) {
-                scroll_ref.update_state(
-                    (physics.offset_x.abs(), physics.offset_y.abs()),
-                    (physics.content_width, physics.content_height),
-                    (physics.viewport_width, physics.viewport_height),
-                );
-            }
+            self.sync_scroll_ref(node_id, &physics);
         }
 
         any_animating
@@ -4255,57 +4932,420 @@ impl RenderTree {
             } else {
                 None
             }
-        })
+        })
+    }
+
+    /// Update render props for a node
+    ///
+    /// This allows event handlers to modify visual properties without
+    /// triggering a full tree rebuild.
+    pub fn update_render_props<F>(&mut self, node_id: LayoutNodeId, f: F)
+    where
+        F: FnOnce(&mut RenderProps),
+    {
+        if let Some(render_node) = self.render_nodes.get_mut(&node_id) {
+            f(&mut render_node.props);
+        }
+    }
+
+    // =========================================================================
+    // Stylesheet Integration
+    // =========================================================================
+
+    /// Set the stylesheet for automatic state modifier application
+    ///
+    /// When a stylesheet is set, elements with IDs will automatically get
+    /// `:hover`, `:active`, `:focus`, `:disabled` styles applied based on
+    /// their current interaction state.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let css = r#"
+    ///     #button { background: blue; }
+    ///     #button:hover { opacity: 0.9; }
+    ///     #button:active { transform: scale(0.98); }
+    /// "#;
+    /// let stylesheet = Stylesheet::parse_with_errors(css).stylesheet;
+    /// tree.set_stylesheet(stylesheet);
+    /// ```
+    pub fn set_stylesheet(&mut self, stylesheet: Stylesheet) {
+        let stylesheet = Arc::new(stylesheet);
+        self.stylesheet = Some(self.resolve_stylesheet(&stylesheet));
+        self.source_stylesheet = Some(stylesheet);
+    }
+
+    /// Set a shared stylesheet reference
+    ///
+    /// `@media` blocks are resolved against the current [`MediaContext`]; the
+    /// resolved copy is what element matching sees.
+    pub fn set_stylesheet_arc(&mut self, stylesheet: Arc<Stylesheet>) {
+        let unchanged = self
+            .source_stylesheet
+            .as_ref()
+            .is_some_and(|s| Arc::ptr_eq(s, &stylesheet));
+        let resolved = if unchanged {
+            self.stylesheet
+                .clone()
+                .unwrap_or_else(|| self.resolve_stylesheet(&stylesheet))
+        } else {
+            self.resolve_stylesheet(&stylesheet)
+        };
+        // Also update the global stylesheet for form widget CSS override resolution
+        crate::css_parser::set_active_stylesheet(Arc::clone(&resolved));
+        self.stylesheet = Some(resolved);
+        self.source_stylesheet = Some(stylesheet);
+        if !unchanged {
+            self.container_index = None;
+        }
+    }
+
+    /// Get the current stylesheet, if any
+    ///
+    /// This is the stylesheet with matching `@media` blocks already applied.
+    pub fn stylesheet(&self) -> Option<&Stylesheet> {
+        self.stylesheet.as_ref().map(|s| s.as_ref())
+    }
+
+    /// Resolve `@media` blocks of a stylesheet against the current context
+    fn resolve_stylesheet(&mut self, stylesheet: &Arc<Stylesheet>) -> Arc<Stylesheet> {
+        if !stylesheet.has_media_rules() {
+            self.media_signature.clear();
+            return Arc::clone(stylesheet);
+        }
+        self.media_signature = stylesheet.media_signature(&self.media_context);
+        Arc::new(stylesheet.resolve_media(&self.media_context))
+    }
+
+    /// Get the environment `@media` blocks are evaluated against
+    pub fn media_context(&self) -> &MediaContext {
+        &self.media_context
+    }
+
+    /// Update the environment `@media` blocks are evaluated against
+    ///
+    /// When the set of matching `@media` blocks changes, the stylesheet is
+    /// re-resolved and every node is restyled in place (no tree rebuild).
+    ///
+    /// Returns `true` if styles changed and layout must be recomputed.
+    pub fn set_media_context(&mut self, ctx: MediaContext) -> bool {
+        if self.media_context == ctx {
+            return false;
+        }
+        self.media_context = ctx;
+
+        let Some(source) = self.source_stylesheet.clone() else {
+            return false;
+        };
+        if !source.has_media_rules() || source.media_signature(&ctx) == self.media_signature {
+            return false;
+        }
+
+        tracing::debug!("Media query results changed, restyling");
+        let resolved = self.resolve_stylesheet(&source);
+        crate::css_parser::set_active_stylesheet(Arc::clone(&resolved));
+        self.stylesheet = Some(resolved);
+        self.container_index = None;
+        self.restyle();
+        true
+    }
+
+    /// Re-apply the active stylesheet to every node without rebuilding the tree
+    ///
+    /// Nodes are reset to the props and layout styles they had before any
+    /// stylesheet rule was applied, then base styles, container query styles
+    /// and layout overrides are applied again. State styles (`:hover`, etc.)
+    /// are re-applied on the next call to `apply_stylesheet_state_styles`.
+    pub fn restyle(&mut self) {
+        self.restyle_nodes(None);
+    }
+
+    /// Restyle every node, or only the nodes in `only`
+    fn restyle_nodes(&mut self, only: Option<&HashSet<LayoutNodeId>>) {
+        let (props, styles): (Vec<_>, Vec<_>) = match only {
+            None => (
+                std::mem::take(&mut self.unstyled_props)
+                    .into_iter()
+                    .collect(),
+                std::mem::take(&mut self.unstyled_taffy_styles)
+                    .into_iter()
+                    .collect(),
+            ),
+            Some(nodes) => (
+                nodes
+                    .iter()
+                    .filter_map(|id| self.unstyled_props.remove_entry(id))
+                    .collect(),
+                nodes
+                    .iter()
+                    .filter_map(|id| self.unstyled_taffy_styles.remove_entry(id))
+                    .collect(),
+            ),
+        };
+        for (node_id, props) in props {
+            if let Some(render_node) = self.render_nodes.get_mut(&node_id) {
+                render_node.props = props;
+            }
+        }
+        for (node_id, style) in styles {
+            self.layout_tree.set_style(node_id, style);
+        }
+        match only {
+            None => {
+                self.base_styles.clear();
+                self.base_taffy_styles.clear();
+                self.complex_state_affected.clear();
+                self.style_lengths.clear();
+            }
+            Some(nodes) => {
+                for node_id in nodes {
+                    self.base_styles.remove(node_id);
+                    self.base_taffy_styles.remove(node_id);
+                    self.complex_state_affected.remove(node_id);
+                    self.style_lengths.remove(node_id);
+                }
+            }
+        }
+
+        self.apply_base_styles_to(only);
+        self.apply_layout_overrides_to(only);
+    }
+
+    /// Replace the stylesheet and restyle the live tree in place
+    ///
+    /// Used for hot reload: nodes, handlers and element state are kept, only
+    /// stylesheet-derived props and layout overrides are re-applied. `diff` is
+    /// the difference between the previous and the new stylesheet; only nodes
+    /// matched by its added, removed or changed selectors are restyled. Changes
+    /// to variables, keyframes, `@media`/`@container` blocks or state selectors
+    /// restyle the whole tree. Call `compute_layout()` afterwards.
+    pub fn reload_stylesheet(&mut self, stylesheet: Arc<Stylesheet>, diff: &StylesheetDiff) {
+        let previous = self.stylesheet.clone();
+        self.set_stylesheet_arc(stylesheet);
+        if diff.is_empty() {
+            return;
+        }
+        self.container_index = None;
+
+        match previous.and_then(|previous| self.nodes_affected_by(&previous, diff)) {
+            Some(nodes) => {
+                tracing::debug!("Stylesheet reload: restyling {} nodes", nodes.len());
+                self.restyle_nodes(Some(&nodes));
+            }
+            None => {
+                // Force container rules to be re-evaluated against the new sheet
+                self.container_matches.clear();
+                self.restyle();
+            }
+        }
+        self.start_all_css_animations();
+    }
+
+    /// Nodes matched by the selectors a stylesheet diff touches
+    ///
+    /// Selectors are matched against both the previous and the current
+    /// stylesheet, so nodes losing a rule are restyled too. Returns `None`
+    /// when the change can affect nodes beyond those selectors.
+    fn nodes_affected_by(
+        &self,
+        previous: &Stylesheet,
+        diff: &StylesheetDiff,
+    ) -> Option<HashSet<LayoutNodeId>> {
+        if diff.variables_changed || diff.keyframes_changed || diff.conditional_rules_changed {
+            return None;
+        }
+        let current = self.stylesheet.as_ref()?;
+        let touched: HashSet<&str> = diff
+            .added
+            .iter()
+            .chain(&diff.removed)
+            .chain(&diff.changed)
+            .map(String::as_str)
+            .collect();
+
+        let mut nodes = HashSet::new();
+        let empty_set = HashSet::new();
+        for sheet in [previous, current.as_ref()] {
+            for (selector, _) in sheet.complex_rules() {
+                if !touched.contains(format!("{:?}", selector).as_str()) {
+                    continue;
+                }
+                // Interaction states are matched lazily; restyle everything
+                if selector.has_state() {
+                    return None;
+                }
+                nodes.extend(self.render_nodes.keys().copied().filter(|&node_id| {
+                    self.complex_selector_matches(selector, node_id, &empty_set, &empty_set, None)
+                }));
+            }
+        }
+        // Simple rules are reported as `#id` or `#id:state`
+        for selector in &touched {
+            if let Some(key) = selector.strip_prefix('#') {
+                let id = key.split(':').next().unwrap_or(key);
+                nodes.extend(self.element_registry.get(id));
+            }
+        }
+        Some(nodes)
     }
 
-    /// Update render props for a node
+    /// Evaluate `@container` rules against the last computed layout
     ///
-    /// This allows event handlers to modify visual properties without
-    /// triggering a full tree rebuild.
-    pub fn update_render_props<F>(&mut self, node_id: LayoutNodeId, f: F)
-    where
-        F: FnOnce(&mut RenderProps),
-    {
-        if let Some(render_node) = self.render_nodes.get_mut(&node_id) {
-            f(&mut render_node.props);
+    /// Call after `compute_layout()`. Each rule's targets and the container
+    /// answering it are resolved once per tree and stylesheet; afterwards only
+    /// the container sizes are compared, and conditions are re-checked when
+    /// one of them changed. When the set of matching rules changes, affected
+    /// nodes are restyled in place.
+    ///
+    /// Returns `true` if styles changed and layout must be recomputed.
+    pub fn apply_container_queries(&mut self) -> bool {
+        let mut index = match self.container_index.take() {
+            Some(index) => index,
+            None => self.build_container_index(),
+        };
+
+        let sizes: HashMap<LayoutNodeId, (f32, f32)> = index
+            .targets
+            .iter()
+            .filter_map(|&(_, _, container)| {
+                let bounds = self.layout_tree.get_bounds(container, (0.0, 0.0))?;
+                Some((container, (bounds.width, bounds.height)))
+            })
+            .collect();
+        if index.sizes.as_ref() == Some(&sizes) {
+            self.container_index = Some(index);
+            return false;
+        }
+
+        let rules = self
+            .stylesheet
+            .as_ref()
+            .map(|s| s.container_rules())
+            .unwrap_or_default();
+        let matches: Vec<(usize, LayoutNodeId)> = index
+            .targets
+            .iter()
+            .filter(|&&(rule_index, _, container)| {
+                sizes.get(&container).is_some_and(|&(width, height)| {
+                    rules
+                        .get(rule_index)
+                        .is_some_and(|rule| rule.query.matches_size(width, height))
+                })
+            })
+            .map(|&(rule_index, node_id, _)| (rule_index, node_id))
+            .collect();
+        index.sizes = Some(sizes);
+        self.container_index = Some(index);
+
+        if matches == self.container_matches {
+            return false;
         }
+        tracing::debug!("Container query results changed ({} active)", matches.len());
+        self.container_matches = matches;
+        self.restyle();
+        true
     }
 
-    // =========================================================================
-    // Stylesheet Integration
-    // =========================================================================
+    /// Resolve every (container rule, target node) pair to the container answering it
+    fn build_container_index(&self) -> ContainerQueryIndex {
+        let mut index = ContainerQueryIndex::default();
+        let Some(stylesheet) = &self.stylesheet else {
+            return index;
+        };
+        let rules = stylesheet.container_rules();
+        if rules.is_empty() {
+            return index;
+        }
 
-    /// Set the stylesheet for automatic state modifier application
-    ///
-    /// When a stylesheet is set, elements with IDs will automatically get
-    /// `:hover`, `:active`, `:focus`, `:disabled` styles applied based on
-    /// their current interaction state.
-    ///
-    /// # Example
-    ///
-    /// ```ignore
-    /// let css = r#"
-    ///     #button { background: blue; }
-    ///     #button:hover { opacity: 0.9; }
-    ///     #button:active { transform: scale(0.98); }
-    /// "#;
-    /// let stylesheet = Stylesheet::parse_with_errors(css).stylesheet;
-    /// tree.set_stylesheet(stylesheet);
-    /// ```
-    pub fn set_stylesheet(&mut self, stylesheet: Stylesheet) {
-        self.stylesheet = Some(Arc::new(stylesheet));
+        // Nodes declared as containers by base rules (name, type)
+        let mut containers: HashMap<LayoutNodeId, (Option<String>, ContainerType)> = HashMap::new();
+        let all_node_ids: Vec<LayoutNodeId> = self.render_nodes.keys().copied().collect();
+        for &node_id in &all_node_ids {
+            let mut name = None;
+            let mut container_type = None;
+            for style in self.matching_base_styles(stylesheet, node_id) {
+                if style.container_name.is_some() {
+                    name = style.container_name.clone();
+                }
+                if style.container_type.is_some() {
+                    container_type = style.container_type;
+                }
+            }
+            if name.is_some() || container_type.is_some() {
+                containers.insert(node_id, (name, container_type.unwrap_or_default()));
+            }
+        }
+
+        for (rule_index, rule) in rules.iter().enumerate() {
+            for &node_id in &all_node_ids {
+                if self
+                    .matching_base_styles(&rule.stylesheet, node_id)
+                    .is_empty()
+                {
+                    continue;
+                }
+                // Nearest ancestor that can answer the query
+                let container =
+                    self.element_registry
+                        .ancestors(node_id)
+                        .into_iter()
+                        .find(|ancestor| {
+                            containers.get(ancestor).is_some_and(|(name, ty)| {
+                                rule.query.accepts_container(name.as_deref(), *ty)
+                            })
+                        });
+                if let Some(container) = container {
+                    index.targets.push((rule_index, node_id, container));
+                }
+            }
+        }
+        index
     }
 
-    /// Set a shared stylesheet reference
-    pub fn set_stylesheet_arc(&mut self, stylesheet: Arc<Stylesheet>) {
-        // Also update the global stylesheet for form widget CSS override resolution
-        crate::css_parser::set_active_stylesheet(Arc::clone(&stylesheet));
-        self.stylesheet = Some(stylesheet);
+    /// Styles of active `@container` rules, in cascade order
+    fn container_query_styles(&self) -> Vec<(LayoutNodeId, crate::element_style::ElementStyle)> {
+        let Some(stylesheet) = &self.stylesheet else {
+            return Vec::new();
+        };
+        let rules = stylesheet.container_rules();
+        let mut styles = Vec::new();
+        for &(rule_index, node_id) in &self.container_matches {
+            let Some(rule) = rules.get(rule_index) else {
+                continue;
+            };
+            for style in self.matching_base_styles(&rule.stylesheet, node_id) {
+                styles.push((node_id, style.clone()));
+            }
+        }
+        styles
     }
 
-    /// Get the current stylesheet, if any
-    pub fn stylesheet(&self) -> Option<&Stylesheet> {
-        self.stylesheet.as_ref().map(|s| s.as_ref())
+    /// Non-state styles from `stylesheet` that apply to a node, lowest
+    /// specificity first (complex rules sorted by specificity, then `#id`)
+    fn matching_base_styles<'s>(
+        &self,
+        stylesheet: &'s Stylesheet,
+        node_id: LayoutNodeId,
+    ) -> Vec<&'s crate::element_style::ElementStyle> {
+        let empty_set = HashSet::new();
+        let mut rules: Vec<&(ComplexSelector, crate::element_style::ElementStyle)> = stylesheet
+            .complex_rules()
+            .iter()
+            .filter(|(selector, _)| !selector.has_state())
+            .filter(|(selector, _)| {
+                self.complex_selector_matches(selector, node_id, &empty_set, &empty_set, None)
+            })
+            .collect();
+        rules.sort_by_key(|(selector, _)| Self::selector_specificity(selector));
+
+        let mut styles: Vec<&crate::element_style::ElementStyle> =
+            rules.into_iter().map(|(_, style)| style).collect();
+        if let Some(id) = self.element_registry.get_id(node_id) {
+            if let Some(style) = stylesheet.get(&id) {
+                styles.push(style);
+            }
+        }
+        styles
     }
 
     /// Apply state-specific styles from the stylesheet to a node
@@ -4519,6 +5559,15 @@ impl RenderTree {
         if let Some(ref cp) = style.clip_path {
             props.clip_path = Some(cp.clone());
         }
+        if let Some(ref path) = style.offset_path {
+            props.offset_path = Some(path.clone());
+        }
+        if let Some(v) = style.offset_distance {
+            props.offset_distance = Some(v);
+        }
+        if let Some(v) = style.offset_rotate {
+            props.offset_rotate = Some(v);
+        }
         if let Some(filter) = style.filter {
             props.filter = Some(filter);
             // Convert blur filter to LayerEffect for GPU processing
@@ -4586,6 +5635,37 @@ impl RenderTree {
         if let Some(ls) = style.letter_spacing {
             props.letter_spacing = Some(ls);
         }
+        // Paragraph layout
+        if let Some(ws) = style.white_space {
+            props.white_space = Some(ws);
+        }
+        if let Some(to) = style.text_overflow {
+            props.text_overflow = Some(to);
+        }
+        if let Some(lc) = style.line_clamp {
+            props.line_clamp = Some(lc);
+        }
+        if let Some(h) = style.hyphens {
+            props.hyphens = Some(h);
+        }
+        if let Some(tj) = style.text_justify {
+            props.text_justify = Some(tj);
+        }
+        // OpenType features: font-variant-numeric first, so explicit
+        // font-feature-settings win for the same tag
+        if style.font_variant_numeric.is_some() || style.font_features.is_some() {
+            let features = style
+                .font_variant_numeric
+                .iter()
+                .chain(style.font_features.iter())
+                .flatten()
+                .copied()
+                .collect();
+            props.font_features = Some(features);
+        }
+        if let Some(fv) = &style.font_variations {
+            props.font_variations = Some(fv.clone());
+        }
         // SVG properties
         if let Some(fill) = style.fill {
             props.fill = Some([fill.r, fill.g, fill.b, fill.a]);
@@ -5060,6 +6140,11 @@ impl RenderTree {
     /// (width, height, padding, margin, gap, flex-direction, alignment, etc.)
     /// from the stylesheet to the corresponding taffy nodes.
     pub fn apply_stylesheet_layout_overrides(&mut self) {
+        self.apply_layout_overrides_to(None);
+    }
+
+    /// Apply stylesheet layout overrides to every node, or only the nodes in `only`
+    fn apply_layout_overrides_to(&mut self, only: Option<&HashSet<LayoutNodeId>>) {
         use crate::element_style::{
             SpacingRect, StyleAlign, StyleDisplay, StyleFlexDirection, StyleJustify, StyleOverflow,
             StylePosition,
@@ -5069,6 +6154,7 @@ impl RenderTree {
             Some(s) => s.clone(),
             None => return,
         };
+        let included = |node_id: &LayoutNodeId| only.map_or(true, |nodes| nodes.contains(node_id));
 
         // Collect (node_id, style) pairs that have layout overrides.
         // Apply complex rules (class/type selectors) FIRST — lower specificity.
@@ -5078,7 +6164,8 @@ impl RenderTree {
         // Complex rules: class, type, and combinator selectors
         let complex_rules = stylesheet.complex_rules();
         if !complex_rules.is_empty() {
-            let all_node_ids: Vec<LayoutNodeId> = self.render_nodes.keys().copied().collect();
+            let all_node_ids: Vec<LayoutNodeId> =
+                self.render_nodes.keys().copied().filter(included).collect();
             let empty_set = std::collections::HashSet::new();
 
             let mut base_rules: Vec<&(
@@ -5107,7 +6194,7 @@ impl RenderTree {
         // Simple ID rules: highest specificity, applied last
         let all_ids = self.element_registry.all_ids();
         for id in &all_ids {
-            if let Some(node_id) = self.element_registry.get(id) {
+            if let Some(node_id) = self.element_registry.get(id).filter(included) {
                 if let Some(style) = stylesheet.get(id) {
                     if style.has_layout_props() {
                         overrides.push((node_id, style.clone()));
@@ -5116,10 +6203,21 @@ impl RenderTree {
             }
         }
 
+        // Matching @container rules apply on top of the regular cascade
+        overrides.extend(
+            self.container_query_styles()
+                .into_iter()
+                .filter(|(node_id, style)| included(node_id) && style.has_layout_props()),
+        );
+
         for (node_id, es) in overrides {
             let Some(mut style) = self.layout_tree.get_style(node_id) else {
                 continue;
             };
+            self.unstyled_taffy_styles
+                .entry(node_id)
+                .or_insert_with(|| style.clone());
+            self.record_style_lengths(node_id, &es);
 
             // Sizing
             if let Some(w) = es.width {
@@ -5447,7 +6545,7 @@ impl RenderTree {
                         ElementState::Active => pressed,
                         ElementState::Focus => focused,
                         ElementState::Disabled => false, // TODO: track disabled state
-                        ElementState::Checked => false, // checked state managed by widget callbacks
+                        ElementState::Checked => self.element_registry.is_checked(node_id),
                     };
                     if !matches {
                         return false;
@@ -6477,6 +7575,7 @@ impl RenderTree {
         check_transition!(skew_x, "transform", default 0.0);
         check_transition!(skew_y, "transform", default 0.0);
         check_transition!(transform_origin, "transform-origin");
+        check_transition!(offset_distance, "offset-distance", default 0.0);
 
         if has_any && duration_ms > 0 {
             // If a transition already exists heading to the same target, let it continue
@@ -6489,7 +7588,7 @@ impl RenderTree {
                 }
             }
             let anim = MultiKeyframeAnimation::new(duration_ms)
-                .keyframe(0.0, from, easing)
+                .keyframe(0.0, from, easing.clone())
                 .keyframe(1.0, to, easing)
                 .delay(delay_ms)
                 .fill_mode(FillMode::Forwards);
@@ -6801,6 +7900,15 @@ impl RenderTree {
             props.font_size = Some(fs);
         }
 
+        // Variable font axes
+        if let Some(axes) = &anim_props.font_variations {
+            props.font_variations = Some(
+                axes.iter()
+                    .map(|&(tag, value)| blinc_core::FontVariation { tag, value })
+                    .collect(),
+            );
+        }
+
         // Corner radius
         if let Some([tl, tr, br, bl]) = anim_props.corner_radius {
             props.border_radius = blinc_core::CornerRadius {
@@ -6946,6 +8054,11 @@ impl RenderTree {
         if let Some(to) = anim_props.transform_origin {
             props.transform_origin = Some(to);
         }
+
+        // Motion path
+        if let Some(d) = anim_props.offset_distance {
+            props.offset_distance = Some(d);
+        }
     }
 
     /// Extract animatable properties from RenderProps into KeyframeProperties
@@ -7075,6 +8188,12 @@ impl RenderTree {
         // Font size
         kp.font_size = props.font_size;
 
+        // Variable font axes
+        kp.font_variations = props
+            .font_variations
+            .as_ref()
+            .map(|axes| axes.iter().map(|v| (v.tag, v.value)).collect());
+
         // Corner radius
         let cr = &props.border_radius;
         kp.corner_radius = Some([cr.top_left, cr.top_right, cr.bottom_right, cr.bottom_left]);
@@ -7136,6 +8255,9 @@ impl RenderTree {
         // Transform origin
         kp.transform_origin = props.transform_origin;
 
+        // Motion path
+        kp.offset_distance = props.offset_distance;
+
         kp
     }
 
@@ -7156,10 +8278,16 @@ impl RenderTree {
     /// border-radius, opacity, etc.) are missing. This method fixes that by
     /// iterating all registered elements and applying their base CSS styles.
     pub fn apply_stylesheet_base_styles(&mut self) {
+        self.apply_base_styles_to(None);
+    }
+
+    /// Apply base stylesheet styles to every node, or only the nodes in `only`
+    fn apply_base_styles_to(&mut self, only: Option<&HashSet<LayoutNodeId>>) {
         let stylesheet = match &self.stylesheet {
             Some(s) => s.clone(),
             None => return,
         };
+        let included = |node_id: &LayoutNodeId| only.map_or(true, |nodes| nodes.contains(node_id));
 
         // CSS specificity order: type(0,0,1) < class(0,1,0) < id(1,0,0)
         // Apply complex base rules FIRST (lower specificity: type, class selectors)
@@ -7167,7 +8295,8 @@ impl RenderTree {
         // Then apply simple ID rules LAST (highest specificity, always override).
         let complex_rules = stylesheet.complex_rules();
         if !complex_rules.is_empty() {
-            let all_node_ids: Vec<LayoutNodeId> = self.render_nodes.keys().copied().collect();
+            let all_node_ids: Vec<LayoutNodeId> =
+                self.render_nodes.keys().copied().filter(included).collect();
             let empty_set = std::collections::HashSet::new();
 
             // Collect non-state rules and sort by specificity (ascending)
@@ -7186,6 +8315,9 @@ impl RenderTree {
                         .complex_selector_matches(selector, node_id, &empty_set, &empty_set, None)
                     {
                         if let Some(render_node) = self.render_nodes.get_mut(&node_id) {
+                            self.unstyled_props
+                                .entry(node_id)
+                                .or_insert_with(|| render_node.props.clone());
                             Self::apply_element_style_to_props(&mut render_node.props, style);
                         }
                     }
@@ -7200,15 +8332,32 @@ impl RenderTree {
             .all_ids()
             .into_iter()
             .filter_map(|id| self.element_registry.get(&id).map(|node_id| (id, node_id)))
+            .filter(|(_, node_id)| included(node_id))
             .collect();
 
         for (element_id, node_id) in &registered_ids {
             if let Some(base_style) = stylesheet.get(element_id) {
                 if let Some(render_node) = self.render_nodes.get_mut(node_id) {
+                    self.unstyled_props
+                        .entry(*node_id)
+                        .or_insert_with(|| render_node.props.clone());
                     Self::apply_element_style_to_props(&mut render_node.props, base_style);
                 }
             }
         }
+
+        // Matching @container rules apply on top of the regular cascade
+        for (node_id, style) in self.container_query_styles() {
+            if !included(&node_id) {
+                continue;
+            }
+            if let Some(render_node) = self.render_nodes.get_mut(&node_id) {
+                self.unstyled_props
+                    .entry(node_id)
+                    .or_insert_with(|| render_node.props.clone());
+                Self::apply_element_style_to_props(&mut render_node.props, &style);
+            }
+        }
     }
 
     /// Apply stylesheet state styles based on EventRouter state
@@ -7333,9 +8482,14 @@ impl RenderTree {
 
         // 3. Add the new child to the parent
         self.layout_tree.add_child(parent_id, new_child_id);
+        self.element_registry
+            .register_parent(new_child_id, parent_id);
+        self.element_registry
+            .register_child_index(new_child_id, 0, 1);
 
         // 4. Collect render props for the new subtree
         self.collect_render_props(new_child, new_child_id);
+        self.container_index = None;
 
         new_child_id
     }
@@ -7532,7 +8686,7 @@ impl RenderTree {
         }
 
         // Push transform for this node's position
-        ctx.push_transform(Transform::translate(bounds.x, bounds.y));
+        ctx.push_transform(self.position_transform(node, &bounds));
 
         // Apply element-specific transform if present (static, set at build time)
         // Transforms are applied around the element's center (like CSS transform-origin: 50% 50%)
@@ -8108,6 +9262,9 @@ impl RenderTree {
                 1.0, // Start with full opacity at root
                 (0.0, 0.0),
             );
+
+            // Pass 4: Shared elements in flight, in the foreground above everything
+            self.render_shared_elements_in_flight(ctx, render_state);
             ctx.set_foreground_layer(false);
 
             // Pop the DPI scale transform
@@ -8187,10 +9344,12 @@ impl RenderTree {
         let binding_opacity = self.get_motion_opacity(node);
 
         // Calculate this node's motion opacity (combine motion values, bindings, and element opacity)
+        // Shared elements in flight draw with the transition's radius and opacity
+        let shared_style = self.shared_element_style(node);
         let node_motion_opacity = motion_values
             .and_then(|m| m.opacity)
             .unwrap_or_else(|| binding_opacity.unwrap_or(1.0))
-            * render_node.props.opacity;
+            * shared_style.map_or(render_node.props.opacity, |(_, opacity)| opacity);
 
         // Combine with inherited opacity from parent motion containers
         // This ensures children fade together with their parent motion container
@@ -8202,7 +9361,7 @@ impl RenderTree {
         }
 
         // Push position transform
-        ctx.push_transform(Transform::translate(bounds.x, bounds.y));
+        ctx.push_transform(self.position_transform(node, &bounds));
 
         // Apply motion translation
         if let Some(motion) = motion_values {
@@ -8366,7 +9525,7 @@ impl RenderTree {
         // Draw shadow BEFORE pushing clip (shadows extend beyond element bounds)
         // This must be done before the clip is applied so shadows aren't clipped
         let rect = Rect::new(0.0, 0.0, bounds.width, bounds.height);
-        let radius = render_node.props.border_radius;
+        let radius = shared_style.map_or(render_node.props.border_radius, |(radius, _)| radius);
         if effective_layer == target_layer {
             // Glass elements have shadows handled by the GPU glass system
             if !matches!(render_node.props.material, Some(Material::Glass(_))) {
@@ -8803,7 +9962,7 @@ impl RenderTree {
             );
 
             // Adjust corner radius for inset
-            let radius = render_node.props.border_radius;
+            let radius = shared_style.map_or(render_node.props.border_radius, |(radius, _)| radius);
             let max_border = left_inset.max(right_inset).max(top_inset).max(bottom_inset);
             let inset_radius = if radius.is_uniform() && radius.top_left > max_border {
                 CornerRadius::uniform((radius.top_left - max_border).max(0.0))
@@ -8846,6 +10005,10 @@ impl RenderTree {
             if is_3d_group && group_3d_children.contains(&child_id) {
                 continue;
             }
+            // Shared elements in flight are drawn in their own pass on top
+            if self.shared_elements.in_flight(child_id).is_some() {
+                continue;
+            }
 
             let child_render = self.render_nodes.get(&child_id);
             let child_is_fixed = child_render.map(|n| n.props.is_fixed).unwrap_or(false);
@@ -9111,7 +10274,7 @@ impl RenderTree {
         };
 
         // Always push transform for proper child positioning
-        ctx.push_transform(Transform::translate(bounds.x, bounds.y));
+        ctx.push_transform(self.position_transform(node, &bounds));
 
         // Apply element-specific transform if present
         // Transforms are applied around the element's center (like CSS transform-origin: 50% 50%)
@@ -9634,7 +10797,7 @@ impl RenderTree {
         };
 
         // Always push transform for proper child positioning
-        ctx.push_transform(Transform::translate(bounds.x, bounds.y));
+        ctx.push_transform(self.position_transform(node, &bounds));
 
         // Apply element-specific transform if present
         // Transforms are applied around the element's center (like CSS transform-origin: 50% 50%)
@@ -10537,6 +11700,38 @@ mod tests {
         assert!(tree.root().is_some());
     }
 
+    #[test]
+    fn test_full_size_root_follows_viewport() {
+        // The windowed app composes its root this way so resizes need no rebuild
+        let ui = div().w_full().h_full().child(div().w_full().h(10.0));
+        let mut tree = RenderTree::from_element(&ui);
+        let root = tree.root().unwrap();
+
+        tree.compute_layout(300.0, 200.0);
+        let bounds = tree.get_bounds(root).unwrap();
+        assert_eq!((bounds.width, bounds.height), (300.0, 200.0));
+
+        tree.compute_layout(500.0, 400.0);
+        let bounds = tree.get_bounds(root).unwrap();
+        assert_eq!((bounds.width, bounds.height), (500.0, 400.0));
+        let child = tree.layout_tree.children(root)[0];
+        assert_eq!(tree.get_bounds(child).unwrap().width, 500.0);
+    }
+
+    #[test]
+    fn test_lone_root_node_is_live() {
+        // No ID, no parent and no children: only the node set knows it exists
+        let ui = div().w(100.0).h(100.0);
+        let mut tree = RenderTree::from_element(&ui);
+        let root = tree.root().unwrap();
+        let handle: ElementHandle =
+            ElementHandle::from_node(root, Arc::clone(tree.element_registry()));
+        assert!(handle.exists());
+
+        tree.remove_subtree_nodes(root);
+        assert!(!handle.exists());
+    }
+
     #[test]
     fn test_compute_layout() {
         let ui = div()
@@ -10555,4 +11750,418 @@ mod tests {
         assert_eq!(bounds.width, 200.0);
         assert_eq!(bounds.height, 200.0);
     }
+
+    #[test]
+    fn test_scroll_into_view_and_anchor_tracking() {
+        use crate::selector::{ScrollBlock, ScrollOptions, ScrollRef};
+
+        let scroll_ref = ScrollRef::new();
+        let mut doc = div().flex_col().w_full();
+        for i in 0..10 {
+            doc = doc.child(
+                div()
+                    .flex_col()
+                    .child(div().id(format!("section-{i}")).w_full().h(20.0))
+                    .child(div().w_full().h(80.0)),
+            );
+        }
+        let ui = div().w(200.0).h(200.0).child(
+            crate::scroll::scroll()
+                .bind(&scroll_ref)
+                .w_full()
+                .h_full()
+                .child(doc),
+        );
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.compute_layout(200.0, 200.0);
+        scroll_ref.track_anchors((0..10).map(|i| format!("section-{i}")));
+        scroll_ref.track_view("section-6");
+
+        crate::selector::scroll_into_view(
+            "section-4",
+            ScrollOptions {
+                block: ScrollBlock::Start,
+                ..ScrollOptions::default()
+            },
+        );
+        assert!(tree.process_pending_scroll_refs());
+        assert_eq!(scroll_ref.scroll_y(), 400.0);
+        assert_eq!(scroll_ref.current_anchor().as_deref(), Some("section-4"));
+        assert_eq!(
+            scroll_ref.view_bounds("section-6"),
+            Some((0.0, 600.0, 200.0, 20.0))
+        );
+
+        // Nearest does not move an element that is already visible
+        scroll_ref.scroll_to("section-5");
+        tree.process_pending_scroll_refs();
+        assert_eq!(scroll_ref.scroll_y(), 400.0);
+    }
+
+    fn node_width(tree: &RenderTree, id: &str) -> f32 {
+        let node = tree.element_registry.get(id).unwrap();
+        tree.layout_tree.get_bounds(node, (0.0, 0.0)).unwrap().width
+    }
+
+    #[test]
+    fn test_media_context_change_restyles() {
+        let ui = div()
+            .w(300.0)
+            .h(300.0)
+            .child(div().id("box").w(20.0).h(10.0));
+        let css = "#box { width: 50px; } @media (min-width: 600px) { #box { width: 100px; } }";
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.set_media_context(MediaContext::new(400.0, 400.0));
+        tree.set_stylesheet(Stylesheet::parse(css).unwrap());
+        tree.apply_stylesheet_base_styles();
+        tree.apply_stylesheet_layout_overrides();
+        tree.compute_layout(300.0, 300.0);
+        assert_eq!(node_width(&tree, "box"), 50.0);
+
+        assert!(tree.set_media_context(MediaContext::new(800.0, 400.0)));
+        tree.compute_layout(300.0, 300.0);
+        assert_eq!(node_width(&tree, "box"), 100.0);
+
+        // Same query results: nothing to do
+        assert!(!tree.set_media_context(MediaContext::new(900.0, 400.0)));
+
+        assert!(tree.set_media_context(MediaContext::new(400.0, 400.0)));
+        tree.compute_layout(300.0, 300.0);
+        assert_eq!(node_width(&tree, "box"), 50.0);
+    }
+
+    #[test]
+    fn test_query_selector_all_document_order() {
+        let ui = div()
+            .child(
+                div()
+                    .id("inbox")
+                    .child(div().id("r1").class("row"))
+                    .child(div().class("row").child(div().class("row").id("nested")))
+                    .child(div().id("r3").class("row")),
+            )
+            .child(div().id("outside").class("row"));
+        let tree = RenderTree::from_element(&ui);
+
+        let rows = tree.query_selector_all("#inbox .row");
+        let ids: Vec<&str> = rows.iter().map(|h| h.id()).collect();
+        assert_eq!(ids, vec!["r1", "", "nested", "r3"]);
+        // Anonymous handles still resolve to their node
+        assert!(rows[1].exists());
+
+        let direct = tree.query_selector_all("#inbox > .row:last-child, #outside");
+        let ids: Vec<&str> = direct.iter().map(|h| h.id()).collect();
+        assert_eq!(ids, vec!["r3", "outside"]);
+
+        assert_eq!(tree.query_selector(".row").unwrap().id(), "r1");
+        assert!(tree.query_selector("#missing").is_none());
+        assert!(tree.query_selector_all("#inbox >").is_empty());
+    }
+
+    #[test]
+    fn test_query_selector_state_matching() {
+        let ui = div()
+            .id("inbox")
+            .child(div().id("a").class("row"))
+            .child(div().id("b").class("row"));
+        let tree = RenderTree::from_element(&ui);
+        let b = tree.query_by_id("b").unwrap();
+        let registry = tree.element_registry();
+        registry.register_checked(b, Arc::new(|| true));
+
+        let checked = tree.query_selector_all("#inbox .row:checked");
+        assert_eq!(checked.len(), 1);
+        assert_eq!(checked[0].id(), "b");
+        assert!(checked[0].is_checked());
+
+        // Interaction states only match when a router is supplied
+        assert!(tree.query_selector(".row:focus").is_none());
+        let mut router = crate::event_router::EventRouter::new();
+        router.set_focus(Some(tree.query_by_id("a").unwrap()));
+        let focused = tree.query_selector_with_state(".row:focus", &router);
+        assert_eq!(focused.unwrap().id(), "a");
+    }
+
+    #[test]
+    fn test_reload_stylesheet_restyles_in_place() {
+        let ui = div()
+            .w(300.0)
+            .h(300.0)
+            .child(div().id("box").w(20.0).h(10.0))
+            .child(div().id("other").w(30.0).h(10.0));
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.set_stylesheet(Stylesheet::parse("#box { width: 50px; }").unwrap());
+        tree.apply_stylesheet_base_styles();
+        tree.apply_stylesheet_layout_overrides();
+        tree.compute_layout(300.0, 300.0);
+        assert_eq!(node_width(&tree, "box"), 50.0);
+        let box_node = tree.element_registry().get("box");
+
+        let old = Stylesheet::parse("#box { width: 50px; }").unwrap();
+        let new = Stylesheet::parse("#other { width: 80px; }").unwrap();
+        tree.reload_stylesheet(Arc::new(new.clone()), &old.diff(&new));
+        tree.compute_layout(300.0, 300.0);
+        // Removed rule reverts to the element's own size, same node is kept
+        assert_eq!(node_width(&tree, "box"), 20.0);
+        assert_eq!(node_width(&tree, "other"), 80.0);
+        assert_eq!(tree.element_registry().get("box"), box_node);
+    }
+
+    #[test]
+    fn test_reload_stylesheet_restyles_only_changed_selectors() {
+        let ui = div()
+            .w(300.0)
+            .h(300.0)
+            .child(div().id("box").class("item").w(20.0).h(10.0))
+            .child(div().id("other").class("item").w(30.0).h(10.0));
+
+        let old = Stylesheet::parse("#box { opacity: 0.5; } .item { height: 20px; }").unwrap();
+        let mut tree = RenderTree::from_element(&ui);
+        tree.set_stylesheet(old.clone());
+        tree.apply_stylesheet_base_styles();
+        tree.apply_stylesheet_layout_overrides();
+        let box_node = tree.element_registry().get("box").unwrap();
+        let other_node = tree.element_registry().get("other").unwrap();
+        // Stand-in for runtime prop changes a full restyle would discard
+        tree.render_nodes.get_mut(&box_node).unwrap().props.opacity = 0.9;
+        tree.render_nodes
+            .get_mut(&other_node)
+            .unwrap()
+            .props
+            .opacity = 0.8;
+
+        let new = Stylesheet::parse(
+            "#box { opacity: 0.5; } .item { height: 20px; } #other { width: 80px; }",
+        )
+        .unwrap();
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, vec!["#other".to_string()]);
+        tree.reload_stylesheet(Arc::new(new.clone()), &diff);
+        tree.compute_layout(300.0, 300.0);
+        assert_eq!(node_width(&tree, "other"), 80.0);
+        assert_eq!(tree.render_nodes[&box_node].props.opacity, 0.9);
+        assert_eq!(tree.render_nodes[&other_node].props.opacity, 1.0);
+
+        // A changed class rule restyles every node it matches, before or after
+        let newer = Stylesheet::parse(
+            "#box { opacity: 0.5; } .item { height: 40px; } #other { width: 80px; }",
+        )
+        .unwrap();
+        tree.reload_stylesheet(Arc::new(newer.clone()), &new.diff(&newer));
+        tree.compute_layout(300.0, 300.0);
+        let height = |node| {
+            tree.layout_tree
+                .get_bounds(node, (0.0, 0.0))
+                .unwrap()
+                .height
+        };
+        assert_eq!(height(box_node), 40.0);
+        assert_eq!(height(other_node), 40.0);
+        assert_eq!(tree.render_nodes[&box_node].props.opacity, 0.5);
+    }
+
+    #[test]
+    fn test_container_query_applies_after_layout() {
+        let ui = div().w(500.0).h(300.0).child(
+            div()
+                .id("panel")
+                .w(400.0)
+                .h(100.0)
+                .child(div().id("item").w(20.0).h(10.0)),
+        );
+        let css = "#panel { container-type: inline-size; } \
+                   @container (min-width: 300px) { #item { width: 100px; } }";
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.set_stylesheet(Stylesheet::parse(css).unwrap());
+        tree.apply_stylesheet_base_styles();
+        tree.apply_stylesheet_layout_overrides();
+        tree.compute_layout(500.0, 300.0);
+        assert_eq!(node_width(&tree, "item"), 20.0);
+
+        assert!(tree.apply_container_queries());
+        tree.compute_layout(500.0, 300.0);
+        assert_eq!(node_width(&tree, "item"), 100.0);
+
+        // Stable once applied
+        assert!(!tree.apply_container_queries());
+    }
+
+    #[test]
+    fn test_container_query_follows_container_resize() {
+        let ui = div().w_full().h_full().child(
+            div()
+                .id("panel")
+                .w_full()
+                .h(100.0)
+                .child(div().id("item").w(20.0).h(10.0)),
+        );
+        let css = "#panel { container-type: inline-size; } \
+                   @container (min-width: 300px) { .wide { width: 100px; } #item { width: 100px; } }";
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.set_stylesheet(Stylesheet::parse(css).unwrap());
+        tree.apply_stylesheet_base_styles();
+        tree.apply_stylesheet_layout_overrides();
+        let mut layout = |tree: &mut RenderTree, width: f32| {
+            tree.compute_layout(width, 300.0);
+            if tree.apply_container_queries() {
+                tree.compute_layout(width, 300.0);
+            }
+        };
+
+        layout(&mut tree, 500.0);
+        assert_eq!(node_width(&tree, "item"), 100.0);
+        layout(&mut tree, 250.0);
+        assert_eq!(node_width(&tree, "item"), 20.0);
+        layout(&mut tree, 400.0);
+        assert_eq!(node_width(&tree, "item"), 100.0);
+
+        // Nodes added later are picked up once the tree changes
+        let panel = tree.query_by_id("panel").unwrap();
+        tree.rebuild_children(panel, &div().id("late").class("wide").w(20.0).h(10.0));
+        tree.apply_stylesheet_base_styles();
+        layout(&mut tree, 400.0);
+        assert_eq!(node_width(&tree, "late"), 100.0);
+    }
+
+    #[test]
+    fn test_contextual_lengths_resolve_at_layout() {
+        use crate::units::{pct, px, rem, vh, vw};
+
+        let ui = div()
+            .w(400.0)
+            .h(900.0)
+            .flex_col()
+            .child(div().id("header").width(vw(50.0)).height(rem(2.0)))
+            .child(
+                div()
+                    .id("sidebar")
+                    .width(pct(100.0) - px(100.0))
+                    .height(vh(100.0) - px(64.0)),
+            );
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.compute_layout(1000.0, 800.0);
+
+        let header = tree.element_registry.get("header").unwrap();
+        let bounds = tree.layout_tree.get_bounds(header, (0.0, 0.0)).unwrap();
+        assert_eq!((bounds.width, bounds.height), (500.0, 32.0));
+
+        let sidebar = tree.element_registry.get("sidebar").unwrap();
+        let bounds = tree.layout_tree.get_bounds(sidebar, (0.0, 0.0)).unwrap();
+        assert_eq!(bounds.height, 736.0);
+        // calc(100% - 100px) of the 400px parent, after the percentage pass
+        assert_eq!(bounds.width, 300.0);
+    }
+
+    #[test]
+    fn test_stylesheet_contextual_lengths() {
+        let ui = div()
+            .w(400.0)
+            .h(300.0)
+            .child(div().id("panel").w(10.0).h(10.0));
+        let css = "#panel { width: calc(50vw - 20px); height: 10vh; }";
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.set_stylesheet(Stylesheet::parse(css).unwrap());
+        tree.apply_stylesheet_base_styles();
+        tree.apply_stylesheet_layout_overrides();
+        tree.compute_layout(600.0, 500.0);
+        assert_eq!(node_width(&tree, "panel"), 280.0);
+
+        // Viewport units follow the viewport without restyling
+        tree.compute_layout(800.0, 500.0);
+        assert_eq!(node_width(&tree, "panel"), 380.0);
+    }
+
+    #[test]
+    fn test_em_font_size_is_stable_across_layouts() {
+        let ui = div().w(400.0).h(300.0).child(
+            div()
+                .id("page")
+                .child(div().id("title").child(div().id("label"))),
+        );
+        let css = "#page { font-size: 10px; } \
+                   #title { font-size: 2em; padding-left: 1em; } \
+                   #label { font-size: clamp(12px, 1.5em, 40px); }";
+
+        let mut tree = RenderTree::from_element(&ui);
+        tree.set_stylesheet(Stylesheet::parse(css).unwrap());
+        tree.apply_stylesheet_base_styles();
+        tree.apply_stylesheet_layout_overrides();
+
+        let font_size = |tree: &RenderTree, id: &str| {
+            let node = tree.element_registry.get(id).unwrap();
+            tree.render_nodes.get(&node).unwrap().props.font_size
+        };
+        for _ in 0..3 {
+            tree.compute_layout(400.0, 300.0);
+            // em in font-size refers to the parent, other em lengths to the
+            // element's own (resolved) font size
+            assert_eq!(font_size(&tree, "title"), Some(20.0));
+            assert_eq!(font_size(&tree, "label"), Some(30.0));
+            let title = tree.element_registry.get("title").unwrap();
+            let layout = tree.layout_tree.get_layout(title).unwrap();
+            assert_eq!(layout.padding.left, 20.0);
+        }
+    }
+
+    #[test]
+    fn test_shared_element_transition() {
+        use blinc_animation::ManualClock;
+        use std::time::Duration;
+
+        let clock = Arc::new(ManualClock::new());
+        let scheduler = Arc::new(Mutex::new(AnimationScheduler::with_clock(clock.clone())));
+        let thumbnail = div()
+            .w(400.0)
+            .h(400.0)
+            .flex_col()
+            .child(div().h(100.0))
+            .child(div().shared_id("card").w(40.0).h(40.0).rounded(8.0));
+        let detail = div()
+            .w(400.0)
+            .h(400.0)
+            .child(div().shared_id("card").w(400.0).h(200.0));
+
+        let mut tree = RenderTree::from_element(&thumbnail);
+        tree.set_animations(&scheduler);
+        tree.compute_layout(400.0, 400.0);
+        assert!(!tree.has_active_shared_transitions());
+
+        // The thumbnail is replaced by the detail card
+        assert_eq!(
+            tree.incremental_update(&detail),
+            UpdateResult::ChildrenChanged
+        );
+        tree.compute_layout(400.0, 400.0);
+        let card = tree.layout().children(tree.root().unwrap())[0];
+        assert!(tree.is_shared_element_in_flight(card));
+
+        // It starts over the thumbnail
+        let placement = tree.get_shared_element_transform(card).unwrap();
+        let corner = placement.transform_point(Point::new(400.0, 200.0));
+        assert!((corner.x - 40.0).abs() < 1e-3 && (corner.y - 140.0).abs() < 1e-3);
+        let Transform::Affine2D(position) = tree.position_transform(
+            card,
+            &tree.layout_tree.get_bounds(card, (0.0, 0.0)).unwrap(),
+        ) else {
+            panic!("expected an affine placement");
+        };
+        assert_eq!(
+            position.transform_point(Point::new(0.0, 0.0)),
+            Point::new(0.0, 100.0)
+        );
+
+        for _ in 0..200 {
+            clock.advance(Duration::from_millis(16));
+            scheduler.lock().unwrap().tick();
+        }
+        assert!(!tree.is_shared_element_in_flight(card));
+        assert!(tree.get_placement_transform(card, 400.0, 200.0).is_none());
+    }
 }
@@ -329,6 +329,16 @@ impl RichText {
         self.align(TextAlign::Right)
     }
 
+    /// Align text to the start edge of its direction (right for RTL text)
+    pub fn text_start(self) -> Self {
+        self.align(TextAlign::Start)
+    }
+
+    /// Align text to the end edge of its direction (left for RTL text)
+    pub fn text_end(self) -> Self {
+        self.align(TextAlign::End)
+    }
+
     /// Set vertical alignment
     pub fn v_align(mut self, v_align: TextVerticalAlign) -> Self {
         self.v_align = v_align;
@@ -0,0 +1,271 @@
+//! Scroll-linked timelines
+//!
+//! A [`ScrollTimeline`] takes its progress from a scroll container instead of
+//! the clock, like CSS scroll-driven animations:
+//!
+//! - [`ScrollTimeline::scroll`] follows the container's scroll offset
+//!   (CSS `scroll()`), optionally between two offsets.
+//! - [`ScrollTimeline::view`] follows an element's visibility as it moves
+//!   through the viewport (CSS `view()`), over a [`ViewRange`].
+//!
+//! Progress is read from the bound [`ScrollRef`], which the renderer updates
+//! every frame, so values are current whenever the UI is rebuilt or drawn.
+//!
+//! # Example
+//!
+//! ```ignore
+//! // Collapsing header: shrink from 200px to 64px over the first 136px of scroll
+//! let scroll_ref = ctx.use_scroll_ref("feed");
+//! let mut collapse = ScrollTimeline::scroll(&scroll_ref).offsets(0.0, 136.0);
+//! let height = collapse.add(0, 1000, 200.0, 64.0);
+//!
+//! div()
+//!     .child(header().h(collapse.get(height).unwrap_or(200.0)))
+//!     .child(scroll().bind(&scroll_ref).child(feed))
+//! ```
+
+use blinc_animation::{Easing, Timeline, TimelineEntryId};
+
+use crate::selector::ScrollRef;
+use crate::units::Axis;
+
+/// Which part of an element's journey through the viewport maps to 0..1
+///
+/// Mirrors the CSS `animation-range` names for view timelines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ViewRange {
+    /// From the element starting to enter until it has fully left
+    #[default]
+    Cover,
+    /// While the element is fully inside the viewport (or fully covers it)
+    Contain,
+    /// From the element starting to enter until it is fully inside
+    Entry,
+    /// From the element starting to leave until it has fully left
+    Exit,
+}
+
+impl ViewRange {
+    /// Progress for an element at `start` with extent `size`, seen through a
+    /// viewport of extent `viewport` scrolled to `scroll`
+    ///
+    /// All positions are along the same axis, in content coordinates.
+    pub fn progress(self, start: f32, size: f32, scroll: f32, viewport: f32) -> f32 {
+        let end = start + size;
+        // Scroll offsets at which the element starts entering, is fully in
+        // (or fully covering), starts leaving and has fully left
+        let entering = start - viewport;
+        let contained = (end - viewport).min(start);
+        let leaving = (end - viewport).max(start);
+        let left = end;
+
+        let (from, to) = match self {
+            ViewRange::Cover => (entering, left),
+            ViewRange::Contain => (contained, leaving),
+            ViewRange::Entry => (entering, contained),
+            ViewRange::Exit => (leaving, left),
+        };
+        if to <= from {
+            return if scroll > to { 1.0 } else { 0.0 };
+        }
+        ((scroll - from) / (to - from)).clamp(0.0, 1.0)
+    }
+}
+
+/// What a scroll timeline measures
+#[derive(Clone, Debug)]
+enum ScrollSource {
+    /// The scroll offset, between two offsets (`None` for the whole range)
+    Scroll { offsets: Option<(f32, f32)> },
+    /// A tracked element's position in the viewport
+    View {
+        element_id: String,
+        range: ViewRange,
+    },
+}
+
+/// A timeline whose progress is driven by a scroll container
+///
+/// Entries are added as on a [`Timeline`]; offsets and durations only set
+/// where each entry sits along the scroll range.
+pub struct ScrollTimeline {
+    scroll_ref: ScrollRef,
+    source: ScrollSource,
+    axis: Axis,
+    timeline: Timeline,
+}
+
+impl ScrollTimeline {
+    /// Progress from the top (0) to the bottom (1) of the scroll range
+    pub fn scroll(scroll_ref: &ScrollRef) -> Self {
+        Self {
+            scroll_ref: scroll_ref.clone(),
+            source: ScrollSource::Scroll { offsets: None },
+            axis: Axis::Vertical,
+            timeline: Timeline::new(),
+        }
+    }
+
+    /// Progress of the element `element_id` through the viewport
+    ///
+    /// The element must be a descendant of the scroll container bound to
+    /// `scroll_ref`; it is tracked from now on.
+    pub fn view(scroll_ref: &ScrollRef, element_id: impl Into<String>) -> Self {
+        let element_id = element_id.into();
+        scroll_ref.track_view(element_id.clone());
+        Self {
+            scroll_ref: scroll_ref.clone(),
+            source: ScrollSource::View {
+                element_id,
+                range: ViewRange::default(),
+            },
+            axis: Axis::Vertical,
+            timeline: Timeline::new(),
+        }
+    }
+
+    /// Scroll axis to follow (default vertical)
+    pub fn axis(mut self, axis: Axis) -> Self {
+        self.axis = axis;
+        self
+    }
+
+    /// Map progress 0..1 to scroll offsets `start..end` (scroll timelines)
+    pub fn offsets(mut self, start: f32, end: f32) -> Self {
+        if let ScrollSource::Scroll { offsets } = &mut self.source {
+            *offsets = Some((start, end));
+        }
+        self
+    }
+
+    /// Part of the element's journey to follow (view timelines)
+    pub fn range(mut self, range: ViewRange) -> Self {
+        if let ScrollSource::View { range: current, .. } = &mut self.source {
+            *current = range;
+        }
+        self
+    }
+
+    /// Add an animation to the timeline at a given offset
+    pub fn add(
+        &mut self,
+        offset_ms: i32,
+        duration_ms: u32,
+        start_value: f32,
+        end_value: f32,
+    ) -> TimelineEntryId {
+        self.timeline
+            .add(offset_ms, duration_ms, start_value, end_value)
+    }
+
+    /// Add an animation with a specific easing function
+    pub fn add_with_easing(
+        &mut self,
+        offset_ms: i32,
+        duration_ms: u32,
+        start_value: f32,
+        end_value: f32,
+        easing: Easing,
+    ) -> TimelineEntryId {
+        self.timeline
+            .add_with_easing(offset_ms, duration_ms, start_value, end_value, easing)
+    }
+
+    /// Current progress (0.0 to 1.0)
+    ///
+    /// 0 until the container has been laid out (or the element found).
+    pub fn progress(&self) -> f32 {
+        let pick = |(x, y): (f32, f32)| match self.axis {
+            Axis::Horizontal => x,
+            Axis::Vertical => y,
+        };
+        let scroll = pick(self.scroll_ref.offset());
+
+        match &self.source {
+            ScrollSource::Scroll { offsets } => {
+                let (start, end) = match offsets {
+                    Some(offsets) => *offsets,
+                    None => match self.scroll_ref.max_scroll() {
+                        Some(max) => (0.0, pick(max)),
+                        None => return 0.0,
+                    },
+                };
+                if end == start {
+                    return if scroll > end { 1.0 } else { 0.0 };
+                }
+                ((scroll - start) / (end - start)).clamp(0.0, 1.0)
+            }
+            ScrollSource::View { element_id, range } => {
+                let (Some((x, y, width, height)), Some(viewport)) = (
+                    self.scroll_ref.view_bounds(element_id),
+                    self.scroll_ref.viewport_size(),
+                ) else {
+                    return 0.0;
+                };
+                let (start, size) = match self.axis {
+                    Axis::Horizontal => (x, width),
+                    Axis::Vertical => (y, height),
+                };
+                range.progress(start, size, scroll, pick(viewport))
+            }
+        }
+    }
+
+    /// Get the current value for a timeline entry
+    pub fn get(&self, entry_id: TimelineEntryId) -> Option<f32> {
+        self.timeline.value_at(entry_id, self.progress())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_ranges() {
+        // 100px element at 500, seen through a 400px viewport
+        let at = |range: ViewRange, scroll| range.progress(500.0, 100.0, scroll, 400.0);
+
+        assert_eq!(at(ViewRange::Cover, 100.0), 0.0);
+        assert_eq!(at(ViewRange::Cover, 350.0), 0.5);
+        assert_eq!(at(ViewRange::Cover, 600.0), 1.0);
+
+        assert_eq!(at(ViewRange::Entry, 150.0), 0.5);
+        assert_eq!(at(ViewRange::Entry, 200.0), 1.0);
+        assert_eq!(at(ViewRange::Contain, 350.0), 0.5);
+        assert_eq!(at(ViewRange::Exit, 500.0), 0.0);
+        assert_eq!(at(ViewRange::Exit, 550.0), 0.5);
+
+        // An element taller than the viewport covers it while "contained"
+        assert_eq!(ViewRange::Contain.progress(0.0, 800.0, 200.0, 400.0), 0.5);
+    }
+
+    #[test]
+    fn test_scroll_timeline_progress() {
+        let scroll_ref = ScrollRef::new();
+        let mut timeline = ScrollTimeline::scroll(&scroll_ref);
+        let opacity = timeline.add(0, 1000, 1.0, 0.0);
+        assert_eq!(timeline.progress(), 0.0);
+
+        scroll_ref.update_state((0.0, 300.0), (500.0, 1000.0), (500.0, 400.0));
+        assert_eq!(timeline.progress(), 0.5);
+        assert_eq!(timeline.get(opacity), Some(0.5));
+
+        let header = ScrollTimeline::scroll(&scroll_ref).offsets(0.0, 100.0);
+        assert_eq!(header.progress(), 1.0);
+    }
+
+    #[test]
+    fn test_view_timeline_progress() {
+        let scroll_ref = ScrollRef::new();
+        let mut reveal = ScrollTimeline::view(&scroll_ref, "card").range(ViewRange::Entry);
+        let slide = reveal.add(0, 1000, 40.0, 0.0);
+        assert_eq!(scroll_ref.views(), ["card"]);
+        assert_eq!(reveal.get(slide), Some(40.0));
+
+        scroll_ref.set_view_bounds("card", Some((0.0, 500.0, 300.0, 100.0)));
+        scroll_ref.update_state((0.0, 150.0), (300.0, 1000.0), (300.0, 400.0));
+        assert_eq!(reveal.progress(), 0.5);
+        assert_eq!(reveal.get(slide), Some(20.0));
+    }
+}
@@ -6,6 +6,7 @@ use blinc_core::context_state::MotionAnimationState;
 use blinc_core::BlincContextState;
 
 use crate::element::{ElementBounds, RenderProps};
+use crate::event_router::{InputModifiers, MouseButton};
 use crate::tree::LayoutNodeId;
 
 use super::registry::{ElementRegistry, OnReadyCallback};
@@ -43,22 +44,55 @@ impl<T> ElementHandle<T> {
         }
     }
 
+    /// Create a handle for a specific node, such as a selector query result
+    ///
+    /// Nodes without a string ID get an anonymous handle that is tied to the
+    /// node itself: it stops existing when the node is removed, and ID-keyed
+    /// features (bounds cache, focus, `on_ready`) are unavailable.
+    pub fn from_node(node_id: LayoutNodeId, registry: Arc<ElementRegistry>) -> Self {
+        Self {
+            string_id: registry.get_id(node_id).unwrap_or_default(),
+            node_id,
+            registry,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Resolve the node currently backing this handle
+    fn live_node(&self) -> Option<LayoutNodeId> {
+        if self.string_id.is_empty() {
+            self.registry
+                .contains_node(self.node_id)
+                .then_some(self.node_id)
+        } else {
+            self.registry.get(&self.string_id)
+        }
+    }
+
     /// Get the underlying layout node ID
     ///
     /// Returns a default ID if the element doesn't exist yet.
     pub fn node_id(&self) -> LayoutNodeId {
         // Refresh from registry in case element was created after handle
-        self.registry.get(&self.string_id).unwrap_or(self.node_id)
+        self.live_node().unwrap_or(self.node_id)
     }
 
     /// Get the string ID of this element
+    ///
+    /// Empty for anonymous handles created from id-less nodes.
     pub fn id(&self) -> &str {
         &self.string_id
     }
 
     /// Check if the element currently exists in the tree
     pub fn exists(&self) -> bool {
-        self.registry.get(&self.string_id).is_some()
+        self.live_node().is_some()
+    }
+
+    /// Check if the element is a toggle widget that is currently checked
+    pub fn is_checked(&self) -> bool {
+        self.live_node()
+            .is_some_and(|node_id| self.registry.is_checked(node_id))
     }
 
     // =========================================================================
@@ -135,11 +169,10 @@ impl<T> ElementHandle<T> {
     }
 
     /// Scroll this element into view with custom options
-    pub fn scroll_into_view_with(&self, _options: ScrollOptions) {
-        // Use BlincContextState callback to scroll the element
-        if let Some(ctx) = BlincContextState::try_get() {
-            ctx.scroll_element_into_view(&self.string_id);
-        }
+    ///
+    /// The nearest enclosing scroll container is scrolled on the next frame.
+    pub fn scroll_into_view_with(&self, options: ScrollOptions) {
+        super::scroll_into_view(&self.string_id, options);
     }
 
     /// Focus this element
@@ -204,7 +237,7 @@ impl<T> ElementHandle<T> {
     /// Use this for more efficient updates when you know exactly what the
     /// new children should be.
     pub fn mark_dirty_subtree(&self, new_children: crate::div::Div) {
-        if let Some(node_id) = self.registry.get(&self.string_id) {
+        if let Some(node_id) = self.live_node() {
             crate::stateful::queue_subtree_rebuild(node_id, new_children);
         }
     }
@@ -226,7 +259,7 @@ impl<T> ElementHandle<T> {
     /// );
     /// ```
     pub fn mark_visual_dirty(&self, props: RenderProps) {
-        if let Some(node_id) = self.registry.get(&self.string_id) {
+        if let Some(node_id) = self.live_node() {
             crate::stateful::queue_prop_update(node_id, props);
         }
     }
@@ -235,9 +268,9 @@ impl<T> ElementHandle<T> {
     // Event Simulation
     // =========================================================================
 
-    /// Simulate a click event on this element
+    /// Simulate a primary-button click at the center of this element
     pub fn click(&self) {
-        self.dispatch_event(ElementEvent::Click { x: 0.0, y: 0.0 });
+        self.dispatch_event(ElementEvent::Activate);
     }
 
     /// Simulate a click at specific coordinates within the element
@@ -254,10 +287,63 @@ impl<T> ElementHandle<T> {
         }
     }
 
-    /// Dispatch a custom event to this element
-    pub fn dispatch_event(&self, _event: ElementEvent) {
-        // TODO: Wire up to EventRouter/HandlerRegistry
-        // This needs access to the event dispatch system
+    /// Simulate pressing and releasing a key while this element is focused
+    pub fn press_key(&self, key: u32) {
+        self.press_key_with_modifiers(key, InputModifiers::default());
+    }
+
+    /// Simulate pressing and releasing a key with modifiers held
+    ///
+    /// Key codes follow the platform mapping used by the event loop
+    /// (8 = Backspace, 13 = Enter, 37..=40 = arrows, ...).
+    pub fn press_key_with_modifiers(&self, key: u32, modifiers: InputModifiers) {
+        self.dispatch_event(ElementEvent::KeyDown { key, modifiers });
+        self.dispatch_event(ElementEvent::KeyUp { key, modifiers });
+    }
+
+    /// Simulate typing text into this element, one character at a time
+    ///
+    /// The element is focused first (text inputs and text areas take focus
+    /// from the router's FOCUS event), so this works for fields that have
+    /// not been clicked.
+    pub fn type_text(&self, text: &str) {
+        self.dispatch_event(ElementEvent::Focus);
+        for ch in text.chars() {
+            self.dispatch_event(ElementEvent::TextInput(ch));
+        }
+    }
+
+    /// Simulate a scroll wheel gesture over this element
+    pub fn scroll_by(&self, delta_x: f32, delta_y: f32) {
+        self.dispatch_event(ElementEvent::Scroll { delta_x, delta_y });
+    }
+
+    /// Simulate dragging this element onto another element
+    ///
+    /// Presses at this element's center, moves to the center of `target`
+    /// and releases there, emitting the same DRAG / DRAG_END sequence as a
+    /// real pointer drag.
+    pub fn drag_to<U>(&self, target: &ElementHandle<U>) {
+        if let Some(target) = target.live_node() {
+            self.dispatch_event(ElementEvent::DragTo { target });
+        }
+    }
+
+    /// Dispatch a synthetic event to this element
+    ///
+    /// The event is queued and routed through the `EventRouter` on the next
+    /// frame, so bubbling, `Stateful` transitions and hover/press visuals
+    /// behave exactly as they do for real input. See
+    /// [`EventRouter::dispatch_synthetic`](crate::event_router::EventRouter::dispatch_synthetic).
+    pub fn dispatch_event(&self, event: ElementEvent) {
+        match self.live_node() {
+            Some(node_id) => crate::event_router::queue_synthetic_event(node_id, event),
+            None => tracing::debug!(
+                "dispatch_event: element '{}' is not in the tree, dropping {:?}",
+                self.string_id,
+                event
+            ),
+        }
     }
 
     // =========================================================================
@@ -297,10 +383,20 @@ impl<T> ElementHandle<T> {
 }
 
 /// Events that can be programmatically dispatched to elements
+///
+/// Coordinates are local to the element's bounds.
 #[derive(Debug, Clone)]
 pub enum ElementEvent {
+    /// Primary-button click at the element's center
+    Activate,
     /// Mouse click at local coordinates
     Click { x: f32, y: f32 },
+    /// Pointer moved to local coordinates
+    PointerMove { x: f32, y: f32 },
+    /// Pointer button pressed at local coordinates
+    PointerDown { x: f32, y: f32, button: MouseButton },
+    /// Pointer button released at local coordinates
+    PointerUp { x: f32, y: f32, button: MouseButton },
     /// Mouse entered element bounds
     MouseEnter,
     /// Mouse left element bounds
@@ -310,10 +406,15 @@ pub enum ElementEvent {
     /// Element lost focus
     Blur,
     /// Key pressed while focused
-    KeyDown {
-        key: u32,      // Key code
-        modifiers: u8, // Modifier flags
-    },
+    KeyDown { key: u32, modifiers: InputModifiers },
+    /// Key released while focused
+    KeyUp { key: u32, modifiers: InputModifiers },
+    /// Character typed while focused
+    TextInput(char),
+    /// Scroll wheel gesture over the element's center
+    Scroll { delta_x: f32, delta_y: f32 },
+    /// Press on this element, drag to the center of `target` and release
+    DragTo { target: LayoutNodeId },
     /// Custom user-defined event
     Custom(u32),
 }
@@ -373,6 +474,26 @@ mod tests {
         // Note: In real usage with distinct IDs this would work properly
     }
 
+    #[test]
+    fn test_dispatch_event_queues_for_live_element() {
+        let _queue = crate::event_router::SYNTHETIC_QUEUE_TEST_LOCK
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let registry = Arc::new(ElementRegistry::new());
+        registry.register("submit", LayoutNodeId::default());
+
+        let missing: ElementHandle<()> = ElementHandle::new("missing", registry.clone());
+        missing.click();
+
+        let handle: ElementHandle<()> = ElementHandle::new("submit", registry);
+        handle.press_key(13);
+
+        let queued = crate::event_router::take_pending_synthetic_events();
+        assert_eq!(queued.len(), 2);
+        assert!(matches!(queued[0].1, ElementEvent::KeyDown { key: 13, .. }));
+        assert!(matches!(queued[1].1, ElementEvent::KeyUp { key: 13, .. }));
+    }
+
     // =========================================================================
     // On-Ready Callback Tests
     // =========================================================================
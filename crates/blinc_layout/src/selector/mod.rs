@@ -7,6 +7,7 @@
 //! - `ElementHandle` - Query result with bounds, events, signals, state access
 //! - `ScrollOptions` - Configuration for scroll-into-view behavior
 //! - `query()` - Global function to query elements from event handlers
+//! - `RenderTree::query_selector_all()` - CSS selector queries against the live tree
 //!
 //! # Example
 //!
@@ -37,12 +38,12 @@ mod handle;
 mod registry;
 mod scroll_ref;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use blinc_core::BlincContextState;
 
 pub use handle::{ElementEvent, ElementHandle, MotionHandle};
-pub use registry::ElementRegistry;
+pub use registry::{CheckedProbe, ElementRegistry};
 
 /// Shared element registry for thread-safe access
 pub type SharedElementRegistry = Arc<ElementRegistry>;
@@ -73,6 +74,37 @@ pub fn query(id: &str) -> Option<ElementHandle<()>> {
     Some(ElementHandle::new(id, registry))
 }
 
+/// Scroll-into-view requests waiting for a render tree to apply them
+static SCROLL_INTO_VIEW_REQUESTS: Mutex<Vec<(String, ScrollOptions)>> = Mutex::new(Vec::new());
+
+/// Scroll the element with `id` into view within its nearest scroll container
+///
+/// The request is applied by the render tree on the next frame, so it can be
+/// made from event handlers and for elements that are not laid out yet.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use blinc_layout::selector::{scroll_into_view, ScrollBlock, ScrollBehavior, ScrollOptions};
+///
+/// scroll_into_view("installation", ScrollOptions {
+///     behavior: ScrollBehavior::Smooth,
+///     block: ScrollBlock::Start,
+///     ..Default::default()
+/// });
+/// ```
+pub fn scroll_into_view(id: &str, options: ScrollOptions) {
+    SCROLL_INTO_VIEW_REQUESTS
+        .lock()
+        .unwrap()
+        .push((id.to_string(), options));
+}
+
+/// Take all pending scroll-into-view requests
+pub(crate) fn take_scroll_into_view_requests() -> Vec<(String, ScrollOptions)> {
+    std::mem::take(&mut *SCROLL_INTO_VIEW_REQUESTS.lock().unwrap())
+}
+
 /// Query a motion animation by its stable key
 ///
 /// Returns a `MotionHandle` that can be used to check the animation state.
@@ -146,6 +178,27 @@ pub enum ScrollBlock {
     Nearest,
 }
 
+impl ScrollBlock {
+    /// Scroll position that aligns the span `start..start + size` within a
+    /// viewport currently scrolled to `current`
+    pub(crate) fn scroll_position(self, start: f32, size: f32, viewport: f32, current: f32) -> f32 {
+        match self {
+            ScrollBlock::Start => start,
+            ScrollBlock::Center => start + size / 2.0 - viewport / 2.0,
+            ScrollBlock::End => start + size - viewport,
+            ScrollBlock::Nearest => {
+                if start < current || size > viewport {
+                    start
+                } else if start + size > current + viewport {
+                    start + size - viewport
+                } else {
+                    current
+                }
+            }
+        }
+    }
+}
+
 /// Horizontal scroll alignment
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ScrollInline {
@@ -159,3 +212,17 @@ pub enum ScrollInline {
     #[default]
     Nearest,
 }
+
+impl ScrollInline {
+    /// Scroll position that aligns the span `start..start + size` within a
+    /// viewport currently scrolled to `current`
+    pub(crate) fn scroll_position(self, start: f32, size: f32, viewport: f32, current: f32) -> f32 {
+        let block = match self {
+            ScrollInline::Start => ScrollBlock::Start,
+            ScrollInline::Center => ScrollBlock::Center,
+            ScrollInline::End => ScrollBlock::End,
+            ScrollInline::Nearest => ScrollBlock::Nearest,
+        };
+        block.scroll_position(start, size, viewport, current)
+    }
+}
@@ -1,6 +1,6 @@
 //! Element registry for O(1) ID-based lookups
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex, RwLock};
 
 use blinc_core::Bounds;
@@ -11,6 +11,9 @@ use crate::tree::LayoutNodeId;
 /// Callback type for on_ready notifications registered via query API
 pub type OnReadyCallback = Arc<dyn Fn(ElementBounds) + Send + Sync>;
 
+/// Live probe for the `:checked` state of a toggle widget
+pub type CheckedProbe = Arc<dyn Fn() -> bool + Send + Sync>;
+
 /// Registry mapping string IDs to layout node IDs
 ///
 /// This provides O(1) lookup of elements by their string ID.
@@ -41,6 +44,10 @@ pub struct ElementRegistry {
     children: RwLock<HashMap<LayoutNodeId, Vec<LayoutNodeId>>>,
     /// Semantic element type for CSS type selector matching (e.g., "button", "a", "ul")
     element_types: RwLock<HashMap<LayoutNodeId, String>>,
+    /// Checked-state probes for toggle widgets (for :checked selector matching)
+    checked: RwLock<HashMap<LayoutNodeId, CheckedProbe>>,
+    /// Every node currently in the tree, with or without an ID or relations
+    nodes: RwLock<HashSet<LayoutNodeId>>,
 }
 
 impl std::fmt::Debug for ElementRegistry {
@@ -81,6 +88,8 @@ impl ElementRegistry {
             sibling_counts: RwLock::new(HashMap::new()),
             children: RwLock::new(HashMap::new()),
             element_types: RwLock::new(HashMap::new()),
+            checked: RwLock::new(HashMap::new()),
+            nodes: RwLock::new(HashSet::new()),
         }
     }
 
@@ -144,6 +153,24 @@ impl ElementRegistry {
         self.element_types.read().ok()?.get(&node_id).cloned()
     }
 
+    /// Register a probe reporting whether a toggle widget is checked
+    pub fn register_checked(&self, node_id: LayoutNodeId, probe: CheckedProbe) {
+        if let Ok(mut checked) = self.checked.write() {
+            checked.insert(node_id, probe);
+        }
+    }
+
+    /// Check if a node is a toggle widget that is currently checked
+    pub fn is_checked(&self, node_id: LayoutNodeId) -> bool {
+        let probe = self
+            .checked
+            .read()
+            .ok()
+            .and_then(|checked| checked.get(&node_id).cloned());
+        // Call outside the lock: probes read reactive state
+        probe.is_some_and(|probe| probe())
+    }
+
     /// Register a child's index within its parent and sibling count
     pub fn register_child_index(&self, child: LayoutNodeId, index: usize, total_siblings: usize) {
         if let Ok(mut indices) = self.child_indices.write() {
@@ -154,6 +181,24 @@ impl ElementRegistry {
         }
     }
 
+    /// Record that a node is part of the tree
+    ///
+    /// Called by RenderTree for every node it builds, so that anonymous
+    /// handles can tell live nodes from stale ones.
+    pub fn register_node(&self, node_id: LayoutNodeId) {
+        if let Ok(mut nodes) = self.nodes.write() {
+            nodes.insert(node_id);
+        }
+    }
+
+    /// Check if a node is part of the registered tree
+    pub fn contains_node(&self, node_id: LayoutNodeId) -> bool {
+        self.nodes
+            .read()
+            .ok()
+            .is_some_and(|nodes| nodes.contains(&node_id))
+    }
+
     /// Check if a node has a specific CSS class
     pub fn has_class(&self, node_id: LayoutNodeId, class: &str) -> bool {
         self.classes
@@ -293,6 +338,12 @@ impl ElementRegistry {
         if let Ok(mut types) = self.element_types.write() {
             types.clear();
         }
+        if let Ok(mut checked) = self.checked.write() {
+            checked.clear();
+        }
+        if let Ok(mut nodes) = self.nodes.write() {
+            nodes.clear();
+        }
         // Note: bounds_cache is NOT cleared here - it's cleared separately
         // via clear_bounds() when layout is recomputed
     }
@@ -345,6 +396,14 @@ impl ElementRegistry {
         if let Ok(mut parents) = self.parents.write() {
             parents.remove(&node_id);
         }
+
+        if let Ok(mut checked) = self.checked.write() {
+            checked.remove(&node_id);
+        }
+
+        if let Ok(mut nodes) = self.nodes.write() {
+            nodes.remove(&node_id);
+        }
     }
 
     /// Get all registered IDs (for debugging)
@@ -1,5 +1,6 @@
 //! ScrollRef - Reference for programmatic scroll control
 
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex, Weak};
 
 use blinc_core::reactive::SignalId;
@@ -32,6 +33,14 @@ pub struct ScrollRefInner {
     pending_scroll: Option<PendingScroll>,
     /// Whether the scroll state has been modified
     dirty: bool,
+    /// Element IDs whose position is tracked, in document order
+    anchors: Vec<String>,
+    /// Last tracked element scrolled to the top of the viewport
+    current_anchor: Option<String>,
+    /// Element IDs whose bounds within the content are tracked
+    views: Vec<String>,
+    /// Bounds (x, y, width, height) of tracked views within the content
+    view_bounds: HashMap<String, (f32, f32, f32, f32)>,
 }
 
 /// A pending scroll operation to be executed by the renderer
@@ -195,6 +204,41 @@ impl ScrollRef {
         self.inner.lock().ok()?.pending_scroll.take()
     }
 
+    /// Tracked anchor IDs (called by renderer each frame)
+    pub(crate) fn anchors(&self) -> Vec<String> {
+        self.inner
+            .lock()
+            .ok()
+            .map(|inner| inner.anchors.clone())
+            .unwrap_or_default()
+    }
+
+    /// Update the current anchor from renderer
+    pub(crate) fn set_current_anchor(&self, anchor: Option<String>) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.current_anchor = anchor;
+        }
+    }
+
+    /// Tracked view element IDs (called by renderer each frame)
+    pub(crate) fn views(&self) -> Vec<String> {
+        self.inner
+            .lock()
+            .ok()
+            .map(|inner| inner.views.clone())
+            .unwrap_or_default()
+    }
+
+    /// Update a tracked view's bounds from renderer
+    pub(crate) fn set_view_bounds(&self, element_id: &str, bounds: Option<(f32, f32, f32, f32)>) {
+        if let Ok(mut inner) = self.inner.lock() {
+            match bounds {
+                Some(bounds) => inner.view_bounds.insert(element_id.to_string(), bounds),
+                None => inner.view_bounds.remove(element_id),
+            };
+        }
+    }
+
     /// Check and clear dirty flag
     pub(crate) fn take_dirty(&self) -> bool {
         if let Ok(mut inner) = self.inner.lock() {
@@ -349,6 +393,46 @@ impl ScrollRef {
         }
     }
 
+    /// Track the position of elements in this container, in document order
+    ///
+    /// After each frame, [`current_anchor`](Self::current_anchor) is the last
+    /// of them that has scrolled into the top fifth of the viewport. Use
+    /// this to highlight the current section in a table of contents.
+    pub fn track_anchors<I, S>(&self, ids: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.anchors = ids.into_iter().map(Into::into).collect();
+            inner.current_anchor = None;
+        }
+    }
+
+    /// The tracked element currently at the top of the viewport
+    pub fn current_anchor(&self) -> Option<String> {
+        self.inner.lock().ok()?.current_anchor.clone()
+    }
+
+    /// Track the bounds of an element in this container
+    ///
+    /// After each frame, [`view_bounds`](Self::view_bounds) reports where the
+    /// element sits within the scrolled content. Used by view-linked
+    /// [`ScrollTimeline`](crate::scroll_timeline::ScrollTimeline)s.
+    pub fn track_view(&self, element_id: impl Into<String>) {
+        let element_id = element_id.into();
+        if let Ok(mut inner) = self.inner.lock() {
+            if !inner.views.contains(&element_id) {
+                inner.views.push(element_id);
+            }
+        }
+    }
+
+    /// Bounds (x, y, width, height) of a tracked element within the content
+    pub fn view_bounds(&self, element_id: &str) -> Option<(f32, f32, f32, f32)> {
+        self.inner.lock().ok()?.view_bounds.get(element_id).copied()
+    }
+
     /// Get scroll progress (0.0 = top, 1.0 = bottom)
     pub fn scroll_progress(&self) -> f32 {
         if let Some((_, max_y)) = self.max_scroll() {
@@ -467,6 +551,63 @@ mod tests {
         assert_eq!(scroll_ref.max_scroll(), Some((200.0, 600.0)));
     }
 
+    #[test]
+    fn test_scroll_block_positions() {
+        // 50px element at 300 in a 200px viewport scrolled to 100
+        assert_eq!(
+            ScrollBlock::Start.scroll_position(300.0, 50.0, 200.0, 100.0),
+            300.0
+        );
+        assert_eq!(
+            ScrollBlock::Center.scroll_position(300.0, 50.0, 200.0, 100.0),
+            225.0
+        );
+        assert_eq!(
+            ScrollBlock::End.scroll_position(300.0, 50.0, 200.0, 100.0),
+            150.0
+        );
+        assert_eq!(
+            ScrollBlock::Nearest.scroll_position(300.0, 50.0, 200.0, 100.0),
+            150.0
+        );
+        assert_eq!(
+            ScrollBlock::Nearest.scroll_position(50.0, 50.0, 200.0, 100.0),
+            50.0
+        );
+        assert_eq!(
+            ScrollBlock::Nearest.scroll_position(150.0, 50.0, 200.0, 100.0),
+            100.0
+        );
+    }
+
+    #[test]
+    fn test_track_view() {
+        let scroll_ref = ScrollRef::new();
+        scroll_ref.track_view("hero");
+        scroll_ref.track_view("hero");
+        assert_eq!(scroll_ref.views(), ["hero"]);
+        assert_eq!(scroll_ref.view_bounds("hero"), None);
+
+        scroll_ref.set_view_bounds("hero", Some((0.0, 400.0, 300.0, 200.0)));
+        assert_eq!(
+            scroll_ref.view_bounds("hero"),
+            Some((0.0, 400.0, 300.0, 200.0))
+        );
+        scroll_ref.set_view_bounds("hero", None);
+        assert_eq!(scroll_ref.view_bounds("hero"), None);
+    }
+
+    #[test]
+    fn test_track_anchors() {
+        let scroll_ref = ScrollRef::new();
+        scroll_ref.track_anchors(["intro", "usage"]);
+        assert_eq!(scroll_ref.anchors(), ["intro", "usage"]);
+        assert_eq!(scroll_ref.current_anchor(), None);
+
+        scroll_ref.set_current_anchor(Some("usage".into()));
+        assert_eq!(scroll_ref.current_anchor().as_deref(), Some("usage"));
+    }
+
     #[test]
     fn test_scroll_progress() {
         let scroll_ref = ScrollRef::new();
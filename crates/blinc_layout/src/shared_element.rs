@@ -0,0 +1,362 @@
+//! Shared-element (hero) transitions
+//!
+//! Elements tagged with the same [`shared_id`](crate::div::Div::shared_id) are
+//! treated as one element across layouts. When the element carrying an id
+//! unmounts and another element with that id mounts in the same frame, or
+//! within [`SHARED_ELEMENT_WINDOW`] of the unmount, the new element starts at
+//! the old one's bounds, corner radius and opacity and springs to its own.
+//!
+//! Bounds are tracked in screen space (layout position plus ancestor scroll),
+//! so the two elements can live in unrelated parts of the tree. While in
+//! flight the new element is drawn in an overlay pass on top of the rest of
+//! the tree, outside its ancestors' clips.
+//!
+//! # Example
+//!
+//! ```ignore
+//! // Thumbnail in the list
+//! div().shared_id(format!("photo-{id}")).w(64.0).h(64.0).rounded(8.0)
+//!
+//! // Header image of the detail view that replaces the list
+//! div().shared_id(format!("photo-{id}")).w_full().h(320.0)
+//! ```
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use blinc_animation::{AnimatedValue, SchedulerHandle, SpringConfig};
+use blinc_core::{Affine2D, CornerRadius};
+
+use crate::element::ElementBounds;
+use crate::tree::LayoutNodeId;
+
+/// How long an unmounted shared element waits for a partner to mount
+pub const SHARED_ELEMENT_WINDOW: Duration = Duration::from_millis(300);
+
+/// Visual state of a shared element, in screen coordinates
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SharedElementSnapshot {
+    /// Screen bounds
+    pub bounds: ElementBounds,
+    /// Corner radius
+    pub corner_radius: CornerRadius,
+    /// Element opacity
+    pub opacity: f32,
+}
+
+/// A shared element present in the current layout
+pub(crate) struct SharedElementMount {
+    pub id: String,
+    pub node: LayoutNodeId,
+    pub snapshot: SharedElementSnapshot,
+    pub spring: SpringConfig,
+}
+
+/// Spring-driven flight of a shared element from an old snapshot to its layout
+pub struct SharedElementTransition {
+    id: String,
+    x: AnimatedValue,
+    y: AnimatedValue,
+    width: AnimatedValue,
+    height: AnimatedValue,
+    radius: [AnimatedValue; 4],
+    opacity: AnimatedValue,
+}
+
+impl SharedElementTransition {
+    /// Start a transition from `from`, springing to `to`
+    pub fn new(
+        id: impl Into<String>,
+        from: SharedElementSnapshot,
+        to: SharedElementSnapshot,
+        spring: SpringConfig,
+        scheduler: SchedulerHandle,
+    ) -> Self {
+        let value = |initial: f32| AnimatedValue::new(scheduler.clone(), initial, spring);
+        let r = from.corner_radius;
+        let mut transition = Self {
+            id: id.into(),
+            x: value(from.bounds.x),
+            y: value(from.bounds.y),
+            width: value(from.bounds.width),
+            height: value(from.bounds.height),
+            radius: [
+                value(r.top_left),
+                value(r.top_right),
+                value(r.bottom_right),
+                value(r.bottom_left),
+            ],
+            opacity: value(from.opacity),
+        };
+        transition.retarget(to);
+        transition
+    }
+
+    /// The shared id being transitioned
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Spring toward a new target, keeping the current position and velocity
+    pub fn retarget(&mut self, to: SharedElementSnapshot) {
+        let r = to.corner_radius;
+        self.x.set_target(to.bounds.x);
+        self.y.set_target(to.bounds.y);
+        self.width.set_target(to.bounds.width);
+        self.height.set_target(to.bounds.height);
+        for (value, target) in
+            self.radius
+                .iter_mut()
+                .zip([r.top_left, r.top_right, r.bottom_right, r.bottom_left])
+        {
+            value.set_target(target);
+        }
+        self.opacity.set_target(to.opacity);
+    }
+
+    /// The current in-flight state
+    pub fn current(&self) -> SharedElementSnapshot {
+        SharedElementSnapshot {
+            bounds: ElementBounds {
+                x: self.x.get(),
+                y: self.y.get(),
+                width: self.width.get().max(0.0),
+                height: self.height.get().max(0.0),
+            },
+            corner_radius: CornerRadius {
+                top_left: self.radius[0].get().max(0.0),
+                top_right: self.radius[1].get().max(0.0),
+                bottom_right: self.radius[2].get().max(0.0),
+                bottom_left: self.radius[3].get().max(0.0),
+            },
+            opacity: self.opacity.get().clamp(0.0, 1.0),
+        }
+    }
+
+    /// Whether any property is still moving
+    pub fn is_animating(&self) -> bool {
+        self.x.is_animating()
+            || self.y.is_animating()
+            || self.width.is_animating()
+            || self.height.is_animating()
+            || self.radius.iter().any(|r| r.is_animating())
+            || self.opacity.is_animating()
+    }
+
+    /// Scale of the current bounds relative to `layout`
+    fn scale(&self, layout: &ElementBounds) -> (f32, f32) {
+        let current = self.current().bounds;
+        let ratio = |size: f32, target: f32| if target > 0.0 { size / target } else { 1.0 };
+        (
+            ratio(current.width, layout.width),
+            ratio(current.height, layout.height),
+        )
+    }
+
+    /// Placement moving an element laid out at `layout` (screen bounds) onto
+    /// the current bounds, relative to the element's own top-left corner
+    pub fn placement(&self, layout: &ElementBounds) -> Affine2D {
+        let current = self.current().bounds;
+        let (sx, sy) = self.scale(layout);
+        Affine2D::translation(current.x - layout.x, current.y - layout.y).then(&Affine2D {
+            elements: [sx, 0.0, 0.0, sy, 0.0, 0.0],
+        })
+    }
+
+    /// Corner radius to draw in the element's own (scaled) coordinates so the
+    /// on-screen radius matches the current one
+    pub fn local_corner_radius(&self, layout: &ElementBounds) -> CornerRadius {
+        let (sx, sy) = self.scale(layout);
+        let scale = (sx + sy) / 2.0;
+        let r = self.current().corner_radius;
+        if scale <= f32::EPSILON {
+            return r;
+        }
+        CornerRadius {
+            top_left: r.top_left / scale,
+            top_right: r.top_right / scale,
+            bottom_right: r.bottom_right / scale,
+            bottom_left: r.bottom_left / scale,
+        }
+    }
+}
+
+/// Matches shared ids across layouts and runs their transitions
+#[derive(Default)]
+pub(crate) struct SharedElements {
+    /// Node carrying each id in the last layout, with its snapshot
+    mounted: HashMap<String, (LayoutNodeId, SharedElementSnapshot)>,
+    /// Unmounted ids waiting for a partner: last visual state and unmount time
+    departed: HashMap<String, (SharedElementSnapshot, Duration)>,
+    /// Transitions by the node flying in
+    transitions: HashMap<LayoutNodeId, SharedElementTransition>,
+}
+
+impl SharedElements {
+    /// Record the shared elements of a new layout, starting a transition for
+    /// every id that moved to a new node
+    pub fn update(
+        &mut self,
+        mounts: Vec<SharedElementMount>,
+        now: Duration,
+        scheduler: &SchedulerHandle,
+    ) {
+        // One node per id: keep the one already carrying it, else the first
+        let mut current: HashMap<String, SharedElementMount> = HashMap::new();
+        for mount in mounts {
+            let keeps_node = self
+                .mounted
+                .get(&mount.id)
+                .is_some_and(|(node, _)| *node == mount.node);
+            if keeps_node || !current.contains_key(&mount.id) {
+                current.insert(mount.id.clone(), mount);
+            }
+        }
+
+        // Ids whose node went away depart from where they were last seen
+        for (id, (node, snapshot)) in self.mounted.drain() {
+            if current.get(&id).is_some_and(|mount| mount.node == node) {
+                continue;
+            }
+            let visual = self
+                .transitions
+                .remove(&node)
+                .filter(|t| t.is_animating())
+                .map(|t| t.current())
+                .unwrap_or(snapshot);
+            self.departed.insert(id, (visual, now));
+        }
+        self.departed
+            .retain(|_, (_, at)| now.saturating_sub(*at) <= SHARED_ELEMENT_WINDOW);
+
+        for mount in current.into_values() {
+            if let Some(transition) = self.transitions.get_mut(&mount.node) {
+                transition.retarget(mount.snapshot);
+            } else if let Some((from, _)) = self.departed.remove(&mount.id) {
+                if from != mount.snapshot {
+                    let transition = SharedElementTransition::new(
+                        mount.id.clone(),
+                        from,
+                        mount.snapshot,
+                        mount.spring,
+                        scheduler.clone(),
+                    );
+                    self.transitions.insert(mount.node, transition);
+                }
+            }
+            self.mounted.insert(mount.id, (mount.node, mount.snapshot));
+        }
+
+        self.transitions.retain(|_, t| t.is_animating());
+    }
+
+    /// The transition of `node`, while it is in flight
+    pub fn in_flight(&self, node: LayoutNodeId) -> Option<&SharedElementTransition> {
+        self.transitions.get(&node).filter(|t| t.is_animating())
+    }
+
+    /// Nodes currently in flight
+    pub fn in_flight_nodes(&self) -> impl Iterator<Item = LayoutNodeId> + '_ {
+        self.transitions
+            .iter()
+            .filter(|(_, t)| t.is_animating())
+            .map(|(node, _)| *node)
+    }
+
+    /// Whether any transition is running
+    pub fn is_animating(&self) -> bool {
+        self.transitions.values().any(|t| t.is_animating())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use blinc_animation::{AnimationScheduler, Clock, ManualClock};
+
+    fn snapshot(x: f32, y: f32, size: f32, radius: f32, opacity: f32) -> SharedElementSnapshot {
+        SharedElementSnapshot {
+            bounds: ElementBounds {
+                x,
+                y,
+                width: size,
+                height: size,
+            },
+            corner_radius: CornerRadius::uniform(radius),
+            opacity,
+        }
+    }
+
+    fn mount(id: &str, node: LayoutNodeId, snapshot: SharedElementSnapshot) -> SharedElementMount {
+        SharedElementMount {
+            id: id.to_string(),
+            node,
+            snapshot,
+            spring: SpringConfig::snappy(),
+        }
+    }
+
+    #[test]
+    fn test_transition_between_nodes() {
+        let clock = Arc::new(ManualClock::new());
+        let scheduler = AnimationScheduler::with_clock(clock.clone());
+        let handle = scheduler.handle();
+        let mut shared = SharedElements::default();
+
+        let mut nodes = slotmap::SlotMap::<LayoutNodeId, ()>::with_key();
+        let (thumb, header) = (nodes.insert(()), nodes.insert(()));
+        let small = snapshot(10.0, 20.0, 50.0, 8.0, 0.5);
+        let large = snapshot(0.0, 100.0, 200.0, 0.0, 1.0);
+
+        shared.update(vec![mount("photo", thumb, small)], clock.now(), &handle);
+        assert!(!shared.is_animating());
+
+        // The thumbnail unmounts and the header mounts in the same layout
+        shared.update(vec![mount("photo", header, large)], clock.now(), &handle);
+        let transition = shared.in_flight(header).unwrap();
+        assert_eq!(transition.current(), small);
+
+        // The placement maps the header's layout bounds onto the thumbnail
+        let placement = transition.placement(&large.bounds);
+        let corner = placement.transform_point(blinc_core::Point::new(200.0, 200.0));
+        assert!((corner.x - 60.0).abs() < 1e-3 && (corner.y - (-30.0)).abs() < 1e-3);
+        let radius = transition.local_corner_radius(&large.bounds);
+        assert!((radius.top_left - 32.0).abs() < 1e-3);
+
+        for _ in 0..200 {
+            clock.advance(Duration::from_millis(16));
+            scheduler.tick();
+        }
+        assert!(shared.in_flight(header).is_none());
+        shared.update(vec![mount("photo", header, large)], clock.now(), &handle);
+        assert!(!shared.is_animating());
+    }
+
+    #[test]
+    fn test_transition_window() {
+        let clock = Arc::new(ManualClock::new());
+        let scheduler = AnimationScheduler::with_clock(clock.clone());
+        let handle = scheduler.handle();
+        let mut shared = SharedElements::default();
+
+        let mut nodes = slotmap::SlotMap::<LayoutNodeId, ()>::with_key();
+        let (a, b, c) = (nodes.insert(()), nodes.insert(()), nodes.insert(()));
+        let from = snapshot(0.0, 0.0, 40.0, 4.0, 1.0);
+        let to = snapshot(100.0, 0.0, 40.0, 4.0, 1.0);
+
+        // Unmounted, then a partner mounts within the window
+        shared.update(vec![mount("tab", a, from)], clock.now(), &handle);
+        shared.update(Vec::new(), clock.now(), &handle);
+        clock.advance(Duration::from_millis(100));
+        shared.update(vec![mount("tab", b, to)], clock.now(), &handle);
+        assert!(shared.in_flight(b).is_some());
+
+        // Too late: the new element simply appears
+        shared.update(Vec::new(), clock.now(), &handle);
+        clock.advance(SHARED_ELEMENT_WINDOW + Duration::from_millis(1));
+        shared.update(vec![mount("tab", c, from)], clock.now(), &handle);
+        assert!(shared.in_flight(c).is_none());
+    }
+}
@@ -204,7 +204,7 @@ impl KeyframeBuilder {
             } else {
                 0.0
             };
-            anim = anim.keyframe(time, *value, self.default_easing);
+            anim = anim.keyframe(time, *value, self.default_easing.clone());
         }
 
         // Apply settings
@@ -2778,6 +2778,12 @@ impl<S: StateTransitions> Stateful<S> {
         self
     }
 
+    /// Report a checked state for `:checked` selector matching
+    pub fn checked_when(self, probe: impl Fn() -> bool + Send + Sync + 'static) -> Self {
+        self.inner.borrow_mut().checked_probe = Some(Arc::new(probe));
+        self
+    }
+
     // =========================================================================
     // Builder pattern methods that return Self (not Div)
     // =========================================================================
@@ -2895,6 +2901,54 @@ impl<S: StateTransitions> Stateful<S> {
         self
     }
 
+    /// Set width using a semantic Length value (builder pattern)
+    pub fn width(self, len: crate::units::Length) -> Self {
+        self.merge_into_inner(Div::new().width(len));
+        self
+    }
+
+    /// Set height using a semantic Length value (builder pattern)
+    pub fn height(self, len: crate::units::Length) -> Self {
+        self.merge_into_inner(Div::new().height(len));
+        self
+    }
+
+    /// Set min-width using a semantic Length value (builder pattern)
+    pub fn min_width(self, len: crate::units::Length) -> Self {
+        self.merge_into_inner(Div::new().min_width(len));
+        self
+    }
+
+    /// Set min-height using a semantic Length value (builder pattern)
+    pub fn min_height(self, len: crate::units::Length) -> Self {
+        self.merge_into_inner(Div::new().min_height(len));
+        self
+    }
+
+    /// Set max-width using a semantic Length value (builder pattern)
+    pub fn max_width(self, len: crate::units::Length) -> Self {
+        self.merge_into_inner(Div::new().max_width(len));
+        self
+    }
+
+    /// Set max-height using a semantic Length value (builder pattern)
+    pub fn max_height(self, len: crate::units::Length) -> Self {
+        self.merge_into_inner(Div::new().max_height(len));
+        self
+    }
+
+    /// Set margin using a semantic Length value (builder pattern)
+    pub fn margin(self, len: crate::units::Length) -> Self {
+        self.merge_into_inner(Div::new().margin(len));
+        self
+    }
+
+    /// Set gap using a semantic Length value (builder pattern)
+    pub fn gap_len(self, len: crate::units::Length) -> Self {
+        self.merge_into_inner(Div::new().gap_len(len));
+        self
+    }
+
     /// Set padding top (builder pattern)
     pub fn pt(self, units: f32) -> Self {
         self.merge_into_inner(Div::new().pt(units));
@@ -3962,6 +4016,20 @@ impl<S: StateTransitions> ElementBuilder for Stateful<S> {
             (*inner).classes()
         }
     }
+
+    fn checked_probe(&self) -> Option<crate::selector::CheckedProbe> {
+        self.ensure_callback_invoked();
+        self.inner.borrow().checked_probe.clone()
+    }
+
+    fn contextual_lengths(&self) -> &[(crate::units::LengthSlot, crate::units::Length)] {
+        self.ensure_callback_invoked();
+        // SAFETY: Same reasoning as element_id().
+        unsafe {
+            let inner = self.inner.as_ptr();
+            &(*inner).contextual_lengths
+        }
+    }
 }
 
 // =========================================================================
@@ -4115,6 +4183,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_checked_selector_matches_selected_radio() {
+        ensure_context_state();
+        reset_call_counters();
+
+        let selected = blinc_core::context_state::BlincContextState::get()
+            .use_state_keyed("checked-radio-test", || "b".to_string());
+        let ui = div().w(300.0).h(200.0).child(
+            crate::widgets::radio_group(&selected)
+                .id("size")
+                .option("a", "Small")
+                .option("b", "Large"),
+        );
+        let tree = RenderTree::from_element(&ui);
+
+        let checked = tree.query_selector_all(":checked");
+        let ids: Vec<&str> = checked.iter().map(|h| h.id()).collect();
+        assert_eq!(ids, vec!["size-b"]);
+        assert!(!tree.query_selector("#size-a").unwrap().is_checked());
+
+        // The probe reads the live selection, no rebuild needed
+        selected.set("a".to_string());
+        assert_eq!(
+            tree.query_selector("#size-a:checked").unwrap().id(),
+            "size-a"
+        );
+        assert!(tree.query_selector("#size-b:checked").is_none());
+    }
+
     #[test]
     fn test_stateful_builder_click_after_scroll_hits_visible_item() {
         use std::sync::{Arc, Mutex};
@@ -0,0 +1,713 @@
+//! Declarative grammars in the TextMate format
+//!
+//! A [`Grammar`] is loaded from a `.tmLanguage.json` document and tokenizes
+//! text one line at a time into scoped tokens. Supported rule kinds:
+//!
+//! - `match` rules with `name` and `captures`
+//! - `begin`/`end` regions with `name`, `contentName`, `beginCaptures`,
+//!   `endCaptures`, `captures`, nested `patterns` and `applyEndPatternLast`
+//! - `include` of `#repository` entries and `$self`/`$base`
+//! - pattern groups (rules holding only `patterns`)
+//!
+//! Patterns use the syntax of the `regex` crate rather than Oniguruma, so
+//! look-around, backreferences and `\G` are not available. Rules whose
+//! patterns don't compile are skipped and counted by
+//! [`Grammar::skipped_patterns`]; includes of other grammars are ignored.
+//!
+//! Open regions carry over to the next line through [`LineState`], which
+//! holds the ids of the regions still open.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+
+use regex::{Captures, Regex};
+use serde_json::{Map, Value};
+
+use super::LineState;
+
+/// Deepest region nesting before further `begin` matches are ignored
+const MAX_DEPTH: usize = 64;
+
+/// Zero-width matches allowed at one position before moving on a char
+const MAX_EMPTY_STEPS: usize = 8;
+
+/// Error loading a grammar
+#[derive(Debug)]
+pub enum GrammarError {
+    /// The document isn't valid JSON
+    Json(serde_json::Error),
+    /// The document isn't a grammar
+    Invalid(String),
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrammarError::Json(err) => write!(f, "invalid grammar JSON: {err}"),
+            GrammarError::Invalid(msg) => write!(f, "invalid grammar: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for GrammarError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GrammarError::Json(err) => Some(err),
+            GrammarError::Invalid(_) => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for GrammarError {
+    fn from(err: serde_json::Error) -> Self {
+        GrammarError::Json(err)
+    }
+}
+
+/// A run of text and the scopes it is in, outermost first
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScopedToken {
+    /// Byte range in the line
+    pub range: Range<usize>,
+    /// Scope names, starting with the grammar's scope name
+    pub scopes: Vec<String>,
+}
+
+type RuleId = usize;
+
+/// Scope names for capture groups, by group index
+type CaptureScopes = Vec<Option<String>>;
+
+#[derive(Debug)]
+enum Rule {
+    Match {
+        regex: Regex,
+        name: Option<String>,
+        captures: CaptureScopes,
+    },
+    Region {
+        begin: Regex,
+        end: Regex,
+        name: Option<String>,
+        content_name: Option<String>,
+        begin_captures: CaptureScopes,
+        end_captures: CaptureScopes,
+        apply_end_last: bool,
+        patterns: Vec<RuleId>,
+        /// `patterns` with groups and includes flattened
+        candidates: Vec<RuleId>,
+    },
+    Group {
+        patterns: Vec<RuleId>,
+    },
+}
+
+/// The root group holding the grammar's top-level patterns
+const ROOT: RuleId = 0;
+
+/// A compiled TextMate grammar
+#[derive(Debug)]
+pub struct Grammar {
+    name: String,
+    scope_name: String,
+    file_types: Vec<String>,
+    rules: Vec<Rule>,
+    /// Top-level patterns with groups and includes flattened
+    root_candidates: Vec<RuleId>,
+    skipped: usize,
+}
+
+impl Grammar {
+    /// Load a grammar from a `.tmLanguage.json` document
+    pub fn from_json(json: &str) -> Result<Self, GrammarError> {
+        let value: Value = serde_json::from_str(json)?;
+        Self::from_value(&value)
+    }
+
+    /// Load a grammar from a parsed `.tmLanguage.json` document
+    pub fn from_value(value: &Value) -> Result<Self, GrammarError> {
+        let doc = value
+            .as_object()
+            .ok_or_else(|| GrammarError::Invalid("expected an object".into()))?;
+        let scope_name = doc
+            .get("scopeName")
+            .and_then(Value::as_str)
+            .ok_or_else(|| GrammarError::Invalid("missing `scopeName`".into()))?
+            .to_string();
+        let name = doc
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| scope_name.clone());
+        let file_types = doc
+            .get("fileTypes")
+            .and_then(Value::as_array)
+            .map(|types| {
+                types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|t| t.trim_start_matches('.').to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut builder = Builder::default();
+        builder.rules.push(None); // ROOT
+
+        // Repository entries get ids up front so includes can refer to
+        // entries defined later, and to themselves
+        let repository = doc.get("repository").and_then(Value::as_object);
+        if let Some(repository) = repository {
+            for key in repository.keys() {
+                let id = builder.rules.len();
+                builder.rules.push(None);
+                builder.repository.insert(key.clone(), id);
+            }
+            for (key, rule) in repository {
+                let id = builder.repository[key];
+                let compiled = rule
+                    .as_object()
+                    .and_then(|rule| builder.compile_rule(rule))
+                    .map(|id| Rule::Group { patterns: vec![id] });
+                builder.rules[id] = compiled;
+            }
+        }
+
+        let patterns = builder.compile_patterns(doc.get("patterns"));
+        builder.rules[ROOT] = Some(Rule::Group { patterns });
+
+        // Rules that failed to compile become empty groups
+        let mut rules: Vec<Rule> = builder
+            .rules
+            .into_iter()
+            .map(|rule| rule.unwrap_or(Rule::Group { patterns: vec![] }))
+            .collect();
+
+        for id in 0..rules.len() {
+            if let Rule::Region { patterns, .. } = &rules[id] {
+                let flat = flatten(&rules, patterns);
+                if let Rule::Region { candidates, .. } = &mut rules[id] {
+                    *candidates = flat;
+                }
+            }
+        }
+        let root_candidates = flatten(&rules, &[ROOT]);
+
+        Ok(Self {
+            name,
+            scope_name,
+            file_types,
+            rules,
+            root_candidates,
+            skipped: builder.skipped,
+        })
+    }
+
+    /// Display name, e.g. `"TOML"`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Root scope, e.g. `"source.toml"`
+    pub fn scope_name(&self) -> &str {
+        &self.scope_name
+    }
+
+    /// File extensions without the dot
+    pub fn file_types(&self) -> &[String] {
+        &self.file_types
+    }
+
+    /// Number of rules left out because their patterns didn't compile
+    pub fn skipped_patterns(&self) -> usize {
+        self.skipped
+    }
+
+    /// Whether this grammar is for `language`, given as a name, file
+    /// extension or scope name (case-insensitive)
+    pub fn matches_language(&self, language: &str) -> bool {
+        let language = language.trim().trim_start_matches('.');
+        self.name.eq_ignore_ascii_case(language)
+            || self.scope_name.eq_ignore_ascii_case(language)
+            || self
+                .scope_name
+                .rsplit('.')
+                .next()
+                .is_some_and(|last| last.eq_ignore_ascii_case(language))
+            || self
+                .file_types
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(language))
+    }
+
+    /// Tokenize one line, given the state at its start
+    ///
+    /// Returns tokens covering the whole line and the state at its end.
+    pub fn tokenize_line(&self, line: &str, state: &LineState) -> (Vec<ScopedToken>, LineState) {
+        let mut stack: Vec<RuleId> = state
+            .stack()
+            .iter()
+            .map(|&id| id as RuleId)
+            .filter(|&id| matches!(self.rules.get(id), Some(Rule::Region { .. })))
+            .collect();
+        let mut tokens = Vec::new();
+        let mut pos = 0;
+        let mut emitted = 0;
+        let mut empty_steps = 0;
+
+        while pos <= line.len() {
+            let scopes = self.scopes(&stack);
+            let Some((start, end, step)) = self.next_match(line, pos, &stack) else {
+                break;
+            };
+
+            let stack_before = stack.len();
+            push_token(&mut tokens, emitted..start, &scopes);
+            match step {
+                Step::Match(id) => {
+                    let Rule::Match {
+                        regex,
+                        name,
+                        captures,
+                    } = &self.rules[id]
+                    else {
+                        unreachable!()
+                    };
+                    let mut scopes = scopes.clone();
+                    scopes.extend(name.clone());
+                    let caps = regex.captures_at(line, start);
+                    push_captures(&mut tokens, start..end, &scopes, caps.as_ref(), captures);
+                }
+                Step::Begin(id) => {
+                    let Rule::Region {
+                        begin,
+                        name,
+                        begin_captures,
+                        ..
+                    } = &self.rules[id]
+                    else {
+                        unreachable!()
+                    };
+                    let mut scopes = scopes.clone();
+                    scopes.extend(name.clone());
+                    let caps = begin.captures_at(line, start);
+                    push_captures(
+                        &mut tokens,
+                        start..end,
+                        &scopes,
+                        caps.as_ref(),
+                        begin_captures,
+                    );
+                    stack.push(id);
+                }
+                Step::End => {
+                    let id = stack.pop().expect("end step needs an open region");
+                    let Rule::Region {
+                        end: end_regex,
+                        content_name,
+                        end_captures,
+                        ..
+                    } = &self.rules[id]
+                    else {
+                        unreachable!()
+                    };
+                    // The end delimiter is outside the region's content
+                    let mut scopes = scopes.clone();
+                    if content_name.is_some() {
+                        scopes.pop();
+                    }
+                    let caps = end_regex.captures_at(line, start);
+                    push_captures(
+                        &mut tokens,
+                        start..end,
+                        &scopes,
+                        caps.as_ref(),
+                        end_captures,
+                    );
+                }
+            }
+            emitted = end;
+
+            if end > pos {
+                pos = end;
+                empty_steps = 0;
+            } else {
+                // Zero-width match: allow a few region changes here, then
+                // move on so the line always finishes
+                empty_steps += 1;
+                if stack.len() == stack_before || empty_steps > MAX_EMPTY_STEPS {
+                    match line[pos..].chars().next() {
+                        Some(c) => pos += c.len_utf8(),
+                        None => break,
+                    }
+                    empty_steps = 0;
+                }
+            }
+        }
+
+        let scopes = self.scopes(&stack);
+        push_token(&mut tokens, emitted..line.len(), &scopes);
+        let state = LineState::from_stack(stack.into_iter().map(|id| id as u32).collect());
+        (tokens, state)
+    }
+
+    /// Scopes inside the open regions
+    fn scopes(&self, stack: &[RuleId]) -> Vec<String> {
+        let mut scopes = vec![self.scope_name.clone()];
+        for &id in stack {
+            if let Rule::Region {
+                name, content_name, ..
+            } = &self.rules[id]
+            {
+                scopes.extend(name.clone());
+                scopes.extend(content_name.clone());
+            }
+        }
+        scopes
+    }
+
+    /// Earliest match at or after `pos`; ties go to the end pattern (unless
+    /// `applyEndPatternLast`), then to the first pattern listed
+    fn next_match(&self, line: &str, pos: usize, stack: &[RuleId]) -> Option<(usize, usize, Step)> {
+        let (candidates, end) = match stack.last().map(|&id| &self.rules[id]) {
+            Some(Rule::Region {
+                candidates,
+                end,
+                apply_end_last,
+                ..
+            }) => (candidates.as_slice(), Some((end, *apply_end_last))),
+            _ => (self.root_candidates.as_slice(), None),
+        };
+
+        let mut best: Option<(usize, usize, Step)> = None;
+        let mut consider = |start: usize, end: usize, step: Step, wins_ties: bool| {
+            let better = match &best {
+                None => true,
+                Some((best_start, _, _)) => {
+                    start < *best_start || (wins_ties && start == *best_start)
+                }
+            };
+            if better {
+                best = Some((start, end, step));
+            }
+        };
+
+        if let Some((end, false)) = end {
+            if let Some(m) = end.find_at(line, pos) {
+                consider(m.start(), m.end(), Step::End, false);
+            }
+        }
+        for &id in candidates {
+            match &self.rules[id] {
+                Rule::Match { regex, .. } => {
+                    if let Some(m) = regex.find_at(line, pos) {
+                        consider(m.start(), m.end(), Step::Match(id), false);
+                    }
+                }
+                Rule::Region { begin, .. } if stack.len() < MAX_DEPTH => {
+                    if let Some(m) = begin.find_at(line, pos) {
+                        consider(m.start(), m.end(), Step::Begin(id), false);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some((end, true)) = end {
+            if let Some(m) = end.find_at(line, pos) {
+                consider(m.start(), m.end(), Step::End, false);
+            }
+        }
+        best
+    }
+}
+
+/// What the next match does
+#[derive(Clone, Copy, Debug)]
+enum Step {
+    Match(RuleId),
+    Begin(RuleId),
+    End,
+}
+
+/// Append a token, merging it into the previous one if the scopes agree
+fn push_token(tokens: &mut Vec<ScopedToken>, range: Range<usize>, scopes: &[String]) {
+    if range.is_empty() {
+        return;
+    }
+    if let Some(last) = tokens.last_mut() {
+        if last.range.end == range.start && last.scopes == scopes {
+            last.range.end = range.end;
+            return;
+        }
+    }
+    tokens.push(ScopedToken {
+        range,
+        scopes: scopes.to_vec(),
+    });
+}
+
+/// Append tokens for a match, splitting it where capture groups start and end
+fn push_captures(
+    tokens: &mut Vec<ScopedToken>,
+    range: Range<usize>,
+    scopes: &[String],
+    caps: Option<&Captures<'_>>,
+    capture_scopes: &CaptureScopes,
+) {
+    let groups: Vec<(Range<usize>, &str)> = match caps {
+        Some(caps) => capture_scopes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, scope)| Some((caps.get(i)?.range(), scope.as_deref()?)))
+            .filter(|(group, _)| !group.is_empty())
+            .collect(),
+        None => Vec::new(),
+    };
+    if groups.is_empty() {
+        push_token(tokens, range, scopes);
+        return;
+    }
+
+    let mut bounds: Vec<usize> = vec![range.start, range.end];
+    for (group, _) in &groups {
+        bounds.push(group.start);
+        bounds.push(group.end);
+    }
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    for pair in bounds.windows(2) {
+        let segment = pair[0]..pair[1];
+        let mut segment_scopes = scopes.to_vec();
+        segment_scopes.extend(
+            groups
+                .iter()
+                .filter(|(group, _)| group.start <= segment.start && segment.end <= group.end)
+                .map(|(_, scope)| scope.to_string()),
+        );
+        push_token(tokens, segment, &segment_scopes);
+    }
+}
+
+/// Match and region rules reachable from `patterns` through groups and includes
+fn flatten(rules: &[Rule], patterns: &[RuleId]) -> Vec<RuleId> {
+    fn visit(rules: &[Rule], id: RuleId, seen: &mut Vec<bool>, out: &mut Vec<RuleId>) {
+        if std::mem::replace(&mut seen[id], true) {
+            return;
+        }
+        match &rules[id] {
+            Rule::Group { patterns } => {
+                for &child in patterns {
+                    visit(rules, child, seen, out);
+                }
+            }
+            Rule::Match { .. } | Rule::Region { .. } => out.push(id),
+        }
+    }
+
+    let mut seen = vec![false; rules.len()];
+    let mut out = Vec::new();
+    for &id in patterns {
+        visit(rules, id, &mut seen, &mut out);
+    }
+    out
+}
+
+#[derive(Default)]
+struct Builder {
+    rules: Vec<Option<Rule>>,
+    repository: HashMap<String, RuleId>,
+    skipped: usize,
+}
+
+impl Builder {
+    fn compile_patterns(&mut self, patterns: Option<&Value>) -> Vec<RuleId> {
+        patterns
+            .and_then(Value::as_array)
+            .map(|patterns| {
+                patterns
+                    .iter()
+                    .filter_map(Value::as_object)
+                    .filter_map(|rule| self.compile_rule(rule))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Compile a rule, returning its id; includes return the included id
+    fn compile_rule(&mut self, rule: &Map<String, Value>) -> Option<RuleId> {
+        if let Some(include) = rule.get("include").and_then(Value::as_str) {
+            return match include {
+                "$self" | "$base" => Some(ROOT),
+                _ => include
+                    .strip_prefix('#')
+                    .and_then(|key| self.repository.get(key).copied()),
+            };
+        }
+
+        let name = scope(rule, "name");
+        let compiled = if let Some(pattern) = rule.get("match").and_then(Value::as_str) {
+            let regex = self.regex(pattern)?;
+            Rule::Match {
+                regex,
+                name,
+                captures: captures(rule.get("captures")),
+            }
+        } else if let Some(begin) = rule.get("begin").and_then(Value::as_str) {
+            let Some(end) = rule.get("end").and_then(Value::as_str) else {
+                self.skipped += 1;
+                return None;
+            };
+            let (begin, end) = (self.regex(begin)?, self.regex(end)?);
+            let shared = rule.get("captures");
+            Rule::Region {
+                begin,
+                end,
+                name,
+                content_name: scope(rule, "contentName"),
+                begin_captures: captures(rule.get("beginCaptures").or(shared)),
+                end_captures: captures(rule.get("endCaptures").or(shared)),
+                apply_end_last: rule
+                    .get("applyEndPatternLast")
+                    .is_some_and(|v| v.as_bool() == Some(true) || v.as_i64() == Some(1)),
+                patterns: self.compile_patterns(rule.get("patterns")),
+                candidates: Vec::new(),
+            }
+        } else if rule.contains_key("patterns") {
+            Rule::Group {
+                patterns: self.compile_patterns(rule.get("patterns")),
+            }
+        } else {
+            return None;
+        };
+
+        let id = self.rules.len();
+        self.rules.push(Some(compiled));
+        Some(id)
+    }
+
+    fn regex(&mut self, pattern: &str) -> Option<Regex> {
+        match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(err) => {
+                tracing::warn!("skipping grammar rule with unsupported pattern {pattern:?}: {err}");
+                self.skipped += 1;
+                None
+            }
+        }
+    }
+}
+
+fn scope(rule: &Map<String, Value>, key: &str) -> Option<String> {
+    rule.get(key)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .filter(|s| !s.is_empty())
+}
+
+fn captures(value: Option<&Value>) -> CaptureScopes {
+    let mut scopes = CaptureScopes::new();
+    let Some(map) = value.and_then(Value::as_object) else {
+        return scopes;
+    };
+    for (index, capture) in map {
+        let (Ok(index), Some(capture)) = (index.parse::<usize>(), capture.as_object()) else {
+            continue;
+        };
+        if scopes.len() <= index {
+            scopes.resize(index + 1, None);
+        }
+        scopes[index] = scope(capture, "name");
+    }
+    scopes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAMMAR: &str = r##"{
+        "name": "Test",
+        "scopeName": "source.test",
+        "fileTypes": ["tst"],
+        "patterns": [
+            { "include": "#comment" },
+            { "match": "\\b(let)\\s+(\\w+)", "captures": {
+                "1": { "name": "storage.type.test" },
+                "2": { "name": "variable.other.test" }
+            } },
+            { "begin": "\"", "end": "\"", "name": "string.quoted.test",
+              "patterns": [
+                { "match": "\\\\.", "name": "constant.character.escape.test" },
+                { "begin": "\\{", "end": "\\}", "name": "meta.interpolation.test",
+                  "patterns": [{ "include": "$self" }] }
+              ] },
+            { "match": "(?<bad)", "name": "invalid.test" }
+        ],
+        "repository": {
+            "comment": { "begin": "/\\*", "end": "\\*/", "name": "comment.block.test" }
+        }
+    }"##;
+
+    fn scopes_of(tokens: &[ScopedToken], line: &str, text: &str) -> Vec<String> {
+        let start = line.find(text).unwrap();
+        tokens
+            .iter()
+            .find(|t| t.range.start <= start && start < t.range.end)
+            .map(|t| t.scopes.clone())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_tokenize_with_captures_and_regions() {
+        let grammar = Grammar::from_json(GRAMMAR).unwrap();
+        assert_eq!(grammar.skipped_patterns(), 1);
+        assert!(grammar.matches_language("TST") && grammar.matches_language("test"));
+
+        let line = r#"let x = "a\n{let y}" /* open"#;
+        let (tokens, state) = grammar.tokenize_line(line, &LineState::default());
+        assert_eq!(tokens.first().unwrap().range.start, 0);
+        assert_eq!(tokens.last().unwrap().range.end, line.len());
+        assert_eq!(
+            scopes_of(&tokens, line, "x"),
+            ["source.test", "variable.other.test"]
+        );
+        assert_eq!(
+            scopes_of(&tokens, line, r"\n"),
+            [
+                "source.test",
+                "string.quoted.test",
+                "constant.character.escape.test"
+            ]
+        );
+        assert_eq!(
+            scopes_of(&tokens, line, "y"),
+            [
+                "source.test",
+                "string.quoted.test",
+                "meta.interpolation.test",
+                "variable.other.test"
+            ]
+        );
+        assert_eq!(state.stack().len(), 1);
+
+        // The comment carries over to the next line
+        let (tokens, state) = grammar.tokenize_line("still */ let", &state);
+        assert_eq!(tokens[0].scopes, ["source.test", "comment.block.test"]);
+        assert_eq!(tokens[0].range, 0..8);
+        assert!(state.is_initial());
+    }
+
+    #[test]
+    fn test_invalid_grammar() {
+        assert!(matches!(
+            Grammar::from_json("{"),
+            Err(GrammarError::Json(_))
+        ));
+        assert!(matches!(
+            Grammar::from_json(r#"{"patterns": []}"#),
+            Err(GrammarError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,340 @@
+//! Grammar registry and the grammar-driven highlighter
+//!
+//! The registry starts with grammars for TOML, YAML, Markdown, JavaScript,
+//! TypeScript, Python, SQL, shell and CSS. More can be added at runtime with
+//! [`register_grammar`]; a grammar registered later takes precedence for the
+//! languages it matches.
+
+use std::sync::{Arc, OnceLock, RwLock};
+
+use blinc_core::Color;
+
+use super::grammar::{Grammar, GrammarError};
+use super::theme::SyntaxTheme;
+use super::{LineState, SyntaxHighlighter, TokenRule, TokenType};
+use crate::styled_text::{StyledLine, TextSpan};
+
+/// Grammars shipped with blinc, as `.tmLanguage.json` documents
+const BUNDLED: &[&str] = &[
+    include_str!("grammars/toml.tmLanguage.json"),
+    include_str!("grammars/yaml.tmLanguage.json"),
+    include_str!("grammars/markdown.tmLanguage.json"),
+    include_str!("grammars/javascript.tmLanguage.json"),
+    include_str!("grammars/typescript.tmLanguage.json"),
+    include_str!("grammars/python.tmLanguage.json"),
+    include_str!("grammars/sql.tmLanguage.json"),
+    include_str!("grammars/shell.tmLanguage.json"),
+    include_str!("grammars/css.tmLanguage.json"),
+];
+
+/// A set of grammars looked up by language name or file extension
+#[derive(Clone, Debug, Default)]
+pub struct GrammarRegistry {
+    grammars: Vec<Arc<Grammar>>,
+}
+
+impl GrammarRegistry {
+    /// Empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry holding the bundled grammars
+    pub fn bundled() -> Self {
+        let mut registry = Self::new();
+        for json in BUNDLED {
+            let grammar = Grammar::from_json(json).expect("bundled grammar is valid");
+            registry.register(grammar);
+        }
+        registry
+    }
+
+    /// Add a grammar, taking precedence over earlier ones
+    pub fn register(&mut self, grammar: Grammar) -> Arc<Grammar> {
+        let grammar = Arc::new(grammar);
+        self.grammars
+            .retain(|g| g.scope_name() != grammar.scope_name());
+        self.grammars.push(Arc::clone(&grammar));
+        grammar
+    }
+
+    /// Grammar for a language name, file extension or scope name
+    pub fn find(&self, language: &str) -> Option<Arc<Grammar>> {
+        self.grammars
+            .iter()
+            .rev()
+            .find(|g| g.matches_language(language))
+            .cloned()
+    }
+
+    /// All grammars, in registration order
+    pub fn grammars(&self) -> &[Arc<Grammar>] {
+        &self.grammars
+    }
+}
+
+/// The global registry, loading the bundled grammars on first use
+fn registry() -> &'static RwLock<GrammarRegistry> {
+    static REGISTRY: OnceLock<RwLock<GrammarRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(GrammarRegistry::bundled()))
+}
+
+/// Add a grammar to the global registry
+pub fn register_grammar(grammar: Grammar) -> Arc<Grammar> {
+    registry().write().unwrap().register(grammar)
+}
+
+/// Load a `.tmLanguage.json` grammar into the global registry
+pub fn register_grammar_json(json: &str) -> Result<Arc<Grammar>, GrammarError> {
+    Ok(register_grammar(Grammar::from_json(json)?))
+}
+
+/// Grammar for a language from the global registry
+pub fn find_grammar(language: &str) -> Option<Arc<Grammar>> {
+    registry().read().unwrap().find(language)
+}
+
+/// Highlighter for a language from the global registry, styled with the
+/// current theme
+pub fn highlighter_for(language: &str) -> Option<GrammarHighlighter> {
+    find_grammar(language)
+        .map(|grammar| GrammarHighlighter::new(grammar, SyntaxTheme::from_theme()))
+}
+
+/// Syntax highlighter driven by a [`Grammar`] and a [`SyntaxTheme`]
+///
+/// # Example
+///
+/// ```ignore
+/// code("[server]\nport = 8080")
+///     .syntax(SyntaxConfig::new(highlighter_for("toml").unwrap()))
+/// ```
+#[derive(Clone, Debug)]
+pub struct GrammarHighlighter {
+    grammar: Arc<Grammar>,
+    theme: Arc<SyntaxTheme>,
+}
+
+impl GrammarHighlighter {
+    pub fn new(grammar: Arc<Grammar>, theme: SyntaxTheme) -> Self {
+        Self {
+            grammar,
+            theme: Arc::new(theme),
+        }
+    }
+
+    pub fn grammar(&self) -> &Grammar {
+        &self.grammar
+    }
+
+    pub fn theme(&self) -> &SyntaxTheme {
+        &self.theme
+    }
+
+    /// Use a different theme
+    pub fn with_theme(mut self, theme: SyntaxTheme) -> Self {
+        self.theme = Arc::new(theme);
+        self
+    }
+}
+
+impl SyntaxHighlighter for GrammarHighlighter {
+    fn token_rules(&self) -> &[TokenRule] {
+        &[]
+    }
+
+    fn default_color(&self) -> Color {
+        self.theme.default_color
+    }
+
+    fn background_color(&self) -> Color {
+        self.theme.background_color
+    }
+
+    fn line_number_color(&self) -> Color {
+        self.theme.line_number_color
+    }
+
+    fn highlight_line(&self, line: &str, state: &LineState) -> (StyledLine, LineState) {
+        let (tokens, state) = self.grammar.tokenize_line(line, state);
+        let mut spans: Vec<TextSpan> = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            let style = self.theme.style_for(&token.scopes);
+            let mut span = TextSpan::new(
+                token.range.start,
+                token.range.end,
+                style.color.unwrap_or(self.theme.default_color),
+                style.bold,
+            );
+            span.italic = style.italic;
+            span.token_type = token_type(&token.scopes);
+
+            if let Some(last) = spans.last_mut() {
+                if last.end == span.start
+                    && last.color == span.color
+                    && last.bold == span.bold
+                    && last.italic == span.italic
+                    && last.token_type == span.token_type
+                {
+                    last.end = span.end;
+                    continue;
+                }
+            }
+            spans.push(span);
+        }
+        (StyledLine::new(line, spans), state)
+    }
+}
+
+/// Token type for the innermost scope that has one
+fn token_type(scopes: &[String]) -> Option<TokenType> {
+    // The first scope is the grammar's own
+    let inner = scopes.get(1..)?;
+    let known = inner.iter().rev().find_map(|scope| {
+        let is = |prefix: &str| {
+            scope
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+        };
+        if is("comment") {
+            Some(TokenType::Comment)
+        } else if is("string") || is("markup.raw") || is("markup.inline.raw") {
+            Some(TokenType::String)
+        } else if is("constant.numeric") {
+            Some(TokenType::Number)
+        } else if is("keyword.operator") {
+            Some(TokenType::Operator)
+        } else if is("keyword") || is("storage") {
+            Some(TokenType::Keyword)
+        } else if is("entity.name.type")
+            || is("entity.name.class")
+            || is("support.type")
+            || is("support.class")
+        {
+            Some(TokenType::Type)
+        } else if is("entity.name.function") || is("support.function") {
+            Some(TokenType::Function)
+        } else if is("variable") {
+            Some(TokenType::Variable)
+        } else {
+            None
+        }
+    });
+    known.or_else(|| inner.last().map(|scope| TokenType::custom(scope.clone())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(language: &str, source: &str) -> Vec<(String, Option<TokenType>)> {
+        let grammar = GrammarRegistry::bundled().find(language).unwrap();
+        let highlighter =
+            GrammarHighlighter::new(grammar, SyntaxTheme::new(Color::WHITE, Color::BLACK));
+        let styled = highlighter.highlight(source);
+        styled
+            .lines
+            .iter()
+            .flat_map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| {
+                        (
+                            line.text[span.start..span.end].to_string(),
+                            span.token_type.clone(),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn token_of(tokens: &[(String, Option<TokenType>)], text: &str) -> Option<TokenType> {
+        tokens
+            .iter()
+            .find(|(t, _)| t.trim() == text)
+            .unwrap_or_else(|| panic!("no token {text:?} in {tokens:?}"))
+            .1
+            .clone()
+    }
+
+    #[test]
+    fn test_bundled_grammars_compile() {
+        let registry = GrammarRegistry::bundled();
+        assert_eq!(registry.grammars().len(), BUNDLED.len());
+        for grammar in registry.grammars() {
+            assert_eq!(grammar.skipped_patterns(), 0, "{}", grammar.name());
+        }
+        for language in [
+            "toml",
+            "yaml",
+            "yml",
+            "md",
+            "markdown",
+            "js",
+            "javascript",
+            "ts",
+            "typescript",
+            "py",
+            "python",
+            "sql",
+            "sh",
+            "bash",
+            "shell",
+            "css",
+        ] {
+            assert!(registry.find(language).is_some(), "{language}");
+        }
+        assert!(registry.find("cobol").is_none());
+    }
+
+    #[test]
+    fn test_languages_tokenize() {
+        let toml = tokens("toml", "[server]\nport = 8080 # comment\nname = \"blinc\"");
+        assert_eq!(token_of(&toml, "8080"), Some(TokenType::Number));
+        assert_eq!(token_of(&toml, "# comment"), Some(TokenType::Comment));
+        assert_eq!(token_of(&toml, "\"blinc\""), Some(TokenType::String));
+
+        let py = tokens(
+            "python",
+            "def greet(name):\n    \"\"\"Say\n    hi\"\"\"\n    return None",
+        );
+        assert_eq!(token_of(&py, "def"), Some(TokenType::Keyword));
+        assert_eq!(token_of(&py, "greet"), Some(TokenType::Function));
+        assert_eq!(token_of(&py, "hi\"\"\""), Some(TokenType::String));
+        assert_eq!(token_of(&py, "return"), Some(TokenType::Keyword));
+
+        let ts = tokens("ts", "/* a\n b */ const x: number = `${y}`;");
+        assert_eq!(token_of(&ts, "b */"), Some(TokenType::Comment));
+        assert_eq!(token_of(&ts, "const"), Some(TokenType::Keyword));
+        assert_eq!(token_of(&ts, "number"), Some(TokenType::Type));
+
+        let sql = tokens("sql", "SELECT id FROM users -- all\nWHERE name = 'x'");
+        assert_eq!(token_of(&sql, "SELECT"), Some(TokenType::Keyword));
+        assert_eq!(token_of(&sql, "'x'"), Some(TokenType::String));
+
+        let sh = tokens("bash", "echo \"$HOME\" # done");
+        assert_eq!(token_of(&sh, "$HOME"), Some(TokenType::Variable));
+        assert_eq!(token_of(&sh, "# done"), Some(TokenType::Comment));
+
+        let css = tokens("css", ".card {\n  color: #fff;\n}");
+        assert_eq!(
+            token_of(&css, "#fff"),
+            Some(TokenType::custom("constant.other.color.rgb-value.css"))
+        );
+
+        let md = tokens("markdown", "# Title\n```\ncode\n```");
+        assert_eq!(token_of(&md, "code"), Some(TokenType::String));
+    }
+
+    #[test]
+    fn test_registered_grammar_takes_precedence() {
+        let mut registry = GrammarRegistry::bundled();
+        let grammar = Grammar::from_json(
+            r#"{ "name": "My TOML", "scopeName": "source.my-toml", "fileTypes": ["toml"], "patterns": [] }"#,
+        )
+        .unwrap();
+        registry.register(grammar);
+        assert_eq!(registry.find("toml").unwrap().name(), "My TOML");
+    }
+}
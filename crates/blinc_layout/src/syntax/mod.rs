@@ -23,6 +23,40 @@
 //! code("let x = 42;")
 //!     .config(SyntaxConfig::new(MyHighlighter { rules: vec![...] }))
 //! ```
+//!
+//! # Incremental highlighting
+//!
+//! Highlighters work line by line. A highlighter that needs context across
+//! lines (block comments, heredocs) overrides
+//! [`SyntaxHighlighter::highlight_line`] and carries it in a [`LineState`].
+//! [`HighlightCache`] uses those states to re-highlight only the lines an
+//! edit touched, plus any following lines whose starting state changed.
+//!
+//! # Grammars
+//!
+//! [`GrammarHighlighter`] tokenizes with a declarative TextMate [`Grammar`]
+//! and colors the resulting scopes with a [`SyntaxTheme`]. Grammars for TOML,
+//! YAML, Markdown, JavaScript, TypeScript, Python, SQL, shell and CSS are
+//! bundled, and more can be loaded at runtime:
+//!
+//! ```ignore
+//! register_grammar_json(include_str!("lua.tmLanguage.json"))?;
+//!
+//! code(source).syntax(SyntaxConfig::for_language("lua").unwrap())
+//! ```
+
+mod grammar;
+mod languages;
+mod theme;
+
+pub use grammar::{Grammar, GrammarError, ScopedToken};
+pub use languages::{
+    find_grammar, highlighter_for, register_grammar, register_grammar_json, GrammarHighlighter,
+    GrammarRegistry,
+};
+pub use theme::{ScopeStyle, SyntaxTheme};
+
+use std::ops::Range;
 
 use blinc_core::Color;
 use regex::Regex;
@@ -186,35 +220,198 @@ pub trait SyntaxHighlighter: Send + Sync {
         Color::rgba(0.45, 0.45, 0.5, 1.0)
     }
 
+    /// Highlight one line, given the state at its start
+    ///
+    /// Returns the styled line and the state at its end. The default applies
+    /// [`token_rules`](Self::token_rules) to the line alone and passes the
+    /// state through unchanged.
+    fn highlight_line(&self, line: &str, state: &LineState) -> (StyledLine, LineState) {
+        (
+            highlight_with_rules(line, self.token_rules(), self.default_color()),
+            state.clone(),
+        )
+    }
+
     /// Apply highlighting to text, returning styled text
     fn highlight(&self, text: &str) -> StyledText {
-        let default_color = self.default_color();
-        let rules = self.token_rules();
+        let mut state = LineState::default();
 
         // Handle empty string specially - .lines() returns empty iterator for ""
         if text.is_empty() {
-            return StyledText::from_lines(vec![highlight_line("", rules, default_color)]);
+            return StyledText::from_lines(vec![self.highlight_line("", &state).0]);
         }
 
         let lines = text
             .lines()
-            .map(|line| highlight_line(line, rules, default_color))
+            .map(|line| {
+                let (styled, next) = self.highlight_line(line, &state);
+                state = next;
+                styled
+            })
             .collect();
 
         StyledText::from_lines(lines)
     }
 }
 
+/// Highlighter state at a line boundary
+///
+/// Holds a stack of highlighter-defined context ids, e.g. "inside a block
+/// comment". The empty stack is the state at the start of a document;
+/// line-based highlighters never leave it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LineState(Vec<u32>);
+
+impl LineState {
+    /// State with the given context stack (innermost last)
+    pub fn from_stack(stack: Vec<u32>) -> Self {
+        Self(stack)
+    }
+
+    /// Context stack, innermost last
+    pub fn stack(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// Innermost context, if any
+    pub fn top(&self) -> Option<u32> {
+        self.0.last().copied()
+    }
+
+    /// Enter a context
+    pub fn push(&mut self, context: u32) {
+        self.0.push(context);
+    }
+
+    /// Leave the innermost context
+    pub fn pop(&mut self) -> Option<u32> {
+        self.0.pop()
+    }
+
+    /// Whether no context is open
+    pub fn is_initial(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Highlighted line with the states it was highlighted between
+#[derive(Clone, Debug)]
+struct CachedLine {
+    styled: StyledLine,
+    start: LineState,
+    end: LineState,
+}
+
+/// Per-line highlighting cache for edited documents
+///
+/// Call [`invalidate`](Self::invalidate) for every edit and
+/// [`update`](Self::update) before rendering. Only invalidated lines are
+/// highlighted again, and re-highlighting continues past them only while
+/// the state flowing into the next line differs from the cached one.
+#[derive(Clone, Debug, Default)]
+pub struct HighlightCache {
+    lines: Vec<Option<CachedLine>>,
+}
+
+impl HighlightCache {
+    /// Empty cache for a document of `line_count` lines
+    pub fn new(line_count: usize) -> Self {
+        Self {
+            lines: vec![None; line_count],
+        }
+    }
+
+    /// Drop everything, e.g. after switching highlighters
+    pub fn reset(&mut self, line_count: usize) {
+        *self = Self::new(line_count);
+    }
+
+    /// Record that `old_lines` lines starting at `first` were replaced by `new_lines` lines
+    pub fn invalidate(&mut self, first: usize, old_lines: usize, new_lines: usize) {
+        let first = first.min(self.lines.len());
+        let end = (first + old_lines).min(self.lines.len());
+        self.lines
+            .splice(first..end, std::iter::repeat(None).take(new_lines));
+    }
+
+    /// Highlight stale lines; `line_text` returns the text of a line
+    ///
+    /// Returns the range of lines that were highlighted again (empty if
+    /// nothing was stale).
+    pub fn update(
+        &mut self,
+        highlighter: &dyn SyntaxHighlighter,
+        line_text: impl Fn(usize) -> String,
+    ) -> Range<usize> {
+        let mut state = LineState::default();
+        let mut touched: Option<Range<usize>> = None;
+
+        for (i, slot) in self.lines.iter_mut().enumerate() {
+            if let Some(cached) = slot {
+                if cached.start == state {
+                    state.clone_from(&cached.end);
+                    continue;
+                }
+            }
+            let (styled, end) = highlighter.highlight_line(&line_text(i), &state);
+            let start = std::mem::replace(&mut state, end.clone());
+            *slot = Some(CachedLine { styled, start, end });
+            touched = Some(touched.map_or(i..i + 1, |r| r.start..i + 1));
+        }
+        touched.unwrap_or(0..0)
+    }
+
+    /// Number of lines
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Whether the cache covers no lines
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Highlighted line, if up to date
+    pub fn line(&self, line: usize) -> Option<&StyledLine> {
+        self.lines.get(line)?.as_ref().map(|cached| &cached.styled)
+    }
+
+    /// State at the end of a line, if up to date
+    pub fn end_state(&self, line: usize) -> Option<&LineState> {
+        self.lines.get(line)?.as_ref().map(|cached| &cached.end)
+    }
+}
+
 /// Configuration for syntax highlighting passed to code elements
+///
+/// Clones share the highlighter. Editors keep their highlighting cache while
+/// they are given the same highlighter, so keep one config around rather
+/// than creating a new one on every rebuild.
+#[derive(Clone)]
 pub struct SyntaxConfig {
-    highlighter: Box<dyn SyntaxHighlighter>,
+    highlighter: std::sync::Arc<dyn SyntaxHighlighter>,
 }
 
 impl SyntaxConfig {
     /// Create a new syntax config with the given highlighter
     pub fn new(highlighter: impl SyntaxHighlighter + 'static) -> Self {
         Self {
-            highlighter: Box::new(highlighter),
+            highlighter: std::sync::Arc::new(highlighter),
+        }
+    }
+
+    /// Highlighting for a language name or file extension
+    ///
+    /// Looks in the grammar registry first, then falls back to the built-in
+    /// Rust and JSON highlighters. Grammar highlighters use the current theme.
+    pub fn for_language(language: &str) -> Option<Self> {
+        if let Some(highlighter) = highlighter_for(language) {
+            return Some(Self::new(highlighter));
+        }
+        match language.trim().to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Some(Self::new(RustHighlighter::new())),
+            "json" | "jsonc" => Some(Self::new(JsonHighlighter::new())),
+            _ => None,
         }
     }
 
@@ -225,7 +422,7 @@ impl SyntaxConfig {
 
     /// Convert the config into an Arc-wrapped highlighter
     pub fn into_arc(self) -> std::sync::Arc<dyn SyntaxHighlighter> {
-        std::sync::Arc::from(self.highlighter)
+        self.highlighter
     }
 }
 
@@ -240,7 +437,11 @@ struct TokenMatch {
 }
 
 /// Highlight a single line of text using the given rules
-fn highlight_line(line: &str, rules: &[TokenRule], default_color: Color) -> StyledLine {
+pub(crate) fn highlight_with_rules(
+    line: &str,
+    rules: &[TokenRule],
+    default_color: Color,
+) -> StyledLine {
     if line.is_empty() {
         return StyledLine::new(line, vec![]);
     }
@@ -498,6 +699,74 @@ mod tests {
         assert!(!styled.lines[0].spans.is_empty());
     }
 
+    /// Highlights `/* ... */` comments across lines and counts calls
+    struct BlockCommentHighlighter {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl SyntaxHighlighter for BlockCommentHighlighter {
+        fn token_rules(&self) -> &[TokenRule] {
+            &[]
+        }
+
+        fn highlight_line(&self, line: &str, state: &LineState) -> (StyledLine, LineState) {
+            self.calls
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            let mut state = state.clone();
+            let mut rest = line;
+            while !rest.is_empty() {
+                let marker = if state.is_initial() { "/*" } else { "*/" };
+                let Some(at) = rest.find(marker) else { break };
+                if state.is_initial() {
+                    state.push(1);
+                } else {
+                    state.pop();
+                }
+                rest = &rest[at + 2..];
+            }
+            (StyledLine::new(line, vec![]), state)
+        }
+    }
+
+    #[test]
+    fn test_highlight_cache_stops_when_state_settles() {
+        let highlighter = BlockCommentHighlighter {
+            calls: Default::default(),
+        };
+        let calls = || {
+            highlighter
+                .calls
+                .swap(0, std::sync::atomic::Ordering::Relaxed)
+        };
+        let mut lines: Vec<String> = (0..10).map(|i| format!("line {i}")).collect();
+        let mut cache = HighlightCache::new(lines.len());
+
+        assert_eq!(cache.update(&highlighter, |i| lines[i].clone()), 0..10);
+        assert_eq!(calls(), 10);
+        assert_eq!(cache.update(&highlighter, |i| lines[i].clone()), 0..0);
+
+        // An edit that keeps the state only re-highlights that line
+        lines[4] = "edited".to_string();
+        cache.invalidate(4, 1, 1);
+        assert_eq!(cache.update(&highlighter, |i| lines[i].clone()), 4..5);
+
+        // Opening a comment re-highlights every following line
+        lines[2] = "/* open".to_string();
+        cache.invalidate(2, 1, 1);
+        assert_eq!(cache.update(&highlighter, |i| lines[i].clone()), 2..10);
+        assert_eq!(cache.end_state(9).and_then(LineState::top), Some(1));
+
+        // Closing it again settles after the closing line
+        lines.insert(3, "close */".to_string());
+        cache.invalidate(3, 0, 1);
+        calls();
+        assert_eq!(cache.update(&highlighter, |i| lines[i].clone()), 3..11);
+        lines[5] = "x".to_string();
+        cache.invalidate(5, 1, 1);
+        assert_eq!(cache.update(&highlighter, |i| lines[i].clone()), 5..6);
+        assert_eq!(calls(), 9);
+    }
+
     #[test]
     fn test_empty_line() {
         let highlighter = PlainHighlighter::new();
@@ -0,0 +1,186 @@
+//! Mapping from grammar scopes to text styles
+
+use blinc_core::Color;
+use blinc_theme::{ColorToken, ThemeState};
+
+/// Style applied to text in a scope
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ScopeStyle {
+    /// Text color, or the theme's default color if `None`
+    pub color: Option<Color>,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+impl ScopeStyle {
+    /// Style with a color
+    pub fn color(color: Color) -> Self {
+        Self {
+            color: Some(color),
+            ..Self::default()
+        }
+    }
+
+    /// Make text bold
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    /// Make text italic
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+}
+
+/// Colors for grammar scopes
+///
+/// Selectors are scope prefixes: `"string"` applies to `string.quoted.double`
+/// and `"keyword.operator"` overrides it for operators. The longest
+/// matching selector wins, and inner scopes win over outer ones.
+#[derive(Clone, Debug)]
+pub struct SyntaxTheme {
+    rules: Vec<(String, ScopeStyle)>,
+    /// Color of text no rule applies to
+    pub default_color: Color,
+    pub background_color: Color,
+    pub line_number_color: Color,
+}
+
+impl SyntaxTheme {
+    /// Theme with no scope rules
+    pub fn new(default_color: Color, background_color: Color) -> Self {
+        Self {
+            rules: Vec::new(),
+            default_color,
+            background_color,
+            line_number_color: Color::rgba(0.45, 0.45, 0.5, 1.0),
+        }
+    }
+
+    /// Theme built from the current `blinc_theme` colors
+    pub fn from_theme() -> Self {
+        let theme = ThemeState::get();
+        let color = |token| theme.color(token);
+
+        let keyword = ScopeStyle::color(color(ColorToken::Primary));
+        let string = ScopeStyle::color(color(ColorToken::Success));
+        let constant = ScopeStyle::color(color(ColorToken::Warning));
+        let ty = ScopeStyle::color(color(ColorToken::Info));
+        let function = ScopeStyle::color(color(ColorToken::Accent));
+        let key = ScopeStyle::color(color(ColorToken::Secondary));
+        let variable = ScopeStyle::color(color(ColorToken::Error));
+        let muted = ScopeStyle::color(color(ColorToken::TextSecondary));
+        let link = ScopeStyle::color(color(ColorToken::TextLink));
+
+        Self::new(color(ColorToken::TextPrimary), color(ColorToken::Surface))
+            .line_number_color(color(ColorToken::TextTertiary))
+            .rule(
+                "comment",
+                ScopeStyle::color(color(ColorToken::TextTertiary)).italic(),
+            )
+            .rule("keyword, storage, variable.language", keyword)
+            .rule("keyword.operator", muted)
+            .rule("string, markup.raw, markup.inline.raw", string)
+            .rule(
+                "constant, string.regexp, keyword.other.unit, constant.character.escape",
+                constant,
+            )
+            .rule(
+                "entity.name.type, entity.name.class, support.type, support.class",
+                ty,
+            )
+            .rule("entity.name.function, support.function", function)
+            .rule(
+                "entity.name.tag, entity.other.attribute-name, variable.other.key, \
+             support.type.property-name",
+                key,
+            )
+            .rule(
+                "variable.parameter, variable.other.readwrite, variable.other.anchor",
+                variable,
+            )
+            .rule("entity.name.section, markup.heading", keyword.bold())
+            .rule("markup.bold", ScopeStyle::default().bold())
+            .rule("markup.italic", ScopeStyle::default().italic())
+            .rule("markup.quote", muted.italic())
+            .rule("markup.underline.link, string.other.link", link)
+            .rule("invalid", variable)
+    }
+
+    /// Style scopes matching any of the comma-separated selectors
+    pub fn rule(mut self, selectors: &str, style: ScopeStyle) -> Self {
+        for selector in selectors
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            self.rules.retain(|(existing, _)| existing != selector);
+            self.rules.push((selector.to_string(), style));
+        }
+        self
+    }
+
+    /// Set the line number color
+    pub fn line_number_color(mut self, color: Color) -> Self {
+        self.line_number_color = color;
+        self
+    }
+
+    /// Style for text in `scopes`, given outermost first
+    pub fn style_for<S: AsRef<str>>(&self, scopes: &[S]) -> ScopeStyle {
+        scopes
+            .iter()
+            .rev()
+            .find_map(|scope| self.style_for_scope(scope.as_ref()))
+            .unwrap_or_default()
+    }
+
+    fn style_for_scope(&self, scope: &str) -> Option<ScopeStyle> {
+        self.rules
+            .iter()
+            .filter(|(selector, _)| selector_matches(selector, scope))
+            .max_by_key(|(selector, _)| selector.len())
+            .map(|(_, style)| *style)
+    }
+}
+
+impl Default for SyntaxTheme {
+    fn default() -> Self {
+        Self::from_theme()
+    }
+}
+
+/// Whether `selector` is `scope` or a dot-separated prefix of it
+fn selector_matches(selector: &str, scope: &str) -> bool {
+    scope
+        .strip_prefix(selector)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_specific_selector_wins() {
+        let red = Color::rgba(1.0, 0.0, 0.0, 1.0);
+        let blue = Color::rgba(0.0, 0.0, 1.0, 1.0);
+        let theme = SyntaxTheme::new(Color::WHITE, Color::BLACK)
+            .rule("keyword", ScopeStyle::color(red))
+            .rule("keyword.operator", ScopeStyle::color(blue).bold());
+
+        assert_eq!(
+            theme.style_for(&["source.x", "keyword.control.x"]).color,
+            Some(red)
+        );
+        assert!(theme.style_for(&["source.x", "keyword.operator.x"]).bold);
+        assert_eq!(theme.style_for(&["source.x", "keywords"]).color, None);
+        // Inner scopes win even with a shorter selector
+        assert_eq!(
+            theme.style_for(&["keyword.operator.x", "keyword.x"]).color,
+            Some(red)
+        );
+    }
+}
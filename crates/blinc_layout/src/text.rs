@@ -25,8 +25,8 @@ use html_escape::decode_html_entities;
 use taffy::prelude::*;
 
 use crate::div::{
-    ElementBuilder, ElementTypeId, FontFamily, FontWeight, TextAlign, TextRenderInfo,
-    TextVerticalAlign,
+    ElementBuilder, ElementTypeId, FontFamily, FontWeight, Hyphens, ParagraphStyle, TextAlign,
+    TextJustify, TextOverflow, TextRenderInfo, TextVerticalAlign, WhiteSpace,
 };
 use crate::element::{RenderLayer, RenderProps};
 use crate::tree::{LayoutNodeId, LayoutTree};
@@ -73,6 +73,10 @@ pub struct Text {
     strikethrough: bool,
     /// Whether text has underline decoration
     underline: bool,
+    /// Paragraph layout: white-space, ellipsis, clamping, hyphenation
+    paragraph: ParagraphStyle,
+    /// OpenType features and variable font axes
+    font_settings: blinc_core::FontSettings,
     /// Whether this element is transparent to hit-testing
     pointer_events_none: bool,
     /// Cursor style when hovering over this text (default: Text cursor)
@@ -117,6 +121,8 @@ impl Text {
             ascender: 14.0 * 0.8, // will be set by update_size_estimate
             strikethrough: false,
             underline: false,
+            paragraph: ParagraphStyle::default(),
+            font_settings: blinc_core::FontSettings::default(),
             pointer_events_none: false,
             cursor: Some(crate::element::CursorStyle::Text), // Text cursor by default
             element_id: None,
@@ -178,6 +184,111 @@ impl Text {
         self.align(TextAlign::Right)
     }
 
+    /// Align text to the start edge of its direction (right for RTL text)
+    pub fn text_start(self) -> Self {
+        self.align(TextAlign::Start)
+    }
+
+    /// Align text to the end edge of its direction (left for RTL text)
+    pub fn text_end(self) -> Self {
+        self.align(TextAlign::End)
+    }
+
+    /// Justify wrapped lines to the full width
+    pub fn text_justify(self) -> Self {
+        self.align(TextAlign::Justify)
+    }
+
+    /// Set how justified text distributes extra space
+    pub fn justify_mode(mut self, mode: TextJustify) -> Self {
+        self.paragraph.justify = Some(mode);
+        self
+    }
+
+    // =========================================================================
+    // Paragraph Layout
+    // =========================================================================
+
+    /// Set white-space handling (collapsing and wrapping)
+    pub fn white_space(mut self, white_space: WhiteSpace) -> Self {
+        self.paragraph.white_space = Some(white_space);
+        self
+    }
+
+    /// Set how lines wider than the element are shown
+    pub fn text_overflow(mut self, overflow: TextOverflow) -> Self {
+        self.paragraph.overflow = Some(overflow);
+        self
+    }
+
+    /// Keep text on one line and end it with an ellipsis if it overflows
+    pub fn ellipsis(mut self) -> Self {
+        self.paragraph.white_space = Some(WhiteSpace::NoWrap);
+        self.text_overflow(TextOverflow::Ellipsis)
+    }
+
+    /// Limit text to `lines` lines, ending the last one with an ellipsis
+    pub fn line_clamp(mut self, lines: u32) -> Self {
+        self.paragraph.line_clamp = Some(lines);
+        self
+    }
+
+    /// Set the hyphenation mode
+    pub fn hyphens(mut self, hyphens: Hyphens) -> Self {
+        self.paragraph.hyphens = Some(hyphens);
+        self
+    }
+
+    /// Hyphenate words automatically (uses the language set with [`Text::lang`])
+    pub fn hyphenate(self) -> Self {
+        self.hyphens(Hyphens::Auto)
+    }
+
+    /// Set the language of the text (e.g. "en-US"), used for hyphenation
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.paragraph.lang = Some(lang.into());
+        self
+    }
+
+    // =========================================================================
+    // OpenType Features & Variable Fonts
+    // =========================================================================
+
+    /// Set an OpenType feature (e.g. `"tnum"`, `"ss01"`; 0 disables)
+    ///
+    /// Invalid tags (not 1-4 ASCII characters) are ignored.
+    pub fn font_feature(mut self, tag: &str, value: u32) -> Self {
+        if let Some(feature) = blinc_core::FontFeature::new(tag, value) {
+            self.font_settings.set_feature(feature);
+            self.update_size_estimate();
+        }
+        self
+    }
+
+    /// Use tabular (fixed-width) digits, for columns of numbers and counters
+    pub fn tabular_nums(self) -> Self {
+        self.font_feature("tnum", 1)
+    }
+
+    /// Set a variable font axis (e.g. `"wght"`, `"wdth"`, `"opsz"`)
+    ///
+    /// Values are clamped to the axis range of the resolved font; axes the
+    /// font doesn't have are ignored.
+    pub fn font_variation(mut self, tag: &str, value: f32) -> Self {
+        if let Some(variation) = blinc_core::FontVariation::new(tag, value) {
+            self.font_settings.set_variation(variation);
+            self.update_size_estimate();
+        }
+        self
+    }
+
+    /// Replace all OpenType features and variation axes
+    pub fn font_settings(mut self, settings: blinc_core::FontSettings) -> Self {
+        self.font_settings = settings;
+        self.update_size_estimate();
+        self
+    }
+
     // =========================================================================
     // Vertical Alignment
     // =========================================================================
@@ -440,6 +551,8 @@ impl Text {
         options.generic_font = self.font_family.generic;
         options.font_weight = self.weight.weight();
         options.italic = self.italic;
+        options.paragraph = self.paragraph.clone();
+        options.font_settings = self.font_settings.clone();
 
         let metrics =
             crate::text_measure::measure_text_with_options(&self.content, self.font_size, &options);
@@ -597,6 +710,8 @@ impl ElementBuilder for Text {
                 generic_font: self.font_family.generic,
                 font_weight: self.weight.weight(),
                 italic: self.italic,
+                paragraph: self.paragraph.clone(),
+                font_settings: self.font_settings.clone(),
             };
             tree.create_text_node(self.style.clone(), context)
         } else {
@@ -651,6 +766,8 @@ impl ElementBuilder for Text {
             ascender: self.ascender,
             strikethrough: self.strikethrough,
             underline: self.underline,
+            paragraph: self.paragraph.clone(),
+            font_settings: self.font_settings.clone(),
         })
     }
 
@@ -0,0 +1,650 @@
+//! Rope-backed text buffer for editable text widgets
+//!
+//! [`TextBuffer`] stores text in a [`ropey::Rope`], so inserts and deletes
+//! cost O(log n) regardless of document size, and cloning a buffer (for undo
+//! snapshots) is O(1) thanks to structural sharing. It is used by
+//! [`TextArea`](crate::widgets::TextArea) and the editable
+//! [`code`](crate::widgets::code()) widget.
+//!
+//! Positions are char offsets unless stated otherwise; conversions to and
+//! from byte offsets, UTF-16 code units (for platform text APIs) and
+//! `(line, column)` pairs are provided. Lines end at `\n`, `\r\n` or `\r`,
+//! and line text never includes its terminator.
+//!
+//! Every edit bumps [`revision`](TextBuffer::revision), which makes it cheap
+//! to tell whether the text changed between two points in time.
+//!
+//! [`LineRows`] complements the buffer for wrapped layouts: it holds the
+//! visual rows of each line and maps between row and line indices without
+//! walking the whole document.
+
+use std::fmt;
+use std::ops::Range;
+
+use ropey::{Rope, RopeSlice};
+
+/// Editable text stored in a rope
+#[derive(Clone, Default)]
+pub struct TextBuffer {
+    rope: Rope,
+    revision: u64,
+}
+
+impl TextBuffer {
+    /// Empty buffer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Buffer holding `text`
+    pub fn from_text(text: &str) -> Self {
+        Self {
+            rope: Rope::from_str(text),
+            revision: 0,
+        }
+    }
+
+    /// The underlying rope
+    pub fn rope(&self) -> &Rope {
+        &self.rope
+    }
+
+    /// Counter bumped by every edit
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Whether the buffer holds no text
+    pub fn is_empty(&self) -> bool {
+        self.rope.len_bytes() == 0
+    }
+
+    /// Length in chars
+    pub fn len_chars(&self) -> usize {
+        self.rope.len_chars()
+    }
+
+    /// Length in bytes of UTF-8
+    pub fn len_bytes(&self) -> usize {
+        self.rope.len_bytes()
+    }
+
+    /// Length in UTF-16 code units
+    pub fn len_utf16(&self) -> usize {
+        self.rope.len_utf16_cu()
+    }
+
+    /// Number of lines; a trailing line break starts a final empty line
+    pub fn len_lines(&self) -> usize {
+        self.rope.len_lines()
+    }
+
+    /// Line `line` without its terminator (empty past the end)
+    pub fn line_slice(&self, line: usize) -> RopeSlice<'_> {
+        if line >= self.len_lines() {
+            return self.rope.slice(0..0);
+        }
+        let slice = self.rope.line(line);
+        let len = slice.len_chars() - terminator_len(slice);
+        slice.slice(..len)
+    }
+
+    /// Line `line` as a string, without its terminator
+    pub fn line(&self, line: usize) -> String {
+        self.line_slice(line).to_string()
+    }
+
+    /// Length of line `line` in chars, without its terminator
+    pub fn line_len(&self, line: usize) -> usize {
+        self.line_slice(line).len_chars()
+    }
+
+    /// Iterate over all lines, without terminators
+    pub fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.len_lines()).map(|i| self.line(i))
+    }
+
+    /// Text in char range `range`
+    pub fn slice(&self, range: Range<usize>) -> String {
+        let range = self.clamp_range(range);
+        self.rope.slice(range).to_string()
+    }
+
+    // =========================================================================
+    // Index conversions (all clamp out-of-range input)
+    // =========================================================================
+
+    /// Char offset of `(line, column)`, clamping the column to the line length
+    pub fn position_to_char(&self, line: usize, column: usize) -> usize {
+        let line = line.min(self.len_lines().saturating_sub(1));
+        self.rope.line_to_char(line) + column.min(self.line_len(line))
+    }
+
+    /// `(line, column)` of a char offset
+    pub fn char_to_position(&self, char_idx: usize) -> (usize, usize) {
+        let char_idx = char_idx.min(self.len_chars());
+        let line = self.rope.char_to_line(char_idx);
+        let column = (char_idx - self.rope.line_to_char(line)).min(self.line_len(line));
+        (line, column)
+    }
+
+    /// Char offset to byte offset
+    pub fn char_to_byte(&self, char_idx: usize) -> usize {
+        self.rope.char_to_byte(char_idx.min(self.len_chars()))
+    }
+
+    /// Byte offset to char offset (a byte inside a char maps to that char)
+    pub fn byte_to_char(&self, byte_idx: usize) -> usize {
+        self.rope.byte_to_char(byte_idx.min(self.len_bytes()))
+    }
+
+    /// Line holding char offset `char_idx`
+    pub fn char_to_line(&self, char_idx: usize) -> usize {
+        self.rope.char_to_line(char_idx.min(self.len_chars()))
+    }
+
+    /// Char offset of the start of line `line` (the end of the text past the last line)
+    pub fn line_to_char(&self, line: usize) -> usize {
+        self.rope.line_to_char(line.min(self.len_lines()))
+    }
+
+    /// Line holding byte offset `byte_idx`
+    pub fn byte_to_line(&self, byte_idx: usize) -> usize {
+        self.rope.byte_to_line(byte_idx.min(self.len_bytes()))
+    }
+
+    /// Byte offset of the start of line `line` (the end of the text past the last line)
+    pub fn line_to_byte(&self, line: usize) -> usize {
+        self.rope.line_to_byte(line.min(self.len_lines()))
+    }
+
+    /// Char offset to UTF-16 code unit offset
+    pub fn char_to_utf16(&self, char_idx: usize) -> usize {
+        self.rope.char_to_utf16_cu(char_idx.min(self.len_chars()))
+    }
+
+    /// UTF-16 code unit offset to char offset
+    pub fn utf16_to_char(&self, utf16_idx: usize) -> usize {
+        self.rope.utf16_cu_to_char(utf16_idx.min(self.len_utf16()))
+    }
+
+    // =========================================================================
+    // Editing
+    // =========================================================================
+
+    /// Insert `text` at char offset `char_idx`
+    pub fn insert(&mut self, char_idx: usize, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.rope.insert(char_idx.min(self.len_chars()), text);
+        self.revision += 1;
+    }
+
+    /// Remove the chars in `range`
+    pub fn remove(&mut self, range: Range<usize>) {
+        let range = self.clamp_range(range);
+        if range.is_empty() {
+            return;
+        }
+        self.rope.remove(range);
+        self.revision += 1;
+    }
+
+    /// Replace the chars in `range` with `text`
+    pub fn replace(&mut self, range: Range<usize>, text: &str) {
+        let range = self.clamp_range(range);
+        let start = range.start;
+        self.remove(range);
+        self.insert(start, text);
+    }
+
+    /// Replace the whole text
+    pub fn set_text(&mut self, text: &str) {
+        self.rope = Rope::from_str(text);
+        self.revision += 1;
+    }
+
+    fn clamp_range(&self, range: Range<usize>) -> Range<usize> {
+        let len = self.len_chars();
+        let end = range.end.min(len);
+        range.start.min(end)..end
+    }
+}
+
+/// Length of the line terminator at the end of `line`, in chars
+fn terminator_len(line: RopeSlice<'_>) -> usize {
+    let len = line.len_chars();
+    match (
+        len.checked_sub(2).map(|i| line.char(i)),
+        len.checked_sub(1).map(|i| line.char(i)),
+    ) {
+        (Some('\r'), Some('\n')) => 2,
+        (_, Some('\n' | '\r')) => 1,
+        _ => 0,
+    }
+}
+
+impl From<&str> for TextBuffer {
+    fn from(text: &str) -> Self {
+        Self::from_text(text)
+    }
+}
+
+impl From<String> for TextBuffer {
+    fn from(text: String) -> Self {
+        Self::from_text(&text)
+    }
+}
+
+impl PartialEq for TextBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.rope == other.rope
+    }
+}
+
+impl fmt::Display for TextBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.rope.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for TextBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextBuffer")
+            .field("len_chars", &self.len_chars())
+            .field("len_lines", &self.len_lines())
+            .field("revision", &self.revision)
+            .finish()
+    }
+}
+
+// =============================================================================
+// Rows per line
+// =============================================================================
+
+/// Lines per [`LineRows`] block; blocks split at twice this size
+const BLOCK_LINES: usize = 256;
+
+/// Visual rows of each line of a document, indexed by row
+///
+/// Lines are kept in blocks of up to a few hundred lines that cache their row
+/// count, so finding the line at a row (or the first row of a line) skips
+/// whole blocks, and replacing the rows of some lines only touches their
+/// block. Rows don't record their line number, so inserting or removing
+/// lines never rewrites the rows after them.
+///
+/// Every line should hold at least one row.
+#[derive(Clone, Debug)]
+pub struct LineRows<T> {
+    blocks: Vec<RowBlock<T>>,
+    len_lines: usize,
+    len_rows: usize,
+}
+
+#[derive(Clone, Debug)]
+struct RowBlock<T> {
+    lines: Vec<Vec<T>>,
+    rows: usize,
+}
+
+impl<T> Default for LineRows<T> {
+    fn default() -> Self {
+        Self {
+            blocks: Vec::new(),
+            len_lines: 0,
+            len_rows: 0,
+        }
+    }
+}
+
+impl<T> LineRows<T> {
+    /// Empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index holding `lines`, each given as its rows
+    pub fn from_lines(lines: impl IntoIterator<Item = Vec<T>>) -> Self {
+        let mut index = Self::new();
+        index.splice_lines(0..0, lines);
+        index
+    }
+
+    /// Whether the index holds no lines
+    pub fn is_empty(&self) -> bool {
+        self.len_lines == 0
+    }
+
+    /// Number of lines
+    pub fn len_lines(&self) -> usize {
+        self.len_lines
+    }
+
+    /// Number of rows across all lines
+    pub fn len_rows(&self) -> usize {
+        self.len_rows
+    }
+
+    /// Remove every line
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Rows of line `line` (empty past the end)
+    pub fn line(&self, line: usize) -> &[T] {
+        if line >= self.len_lines {
+            return &[];
+        }
+        let (block, offset) = self.block_of_line(line);
+        &self.blocks[block].lines[offset]
+    }
+
+    /// Index of the first row of line `line` (the row count past the end)
+    pub fn first_row(&self, line: usize) -> usize {
+        if line >= self.len_lines {
+            return self.len_rows;
+        }
+        let mut rows = 0;
+        let mut first_line = 0;
+        for block in &self.blocks {
+            if line < first_line + block.lines.len() {
+                let before = &block.lines[..line - first_line];
+                return rows + before.iter().map(Vec::len).sum::<usize>();
+            }
+            first_line += block.lines.len();
+            rows += block.rows;
+        }
+        self.len_rows
+    }
+
+    /// Line holding row `row` and the row's index within that line
+    pub fn locate(&self, row: usize) -> Option<(usize, usize)> {
+        if row >= self.len_rows {
+            return None;
+        }
+        let (block, mut line, mut offset) = self.block_of_row(row);
+        for rows in &self.blocks[block].lines {
+            if offset < rows.len() {
+                return Some((line, offset));
+            }
+            offset -= rows.len();
+            line += 1;
+        }
+        None
+    }
+
+    /// Row `row` and the line it belongs to
+    pub fn row(&self, row: usize) -> Option<(usize, &T)> {
+        let (line, offset) = self.locate(row)?;
+        Some((line, &self.line(line)[offset]))
+    }
+
+    /// Rows in `rows`, each with the line it belongs to
+    pub fn rows(&self, rows: Range<usize>) -> impl Iterator<Item = (usize, &T)> + '_ {
+        let rows = rows.start.min(self.len_rows)..rows.end.min(self.len_rows);
+        let (block, first_line, skip) = if rows.is_empty() {
+            (self.blocks.len(), 0, 0)
+        } else {
+            self.block_of_row(rows.start)
+        };
+        self.blocks[block..]
+            .iter()
+            .flat_map(|block| block.lines.iter())
+            .zip(first_line..)
+            .flat_map(|(line_rows, line)| line_rows.iter().map(move |row| (line, row)))
+            .skip(skip)
+            .take(rows.len())
+    }
+
+    /// All rows, each with the line it belongs to
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> + '_ {
+        self.rows(0..self.len_rows)
+    }
+
+    /// Replace the rows of line `line`
+    pub fn set_line(&mut self, line: usize, rows: Vec<T>) {
+        self.splice_lines(line..line + 1, Some(rows));
+    }
+
+    /// Replace the lines in `lines` with `new_lines`, each given as its rows
+    ///
+    /// Only the blocks holding the replaced lines change; lines after them
+    /// move to new indices without being touched.
+    pub fn splice_lines(
+        &mut self,
+        lines: Range<usize>,
+        new_lines: impl IntoIterator<Item = Vec<T>>,
+    ) {
+        let end = lines.end.min(self.len_lines);
+        let start = lines.start.min(end);
+
+        if self.blocks.is_empty() {
+            self.blocks.push(RowBlock {
+                lines: Vec::new(),
+                rows: 0,
+            });
+        }
+        let (block, offset) = if start == self.len_lines {
+            let last = self.blocks.len() - 1;
+            (last, self.blocks[last].lines.len())
+        } else {
+            self.block_of_line(start)
+        };
+
+        // Remove the old lines, possibly spanning several blocks
+        let mut remaining = end - start;
+        let mut tail = Vec::new();
+        {
+            let first = &mut self.blocks[block];
+            let take = remaining.min(first.lines.len() - offset);
+            let removed: usize = first
+                .lines
+                .drain(offset..offset + take)
+                .map(|r| r.len())
+                .sum();
+            first.rows -= removed;
+            self.len_rows -= removed;
+            self.len_lines -= take;
+            remaining -= take;
+            if remaining == 0 {
+                tail = first.lines.split_off(offset);
+                first.rows -= tail.iter().map(Vec::len).sum::<usize>();
+            }
+        }
+        while remaining > 0 {
+            let next = &mut self.blocks[block + 1];
+            let take = remaining.min(next.lines.len());
+            let removed: usize = next.lines.drain(..take).map(|r| r.len()).sum();
+            next.rows -= removed;
+            self.len_rows -= removed;
+            self.len_lines -= take;
+            remaining -= take;
+            if next.lines.is_empty() {
+                self.blocks.remove(block + 1);
+            }
+        }
+
+        // Insert the new lines followed by the rest of the first block
+        let first = &mut self.blocks[block];
+        for line_rows in new_lines {
+            debug_assert!(!line_rows.is_empty(), "every line needs a row");
+            first.rows += line_rows.len();
+            self.len_rows += line_rows.len();
+            self.len_lines += 1;
+            first.lines.push(line_rows);
+        }
+        first.rows += tail.iter().map(Vec::len).sum::<usize>();
+        first.lines.append(&mut tail);
+
+        self.rebalance(block);
+    }
+
+    /// Split an oversized block and drop or merge an undersized one
+    fn rebalance(&mut self, block: usize) {
+        let len = self.blocks[block].lines.len();
+        if len > BLOCK_LINES * 2 {
+            let mut lines = std::mem::take(&mut self.blocks[block].lines);
+            let mut chunks = Vec::new();
+            while !lines.is_empty() {
+                let rest = lines.split_off(BLOCK_LINES.min(lines.len()));
+                let rows = lines.iter().map(Vec::len).sum();
+                chunks.push(RowBlock { lines, rows });
+                lines = rest;
+            }
+            self.blocks.splice(block..block + 1, chunks);
+        } else if len == 0 {
+            self.blocks.remove(block);
+        } else if len < BLOCK_LINES / 2
+            && block + 1 < self.blocks.len()
+            && len + self.blocks[block + 1].lines.len() <= BLOCK_LINES * 2
+        {
+            let next = self.blocks.remove(block + 1);
+            let merged = &mut self.blocks[block];
+            merged.rows += next.rows;
+            merged.lines.extend(next.lines);
+        }
+    }
+
+    /// Block holding line `line` (which must exist) and the line's offset in it
+    fn block_of_line(&self, mut line: usize) -> (usize, usize) {
+        for (idx, block) in self.blocks.iter().enumerate() {
+            if line < block.lines.len() {
+                return (idx, line);
+            }
+            line -= block.lines.len();
+        }
+        unreachable!("line out of range")
+    }
+
+    /// Block holding row `row` (which must exist), the block's first line and
+    /// the row's offset in the block
+    fn block_of_row(&self, mut row: usize) -> (usize, usize, usize) {
+        let mut first_line = 0;
+        for (idx, block) in self.blocks.iter().enumerate() {
+            if row < block.rows {
+                return (idx, first_line, row);
+            }
+            row -= block.rows;
+            first_line += block.lines.len();
+        }
+        unreachable!("row out of range")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_and_positions() {
+        let buffer = TextBuffer::from_text("héllo\r\nwörld\n");
+        assert_eq!(buffer.len_lines(), 3);
+        assert_eq!(buffer.line(0), "héllo");
+        assert_eq!(buffer.line(1), "wörld");
+        assert_eq!(buffer.line(2), "");
+        assert_eq!(buffer.line_len(0), 5);
+
+        assert_eq!(buffer.position_to_char(1, 2), 9);
+        assert_eq!(buffer.char_to_position(9), (1, 2));
+        // Columns clamp to the line, before the terminator
+        assert_eq!(buffer.position_to_char(0, 99), 5);
+        assert_eq!(buffer.char_to_position(6), (0, 5));
+        assert_eq!(buffer.position_to_char(9, 0), buffer.len_chars());
+    }
+
+    #[test]
+    fn test_index_conversions() {
+        let buffer = TextBuffer::from_text("a😀b\nc");
+        assert_eq!(buffer.char_to_byte(2), 5);
+        assert_eq!(buffer.byte_to_char(5), 2);
+        // The emoji takes two UTF-16 code units
+        assert_eq!(buffer.char_to_utf16(2), 3);
+        assert_eq!(buffer.utf16_to_char(3), 2);
+        assert_eq!(buffer.len_utf16(), 6);
+        assert_eq!(buffer.char_to_line(4), 1);
+        assert_eq!(buffer.line_to_byte(1), 7);
+        assert_eq!(buffer.byte_to_line(7), 1);
+    }
+
+    #[test]
+    fn test_edits_bump_revision() {
+        let mut buffer = TextBuffer::from_text("hello world");
+        let snapshot = buffer.clone();
+
+        buffer.replace(0..5, "goodbye");
+        assert_eq!(buffer.to_string(), "goodbye world");
+        buffer.insert(99, "!");
+        buffer.remove(7..13);
+        assert_eq!(buffer.to_string(), "goodbye!");
+        assert_eq!(buffer.revision(), 4);
+
+        // No-op edits leave the revision alone
+        buffer.remove(3..3);
+        buffer.insert(0, "");
+        assert_eq!(buffer.revision(), 4);
+
+        // Snapshots are unaffected by later edits
+        assert_eq!(snapshot.to_string(), "hello world");
+        assert_ne!(snapshot, buffer);
+    }
+
+    #[test]
+    fn test_line_rows_maps_rows_and_lines() {
+        let mut rows = LineRows::from_lines(vec![vec!['a'], vec!['b', 'c'], vec!['d']]);
+        assert_eq!((rows.len_lines(), rows.len_rows()), (3, 4));
+        assert_eq!(rows.first_row(1), 1);
+        assert_eq!(rows.first_row(2), 3);
+        assert_eq!(rows.locate(2), Some((1, 1)));
+        assert_eq!(rows.row(3), Some((2, &'d')));
+        assert_eq!(rows.locate(4), None);
+
+        // Replace line 1 with three lines; the row after keeps its content
+        rows.splice_lines(1..2, vec![vec!['x'], vec!['y', 'z'], vec!['w']]);
+        let all: Vec<(usize, char)> = rows.iter().map(|(line, c)| (line, *c)).collect();
+        assert_eq!(
+            all,
+            vec![(0, 'a'), (1, 'x'), (2, 'y'), (2, 'z'), (3, 'w'), (4, 'd')]
+        );
+        rows.set_line(0, vec!['a', 'b']);
+        assert_eq!(rows.rows(1..3).map(|(_, c)| *c).collect::<String>(), "bx");
+        rows.splice_lines(0..5, vec![vec!['q']]);
+        assert_eq!((rows.len_lines(), rows.len_rows()), (1, 1));
+    }
+
+    #[test]
+    fn test_line_rows_across_blocks() {
+        let n = BLOCK_LINES * 5 + 7;
+        let mut rows = LineRows::from_lines((0..n).map(|i| vec![i; 1 + i % 3]));
+        let expected_rows: usize = (0..n).map(|i| 1 + i % 3).sum();
+        assert_eq!(rows.len_rows(), expected_rows);
+        let row = rows.first_row(n - 1);
+        assert_eq!(rows.row(row), Some((n - 1, &(n - 1))));
+
+        // Remove a range spanning several blocks, then insert at the end
+        rows.splice_lines(10..BLOCK_LINES * 3, std::iter::empty());
+        rows.splice_lines(rows.len_lines()..rows.len_lines(), vec![vec![usize::MAX]]);
+        let lines: Vec<usize> = rows.iter().map(|(line, _)| line).collect();
+        assert!(lines.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(rows.len_lines(), n - (BLOCK_LINES * 3 - 10) + 1);
+        assert_eq!(rows.line(10)[0], BLOCK_LINES * 3);
+        assert_eq!(rows.line(rows.len_lines() - 1), &[usize::MAX]);
+        let expected_rows: usize = rows.iter().count();
+        assert_eq!(rows.len_rows(), expected_rows);
+    }
+
+    #[test]
+    fn test_large_document() {
+        let text: String = (0..100_000).map(|i| format!("line {i}\n")).collect();
+        let mut buffer = TextBuffer::from_text(&text);
+        assert_eq!(buffer.len_lines(), 100_001);
+
+        let at = buffer.position_to_char(50_000, 4);
+        buffer.insert(at, " fifty thousand");
+        assert_eq!(buffer.line(50_000), "line fifty thousand 50000");
+        assert_eq!(buffer.line(99_999), "line 99999");
+    }
+}
@@ -22,6 +22,10 @@ pub struct TextLayoutOptions {
     pub font_weight: u16,
     /// Whether text is italic
     pub italic: bool,
+    /// Paragraph layout settings (white-space, clamping, hyphenation)
+    pub paragraph: crate::div::ParagraphStyle,
+    /// OpenType features and variable font axes
+    pub font_settings: blinc_core::FontSettings,
 }
 
 impl TextLayoutOptions {
@@ -36,6 +40,8 @@ impl TextLayoutOptions {
             generic_font: crate::div::GenericFont::System,
             font_weight: 400,
             italic: false,
+            paragraph: crate::div::ParagraphStyle::default(),
+            font_settings: blinc_core::FontSettings::default(),
         }
     }
 
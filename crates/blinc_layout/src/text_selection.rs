@@ -3,9 +3,14 @@
 //! Provides a centralized location to track what text is currently selected
 //! across all text input widgets. This enables clipboard operations (copy/cut/paste)
 //! to work with any focused text input.
+//!
+//! Also provides caret and selection geometry for bidirectional text, shared
+//! by the text input and text area widgets.
 
 use std::sync::{Arc, Mutex, OnceLock};
 
+use unicode_bidi::BidiInfo;
+
 /// Source of the selected text
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SelectionSource {
@@ -13,6 +18,8 @@ pub enum SelectionSource {
     TextInput,
     /// Selection from a text area widget
     TextArea,
+    /// Selection from a rich text editor
+    RichText,
     /// Selection from static/label text
     StaticText,
 }
@@ -105,6 +112,176 @@ pub fn can_cut_selection() -> bool {
     guard.can_cut
 }
 
+// ============================================================================
+// Bidi caret geometry
+// ============================================================================
+
+/// Caret and selection geometry for a single line of bidirectional text
+///
+/// Maps logical character indices to on-screen x positions after the
+/// Unicode Bidirectional Algorithm has reordered the line. Only built for
+/// text containing right-to-left characters; plain left-to-right text keeps
+/// the prefix-width measurements the widgets already use.
+#[derive(Debug, Clone)]
+pub struct BidiCaretMap {
+    /// Per logical character: (visual left edge, advance, embedding level)
+    chars: Vec<(f32, f32, u8)>,
+    /// Total line width
+    width: f32,
+}
+
+impl BidiCaretMap {
+    /// Build the map for a line, measuring prefixes with `measure`
+    ///
+    /// Returns `None` when the text has no right-to-left content.
+    pub fn new(text: &str, measure: impl Fn(&str) -> f32) -> Option<Self> {
+        let info = BidiInfo::new(text, None);
+        if !info.has_rtl() {
+            return None;
+        }
+
+        // Logical advances from prefix width differences
+        let offsets: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+        let mut advances = Vec::with_capacity(offsets.len());
+        let mut prev = 0.0;
+        for n in 0..offsets.len() {
+            let end = offsets.get(n + 1).copied().unwrap_or(text.len());
+            let width = measure(&text[..end]);
+            advances.push((width - prev).max(0.0));
+            prev = width;
+        }
+
+        // Lay characters out left to right in visual order
+        let mut chars = vec![(0.0, 0.0, 0); offsets.len()];
+        let mut x = 0.0;
+        for para in &info.paragraphs {
+            let (levels, runs) = info.visual_runs(para, para.range.clone());
+            for run in runs {
+                let start = offsets.partition_point(|&b| b < run.start);
+                let end = offsets.partition_point(|&b| b < run.end);
+                let mut indices: Vec<usize> = (start..end).collect();
+                if levels[run.start].is_rtl() {
+                    indices.reverse();
+                }
+                for i in indices {
+                    chars[i] = (x, advances[i], levels[offsets[i]].number());
+                    x += advances[i];
+                }
+            }
+        }
+
+        Some(Self { chars, width: x })
+    }
+
+    /// Total width of the line
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// X position of the caret before the character at `index`
+    ///
+    /// The caret sits on the leading edge of that character: its left edge
+    /// in left-to-right runs and its right edge in right-to-left runs. At the
+    /// end of the text it sits on the trailing edge of the last character.
+    pub fn caret_x(&self, index: usize) -> f32 {
+        match self.chars.get(index) {
+            Some(&(x, w, level)) if level % 2 == 1 => x + w,
+            Some(&(x, _, _)) => x,
+            None => match self.chars.last() {
+                Some(&(x, _, level)) if level % 2 == 1 => x,
+                Some(&(x, w, _)) => x + w,
+                None => 0.0,
+            },
+        }
+    }
+
+    /// Character index whose caret is closest to `x` (hit testing)
+    pub fn index_at_x(&self, x: f32) -> usize {
+        (0..=self.chars.len())
+            .min_by(|&a, &b| {
+                (self.caret_x(a) - x)
+                    .abs()
+                    .total_cmp(&(self.caret_x(b) - x).abs())
+            })
+            .unwrap_or(0)
+    }
+
+    /// Index of the next caret position to the visual left or right
+    ///
+    /// Returns `index` unchanged at the visual edge of the line.
+    pub fn move_visual(&self, index: usize, right: bool) -> usize {
+        let current = self.caret_x(index);
+        (0..=self.chars.len())
+            .filter(|&i| {
+                let x = self.caret_x(i);
+                if right {
+                    x > current + 0.01
+                } else {
+                    x < current - 0.01
+                }
+            })
+            .min_by(|&a, &b| {
+                (self.caret_x(a) - current)
+                    .abs()
+                    .total_cmp(&(self.caret_x(b) - current).abs())
+            })
+            .unwrap_or(index)
+    }
+
+    /// Highlight spans `(left, right)` covering the characters `start..end`
+    ///
+    /// A logically contiguous selection may be visually split across runs,
+    /// so this can return several disjoint spans, sorted left to right.
+    pub fn selection_ranges(&self, start: usize, end: usize) -> Vec<(f32, f32)> {
+        let end = end.min(self.chars.len());
+        let mut spans: Vec<(f32, f32)> = self
+            .chars
+            .get(start..end)
+            .unwrap_or(&[])
+            .iter()
+            .map(|&(x, w, _)| (x, x + w))
+            .collect();
+        spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut merged: Vec<(f32, f32)> = Vec::with_capacity(spans.len());
+        for (left, right) in spans {
+            match merged.last_mut() {
+                Some(last) if left <= last.1 + 0.01 => last.1 = last.1.max(right),
+                _ => merged.push((left, right)),
+            }
+        }
+        merged
+    }
+}
+
+/// Highlight spans `(left, right)` for the characters `start..end` of a line
+///
+/// Left-to-right text yields a single span from prefix widths; text with
+/// right-to-left content may yield several disjoint spans.
+pub fn selection_spans(
+    text: &str,
+    start: usize,
+    end: usize,
+    measure: impl Fn(&str) -> f32,
+) -> Vec<(f32, f32)> {
+    if start >= end {
+        return Vec::new();
+    }
+    if let Some(map) = BidiCaretMap::new(text, &measure) {
+        return map.selection_ranges(start, end);
+    }
+
+    let prefix_width = |chars: usize| {
+        let byte = text
+            .char_indices()
+            .nth(chars)
+            .map(|(i, _)| i)
+            .unwrap_or(text.len());
+        measure(&text[..byte])
+    };
+    vec![(prefix_width(start), prefix_width(end))]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,4 +316,41 @@ mod tests {
         clear_selection();
         assert!(get_selected_text().is_none());
     }
+
+    /// Fixed 10px advance per character
+    fn measure(text: &str) -> f32 {
+        text.chars().count() as f32 * 10.0
+    }
+
+    #[test]
+    fn test_bidi_caret_map() {
+        assert!(BidiCaretMap::new("plain text", measure).is_none());
+
+        // Visual order: "ab " followed by the Hebrew word reversed
+        let map = BidiCaretMap::new("ab אב", measure).unwrap();
+        assert_eq!(map.width(), 50.0);
+        assert_eq!(map.caret_x(0), 0.0);
+        assert_eq!(map.caret_x(2), 20.0);
+        // Leading edge of an RTL character is its right edge
+        assert_eq!(map.caret_x(3), 50.0);
+        assert_eq!(map.caret_x(4), 40.0);
+        assert_eq!(map.caret_x(5), 30.0);
+
+        assert_eq!(map.index_at_x(44.0), 4);
+        assert_eq!(map.move_visual(2, true), 5);
+        assert_eq!(map.move_visual(5, true), 4);
+        assert_eq!(map.move_visual(3, true), 3);
+    }
+
+    #[test]
+    fn test_selection_spans() {
+        assert_eq!(selection_spans("hello", 1, 3, measure), vec![(10.0, 30.0)]);
+        assert!(selection_spans("hello", 2, 2, measure).is_empty());
+
+        // "b", " " and the first Hebrew letter are visually split
+        assert_eq!(
+            selection_spans("ab אב", 1, 4, measure),
+            vec![(10.0, 30.0), (40.0, 50.0)]
+        );
+    }
 }
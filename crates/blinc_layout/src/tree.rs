@@ -33,6 +33,10 @@ pub struct TextMeasureContext {
     pub font_weight: u16,
     /// Whether text is italic
     pub italic: bool,
+    /// Paragraph layout settings (white-space, clamping, hyphenation)
+    pub paragraph: crate::div::ParagraphStyle,
+    /// OpenType features and variable font axes (affect glyph advances)
+    pub font_settings: blinc_core::FontSettings,
 }
 
 impl LayoutNodeId {
@@ -89,6 +93,8 @@ fn text_measure_function(
         options.font_weight = ctx.font_weight;
         options.italic = ctx.italic;
         options.line_height = ctx.line_height;
+        options.paragraph = ctx.paragraph.clone();
+        options.font_settings = ctx.font_settings.clone();
         // No max_width for non-wrapping
 
         let metrics = measure_text_with_options(&ctx.content, ctx.font_size, &options);
@@ -115,6 +121,8 @@ fn text_measure_function(
     options.font_weight = ctx.font_weight;
     options.italic = ctx.italic;
     options.line_height = ctx.line_height;
+    options.paragraph = ctx.paragraph.clone();
+    options.font_settings = ctx.font_settings.clone();
     options.max_width = max_width;
 
     let metrics = measure_text_with_options(&ctx.content, ctx.font_size, &options);
@@ -232,6 +240,13 @@ impl LayoutTree {
             .collect()
     }
 
+    /// Get the parent of a layout node
+    pub fn parent(&self, id: LayoutNodeId) -> Option<LayoutNodeId> {
+        let &taffy_node = self.node_map.get(id)?;
+        let parent = self.taffy.parent(taffy_node)?;
+        self.reverse_map.get(&parent).copied()
+    }
+
     /// Get computed layout as ElementBounds with parent offset
     pub fn get_bounds(&self, id: LayoutNodeId, parent_offset: (f32, f32)) -> Option<ElementBounds> {
         self.get_layout(id)
@@ -6,7 +6,7 @@
 //! # Examples
 //!
 //! ```rust,ignore
-//! use blinc_layout::units::{px, sp, pct, Length};
+//! use blinc_layout::units::{px, sp, pct, vh, rem, Length};
 //!
 //! // Raw pixels
 //! div().padding(px(16.0))
@@ -16,12 +16,22 @@
 //!
 //! // Percentage
 //! div().w(pct(50.0))  // 50% of parent
+//!
+//! // Relative units and math, resolved at layout time
+//! div().height(vh(100.0) - px(64.0))        // calc(100vh - 64px)
+//! div().width(pct(30.0).clamp(rem(12.0), px(320.0)))  // clamp(12rem, 30%, 320px)
 //! ```
 
-use taffy::{LengthPercentage, LengthPercentageAuto};
+use std::ops::{Add, Div, Mul, Sub};
+use std::sync::Arc;
+
+use taffy::{Dimension, LengthPercentage, LengthPercentageAuto};
+
+/// Root font size used to resolve `em` and `rem` when nothing else is known
+pub const DEFAULT_FONT_SIZE: f32 = 16.0;
 
 /// A length value with its unit
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Length {
     /// Raw pixels (no scaling)
     Px(f32),
@@ -31,16 +41,126 @@ pub enum Length {
     Pct(f32),
     /// Auto sizing
     Auto,
+    /// Multiple of the element's font size
+    Em(f32),
+    /// Multiple of the root font size
+    Rem(f32),
+    /// Percentage of the viewport width
+    Vw(f32),
+    /// Percentage of the viewport height
+    Vh(f32),
+    /// Percentage of the smaller viewport dimension
+    Vmin(f32),
+    /// Percentage of the larger viewport dimension
+    Vmax(f32),
+    /// Math expression (`calc()`, `min()`, `max()`, `clamp()`)
+    ///
+    /// Shared so that cloning styles does not copy the expression tree.
+    Calc(Arc<CalcExpr>),
+}
+
+/// Math expression over lengths, mirroring CSS math functions
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcExpr {
+    /// `a + b`
+    Sum(Length, Length),
+    /// `a - b`
+    Difference(Length, Length),
+    /// `a * n`
+    Product(Length, f32),
+    /// `a / n`
+    Quotient(Length, f32),
+    /// `min(a, b, ...)`
+    Min(Vec<Length>),
+    /// `max(a, b, ...)`
+    Max(Vec<Length>),
+    /// `clamp(min, value, max)`
+    Clamp(Length, Length, Length),
+}
+
+/// Values relative lengths are resolved against
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LengthContext {
+    /// Font size of the element (for `em`)
+    pub font_size: f32,
+    /// Root font size (for `rem`)
+    pub root_font_size: f32,
+    /// Viewport width in logical pixels (for `vw`, `vmin`, `vmax`)
+    pub viewport_width: f32,
+    /// Viewport height in logical pixels (for `vh`, `vmin`, `vmax`)
+    pub viewport_height: f32,
+    /// Size percentages refer to, if known
+    pub percent_base: Option<f32>,
+}
+
+impl Default for LengthContext {
+    fn default() -> Self {
+        Self {
+            font_size: DEFAULT_FONT_SIZE,
+            root_font_size: DEFAULT_FONT_SIZE,
+            viewport_width: 0.0,
+            viewport_height: 0.0,
+            percent_base: None,
+        }
+    }
+}
+
+impl LengthContext {
+    /// Create a context for the given viewport size
+    pub fn new(viewport_width: f32, viewport_height: f32) -> Self {
+        Self {
+            viewport_width,
+            viewport_height,
+            ..Default::default()
+        }
+    }
+
+    /// Set the element font size
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    /// Set the root font size
+    pub fn with_root_font_size(mut self, root_font_size: f32) -> Self {
+        self.root_font_size = root_font_size;
+        self
+    }
+
+    /// Set the size percentages refer to
+    pub fn with_percent_base(mut self, base: f32) -> Self {
+        self.percent_base = Some(base);
+        self
+    }
 }
 
 impl Length {
     /// Convert to raw pixels
-    pub fn to_px(self) -> f32 {
+    ///
+    /// Relative units resolve against [`LengthContext::default`]: `em`/`rem`
+    /// use a 16px font size, viewport units and percentages become 0.
+    /// Use [`Length::resolve`] when the context is known.
+    pub fn to_px(&self) -> f32 {
+        self.resolve(&LengthContext::default()).unwrap_or(0.0)
+    }
+
+    /// Resolve to pixels against a context
+    ///
+    /// Returns `None` for `auto`, and for percentages when the context has no
+    /// percent base.
+    pub fn resolve(&self, ctx: &LengthContext) -> Option<f32> {
         match self {
-            Length::Px(v) => v,
-            Length::Sp(v) => v * 4.0,
-            Length::Pct(_) => 0.0, // Percentage needs context
-            Length::Auto => 0.0,
+            Length::Px(v) => Some(*v),
+            Length::Sp(v) => Some(v * 4.0),
+            Length::Pct(v) => ctx.percent_base.map(|base| base * v / 100.0),
+            Length::Auto => None,
+            Length::Em(v) => Some(v * ctx.font_size),
+            Length::Rem(v) => Some(v * ctx.root_font_size),
+            Length::Vw(v) => Some(v * ctx.viewport_width / 100.0),
+            Length::Vh(v) => Some(v * ctx.viewport_height / 100.0),
+            Length::Vmin(v) => Some(v * ctx.viewport_width.min(ctx.viewport_height) / 100.0),
+            Length::Vmax(v) => Some(v * ctx.viewport_width.max(ctx.viewport_height) / 100.0),
+            Length::Calc(expr) => expr.resolve(ctx),
         }
     }
 
@@ -53,6 +173,98 @@ impl Length {
     pub fn is_auto(&self) -> bool {
         matches!(self, Length::Auto)
     }
+
+    /// Check if resolving this length needs a [`LengthContext`]
+    ///
+    /// True for font-relative and viewport units and for math expressions.
+    /// Plain pixels, spacing units, percentages and `auto` map directly to
+    /// layout values.
+    pub fn is_contextual(&self) -> bool {
+        matches!(
+            self,
+            Length::Em(_)
+                | Length::Rem(_)
+                | Length::Vw(_)
+                | Length::Vh(_)
+                | Length::Vmin(_)
+                | Length::Vmax(_)
+                | Length::Calc(_)
+        )
+    }
+
+    /// Check if this length refers to a percentage anywhere in its expression
+    pub fn uses_percentage(&self) -> bool {
+        match self {
+            Length::Pct(_) => true,
+            Length::Calc(expr) => expr.operands().any(Length::uses_percentage),
+            _ => false,
+        }
+    }
+
+    /// The smaller of two lengths (`min(self, other)`)
+    pub fn min(self, other: impl Into<Length>) -> Length {
+        CalcExpr::Min(vec![self, other.into()]).into()
+    }
+
+    /// The larger of two lengths (`max(self, other)`)
+    pub fn max(self, other: impl Into<Length>) -> Length {
+        CalcExpr::Max(vec![self, other.into()]).into()
+    }
+
+    /// Clamp this length between `min` and `max` (`clamp(min, self, max)`)
+    pub fn clamp(self, min: impl Into<Length>, max: impl Into<Length>) -> Length {
+        CalcExpr::Clamp(min.into(), self, max.into()).into()
+    }
+}
+
+impl CalcExpr {
+    /// Resolve to pixels against a context
+    pub fn resolve(&self, ctx: &LengthContext) -> Option<f32> {
+        match self {
+            CalcExpr::Sum(a, b) => Some(a.resolve(ctx)? + b.resolve(ctx)?),
+            CalcExpr::Difference(a, b) => Some(a.resolve(ctx)? - b.resolve(ctx)?),
+            CalcExpr::Product(a, n) => Some(a.resolve(ctx)? * n),
+            CalcExpr::Quotient(a, n) => {
+                if *n == 0.0 {
+                    None
+                } else {
+                    Some(a.resolve(ctx)? / n)
+                }
+            }
+            CalcExpr::Min(values) => values
+                .iter()
+                .map(|v| v.resolve(ctx))
+                .try_fold(f32::INFINITY, |acc, v| v.map(|v| acc.min(v)))
+                .filter(|v| v.is_finite()),
+            CalcExpr::Max(values) => values
+                .iter()
+                .map(|v| v.resolve(ctx))
+                .try_fold(f32::NEG_INFINITY, |acc, v| v.map(|v| acc.max(v)))
+                .filter(|v| v.is_finite()),
+            CalcExpr::Clamp(min, value, max) => {
+                let (min, value, max) = (min.resolve(ctx)?, value.resolve(ctx)?, max.resolve(ctx)?);
+                // CSS: the minimum wins when it exceeds the maximum
+                Some(value.min(max).max(min))
+            }
+        }
+    }
+
+    /// Iterate over the lengths this expression combines
+    pub fn operands(&self) -> impl Iterator<Item = &Length> {
+        let operands: Vec<&Length> = match self {
+            CalcExpr::Sum(a, b) | CalcExpr::Difference(a, b) => vec![a, b],
+            CalcExpr::Product(a, _) | CalcExpr::Quotient(a, _) => vec![a],
+            CalcExpr::Min(values) | CalcExpr::Max(values) => values.iter().collect(),
+            CalcExpr::Clamp(min, value, max) => vec![min, value, max],
+        };
+        operands.into_iter()
+    }
+}
+
+impl From<CalcExpr> for Length {
+    fn from(expr: CalcExpr) -> Self {
+        Length::Calc(Arc::new(expr))
+    }
 }
 
 impl Default for Length {
@@ -61,14 +273,47 @@ impl Default for Length {
     }
 }
 
+impl<T: Into<Length>> Add<T> for Length {
+    type Output = Length;
+
+    fn add(self, rhs: T) -> Length {
+        CalcExpr::Sum(self, rhs.into()).into()
+    }
+}
+
+impl<T: Into<Length>> Sub<T> for Length {
+    type Output = Length;
+
+    fn sub(self, rhs: T) -> Length {
+        CalcExpr::Difference(self, rhs.into()).into()
+    }
+}
+
+impl Mul<f32> for Length {
+    type Output = Length;
+
+    fn mul(self, rhs: f32) -> Length {
+        CalcExpr::Product(self, rhs).into()
+    }
+}
+
+impl Div<f32> for Length {
+    type Output = Length;
+
+    fn div(self, rhs: f32) -> Length {
+        CalcExpr::Quotient(self, rhs).into()
+    }
+}
+
 // Conversion to Taffy types
+//
+// Contextual lengths (em, vw, calc, ...) convert with the default context;
+// the render tree re-resolves them at layout time.
 impl From<Length> for LengthPercentage {
     fn from(len: Length) -> Self {
         match len {
-            Length::Px(v) => LengthPercentage::Length(v),
-            Length::Sp(v) => LengthPercentage::Length(v * 4.0),
             Length::Pct(v) => LengthPercentage::Percent(v / 100.0),
-            Length::Auto => LengthPercentage::Length(0.0),
+            other => LengthPercentage::Length(other.to_px()),
         }
     }
 }
@@ -76,10 +321,19 @@ impl From<Length> for LengthPercentage {
 impl From<Length> for LengthPercentageAuto {
     fn from(len: Length) -> Self {
         match len {
-            Length::Px(v) => LengthPercentageAuto::Length(v),
-            Length::Sp(v) => LengthPercentageAuto::Length(v * 4.0),
             Length::Pct(v) => LengthPercentageAuto::Percent(v / 100.0),
             Length::Auto => LengthPercentageAuto::Auto,
+            other => LengthPercentageAuto::Length(other.to_px()),
+        }
+    }
+}
+
+impl From<Length> for Dimension {
+    fn from(len: Length) -> Self {
+        match len {
+            Length::Pct(v) => Dimension::Percent(v / 100.0),
+            Length::Auto => Dimension::Auto,
+            other => Dimension::Length(other.to_px()),
         }
     }
 }
@@ -109,6 +363,42 @@ pub const fn pct(value: f32) -> Length {
     Length::Pct(value)
 }
 
+/// Create a length relative to the element's font size
+#[inline]
+pub const fn em(value: f32) -> Length {
+    Length::Em(value)
+}
+
+/// Create a length relative to the root font size
+#[inline]
+pub const fn rem(value: f32) -> Length {
+    Length::Rem(value)
+}
+
+/// Create a length in percent of the viewport width
+#[inline]
+pub const fn vw(value: f32) -> Length {
+    Length::Vw(value)
+}
+
+/// Create a length in percent of the viewport height
+#[inline]
+pub const fn vh(value: f32) -> Length {
+    Length::Vh(value)
+}
+
+/// Create a length in percent of the smaller viewport dimension
+#[inline]
+pub const fn vmin(value: f32) -> Length {
+    Length::Vmin(value)
+}
+
+/// Create a length in percent of the larger viewport dimension
+#[inline]
+pub const fn vmax(value: f32) -> Length {
+    Length::Vmax(value)
+}
+
 /// Tuple conversion for ergonomic unit specification
 /// Allows: `(16.0, Px)` or `(4.0, Sp)` syntax
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -119,6 +409,18 @@ pub enum Unit {
     Sp,
     /// Percentage
     Pct,
+    /// Element font size
+    Em,
+    /// Root font size
+    Rem,
+    /// Percent of viewport width
+    Vw,
+    /// Percent of viewport height
+    Vh,
+    /// Percent of the smaller viewport dimension
+    Vmin,
+    /// Percent of the larger viewport dimension
+    Vmax,
 }
 
 impl From<(f32, Unit)> for Length {
@@ -127,6 +429,12 @@ impl From<(f32, Unit)> for Length {
             Unit::Px => Length::Px(value),
             Unit::Sp => Length::Sp(value),
             Unit::Pct => Length::Pct(value),
+            Unit::Em => Length::Em(value),
+            Unit::Rem => Length::Rem(value),
+            Unit::Vw => Length::Vw(value),
+            Unit::Vh => Length::Vh(value),
+            Unit::Vmin => Length::Vmin(value),
+            Unit::Vmax => Length::Vmax(value),
         }
     }
 }
@@ -138,6 +446,163 @@ impl From<f32> for Length {
     }
 }
 
+// ============================================================================
+// Layout-time resolution
+// ============================================================================
+
+/// Which axis a percentage length refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// Width of the containing block
+    Horizontal,
+    /// Height of the containing block
+    Vertical,
+}
+
+/// A style property that accepts a contextual [`Length`]
+///
+/// Lengths bound to a slot are resolved by the render tree before each layout
+/// pass, once the viewport, font size and parent size are known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LengthSlot {
+    /// `width`
+    Width,
+    /// `height`
+    Height,
+    /// `min-width`
+    MinWidth,
+    /// `min-height`
+    MinHeight,
+    /// `max-width`
+    MaxWidth,
+    /// `max-height`
+    MaxHeight,
+    /// `flex-basis`
+    FlexBasis,
+    /// `padding-top`
+    PaddingTop,
+    /// `padding-right`
+    PaddingRight,
+    /// `padding-bottom`
+    PaddingBottom,
+    /// `padding-left`
+    PaddingLeft,
+    /// `margin-top`
+    MarginTop,
+    /// `margin-right`
+    MarginRight,
+    /// `margin-bottom`
+    MarginBottom,
+    /// `margin-left`
+    MarginLeft,
+    /// Gap between columns (`column-gap`)
+    ColumnGap,
+    /// Gap between rows (`row-gap`)
+    RowGap,
+    /// `top`
+    Top,
+    /// `right`
+    Right,
+    /// `bottom`
+    Bottom,
+    /// `left`
+    Left,
+    /// Text size; applied to render props rather than layout style
+    FontSize,
+}
+
+impl LengthSlot {
+    /// Axis percentages in this slot refer to, or `None` if they are unsupported
+    ///
+    /// As in CSS, padding and margin percentages always refer to the width.
+    pub fn percent_axis(self) -> Option<Axis> {
+        use LengthSlot::*;
+        match self {
+            Width | MinWidth | MaxWidth | FlexBasis | Left | Right | ColumnGap | PaddingTop
+            | PaddingRight | PaddingBottom | PaddingLeft | MarginTop | MarginRight
+            | MarginBottom | MarginLeft => Some(Axis::Horizontal),
+            Height | MinHeight | MaxHeight | Top | Bottom | RowGap => Some(Axis::Vertical),
+            FontSize => None,
+        }
+    }
+
+    /// Slots covered by a CSS property name (shorthands expand to several)
+    pub fn for_css_property(name: &str) -> &'static [LengthSlot] {
+        use LengthSlot::*;
+        match name {
+            "width" => &[Width],
+            "height" => &[Height],
+            "min-width" => &[MinWidth],
+            "min-height" => &[MinHeight],
+            "max-width" => &[MaxWidth],
+            "max-height" => &[MaxHeight],
+            "flex-basis" => &[FlexBasis],
+            "padding" => &[PaddingTop, PaddingRight, PaddingBottom, PaddingLeft],
+            "padding-top" => &[PaddingTop],
+            "padding-right" => &[PaddingRight],
+            "padding-bottom" => &[PaddingBottom],
+            "padding-left" => &[PaddingLeft],
+            "margin" => &[MarginTop, MarginRight, MarginBottom, MarginLeft],
+            "margin-top" => &[MarginTop],
+            "margin-right" => &[MarginRight],
+            "margin-bottom" => &[MarginBottom],
+            "margin-left" => &[MarginLeft],
+            "gap" => &[RowGap, ColumnGap],
+            "row-gap" => &[RowGap],
+            "column-gap" => &[ColumnGap],
+            "inset" => &[Top, Right, Bottom, Left],
+            "top" => &[Top],
+            "right" => &[Right],
+            "bottom" => &[Bottom],
+            "left" => &[Left],
+            "font-size" => &[FontSize],
+            _ => &[],
+        }
+    }
+
+    /// Write a resolved pixel value into a taffy style
+    ///
+    /// Does nothing for [`LengthSlot::FontSize`].
+    pub fn apply(self, style: &mut taffy::Style, px: f32) {
+        self.apply_length(style, Length::Px(px));
+    }
+
+    /// Write a length into a taffy style
+    ///
+    /// Percentages and `auto` map to their taffy equivalents; contextual
+    /// lengths use [`Length::to_px`]. Does nothing for [`LengthSlot::FontSize`].
+    pub fn apply_length(self, style: &mut taffy::Style, len: Length) {
+        use LengthSlot::*;
+        let dim = Dimension::from(len.clone());
+        let lp = LengthPercentage::from(len.clone());
+        let lpa = LengthPercentageAuto::from(len);
+        match self {
+            Width => style.size.width = dim,
+            Height => style.size.height = dim,
+            MinWidth => style.min_size.width = dim,
+            MinHeight => style.min_size.height = dim,
+            MaxWidth => style.max_size.width = dim,
+            MaxHeight => style.max_size.height = dim,
+            FlexBasis => style.flex_basis = dim,
+            PaddingTop => style.padding.top = lp,
+            PaddingRight => style.padding.right = lp,
+            PaddingBottom => style.padding.bottom = lp,
+            PaddingLeft => style.padding.left = lp,
+            MarginTop => style.margin.top = lpa,
+            MarginRight => style.margin.right = lpa,
+            MarginBottom => style.margin.bottom = lpa,
+            MarginLeft => style.margin.left = lpa,
+            ColumnGap => style.gap.width = lp,
+            RowGap => style.gap.height = lp,
+            Top => style.inset.top = lpa,
+            Right => style.inset.right = lpa,
+            Bottom => style.inset.bottom = lpa,
+            Left => style.inset.left = lpa,
+            FontSize => {}
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,4 +638,57 @@ mod tests {
         let lp: LengthPercentage = pct(50.0).into();
         assert!(matches!(lp, LengthPercentage::Percent(v) if (v - 0.5).abs() < 0.001));
     }
+
+    #[test]
+    fn test_relative_units() {
+        let ctx = LengthContext::new(1000.0, 500.0).with_font_size(20.0);
+        assert_eq!(em(2.0).resolve(&ctx), Some(40.0));
+        assert_eq!(rem(2.0).resolve(&ctx), Some(32.0));
+        assert_eq!(vw(10.0).resolve(&ctx), Some(100.0));
+        assert_eq!(vh(10.0).resolve(&ctx), Some(50.0));
+        assert_eq!(vmin(10.0).resolve(&ctx), Some(50.0));
+        assert_eq!(vmax(10.0).resolve(&ctx), Some(100.0));
+        assert_eq!(pct(50.0).resolve(&ctx), None);
+        assert_eq!(
+            pct(50.0).resolve(&ctx.with_percent_base(300.0)),
+            Some(150.0)
+        );
+    }
+
+    #[test]
+    fn test_math_expressions() {
+        let ctx = LengthContext::new(1000.0, 800.0);
+        assert_eq!((vh(100.0) - px(64.0)).resolve(&ctx), Some(736.0));
+        assert_eq!((rem(1.0) * 2.0 + px(4.0)).resolve(&ctx), Some(36.0));
+        assert_eq!(vw(50.0).min(px(300.0)).resolve(&ctx), Some(300.0));
+        assert_eq!(vw(10.0).max(px(300.0)).resolve(&ctx), Some(300.0));
+        assert_eq!(vw(2.0).clamp(px(14.0), px(22.0)).resolve(&ctx), Some(20.0));
+        assert_eq!(vw(1.0).clamp(px(14.0), px(22.0)).resolve(&ctx), Some(14.0));
+        // Percentages inside an expression need a base
+        let sidebar = pct(100.0) - px(240.0);
+        assert!(sidebar.uses_percentage());
+        assert_eq!(sidebar.resolve(&ctx), None);
+        assert_eq!(sidebar.resolve(&ctx.with_percent_base(1000.0)), Some(760.0));
+    }
+
+    #[test]
+    fn test_calc_lengths_share_expressions() {
+        let a = vh(100.0) - px(64.0);
+        assert_eq!(a, vh(100.0) - px(64.0));
+        assert_ne!(a, vh(100.0) - px(32.0));
+
+        // Clones share the expression and free it with the last copy
+        let b = a.clone();
+        let (Length::Calc(ea), Length::Calc(eb)) = (&a, &b) else {
+            panic!("expected calc lengths");
+        };
+        assert!(Arc::ptr_eq(ea, eb));
+        assert_eq!(Arc::strong_count(ea), 2);
+        drop(b);
+        let Length::Calc(ea) = &a else { unreachable!() };
+        assert_eq!(Arc::strong_count(ea), 1);
+
+        assert_eq!(a.resolve(&LengthContext::new(0.0, 800.0)), Some(736.0));
+        assert_eq!(a.to_px(), -64.0);
+    }
 }
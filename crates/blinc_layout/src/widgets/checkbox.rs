@@ -211,6 +211,8 @@ fn apply_style_to_checkbox(
 /// The fully-built checkbox component (Div containing stateful checkbox + optional label)
 pub struct Checkbox {
     inner: crate::div::Div,
+    /// Checked state, exposed for `:checked` selector matching
+    checked: State<bool>,
 }
 
 impl Checkbox {
@@ -218,6 +220,7 @@ impl Checkbox {
     fn with_config(instance_key: &InstanceKey, config: CheckboxConfig) -> Self {
         let checked_state = config.checked.clone();
         let checked_for_click = config.checked.clone();
+        let checked = config.checked.clone();
         let on_change = config.on_change.clone();
         let disabled = config.disabled;
 
@@ -334,7 +337,7 @@ impl Checkbox {
                 .child(checkbox)
         };
 
-        Self { inner }
+        Self { inner, checked }
     }
 }
 
@@ -374,6 +377,11 @@ impl ElementBuilder for Checkbox {
     fn element_classes(&self) -> &[String] {
         self.inner.element_classes()
     }
+
+    fn checked_probe(&self) -> Option<crate::selector::CheckedProbe> {
+        let checked = self.checked.clone();
+        Some(Arc::new(move || checked.get()))
+    }
 }
 
 /// Builder for creating Checkbox components with fluent API.
@@ -521,6 +529,10 @@ impl ElementBuilder for CheckboxBuilder {
     fn element_classes(&self) -> &[String] {
         self.get_or_build().element_classes()
     }
+
+    fn checked_probe(&self) -> Option<crate::selector::CheckedProbe> {
+        self.get_or_build().checked_probe()
+    }
 }
 
 /// Create a checkbox with reactive checked state
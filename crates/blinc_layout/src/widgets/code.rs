@@ -6,6 +6,18 @@
 //! - Read-only by default, editable with `.edit(true)`
 //! - All Div layout methods via Deref
 //!
+//! # Editor mode
+//!
+//! [`code_editor()`] edits a [`SharedCodeState`] that survives rebuilds and
+//! adds what query and config editors need:
+//! - A gutter with line numbers, fold markers and [`Diagnostic`] markers
+//! - Folding by indentation or by brackets ([`FoldMode`])
+//! - Find and replace, optionally with regular expressions ([`SearchQuery`])
+//! - Multiple cursors (Alt+click, Ctrl/Cmd+D) and column selection
+//!   (Alt+Shift+click, Alt+Shift+Up/Down)
+//! - Current-line highlight and bracket matching
+//! - Re-highlighting of edited lines only, through [`HighlightCache`]
+//!
 //! # Example
 //!
 //! ```ignore
@@ -25,27 +37,41 @@
 //!     .on_change(|new_content| {
 //!         println!("Content changed: {}", new_content);
 //!     })
+//!
+//! // Editor with persistent state
+//! let query = ctx.use_state_for("query", || code_state("SELECT * FROM users"));
+//! code_editor(&query)
+//!     .line_numbers(true)
+//!     .folding(FoldMode::Indentation)
 //! ```
 
-use std::ops::{Deref, DerefMut};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::{Deref, DerefMut, Range};
 use std::sync::{Arc, Mutex};
 
 use blinc_core::{Brush, Color, CornerRadius, Rect};
 use blinc_theme::{ColorToken, ThemeState};
+use regex::{Regex, RegexBuilder};
 
 use crate::canvas::canvas;
-use crate::div::{div, Div, ElementBuilder, ElementTypeId};
+use crate::div::{div, Div, ElementBuilder, ElementTypeId, GenericFont};
 use crate::element::RenderProps;
-use crate::styled_text::StyledText;
-use crate::syntax::{SyntaxConfig, SyntaxHighlighter, TokenHit};
+use crate::styled_text::{StyledLine, TextSpan};
+use crate::syntax::{HighlightCache, SyntaxConfig, SyntaxHighlighter, TokenHit, TokenType};
 use crate::text::text;
+use crate::text_buffer::TextBuffer;
+use crate::text_measure::{measure_text_with_options, TextLayoutOptions};
 use crate::tree::{LayoutNodeId, LayoutTree};
 use crate::widgets::cursor::{cursor_state, CursorAnimation, SharedCursorState};
 use crate::widgets::text_area::TextPosition;
+use crate::widgets::text_edit::{leading_indent, word_range_at};
 use crate::widgets::text_input::{
     decrement_focus_count, increment_focus_count, request_continuous_redraw_pub, request_rebuild,
 };
 
+/// Furthest a bracket match is searched for, in chars
+const MAX_BRACKET_SCAN: usize = 100_000;
+
 // ============================================================================
 // Configuration
 // ============================================================================
@@ -67,6 +93,10 @@ pub struct CodeConfig {
     pub corner_radius: f32,
     /// Whether editing is enabled
     pub editable: bool,
+    /// Code folding (`None` disables fold markers)
+    pub folding: Option<FoldMode>,
+    /// Highlight lines holding a cursor (when editable)
+    pub highlight_current_line: bool,
     /// Background color
     pub bg_color: Color,
     /// Text color (default, when no syntax highlighting)
@@ -81,11 +111,26 @@ pub struct CodeConfig {
     pub gutter_bg_color: Color,
     /// Gutter separator color
     pub gutter_separator_color: Color,
+    /// Current line background color
+    pub current_line_color: Color,
+    /// Outline color of matching brackets
+    pub bracket_match_color: Color,
+    /// Search match background color
+    pub search_match_color: Color,
+    /// Fold marker color
+    pub fold_marker_color: Color,
+    /// Error diagnostic marker color
+    pub error_color: Color,
+    /// Warning diagnostic marker color
+    pub warning_color: Color,
+    /// Info and hint diagnostic marker color
+    pub info_color: Color,
 }
 
 impl Default for CodeConfig {
     fn default() -> Self {
         let theme = ThemeState::get();
+        let text_color = theme.color(ColorToken::TextPrimary);
         Self {
             font_size: 13.0,
             line_height: 1.5,
@@ -94,272 +139,1093 @@ impl Default for CodeConfig {
             padding: 16.0,
             corner_radius: 8.0,
             editable: false,
+            folding: None,
+            highlight_current_line: true,
             bg_color: theme.color(ColorToken::Surface),
-            text_color: theme.color(ColorToken::TextPrimary),
+            text_color,
             line_number_color: theme.color(ColorToken::TextTertiary),
             cursor_color: theme.color(ColorToken::Accent),
             selection_color: theme.color(ColorToken::Selection),
             gutter_bg_color: theme.color(ColorToken::SurfaceOverlay),
             gutter_separator_color: theme.color(ColorToken::Border),
+            current_line_color: tint(text_color),
+            bracket_match_color: theme.color(ColorToken::TextTertiary),
+            search_match_color: theme.color(ColorToken::WarningBg),
+            fold_marker_color: theme.color(ColorToken::TextTertiary),
+            error_color: theme.color(ColorToken::Error),
+            warning_color: theme.color(ColorToken::Warning),
+            info_color: theme.color(ColorToken::Info),
+        }
+    }
+}
+
+impl CodeConfig {
+    fn severity_color(&self, severity: DiagnosticSeverity) -> Color {
+        match severity {
+            DiagnosticSeverity::Error => self.error_color,
+            DiagnosticSeverity::Warning => self.warning_color,
+            DiagnosticSeverity::Info | DiagnosticSeverity::Hint => self.info_color,
+        }
+    }
+}
+
+/// Faint version of the text color, visible on any background
+fn tint(color: Color) -> Color {
+    Color::rgba(color.r, color.g, color.b, 0.06)
+}
+
+// ============================================================================
+// Editor Model
+// ============================================================================
+
+/// How foldable regions are found
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FoldMode {
+    /// A line folds the following lines that are indented deeper
+    Indentation,
+    /// A line with an opening bracket folds the lines up to its closing
+    /// bracket, ignoring brackets the highlighter marks as strings or comments
+    Brackets,
+}
+
+/// A foldable region: line `start` stays visible, `start + 1..=end` fold away
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FoldRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Severity of a [`Diagnostic`], least severe first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DiagnosticSeverity {
+    Hint,
+    Info,
+    Warning,
+    Error,
+}
+
+/// A message attached to a line, shown as a gutter marker
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// Line index (0-based)
+    pub line: usize,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(line: usize, severity: DiagnosticSeverity, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            severity,
+            message: message.into(),
+        }
+    }
+
+    /// Error on `line`
+    pub fn error(line: usize, message: impl Into<String>) -> Self {
+        Self::new(line, DiagnosticSeverity::Error, message)
+    }
+
+    /// Warning on `line`
+    pub fn warning(line: usize, message: impl Into<String>) -> Self {
+        Self::new(line, DiagnosticSeverity::Warning, message)
+    }
+}
+
+/// What to look for with [`CodeState::set_search`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Text, or a regular expression if `regex` is set
+    pub pattern: String,
+    /// Treat the pattern as a regular expression; replacements may then use `$1`
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Only match whole words
+    pub whole_word: bool,
+}
+
+impl SearchQuery {
+    /// Case-insensitive plain text search
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Treat the pattern as a regular expression
+    pub fn regex(mut self, enabled: bool) -> Self {
+        self.regex = enabled;
+        self
+    }
+
+    /// Match case
+    pub fn case_sensitive(mut self, enabled: bool) -> Self {
+        self.case_sensitive = enabled;
+        self
+    }
+
+    /// Only match whole words
+    pub fn whole_word(mut self, enabled: bool) -> Self {
+        self.whole_word = enabled;
+        self
+    }
+
+    /// Compile into a regular expression
+    pub fn compile(&self) -> Result<Regex, regex::Error> {
+        let pattern = if self.regex {
+            self.pattern.clone()
+        } else {
+            regex::escape(&self.pattern)
+        };
+        let pattern = if self.whole_word {
+            format!(r"\b(?:{pattern})\b")
+        } else {
+            pattern
+        };
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .multi_line(true)
+            .build()
+    }
+}
+
+/// Active search with matches for one buffer revision
+#[derive(Debug, Clone)]
+struct Search {
+    query: SearchQuery,
+    regex: Regex,
+    /// Matches as char ranges
+    matches: Vec<Range<usize>>,
+    revision: u64,
+}
+
+/// A cursor with an optional selection; no selection when `anchor == head`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Caret {
+    /// Fixed end of the selection
+    pub anchor: TextPosition,
+    /// Moving end, where the cursor is drawn
+    pub head: TextPosition,
+}
+
+impl Caret {
+    /// Caret without a selection
+    pub fn new(pos: TextPosition) -> Self {
+        Self {
+            anchor: pos,
+            head: pos,
         }
     }
+
+    /// Caret selecting from `anchor` to `head`
+    pub fn selection(anchor: TextPosition, head: TextPosition) -> Self {
+        Self { anchor, head }
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.anchor != self.head
+    }
+
+    /// Earlier end
+    pub fn start(&self) -> TextPosition {
+        order_positions(self.anchor, self.head).0
+    }
+
+    /// Later end
+    pub fn end(&self) -> TextPosition {
+        order_positions(self.anchor, self.head).1
+    }
 }
 
 // ============================================================================
-// Internal State (not exposed to users)
+// Editor State
 // ============================================================================
 
-/// Internal state for editable code blocks
+/// State of a code block: text, cursors, folds, diagnostics and search
+///
+/// Shared with [`code_editor()`] through [`SharedCodeState`] so that it
+/// survives rebuilds and can be driven by toolbars and find bars.
 #[derive(Debug, Clone)]
-struct CodeState {
-    /// Lines of text
-    lines: Vec<String>,
-    /// Cursor position
-    cursor: TextPosition,
-    /// Selection start (if selecting)
-    selection_start: Option<TextPosition>,
+pub struct CodeState {
+    /// Text content
+    buffer: TextBuffer,
+    /// Cursors, primary first; never empty
+    carets: Vec<Caret>,
     /// Whether currently focused
     focused: bool,
     /// Canvas-based cursor state
     cursor_state: SharedCursorState,
+    /// Highlighted lines, invalidated by edits
+    highlight: HighlightCache,
+    /// Identity of the highlighter `highlight` was built with
+    highlighter_id: usize,
+    fold_mode: Option<FoldMode>,
+    /// Folded regions, start line to last hidden line
+    folded: BTreeMap<usize, usize>,
+    diagnostics: Vec<Diagnostic>,
+    search: Option<Search>,
 }
 
 impl Default for CodeState {
     fn default() -> Self {
-        Self {
-            lines: vec![String::new()],
-            cursor: TextPosition::default(),
-            selection_start: None,
-            focused: false,
-            cursor_state: cursor_state(),
-        }
+        Self::new("")
     }
 }
 
 impl CodeState {
-    fn new(content: &str) -> Self {
-        let lines: Vec<String> = if content.is_empty() {
-            vec![String::new()]
-        } else {
-            content.lines().map(|s| s.to_string()).collect()
-        };
-
+    pub fn new(content: &str) -> Self {
+        let buffer = TextBuffer::from_text(content);
         Self {
-            lines,
-            cursor: TextPosition::default(),
-            selection_start: None,
+            highlight: HighlightCache::new(buffer.len_lines()),
+            buffer,
+            carets: vec![Caret::default()],
             focused: false,
             cursor_state: cursor_state(),
+            highlighter_id: 0,
+            fold_mode: None,
+            folded: BTreeMap::new(),
+            diagnostics: Vec::new(),
+            search: None,
         }
     }
 
     /// Get full text content
-    fn value(&self) -> String {
-        self.lines.join("\n")
+    pub fn value(&self) -> String {
+        self.buffer.to_string()
+    }
+
+    /// Replace the content, resetting cursors and folds
+    pub fn set_value(&mut self, text: &str) {
+        self.replace_chars(0..self.buffer.len_chars(), text);
+        self.carets = vec![Caret::default()];
+        self.folded.clear();
+    }
+
+    /// The underlying text buffer
+    pub fn buffer(&self) -> &TextBuffer {
+        &self.buffer
     }
 
     /// Check if empty
-    fn is_empty(&self) -> bool {
-        self.lines.len() == 1 && self.lines[0].is_empty()
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
     }
 
-    /// Insert text at cursor position
-    fn insert(&mut self, text: &str) {
-        // Delete selection first if any
-        self.delete_selection();
+    pub fn line_count(&self) -> usize {
+        self.buffer.len_lines()
+    }
 
-        for ch in text.chars() {
-            if ch == '\n' {
-                self.insert_newline();
-            } else {
-                self.insert_char(ch);
-            }
+    /// Text of a line, without its terminator
+    pub fn line(&self, line: usize) -> String {
+        self.buffer.line(line)
+    }
+
+    fn line_len(&self, line: usize) -> usize {
+        self.buffer.line_len(line)
+    }
+
+    /// Char offset of a position in the buffer
+    fn char_index(&self, pos: TextPosition) -> usize {
+        self.buffer.position_to_char(pos.line, pos.column)
+    }
+
+    /// Position of a char offset
+    fn position(&self, char_idx: usize) -> TextPosition {
+        let (line, column) = self.buffer.char_to_position(char_idx);
+        TextPosition::new(line, column)
+    }
+
+    /// Clamp a position into the buffer
+    fn clamp(&self, pos: TextPosition) -> TextPosition {
+        let line = pos.line.min(self.line_count() - 1);
+        TextPosition::new(line, pos.column.min(self.line_len(line)))
+    }
+
+    fn caret_range(&self, caret: &Caret) -> Range<usize> {
+        self.char_index(caret.start())..self.char_index(caret.end())
+    }
+
+    // ========================================================================
+    // Editing
+    // ========================================================================
+
+    /// Replace a char range, keeping highlighting and folds in step; returns
+    /// the end of the new text
+    fn replace_chars(&mut self, range: Range<usize>, text: &str) -> usize {
+        let first = self.buffer.char_to_line(range.start);
+        let old_lines = self.buffer.char_to_line(range.end) - first + 1;
+        self.buffer.replace(range.clone(), text);
+        let end = range.start + text.chars().count();
+        let new_lines = self.buffer.char_to_line(end) - first + 1;
+        self.highlight.invalidate(first, old_lines, new_lines);
+
+        if old_lines != new_lines {
+            // Folds below the edit move with it; folds the edit touched open
+            let last_old = first + old_lines - 1;
+            self.folded = std::mem::take(&mut self.folded)
+                .into_iter()
+                .filter_map(|(start, end)| {
+                    if end < first {
+                        Some((start, end))
+                    } else if start > last_old {
+                        Some((start + new_lines - old_lines, end + new_lines - old_lines))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
         }
+        end
+    }
+
+    /// Replace the text between two positions, returning the end of the new text
+    fn replace_range(&mut self, from: TextPosition, to: TextPosition, text: &str) -> TextPosition {
+        let end = self.replace_chars(self.char_index(from)..self.char_index(to), text);
+        self.position(end)
     }
 
-    fn insert_char(&mut self, ch: char) {
-        if self.cursor.line < self.lines.len() {
-            let line = &mut self.lines[self.cursor.line];
-            let byte_pos = char_to_byte_pos(line, self.cursor.column);
-            line.insert(byte_pos, ch);
-            self.cursor.column += 1;
+    /// Edit at every cursor
+    ///
+    /// `edit` maps a cursor to the char range to replace and the text to put
+    /// there, all against the text before any of the edits. Each cursor ends
+    /// up after its new text.
+    fn edit_carets(&mut self, edit: impl Fn(&Self, &Caret) -> (Range<usize>, String)) {
+        let mut edits: Vec<(usize, Range<usize>, String)> = self
+            .carets
+            .iter()
+            .enumerate()
+            .map(|(i, caret)| {
+                let (range, text) = edit(self, caret);
+                (i, range, text)
+            })
+            .collect();
+        edits.sort_by_key(|(_, range, _)| range.start);
+
+        let mut heads = vec![0; edits.len()];
+        let mut delta = 0isize;
+        let mut prev_end = 0;
+        for (i, range, text) in edits {
+            // Overlapping edits, e.g. two cursors deleting the same char, apply once
+            let start = range.start.max(prev_end);
+            let end = range.end.max(start);
+            let shifted_start = (start as isize + delta) as usize;
+            heads[i] = if start == end && text.is_empty() {
+                shifted_start
+            } else {
+                self.replace_chars(shifted_start..shifted_start + (end - start), &text)
+            };
+            delta += text.chars().count() as isize - (end - start) as isize;
+            prev_end = end;
         }
+
+        self.carets = heads
+            .into_iter()
+            .map(|head| Caret::new(self.position(head)))
+            .collect();
+        self.normalize_carets();
     }
 
-    fn insert_newline(&mut self) {
-        if self.cursor.line < self.lines.len() {
-            let current_line = &self.lines[self.cursor.line];
-            let byte_pos = char_to_byte_pos(current_line, self.cursor.column);
+    /// Insert text at every cursor, replacing selections
+    pub fn insert(&mut self, text: &str) {
+        self.edit_carets(|s, caret| (s.caret_range(caret), text.to_string()));
+    }
 
-            let new_line = current_line[byte_pos..].to_string();
-            self.lines[self.cursor.line] = current_line[..byte_pos].to_string();
-            self.lines.insert(self.cursor.line + 1, new_line);
+    /// Break the line at every cursor, keeping its indentation
+    pub fn insert_newline(&mut self) {
+        self.edit_carets(|s, caret| {
+            let start = caret.start();
+            let line = s.line(start.line);
+            let indent: String = leading_indent(&line).chars().take(start.column).collect();
+            (s.caret_range(caret), format!("\n{indent}"))
+        });
+    }
 
-            self.cursor.line += 1;
-            self.cursor.column = 0;
-        }
+    /// Delete selections, or the char before each cursor
+    pub fn delete_backward(&mut self) {
+        self.edit_carets(|s, caret| {
+            let range = s.caret_range(caret);
+            if caret.has_selection() || range.start == 0 {
+                (range, String::new())
+            } else {
+                (range.start - 1..range.end, String::new())
+            }
+        });
+    }
+
+    /// Delete selections, or the char after each cursor
+    pub fn delete_forward(&mut self) {
+        let len = self.buffer.len_chars();
+        self.edit_carets(|s, caret| {
+            let range = s.caret_range(caret);
+            if caret.has_selection() || range.end == len {
+                (range, String::new())
+            } else {
+                (range.start..range.end + 1, String::new())
+            }
+        });
+    }
+
+    /// Delete every selection
+    pub fn delete_selection(&mut self) {
+        self.edit_carets(|s, caret| (s.caret_range(caret), String::new()));
+    }
+
+    // ========================================================================
+    // Cursors
+    // ========================================================================
+
+    /// All cursors, primary first
+    pub fn carets(&self) -> &[Caret] {
+        &self.carets
+    }
+
+    /// Position of the primary cursor
+    pub fn cursor(&self) -> TextPosition {
+        self.carets[0].head
+    }
+
+    /// Place a single cursor
+    pub fn set_cursor(&mut self, pos: TextPosition) {
+        self.carets = vec![Caret::new(self.clamp(pos))];
     }
 
-    fn delete_backward(&mut self) {
-        if self.selection_start.is_some() {
-            self.delete_selection();
+    /// Select between two positions with a single cursor
+    pub fn set_selection(&mut self, anchor: TextPosition, head: TextPosition) {
+        self.carets = vec![Caret::selection(self.clamp(anchor), self.clamp(head))];
+    }
+
+    /// Text selected by the primary cursor
+    pub fn selected_text(&self) -> String {
+        self.buffer.slice(self.caret_range(&self.carets[0]))
+    }
+
+    /// Select everything
+    pub fn select_all(&mut self) {
+        let end = self.position(self.buffer.len_chars());
+        self.carets = vec![Caret::selection(TextPosition::default(), end)];
+    }
+
+    /// Add a cursor
+    pub fn add_caret(&mut self, pos: TextPosition) {
+        let pos = self.clamp(pos);
+        self.carets.push(Caret::new(pos));
+        self.normalize_carets();
+    }
+
+    /// Keep only the primary cursor
+    pub fn collapse_carets(&mut self) {
+        self.carets.truncate(1);
+    }
+
+    /// Add a cursor on the line above the topmost one (or below the
+    /// bottommost), keeping its columns
+    pub fn add_caret_vertical(&mut self, up: bool) {
+        let edge = if up {
+            self.carets.iter().min_by_key(|c| key(c.head))
+        } else {
+            self.carets.iter().max_by_key(|c| key(c.head))
+        };
+        let edge = *edge.expect("carets are never empty");
+        let Some(line) = self.step_line(edge.head.line, up) else {
             return;
+        };
+        let len = self.line_len(line);
+        let head = TextPosition::new(line, edge.head.column.min(len));
+        let caret = if edge.anchor.line == edge.head.line {
+            Caret::selection(TextPosition::new(line, edge.anchor.column.min(len)), head)
+        } else {
+            Caret::new(head)
+        };
+        self.carets.push(caret);
+        self.normalize_carets();
+    }
+
+    /// Column selection: one cursor per line between `from` and `to`,
+    /// selecting between their columns
+    pub fn add_column_selection(&mut self, from: TextPosition, to: TextPosition) {
+        let (from, to) = (self.clamp(from), self.clamp(to));
+        let lines = from.line.min(to.line)..=from.line.max(to.line);
+        let caret_on = |line: usize| {
+            let len = self.line_len(line);
+            Caret::selection(
+                TextPosition::new(line, from.column.min(len)),
+                TextPosition::new(line, to.column.min(len)),
+            )
+        };
+        let mut carets = vec![caret_on(to.line)];
+        carets.extend(
+            lines
+                .filter(|&line| line != to.line && !self.is_line_hidden(line))
+                .map(caret_on),
+        );
+        self.carets = carets;
+        self.normalize_carets();
+    }
+
+    /// Select the word at the primary cursor or, with a selection, add a
+    /// cursor selecting its next occurrence; returns whether anything changed
+    pub fn add_next_occurrence(&mut self) -> bool {
+        let primary = self.carets[0];
+        if !primary.has_selection() {
+            let TextPosition { line, column } = primary.head;
+            let (start, end) = word_range_at(&self.line(line), column);
+            if start == end {
+                return false;
+            }
+            self.carets[0] =
+                Caret::selection(TextPosition::new(line, start), TextPosition::new(line, end));
+            return true;
         }
 
-        if self.cursor.column > 0 {
-            let line = &mut self.lines[self.cursor.line];
-            let byte_pos = char_to_byte_pos(line, self.cursor.column - 1);
-            let end_byte = char_to_byte_pos(line, self.cursor.column);
-            line.replace_range(byte_pos..end_byte, "");
-            self.cursor.column -= 1;
-        } else if self.cursor.line > 0 {
-            // Merge with previous line
-            let current = self.lines.remove(self.cursor.line);
-            self.cursor.line -= 1;
-            self.cursor.column = self.lines[self.cursor.line].chars().count();
-            self.lines[self.cursor.line].push_str(&current);
+        let needle = self.selected_text();
+        let text = self.buffer.to_string();
+        let after = self
+            .carets
+            .iter()
+            .map(|c| self.char_index(c.end()))
+            .max()
+            .unwrap_or(0);
+        let after_byte = self.buffer.char_to_byte(after);
+        let Some(byte) = text[after_byte..]
+            .find(&needle)
+            .map(|b| b + after_byte)
+            .or_else(|| text.find(&needle))
+        else {
+            return false;
+        };
+        let start = self.buffer.byte_to_char(byte);
+        let range = start..start + needle.chars().count();
+        if self.carets.iter().any(|c| self.caret_range(c) == range) {
+            return false;
         }
+        let caret = Caret::selection(self.position(range.start), self.position(range.end));
+        self.reveal(caret.head.line);
+        self.carets.push(caret);
+        self.normalize_carets();
+        true
     }
 
-    fn delete_forward(&mut self) {
-        if self.selection_start.is_some() {
-            self.delete_selection();
-            return;
+    /// Sort, merge overlapping cursors and keep the primary first
+    fn normalize_carets(&mut self) {
+        let primary = self.carets[0];
+        let mut carets = std::mem::take(&mut self.carets);
+        carets.sort_by_key(|c| key(c.start()));
+
+        let mut merged: Vec<Caret> = Vec::with_capacity(carets.len());
+        let mut primary_index = 0;
+        for caret in carets {
+            if let Some(last) = merged.last_mut() {
+                if key(caret.start()) < key(last.end()) || caret.start() == last.start() {
+                    let (start, end) = (
+                        last.start(),
+                        std::cmp::max_by_key(last.end(), caret.end(), |p| key(*p)),
+                    );
+                    *last = if key(last.head) >= key(last.anchor) {
+                        Caret::selection(start, end)
+                    } else {
+                        Caret::selection(end, start)
+                    };
+                    if caret == primary {
+                        primary_index = merged.len() - 1;
+                    }
+                    continue;
+                }
+            }
+            if caret == primary {
+                primary_index = merged.len();
+            }
+            merged.push(caret);
         }
 
-        if self.cursor.line < self.lines.len() {
-            let line_len = self.lines[self.cursor.line].chars().count();
-            if self.cursor.column < line_len {
-                let line = &mut self.lines[self.cursor.line];
-                let byte_pos = char_to_byte_pos(line, self.cursor.column);
-                let end_byte = char_to_byte_pos(line, self.cursor.column + 1);
-                line.replace_range(byte_pos..end_byte, "");
-            } else if self.cursor.line + 1 < self.lines.len() {
-                // Merge with next line
-                let next = self.lines.remove(self.cursor.line + 1);
-                self.lines[self.cursor.line].push_str(&next);
-            }
+        let primary = merged.remove(primary_index);
+        merged.insert(0, primary);
+        self.carets = merged;
+    }
+
+    /// Move every cursor; `to` maps a head to its new position
+    fn move_carets(&mut self, select: bool, to: impl Fn(&Self, TextPosition) -> TextPosition) {
+        for i in 0..self.carets.len() {
+            let caret = self.carets[i];
+            let head = to(self, caret.head);
+            let anchor = if select { caret.anchor } else { head };
+            self.carets[i] = Caret::selection(anchor, head);
         }
+        self.normalize_carets();
     }
 
-    fn delete_selection(&mut self) {
-        let Some(sel_start) = self.selection_start.take() else {
-            return;
-        };
+    pub fn move_left(&mut self, select: bool) {
+        self.move_carets(select, |s, pos| {
+            if pos.column > 0 {
+                TextPosition::new(pos.line, pos.column - 1)
+            } else if let Some(line) = s.step_line(pos.line, true) {
+                TextPosition::new(line, s.line_len(line))
+            } else {
+                pos
+            }
+        });
+    }
 
-        let (start, end) = order_positions(sel_start, self.cursor);
+    pub fn move_right(&mut self, select: bool) {
+        self.move_carets(select, |s, pos| {
+            if pos.column < s.line_len(pos.line) {
+                TextPosition::new(pos.line, pos.column + 1)
+            } else if let Some(line) = s.step_line(pos.line, false) {
+                TextPosition::new(line, 0)
+            } else {
+                pos
+            }
+        });
+    }
 
-        if start.line == end.line {
-            let line = &mut self.lines[start.line];
-            let start_byte = char_to_byte_pos(line, start.column);
-            let end_byte = char_to_byte_pos(line, end.column);
-            line.replace_range(start_byte..end_byte, "");
-        } else {
-            // Multi-line selection
-            let start_byte = char_to_byte_pos(&self.lines[start.line], start.column);
-            let end_byte = char_to_byte_pos(&self.lines[end.line], end.column);
+    pub fn move_up(&mut self, select: bool) {
+        self.move_carets(select, |s, pos| match s.step_line(pos.line, true) {
+            Some(line) => TextPosition::new(line, pos.column.min(s.line_len(line))),
+            None => pos,
+        });
+    }
 
-            let new_line = self.lines[start.line][..start_byte].to_string()
-                + &self.lines[end.line][end_byte..];
+    pub fn move_down(&mut self, select: bool) {
+        self.move_carets(select, |s, pos| match s.step_line(pos.line, false) {
+            Some(line) => TextPosition::new(line, pos.column.min(s.line_len(line))),
+            None => pos,
+        });
+    }
+
+    pub fn move_to_line_start(&mut self, select: bool) {
+        self.move_carets(select, |_, pos| TextPosition::new(pos.line, 0));
+    }
 
-            // Remove lines in between
-            for _ in start.line..=end.line {
-                self.lines.remove(start.line);
+    pub fn move_to_line_end(&mut self, select: bool) {
+        self.move_carets(select, |s, pos| {
+            TextPosition::new(pos.line, s.line_len(pos.line))
+        });
+    }
+
+    /// Next visible line above or below `line`
+    fn step_line(&self, line: usize, up: bool) -> Option<usize> {
+        let mut line = line;
+        loop {
+            line = if up {
+                line.checked_sub(1)?
+            } else if line + 1 < self.line_count() {
+                line + 1
+            } else {
+                return None;
+            };
+            if !self.is_line_hidden(line) {
+                return Some(line);
             }
-            self.lines.insert(start.line, new_line);
         }
+    }
 
-        self.cursor = start;
+    /// Handle a press on a position
+    ///
+    /// Shift extends the selection, Alt adds a cursor and Alt+Shift makes a
+    /// column selection from the primary cursor.
+    fn click(&mut self, pos: TextPosition, shift: bool, alt: bool) {
+        match (alt, shift) {
+            (true, true) => self.add_column_selection(self.carets[0].anchor, pos),
+            (true, false) => self.add_caret(pos),
+            (false, true) => {
+                let anchor = self.carets[0].anchor;
+                self.set_selection(anchor, pos);
+            }
+            (false, false) => self.set_cursor(pos),
+        }
     }
 
-    fn move_left(&mut self, select: bool) {
-        if select && self.selection_start.is_none() {
-            self.selection_start = Some(self.cursor);
-        } else if !select {
-            self.selection_start = None;
+    // ========================================================================
+    // Folding
+    // ========================================================================
+
+    pub fn fold_mode(&self) -> Option<FoldMode> {
+        self.fold_mode
+    }
+
+    /// Enable folding; folds are ignored while it is `None`
+    pub fn set_fold_mode(&mut self, mode: Option<FoldMode>) {
+        self.fold_mode = mode;
+    }
+
+    /// Foldable regions, ordered by start line
+    pub fn fold_ranges(&self) -> Vec<FoldRange> {
+        match self.fold_mode {
+            None => Vec::new(),
+            Some(FoldMode::Indentation) => indentation_folds(self.buffer.lines()),
+            Some(FoldMode::Brackets) => self.bracket_folds(),
         }
+    }
 
-        if self.cursor.column > 0 {
-            self.cursor.column -= 1;
-        } else if self.cursor.line > 0 {
-            self.cursor.line -= 1;
-            self.cursor.column = self.lines[self.cursor.line].chars().count();
+    /// Fold the region starting at `line`; returns whether there is one
+    ///
+    /// Cursors inside the region move to the end of `line`.
+    pub fn fold(&mut self, line: usize) -> bool {
+        let Some(range) = self.fold_ranges().into_iter().find(|r| r.start == line) else {
+            return false;
+        };
+        self.folded.insert(range.start, range.end);
+        let fold_end = TextPosition::new(line, self.line_len(line));
+        let hidden = |pos: TextPosition| pos.line > range.start && pos.line <= range.end;
+        for caret in &mut self.carets {
+            if hidden(caret.anchor) {
+                caret.anchor = fold_end;
+            }
+            if hidden(caret.head) {
+                caret.head = fold_end;
+            }
         }
+        self.normalize_carets();
+        true
+    }
+
+    /// Unfold the region starting at `line`; returns whether it was folded
+    pub fn unfold(&mut self, line: usize) -> bool {
+        self.folded.remove(&line).is_some()
     }
 
-    fn move_right(&mut self, select: bool) {
-        if select && self.selection_start.is_none() {
-            self.selection_start = Some(self.cursor);
-        } else if !select {
-            self.selection_start = None;
+    /// Fold or unfold the region starting at `line`
+    pub fn toggle_fold(&mut self, line: usize) {
+        if !self.unfold(line) {
+            self.fold(line);
         }
+    }
+
+    pub fn unfold_all(&mut self) {
+        self.folded.clear();
+    }
 
-        if self.cursor.line < self.lines.len() {
-            let line_len = self.lines[self.cursor.line].chars().count();
-            if self.cursor.column < line_len {
-                self.cursor.column += 1;
-            } else if self.cursor.line + 1 < self.lines.len() {
-                self.cursor.line += 1;
-                self.cursor.column = 0;
+    /// Whether the region starting at `line` is folded
+    pub fn is_folded(&self, line: usize) -> bool {
+        self.fold_mode.is_some() && self.folded.contains_key(&line)
+    }
+
+    /// Whether `line` is inside a folded region
+    pub fn is_line_hidden(&self, line: usize) -> bool {
+        self.fold_mode.is_some() && self.folded.range(..line).any(|(_, &end)| line <= end)
+    }
+
+    /// Unfold every region hiding `line`
+    fn reveal(&mut self, line: usize) {
+        self.folded
+            .retain(|&start, &mut end| !(start < line && line <= end));
+    }
+
+    fn bracket_folds(&self) -> Vec<FoldRange> {
+        let mut ranges = Vec::new();
+        let mut open: Vec<(char, usize)> = Vec::new();
+        for (line_index, line) in self.buffer.lines().enumerate() {
+            for (byte, c) in line.char_indices() {
+                if !is_bracket(c) || !self.is_code_at(line_index, byte) {
+                    continue;
+                }
+                if closing_bracket(c).is_some() {
+                    open.push((c, line_index));
+                } else if open.last().and_then(|&(o, _)| closing_bracket(o)) == Some(c) {
+                    let (_, start) = open.pop().unwrap();
+                    // The closing line stays visible
+                    if line_index > start + 1 {
+                        ranges.push(FoldRange {
+                            start,
+                            end: line_index - 1,
+                        });
+                    }
+                }
             }
         }
+        // Several brackets opening on one line fold as far as the outermost
+        ranges.sort_by_key(|r| (r.start, std::cmp::Reverse(r.end)));
+        ranges.dedup_by_key(|r| r.start);
+        ranges
+    }
+
+    /// Whether the byte at `byte` of `line` is code rather than a string or
+    /// comment, as far as the highlighter knows
+    fn is_code_at(&self, line: usize, byte: usize) -> bool {
+        let Some(styled) = self.highlight.line(line) else {
+            return true;
+        };
+        !styled.spans.iter().any(|span| {
+            span.start <= byte
+                && byte < span.end
+                && matches!(
+                    span.token_type,
+                    Some(TokenType::String) | Some(TokenType::Comment)
+                )
+        })
+    }
+
+    fn is_code_at_char(&self, char_idx: usize) -> bool {
+        let line = self.buffer.char_to_line(char_idx);
+        let byte = self.buffer.char_to_byte(char_idx) - self.buffer.line_to_byte(line);
+        self.is_code_at(line, byte)
     }
 
-    fn move_up(&mut self, select: bool) {
-        if select && self.selection_start.is_none() {
-            self.selection_start = Some(self.cursor);
-        } else if !select {
-            self.selection_start = None;
+    // ========================================================================
+    // Bracket matching
+    // ========================================================================
+
+    /// Bracket at (or else just before) the primary cursor and its partner
+    pub fn matching_bracket(&self) -> Option<(TextPosition, TextPosition)> {
+        let at = self.char_index(self.cursor());
+        let rope = self.buffer.rope();
+        [Some(at), at.checked_sub(1)]
+            .into_iter()
+            .flatten()
+            .filter(|&i| i < rope.len_chars())
+            .find_map(|i| {
+                let c = rope.char(i);
+                if !is_bracket(c) || !self.is_code_at_char(i) {
+                    return None;
+                }
+                let partner = self.find_partner(i, c)?;
+                Some((self.position(i), self.position(partner)))
+            })
+    }
+
+    /// Char offset of the bracket matching `bracket` at `at`
+    fn find_partner(&self, at: usize, bracket: char) -> Option<usize> {
+        let rope = self.buffer.rope();
+        let mut depth = 0usize;
+        let mut visit = |i: usize, c: char| -> Option<usize> {
+            if !is_bracket(c) || !self.is_code_at_char(i) {
+                return None;
+            }
+            let same_direction = closing_bracket(c).is_some() == closing_bracket(bracket).is_some();
+            if same_direction {
+                depth += 1;
+                None
+            } else if depth == 0 {
+                Some(i)
+            } else {
+                depth -= 1;
+                None
+            }
+        };
+
+        if let Some(close) = closing_bracket(bracket) {
+            rope.chars_at(at + 1)
+                .enumerate()
+                .take(MAX_BRACKET_SCAN)
+                .find_map(|(n, c)| visit(at + 1 + n, c))
+                .filter(|&i| rope.char(i) == close)
+        } else {
+            let open = opening_bracket(bracket)?;
+            let mut chars = rope.chars_at(at);
+            std::iter::from_fn(|| chars.prev())
+                .enumerate()
+                .take(MAX_BRACKET_SCAN)
+                .find_map(|(n, c)| visit(at - 1 - n, c))
+                .filter(|&i| rope.char(i) == open)
         }
+    }
 
-        if self.cursor.line > 0 {
-            self.cursor.line -= 1;
-            let line_len = self.lines[self.cursor.line].chars().count();
-            self.cursor.column = self.cursor.column.min(line_len);
+    // ========================================================================
+    // Find and replace
+    // ========================================================================
+
+    /// Start a search, or clear it with `None`; returns the number of matches
+    pub fn set_search(&mut self, query: Option<SearchQuery>) -> Result<usize, regex::Error> {
+        let Some(query) = query else {
+            self.search = None;
+            return Ok(0);
+        };
+        let regex = query.compile()?;
+        self.search = Some(Search {
+            query,
+            regex,
+            matches: Vec::new(),
+            revision: u64::MAX,
+        });
+        Ok(self.search_ranges().len())
+    }
+
+    /// The active search
+    pub fn search_query(&self) -> Option<&SearchQuery> {
+        self.search.as_ref().map(|search| &search.query)
+    }
+
+    /// Matches of the active search
+    pub fn search_matches(&mut self) -> Vec<(TextPosition, TextPosition)> {
+        self.search_ranges()
+            .into_iter()
+            .map(|m| (self.position(m.start), self.position(m.end)))
+            .collect()
+    }
+
+    /// Matches of the active search as char ranges, refreshed after edits
+    fn search_ranges(&mut self) -> Vec<Range<usize>> {
+        let revision = self.buffer.revision();
+        let Some(search) = self.search.as_mut() else {
+            return Vec::new();
+        };
+        if search.revision != revision {
+            let text = self.buffer.to_string();
+            search.matches = search
+                .regex
+                .find_iter(&text)
+                .filter(|m| !m.is_empty())
+                .map(|m| self.buffer.byte_to_char(m.start())..self.buffer.byte_to_char(m.end()))
+                .collect();
+            search.revision = revision;
         }
+        search.matches.clone()
+    }
+
+    /// Select the next match after the primary cursor, wrapping around
+    pub fn find_next(&mut self) -> bool {
+        let matches = self.search_ranges();
+        let after = self.char_index(self.carets[0].end());
+        let found = matches
+            .iter()
+            .find(|m| m.start >= after)
+            .or(matches.first());
+        self.select_match(found.cloned())
+    }
+
+    /// Select the previous match before the primary cursor, wrapping around
+    pub fn find_previous(&mut self) -> bool {
+        let matches = self.search_ranges();
+        let before = self.char_index(self.carets[0].start());
+        let found = matches
+            .iter()
+            .rev()
+            .find(|m| m.start < before)
+            .or(matches.last());
+        self.select_match(found.cloned())
     }
 
-    fn move_down(&mut self, select: bool) {
-        if select && self.selection_start.is_none() {
-            self.selection_start = Some(self.cursor);
-        } else if !select {
-            self.selection_start = None;
+    fn select_match(&mut self, found: Option<Range<usize>>) -> bool {
+        let Some(m) = found else {
+            return false;
+        };
+        let caret = Caret::selection(self.position(m.start), self.position(m.end));
+        self.reveal(caret.start().line);
+        self.carets = vec![caret];
+        true
+    }
+
+    /// Replace the selected match and select the next one
+    ///
+    /// If the selection isn't a match, only selects the next match. Returns
+    /// whether anything was replaced.
+    pub fn replace_current(&mut self, replacement: &str) -> bool {
+        let selected = self.caret_range(&self.carets[0]);
+        if !self.search_ranges().contains(&selected) {
+            self.find_next();
+            return false;
         }
+        let text = self.buffer.to_string();
+        let new_text = self.expand_replacement(&text, &selected, replacement);
+        let end = self.replace_chars(selected, &new_text);
+        self.set_cursor(self.position(end));
+        self.find_next();
+        true
+    }
 
-        if self.cursor.line + 1 < self.lines.len() {
-            self.cursor.line += 1;
-            let line_len = self.lines[self.cursor.line].chars().count();
-            self.cursor.column = self.cursor.column.min(line_len);
+    /// Replace every match; returns how many were replaced
+    pub fn replace_all(&mut self, replacement: &str) -> usize {
+        let matches = self.search_ranges();
+        let text = self.buffer.to_string();
+        let replacements: Vec<String> = matches
+            .iter()
+            .map(|m| self.expand_replacement(&text, m, replacement))
+            .collect();
+        for (m, new_text) in matches.iter().zip(&replacements).rev() {
+            self.replace_chars(m.clone(), new_text);
         }
+        let cursor = self.carets[0].head;
+        self.set_cursor(cursor);
+        matches.len()
     }
 
-    fn move_to_line_start(&mut self, select: bool) {
-        if select && self.selection_start.is_none() {
-            self.selection_start = Some(self.cursor);
-        } else if !select {
-            self.selection_start = None;
+    /// Put a cursor on every match
+    pub fn select_all_matches(&mut self) -> bool {
+        let matches = self.search_ranges();
+        if matches.is_empty() {
+            return false;
+        }
+        self.carets = matches
+            .iter()
+            .map(|m| Caret::selection(self.position(m.start), self.position(m.end)))
+            .collect();
+        for line in self.carets.iter().map(|c| c.head.line).collect::<Vec<_>>() {
+            self.reveal(line);
         }
-        self.cursor.column = 0;
+        self.normalize_carets();
+        true
     }
 
-    fn move_to_line_end(&mut self, select: bool) {
-        if select && self.selection_start.is_none() {
-            self.selection_start = Some(self.cursor);
-        } else if !select {
-            self.selection_start = None;
+    /// Replacement text for a match, expanding `$n` groups for regex searches
+    fn expand_replacement(&self, text: &str, m: &Range<usize>, replacement: &str) -> String {
+        let Some(search) = self.search.as_ref().filter(|s| s.query.regex) else {
+            return replacement.to_string();
+        };
+        let start = self.buffer.char_to_byte(m.start);
+        let mut expanded = String::new();
+        match search.regex.captures_at(text, start) {
+            Some(captures) if captures.get(0).map(|g| g.start()) == Some(start) => {
+                captures.expand(replacement, &mut expanded);
+            }
+            _ => expanded.push_str(replacement),
         }
-        if self.cursor.line < self.lines.len() {
-            self.cursor.column = self.lines[self.cursor.line].chars().count();
+        expanded
+    }
+
+    // ========================================================================
+    // Diagnostics and highlighting
+    // ========================================================================
+
+    /// Replace the diagnostics shown in the gutter
+    pub fn set_diagnostics(&mut self, diagnostics: Vec<Diagnostic>) {
+        self.diagnostics = diagnostics;
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    /// Most severe diagnostic on `line`
+    fn line_severity(&self, line: usize) -> Option<DiagnosticSeverity> {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.line == line)
+            .map(|d| d.severity)
+            .max()
+    }
+
+    /// Bring highlighting up to date; returns the lines highlighted again
+    fn update_highlighting(
+        &mut self,
+        highlighter: Option<&Arc<dyn SyntaxHighlighter>>,
+    ) -> Range<usize> {
+        let id = highlighter.map_or(0, |h| Arc::as_ptr(h) as *const () as usize);
+        if id != self.highlighter_id {
+            self.highlighter_id = id;
+            self.highlight.reset(self.buffer.len_lines());
         }
+        let Some(highlighter) = highlighter else {
+            return 0..0;
+        };
+        let buffer = &self.buffer;
+        self.highlight
+            .update(highlighter.as_ref(), |line| buffer.line(line))
     }
-}
 
-/// Convert character position to byte position
-fn char_to_byte_pos(s: &str, char_pos: usize) -> usize {
-    s.char_indices()
-        .nth(char_pos)
-        .map(|(i, _)| i)
-        .unwrap_or(s.len())
+    /// Styled text of a line, plain if not highlighted
+    fn styled_line(&self, line: usize, color: Color) -> StyledLine {
+        if let Some(styled) = self.highlight.line(line) {
+            return styled.clone();
+        }
+        let text = self.line(line);
+        let spans = if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![TextSpan::new(0, text.len(), color, false)]
+        };
+        StyledLine::new(text, spans)
+    }
 }
 
 /// Order two positions (earlier, later)
@@ -371,7 +1237,99 @@ fn order_positions(a: TextPosition, b: TextPosition) -> (TextPosition, TextPosit
     }
 }
 
-type SharedCodeState = Arc<Mutex<CodeState>>;
+/// Sort key of a position
+fn key(pos: TextPosition) -> (usize, usize) {
+    (pos.line, pos.column)
+}
+
+fn is_bracket(c: char) -> bool {
+    matches!(c, '(' | ')' | '[' | ']' | '{' | '}')
+}
+
+fn closing_bracket(open: char) -> Option<char> {
+    match open {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        _ => None,
+    }
+}
+
+fn opening_bracket(close: char) -> Option<char> {
+    match close {
+        ')' => Some('('),
+        ']' => Some('['),
+        '}' => Some('{'),
+        _ => None,
+    }
+}
+
+/// Foldable regions by indentation: a line folds the following lines
+/// indented deeper, up to the last of them that isn't blank
+fn indentation_folds(lines: impl Iterator<Item = String>) -> Vec<FoldRange> {
+    let mut ranges = Vec::new();
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut last_content = 0;
+    let close = |open: (usize, usize), last_content: usize, ranges: &mut Vec<FoldRange>| {
+        if last_content > open.0 {
+            ranges.push(FoldRange {
+                start: open.0,
+                end: last_content,
+            });
+        }
+    };
+
+    for (i, line) in lines.enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let indent: usize = leading_indent(&line)
+            .chars()
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum();
+        while open.last().is_some_and(|&(_, width)| width >= indent) {
+            close(open.pop().unwrap(), last_content, &mut ranges);
+        }
+        open.push((i, indent));
+        last_content = i;
+    }
+    while let Some(entry) = open.pop() {
+        close(entry, last_content, &mut ranges);
+    }
+    ranges.sort_by_key(|r| r.start);
+    ranges
+}
+
+/// Width of `text` in the code font
+fn measure_code(text: &str, font_size: f32) -> f32 {
+    let options = TextLayoutOptions {
+        generic_font: GenericFont::Monospace,
+        ..TextLayoutOptions::new()
+    };
+    measure_text_with_options(text, font_size, &options).width
+}
+
+/// Column in `line` closest to `x`
+fn column_at(line: &str, x: f32, font_size: f32) -> usize {
+    let mut best = (0, x.abs());
+    let mut prefix = String::new();
+    for (i, c) in line.chars().enumerate() {
+        prefix.push(c);
+        let dist = (measure_code(&prefix, font_size) - x).abs();
+        if dist < best.1 {
+            best = (i + 1, dist);
+        }
+    }
+    best.0
+}
+
+/// Shared code state handle
+pub type SharedCodeState = Arc<Mutex<CodeState>>;
+
+/// Create a shared code state for [`code_editor()`]
+pub fn code_state(content: &str) -> SharedCodeState {
+    Arc::new(Mutex::new(CodeState::new(content)))
+}
 
 // ============================================================================
 // Code Widget
@@ -391,9 +1349,7 @@ pub struct Code {
     /// The actual visual structure (Div with all children)
     /// This is rebuilt whenever config changes
     inner: Div,
-    /// Static content (for read-only mode)
-    content: String,
-    /// Internal state (for edit mode)
+    /// Text, cursors and folds
     state: SharedCodeState,
     /// Configuration
     config: CodeConfig,
@@ -423,15 +1379,15 @@ impl DerefMut for Code {
 impl Code {
     /// Create a new code block with the given content
     pub fn new(content: impl Into<String>) -> Self {
-        let content = content.into();
-        let state = Arc::new(Mutex::new(CodeState::new(&content)));
-        let config = CodeConfig::default();
+        Self::with_state(&code_state(&content.into()))
+    }
 
+    /// Create a code block showing shared state
+    pub fn with_state(state: &SharedCodeState) -> Self {
         let mut code = Self {
             inner: Div::new(),
-            content,
-            state,
-            config,
+            state: Arc::clone(state),
+            config: CodeConfig::default(),
             highlighter: None,
             on_change: None,
             on_token_click: None,
@@ -481,6 +1437,39 @@ impl Code {
         self.config.bg_color = bg_color;
         self.config.text_color = text_color;
         self.config.line_number_color = line_number_color;
+        self.config.current_line_color = tint(text_color);
+        self.rebuild_inner();
+        self
+    }
+
+    /// Highlight as the given language, by name or file extension
+    ///
+    /// Leaves highlighting off if no grammar or built-in highlighter knows
+    /// the language.
+    pub fn language(self, language: &str) -> Self {
+        match SyntaxConfig::for_language(language) {
+            Some(config) => self.syntax(config),
+            None => self,
+        }
+    }
+
+    /// Enable code folding with fold markers in the gutter
+    pub fn folding(mut self, mode: FoldMode) -> Self {
+        self.config.folding = Some(mode);
+        self.rebuild_inner();
+        self
+    }
+
+    /// Enable or disable the current-line highlight
+    pub fn highlight_current_line(mut self, enabled: bool) -> Self {
+        self.config.highlight_current_line = enabled;
+        self.rebuild_inner();
+        self
+    }
+
+    /// Show diagnostics in the gutter
+    pub fn diagnostics(mut self, diagnostics: Vec<Diagnostic>) -> Self {
+        self.state.lock().unwrap().set_diagnostics(diagnostics);
         self.rebuild_inner();
         self
     }
@@ -512,6 +1501,7 @@ impl Code {
         F: Fn(&str) + Send + Sync + 'static,
     {
         self.on_change = Some(Arc::new(callback));
+        self.rebuild_inner();
         self
     }
 
@@ -553,64 +1543,101 @@ impl Code {
     // Internal Methods
     // ========================================================================
 
-    /// Get the styled content with syntax highlighting applied
-    fn get_styled_content(&self) -> StyledText {
-        let content = if self.config.editable {
-            self.state.lock().unwrap().value()
-        } else {
-            self.content.clone()
-        };
-
-        if let Some(ref highlighter) = self.highlighter {
-            highlighter.highlight(&content)
-        } else {
-            StyledText::plain(&content, self.config.text_color)
-        }
-    }
-
     /// Create the visual structure (the actual code display)
     fn create_visual_structure(&self) -> Div {
-        let styled = self.get_styled_content();
-        let line_height_px = self.config.font_size * self.config.line_height;
-        let num_lines = styled.line_count().max(1);
+        let config = &self.config;
+        let mut guard = self.state.lock().unwrap();
+        guard.set_fold_mode(config.folding);
+        guard.update_highlighting(self.highlighter.as_ref());
+        let search_matches = guard.search_ranges();
+        let state = &*guard;
+
+        let line_height_px = config.font_size * config.line_height;
+        let editing = config.editable && state.focused;
+        let visible: Vec<usize> = (0..state.line_count())
+            .filter(|&line| !state.is_line_hidden(line))
+            .collect();
+        let fold_starts: BTreeSet<usize> = state.fold_ranges().iter().map(|r| r.start).collect();
+        let caret_lines: BTreeSet<usize> = state.carets.iter().map(|c| c.head.line).collect();
+        let bracket = if editing {
+            state.matching_bracket()
+        } else {
+            None
+        };
 
         // Main container
         let mut container = div()
             .flex_row()
-            .bg(self.config.bg_color)
-            .rounded(self.config.corner_radius)
+            .bg(config.bg_color)
+            .rounded(config.corner_radius)
             .overflow_clip();
 
-        // Line numbers gutter (if enabled)
-        if self.config.line_numbers {
+        // Gutter with line numbers, fold markers and diagnostics
+        let has_diagnostics = !state.diagnostics.is_empty();
+        if config.line_numbers || config.folding.is_some() || has_diagnostics {
             let mut line_numbers_col = div()
                 .flex_col()
-                .padding_y_px(self.config.padding)
-                .padding_x_px(8.0);
+                .padding_y_px(config.padding)
+                .padding_x_px(if config.folding.is_some() { 4.0 } else { 8.0 });
+
+            for &line in &visible {
+                let mut row = div().h(line_height_px).flex_row().items_center();
+
+                if has_diagnostics {
+                    let mut marker = div().w(10.0).flex_row().items_center();
+                    if let Some(severity) = state.line_severity(line) {
+                        marker = marker.child(
+                            div()
+                                .w(6.0)
+                                .h(6.0)
+                                .rounded(3.0)
+                                .bg(config.severity_color(severity)),
+                        );
+                    }
+                    row = row.child(marker);
+                }
 
-            for line_num in 1..=num_lines {
-                line_numbers_col = line_numbers_col.child(
-                    div()
-                        .h(line_height_px)
-                        .flex_row()
-                        .justify_end()
-                        .items_center()
-                        .child(
-                            text(format!("{}", line_num))
-                                .size(self.config.font_size)
-                                .color(self.config.line_number_color)
-                                .text_right(),
-                        ),
-                );
+                let mut number = div().flex_grow().flex_row().justify_end();
+                if config.line_numbers {
+                    number = number.child(
+                        text(format!("{}", line + 1))
+                            .size(config.font_size)
+                            .color(config.line_number_color)
+                            .text_right(),
+                    );
+                }
+                row = row.child(number);
+
+                if config.folding.is_some() {
+                    let mut marker = div().w(14.0).flex_row().justify_center();
+                    if fold_starts.contains(&line) {
+                        let glyph = if state.is_folded(line) { "▸" } else { "▾" };
+                        let state_for_fold = Arc::clone(&self.state);
+                        marker = marker
+                            .child(
+                                text(glyph)
+                                    .size(config.font_size)
+                                    .color(config.fold_marker_color),
+                            )
+                            .on_mouse_down(move |_ctx| {
+                                state_for_fold.lock().unwrap().toggle_fold(line);
+                                request_rebuild();
+                            });
+                    }
+                    row = row.child(marker);
+                }
+
+                line_numbers_col = line_numbers_col.child(row);
             }
 
+            let fold_width = if config.folding.is_some() { 14.0 } else { 0.0 };
             // Gutter with separator (separator as a 1px wide div)
             let gutter = div()
                 .flex_row()
-                .bg(self.config.gutter_bg_color)
-                .w(self.config.gutter_width)
+                .bg(config.gutter_bg_color)
+                .w(config.gutter_width + fold_width)
                 .child(line_numbers_col.flex_grow())
-                .child(div().w(1.0).h_full().bg(self.config.gutter_separator_color));
+                .child(div().w(1.0).h_full().bg(config.gutter_separator_color));
 
             container = container.child(gutter);
         }
@@ -620,34 +1647,102 @@ impl Code {
         let mut code_area = div()
             .flex_col()
             .flex_grow()
-            .padding_x_px(self.config.padding)
-            .padding_y_px(self.config.padding)
+            .padding_x_px(config.padding)
+            .padding_y_px(config.padding)
             .relative();
 
-        // Render each line with styled spans
-        for styled_line in &styled.lines {
+        for &line in &visible {
+            let styled_line = state.styled_line(line, config.text_color);
+            let line_start = state.buffer.line_to_char(line);
+            let line_end = line_start + styled_line.text.chars().count();
+
             // Don't use overflow_clip on line divs - rely on outer container's clip
-            let mut line_div = div().h(line_height_px).flex_row().items_center();
+            let mut line_div = div().h(line_height_px).flex_row().items_center().relative();
 
-            if styled_line.spans.is_empty() {
-                // Empty line - add a space to maintain height
+            // Backgrounds go first so the text draws over them
+            if editing && config.highlight_current_line && caret_lines.contains(&line) {
                 line_div = line_div.child(
-                    text(" ")
-                        .size(self.config.font_size)
-                        .color(self.config.text_color),
+                    div()
+                        .absolute()
+                        .left(0.0)
+                        .top(0.0)
+                        .w_full()
+                        .h(line_height_px)
+                        .bg(config.current_line_color),
                 );
+            }
+            let first_match = search_matches.partition_point(|m| m.end <= line_start);
+            for m in search_matches[first_match..]
+                .iter()
+                .take_while(|m| m.start < line_end)
+            {
+                let (from, to) = (m.start.max(line_start), m.end.min(line_end));
+                line_div = line_div.child(self.highlight_box(
+                    &styled_line.text,
+                    from - line_start,
+                    to - line_start,
+                    0.0,
+                    config.search_match_color,
+                ));
+            }
+            if config.editable {
+                for caret in state.carets.iter().filter(|c| c.has_selection()) {
+                    let range = state.caret_range(caret);
+                    if range.end < line_start || range.start > line_end {
+                        continue;
+                    }
+                    let (from, to) = (range.start.max(line_start), range.end.min(line_end));
+                    // A selected line break shows as a sliver after the text
+                    let extra = if range.end > line_end {
+                        config.font_size * 0.3
+                    } else {
+                        0.0
+                    };
+                    if from < to || extra > 0.0 {
+                        line_div = line_div.child(self.highlight_box(
+                            &styled_line.text,
+                            from - line_start,
+                            to - line_start,
+                            extra,
+                            config.selection_color,
+                        ));
+                    }
+                }
+            }
+            if let Some((a, b)) = bracket {
+                for pos in [a, b].into_iter().filter(|p| p.line == line) {
+                    line_div = line_div.child(
+                        self.highlight_box(
+                            &styled_line.text,
+                            pos.column,
+                            pos.column + 1,
+                            0.0,
+                            Color::TRANSPARENT,
+                        )
+                        .border(1.0, config.bracket_match_color),
+                    );
+                }
+            }
+
+            if styled_line.spans.is_empty() {
+                // Empty line - add a space to maintain height
+                line_div =
+                    line_div.child(text(" ").size(config.font_size).color(config.text_color));
             } else {
                 // Render each span with its color
                 for span in &styled_line.spans {
                     let span_text = &styled_line.text[span.start..span.end];
                     let mut txt = text(span_text)
-                        .size(self.config.font_size)
+                        .size(config.font_size)
                         .color(span.color)
                         .no_wrap(); // Don't wrap individual spans
 
                     if span.bold {
                         txt = txt.bold();
                     }
+                    if span.italic {
+                        txt = txt.italic();
+                    }
 
                     txt = txt.monospace();
 
@@ -655,46 +1750,103 @@ impl Code {
                 }
             }
 
+            if state.is_folded(line) {
+                line_div = line_div.child(
+                    text(" …")
+                        .size(config.font_size)
+                        .color(config.fold_marker_color)
+                        .monospace(),
+                );
+            }
+
+            if editing {
+                for caret in state.carets.iter().filter(|c| c.head.line == line) {
+                    line_div = line_div.child(self.cursor_canvas(
+                        state,
+                        &styled_line.text,
+                        caret.head.column,
+                        caret == &state.carets[0],
+                    ));
+                }
+            }
+
+            if config.editable {
+                let state_for_click = Arc::clone(&self.state);
+                let line_text = styled_line.text.clone();
+                let font_size = config.font_size;
+                line_div = line_div.on_mouse_down(move |ctx| {
+                    let mut s = state_for_click.lock().unwrap();
+                    let column = column_at(&line_text, ctx.local_x, font_size);
+                    s.click(TextPosition::new(line, column), ctx.shift, ctx.alt);
+                    if let Ok(mut cs) = s.cursor_state.lock() {
+                        cs.reset_blink();
+                    };
+                });
+            }
+
             code_area = code_area.child(line_div);
         }
 
-        // Add cursor if editable and focused
-        if self.config.editable {
-            let state = self.state.lock().unwrap();
-            if state.focused {
-                let cursor_height = self.config.font_size * 1.2;
-                let cursor_line = state.cursor.line;
-                let cursor_col = state.cursor.column;
-
-                // Calculate cursor x position
-                let cursor_x = if cursor_col > 0 && cursor_line < state.lines.len() {
-                    let line_text = &state.lines[cursor_line];
-                    let text_before: String = line_text.chars().take(cursor_col).collect();
-                    crate::text_measure::measure_text(&text_before, self.config.font_size).width
-                } else {
-                    0.0
-                };
-
-                let cursor_top =
-                    (cursor_line as f32 * line_height_px) + (line_height_px - cursor_height) / 2.0;
-
-                let cursor_state_clone = Arc::clone(&state.cursor_state);
-
-                // Update cursor state
-                {
-                    if let Ok(mut cs) = cursor_state_clone.lock() {
-                        cs.visible = true;
-                        cs.color = self.config.cursor_color;
-                        cs.x = cursor_x;
-                        cs.animation = CursorAnimation::SmoothFade;
-                    }
-                }
+        drop(guard);
+        container = container.child(code_area);
+
+        // Add event handlers if editable
+        if config.editable {
+            container = self.attach_handlers(container);
+        }
 
-                drop(state);
+        container
+    }
+
+    /// Box behind the chars `from..to` of a line
+    fn highlight_box(&self, line: &str, from: usize, to: usize, extra: f32, color: Color) -> Div {
+        let font_size = self.config.font_size;
+        let prefix: String = line.chars().take(from).collect();
+        let selected: String = line
+            .chars()
+            .skip(from)
+            .take(to.saturating_sub(from))
+            .collect();
+        let left = measure_code(&prefix, font_size);
+        let width = measure_code(&selected, font_size) + extra;
+        let height = font_size * 1.2;
+        div()
+            .absolute()
+            .left(left)
+            .top((font_size * self.config.line_height - height) / 2.0)
+            .w(width)
+            .h(height)
+            .bg(color)
+    }
+
+    /// Blinking cursor before char `column` of a line
+    fn cursor_canvas(&self, state: &CodeState, line: &str, column: usize, primary: bool) -> Div {
+        let font_size = self.config.font_size;
+        let cursor_height = font_size * 1.2;
+        let prefix: String = line.chars().take(column).collect();
+        let cursor_x = measure_code(&prefix, font_size);
+        let cursor_top = (font_size * self.config.line_height - cursor_height) / 2.0;
+
+        let cursor_state_clone = Arc::clone(&state.cursor_state);
+        if primary {
+            if let Ok(mut cs) = cursor_state_clone.lock() {
+                cs.visible = true;
+                cs.color = self.config.cursor_color;
+                cs.x = cursor_x;
+                cs.animation = CursorAnimation::SmoothFade;
+            }
+        }
 
-                // Add cursor canvas
-                let cursor_color = self.config.cursor_color;
-                let cursor_canvas = canvas(
+        // All cursors blink together, driven by the shared cursor state
+        let cursor_color = self.config.cursor_color;
+        div()
+            .absolute()
+            .top(cursor_top)
+            .left(cursor_x)
+            .w(2.0)
+            .h(cursor_height)
+            .child(
+                canvas(
                     move |ctx: &mut dyn blinc_core::DrawContext,
                           bounds: crate::canvas::CanvasBounds| {
                         let cs = cursor_state_clone.lock().unwrap();
@@ -722,147 +1874,132 @@ impl Code {
                         );
                     },
                 )
-                .absolute()
-                .top(cursor_top)
-                .left(cursor_x)
                 .w(2.0)
-                .h(cursor_height);
+                .h(cursor_height),
+            )
+    }
 
-                code_area = code_area.child(cursor_canvas);
-            }
-        }
+    fn attach_handlers(&self, container: Div) -> Div {
+        let state_for_click = Arc::clone(&self.state);
+        let state_for_key = Arc::clone(&self.state);
+        let state_for_text = Arc::clone(&self.state);
+        let state_for_blur = Arc::clone(&self.state);
+        let on_change_for_key = self.on_change.clone();
+        let on_change_for_text = self.on_change.clone();
 
-        container = container.child(code_area);
+        container
+            .on_mouse_down(move |_ctx| {
+                let mut s = state_for_click.lock().unwrap();
+                if !s.focused {
+                    s.focused = true;
+                    increment_focus_count();
+                    request_continuous_redraw_pub();
+                }
+                request_rebuild();
+            })
+            .on_blur(move |_ctx| {
+                let mut s = state_for_blur.lock().unwrap();
+                s.focused = false;
+                s.collapse_carets();
+                let cursor = s.cursor();
+                s.set_cursor(cursor);
+                if let Ok(mut cs) = s.cursor_state.lock() {
+                    cs.visible = false;
+                }
+                decrement_focus_count();
+                request_rebuild();
+            })
+            .on_key_down(move |ctx| {
+                let mut s = state_for_key.lock().unwrap();
+                if !s.focused {
+                    return;
+                }
 
-        // Add event handlers if editable
-        if self.config.editable {
-            let state_for_click = Arc::clone(&self.state);
-            let state_for_key = Arc::clone(&self.state);
-            let state_for_text = Arc::clone(&self.state);
-            let state_for_blur = Arc::clone(&self.state);
-            let on_change_for_key = self.on_change.clone();
-            let on_change_for_text = self.on_change.clone();
-
-            container = container
-                .on_mouse_down(move |_ctx| {
-                    let mut s = state_for_click.lock().unwrap();
-                    if !s.focused {
-                        s.focused = true;
-                        increment_focus_count();
-                        request_continuous_redraw_pub();
+                let revision = s.buffer.revision();
+                let command = ctx.ctrl || ctx.meta;
+                let mut cursor_changed = true;
+
+                match ctx.key_code {
+                    // Backspace
+                    8 => s.delete_backward(),
+                    // Delete
+                    127 => s.delete_forward(),
+                    // Enter
+                    13 => s.insert_newline(),
+                    // Tab - insert spaces
+                    9 => s.insert("    "),
+                    // Escape - back to a single cursor
+                    27 => s.collapse_carets(),
+                    // Alt+Shift or Ctrl/Cmd+Alt with Up/Down - add a cursor (column selection)
+                    38 | 40 if ctx.alt && (ctx.shift || command) => {
+                        s.add_caret_vertical(ctx.key_code == 38)
                     }
-                    request_rebuild();
-                })
-                .on_blur(move |_ctx| {
-                    let mut s = state_for_blur.lock().unwrap();
-                    s.focused = false;
-                    s.selection_start = None;
-                    if let Ok(mut cs) = s.cursor_state.lock() {
-                        cs.visible = false;
+                    37 => s.move_left(ctx.shift),
+                    39 => s.move_right(ctx.shift),
+                    38 => s.move_up(ctx.shift),
+                    40 => s.move_down(ctx.shift),
+                    36 => s.move_to_line_start(ctx.shift),
+                    35 => s.move_to_line_end(ctx.shift),
+                    // Ctrl/Cmd+A - select all
+                    65 if command => s.select_all(),
+                    // Ctrl/Cmd+D - add the next occurrence of the selection
+                    68 if command => {
+                        s.add_next_occurrence();
                     }
-                    decrement_focus_count();
-                    request_rebuild();
-                })
-                .on_key_down(move |ctx| {
-                    let mut s = state_for_key.lock().unwrap();
-                    if !s.focused {
-                        return;
+                    // Ctrl/Cmd+G, with Shift backwards - next search match
+                    71 if command && ctx.shift => {
+                        s.find_previous();
                     }
-
-                    let mut changed = false;
-                    let mut cursor_changed = true;
-
-                    match ctx.key_code {
-                        8 => {
-                            // Backspace
-                            s.delete_backward();
-                            changed = true;
-                        }
-                        127 => {
-                            // Delete
-                            s.delete_forward();
-                            changed = true;
-                        }
-                        13 => {
-                            // Enter
-                            s.insert("\n");
-                            changed = true;
-                        }
-                        37 => {
-                            // Left arrow
-                            s.move_left(ctx.shift);
-                        }
-                        39 => {
-                            // Right arrow
-                            s.move_right(ctx.shift);
-                        }
-                        38 => {
-                            // Up arrow
-                            s.move_up(ctx.shift);
-                        }
-                        40 => {
-                            // Down arrow
-                            s.move_down(ctx.shift);
-                        }
-                        36 => {
-                            // Home
-                            s.move_to_line_start(ctx.shift);
-                        }
-                        35 => {
-                            // End
-                            s.move_to_line_end(ctx.shift);
-                        }
-                        9 => {
-                            // Tab - insert spaces
-                            s.insert("    ");
-                            changed = true;
-                        }
-                        _ => {
-                            cursor_changed = false;
-                        }
+                    71 if command => {
+                        s.find_next();
                     }
-
-                    // Reset cursor blink on keystroke
-                    if cursor_changed {
-                        if let Ok(mut cs) = s.cursor_state.lock() {
-                            cs.reset_blink();
-                        }
+                    // Ctrl/Cmd+Shift+L - a cursor on every search match
+                    76 if command && ctx.shift => {
+                        s.select_all_matches();
                     }
-
-                    if changed {
-                        if let Some(ref callback) = on_change_for_key {
-                            callback(&s.value());
-                        }
+                    _ => {
+                        cursor_changed = false;
                     }
+                }
 
-                    if cursor_changed {
-                        request_rebuild();
+                // Reset cursor blink on keystroke
+                if cursor_changed {
+                    if let Ok(mut cs) = s.cursor_state.lock() {
+                        cs.reset_blink();
                     }
-                })
-                .on_text_input(move |ctx| {
-                    let mut s = state_for_text.lock().unwrap();
-                    if !s.focused {
-                        return;
+                }
+
+                if s.buffer.revision() != revision {
+                    if let Some(ref callback) = on_change_for_key {
+                        callback(&s.value());
                     }
+                }
 
-                    if let Some(c) = ctx.key_char {
-                        s.insert(&c.to_string());
+                if cursor_changed {
+                    request_rebuild();
+                }
+            })
+            .on_text_input(move |ctx| {
+                let mut s = state_for_text.lock().unwrap();
+                if !s.focused {
+                    return;
+                }
 
-                        // Reset cursor blink
-                        if let Ok(mut cs) = s.cursor_state.lock() {
-                            cs.reset_blink();
-                        }
+                if let Some(c) = ctx.key_char {
+                    s.insert(&c.to_string());
 
-                        if let Some(ref callback) = on_change_for_text {
-                            callback(&s.value());
-                        }
+                    // Reset cursor blink
+                    if let Ok(mut cs) = s.cursor_state.lock() {
+                        cs.reset_blink();
+                    }
 
-                        request_rebuild();
+                    if let Some(ref callback) = on_change_for_text {
+                        callback(&s.value());
                     }
-                });
-        }
 
-        container
+                    request_rebuild();
+                }
+            })
     }
 
     // ========================================================================
@@ -985,9 +2122,24 @@ pub fn pre(content: impl Into<String>) -> Code {
     Code::new(content)
 }
 
+/// Create an editable code block for shared state
+///
+/// # Example
+/// ```ignore
+/// let state = code_state("[server]\nport = 8080");
+/// code_editor(&state)
+///     .line_numbers(true)
+///     .folding(FoldMode::Indentation)
+///     .on_change(|text| save_config(text))
+/// ```
+pub fn code_editor(state: &SharedCodeState) -> Code {
+    Code::with_state(state).edit(true)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::syntax::RustHighlighter;
 
     fn init_theme() {
         let _ = ThemeState::try_get().unwrap_or_else(|| {
@@ -996,6 +2148,10 @@ mod tests {
         });
     }
 
+    fn pos(line: usize, column: usize) -> TextPosition {
+        TextPosition::new(line, column)
+    }
+
     #[test]
     fn test_code_creation() {
         init_theme();
@@ -1022,7 +2178,7 @@ mod tests {
     #[test]
     fn test_code_state_insert() {
         let mut state = CodeState::new("hello");
-        state.cursor = TextPosition::new(0, 5);
+        state.set_cursor(pos(0, 5));
         state.insert(" world");
         assert_eq!(state.value(), "hello world");
     }
@@ -1030,18 +2186,179 @@ mod tests {
     #[test]
     fn test_code_state_newline() {
         let mut state = CodeState::new("hello world");
-        state.cursor = TextPosition::new(0, 5);
+        state.set_cursor(pos(0, 5));
         state.insert_newline();
-        assert_eq!(state.lines.len(), 2);
-        assert_eq!(state.lines[0], "hello");
-        assert_eq!(state.lines[1], " world");
+        assert_eq!(state.line_count(), 2);
+        assert_eq!(state.buffer.line(0), "hello");
+        assert_eq!(state.buffer.line(1), " world");
     }
 
     #[test]
     fn test_code_state_delete() {
         let mut state = CodeState::new("hello");
-        state.cursor = TextPosition::new(0, 5);
+        state.set_cursor(pos(0, 5));
         state.delete_backward();
         assert_eq!(state.value(), "hell");
     }
+
+    #[test]
+    fn test_newline_keeps_indentation() {
+        let mut state = CodeState::new("fn main() {\n    let x = 1;\n}");
+        state.set_cursor(pos(1, 14));
+        state.insert_newline();
+        state.insert("let y = 2;");
+        assert_eq!(state.line(2), "    let y = 2;");
+    }
+
+    #[test]
+    fn test_multiple_cursors() {
+        let mut state = CodeState::new("a = 1\nbb = 2\nccc = 3");
+        state.set_cursor(pos(0, 1));
+        state.add_caret(pos(1, 2));
+        state.add_caret(pos(2, 3));
+        state.insert("_x");
+        assert_eq!(state.value(), "a_x = 1\nbb_x = 2\nccc_x = 3");
+        assert_eq!(state.cursor(), pos(0, 3));
+
+        state.delete_backward();
+        state.delete_backward();
+        assert_eq!(state.value(), "a = 1\nbb = 2\nccc = 3");
+
+        // Cursors that meet merge
+        state.move_to_line_start(false);
+        state.move_up(false);
+        assert_eq!(state.carets().len(), 2);
+        state.collapse_carets();
+        assert_eq!(state.carets().len(), 1);
+    }
+
+    #[test]
+    fn test_column_selection_and_next_occurrence() {
+        let mut state = CodeState::new("let a = 1;\nlet b = 2;\nlet c = 3;");
+        state.add_column_selection(pos(0, 4), pos(2, 5));
+        assert_eq!(state.carets().len(), 3);
+        assert_eq!(state.carets()[0].head, pos(2, 5));
+        state.insert("x");
+        assert_eq!(state.value(), "let x = 1;\nlet x = 2;\nlet x = 3;");
+
+        // Ctrl+D: select the word, then add its next occurrences
+        state.set_cursor(pos(0, 0));
+        assert!(state.add_next_occurrence());
+        assert_eq!(state.selected_text(), "let");
+        assert!(state.add_next_occurrence());
+        assert!(state.add_next_occurrence());
+        assert!(!state.add_next_occurrence());
+        state.insert("var");
+        assert_eq!(state.value(), "var x = 1;\nvar x = 2;\nvar x = 3;");
+
+        state.set_cursor(pos(0, 2));
+        state.add_caret_vertical(false);
+        assert_eq!(state.carets()[1].head, pos(1, 2));
+    }
+
+    #[test]
+    fn test_indentation_folding() {
+        let mut state = CodeState::new("a:\n  b: 1\n  c:\n    d: 2\n\ne: 3");
+        state.set_fold_mode(Some(FoldMode::Indentation));
+        assert_eq!(
+            state.fold_ranges(),
+            vec![
+                FoldRange { start: 0, end: 3 },
+                FoldRange { start: 2, end: 3 }
+            ]
+        );
+
+        state.set_cursor(pos(3, 2));
+        assert!(state.fold(0));
+        assert!(state.is_line_hidden(1) && state.is_line_hidden(3));
+        assert!(!state.is_line_hidden(4));
+        assert_eq!(state.cursor(), pos(0, 2));
+        state.move_down(false);
+        assert_eq!(state.cursor(), pos(4, 0));
+
+        // Lines added above shift the fold
+        state.unfold_all();
+        assert!(state.fold(2));
+        state.set_cursor(pos(0, 0));
+        state.insert("top\n");
+        assert!(state.is_folded(3));
+        assert!(state.is_line_hidden(4) && !state.is_line_hidden(5));
+        state.toggle_fold(3);
+        assert!(!state.is_line_hidden(4));
+    }
+
+    #[test]
+    fn test_bracket_folding_and_matching() {
+        let source = "fn main() {\n    let s = \"{\";\n    call(\n        1,\n    );\n}\n";
+        let mut state = CodeState::new(source);
+        let highlighter: Arc<dyn SyntaxHighlighter> = Arc::new(RustHighlighter::new());
+        state.update_highlighting(Some(&highlighter));
+        state.set_fold_mode(Some(FoldMode::Brackets));
+        assert_eq!(
+            state.fold_ranges(),
+            vec![
+                FoldRange { start: 0, end: 4 },
+                FoldRange { start: 2, end: 3 }
+            ]
+        );
+
+        // The brace in the string literal is skipped
+        state.set_cursor(pos(0, 10));
+        assert_eq!(state.matching_bracket(), Some((pos(0, 10), pos(5, 0))));
+        state.set_cursor(pos(5, 1));
+        assert_eq!(state.matching_bracket(), Some((pos(5, 0), pos(0, 10))));
+        state.set_cursor(pos(1, 4));
+        assert_eq!(state.matching_bracket(), None);
+    }
+
+    #[test]
+    fn test_find_and_replace() {
+        let mut state = CodeState::new("width = 10\nheight = 20\nWidth = 30");
+        assert_eq!(state.set_search(Some(SearchQuery::new("width"))), Ok(2));
+        assert_eq!(
+            state
+                .set_search(Some(SearchQuery::new("width").case_sensitive(true)))
+                .unwrap(),
+            1
+        );
+        assert!(state
+            .set_search(Some(SearchQuery::new("(").regex(true)))
+            .is_err());
+
+        let query = SearchQuery::new(r"(\w+) = (\d+)").regex(true);
+        assert_eq!(state.set_search(Some(query)), Ok(3));
+        assert!(state.find_next());
+        assert_eq!(state.selected_text(), "width = 10");
+        assert!(state.replace_current("$2 = $1"));
+        assert_eq!(state.line(0), "10 = width");
+        assert_eq!(state.selected_text(), "height = 20");
+
+        assert_eq!(state.replace_all("${1}: $2"), 2);
+        assert_eq!(state.value(), "10 = width\nheight: 20\nWidth: 30");
+
+        state.set_search(Some(SearchQuery::new("th"))).unwrap();
+        assert!(state.select_all_matches());
+        assert_eq!(state.carets().len(), 2);
+        state.insert("TH");
+        assert_eq!(state.value(), "10 = widTH\nheight: 20\nWidTH: 30");
+    }
+
+    #[test]
+    fn test_edits_rehighlight_incrementally() {
+        let lines: Vec<String> = (0..50).map(|i| format!("let x{i} = {i};")).collect();
+        let mut state = CodeState::new(&lines.join("\n"));
+        let highlighter: Arc<dyn SyntaxHighlighter> = Arc::new(RustHighlighter::new());
+        assert_eq!(state.update_highlighting(Some(&highlighter)), 0..50);
+
+        state.set_cursor(pos(20, 0));
+        state.insert("pub ");
+        assert_eq!(state.update_highlighting(Some(&highlighter)), 20..21);
+        state.insert_newline();
+        assert_eq!(state.update_highlighting(Some(&highlighter)), 20..22);
+        assert_eq!(state.update_highlighting(Some(&highlighter)), 0..0);
+
+        // A different highlighter starts over
+        let other: Arc<dyn SyntaxHighlighter> = Arc::new(RustHighlighter::new());
+        assert_eq!(state.update_highlighting(Some(&other)), 0..51);
+    }
 }
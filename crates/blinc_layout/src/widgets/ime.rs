@@ -0,0 +1,273 @@
+//! IME composition support for text widgets
+//!
+//! Input methods for Chinese, Japanese, Korean and other scripts compose text
+//! in several steps before committing it. While composing, [`TextInput`] and
+//! [`TextArea`] show the uncommitted *preedit* text underlined at the caret,
+//! with its own composition cursor, and report the caret rectangle so the
+//! platform can place the IME candidate window next to it.
+//!
+//! Composition events arrive as `COMPOSITION_START`, `COMPOSITION_UPDATE`,
+//! `COMPOSITION_COMMIT` and `COMPOSITION_CANCEL` and are broadcast to all text
+//! widgets; only the focused one reacts.
+//!
+//! [`ImeSimulator`] drives the same state transitions in memory, for tests.
+//!
+//! [`TextInput`]: crate::widgets::TextInput
+//! [`TextArea`]: crate::widgets::TextArea
+
+use std::sync::Mutex;
+
+use blinc_core::events::event_types;
+
+/// In-progress IME composition
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Composition {
+    /// Uncommitted preedit text, shown underlined at the caret
+    pub text: String,
+    /// Caret or selection inside the preedit, in chars (`None` if the IME doesn't report one)
+    pub cursor: Option<(usize, usize)>,
+}
+
+impl Composition {
+    /// Caret offset inside the preedit, in chars
+    ///
+    /// Falls back to the end of the preedit when the IME doesn't report one.
+    pub fn caret(&self) -> usize {
+        let len = self.text.chars().count();
+        self.cursor.map(|(_, end)| end.min(len)).unwrap_or(len)
+    }
+
+    /// Preedit length in chars
+    pub fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Whether the preedit is empty
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+}
+
+/// Caret rectangle in logical window coordinates
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ImeCaretRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Callback that positions the platform IME
+///
+/// `Some(rect)` enables IME with the candidate window anchored at `rect`;
+/// `None` disables IME because no text widget is focused.
+#[allow(clippy::type_complexity)]
+static IME_CARET_CALLBACK: Mutex<Option<Box<dyn Fn(Option<ImeCaretRect>) + Send + Sync>>> =
+    Mutex::new(None);
+
+/// Set the callback that forwards caret rectangles to the platform IME
+///
+/// This should be called once during app initialization, like
+/// [`set_continuous_redraw_callback`](crate::widgets::set_continuous_redraw_callback).
+pub fn set_ime_caret_callback<F>(callback: F)
+where
+    F: Fn(Option<ImeCaretRect>) + Send + Sync + 'static,
+{
+    let mut guard = IME_CARET_CALLBACK.lock().unwrap();
+    *guard = Some(Box::new(callback));
+}
+
+/// Report the focused widget's caret rectangle (or `None` on blur)
+pub(crate) fn notify_ime_caret(rect: Option<ImeCaretRect>) {
+    if let Ok(guard) = IME_CARET_CALLBACK.lock() {
+        if let Some(ref callback) = *guard {
+            callback(rect);
+        }
+    }
+}
+
+/// Something that accepts IME composition events
+///
+/// Implemented by [`TextInputState`](crate::widgets::TextInputState) and
+/// [`TextAreaState`](crate::widgets::TextAreaState) for direct, in-memory
+/// editing, and by [`RenderTree`](crate::RenderTree), which broadcasts the
+/// events to the focused text widget.
+pub trait ImeTarget {
+    /// Composition started
+    fn ime_start(&mut self);
+    /// Preedit text or cursor changed
+    fn ime_update(&mut self, text: &str, cursor: Option<(usize, usize)>);
+    /// Composition committed final text
+    fn ime_commit(&mut self, text: &str);
+    /// Composition cancelled
+    fn ime_cancel(&mut self);
+}
+
+impl ImeTarget for crate::renderer::RenderTree {
+    fn ime_start(&mut self) {
+        self.broadcast_composition_event(event_types::COMPOSITION_START, None, None);
+    }
+
+    fn ime_update(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        self.broadcast_composition_event(event_types::COMPOSITION_UPDATE, Some(text), cursor);
+    }
+
+    fn ime_commit(&mut self, text: &str) {
+        self.broadcast_composition_event(event_types::COMPOSITION_COMMIT, Some(text), None);
+    }
+
+    fn ime_cancel(&mut self) {
+        self.broadcast_composition_event(event_types::COMPOSITION_CANCEL, None, None);
+    }
+}
+
+/// In-memory input method for tests
+///
+/// Mirrors how a real IME drives a text widget: the first preedit opens a
+/// composition, later preedits update it, and it ends with a commit or cancel.
+///
+/// ```ignore
+/// let mut ime = ImeSimulator::new();
+/// ime.preedit(&mut state, "にほ");
+/// ime.preedit(&mut state, "にほん");
+/// ime.commit(&mut state, "日本");
+/// assert_eq!(state.value, "日本");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ImeSimulator {
+    preedit: Option<String>,
+}
+
+impl ImeSimulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a composition is open
+    pub fn is_composing(&self) -> bool {
+        self.preedit.is_some()
+    }
+
+    /// Current preedit text
+    pub fn preedit_text(&self) -> Option<&str> {
+        self.preedit.as_deref()
+    }
+
+    /// Show `text` as preedit with the caret at its end
+    pub fn preedit(&mut self, target: &mut impl ImeTarget, text: &str) {
+        let caret = text.chars().count();
+        self.preedit_with_cursor(target, text, Some((caret, caret)));
+    }
+
+    /// Show `text` as preedit with an explicit caret or selection
+    pub fn preedit_with_cursor(
+        &mut self,
+        target: &mut impl ImeTarget,
+        text: &str,
+        cursor: Option<(usize, usize)>,
+    ) {
+        if self.preedit.is_none() {
+            target.ime_start();
+        }
+        target.ime_update(text, cursor);
+        self.preedit = Some(text.to_string());
+    }
+
+    /// Commit `text`, ending the composition
+    ///
+    /// Committing without an open composition inserts `text` directly, as
+    /// IMEs do for plain characters.
+    pub fn commit(&mut self, target: &mut impl ImeTarget, text: &str) {
+        target.ime_commit(text);
+        self.preedit = None;
+    }
+
+    /// Commit the current preedit text unchanged
+    pub fn commit_preedit(&mut self, target: &mut impl ImeTarget) {
+        if let Some(text) = self.preedit.take() {
+            target.ime_commit(&text);
+        }
+    }
+
+    /// Cancel the composition, discarding the preedit
+    pub fn cancel(&mut self, target: &mut impl ImeTarget) {
+        if self.preedit.take().is_some() {
+            target.ime_cancel();
+        }
+    }
+
+    /// Compose through each preedit step, then commit `result`
+    pub fn compose(&mut self, target: &mut impl ImeTarget, steps: &[&str], result: &str) {
+        for step in steps {
+            self.preedit(target, step);
+        }
+        self.commit(target, result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widgets::text_area::{TextAreaState, TextPosition};
+    use crate::widgets::text_input::TextInputData;
+
+    #[test]
+    fn test_text_input_composition() {
+        let mut data = TextInputData::with_value("ab");
+        data.cursor = 1;
+        let mut ime = ImeSimulator::new();
+
+        ime.preedit(&mut data, "に");
+        ime.preedit_with_cursor(&mut data, "にほ", Some((1, 1)));
+        // Preedit is not part of the value until committed
+        assert_eq!(data.value, "ab");
+        assert!(data.is_composing());
+        let (display, range) = data.composed_text();
+        assert_eq!(display, "aにほb");
+        assert_eq!(range, Some((1, 3)));
+        assert_eq!(data.composition.as_ref().unwrap().caret(), 1);
+
+        ime.commit(&mut data, "日本");
+        assert_eq!(data.value, "a日本b");
+        assert_eq!(data.cursor, 3);
+        assert!(!data.is_composing());
+    }
+
+    #[test]
+    fn test_composition_cancel_and_selection_replace() {
+        let mut data = TextInputData::with_value("hello");
+        data.selection_start = Some(0);
+        let mut ime = ImeSimulator::new();
+
+        // Starting a composition replaces the selection
+        ime.preedit(&mut data, "한");
+        assert_eq!(data.value, "");
+        ime.cancel(&mut data);
+        assert_eq!(data.value, "");
+        assert!(data.composition.is_none());
+
+        // Committing without a composition inserts directly
+        ime.commit(&mut data, "글");
+        assert_eq!(data.value, "글");
+    }
+
+    #[test]
+    fn test_text_area_composition() {
+        let mut state = TextAreaState::with_value("first\nsecond");
+        state.cursor = TextPosition::new(1, 3);
+        let mut ime = ImeSimulator::new();
+
+        ime.compose(&mut state, &["zh", "zhong"], "中");
+        assert_eq!(state.value(), "first\nsec中ond");
+        assert_eq!(state.cursor, TextPosition::new(1, 4));
+        assert!(!state.is_composing());
+
+        ime.preedit(&mut state, "wen");
+        let (line, range) = state.composed_line(1).unwrap();
+        assert_eq!(line, "sec中wenond");
+        assert_eq!(range, (4, 7));
+        assert!(state.composed_line(0).is_none());
+        ime.commit_preedit(&mut state);
+        assert_eq!(state.value(), "first\nsec中wenond");
+    }
+}
@@ -26,6 +26,7 @@
 //! ```
 
 use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 
 use blinc_core::Color;
 use blinc_theme::{ColorToken, ThemeState};
@@ -81,10 +82,16 @@ impl Default for LinkConfig {
     }
 }
 
+/// Click handler receiving the link URL
+type ClickHandler = Arc<dyn Fn(&str, &crate::event_handler::EventContext) + Send + Sync>;
+
 /// A hyperlink widget
 pub struct Link {
     inner: Div,
+    label: String,
     url: String,
+    config: LinkConfig,
+    handler: Option<ClickHandler>,
     css_element_id: Option<String>,
     css_classes: Vec<String>,
 }
@@ -95,75 +102,29 @@ impl Link {
     /// By default, clicking the link opens the URL in the system browser.
     /// Use `.on_click()` to override this behavior.
     pub fn new(label: impl Into<String>, url: impl Into<String>) -> Self {
-        let config = LinkConfig::default();
-        let label = label.into();
-        let url = url.into();
-
-        // Build the inner div with text - include all final styling here
-        // so that build() and render_props()/children_builders() are consistent
-        let mut text_element = text(&label)
-            .size(config.font_size)
-            .color(config.text_color)
-            .no_cursor(); // Text inside link shouldn't override pointer cursor
-
-        // Apply underline by default (not hover-only)
-        if config.underline && !config.underline_on_hover_only {
-            text_element = text_element.underline();
-        }
-
-        // Default click handler opens URL in system browser
-        let url_for_click = url.clone();
-        let inner = div()
-            .child(text_element)
-            .cursor_pointer()
-            .on_click(move |_ctx| {
-                open_url(&url_for_click);
-            });
-
-        Self {
-            inner,
-            url,
+        let mut link = Self {
+            inner: div(),
+            label: label.into(),
+            url: url.into(),
+            config: LinkConfig::default(),
+            handler: None,
             css_element_id: None,
             css_classes: Vec::new(),
-        }
+        };
+        link.rebuild();
+        link
     }
 
     /// Set a custom click handler (receives URL and event context)
     ///
     /// This replaces the default behavior of opening the URL in the browser.
-    pub fn on_click<F>(self, handler: F) -> Self
+    pub fn on_click<F>(mut self, handler: F) -> Self
     where
         F: Fn(&str, &crate::event_handler::EventContext) + Send + Sync + 'static,
     {
-        // Rebuild without the default handler and add the custom one
-        let label = self.extract_label();
-        let url = self.url;
-        let config = LinkConfig::default();
-
-        let mut text_element = text(&label)
-            .size(config.font_size)
-            .color(config.text_color)
-            .no_cursor();
-
-        if config.underline && !config.underline_on_hover_only {
-            text_element = text_element.underline();
-        }
-
-        // Use custom click handler instead of default
-        let url_for_click = url.clone();
-        let inner = div()
-            .child(text_element)
-            .cursor_pointer()
-            .on_click(move |ctx| {
-                handler(&url_for_click, ctx);
-            });
-
-        Self {
-            inner,
-            url,
-            css_element_id: None,
-            css_classes: Vec::new(),
-        }
+        self.handler = Some(Arc::new(handler));
+        self.rebuild();
+        self
     }
 
     /// Set the text color
@@ -219,50 +180,35 @@ impl Link {
     }
 
     /// Helper to rebuild the link with a modified config
-    fn rebuild_with_config(self, modify: impl FnOnce(&mut LinkConfig)) -> Self {
-        let label = self.extract_label();
-        let url = self.url;
-        let css_element_id = self.css_element_id;
-        let css_classes = self.css_classes;
-
-        let mut config = LinkConfig::default();
-        modify(&mut config);
+    fn rebuild_with_config(mut self, modify: impl FnOnce(&mut LinkConfig)) -> Self {
+        modify(&mut self.config);
+        self.rebuild();
+        self
+    }
 
-        let mut text_element = text(&label)
-            .size(config.font_size)
-            .color(config.text_color)
-            .no_cursor();
+    /// Rebuild the inner structure from the label, config and handler
+    fn rebuild(&mut self) {
+        let mut text_element = text(&self.label)
+            .size(self.config.font_size)
+            .color(self.config.text_color)
+            .no_cursor(); // Text inside link shouldn't override pointer cursor
 
-        if config.underline && !config.underline_on_hover_only {
+        // Apply underline by default (not hover-only)
+        if self.config.underline && !self.config.underline_on_hover_only {
             text_element = text_element.underline();
         }
 
-        let url_for_click = url.clone();
-        let inner = div()
-            .child(text_element)
-            .cursor_pointer()
-            .on_click(move |_ctx| {
-                open_url(&url_for_click);
-            });
-
-        Self {
-            inner,
-            url,
-            css_element_id,
-            css_classes,
-        }
-    }
-
-    /// Extract label text from the inner structure
-    fn extract_label(&self) -> String {
-        // The first child should be the text element
-        // We can get its content via text_render_info
-        if let Some(child) = self.inner.children_builders().first() {
-            if let Some(info) = child.text_render_info() {
-                return info.content;
-            }
-        }
-        String::new()
+        // Default click handler opens URL in system browser
+        let url = self.url.clone();
+        let handler = self.handler.clone();
+        self.inner =
+            div()
+                .child(text_element)
+                .cursor_pointer()
+                .on_click(move |ctx| match &handler {
+                    Some(handler) => handler(&url, ctx),
+                    None => open_url(&url),
+                });
     }
 }
 
@@ -9,6 +9,7 @@
 //! - [`checkbox()`] - Toggle checkbox with label support
 //! - [`text_input()`] - Single-line text input with validation
 //! - [`text_area()`] - Multi-line text area
+//! - [`rich_editor()`] - Rich text editor with Markdown import/export
 //! - [`scroll()`] - Scrollable container with bounce physics
 //! - [`code()`] - Code block with syntax highlighting and line numbers
 //!
@@ -37,13 +38,16 @@ pub mod checkbox;
 pub mod code;
 pub mod cursor;
 pub mod hr;
+pub mod ime;
 pub mod link;
 pub mod list;
 pub mod overlay;
 pub mod radio;
+pub mod rich_editor;
 pub mod scroll;
 pub mod table;
 pub mod text_area;
+pub mod text_edit;
 pub mod text_input;
 
 // Re-export button widget
@@ -88,6 +92,22 @@ pub use text_area::{
     TextAreaConfig, TextAreaState, TextPosition,
 };
 
+// Re-export shared editing helpers
+pub use text_edit::{
+    next_word_boundary, prev_word_boundary, word_range_at, wrap_ranges, ClickCounter, EditHistory,
+    EditKind,
+};
+
+// Re-export rich text editor
+pub use rich_editor::{
+    rich_editor, rich_editor_state, rich_editor_state_from_markdown, Block, BlockType, DocPosition,
+    Mark, Marks, RichDocument, RichEditor, RichEditorConfig, RichEditorState,
+    SharedRichEditorState, TextRun,
+};
+
+// Re-export IME composition support
+pub use ime::{set_ime_caret_callback, Composition, ImeCaretRect, ImeSimulator, ImeTarget};
+
 // Re-export scroll widget
 pub use scroll::{
     scroll, scroll_no_bounce, Scroll, ScrollConfig, ScrollDirection, ScrollPhysics,
@@ -102,7 +122,10 @@ pub use cursor::{
 };
 
 // Re-export code widget
-pub use code::{code, pre, Code, CodeConfig};
+pub use code::{
+    code, code_editor, code_state, pre, Caret, Code, CodeConfig, CodeState, Diagnostic,
+    DiagnosticSeverity, FoldMode, FoldRange, SearchQuery, SharedCodeState,
+};
 
 // Re-export overlay widget
 pub use overlay::{
@@ -324,6 +324,11 @@ fn build_radio_button(
         radio = radio.id(css_id);
     }
 
+    // Report selection for `:checked` selector matching
+    let selected_for_probe = config.selected.clone();
+    let value_for_probe = option.value.clone();
+    radio = radio.checked_when(move || selected_for_probe.get() == value_for_probe);
+
     // Click handler
     radio = radio.on_click(move |_| {
         if option_disabled {
@@ -0,0 +1,2246 @@
+//! Editable rich text
+//!
+//! [`rich_editor()`] is a WYSIWYG editor for a [`RichDocument`]: a list of
+//! blocks (paragraphs, headings and list items), each holding runs of text
+//! with inline marks (bold, italic, underline, code and links).
+//!
+//! Editing goes through [`RichEditorState`], whose commands are meant to be
+//! wired straight to toolbar buttons:
+//!
+//! - [`toggle_mark`](RichEditorState::toggle_mark) and
+//!   [`set_link`](RichEditorState::set_link) for inline formatting
+//! - [`set_block_type`](RichEditorState::set_block_type) for headings and lists
+//! - [`mark_active`](RichEditorState::mark_active) and
+//!   [`block_type`](RichEditorState::block_type) to show toolbar state
+//!
+//! The same commands are bound to the usual shortcuts (Ctrl/Cmd with B, I,
+//! U and E for marks, Ctrl/Cmd+Alt+0-6 for paragraphs and headings,
+//! Ctrl/Cmd+Shift+7/8 for numbered and bulleted lists, Z and Y for undo and
+//! redo).
+//!
+//! Documents convert to and from Markdown, which is also the clipboard
+//! format for rich content: bold is `**`, italic `*`, inline code uses
+//! backticks and underline, which Markdown lacks, is written as `<u>`.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use blinc_layout::prelude::*;
+//!
+//! let note = ctx.use_state_for("note", || rich_editor_state_from_markdown("# Notes"));
+//!
+//! div()
+//!     .child(button("B").on_click({
+//!         let note = note.clone();
+//!         move |_| note.lock().unwrap().toggle_mark(Mark::Bold)
+//!     }))
+//!     .child(rich_editor(&note).w(480.0).on_change(|md| save(md)))
+//! ```
+
+use std::ops::{Deref, DerefMut, Range};
+use std::sync::{Arc, Mutex};
+
+use blinc_core::{Brush, Color, CornerRadius, Rect};
+use blinc_theme::{ColorToken, ThemeState};
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+use crate::canvas::canvas;
+use crate::div::{div, Div, ElementBuilder, ElementTypeId};
+use crate::element::RenderProps;
+use crate::rich_text::RichText;
+use crate::styled_text::{StyledLine, StyledText, TextSpan};
+use crate::text::text;
+use crate::text_selection::{set_selection, SelectionSource};
+use crate::tree::{LayoutNodeId, LayoutTree};
+use crate::widgets::cursor::{cursor_state, CursorAnimation, SharedCursorState};
+use crate::widgets::text_edit::{
+    next_word_boundary, prev_word_boundary, word_range_at, wrap_ranges, ClickCounter, EditHistory,
+    EditKind,
+};
+use crate::widgets::text_input::{
+    decrement_focus_count, elapsed_ms, increment_focus_count, request_continuous_redraw_pub,
+    request_rebuild,
+};
+
+// ============================================================================
+// Document Model
+// ============================================================================
+
+/// Inline formatting toggled by [`RichEditorState::toggle_mark`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Mark {
+    Bold,
+    Italic,
+    Underline,
+    /// Inline code
+    Code,
+}
+
+/// Formatting of a run of text
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Marks {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub code: bool,
+    /// Link target
+    pub link: Option<String>,
+}
+
+impl Marks {
+    /// Whether `mark` is set
+    pub fn has(&self, mark: Mark) -> bool {
+        match mark {
+            Mark::Bold => self.bold,
+            Mark::Italic => self.italic,
+            Mark::Underline => self.underline,
+            Mark::Code => self.code,
+        }
+    }
+
+    /// Set or clear `mark`
+    pub fn set(&mut self, mark: Mark, on: bool) {
+        match mark {
+            Mark::Bold => self.bold = on,
+            Mark::Italic => self.italic = on,
+            Mark::Underline => self.underline = on,
+            Mark::Code => self.code = on,
+        }
+    }
+}
+
+/// Text with uniform formatting
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextRun {
+    pub text: String,
+    pub marks: Marks,
+}
+
+impl TextRun {
+    pub fn new(text: impl Into<String>, marks: Marks) -> Self {
+        Self {
+            text: text.into(),
+            marks,
+        }
+    }
+
+    /// Unformatted text
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::new(text, Marks::default())
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+}
+
+/// Kind of a block, set with [`RichEditorState::set_block_type`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlockType {
+    #[default]
+    Paragraph,
+    /// Heading level 1-6
+    Heading(u8),
+    BulletItem,
+    NumberedItem,
+}
+
+impl BlockType {
+    /// Whether this is a list item
+    pub fn is_list(self) -> bool {
+        matches!(self, Self::BulletItem | Self::NumberedItem)
+    }
+}
+
+/// A paragraph, heading or list item
+///
+/// Runs are kept normalized: no empty runs and no two neighbours with the
+/// same marks.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Block {
+    pub kind: BlockType,
+    pub runs: Vec<TextRun>,
+}
+
+impl Block {
+    /// Empty block
+    pub fn new(kind: BlockType) -> Self {
+        Self {
+            kind,
+            runs: Vec::new(),
+        }
+    }
+
+    /// Block holding unformatted `text`
+    pub fn with_text(kind: BlockType, text: impl Into<String>) -> Self {
+        let mut block = Self {
+            kind,
+            runs: vec![TextRun::plain(text)],
+        };
+        block.normalize();
+        block
+    }
+
+    /// Text without formatting
+    pub fn text(&self) -> String {
+        self.runs.iter().map(|run| run.text.as_str()).collect()
+    }
+
+    /// Length in chars
+    pub fn len(&self) -> usize {
+        self.runs.iter().map(TextRun::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Marks of the char at `offset`, if any
+    pub fn marks_at(&self, offset: usize) -> Option<&Marks> {
+        let mut start = 0;
+        for run in &self.runs {
+            let end = start + run.len();
+            if offset < end {
+                return Some(&run.marks);
+            }
+            start = end;
+        }
+        None
+    }
+
+    /// Split the run containing `offset` so a run starts there; returns its index
+    fn split_at(&mut self, offset: usize) -> usize {
+        let mut start = 0;
+        for i in 0..self.runs.len() {
+            if offset == start {
+                return i;
+            }
+            let len = self.runs[i].len();
+            if offset < start + len {
+                let byte = char_to_byte(&self.runs[i].text, offset - start);
+                let tail = self.runs[i].text.split_off(byte);
+                let marks = self.runs[i].marks.clone();
+                self.runs.insert(i + 1, TextRun::new(tail, marks));
+                return i + 1;
+            }
+            start += len;
+        }
+        self.runs.len()
+    }
+
+    /// Runs covering the chars in `range`
+    fn slice(&self, range: Range<usize>) -> Vec<TextRun> {
+        let mut copy = self.clone();
+        let start = copy.split_at(range.start);
+        let end = copy.split_at(range.end.max(range.start));
+        copy.runs.drain(start..end).collect()
+    }
+
+    /// Remove the chars in `range`
+    fn remove(&mut self, range: Range<usize>) {
+        let start = self.split_at(range.start);
+        let end = self.split_at(range.end.max(range.start));
+        self.runs.drain(start..end);
+        self.normalize();
+    }
+
+    /// Insert `runs` at char `offset`
+    fn insert_runs(&mut self, offset: usize, runs: Vec<TextRun>) {
+        let at = self.split_at(offset);
+        self.runs.splice(at..at, runs);
+        self.normalize();
+    }
+
+    /// Remove and return everything from char `offset` on
+    fn split_off(&mut self, offset: usize) -> Vec<TextRun> {
+        let at = self.split_at(offset);
+        let tail = self.runs.split_off(at);
+        self.normalize();
+        tail
+    }
+
+    /// Update the marks of the chars in `range`
+    fn update_marks(&mut self, range: Range<usize>, f: impl Fn(&mut Marks)) {
+        let start = self.split_at(range.start);
+        let end = self.split_at(range.end.max(range.start));
+        for run in &mut self.runs[start..end] {
+            f(&mut run.marks);
+        }
+        self.normalize();
+    }
+
+    fn normalize(&mut self) {
+        let mut runs: Vec<TextRun> = Vec::with_capacity(self.runs.len());
+        for run in self.runs.drain(..) {
+            if run.text.is_empty() {
+                continue;
+            }
+            match runs.last_mut() {
+                Some(last) if last.marks == run.marks => last.text.push_str(&run.text),
+                _ => runs.push(run),
+            }
+        }
+        self.runs = runs;
+    }
+}
+
+/// Position in a [`RichDocument`]: a block and a char offset within it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DocPosition {
+    pub block: usize,
+    pub offset: usize,
+}
+
+impl DocPosition {
+    pub fn new(block: usize, offset: usize) -> Self {
+        Self { block, offset }
+    }
+}
+
+/// A rich text document; always holds at least one block
+#[derive(Clone, Debug, PartialEq)]
+pub struct RichDocument {
+    pub blocks: Vec<Block>,
+}
+
+impl Default for RichDocument {
+    fn default() -> Self {
+        Self {
+            blocks: vec![Block::default()],
+        }
+    }
+}
+
+impl RichDocument {
+    /// Document with one empty paragraph
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One paragraph per line of `text`
+    pub fn from_plain_text(text: &str) -> Self {
+        Self::from_blocks(
+            text.split('\n')
+                .map(|line| Block::with_text(BlockType::Paragraph, line.trim_end_matches('\r')))
+                .collect(),
+        )
+    }
+
+    /// Parse Markdown
+    ///
+    /// Paragraphs, ATX and setext headings, bullet and numbered lists,
+    /// emphasis, strong emphasis, inline code, links and `<u>` underline are
+    /// kept. Code blocks become paragraphs of inline code; other constructs
+    /// (quotes, tables, images) keep their text only.
+    pub fn from_markdown(markdown: &str) -> Self {
+        let mut import = MarkdownImport::default();
+        for event in Parser::new_ext(markdown, Options::empty()) {
+            import.event(event);
+        }
+        import.finish_block();
+        Self::from_blocks(import.blocks)
+    }
+
+    fn from_blocks(blocks: Vec<Block>) -> Self {
+        if blocks.is_empty() {
+            Self::default()
+        } else {
+            Self { blocks }
+        }
+    }
+
+    /// Write as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let mut number = 0;
+        for (i, block) in self.blocks.iter().enumerate() {
+            let prev = i.checked_sub(1).map(|p| self.blocks[p].kind);
+            if prev.is_some() {
+                // Items of one list stay together; everything else is separated
+                let same_list = block.kind.is_list() && prev == Some(block.kind);
+                out.push_str(if same_list { "\n" } else { "\n\n" });
+            }
+            number = match block.kind {
+                BlockType::NumberedItem if prev == Some(BlockType::NumberedItem) => number + 1,
+                BlockType::NumberedItem => 1,
+                _ => 0,
+            };
+
+            let inline = inline_markdown(&block.runs);
+            match block.kind {
+                BlockType::Paragraph => out.push_str(&escape_block_start(&inline)),
+                BlockType::Heading(level) => {
+                    out.push_str(&"#".repeat(level.clamp(1, 6) as usize));
+                    out.push(' ');
+                    out.push_str(&inline);
+                }
+                BlockType::BulletItem => {
+                    out.push_str("- ");
+                    out.push_str(&inline);
+                }
+                BlockType::NumberedItem => {
+                    out.push_str(&format!("{number}. "));
+                    out.push_str(&inline);
+                }
+            }
+        }
+        out
+    }
+
+    /// Text without formatting, one line per block
+    pub fn to_plain_text(&self) -> String {
+        self.blocks
+            .iter()
+            .map(Block::text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Whether the document holds no text
+    pub fn is_empty(&self) -> bool {
+        self.blocks.iter().all(Block::is_empty)
+    }
+
+    /// Clamp a position into the document
+    pub fn clamp(&self, pos: DocPosition) -> DocPosition {
+        let block = pos.block.min(self.blocks.len() - 1);
+        DocPosition::new(block, pos.offset.min(self.blocks[block].len()))
+    }
+
+    /// End of the document
+    pub fn end(&self) -> DocPosition {
+        let last = self.blocks.len() - 1;
+        DocPosition::new(last, self.blocks[last].len())
+    }
+
+    /// Copy of the content between two positions
+    pub fn slice(&self, from: DocPosition, to: DocPosition) -> RichDocument {
+        let (from, to) = (self.clamp(from.min(to)), self.clamp(from.max(to)));
+        let blocks = (from.block..=to.block)
+            .map(|i| {
+                let block = &self.blocks[i];
+                let start = if i == from.block { from.offset } else { 0 };
+                let end = if i == to.block {
+                    to.offset
+                } else {
+                    block.len()
+                };
+                Block {
+                    kind: block.kind,
+                    runs: block.slice(start..end),
+                }
+            })
+            .collect();
+        Self { blocks }
+    }
+
+    /// Remove the content between two positions, joining the blocks at either end
+    fn remove(&mut self, from: DocPosition, to: DocPosition) {
+        let (from, to) = (self.clamp(from.min(to)), self.clamp(from.max(to)));
+        if from.block == to.block {
+            self.blocks[from.block].remove(from.offset..to.offset);
+            return;
+        }
+        let tail = self.blocks[to.block].split_off(to.offset);
+        self.blocks.drain(from.block + 1..=to.block);
+        let first = &mut self.blocks[from.block];
+        first.split_off(from.offset);
+        first.runs.extend(tail);
+        first.normalize();
+    }
+
+    /// Insert `fragment` at `at`, returning the position after it
+    ///
+    /// The first fragment block merges into the block at `at`, taking over
+    /// its type if that block is empty; the rest are inserted after it.
+    fn insert(&mut self, at: DocPosition, fragment: RichDocument) -> DocPosition {
+        let at = self.clamp(at);
+        let mut blocks = fragment.blocks.into_iter();
+        let Some(first) = blocks.next() else {
+            return at;
+        };
+        let rest: Vec<Block> = blocks.collect();
+
+        let target = &mut self.blocks[at.block];
+        if target.is_empty() {
+            target.kind = first.kind;
+        }
+        if rest.is_empty() {
+            let len: usize = first.runs.iter().map(TextRun::len).sum();
+            target.insert_runs(at.offset, first.runs);
+            return DocPosition::new(at.block, at.offset + len);
+        }
+
+        let tail = target.split_off(at.offset);
+        target.runs.extend(first.runs);
+        target.normalize();
+
+        let count = rest.len();
+        self.blocks.splice(at.block + 1..at.block + 1, rest);
+        let last = &mut self.blocks[at.block + count];
+        let end = DocPosition::new(at.block + count, last.len());
+        last.runs.extend(tail);
+        last.normalize();
+        end
+    }
+}
+
+/// Builds blocks from pulldown-cmark events
+#[derive(Default)]
+struct MarkdownImport {
+    blocks: Vec<Block>,
+    current: Option<Block>,
+    marks: Marks,
+    /// Whether each open list is numbered
+    lists: Vec<bool>,
+    in_code_block: bool,
+}
+
+impl MarkdownImport {
+    fn event(&mut self, event: Event<'_>) {
+        match event {
+            // Tight and loose list items both put their text in the item block
+            Event::Start(Tag::Paragraph) if !self.current.as_ref().is_some_and(Block::is_empty) => {
+                self.start_block(BlockType::Paragraph);
+            }
+            Event::Start(Tag::Heading { level, .. }) => {
+                self.start_block(BlockType::Heading(level as u8));
+            }
+            Event::Start(Tag::List(start)) => {
+                self.finish_block();
+                self.lists.push(start.is_some());
+            }
+            Event::End(TagEnd::List(_)) => {
+                self.finish_block();
+                self.lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                let kind = if self.lists.last() == Some(&true) {
+                    BlockType::NumberedItem
+                } else {
+                    BlockType::BulletItem
+                };
+                self.start_block(kind);
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                self.finish_block();
+                self.in_code_block = true;
+                self.marks.code = true;
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                self.in_code_block = false;
+                self.marks.code = false;
+            }
+            Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item) => {
+                self.finish_block();
+            }
+            Event::Start(Tag::Emphasis) => self.marks.italic = true,
+            Event::End(TagEnd::Emphasis) => self.marks.italic = false,
+            Event::Start(Tag::Strong) => self.marks.bold = true,
+            Event::End(TagEnd::Strong) => self.marks.bold = false,
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                self.marks.link = Some(dest_url.to_string());
+            }
+            Event::End(TagEnd::Link) => self.marks.link = None,
+            Event::Text(text) if self.in_code_block => {
+                for line in text.trim_end_matches('\n').split('\n') {
+                    self.start_block(BlockType::Paragraph);
+                    self.push_text(line, self.marks.clone());
+                    self.finish_block();
+                }
+            }
+            Event::Text(text) => self.push_text(&text, self.marks.clone()),
+            Event::Code(code) => {
+                let marks = Marks {
+                    code: true,
+                    ..self.marks.clone()
+                };
+                self.push_text(&code, marks);
+            }
+            Event::SoftBreak => self.push_text(" ", self.marks.clone()),
+            Event::HardBreak => {
+                let kind = self
+                    .current
+                    .as_ref()
+                    .map_or(BlockType::Paragraph, |b| b.kind);
+                self.start_block(if kind.is_list() {
+                    BlockType::Paragraph
+                } else {
+                    kind
+                });
+            }
+            Event::InlineHtml(html) => match html.trim().to_ascii_lowercase().as_str() {
+                "<u>" => self.marks.underline = true,
+                "</u>" => self.marks.underline = false,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    fn start_block(&mut self, kind: BlockType) {
+        self.finish_block();
+        self.current = Some(Block::new(kind));
+    }
+
+    fn finish_block(&mut self) {
+        if let Some(block) = self.current.take() {
+            self.blocks.push(block);
+        }
+    }
+
+    fn push_text(&mut self, text: &str, marks: Marks) {
+        let block = self.current.get_or_insert_with(Block::default);
+        block.runs.push(TextRun::new(text, marks));
+        block.normalize();
+    }
+}
+
+/// Delimiters that stay open across runs when writing Markdown
+#[derive(Clone, Debug, PartialEq)]
+enum Delimiter {
+    Link(String),
+    Bold,
+    Italic,
+    Underline,
+}
+
+impl Delimiter {
+    fn open(&self) -> &'static str {
+        match self {
+            Self::Link(_) => "[",
+            Self::Bold => "**",
+            Self::Italic => "*",
+            Self::Underline => "<u>",
+        }
+    }
+
+    fn close(&self) -> String {
+        match self {
+            Self::Link(url) => format!("]({url})"),
+            Self::Bold => "**".to_string(),
+            Self::Italic => "*".to_string(),
+            Self::Underline => "</u>".to_string(),
+        }
+    }
+
+    /// Delimiters for `marks`, outermost first
+    fn for_marks(marks: &Marks) -> Vec<Self> {
+        let mut delimiters = Vec::new();
+        if let Some(url) = &marks.link {
+            delimiters.push(Self::Link(url.clone()));
+        }
+        if marks.bold {
+            delimiters.push(Self::Bold);
+        }
+        if marks.italic {
+            delimiters.push(Self::Italic);
+        }
+        if marks.underline {
+            delimiters.push(Self::Underline);
+        }
+        delimiters
+    }
+}
+
+/// Write runs as inline Markdown
+///
+/// Delimiters shared by neighbouring runs stay open, so `**a *b***` rather
+/// than `**a *****b***`. Whitespace next to a closing or opening delimiter
+/// is moved outside it, which CommonMark requires.
+fn inline_markdown(runs: &[TextRun]) -> String {
+    let mut out = String::new();
+    let mut open: Vec<Delimiter> = Vec::new();
+
+    let split = runs.iter().enumerate().flat_map(|(i, run)| {
+        let prev = i.checked_sub(1).and_then(|p| runs.get(p));
+        split_edge_whitespace(run, prev, runs.get(i + 1))
+    });
+    for run in split {
+        let wanted = Delimiter::for_marks(&run.marks);
+        let keep = open.iter().zip(&wanted).take_while(|(a, b)| a == b).count();
+        while open.len() > keep {
+            out.push_str(&open.pop().unwrap().close());
+        }
+        for delimiter in &wanted[keep..] {
+            out.push_str(delimiter.open());
+            open.push(delimiter.clone());
+        }
+
+        if run.marks.code {
+            out.push_str(&code_span(&run.text));
+        } else {
+            out.push_str(&escape_inline(&run.text));
+        }
+    }
+    while let Some(delimiter) = open.pop() {
+        out.push_str(&delimiter.close());
+    }
+    out
+}
+
+/// Split leading and trailing whitespace off an emphasized run
+///
+/// The whitespace keeps the emphasis it shares with the neighbouring run on
+/// that side, since those delimiters stay open across it.
+fn split_edge_whitespace(
+    run: &TextRun,
+    prev: Option<&TextRun>,
+    next: Option<&TextRun>,
+) -> Vec<TextRun> {
+    let marks = &run.marks;
+    if marks.code || !(marks.bold || marks.italic || marks.underline) {
+        return vec![run.clone()];
+    }
+    let core = run.text.trim();
+    if core.is_empty() || core.len() == run.text.len() {
+        return vec![run.clone()];
+    }
+    let shared_with = |other: Option<&TextRun>| {
+        let other = other.map(|run| run.marks.clone()).unwrap_or_default();
+        Marks {
+            bold: marks.bold && other.bold && !other.code,
+            italic: marks.italic && other.italic && !other.code,
+            underline: marks.underline && other.underline && !other.code,
+            code: false,
+            link: marks.link.clone(),
+        }
+    };
+    let start = run.text.len() - run.text.trim_start().len();
+    let end = start + core.len();
+    [
+        TextRun::new(&run.text[..start], shared_with(prev)),
+        TextRun::new(core, marks.clone()),
+        TextRun::new(&run.text[end..], shared_with(next)),
+    ]
+    .into_iter()
+    .filter(|run| !run.text.is_empty())
+    .collect()
+}
+
+/// Inline code span with a fence longer than any backtick run inside
+fn code_span(code: &str) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for c in code.chars() {
+        current = if c == '`' { current + 1 } else { 0 };
+        longest = longest.max(current);
+    }
+    let fence = "`".repeat(longest + 1);
+    // Readers strip one space from each side when both are present
+    let spaced = code.starts_with(' ') && code.ends_with(' ') && code.trim() != "";
+    let pad = if code.starts_with('`') || code.ends_with('`') || spaced {
+        " "
+    } else {
+        ""
+    };
+    format!("{fence}{pad}{code}{pad}{fence}")
+}
+
+/// Escape characters with inline meaning
+fn escape_inline(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Escape a paragraph start that would read as a heading, quote or list
+fn escape_block_start(inline: &str) -> String {
+    let digits = inline.chars().take_while(char::is_ascii_digit).count();
+    let after_digits = inline[digits..].chars().next();
+    if inline.starts_with(['#', '-', '+']) {
+        format!("\\{inline}")
+    } else if digits > 0 && matches!(after_digits, Some('.' | ')')) {
+        format!("{}\\{}", &inline[..digits], &inline[digits..])
+    } else {
+        inline.to_string()
+    }
+}
+
+fn char_to_byte(text: &str, char_pos: usize) -> usize {
+    text.char_indices()
+        .nth(char_pos)
+        .map_or(text.len(), |(i, _)| i)
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Rich editor configuration
+#[derive(Clone)]
+pub struct RichEditorConfig {
+    /// Width in pixels
+    pub width: f32,
+    /// Minimum height in pixels
+    pub min_height: f32,
+    /// Body font size in pixels
+    pub font_size: f32,
+    /// Line height multiplier
+    pub line_height: f32,
+    /// Space between blocks in pixels
+    pub block_spacing: f32,
+    /// Indent of list items (room for the marker) in pixels
+    pub list_indent: f32,
+    /// Padding inside the editor
+    pub padding: f32,
+    /// Corner radius
+    pub corner_radius: f32,
+    /// Border width
+    pub border_width: f32,
+    /// Placeholder shown while empty
+    pub placeholder: String,
+    pub bg_color: Color,
+    pub border_color: Color,
+    pub focused_border_color: Color,
+    pub text_color: Color,
+    pub placeholder_color: Color,
+    pub link_color: Color,
+    pub code_color: Color,
+    pub cursor_color: Color,
+    pub selection_color: Color,
+}
+
+impl Default for RichEditorConfig {
+    fn default() -> Self {
+        let theme = ThemeState::get();
+        Self {
+            width: 400.0,
+            min_height: 120.0,
+            font_size: 14.0,
+            line_height: 1.5,
+            block_spacing: 6.0,
+            list_indent: 24.0,
+            padding: 12.0,
+            corner_radius: 8.0,
+            border_width: 1.5,
+            placeholder: String::new(),
+            bg_color: theme.color(ColorToken::InputBg),
+            border_color: theme.color(ColorToken::BorderSecondary),
+            focused_border_color: theme.color(ColorToken::BorderFocus),
+            text_color: theme.color(ColorToken::TextPrimary),
+            placeholder_color: theme.color(ColorToken::TextTertiary),
+            link_color: theme.color(ColorToken::TextLink),
+            code_color: theme.color(ColorToken::Accent),
+            cursor_color: theme.color(ColorToken::Accent),
+            selection_color: theme.color(ColorToken::Selection),
+        }
+    }
+}
+
+impl RichEditorConfig {
+    /// Font size of blocks of type `kind`
+    pub fn font_size_for(&self, kind: BlockType) -> f32 {
+        let scale = match kind {
+            BlockType::Heading(1) => 2.0,
+            BlockType::Heading(2) => 1.5,
+            BlockType::Heading(3) => 1.25,
+            BlockType::Heading(_) => 1.1,
+            _ => 1.0,
+        };
+        self.font_size * scale
+    }
+
+    /// Width available to the text of blocks of type `kind`
+    fn text_width_for(&self, kind: BlockType) -> f32 {
+        let indent = if kind.is_list() {
+            self.list_indent
+        } else {
+            0.0
+        };
+        self.width - self.padding * 2.0 - self.border_width * 2.0 - indent
+    }
+}
+
+// ============================================================================
+// Editor State
+// ============================================================================
+
+/// Undo snapshot
+#[derive(Clone, Debug)]
+pub(crate) struct RichEditorSnapshot {
+    doc: RichDocument,
+    cursor: DocPosition,
+    selection_start: Option<DocPosition>,
+}
+
+/// One rendered row of a (possibly wrapped) block
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct RichRow {
+    block: usize,
+    /// Char range of the block shown on this row
+    range: Range<usize>,
+    font_size: f32,
+}
+
+/// Editing state of a rich editor
+#[derive(Clone, Debug)]
+pub struct RichEditorState {
+    pub(crate) doc: RichDocument,
+    /// Caret position
+    pub cursor: DocPosition,
+    /// Other end of the selection, if any
+    pub selection_start: Option<DocPosition>,
+    /// Marks for the next typed text, set by toggling a mark without a selection
+    pending_marks: Option<Marks>,
+    pub(crate) history: EditHistory<RichEditorSnapshot>,
+    /// Rows from the last layout, for vertical movement and hit testing
+    pub(crate) rows: Vec<RichRow>,
+    pub(crate) focused: bool,
+    click_counter: ClickCounter,
+    cursor_state: SharedCursorState,
+}
+
+impl Default for RichEditorState {
+    fn default() -> Self {
+        Self::with_document(RichDocument::default())
+    }
+}
+
+impl RichEditorState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// State editing `doc`, with the caret at the end
+    pub fn with_document(doc: RichDocument) -> Self {
+        let cursor = doc.end();
+        Self {
+            doc,
+            cursor,
+            selection_start: None,
+            pending_marks: None,
+            history: EditHistory::new(),
+            rows: Vec::new(),
+            focused: false,
+            click_counter: ClickCounter::new(),
+            cursor_state: cursor_state(),
+        }
+    }
+
+    /// State editing parsed Markdown
+    pub fn with_markdown(markdown: &str) -> Self {
+        Self::with_document(RichDocument::from_markdown(markdown))
+    }
+
+    /// The document
+    pub fn document(&self) -> &RichDocument {
+        &self.doc
+    }
+
+    /// Replace the document, clearing the selection and undo history
+    pub fn set_document(&mut self, doc: RichDocument) {
+        self.cursor = doc.end();
+        self.doc = doc;
+        self.selection_start = None;
+        self.pending_marks = None;
+        self.rows.clear();
+        self.history.clear();
+    }
+
+    /// The document as Markdown
+    pub fn markdown(&self) -> String {
+        self.doc.to_markdown()
+    }
+
+    /// The document as plain text
+    pub fn plain_text(&self) -> String {
+        self.doc.to_plain_text()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.doc.is_empty()
+    }
+
+    // ========================================================================
+    // Editing
+    // ========================================================================
+
+    /// Insert text at the caret, replacing the selection
+    ///
+    /// The text takes the marks of the text before the caret (or those
+    /// toggled since the caret last moved); line breaks start new blocks.
+    pub fn insert_text(&mut self, text: &str) {
+        let kind = if text.chars().count() == 1 {
+            EditKind::Typing
+        } else {
+            EditKind::Other
+        };
+        self.edit(kind, |s| {
+            let marks = s.typing_marks();
+            s.delete_selection_internal();
+            for (i, line) in text.split('\n').enumerate() {
+                if i > 0 {
+                    s.split_block();
+                }
+                let line = line.trim_end_matches('\r');
+                let run = TextRun::new(line, marks.clone());
+                let len = run.len();
+                s.doc.blocks[s.cursor.block].insert_runs(s.cursor.offset, vec![run]);
+                s.cursor.offset += len;
+            }
+            s.pending_marks = Some(marks);
+        });
+    }
+
+    /// Start a new block at the caret (Enter)
+    ///
+    /// Enter in an empty list item ends the list instead.
+    pub fn insert_paragraph(&mut self) {
+        self.edit(EditKind::Other, |s| {
+            s.delete_selection_internal();
+            let block = &mut s.doc.blocks[s.cursor.block];
+            if block.kind.is_list() && block.is_empty() {
+                block.kind = BlockType::Paragraph;
+            } else {
+                s.split_block();
+            }
+        });
+    }
+
+    /// Split the block at the caret, moving the caret to the new block
+    fn split_block(&mut self) {
+        let DocPosition { block, offset } = self.cursor;
+        let current = &mut self.doc.blocks[block];
+        let kind = match current.kind {
+            // Text after a heading is body text
+            BlockType::Heading(_) if offset == current.len() => BlockType::Paragraph,
+            kind => kind,
+        };
+        let tail = current.split_off(offset);
+        self.doc
+            .blocks
+            .insert(block + 1, Block { kind, runs: tail });
+        self.cursor = DocPosition::new(block + 1, 0);
+    }
+
+    /// Delete the selection or the char before the caret (Backspace)
+    ///
+    /// At the start of a heading or list item this first turns it into a
+    /// paragraph; at the start of a paragraph it joins the previous block.
+    pub fn delete_backward(&mut self) {
+        self.edit(EditKind::Deletion, |s| {
+            if s.delete_selection_internal() {
+                return;
+            }
+            let DocPosition { block, offset } = s.cursor;
+            if offset > 0 {
+                s.doc.blocks[block].remove(offset - 1..offset);
+                s.cursor.offset -= 1;
+            } else if s.doc.blocks[block].kind != BlockType::Paragraph {
+                s.doc.blocks[block].kind = BlockType::Paragraph;
+            } else if block > 0 {
+                let prev_end = DocPosition::new(block - 1, s.doc.blocks[block - 1].len());
+                s.doc.remove(prev_end, s.cursor);
+                s.cursor = prev_end;
+            }
+        });
+    }
+
+    /// Delete the selection or the char after the caret (Delete)
+    pub fn delete_forward(&mut self) {
+        self.edit(EditKind::Deletion, |s| {
+            if s.delete_selection_internal() {
+                return;
+            }
+            let DocPosition { block, offset } = s.cursor;
+            if offset < s.doc.blocks[block].len() {
+                s.doc.blocks[block].remove(offset..offset + 1);
+            } else if block + 1 < s.doc.blocks.len() {
+                s.doc.remove(s.cursor, DocPosition::new(block + 1, 0));
+            }
+        });
+    }
+
+    /// Delete the selection; returns whether anything was selected
+    pub fn delete_selection(&mut self) -> bool {
+        let mut deleted = false;
+        self.edit(EditKind::Deletion, |s| {
+            deleted = s.delete_selection_internal()
+        });
+        deleted
+    }
+
+    fn delete_selection_internal(&mut self) -> bool {
+        let Some((from, to)) = self.selection_range() else {
+            self.selection_start = None;
+            return false;
+        };
+        self.doc.remove(from, to);
+        self.cursor = from;
+        self.selection_start = None;
+        true
+    }
+
+    /// Run an edit, recording an undo step if the document changed
+    fn edit(&mut self, kind: EditKind, f: impl FnOnce(&mut Self)) {
+        let before = self.snapshot();
+        f(self);
+        if self.doc != before.doc {
+            self.history.record(before, kind, elapsed_ms());
+        }
+    }
+
+    // ========================================================================
+    // Formatting commands
+    // ========================================================================
+
+    /// Toggle an inline mark on the selection
+    ///
+    /// The mark is removed if the whole selection has it and added
+    /// otherwise. Without a selection it applies to the next typed text.
+    pub fn toggle_mark(&mut self, mark: Mark) {
+        let Some((from, to)) = self.selection_range() else {
+            let mut marks = self.typing_marks();
+            marks.set(mark, !marks.has(mark));
+            self.pending_marks = Some(marks);
+            return;
+        };
+
+        let on = !self.selection_has(|marks| marks.has(mark));
+        self.history.break_group();
+        self.edit(EditKind::Other, |s| {
+            s.update_marks(from, to, |marks| marks.set(mark, on));
+        });
+    }
+
+    /// Set or remove (`None`) the link on the selection
+    pub fn set_link(&mut self, url: Option<String>) {
+        let Some((from, to)) = self.selection_range() else {
+            let mut marks = self.typing_marks();
+            marks.link = url;
+            self.pending_marks = Some(marks);
+            return;
+        };
+        self.history.break_group();
+        self.edit(EditKind::Other, |s| {
+            s.update_marks(from, to, |marks| marks.link = url.clone());
+        });
+    }
+
+    /// Whether `mark` applies to the whole selection, or to typed text at the caret
+    pub fn mark_active(&self, mark: Mark) -> bool {
+        if self.selection_range().is_some() {
+            self.selection_has(|marks| marks.has(mark))
+        } else {
+            self.typing_marks().has(mark)
+        }
+    }
+
+    /// Link at the caret or on the whole selection
+    pub fn link(&self) -> Option<String> {
+        let Some((from, to)) = self.selection_range() else {
+            return self.typing_marks().link;
+        };
+        let selected = self.doc.slice(from, to);
+        let mut runs = selected.blocks.iter().flat_map(|block| &block.runs);
+        let url = runs.next()?.marks.link.clone()?;
+        runs.all(|run| run.marks.link.as_ref() == Some(&url))
+            .then_some(url)
+    }
+
+    /// Set the type of every block touched by the selection
+    ///
+    /// If they all have that type already they become paragraphs, so a
+    /// toolbar button toggles.
+    pub fn set_block_type(&mut self, kind: BlockType) {
+        let (from, to) = self.selection_range().unwrap_or((self.cursor, self.cursor));
+        let blocks = from.block..=to.block;
+        let all = self.doc.blocks[blocks.clone()]
+            .iter()
+            .all(|block| block.kind == kind);
+        let kind = if all { BlockType::Paragraph } else { kind };
+
+        self.history.break_group();
+        self.edit(EditKind::Other, |s| {
+            for block in &mut s.doc.blocks[blocks] {
+                block.kind = kind;
+            }
+        });
+    }
+
+    /// Type of the block holding the caret
+    pub fn block_type(&self) -> BlockType {
+        self.doc.blocks[self.cursor.block].kind
+    }
+
+    /// Marks typed text would get at the caret
+    fn typing_marks(&self) -> Marks {
+        if let Some(marks) = &self.pending_marks {
+            return marks.clone();
+        }
+        let block = &self.doc.blocks[self.cursor.block];
+        let offset = self.cursor.offset;
+        let Some(before) = offset.checked_sub(1).and_then(|o| block.marks_at(o)) else {
+            // At the start of a block, continue the formatting that follows
+            return block.marks_at(0).cloned().unwrap_or_default();
+        };
+        let mut marks = before.clone();
+        // Links end where they end; typing after one doesn't extend it
+        if block.marks_at(offset).map(|m| &m.link) != Some(&marks.link) {
+            marks.link = None;
+        }
+        marks
+    }
+
+    /// Whether every selected run satisfies `f`
+    fn selection_has(&self, f: impl Fn(&Marks) -> bool) -> bool {
+        let Some((from, to)) = self.selection_range() else {
+            return false;
+        };
+        self.doc
+            .slice(from, to)
+            .blocks
+            .iter()
+            .flat_map(|block| &block.runs)
+            .all(|run| f(&run.marks))
+    }
+
+    fn update_marks(&mut self, from: DocPosition, to: DocPosition, f: impl Fn(&mut Marks)) {
+        for i in from.block..=to.block {
+            let block = &mut self.doc.blocks[i];
+            let start = if i == from.block { from.offset } else { 0 };
+            let end = if i == to.block {
+                to.offset
+            } else {
+                block.len()
+            };
+            block.update_marks(start..end, &f);
+        }
+    }
+
+    // ========================================================================
+    // Selection and clipboard
+    // ========================================================================
+
+    /// Ordered selection bounds, if anything is selected
+    pub fn selection_range(&self) -> Option<(DocPosition, DocPosition)> {
+        let start = self.selection_start?;
+        (start != self.cursor).then(|| (start.min(self.cursor), start.max(self.cursor)))
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.selection_range().is_some()
+    }
+
+    /// Select the whole document
+    pub fn select_all(&mut self) {
+        self.selection_start = Some(DocPosition::default());
+        self.cursor = self.doc.end();
+        self.pending_marks = None;
+    }
+
+    /// The selection as a document
+    pub fn selected_document(&self) -> Option<RichDocument> {
+        let (from, to) = self.selection_range()?;
+        Some(self.doc.slice(from, to))
+    }
+
+    /// The selection as Markdown (rich copy)
+    pub fn selection_as_markdown(&self) -> Option<String> {
+        self.selected_document().map(|doc| doc.to_markdown())
+    }
+
+    /// The selection as plain text
+    pub fn selection_as_plain_text(&self) -> Option<String> {
+        self.selected_document().map(|doc| doc.to_plain_text())
+    }
+
+    /// Paste Markdown at the caret, replacing the selection
+    pub fn paste_markdown(&mut self, markdown: &str) {
+        self.paste(RichDocument::from_markdown(markdown));
+    }
+
+    /// Paste plain text at the caret, replacing the selection
+    ///
+    /// Like typing, the text takes the formatting at the caret.
+    pub fn paste_plain_text(&mut self, text: &str) {
+        self.history.break_group();
+        self.insert_text(text);
+        self.history.break_group();
+    }
+
+    /// Paste a document at the caret, replacing the selection
+    pub fn paste(&mut self, fragment: RichDocument) {
+        self.edit(EditKind::Other, |s| {
+            s.delete_selection_internal();
+            s.cursor = s.doc.insert(s.cursor, fragment);
+            s.pending_marks = None;
+        });
+    }
+
+    // ========================================================================
+    // Undo
+    // ========================================================================
+
+    fn snapshot(&self) -> RichEditorSnapshot {
+        RichEditorSnapshot {
+            doc: self.doc.clone(),
+            cursor: self.cursor,
+            selection_start: self.selection_start,
+        }
+    }
+
+    fn restore(&mut self, snapshot: RichEditorSnapshot) {
+        self.doc = snapshot.doc;
+        self.cursor = snapshot.cursor;
+        self.selection_start = snapshot.selection_start;
+        self.pending_marks = None;
+    }
+
+    /// Undo the last edit group; returns whether anything changed
+    pub fn undo(&mut self) -> bool {
+        let current = self.snapshot();
+        match self.history.undo(current) {
+            Some(snapshot) => {
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Redo the last undone edit group; returns whether anything changed
+    pub fn redo(&mut self) -> bool {
+        let current = self.snapshot();
+        match self.history.redo(current) {
+            Some(snapshot) => {
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    // ========================================================================
+    // Caret movement
+    // ========================================================================
+
+    /// Start or drop the selection before moving the caret
+    fn begin_move(&mut self, select: bool) {
+        if select && self.selection_start.is_none() {
+            self.selection_start = Some(self.cursor);
+        } else if !select {
+            self.selection_start = None;
+        }
+        self.pending_marks = None;
+        self.history.break_group();
+    }
+
+    pub fn move_left(&mut self, select: bool) {
+        if let (false, Some((from, _))) = (select, self.selection_range()) {
+            self.begin_move(false);
+            self.cursor = from;
+            return;
+        }
+        self.begin_move(select);
+        if self.cursor.offset > 0 {
+            self.cursor.offset -= 1;
+        } else if self.cursor.block > 0 {
+            self.cursor.block -= 1;
+            self.cursor.offset = self.doc.blocks[self.cursor.block].len();
+        }
+    }
+
+    pub fn move_right(&mut self, select: bool) {
+        if let (false, Some((_, to))) = (select, self.selection_range()) {
+            self.begin_move(false);
+            self.cursor = to;
+            return;
+        }
+        self.begin_move(select);
+        if self.cursor.offset < self.doc.blocks[self.cursor.block].len() {
+            self.cursor.offset += 1;
+        } else if self.cursor.block + 1 < self.doc.blocks.len() {
+            self.cursor = DocPosition::new(self.cursor.block + 1, 0);
+        }
+    }
+
+    /// Move to the previous word boundary, crossing to the previous block at its start
+    pub fn move_word_left(&mut self, select: bool) {
+        self.begin_move(select);
+        if self.cursor.offset == 0 {
+            if self.cursor.block > 0 {
+                self.cursor.block -= 1;
+                self.cursor.offset = self.doc.blocks[self.cursor.block].len();
+            }
+        } else {
+            let text = self.doc.blocks[self.cursor.block].text();
+            self.cursor.offset = prev_word_boundary(&text, self.cursor.offset);
+        }
+    }
+
+    /// Move to the next word boundary, crossing to the next block at its end
+    pub fn move_word_right(&mut self, select: bool) {
+        self.begin_move(select);
+        let block = &self.doc.blocks[self.cursor.block];
+        if self.cursor.offset >= block.len() {
+            if self.cursor.block + 1 < self.doc.blocks.len() {
+                self.cursor = DocPosition::new(self.cursor.block + 1, 0);
+            }
+        } else {
+            self.cursor.offset = next_word_boundary(&block.text(), self.cursor.offset);
+        }
+    }
+
+    /// Move up a row, keeping the horizontal position
+    pub fn move_up(&mut self, select: bool) {
+        self.begin_move(select);
+        match self.row_for(self.cursor) {
+            Some(row) if row > 0 => {
+                let x = self.x_in_row(row, self.cursor.offset);
+                self.cursor = self.position_in_row(row - 1, x);
+            }
+            Some(_) => {}
+            // Not laid out yet: move by block
+            None if self.cursor.block > 0 => {
+                let block = self.cursor.block - 1;
+                let offset = self.cursor.offset.min(self.doc.blocks[block].len());
+                self.cursor = DocPosition::new(block, offset);
+            }
+            None => {}
+        }
+    }
+
+    /// Move down a row, keeping the horizontal position
+    pub fn move_down(&mut self, select: bool) {
+        self.begin_move(select);
+        match self.row_for(self.cursor) {
+            Some(row) if row + 1 < self.rows.len() => {
+                let x = self.x_in_row(row, self.cursor.offset);
+                self.cursor = self.position_in_row(row + 1, x);
+            }
+            Some(_) => {}
+            None if self.cursor.block + 1 < self.doc.blocks.len() => {
+                let block = self.cursor.block + 1;
+                let offset = self.cursor.offset.min(self.doc.blocks[block].len());
+                self.cursor = DocPosition::new(block, offset);
+            }
+            None => {}
+        }
+    }
+
+    /// Move to the start of the row
+    pub fn move_to_line_start(&mut self, select: bool) {
+        self.begin_move(select);
+        self.cursor.offset = self
+            .row_for(self.cursor)
+            .map_or(0, |row| self.rows[row].range.start);
+    }
+
+    /// Move to the end of the row
+    pub fn move_to_line_end(&mut self, select: bool) {
+        self.begin_move(select);
+        let block_len = self.doc.blocks[self.cursor.block].len();
+        self.cursor.offset = self
+            .row_for(self.cursor)
+            .map_or(block_len, |row| self.rows[row].range.end);
+    }
+
+    /// Select the word at `pos` (double-click)
+    pub fn select_word_at(&mut self, pos: DocPosition) {
+        let pos = self.doc.clamp(pos);
+        let text = self.doc.blocks[pos.block].text();
+        let (start, end) = word_range_at(&text, pos.offset);
+        self.selection_start = Some(DocPosition::new(pos.block, start));
+        self.cursor = DocPosition::new(pos.block, end);
+        self.pending_marks = None;
+    }
+
+    /// Select the block at `block` (triple-click)
+    pub fn select_block(&mut self, block: usize) {
+        let block = block.min(self.doc.blocks.len() - 1);
+        self.selection_start = Some(DocPosition::new(block, 0));
+        self.cursor = DocPosition::new(block, self.doc.blocks[block].len());
+        self.pending_marks = None;
+    }
+
+    // ========================================================================
+    // Layout
+    // ========================================================================
+
+    /// Wrap every block into rows for `config`
+    pub(crate) fn layout(&mut self, config: &RichEditorConfig) {
+        self.rows.clear();
+        for (index, block) in self.doc.blocks.iter().enumerate() {
+            let font_size = config.font_size_for(block.kind);
+            let width = config.text_width_for(block.kind);
+            for range in wrap_ranges(&block.text(), font_size, width) {
+                self.rows.push(RichRow {
+                    block: index,
+                    range,
+                    font_size,
+                });
+            }
+        }
+    }
+
+    /// Row holding `pos`, preferring the later row at a wrap point
+    fn row_for(&self, pos: DocPosition) -> Option<usize> {
+        let first = self.rows.partition_point(|row| row.block < pos.block);
+        let rows = self.rows.get(first..)?;
+        let count = rows.iter().take_while(|row| row.block == pos.block).count();
+        (0..count)
+            .find(|&i| {
+                let range = &rows[i].range;
+                pos.offset >= range.start && (pos.offset < range.end || i + 1 == count)
+            })
+            .map(|i| first + i)
+    }
+
+    /// X of char `offset` within row `row`
+    fn x_in_row(&self, row: usize, offset: usize) -> f32 {
+        let row = &self.rows[row];
+        let text = self.doc.blocks[row.block].text();
+        let before: String = text
+            .chars()
+            .skip(row.range.start)
+            .take(offset.saturating_sub(row.range.start))
+            .collect();
+        crate::text_measure::measure_text(&before, row.font_size).width
+    }
+
+    /// Position closest to `x` in row `row`
+    fn position_in_row(&self, row: usize, x: f32) -> DocPosition {
+        let info = &self.rows[row];
+        let text = self.doc.blocks[info.block].text();
+        let chars: Vec<char> = text.chars().collect();
+        let last_row = self
+            .rows
+            .get(row + 1)
+            .map_or(true, |r| r.block != info.block);
+        // A wrapped row's end is the next row's start
+        let end = if last_row {
+            info.range.end
+        } else {
+            info.range.end.saturating_sub(1).max(info.range.start)
+        };
+
+        let mut best = info.range.start;
+        let mut best_dist = f32::MAX;
+        for offset in info.range.start..=end {
+            let prefix = String::from_iter(&chars[info.range.start..offset]);
+            let width = crate::text_measure::measure_text(&prefix, info.font_size).width;
+            let dist = (width - x).abs();
+            if dist < best_dist {
+                best_dist = dist;
+                best = offset;
+            }
+        }
+        DocPosition::new(info.block, best)
+    }
+
+    /// Handle a press at `x` in row `row`
+    fn click(&mut self, row: usize, x: f32, window_pos: (f32, f32), shift: bool) {
+        if row >= self.rows.len() {
+            return;
+        }
+        let pos = self.position_in_row(row, x);
+        let clicks = self
+            .click_counter
+            .register(window_pos.0, window_pos.1, elapsed_ms());
+        self.begin_move(shift);
+        match clicks {
+            2 => self.select_word_at(pos),
+            3 => self.select_block(pos.block),
+            _ => self.cursor = pos,
+        }
+    }
+}
+
+/// Shared rich editor state handle
+pub type SharedRichEditorState = Arc<Mutex<RichEditorState>>;
+
+/// Create an empty shared rich editor state
+pub fn rich_editor_state() -> SharedRichEditorState {
+    Arc::new(Mutex::new(RichEditorState::new()))
+}
+
+/// Create a shared rich editor state from Markdown
+pub fn rich_editor_state_from_markdown(markdown: &str) -> SharedRichEditorState {
+    Arc::new(Mutex::new(RichEditorState::with_markdown(markdown)))
+}
+
+// ============================================================================
+// Rich Editor Widget
+// ============================================================================
+
+/// Type alias for the on_change callback (receives the document as Markdown)
+type OnChangeCallback = Arc<dyn Fn(&str) + Send + Sync + 'static>;
+
+/// WYSIWYG rich text editor
+///
+/// Usage: `rich_editor(&state).w(480.0).on_change(|markdown| ...)`
+pub struct RichEditor {
+    /// The visual structure, rebuilt whenever config changes
+    inner: Div,
+    state: SharedRichEditorState,
+    config: RichEditorConfig,
+    on_change: Option<OnChangeCallback>,
+}
+
+impl Deref for RichEditor {
+    type Target = Div;
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for RichEditor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl RichEditor {
+    /// Create an editor for shared state
+    pub fn new(state: &SharedRichEditorState) -> Self {
+        let mut editor = Self {
+            inner: Div::new(),
+            state: Arc::clone(state),
+            config: RichEditorConfig::default(),
+            on_change: None,
+        };
+        editor.rebuild_inner();
+        editor
+    }
+
+    fn rebuild_inner(&mut self) {
+        self.inner = self.create_visual_structure();
+    }
+
+    // ========================================================================
+    // Builder Methods
+    // ========================================================================
+
+    /// Set the width
+    pub fn w(mut self, px: f32) -> Self {
+        self.config.width = px;
+        self.rebuild_inner();
+        self
+    }
+
+    /// Set the minimum height
+    pub fn min_h(mut self, px: f32) -> Self {
+        self.config.min_height = px;
+        self.rebuild_inner();
+        self
+    }
+
+    /// Set the body font size
+    pub fn font_size(mut self, size: f32) -> Self {
+        self.config.font_size = size;
+        self.rebuild_inner();
+        self
+    }
+
+    /// Set the placeholder shown while empty
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.config.placeholder = placeholder.into();
+        self.rebuild_inner();
+        self
+    }
+
+    /// Set the corner radius
+    pub fn rounded(mut self, radius: f32) -> Self {
+        self.config.corner_radius = radius;
+        self.rebuild_inner();
+        self
+    }
+
+    /// Set the background color
+    pub fn bg(mut self, color: Color) -> Self {
+        self.config.bg_color = color;
+        self.rebuild_inner();
+        self
+    }
+
+    /// Set the text color
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.config.text_color = color;
+        self.rebuild_inner();
+        self
+    }
+
+    /// Set callback for content changes; receives the document as Markdown
+    pub fn on_change<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.on_change = Some(Arc::new(callback));
+        self.rebuild_inner();
+        self
+    }
+
+    // ========================================================================
+    // Internal Methods
+    // ========================================================================
+
+    fn create_visual_structure(&self) -> Div {
+        let config = &self.config;
+        let mut state = self.state.lock().unwrap();
+        state.layout(config);
+        let state = &*state;
+
+        let border_color = if state.focused {
+            config.focused_border_color
+        } else {
+            config.border_color
+        };
+        let mut container = div()
+            .flex_col()
+            .w(config.width)
+            .min_h(config.min_height)
+            .padding_x_px(config.padding)
+            .padding_y_px(config.padding)
+            .bg(config.bg_color)
+            .rounded(config.corner_radius)
+            .border(config.border_width, border_color)
+            .overflow_clip();
+
+        let selection = state.selection_range();
+        let cursor_row = state.focused.then(|| state.row_for(state.cursor)).flatten();
+        let show_placeholder = state.is_empty() && !config.placeholder.is_empty();
+
+        let mut number = 0;
+        let mut row_index = 0;
+        for (block_index, block) in state.doc.blocks.iter().enumerate() {
+            number = match block.kind {
+                BlockType::NumberedItem => number + 1,
+                _ => 0,
+            };
+            let font_size = config.font_size_for(block.kind);
+            let row_height = font_size * config.line_height;
+            let block_text = block.text();
+
+            let mut block_div = div().flex_col();
+            if block_index > 0 {
+                block_div = block_div.mt(config.block_spacing);
+            }
+
+            let mut first_row = true;
+            while state
+                .rows
+                .get(row_index)
+                .is_some_and(|row| row.block == block_index)
+            {
+                let row = &state.rows[row_index];
+                let mut row_div = div().h(row_height).flex_row().items_center();
+
+                if block.kind.is_list() {
+                    let marker = match (first_row, block.kind) {
+                        (false, _) => String::new(),
+                        (true, BlockType::NumberedItem) => format!("{number}."),
+                        (true, _) => "•".to_string(),
+                    };
+                    row_div = row_div.child(
+                        div().w(config.list_indent).child(
+                            text(marker)
+                                .size(font_size)
+                                .color(config.text_color)
+                                .no_wrap(),
+                        ),
+                    );
+                }
+
+                let mut text_div = div()
+                    .flex_grow()
+                    .h(row_height)
+                    .flex_row()
+                    .items_center()
+                    .relative();
+
+                // Selection highlight behind the text
+                if let Some((from, to)) = selection {
+                    let block_pos = |offset| DocPosition::new(block_index, offset);
+                    let start = row.range.start.max(if from.block == block_index {
+                        from.offset
+                    } else {
+                        0
+                    });
+                    let end = row.range.end.min(if to.block == block_index {
+                        to.offset
+                    } else {
+                        block.len()
+                    });
+                    let covered =
+                        block_pos(row.range.start) < to && from < block_pos(row.range.end);
+                    // Selected line breaks show as a sliver after the text
+                    let breaks = to.block > block_index && row.range.end == block.len();
+                    if covered || breaks {
+                        let left = state.x_in_row(row_index, start);
+                        let right = state.x_in_row(row_index, end.max(start))
+                            + if breaks { font_size * 0.3 } else { 0.0 };
+                        text_div = text_div.child(
+                            div()
+                                .absolute()
+                                .left(left)
+                                .top(0.0)
+                                .w((right - left).max(0.0))
+                                .h(row_height)
+                                .bg(config.selection_color),
+                        );
+                    }
+                }
+
+                if show_placeholder && block_index == 0 {
+                    text_div = text_div.child(
+                        text(&config.placeholder)
+                            .size(font_size)
+                            .color(config.placeholder_color)
+                            .no_wrap(),
+                    );
+                } else {
+                    text_div = text_div.child(self.row_text(block, &block_text, row, font_size));
+                }
+
+                if cursor_row == Some(row_index) {
+                    let x = state.x_in_row(row_index, state.cursor.offset);
+                    text_div = text_div.child(self.cursor_canvas(state, x, row_height, font_size));
+                }
+
+                let state_for_row = Arc::clone(&self.state);
+                let row_for_click = row_index;
+                text_div = text_div.on_mouse_down(move |ctx| {
+                    let mut s = state_for_row.lock().unwrap();
+                    s.click(
+                        row_for_click,
+                        ctx.local_x,
+                        (ctx.mouse_x, ctx.mouse_y),
+                        ctx.shift,
+                    );
+                    if let Ok(mut cs) = s.cursor_state.lock() {
+                        cs.reset_blink();
+                    };
+                });
+
+                block_div = block_div.child(row_div.child(text_div));
+                first_row = false;
+                row_index += 1;
+            }
+            container = container.child(block_div);
+        }
+
+        self.attach_handlers(container)
+    }
+
+    /// Styled text for one row of a block
+    fn row_text(&self, block: &Block, block_text: &str, row: &RichRow, font_size: f32) -> RichText {
+        let config = &self.config;
+        let row_start = char_to_byte(block_text, row.range.start);
+        let row_end = char_to_byte(block_text, row.range.end);
+        let row_text = &block_text[row_start..row_end];
+
+        let mut spans = Vec::new();
+        let mut run_start = 0;
+        for run in &block.runs {
+            let run_end = run_start + run.text.len();
+            let (start, end) = (run_start.max(row_start), run_end.min(row_end));
+            run_start = run_end;
+            if start >= end {
+                continue;
+            }
+            let marks = &run.marks;
+            let color = if marks.link.is_some() {
+                config.link_color
+            } else if marks.code {
+                config.code_color
+            } else {
+                config.text_color
+            };
+            spans.push(
+                TextSpan::new(start - row_start, end - row_start, color, marks.bold)
+                    .with_italic(marks.italic)
+                    .with_underline(marks.underline || marks.link.is_some()),
+            );
+        }
+
+        RichText::from_styled(StyledText::from_lines(vec![StyledLine::new(
+            if row_text.is_empty() { " " } else { row_text },
+            spans,
+        )]))
+        .size(font_size)
+        .default_color(config.text_color)
+        .no_wrap()
+    }
+
+    fn cursor_canvas(
+        &self,
+        state: &RichEditorState,
+        x: f32,
+        row_height: f32,
+        font_size: f32,
+    ) -> Div {
+        let cursor_height = font_size * 1.2;
+        let cursor_state = Arc::clone(&state.cursor_state);
+        if let Ok(mut cs) = cursor_state.lock() {
+            cs.visible = true;
+            cs.color = self.config.cursor_color;
+            cs.x = x;
+            cs.animation = CursorAnimation::SmoothFade;
+        }
+
+        let cursor_color = self.config.cursor_color;
+        div()
+            .absolute()
+            .left(x)
+            .top((row_height - cursor_height) / 2.0)
+            .w(2.0)
+            .h(cursor_height)
+            .child(
+                canvas(
+                    move |ctx: &mut dyn blinc_core::DrawContext,
+                          bounds: crate::canvas::CanvasBounds| {
+                        let cs = cursor_state.lock().unwrap();
+                        if !cs.visible {
+                            return;
+                        }
+                        let opacity = cs.current_opacity();
+                        if opacity < 0.01 {
+                            return;
+                        }
+                        let color = Color::rgba(
+                            cursor_color.r,
+                            cursor_color.g,
+                            cursor_color.b,
+                            cursor_color.a * opacity,
+                        );
+                        ctx.fill_rect(
+                            Rect::new(0.0, 0.0, bounds.width, bounds.height),
+                            CornerRadius::default(),
+                            Brush::Solid(color),
+                        );
+                    },
+                )
+                .w(2.0)
+                .h(cursor_height),
+            )
+    }
+
+    fn attach_handlers(&self, container: Div) -> Div {
+        let state_for_click = Arc::clone(&self.state);
+        let state_for_blur = Arc::clone(&self.state);
+        let state_for_key = Arc::clone(&self.state);
+        let state_for_text = Arc::clone(&self.state);
+        let on_change_for_key = self.on_change.clone();
+        let on_change_for_text = self.on_change.clone();
+
+        container
+            .on_mouse_down(move |_ctx| {
+                let mut s = state_for_click.lock().unwrap();
+                if !s.focused {
+                    s.focused = true;
+                    increment_focus_count();
+                    request_continuous_redraw_pub();
+                }
+                request_rebuild();
+            })
+            .on_blur(move |_ctx| {
+                let mut s = state_for_blur.lock().unwrap();
+                if s.focused {
+                    s.focused = false;
+                    decrement_focus_count();
+                }
+                s.selection_start = None;
+                if let Ok(mut cs) = s.cursor_state.lock() {
+                    cs.visible = false;
+                }
+                request_rebuild();
+            })
+            .on_key_down(move |ctx| {
+                let mut s = state_for_key.lock().unwrap();
+                if !s.focused {
+                    return;
+                }
+
+                let before = s.doc.clone();
+                let command = ctx.ctrl || ctx.meta;
+                let mut handled = true;
+                match ctx.key_code {
+                    8 if command || ctx.alt => {
+                        if !s.has_selection() {
+                            s.move_word_left(true);
+                        }
+                        s.delete_selection();
+                    }
+                    8 => s.delete_backward(),
+                    127 => s.delete_forward(),
+                    13 => s.insert_paragraph(),
+                    37 if command || ctx.alt => s.move_word_left(ctx.shift),
+                    39 if command || ctx.alt => s.move_word_right(ctx.shift),
+                    37 => s.move_left(ctx.shift),
+                    39 => s.move_right(ctx.shift),
+                    38 => s.move_up(ctx.shift),
+                    40 => s.move_down(ctx.shift),
+                    36 => s.move_to_line_start(ctx.shift),
+                    35 => s.move_to_line_end(ctx.shift),
+                    // Ctrl/Cmd+Alt+0-6: paragraph and headings
+                    48 if command && ctx.alt => s.set_block_type(BlockType::Paragraph),
+                    49..=54 if command && ctx.alt => {
+                        s.set_block_type(BlockType::Heading((ctx.key_code - 48) as u8))
+                    }
+                    // Ctrl/Cmd+Shift+7/8: numbered and bulleted lists
+                    55 if command && ctx.shift => s.set_block_type(BlockType::NumberedItem),
+                    56 if command && ctx.shift => s.set_block_type(BlockType::BulletItem),
+                    65 if command => s.select_all(),
+                    66 if command => s.toggle_mark(Mark::Bold),
+                    67 | 88 if command => {
+                        if let Some(text) = s.selection_as_plain_text() {
+                            let cut = ctx.key_code == 88;
+                            set_selection(text, SelectionSource::RichText, true);
+                            if cut {
+                                s.delete_selection();
+                            }
+                        }
+                    }
+                    69 if command => s.toggle_mark(Mark::Code),
+                    73 if command => s.toggle_mark(Mark::Italic),
+                    85 if command => s.toggle_mark(Mark::Underline),
+                    90 if command && ctx.shift => {
+                        s.redo();
+                    }
+                    90 if command => {
+                        s.undo();
+                    }
+                    89 if command => {
+                        s.redo();
+                    }
+                    _ => handled = false,
+                }
+                if !handled {
+                    return;
+                }
+
+                if let Ok(mut cs) = s.cursor_state.lock() {
+                    cs.reset_blink();
+                }
+                if s.doc != before {
+                    if let Some(ref callback) = on_change_for_key {
+                        callback(&s.markdown());
+                    }
+                }
+                request_rebuild();
+            })
+            .on_text_input(move |ctx| {
+                let mut s = state_for_text.lock().unwrap();
+                if !s.focused {
+                    return;
+                }
+
+                if let Some(c) = ctx.key_char {
+                    s.insert_text(&c.to_string());
+                    if let Ok(mut cs) = s.cursor_state.lock() {
+                        cs.reset_blink();
+                    }
+                    if let Some(ref callback) = on_change_for_text {
+                        callback(&s.markdown());
+                    }
+                    request_rebuild();
+                }
+            })
+    }
+}
+
+impl ElementBuilder for RichEditor {
+    fn build(&self, tree: &mut LayoutTree) -> LayoutNodeId {
+        self.inner.build(tree)
+    }
+
+    fn render_props(&self) -> RenderProps {
+        self.inner.render_props()
+    }
+
+    fn children_builders(&self) -> &[Box<dyn ElementBuilder>] {
+        self.inner.children_builders()
+    }
+
+    fn element_type_id(&self) -> ElementTypeId {
+        ElementTypeId::Div
+    }
+
+    fn semantic_type_name(&self) -> Option<&'static str> {
+        Some("rich-editor")
+    }
+
+    fn event_handlers(&self) -> Option<&crate::event_handler::EventHandlers> {
+        ElementBuilder::event_handlers(&self.inner)
+    }
+
+    fn layout_style(&self) -> Option<&taffy::Style> {
+        self.inner.layout_style()
+    }
+}
+
+/// Create a rich text editor for shared state
+///
+/// # Example
+/// ```ignore
+/// let state = rich_editor_state_from_markdown("Some **bold** text");
+/// rich_editor(&state).w(480.0).placeholder("Write a comment...")
+/// ```
+pub fn rich_editor(state: &SharedRichEditorState) -> RichEditor {
+    RichEditor::new(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bold() -> Marks {
+        Marks {
+            bold: true,
+            ..Marks::default()
+        }
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let markdown = "# Title\n\nSome **bold**, *italic*, <u>underlined</u> and `code` text \
+                        with a [link](https://example.com).\n\n- one\n- **two**\n\n1. first\n2. second";
+        let doc = RichDocument::from_markdown(markdown);
+
+        let kinds: Vec<BlockType> = doc.blocks.iter().map(|b| b.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                BlockType::Heading(1),
+                BlockType::Paragraph,
+                BlockType::BulletItem,
+                BlockType::BulletItem,
+                BlockType::NumberedItem,
+                BlockType::NumberedItem,
+            ]
+        );
+        let para = &doc.blocks[1];
+        assert_eq!(para.runs[1], TextRun::new("bold", bold()));
+        assert!(para
+            .runs
+            .iter()
+            .any(|r| r.marks.underline && r.text == "underlined"));
+        assert!(para.runs.iter().any(|r| r.marks.code && r.text == "code"));
+        assert!(para
+            .runs
+            .iter()
+            .any(|r| r.marks.link.as_deref() == Some("https://example.com")));
+
+        assert_eq!(doc.to_markdown(), markdown);
+        assert_eq!(RichDocument::from_markdown(&doc.to_markdown()), doc);
+    }
+
+    #[test]
+    fn test_markdown_export_nests_and_escapes() {
+        let doc = RichDocument {
+            blocks: vec![Block {
+                kind: BlockType::Paragraph,
+                runs: vec![
+                    TextRun::new("# not a heading ", Marks::default()),
+                    TextRun::new("bold ", bold()),
+                    TextRun::new(
+                        "both",
+                        Marks {
+                            bold: true,
+                            italic: true,
+                            ..Marks::default()
+                        },
+                    ),
+                    TextRun::new(
+                        " `tick`",
+                        Marks {
+                            code: true,
+                            ..Marks::default()
+                        },
+                    ),
+                ],
+            }],
+        };
+        let markdown = doc.to_markdown();
+        assert_eq!(markdown, "\\# not a heading **bold *both***``  `tick` ``");
+        assert_eq!(RichDocument::from_markdown(&markdown), doc);
+    }
+
+    #[test]
+    fn test_toggle_mark_and_typing() {
+        let mut state = RichEditorState::new();
+        state.insert_text("hello");
+        state.toggle_mark(Mark::Bold);
+        assert!(state.mark_active(Mark::Bold));
+        state.insert_text(" world");
+        assert_eq!(state.markdown(), "hello **world**");
+
+        // Toggling on a partly bold selection makes it all bold, then plain
+        state.selection_start = Some(DocPosition::new(0, 0));
+        state.toggle_mark(Mark::Bold);
+        assert_eq!(state.markdown(), "**hello world**");
+        state.toggle_mark(Mark::Bold);
+        assert_eq!(state.markdown(), "hello world");
+        assert!(!state.mark_active(Mark::Bold));
+    }
+
+    #[test]
+    fn test_block_commands() {
+        let mut state = RichEditorState::with_markdown("first\n\nsecond");
+        state.selection_start = Some(DocPosition::new(0, 2));
+        state.set_block_type(BlockType::BulletItem);
+        assert_eq!(state.markdown(), "- first\n- second");
+        assert_eq!(state.block_type(), BlockType::BulletItem);
+
+        // Setting the same type again toggles back
+        state.set_block_type(BlockType::BulletItem);
+        assert_eq!(state.markdown(), "first\n\nsecond");
+
+        // Enter continues a list; Enter on an empty item ends it
+        let mut state = RichEditorState::with_markdown("- item");
+        state.insert_paragraph();
+        state.insert_text("next");
+        state.insert_paragraph();
+        state.insert_paragraph();
+        state.insert_text("after");
+        assert_eq!(state.markdown(), "- item\n- next\n\nafter");
+
+        // Backspace at the start of an item turns it into a paragraph, then joins
+        state.cursor = DocPosition::new(1, 0);
+        state.delete_backward();
+        assert_eq!(state.markdown(), "- item\n\nnext\n\nafter");
+        state.delete_backward();
+        assert_eq!(state.markdown(), "- itemnext\n\nafter");
+    }
+
+    #[test]
+    fn test_clipboard_and_undo() {
+        let mut state = RichEditorState::with_markdown("# Title\n\nbody **bold** text");
+        state.selection_start = Some(DocPosition::new(0, 2));
+        state.cursor = DocPosition::new(1, 9);
+        assert_eq!(
+            state.selection_as_markdown().as_deref(),
+            Some("# tle\n\nbody **bold**")
+        );
+        assert_eq!(
+            state.selection_as_plain_text().as_deref(),
+            Some("tle\nbody bold")
+        );
+
+        state.paste_markdown("*new*\n\n- item");
+        assert_eq!(state.markdown(), "# Ti*new*\n\n- item text");
+        state.paste_plain_text("!");
+        assert_eq!(state.markdown(), "# Ti*new*\n\n- item! text");
+
+        assert!(state.undo());
+        assert!(state.undo());
+        assert_eq!(state.markdown(), "# Title\n\nbody **bold** text");
+        assert!(state.redo());
+        assert_eq!(state.markdown(), "# Ti*new*\n\n- item text");
+    }
+}
@@ -25,9 +25,17 @@ use crate::stateful::{
     refresh_stateful, SharedState, StateTransitions, Stateful, StatefulInner, TextFieldState,
 };
 use crate::text::text;
+use crate::text_buffer::{LineRows, TextBuffer};
+use crate::text_selection::{selection_spans, BidiCaretMap};
 use crate::tree::{LayoutNodeId, LayoutTree};
 use crate::widgets::cursor::{cursor_state, CursorAnimation, SharedCursorState};
+use crate::widgets::ime::{notify_ime_caret, Composition, ImeCaretRect, ImeTarget};
 use crate::widgets::scroll::{Scroll, ScrollDirection, ScrollPhysics, SharedScrollPhysics};
+use crate::widgets::text_edit::{
+    closing_pair, indent_unit, is_closing_pair, leading_indent, next_word_boundary,
+    prev_word_boundary, should_auto_pair, word_range_at, wrap_ranges, ClickCounter, DragMove,
+    EditHistory, EditKind,
+};
 use crate::widgets::text_input::{
     elapsed_ms, increment_focus_count, request_continuous_redraw_pub, set_focused_text_area,
 };
@@ -320,10 +328,11 @@ impl TextAreaConfig {
 }
 
 /// A visual line segment - represents a portion of a logical line that fits on one visual line
+///
+/// Rows are stored per logical line in a [`LineRows`] index, which tracks the
+/// logical line each row belongs to.
 #[derive(Clone, Debug)]
 pub struct VisualLine {
-    /// Index of the logical line this visual line belongs to
-    pub logical_line: usize,
     /// Start character index within the logical line
     pub start_char: usize,
     /// End character index within the logical line (exclusive)
@@ -332,13 +341,45 @@ pub struct VisualLine {
     pub text: String,
     /// Width of this visual line in pixels
     pub width: f32,
+    /// Placeholder for a paragraph that hasn't been wrapped yet
+    ///
+    /// Large documents only wrap paragraphs near the viewport; the rest
+    /// count as one row with no cached text until they scroll into view.
+    pub(crate) pending: bool,
+}
+
+impl VisualLine {
+    /// Unwrapped placeholder row for a whole paragraph of `len` chars
+    fn pending(len: usize) -> Self {
+        Self {
+            start_char: 0,
+            end_char: len,
+            text: String::new(),
+            width: 0.0,
+            pending: true,
+        }
+    }
+}
+
+/// Documents with at most this many lines are wrapped eagerly
+const EAGER_WRAP_LINES: usize = 1000;
+
+/// Documents with at most this many rows render every row
+const VIRTUALIZE_ROWS: usize = 500;
+
+/// Text and caret of a [`TextAreaState`], kept by its undo history
+#[derive(Clone, Debug)]
+pub(crate) struct TextAreaSnapshot {
+    buffer: TextBuffer,
+    cursor: TextPosition,
+    selection_start: Option<TextPosition>,
 }
 
 /// TextArea widget state
 #[derive(Clone)]
 pub struct TextAreaState {
-    /// Lines of text
-    pub lines: Vec<String>,
+    /// Text content
+    pub(crate) buffer: TextBuffer,
     /// Cursor position
     pub cursor: TextPosition,
     /// Selection start position (if selecting)
@@ -368,9 +409,13 @@ pub struct TextAreaState {
     pub(crate) available_width: f32,
     /// Cached wrap enabled flag
     pub(crate) wrap_enabled: bool,
-    /// Computed visual lines (recomputed when text or width changes)
+    /// Computed visual lines, grouped by logical line (re-wrapped per paragraph on edits)
     /// Each VisualLine represents one rendered row of text
-    pub(crate) visual_lines: Vec<VisualLine>,
+    pub(crate) visual_lines: LineRows<VisualLine>,
+    /// Font size, width and wrap flag `visual_lines` were computed with
+    pub(crate) wrap_params: Option<(f32, f32, bool)>,
+    /// Visual rows included in the last build (rows outside are not rendered)
+    pub(crate) rendered_rows: std::ops::Range<usize>,
     /// Reference to the Stateful's shared state for triggering incremental updates
     pub(crate) stateful_state: Option<SharedState<TextFieldState>>,
     /// Last clicked visual line index (set by line click handlers, read by main handler)
@@ -386,12 +431,26 @@ pub struct TextAreaState {
     pub layout_bounds_storage: crate::renderer::LayoutBoundsStorage,
     /// CSS element ID for stylesheet matching (set via TextArea::id())
     pub(crate) css_element_id: Option<String>,
+    /// In-progress IME composition, shown at the cursor but not yet in `lines`
+    pub composition: Option<Composition>,
+    /// Window position of the unscrolled text content origin, for IME caret reporting
+    pub(crate) ime_origin: Option<(f32, f32)>,
+    /// Keep the current line's indentation on Enter, indenting after an opening bracket
+    pub auto_indent: bool,
+    /// Insert closing brackets and quotes as pairs, and wrap selections in them
+    pub bracket_pairing: bool,
+    /// Undo/redo history
+    pub(crate) history: EditHistory<TextAreaSnapshot>,
+    /// Detects double and triple clicks
+    pub(crate) click_counter: ClickCounter,
+    /// Pending drag-to-move of the selection
+    pub(crate) drag_move: Option<DragMove<TextPosition>>,
 }
 
 impl std::fmt::Debug for TextAreaState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TextAreaState")
-            .field("lines", &self.lines)
+            .field("buffer", &self.buffer)
             .field("cursor", &self.cursor)
             .field("selection_start", &self.selection_start)
             .field("visual", &self.visual)
@@ -399,6 +458,9 @@ impl std::fmt::Debug for TextAreaState {
             .field("disabled", &self.disabled)
             .field("focus_time_ms", &self.focus_time_ms)
             .field("cursor_blink_interval_ms", &self.cursor_blink_interval_ms)
+            .field("composition", &self.composition)
+            .field("auto_indent", &self.auto_indent)
+            .field("bracket_pairing", &self.bracket_pairing)
             // Skip stateful_state since StatefulInner doesn't implement Debug
             .finish()
     }
@@ -407,7 +469,7 @@ impl std::fmt::Debug for TextAreaState {
 impl Default for TextAreaState {
     fn default() -> Self {
         Self {
-            lines: vec![String::new()],
+            buffer: TextBuffer::new(),
             cursor: TextPosition::default(),
             selection_start: None,
             visual: TextFieldState::Idle,
@@ -417,18 +479,27 @@ impl Default for TextAreaState {
             cursor_blink_interval_ms: 530, // Standard cursor blink rate (~530ms)
             cursor_state: cursor_state(),
             scroll_physics: Arc::new(Mutex::new(ScrollPhysics::default())),
-            viewport_height: 120.0,   // Default height from TextAreaConfig
-            line_height: 14.0 * 1.4,  // Default font_size * line_height
-            font_size: 14.0,          // Default font size
-            available_width: 276.0,   // Default width minus padding/borders
-            wrap_enabled: true,       // Default to wrapping (visual lines handle cursor tracking)
-            visual_lines: Vec::new(), // Computed on first layout
+            viewport_height: 120.0,  // Default height from TextAreaConfig
+            line_height: 14.0 * 1.4, // Default font_size * line_height
+            font_size: 14.0,         // Default font size
+            available_width: 276.0,  // Default width minus padding/borders
+            wrap_enabled: true,      // Default to wrapping (visual lines handle cursor tracking)
+            visual_lines: LineRows::new(), // Computed on first layout
+            wrap_params: None,
+            rendered_rows: 0..0,
             stateful_state: None,
             clicked_visual_line: None, // Set by line click handlers
             change_version: Arc::new(AtomicU64::new(0)),
             change_signal_id: None,
             layout_bounds_storage: Arc::new(Mutex::new(None)),
             css_element_id: None,
+            composition: None,
+            ime_origin: None,
+            auto_indent: false,
+            bracket_pairing: false,
+            history: EditHistory::new(),
+            click_counter: ClickCounter::new(),
+            drag_move: None,
         }
     }
 }
@@ -441,21 +512,9 @@ impl TextAreaState {
 
     /// Create with initial value
     pub fn with_value(value: impl Into<String>) -> Self {
-        let value = value.into();
-        let lines: Vec<String> = if value.is_empty() {
-            vec![String::new()]
-        } else {
-            value.lines().map(|s| s.to_string()).collect()
-        };
-        let cursor = TextPosition::new(
-            lines.len().saturating_sub(1),
-            lines.last().map(|l| l.chars().count()).unwrap_or(0),
-        );
-        Self {
-            lines,
-            cursor,
-            ..Default::default()
-        }
+        let mut state = Self::default();
+        state.set_value(&value.into());
+        state
     }
 
     /// Create with placeholder
@@ -467,37 +526,44 @@ impl TextAreaState {
     }
 
     /// Get the full text value
+    ///
+    /// Line breaks are returned as they were entered (`\n`, or `\r\n` from
+    /// loaded text).
     pub fn value(&self) -> String {
-        self.lines.join("\n")
+        self.buffer.to_string()
     }
 
-    /// Set the text value
+    /// Set the text value, placing the cursor at the end
     pub fn set_value(&mut self, value: &str) {
-        self.lines = if value.is_empty() {
-            vec![String::new()]
-        } else {
-            value.lines().map(|s| s.to_string()).collect()
-        };
-        self.cursor = TextPosition::new(
-            self.lines.len().saturating_sub(1),
-            self.lines.last().map(|l| l.chars().count()).unwrap_or(0),
-        );
+        self.buffer.set_text(value);
+        self.cursor = self.position_at(self.buffer.len_chars());
         self.selection_start = None;
+        self.invalidate_visual_lines();
+    }
+
+    /// The text buffer
+    pub fn buffer(&self) -> &TextBuffer {
+        &self.buffer
     }
 
     /// Get number of lines
     pub fn line_count(&self) -> usize {
-        self.lines.len()
+        self.buffer.len_lines()
     }
 
-    /// Get a specific line
-    pub fn get_line(&self, index: usize) -> Option<&str> {
-        self.lines.get(index).map(|s| s.as_str())
+    /// Get a specific line, without its line break
+    pub fn get_line(&self, index: usize) -> Option<String> {
+        (index < self.line_count()).then(|| self.buffer.line(index))
+    }
+
+    /// Length of line `line` in chars
+    fn line_len(&self, line: usize) -> usize {
+        self.buffer.line_len(line)
     }
 
     /// Is empty?
     pub fn is_empty(&self) -> bool {
-        self.lines.len() == 1 && self.lines[0].is_empty()
+        self.buffer.is_empty()
     }
 
     /// Is focused?
@@ -563,8 +629,63 @@ impl TextAreaState {
         }
     }
 
+    /// Whether an IME composition is in progress
+    pub fn is_composing(&self) -> bool {
+        self.composition.is_some()
+    }
+
+    /// Logical line `line` with the preedit spliced in at the cursor
+    ///
+    /// Returns `None` unless composing on that line; otherwise the composed text
+    /// and the preedit's char range within it.
+    pub fn composed_line(&self, line: usize) -> Option<(String, (usize, usize))> {
+        let comp = self.composition.as_ref()?;
+        if self.cursor.line != line {
+            return None;
+        }
+        let mut text = self.get_line(line)?;
+        let column = self.cursor.column.min(text.chars().count());
+        text.insert_str(char_to_byte_pos(&text, column), &comp.text);
+        Some((text, (column, column + comp.len())))
+    }
+
+    /// Caret rectangle in window coordinates for positioning the IME candidate window
+    pub fn ime_caret_rect(&self) -> Option<ImeCaretRect> {
+        let (origin_x, origin_y) = self.ime_origin?;
+        let (mut caret_x, caret_y) = if self.visual_lines.is_empty() {
+            let line = self.get_line(self.cursor.line)?;
+            let before: String = line.chars().take(self.cursor.column).collect();
+            (
+                crate::text_measure::measure_text(&before, self.font_size).width,
+                self.cursor.line as f32 * self.line_height,
+            )
+        } else {
+            self.cursor_position_from_visual_lines()
+        };
+        if let Some(comp) = &self.composition {
+            let before: String = comp.text.chars().take(comp.caret()).collect();
+            caret_x += crate::text_measure::measure_text(&before, self.font_size).width;
+        }
+        Some(ImeCaretRect {
+            x: origin_x + caret_x,
+            y: origin_y + caret_y - self.scroll_offset(),
+            width: 2.0,
+            height: self.line_height,
+        })
+    }
+
     /// Insert text at cursor
     pub fn insert(&mut self, text: &str) {
+        let kind = if text.contains('\n') {
+            EditKind::Other
+        } else {
+            EditKind::Typing
+        };
+        self.edit(kind, |s| s.insert_internal(text));
+    }
+
+    /// Internal insertion (no history)
+    fn insert_internal(&mut self, text: &str) {
         self.delete_selection();
 
         if text.contains('\n') {
@@ -580,94 +701,387 @@ impl TextAreaState {
     }
 
     fn insert_text(&mut self, text: &str) {
-        let line_idx = self.cursor.line.min(self.lines.len().saturating_sub(1));
-        let byte_pos = char_to_byte_pos(&self.lines[line_idx], self.cursor.column);
-        self.lines[line_idx].insert_str(byte_pos, text);
-        self.cursor.column += text.chars().count();
+        self.cursor = self.replace_range(self.cursor, self.cursor, text);
+    }
+
+    /// Replace the text between two positions, returning the end of the new text
+    ///
+    /// All edits go through here so only the affected paragraphs are re-wrapped.
+    fn replace_range(&mut self, from: TextPosition, to: TextPosition, text: &str) -> TextPosition {
+        let (from, to) = self.order_positions(from, to);
+        let start = self.char_index(from);
+        let end = self.char_index(to);
+        let from_line = self.position_at(start).line;
+        let old_lines = self.position_at(end).line - from_line + 1;
+
+        self.buffer.replace(start..end, text);
+        let end_pos = self.position_at(start + text.chars().count());
+        self.rewrap_lines(from_line, old_lines, end_pos.line - from_line + 1);
+        end_pos
+    }
+
+    /// Char offset of a position in the buffer
+    fn char_index(&self, pos: TextPosition) -> usize {
+        self.buffer.position_to_char(pos.line, pos.column)
+    }
+
+    /// Position of a char offset in the buffer
+    fn position_at(&self, char_idx: usize) -> TextPosition {
+        let (line, column) = self.buffer.char_to_position(char_idx);
+        TextPosition::new(line, column)
+    }
+
+    /// Type a single character
+    ///
+    /// With [`bracket_pairing`](Self::bracket_pairing) enabled, opening
+    /// brackets and quotes insert their closing pair (or wrap the selection),
+    /// and typing a closing character that is already next to the cursor
+    /// steps over it.
+    pub fn type_char(&mut self, c: char) {
+        if !self.bracket_pairing {
+            return self.insert(c.encode_utf8(&mut [0; 4]));
+        }
+
+        self.edit(EditKind::Typing, |s| {
+            let has_selection = s.selection_start.is_some_and(|start| start != s.cursor);
+            let line = s.buffer.line(s.cursor.line);
+            let prev = s
+                .cursor
+                .column
+                .checked_sub(1)
+                .and_then(|i| line.chars().nth(i));
+            let next = line.chars().nth(s.cursor.column);
+
+            if has_selection {
+                if let (Some(close), Some(text)) = (closing_pair(c), s.selected_text()) {
+                    // Wrap the selection, keeping it selected inside the pair
+                    s.delete_selection();
+                    s.insert_text(c.encode_utf8(&mut [0; 4]));
+                    let start = s.cursor;
+                    s.insert_internal(&text);
+                    let end = s.cursor;
+                    s.insert_text(close.encode_utf8(&mut [0; 4]));
+                    s.selection_start = Some(start);
+                    s.cursor = end;
+                    return;
+                }
+            } else if is_closing_pair(c) && next == Some(c) {
+                s.selection_start = None;
+                s.cursor.column += 1;
+                return;
+            } else if should_auto_pair(c, prev, next) {
+                if let Some(close) = closing_pair(c) {
+                    s.selection_start = None;
+                    s.insert_text(&format!("{c}{close}"));
+                    s.cursor.column -= 1;
+                    return;
+                }
+            }
+            s.insert_internal(c.encode_utf8(&mut [0; 4]));
+        });
     }
 
     /// Insert a newline at cursor
+    ///
+    /// With [`auto_indent`](Self::auto_indent) enabled, the new line keeps the
+    /// current line's indentation, one level deeper after an opening bracket.
+    /// Between a bracket pair the closing bracket moves to its own line.
     pub fn insert_newline(&mut self) {
-        self.insert_newline_internal();
+        self.edit(EditKind::Other, |s| {
+            if !s.auto_indent {
+                return s.insert_newline_internal();
+            }
+            s.delete_selection();
+
+            let line = s.buffer.line(s.cursor.line);
+            let before: String = line.chars().take(s.cursor.column).collect();
+            let indent = leading_indent(&before).to_string();
+            let prev = before.chars().last();
+            let next = line.chars().nth(s.cursor.column);
+            let opens_block = matches!(prev, Some('(' | '[' | '{'));
+
+            s.insert_newline_internal();
+            s.insert_text(&indent);
+            if opens_block {
+                s.insert_text(indent_unit(&indent));
+                if next.is_some() && next == prev.and_then(closing_pair) {
+                    let caret = s.cursor;
+                    s.insert_newline_internal();
+                    s.insert_text(&indent);
+                    s.cursor = caret;
+                }
+            }
+        });
     }
 
     /// Internal newline insertion (no notify)
     fn insert_newline_internal(&mut self) {
         self.delete_selection();
-
-        let line_idx = self.cursor.line.min(self.lines.len().saturating_sub(1));
-        let byte_pos = char_to_byte_pos(&self.lines[line_idx], self.cursor.column);
-
-        let after = self.lines[line_idx].split_off(byte_pos);
-        self.lines.insert(line_idx + 1, after);
-
-        self.cursor.line += 1;
-        self.cursor.column = 0;
+        self.cursor = self.replace_range(self.cursor, self.cursor, "\n");
     }
 
     /// Delete character before cursor (backspace)
+    ///
+    /// With [`bracket_pairing`](Self::bracket_pairing) enabled, deleting an
+    /// opening bracket right before its closing pair removes both.
     pub fn delete_backward(&mut self) {
+        self.edit(EditKind::Deletion, |s| s.delete_backward_internal());
+    }
+
+    fn delete_backward_internal(&mut self) {
         if self.delete_selection() {
             return;
         }
 
-        if self.cursor.column > 0 {
-            let start_byte =
-                char_to_byte_pos(&self.lines[self.cursor.line], self.cursor.column - 1);
-            let end_byte = char_to_byte_pos(&self.lines[self.cursor.line], self.cursor.column);
-            self.lines[self.cursor.line].replace_range(start_byte..end_byte, "");
-            self.cursor.column -= 1;
-        } else if self.cursor.line > 0 {
-            let current_line = self.lines.remove(self.cursor.line);
-            self.cursor.line -= 1;
-            self.cursor.column = self.lines[self.cursor.line].chars().count();
-            self.lines[self.cursor.line].push_str(&current_line);
+        let TextPosition { line, column } = self.cursor;
+        if self.bracket_pairing && column > 0 {
+            let text = self.buffer.line(line);
+            let prev = text.chars().nth(column - 1);
+            let next = text.chars().nth(column);
+            if next.is_some() && next == prev.and_then(closing_pair) {
+                self.cursor = self.replace_range(
+                    TextPosition::new(line, column - 1),
+                    TextPosition::new(line, column + 1),
+                    "",
+                );
+                return;
+            }
+        }
+
+        if column > 0 {
+            self.cursor = self.replace_range(TextPosition::new(line, column - 1), self.cursor, "");
+        } else if line > 0 {
+            let prev_end = TextPosition::new(line - 1, self.line_len(line - 1));
+            self.cursor = self.replace_range(prev_end, self.cursor, "");
         }
     }
 
     /// Delete character after cursor (delete)
     pub fn delete_forward(&mut self) {
+        self.edit(EditKind::Deletion, |s| s.delete_forward_internal());
+    }
+
+    fn delete_forward_internal(&mut self) {
         if self.delete_selection() {
             return;
         }
 
-        let line_len = self.lines[self.cursor.line].chars().count();
-        if self.cursor.column < line_len {
-            let start_byte = char_to_byte_pos(&self.lines[self.cursor.line], self.cursor.column);
-            let end_byte = char_to_byte_pos(&self.lines[self.cursor.line], self.cursor.column + 1);
-            self.lines[self.cursor.line].replace_range(start_byte..end_byte, "");
-        } else if self.cursor.line < self.lines.len() - 1 {
-            let next_line = self.lines.remove(self.cursor.line + 1);
-            self.lines[self.cursor.line].push_str(&next_line);
+        let TextPosition { line, column } = self.cursor;
+        if column < self.line_len(line) {
+            self.replace_range(self.cursor, TextPosition::new(line, column + 1), "");
+        } else if line + 1 < self.line_count() {
+            self.replace_range(self.cursor, TextPosition::new(line + 1, 0), "");
         }
     }
 
+    /// Delete from the cursor back to the previous word boundary (Ctrl+Backspace)
+    pub fn delete_word_backward(&mut self) {
+        self.edit(EditKind::Deletion, |s| {
+            if !s.has_selection() {
+                s.selection_start = None;
+                s.move_word_left(true);
+            }
+            s.delete_selection();
+        });
+    }
+
+    /// Delete from the cursor to the next word boundary (Ctrl+Delete)
+    pub fn delete_word_forward(&mut self) {
+        self.edit(EditKind::Deletion, |s| {
+            if !s.has_selection() {
+                s.selection_start = None;
+                s.move_word_right(true);
+            }
+            s.delete_selection();
+        });
+    }
+
+    /// Whether a non-empty range is selected
+    fn has_selection(&self) -> bool {
+        self.selection_start
+            .is_some_and(|start| start != self.cursor)
+    }
+
+    /// Apply an edit, recording the previous state in the undo history if the text changed
+    fn edit(&mut self, kind: EditKind, f: impl FnOnce(&mut Self)) {
+        let before = self.snapshot();
+        f(self);
+        if self.buffer.revision() != before.buffer.revision() {
+            self.history.record(before, kind, elapsed_ms());
+        }
+    }
+
+    fn snapshot(&self) -> TextAreaSnapshot {
+        TextAreaSnapshot {
+            buffer: self.buffer.clone(),
+            cursor: self.cursor,
+            selection_start: self.selection_start,
+        }
+    }
+
+    fn restore(&mut self, snapshot: TextAreaSnapshot) {
+        self.buffer = snapshot.buffer;
+        self.cursor = snapshot.cursor;
+        self.selection_start = snapshot.selection_start;
+        self.composition = None;
+        self.invalidate_visual_lines();
+    }
+
+    /// Undo the last edit group; returns whether anything changed
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo(self.snapshot()) {
+            Some(snapshot) => {
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Redo the last undone edit group; returns whether anything changed
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo(self.snapshot()) {
+            Some(snapshot) => {
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// End the current undo group, so the next edit is undone separately
+    ///
+    /// Called when the caret is moved by keyboard or mouse.
+    pub fn break_undo_group(&mut self) {
+        self.history.break_group();
+    }
+
+    /// Forget the undo history (e.g. after loading a new document)
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Move to the previous word boundary, crossing to the previous line at column 0
+    pub fn move_word_left(&mut self, select: bool) {
+        self.begin_move(select);
+        if self.cursor.column == 0 {
+            if self.cursor.line > 0 {
+                self.cursor.line -= 1;
+                self.cursor.column = self.line_len(self.cursor.line);
+            }
+        } else {
+            self.cursor.column =
+                prev_word_boundary(&self.buffer.line(self.cursor.line), self.cursor.column);
+        }
+    }
+
+    /// Move to the next word boundary, crossing to the next line at the line end
+    pub fn move_word_right(&mut self, select: bool) {
+        self.begin_move(select);
+        if self.cursor.column >= self.line_len(self.cursor.line) {
+            if self.cursor.line + 1 < self.line_count() {
+                self.cursor.line += 1;
+                self.cursor.column = 0;
+            }
+        } else {
+            self.cursor.column =
+                next_word_boundary(&self.buffer.line(self.cursor.line), self.cursor.column);
+        }
+    }
+
+    /// Start or drop the selection before moving the cursor
+    fn begin_move(&mut self, select: bool) {
+        if select && self.selection_start.is_none() {
+            self.selection_start = Some(self.cursor);
+        } else if !select {
+            self.selection_start = None;
+        }
+    }
+
+    /// Select the word at `pos` (double-click)
+    pub fn select_word_at(&mut self, pos: TextPosition) {
+        let Some(line) = self.get_line(pos.line) else {
+            return;
+        };
+        let (start, end) = word_range_at(&line, pos.column);
+        self.selection_start = Some(TextPosition::new(pos.line, start));
+        self.cursor = TextPosition::new(pos.line, end);
+    }
+
+    /// Select logical line `line` including its line break (triple-click)
+    pub fn select_line(&mut self, line: usize) {
+        let line = line.min(self.line_count() - 1);
+        self.selection_start = Some(TextPosition::new(line, 0));
+        self.cursor = if line + 1 < self.line_count() {
+            TextPosition::new(line + 1, 0)
+        } else {
+            TextPosition::new(line, self.line_len(line))
+        };
+    }
+
+    /// Whether `pos` lies strictly inside the selection
+    pub fn selection_contains(&self, pos: TextPosition) -> bool {
+        let Some(start) = self.selection_start else {
+            return false;
+        };
+        let (from, to) = self.order_positions(start, self.cursor);
+        let after_from =
+            pos.line > from.line || (pos.line == from.line && pos.column > from.column);
+        let before_to = pos.line < to.line || (pos.line == to.line && pos.column < to.column);
+        after_from && before_to
+    }
+
+    /// Move the selected text to `target` (drag-to-move)
+    ///
+    /// The moved text stays selected. Returns `false` if nothing is selected
+    /// or `target` lies within the selection.
+    pub fn move_selection_to(&mut self, target: TextPosition) -> bool {
+        let Some(start) = self.selection_start else {
+            return false;
+        };
+        let (from, to) = self.order_positions(start, self.cursor);
+        let (from, to, target) = (
+            self.char_index(from),
+            self.char_index(to),
+            self.char_index(target),
+        );
+        if from == to || (from..=to).contains(&target) {
+            return false;
+        }
+        let Some(text) = self.selected_text() else {
+            return false;
+        };
+
+        self.edit(EditKind::Other, |s| {
+            s.delete_selection();
+            let at = if target > to {
+                target - (to - from)
+            } else {
+                target
+            };
+            let at = s.position_at(at);
+            s.cursor = at;
+            s.insert_internal(&text);
+            s.selection_start = Some(at);
+        });
+        true
+    }
+
     /// Delete selected text
     fn delete_selection(&mut self) -> bool {
         if let Some(start) = self.selection_start {
             let (from, to) = self.order_positions(start, self.cursor);
 
             if from != to {
-                if from.line == to.line {
-                    let start_byte = char_to_byte_pos(&self.lines[from.line], from.column);
-                    let end_byte = char_to_byte_pos(&self.lines[from.line], to.column);
-                    self.lines[from.line].replace_range(start_byte..end_byte, "");
-                } else {
-                    let from_byte = char_to_byte_pos(&self.lines[from.line], from.column);
-                    self.lines[from.line].truncate(from_byte);
-
-                    let to_byte = char_to_byte_pos(&self.lines[to.line], to.column);
-                    let after_text = self.lines[to.line][to_byte..].to_string();
-                    self.lines[from.line].push_str(&after_text);
-
-                    for _ in from.line + 1..=to.line {
-                        if from.line + 1 < self.lines.len() {
-                            self.lines.remove(from.line + 1);
-                        }
-                    }
-                }
-
-                self.cursor = from;
+                self.cursor = self.replace_range(from, to, "");
                 self.selection_start = None;
                 return true;
             }
@@ -702,7 +1116,7 @@ impl TextAreaState {
             self.cursor.column -= 1;
         } else if self.cursor.line > 0 {
             self.cursor.line -= 1;
-            self.cursor.column = self.lines[self.cursor.line].chars().count();
+            self.cursor.column = self.line_len(self.cursor.line);
         }
 
         if !select {
@@ -723,10 +1137,9 @@ impl TextAreaState {
             }
         }
 
-        let line_len = self.lines[self.cursor.line].chars().count();
-        if self.cursor.column < line_len {
+        if self.cursor.column < self.line_len(self.cursor.line) {
             self.cursor.column += 1;
-        } else if self.cursor.line < self.lines.len() - 1 {
+        } else if self.cursor.line + 1 < self.line_count() {
             self.cursor.line += 1;
             self.cursor.column = 0;
         }
@@ -736,6 +1149,96 @@ impl TextAreaState {
         }
     }
 
+    /// Move cursor one caret position visually left or right
+    ///
+    /// Same as `move_left`/`move_right` for left-to-right lines; on lines
+    /// with right-to-left runs the arrow keys follow screen order, falling
+    /// back to logical movement at the visual edge of the line.
+    pub fn move_visual(&mut self, right: bool, select: bool) {
+        let logical = |state: &mut Self| {
+            if right {
+                state.move_right(select);
+            } else {
+                state.move_left(select);
+            }
+        };
+        if !select && self.selection_start.is_some() {
+            return logical(self);
+        }
+
+        let (start_char, line_text) = match self.visual_lines.row(self.visual_line_for_cursor()) {
+            Some((line, vl)) if line == self.cursor.line && !vl.pending => {
+                (vl.start_char, vl.text.clone())
+            }
+            _ => match self.get_line(self.cursor.line) {
+                Some(line) => (0, line),
+                None => return,
+            },
+        };
+        let Some(map) = self.caret_map(&line_text) else {
+            return logical(self);
+        };
+
+        let local = self.cursor.column.saturating_sub(start_char);
+        let next = map.move_visual(local, right);
+        if next == local {
+            return logical(self);
+        }
+
+        if select && self.selection_start.is_none() {
+            self.selection_start = Some(self.cursor);
+        }
+        self.cursor.column = start_char + next;
+    }
+
+    /// Bidi caret geometry for one line of text (`None` for pure LTR text)
+    fn caret_map(&self, line: &str) -> Option<BidiCaretMap> {
+        let font_size = self.font_size;
+        BidiCaretMap::new(line, |s| {
+            crate::text_measure::measure_text(s, font_size).width
+        })
+    }
+
+    /// Selection highlight spans `(left, right)` for one line segment
+    ///
+    /// `start_char..end_char` is the segment's character range within
+    /// logical line `line`, and `text` its content.
+    fn selection_spans_for(
+        &self,
+        line: usize,
+        start_char: usize,
+        end_char: usize,
+        text: &str,
+    ) -> Vec<(f32, f32)> {
+        let Some(start) = self.selection_start else {
+            return Vec::new();
+        };
+        let (from, to) = self.order_positions(start, self.cursor);
+        if from.line > line || to.line < line {
+            return Vec::new();
+        }
+
+        // Part of this segment covered by the selection
+        let sel_start = if from.line < line {
+            start_char
+        } else {
+            from.column.max(start_char)
+        };
+        let sel_end = if to.line > line {
+            end_char
+        } else {
+            to.column.min(end_char)
+        };
+        if sel_start >= sel_end {
+            return Vec::new();
+        }
+
+        let font_size = self.font_size;
+        selection_spans(text, sel_start - start_char, sel_end - start_char, |s| {
+            crate::text_measure::measure_text(s, font_size).width
+        })
+    }
+
     /// Move cursor up (handles visual lines for wrapped text)
     pub fn move_up(&mut self, select: bool) {
         if select && self.selection_start.is_none() {
@@ -746,27 +1249,29 @@ impl TextAreaState {
 
         // If we have visual lines, use them for navigation
         if !self.visual_lines.is_empty() && self.wrap_enabled {
+            self.realize_cursor_row();
             let current_visual_idx = self.visual_line_for_cursor();
             if current_visual_idx > 0 {
-                // Move to the visual line above
-                let prev_visual_idx = current_visual_idx - 1;
-                let prev_vl = &self.visual_lines[prev_visual_idx];
-
                 // Calculate cursor x position to maintain horizontal position
                 let cursor_x = self.cursor_x_in_visual_line();
 
+                // Move to the visual line above; wrapping it may add rows above the cursor
+                self.realize_rows(current_visual_idx - 1..current_visual_idx);
+                let prev_visual_idx = self.visual_line_for_cursor() - 1;
+
                 // Find the column in the previous visual line that best matches our x position
                 let column = self.find_column_at_x(prev_visual_idx, cursor_x);
 
-                self.cursor.line = prev_vl.logical_line;
-                self.cursor.column = column;
+                if let Some((line, _)) = self.visual_lines.locate(prev_visual_idx) {
+                    self.cursor.line = line;
+                    self.cursor.column = column;
+                }
             }
         } else {
             // Fallback: simple logical line navigation
             if self.cursor.line > 0 {
                 self.cursor.line -= 1;
-                let line_len = self.lines[self.cursor.line].chars().count();
-                self.cursor.column = self.cursor.column.min(line_len);
+                self.cursor.column = self.cursor.column.min(self.line_len(self.cursor.line));
             }
         }
     }
@@ -781,11 +1286,12 @@ impl TextAreaState {
 
         // If we have visual lines, use them for navigation
         if !self.visual_lines.is_empty() && self.wrap_enabled {
+            self.realize_cursor_row();
             let current_visual_idx = self.visual_line_for_cursor();
-            if current_visual_idx < self.visual_lines.len() - 1 {
+            if current_visual_idx < self.visual_lines.len_rows() - 1 {
                 // Move to the visual line below
                 let next_visual_idx = current_visual_idx + 1;
-                let next_vl = &self.visual_lines[next_visual_idx];
+                self.realize_rows(next_visual_idx..next_visual_idx + 1);
 
                 // Calculate cursor x position to maintain horizontal position
                 let cursor_x = self.cursor_x_in_visual_line();
@@ -793,30 +1299,33 @@ impl TextAreaState {
                 // Find the column in the next visual line that best matches our x position
                 let column = self.find_column_at_x(next_visual_idx, cursor_x);
 
-                self.cursor.line = next_vl.logical_line;
-                self.cursor.column = column;
+                if let Some((line, _)) = self.visual_lines.locate(next_visual_idx) {
+                    self.cursor.line = line;
+                    self.cursor.column = column;
+                }
             }
         } else {
             // Fallback: simple logical line navigation
-            if self.cursor.line < self.lines.len() - 1 {
+            if self.cursor.line + 1 < self.line_count() {
                 self.cursor.line += 1;
-                let line_len = self.lines[self.cursor.line].chars().count();
-                self.cursor.column = self.cursor.column.min(line_len);
+                self.cursor.column = self.cursor.column.min(self.line_len(self.cursor.line));
             }
         }
     }
 
     /// Find the column in a visual line that best matches a given x position
     fn find_column_at_x(&self, visual_line_idx: usize, target_x: f32) -> usize {
-        if visual_line_idx >= self.visual_lines.len() {
+        let Some((_, vl)) = self.visual_lines.row(visual_line_idx) else {
             return 0;
-        }
-
-        let vl = &self.visual_lines[visual_line_idx];
+        };
         if vl.text.is_empty() {
             return vl.start_char;
         }
 
+        if let Some(map) = self.caret_map(&vl.text) {
+            return vl.start_char + map.index_at_x(target_x);
+        }
+
         let char_count = vl.text.chars().count();
         let mut best_pos = 0;
         let mut min_dist = f32::MAX;
@@ -853,7 +1362,7 @@ impl TextAreaState {
         } else if !select {
             self.selection_start = None;
         }
-        self.cursor.column = self.lines[self.cursor.line].chars().count();
+        self.cursor.column = self.line_len(self.cursor.line);
     }
 
     /// Move to start of text
@@ -873,65 +1382,24 @@ impl TextAreaState {
         } else if !select {
             self.selection_start = None;
         }
-        let last_line = self.lines.len().saturating_sub(1);
-        self.cursor = TextPosition::new(last_line, self.lines[last_line].chars().count());
+        self.cursor = self.position_at(self.buffer.len_chars());
     }
 
     /// Select all text
     pub fn select_all(&mut self) {
         self.selection_start = Some(TextPosition::new(0, 0));
-        let last_line = self.lines.len().saturating_sub(1);
-        self.cursor = TextPosition::new(last_line, self.lines[last_line].chars().count());
+        self.cursor = self.position_at(self.buffer.len_chars());
     }
 
     /// Get selected text
     pub fn selected_text(&self) -> Option<String> {
         self.selection_start.map(|start| {
             let (from, to) = self.order_positions(start, self.cursor);
-
-            if from.line == to.line {
-                self.lines[from.line]
-                    .chars()
-                    .skip(from.column)
-                    .take(to.column - from.column)
-                    .collect()
-            } else {
-                let mut result = String::new();
-                result.extend(self.lines[from.line].chars().skip(from.column));
-
-                for line in &self.lines[from.line + 1..to.line] {
-                    result.push('\n');
-                    result.push_str(line);
-                }
-
-                if to.line > from.line {
-                    result.push('\n');
-                    result.extend(self.lines[to.line].chars().take(to.column));
-                }
-
-                result
-            }
+            self.buffer
+                .slice(self.char_index(from)..self.char_index(to))
         })
     }
 
-    /// Calculate the number of visual lines a text line takes when wrapped
-    ///
-    /// Returns 1 for short lines, more for lines that wrap.
-    fn visual_lines_for_text(text: &str, font_size: f32, available_width: f32) -> usize {
-        if text.is_empty() || available_width <= 0.0 {
-            return 1;
-        }
-
-        let metrics = crate::text_measure::measure_text(text, font_size);
-        if metrics.width <= available_width {
-            return 1;
-        }
-
-        // Estimate number of lines by dividing total width by available width
-        // Add 1 because integer division rounds down
-        ((metrics.width / available_width).ceil() as usize).max(1)
-    }
-
     /// Compute visual lines for all text content
     ///
     /// This creates a list of VisualLine entries that map logical lines to
@@ -941,106 +1409,157 @@ impl TextAreaState {
     /// - The actual text content and its measured width
     ///
     /// Call this whenever:
-    /// - Text content changes
     /// - Available width changes (e.g., container resize)
     /// - Font size changes
+    /// - The viewport scrolls
+    ///
+    /// Edits re-wrap the paragraphs they touch as they happen. Documents
+    /// longer than [`EAGER_WRAP_LINES`] only wrap the paragraphs around the
+    /// viewport and the cursor; the rest stay one pending row each.
     pub fn compute_visual_lines(&mut self) {
-        self.visual_lines.clear();
+        let params = (self.font_size, self.available_width, self.wrap_enabled);
+        if self.wrap_params != Some(params) || self.visual_lines.is_empty() {
+            self.wrap_params = Some(params);
+            let eager = self.line_count() <= EAGER_WRAP_LINES;
+            let lines = (0..self.line_count()).map(|line| {
+                if eager {
+                    self.wrap_line(line)
+                } else {
+                    vec![VisualLine::pending(self.line_len(line))]
+                }
+            });
+            let visual_lines = LineRows::from_lines(lines);
+            self.visual_lines = visual_lines;
+        }
 
-        let font_size = self.font_size;
-        let available_width = self.available_width;
-        let wrap_enabled = self.wrap_enabled;
-
-        for (logical_line_idx, line_text) in self.lines.iter().enumerate() {
-            if line_text.is_empty() {
-                // Empty line still takes up one visual line
-                self.visual_lines.push(VisualLine {
-                    logical_line: logical_line_idx,
-                    start_char: 0,
-                    end_char: 0,
-                    text: String::new(),
-                    width: 0.0,
-                });
-                continue;
-            }
+        // Paragraphs left pending (large documents and pastes) wrap on demand
+        self.realize_rows(self.render_rows());
+        self.realize_cursor_row();
+    }
 
-            if !wrap_enabled || available_width <= 0.0 {
-                // No wrapping - entire logical line is one visual line
-                let width = crate::text_measure::measure_text(line_text, font_size).width;
-                self.visual_lines.push(VisualLine {
-                    logical_line: logical_line_idx,
-                    start_char: 0,
-                    end_char: line_text.chars().count(),
-                    text: line_text.clone(),
-                    width,
-                });
-                continue;
-            }
+    /// Drop the computed visual lines so the next layout re-wraps everything
+    fn invalidate_visual_lines(&mut self) {
+        self.visual_lines.clear();
+        self.wrap_params = None;
+    }
 
-            // Wrapping enabled - split line into visual lines
-            // Use word-based wrapping: try to break at word boundaries
-            let chars: Vec<char> = line_text.chars().collect();
-            let char_count = chars.len();
-            let mut start_char = 0;
-
-            while start_char < char_count {
-                // Find how many characters fit in available_width
-                let mut end_char = start_char;
-                let mut last_word_break = start_char;
-                let mut current_width = 0.0;
-
-                // Scan forward to find break point
-                while end_char < char_count {
-                    // Check width up to this character (inclusive)
-                    let test_end = end_char + 1;
-                    let test_text: String = chars[start_char..test_end].iter().collect();
-                    let test_width = crate::text_measure::measure_text(&test_text, font_size).width;
-
-                    if test_width > available_width && end_char > start_char {
-                        // This character would exceed width, break here
-                        break;
-                    }
+    /// Re-wrap after `old_lines` logical lines starting at `first_line` were
+    /// replaced by `new_lines` lines
+    fn rewrap_lines(&mut self, first_line: usize, old_lines: usize, new_lines: usize) {
+        if self.wrap_params.is_none() {
+            // Nothing computed yet; the next layout wraps everything
+            return;
+        }
 
-                    current_width = test_width;
-                    end_char = test_end;
+        let lines = first_line..first_line + new_lines;
+        let rows: Vec<Vec<VisualLine>> = if new_lines > EAGER_WRAP_LINES {
+            // Large pastes wrap lazily as they scroll into view
+            lines
+                .map(|line| vec![VisualLine::pending(self.line_len(line))])
+                .collect()
+        } else {
+            lines.map(|line| self.wrap_line(line)).collect()
+        };
+        // Later paragraphs keep their rows; the index renumbers them for free
+        self.visual_lines
+            .splice_lines(first_line..first_line + old_lines, rows);
+    }
 
-                    // Track word boundary (space character)
-                    if end_char < char_count && chars[end_char - 1].is_whitespace() {
-                        last_word_break = end_char;
-                    }
-                }
+    /// Wrap the pending paragraphs with rows in `rows`
+    fn realize_rows(&mut self, rows: std::ops::Range<usize>) {
+        let pending: Vec<usize> = self
+            .visual_lines
+            .rows(rows)
+            .filter(|(_, vl)| vl.pending)
+            .map(|(line, _)| line)
+            .collect();
+        for line in pending {
+            let wrapped = self.wrap_line(line);
+            self.visual_lines.set_line(line, wrapped);
+        }
+    }
 
-                // If we found a word break, use it (unless it's at the start)
-                if last_word_break > start_char && end_char < char_count {
-                    end_char = last_word_break;
-                    let text: String = chars[start_char..end_char].iter().collect();
-                    current_width = crate::text_measure::measure_text(&text, font_size).width;
-                }
+    /// Wrap the paragraph holding the cursor
+    fn realize_cursor_row(&mut self) {
+        let idx = self.visual_line_for_cursor();
+        self.realize_rows(idx..idx + 1);
+    }
 
-                // Create visual line
-                let text: String = chars[start_char..end_char].iter().collect();
-                self.visual_lines.push(VisualLine {
-                    logical_line: logical_line_idx,
-                    start_char,
-                    end_char,
-                    text,
-                    width: current_width,
-                });
-
-                start_char = end_char;
-            }
+    /// Visual rows currently inside the viewport
+    ///
+    /// Before the first layout every logical line counts as one row.
+    fn visible_rows(&self) -> std::ops::Range<usize> {
+        let rows = self.visual_line_count();
+        if self.line_height <= 0.0 {
+            return 0..rows;
+        }
+        let first = (self.scroll_offset() / self.line_height).floor().max(0.0) as usize;
+        let count = (self.viewport_height / self.line_height).ceil().max(1.0) as usize;
+        first.min(rows)..(first + count + 1).min(rows)
+    }
 
-            // If line ended exactly at boundary, we should have at least one visual line
-            if self.visual_lines.last().map(|vl| vl.logical_line) != Some(logical_line_idx) {
-                self.visual_lines.push(VisualLine {
-                    logical_line: logical_line_idx,
-                    start_char: char_count,
-                    end_char: char_count,
-                    text: String::new(),
-                    width: 0.0,
-                });
-            }
+    /// Visual rows to render: the viewport plus one viewport of overscan either side
+    pub(crate) fn render_rows(&self) -> std::ops::Range<usize> {
+        let rows = self.visual_line_count();
+        if rows <= VIRTUALIZE_ROWS {
+            return 0..rows;
         }
+        let visible = self.visible_rows();
+        let overscan = visible.len();
+        visible.start.saturating_sub(overscan)..(visible.end + overscan).min(rows)
+    }
+
+    /// Whether scrolling brought rows outside the last rendered window into view
+    pub(crate) fn needs_render_window_update(&self) -> bool {
+        let visible = self.visible_rows();
+        visible.start < self.rendered_rows.start || visible.end > self.rendered_rows.end
+    }
+
+    /// Split logical line `line` into visual rows
+    fn wrap_line(&self, line: usize) -> Vec<VisualLine> {
+        let font_size = self.font_size;
+        let available_width = self.available_width;
+        let text = self.buffer.line(line);
+        let measure = |chars: &[char]| {
+            crate::text_measure::measure_text(&String::from_iter(chars), font_size)
+        };
+
+        if text.is_empty() {
+            // Empty line still takes up one visual line
+            return vec![VisualLine {
+                start_char: 0,
+                end_char: 0,
+                text,
+                width: 0.0,
+                pending: false,
+            }];
+        }
+
+        if !self.wrap_enabled || available_width <= 0.0 {
+            // No wrapping - entire logical line is one visual line
+            let width = crate::text_measure::measure_text(&text, font_size).width;
+            return vec![VisualLine {
+                start_char: 0,
+                end_char: text.chars().count(),
+                text,
+                width,
+                pending: false,
+            }];
+        }
+
+        // Wrapping enabled - split line into visual lines, breaking after
+        // whitespace where possible
+        let chars: Vec<char> = text.chars().collect();
+        wrap_ranges(&text, font_size, available_width)
+            .into_iter()
+            .map(|range| VisualLine {
+                start_char: range.start,
+                end_char: range.end,
+                text: chars[range.clone()].iter().collect(),
+                width: measure(&chars[range]).width,
+                pending: false,
+            })
+            .collect()
     }
 
     /// Calculate cursor position from a known visual line index and x coordinate
@@ -1048,14 +1567,12 @@ impl TextAreaState {
     /// This is used when a line element's click handler has already determined
     /// which visual line was clicked. The x coordinate is relative to the line element.
     pub fn cursor_position_from_visual_line(&self, visual_line_idx: usize, x: f32) -> TextPosition {
-        if visual_line_idx >= self.visual_lines.len() {
+        let Some((line, _)) = self.visual_lines.locate(visual_line_idx) else {
             return TextPosition::default();
-        }
-
-        let vl = &self.visual_lines[visual_line_idx];
+        };
         let column = self.char_position_in_visual_line(visual_line_idx, x, self.font_size);
 
-        TextPosition::new(vl.logical_line, column)
+        TextPosition::new(line, column)
     }
 
     /// Get the visual line index for a given cursor position
@@ -1065,54 +1582,46 @@ impl TextAreaState {
         let cursor_line = self.cursor.line;
         let cursor_col = self.cursor.column;
 
-        for (idx, vl) in self.visual_lines.iter().enumerate() {
-            if vl.logical_line == cursor_line {
-                // Check if cursor is within this visual line's range
-                if cursor_col >= vl.start_char && cursor_col <= vl.end_char {
-                    return idx;
-                }
-                // If cursor is at the end of a wrapped line, it might be at start of next visual line
-                if cursor_col == vl.end_char {
-                    // Check if there's another visual line for same logical line
-                    if idx + 1 < self.visual_lines.len()
-                        && self.visual_lines[idx + 1].logical_line == cursor_line
-                        && self.visual_lines[idx + 1].start_char == cursor_col
-                    {
-                        return idx + 1;
-                    }
-                    return idx;
-                }
-            }
-        }
-
-        // Fallback: return last visual line
-        self.visual_lines.len().saturating_sub(1)
+        // Rows are grouped by logical line, so jump straight to the paragraph
+        let first = self.visual_lines.first_row(cursor_line);
+        self.visual_lines
+            .line(cursor_line)
+            .iter()
+            .position(|vl| cursor_col >= vl.start_char && cursor_col <= vl.end_char)
+            .map(|offset| first + offset)
+            // Fallback: return last visual line
+            .unwrap_or(self.visual_lines.len_rows().saturating_sub(1))
     }
 
     /// Get cursor X position within its visual line
     ///
     /// Returns the pixel offset from the left edge of the visual line to the cursor.
     pub fn cursor_x_in_visual_line(&self) -> f32 {
-        let cursor_line = self.cursor.line;
         let cursor_col = self.cursor.column;
 
         // Find the visual line containing the cursor
-        for vl in &self.visual_lines {
-            if vl.logical_line == cursor_line
-                && cursor_col >= vl.start_char
-                && cursor_col <= vl.end_char
-            {
-                // Measure text from start of visual line to cursor
-                let local_col = cursor_col - vl.start_char;
-                if local_col == 0 {
-                    return 0.0;
-                }
-                let text_before: String = vl.text.chars().take(local_col).collect();
-                return crate::text_measure::measure_text(&text_before, self.font_size).width;
-            }
+        let Some((line, vl)) = self.visual_lines.row(self.visual_line_for_cursor()) else {
+            return 0.0;
+        };
+        if line != self.cursor.line || cursor_col < vl.start_char || cursor_col > vl.end_char {
+            return 0.0;
+        }
+
+        // Measure text from start of visual line to cursor
+        let local_col = cursor_col - vl.start_char;
+        let text = if vl.pending {
+            self.buffer.line(line)
+        } else {
+            vl.text.clone()
+        };
+        if let Some(map) = self.caret_map(&text) {
+            return map.caret_x(local_col);
         }
-
-        0.0
+        if local_col == 0 {
+            return 0.0;
+        }
+        let text_before: String = text.chars().take(local_col).collect();
+        crate::text_measure::measure_text(&text_before, self.font_size).width
     }
 
     /// Get cursor position (x, visual_y) using computed visual lines
@@ -1129,9 +1638,9 @@ impl TextAreaState {
     pub fn visual_line_count(&self) -> usize {
         if self.visual_lines.is_empty() {
             // Fallback when visual lines not computed yet
-            self.lines.len()
+            self.line_count()
         } else {
-            self.visual_lines.len()
+            self.visual_lines.len_rows()
         }
     }
 
@@ -1140,73 +1649,19 @@ impl TextAreaState {
         self.visual_line_count() as f32 * self.line_height
     }
 
-    /// Calculate cursor Y position accounting for text wrapping
-    ///
-    /// Returns (cursor_y, content_height) where cursor_y is the visual Y position
-    /// of the cursor line, and content_height is the total visual height.
-    fn calculate_wrapped_positions(
-        &self,
-        font_size: f32,
-        line_height: f32,
-        available_width: f32,
-        wrap_enabled: bool,
-    ) -> (f32, f32) {
-        if !wrap_enabled || available_width <= 0.0 {
-            // No wrapping - simple calculation
-            let cursor_y = self.cursor.line as f32 * line_height;
-            let content_height = self.lines.len() as f32 * line_height;
-            return (cursor_y, content_height);
-        }
-
-        // With wrapping, count visual lines up to cursor line
-        let mut visual_line_count = 0usize;
-        let mut cursor_visual_y = 0.0f32;
-
-        for (idx, line) in self.lines.iter().enumerate() {
-            let line_visual_count = Self::visual_lines_for_text(line, font_size, available_width);
-
-            if idx < self.cursor.line {
-                visual_line_count += line_visual_count;
-            } else if idx == self.cursor.line {
-                cursor_visual_y = visual_line_count as f32 * line_height;
-                // For the cursor's line, we need to account for wrapped position
-                // within that line based on cursor column position
-                if line_visual_count > 1 && !line.is_empty() {
-                    let text_before: String = line.chars().take(self.cursor.column).collect();
-                    let prefix_width =
-                        crate::text_measure::measure_text(&text_before, font_size).width;
-                    let lines_into = (prefix_width / available_width).floor() as usize;
-                    cursor_visual_y += lines_into as f32 * line_height;
-                }
-                visual_line_count += line_visual_count;
-            } else {
-                visual_line_count += line_visual_count;
-            }
-        }
-
-        let content_height = visual_line_count as f32 * line_height;
-        (cursor_visual_y, content_height)
-    }
-
     /// Ensure the cursor is visible by adjusting scroll offset if needed
     ///
     /// This should be called after any cursor movement to auto-scroll
     /// when the cursor moves outside the visible area.
     /// Uses cached wrap settings from the state.
     pub fn ensure_cursor_visible(&mut self, line_height: f32, viewport_height: f32) {
-        // Use visual lines for accurate cursor position if available
-        let (cursor_y, content_height) = if !self.visual_lines.is_empty() {
-            let (_, cursor_y) = self.cursor_position_from_visual_lines();
-            let content_height = self.content_height_from_visual_lines();
-            (cursor_y, content_height)
-        } else {
-            self.calculate_wrapped_positions(
-                self.font_size,
-                line_height,
-                self.available_width,
-                self.wrap_enabled,
-            )
-        };
+        // Wrap lazily before the first layout, so only the cursor's paragraph is measured
+        if self.visual_lines.is_empty() {
+            self.compute_visual_lines();
+        }
+        self.realize_cursor_row();
+        let (_, cursor_y) = self.cursor_position_from_visual_lines();
+        let content_height = self.content_height_from_visual_lines();
         let cursor_bottom = cursor_y + line_height;
 
         // Get current scroll offset from physics (offset_y is negative when scrolled down)
@@ -1242,10 +1697,6 @@ impl TextAreaState {
     /// Takes x/y coordinates relative to the text content area (after padding).
     /// Returns the TextPosition (line, column) for the clicked location.
     pub fn cursor_position_from_xy(&self, x: f32, y: f32) -> TextPosition {
-        if self.lines.is_empty() {
-            return TextPosition::default();
-        }
-
         let line_height = self.line_height;
         let font_size = self.font_size;
         let scroll_offset = self.scroll_offset();
@@ -1259,17 +1710,21 @@ impl TextAreaState {
         // Use computed visual lines if available for accurate positioning
         if !self.visual_lines.is_empty() {
             // Clamp to valid range
-            let visual_line_idx = visual_line_idx.min(self.visual_lines.len().saturating_sub(1));
-            let vl = &self.visual_lines[visual_line_idx];
+            let visual_line_idx =
+                visual_line_idx.min(self.visual_lines.len_rows().saturating_sub(1));
+            let line = self
+                .visual_lines
+                .locate(visual_line_idx)
+                .map_or(0, |(line, _)| line);
 
             // Find character position within this visual line
             let column = self.char_position_in_visual_line(visual_line_idx, x, font_size);
 
-            return TextPosition::new(vl.logical_line, column);
+            return TextPosition::new(line, column);
         }
 
         // Fallback: no visual lines computed
-        let logical_line = visual_line_idx.min(self.lines.len().saturating_sub(1));
+        let logical_line = visual_line_idx.min(self.line_count() - 1);
         let column = self.char_position_from_x(logical_line, x, font_size);
         TextPosition::new(logical_line, column)
     }
@@ -1281,15 +1736,17 @@ impl TextAreaState {
         x: f32,
         font_size: f32,
     ) -> usize {
-        if visual_line_idx >= self.visual_lines.len() {
+        let Some((_, vl)) = self.visual_lines.row(visual_line_idx) else {
             return 0;
-        }
-
-        let vl = &self.visual_lines[visual_line_idx];
+        };
         if vl.text.is_empty() {
             return vl.start_char;
         }
 
+        if let Some(map) = self.caret_map(&vl.text) {
+            return vl.start_char + map.index_at_x(x);
+        }
+
         let char_count = vl.text.chars().count();
         let mut best_pos = 0;
         let mut min_dist = f32::MAX;
@@ -1312,15 +1769,17 @@ impl TextAreaState {
 
     /// Find character position from x coordinate within a line
     fn char_position_from_x(&self, line_index: usize, x: f32, font_size: f32) -> usize {
-        if line_index >= self.lines.len() {
+        let Some(line) = self.get_line(line_index) else {
             return 0;
-        }
-
-        let line = &self.lines[line_index];
+        };
         if line.is_empty() {
             return 0;
         }
 
+        if let Some(map) = self.caret_map(&line) {
+            return map.index_at_x(x);
+        }
+
         let char_count = line.chars().count();
         let mut best_pos = 0;
         let mut min_dist = f32::MAX;
@@ -1352,6 +1811,35 @@ fn char_to_byte_pos(line: &str, char_pos: usize) -> usize {
 /// Shared text area state handle
 pub type SharedTextAreaState = Arc<Mutex<TextAreaState>>;
 
+impl ImeTarget for TextAreaState {
+    fn ime_start(&mut self) {
+        // The composition replaces the selection, like typing would
+        self.edit(EditKind::Typing, |s| {
+            s.delete_selection();
+        });
+        self.composition = Some(Composition::default());
+    }
+
+    fn ime_update(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        if self.composition.is_none() {
+            self.ime_start();
+        }
+        self.composition = Some(Composition {
+            text: text.to_string(),
+            cursor,
+        });
+    }
+
+    fn ime_commit(&mut self, text: &str) {
+        self.composition = None;
+        self.insert(text);
+    }
+
+    fn ime_cancel(&mut self) {
+        self.composition = None;
+    }
+}
+
 /// Create a shared text area state
 pub fn text_area_state() -> SharedTextAreaState {
     Arc::new(Mutex::new(TextAreaState::new()))
@@ -1477,6 +1965,7 @@ impl TextArea {
 
                     // Recompute visual lines for proper cursor tracking with wrapped text
                     data_guard.compute_visual_lines();
+                    data_guard.rendered_rows = data_guard.render_rows();
 
                     // Determine colors based on visual state
                     let (bg, border) = match visual {
@@ -1544,12 +2033,20 @@ impl TextArea {
         let data_for_click = Arc::clone(&data);
         let data_for_text = Arc::clone(&data);
         let data_for_key = Arc::clone(&data);
+        let data_for_drag = Arc::clone(&data);
+        let data_for_drop = Arc::clone(&data);
+        let data_for_up = Arc::clone(&data);
         let config_for_click = Arc::clone(&config);
         let shared_for_click = Arc::clone(&shared_state);
         let shared_for_text = Arc::clone(&shared_state);
         let shared_for_key = Arc::clone(&shared_state);
+        let shared_for_drop = Arc::clone(&shared_state);
+        let shared_for_up = Arc::clone(&shared_state);
+        let shared_for_ime = Arc::clone(&shared_state);
+        let data_for_focus = Arc::clone(&data);
+        let shared_for_focus = Arc::clone(&shared_state);
 
-        Stateful::with_shared_state(shared_state)
+        let inner = Stateful::with_shared_state(shared_state)
             // Handle mouse down to focus and position cursor
             .on_mouse_down(move |ctx| {
                 // First, forcibly blur any previously focused text input/area
@@ -1617,6 +2114,18 @@ impl TextArea {
                         d.compute_visual_lines();
                     }
 
+                    // Window position of the unscrolled text content, for placing the
+                    // IME candidate window. Line elements report coordinates relative
+                    // to their own row.
+                    let row_top = d
+                        .clicked_visual_line
+                        .map_or(0.0, |row| row as f32 * line_height);
+                    let scroll_offset = d.scroll_offset();
+                    d.ime_origin = Some((
+                        ctx.mouse_x - click_x,
+                        ctx.mouse_y - click_y - row_top + scroll_offset,
+                    ));
+
                     // Position cursor at click location
                     // Use clicked_visual_line if set by a line element's click handler,
                     // otherwise fall back to y-coordinate calculation
@@ -1631,17 +2140,75 @@ impl TextArea {
                         let text_y = click_y.max(0.0);
                         d.cursor_position_from_xy(text_x, text_y)
                     };
-                    d.cursor = new_pos;
-                    d.selection_start = None; // Clear any selection
+
+                    // Double-click selects a word, triple-click a line. A press
+                    // inside the selection may start dragging it elsewhere.
+                    let clicks = d
+                        .click_counter
+                        .register(ctx.mouse_x, ctx.mouse_y, elapsed_ms());
+                    d.break_undo_group();
+                    d.drag_move = None;
+                    match clicks {
+                        2 => d.select_word_at(new_pos),
+                        3 => d.select_line(new_pos.line),
+                        _ if d.selection_contains(new_pos) => {
+                            d.drag_move = Some(DragMove {
+                                press: new_pos,
+                                drop: None,
+                            });
+                        }
+                        _ => {
+                            d.cursor = new_pos;
+                            d.selection_start = None; // Clear any selection
+                        }
+                    }
                     d.reset_cursor_blink();
 
-                    true // needs refresh
+                    // Enable IME at the new caret
+                    Some(d.ime_caret_rect())
                 }; // Lock released here
 
                 // Trigger incremental refresh AFTER releasing the data lock
-                if needs_refresh {
+                if let Some(caret) = needs_refresh {
                     refresh_stateful(&shared_for_click);
+                    notify_ime_caret(caret);
+                }
+            })
+            // Handle focus from the event router (keyboard navigation,
+            // ElementHandle::focus/type_text) - keeps the cursor where it was
+            .on_event(event_types::FOCUS, move |_ctx| {
+                let focusable = data_for_focus
+                    .lock()
+                    .is_ok_and(|d| !d.disabled && !d.visual.is_focused());
+                if !focusable {
+                    return;
                 }
+                // Blur any other text input/area before taking our own lock
+                set_focused_text_area(&data_for_focus);
+
+                let caret = {
+                    let mut d = match data_for_focus.lock() {
+                        Ok(d) => d,
+                        Err(_) => return,
+                    };
+
+                    {
+                        let mut shared = shared_for_focus.lock().unwrap();
+                        if let Some(new_state) = shared.state.on_event(event_types::FOCUS) {
+                            shared.state = new_state;
+                            shared.needs_visual_update = true;
+                        }
+                    }
+
+                    d.visual = TextFieldState::Focused;
+                    increment_focus_count();
+                    request_continuous_redraw_pub();
+                    d.reset_cursor_blink();
+                    d.ime_caret_rect()
+                }; // Lock released here
+
+                refresh_stateful(&shared_for_focus);
+                notify_ime_caret(caret);
             })
             // Handle text input
             .on_event(event_types::TEXT_INPUT, move |ctx| {
@@ -1651,12 +2218,13 @@ impl TextArea {
                         Err(_) => return,
                     };
 
-                    if d.disabled || !d.visual.is_focused() {
+                    // Keystrokes belong to the IME while composing
+                    if d.disabled || !d.visual.is_focused() || d.is_composing() {
                         return;
                     }
 
                     if let Some(c) = ctx.key_char {
-                        d.insert(&c.to_string());
+                        d.type_char(c);
                         d.reset_cursor_blink();
                         // Recompute visual lines after text change
                         d.compute_visual_lines();
@@ -1689,14 +2257,39 @@ impl TextArea {
                         Err(_) => return,
                     };
 
-                    if d.disabled || !d.visual.is_focused() {
+                    if d.disabled || !d.visual.is_focused() || d.is_composing() {
                         return;
                     }
 
                     let mut cursor_changed = true;
                     let mut should_blur = false;
                     let mut text_changed = false;
+                    // Ctrl (or Alt/Option on macOS) acts on whole words
+                    let by_word = ctx.ctrl || ctx.alt;
+                    let shortcut = ctx.ctrl || ctx.meta;
                     match ctx.key_code {
+                        90 if shortcut => {
+                            // Ctrl/Cmd+Z undo, with Shift redo
+                            text_changed = if ctx.shift { d.redo() } else { d.undo() };
+                        }
+                        89 if shortcut => {
+                            // Ctrl/Cmd+Y redo
+                            text_changed = d.redo();
+                        }
+                        65 if shortcut => {
+                            // Ctrl/Cmd+A
+                            d.select_all();
+                        }
+                        8 if by_word => {
+                            d.delete_word_backward();
+                            text_changed = true;
+                        }
+                        127 if by_word => {
+                            d.delete_word_forward();
+                            text_changed = true;
+                        }
+                        37 if by_word => d.move_word_left(ctx.shift),
+                        39 if by_word => d.move_word_right(ctx.shift),
                         8 => {
                             // Backspace
                             d.delete_backward();
@@ -1716,11 +2309,11 @@ impl TextArea {
                         }
                         37 => {
                             // Left arrow
-                            d.move_left(ctx.shift);
+                            d.move_visual(false, ctx.shift);
                         }
                         39 => {
                             // Right arrow
-                            d.move_right(ctx.shift);
+                            d.move_visual(true, ctx.shift);
                         }
                         38 => {
                             // Up arrow
@@ -1751,6 +2344,9 @@ impl TextArea {
                     // Recompute visual lines after text changes
                     if text_changed {
                         d.compute_visual_lines();
+                    } else if cursor_changed {
+                        // Moving the caret ends the current undo group
+                        d.break_undo_group();
                     }
 
                     if cursor_changed && !should_blur {
@@ -1784,9 +2380,140 @@ impl TextArea {
                     crate::stateful::check_stateful_deps(&[signal_id]);
                 }
             })
+            // Track the drop position while dragging a selection
+            .on_drag(move |ctx| {
+                let Ok(mut d) = data_for_drag.lock() else {
+                    return;
+                };
+                let (Some(_), Some((origin_x, origin_y))) = (d.drag_move, d.ime_origin) else {
+                    return;
+                };
+                let drop = d.cursor_position_from_xy(
+                    (ctx.mouse_x - origin_x).max(0.0),
+                    (ctx.mouse_y - origin_y).max(0.0),
+                );
+                if let Some(drag) = d.drag_move.as_mut() {
+                    drag.drop = Some(drop);
+                }
+            })
+            // Drop a dragged selection at the pointer
+            .on_drag_end(move |_ctx| {
+                let change_signal = {
+                    let mut d = match data_for_drop.lock() {
+                        Ok(d) => d,
+                        Err(_) => return,
+                    };
+                    let Some(drop) = d.drag_move.take().and_then(|drag| drag.drop) else {
+                        return;
+                    };
+                    if !d.move_selection_to(drop) {
+                        return;
+                    }
+                    d.compute_visual_lines();
+                    d.reset_cursor_blink();
+                    d.change_signal_id
+                }; // Lock released here
+
+                refresh_stateful(&shared_for_drop);
+                if let Some(signal_id) = change_signal {
+                    crate::stateful::check_stateful_deps(&[signal_id]);
+                }
+            })
+            // A press inside the selection without dragging places the caret
+            .on_mouse_up(move |_ctx| {
+                {
+                    let mut d = match data_for_up.lock() {
+                        Ok(d) => d,
+                        Err(_) => return,
+                    };
+                    let Some(drag) = d.drag_move.take() else {
+                        return;
+                    };
+                    d.cursor = drag.press;
+                    d.selection_start = None;
+                    d.reset_cursor_blink();
+                } // Lock released here
+
+                refresh_stateful(&shared_for_up);
+            })
             // Set text cursor (I-beam) for text area
-            .cursor_text()
+            .cursor_text();
         // Note: Scroll events are handled by the scroll() widget inside build_content
+
+        Self::with_composition_handlers(inner, data, shared_for_ime)
+    }
+
+    /// Attach IME composition handlers (start/update/commit/cancel)
+    fn with_composition_handlers(
+        mut inner: Stateful<TextFieldState>,
+        data: SharedTextAreaState,
+        shared_state: SharedState<TextFieldState>,
+    ) -> Stateful<TextFieldState> {
+        use blinc_core::events::event_types;
+
+        for event_type in [
+            event_types::COMPOSITION_START,
+            event_types::COMPOSITION_UPDATE,
+            event_types::COMPOSITION_COMMIT,
+            event_types::COMPOSITION_CANCEL,
+        ] {
+            let data = Arc::clone(&data);
+            let shared = Arc::clone(&shared_state);
+            inner = inner.on_event(event_type, move |ctx| {
+                let (caret, change_signal) = {
+                    let mut d = match data.lock() {
+                        Ok(d) => d,
+                        Err(_) => return,
+                    };
+
+                    if d.disabled || !d.visual.is_focused() {
+                        return;
+                    }
+
+                    let text = ctx.composition_text.as_deref().unwrap_or("");
+                    let mut text_changed = false;
+                    match ctx.event_type {
+                        event_types::COMPOSITION_START => {
+                            text_changed = d.selection_start.is_some();
+                            d.ime_start();
+                        }
+                        event_types::COMPOSITION_UPDATE => {
+                            d.ime_update(text, ctx.composition_cursor)
+                        }
+                        event_types::COMPOSITION_COMMIT => {
+                            text_changed = true;
+                            d.ime_commit(text);
+                        }
+                        _ => d.ime_cancel(),
+                    }
+
+                    if text_changed {
+                        d.compute_visual_lines();
+                    }
+                    d.reset_cursor_blink();
+                    let line_height = d.line_height;
+                    let viewport_height = d.viewport_height;
+                    d.ensure_cursor_visible(line_height, viewport_height);
+
+                    let change_signal = if text_changed {
+                        d.change_signal_id
+                    } else {
+                        None
+                    };
+                    (d.ime_caret_rect(), change_signal)
+                }; // Lock released here
+
+                refresh_stateful(&shared);
+                if caret.is_some() {
+                    notify_ime_caret(caret);
+                }
+
+                if let Some(signal_id) = change_signal {
+                    crate::stateful::check_stateful_deps(&[signal_id]);
+                }
+            });
+        }
+        inner
     }
 
     /// Build the content div based on current visual state and data
@@ -1801,7 +2528,9 @@ impl TextArea {
         // Note: Visual styling (bg, border, rounded) is now applied directly to the
         // container in the callback via set_* methods, not here.
 
-        let text_color = if data.is_empty() {
+        // While composing, the preedit replaces the placeholder
+        let show_placeholder = data.is_empty() && !data.is_composing();
+        let text_color = if show_placeholder {
             config.placeholder_color
         } else if data.disabled {
             Color::rgba(0.4, 0.4, 0.4, 1.0)
@@ -1823,14 +2552,14 @@ impl TextArea {
 
         // Use visual lines for cursor positioning (computed in callback before build_content)
         // This provides accurate cursor tracking for wrapped text
-        let (cursor_x, cursor_visual_y) = if !data.visual_lines.is_empty() {
+        let (mut cursor_x, cursor_visual_y) = if !data.visual_lines.is_empty() {
             data.cursor_position_from_visual_lines()
         } else {
             // Fallback: simple calculation when visual lines not yet computed
             let cursor_line = data.cursor.line;
             let cursor_col = data.cursor.column;
-            let cursor_x = if cursor_col > 0 && cursor_line < data.lines.len() {
-                let line_text = &data.lines[cursor_line];
+            let cursor_x = if cursor_col > 0 && cursor_line < data.line_count() {
+                let line_text = data.buffer.line_slice(cursor_line);
                 let text_before: String = line_text.chars().take(cursor_col).collect();
                 crate::text_measure::measure_text(&text_before, config.font_size).width
             } else {
@@ -1840,6 +2569,44 @@ impl TextArea {
             (cursor_x, cursor_y)
         };
 
+        // While composing, the row holding the cursor shows the preedit spliced in
+        // at `column`: (row, column within the row, x of the splice point)
+        let composition_row: Option<(usize, usize, f32)> = data.composition.as_ref().map(|_| {
+            let (row, row_text, column) = if config.wrap && !data.visual_lines.is_empty() {
+                let row = data.visual_line_for_cursor();
+                let vl = data.visual_lines.row(row).map(|(_, vl)| vl);
+                let start = vl.map_or(0, |vl| vl.start_char);
+                (
+                    row,
+                    vl.map(|vl| vl.text.clone()).unwrap_or_default(),
+                    data.cursor.column.saturating_sub(start),
+                )
+            } else {
+                let line = data.get_line(data.cursor.line).unwrap_or_default();
+                (data.cursor.line, line, data.cursor.column)
+            };
+            let before: String = row_text.chars().take(column).collect();
+            let x = crate::text_measure::measure_text(&before, config.font_size).width;
+            (row, column, x)
+        });
+        let splice_preedit = |row: usize, row_text: &str| -> Option<String> {
+            let (comp_row, column, _) = composition_row?;
+            let comp = data.composition.as_ref()?;
+            if comp_row != row {
+                return None;
+            }
+            let mut composed = row_text.to_string();
+            let column = column.min(composed.chars().count());
+            composed.insert_str(char_to_byte_pos(&composed, column), &comp.text);
+            Some(composed)
+        };
+
+        // The caret sits inside the preedit while composing
+        if let Some(comp) = &data.composition {
+            let before: String = comp.text.chars().take(comp.caret()).collect();
+            cursor_x += crate::text_measure::measure_text(&before, config.font_size).width;
+        }
+
         // Clone the cursor state for the canvas callback
         let cursor_state_for_canvas = Arc::clone(&data.cursor_state);
 
@@ -1905,6 +2672,9 @@ impl TextArea {
             None
         };
 
+        // Large documents only render the rows around the viewport
+        let rows = data.render_rows();
+
         // Build text content - left-aligned column of text lines
         // Use relative positioning to allow cursor absolute positioning within
         // Note: Don't use w_full() here - each line has explicit width, and w_full would
@@ -1917,7 +2687,49 @@ impl TextArea {
             .relative()
             .overflow_visible();
 
-        if data.is_empty() {
+        // Selection highlights go first so they draw behind the text; they are
+        // absolute so they don't take part in the column layout
+        if !data.is_empty() && data.selection_start.is_some() {
+            // The naturally wrapped fallback has no per-row geometry yet
+            let segments =
+                data.visual_lines
+                    .rows(rows.clone())
+                    .zip(rows.clone())
+                    .map(|((line, vl), row)| {
+                        (row, (line, vl.start_char, vl.end_char, vl.text.as_str()))
+                    });
+
+            for (row, (line, start_char, end_char, line_text)) in segments {
+                for (left, right) in data.selection_spans_for(line, start_char, end_char, line_text)
+                {
+                    text_content = text_content.child(
+                        div()
+                            .absolute()
+                            .left(left)
+                            .top(row as f32 * line_height)
+                            .w(right - left)
+                            .h(line_height)
+                            .bg(config.selection_color),
+                    );
+                }
+            }
+        }
+
+        // Underline the preedit text
+        if let (Some((row, _, left)), Some(comp)) = (composition_row, &data.composition) {
+            let width = crate::text_measure::measure_text(&comp.text, config.font_size).width;
+            text_content = text_content.child(
+                div()
+                    .absolute()
+                    .left(left)
+                    .top(row as f32 * line_height + (line_height + config.font_size) / 2.0)
+                    .w(width)
+                    .h(1.0)
+                    .bg(text_color),
+            );
+        }
+
+        if show_placeholder {
             // Use state's placeholder if available, otherwise fall back to config
             let placeholder = if !data.placeholder.is_empty() {
                 &data.placeholder
@@ -1940,12 +2752,20 @@ impl TextArea {
                             .no_wrap(),
                     ),
             );
-        } else if config.wrap && !data.visual_lines.is_empty() {
-            // Wrapping mode with computed visual lines
+        } else if !data.visual_lines.is_empty() {
+            // Computed visual lines (one per logical line when not wrapping)
             // Render each visual line segment for precise cursor alignment
             // Each line has a click handler that stores its visual line index
-            for (visual_line_idx, vl) in data.visual_lines.iter().enumerate() {
-                let line_text = if vl.text.is_empty() {
+            // Rows outside the render window are replaced by spacers
+            if rows.start > 0 {
+                text_content = text_content.child(div().h(rows.start as f32 * line_height));
+            }
+            for ((_, vl), visual_line_idx) in data.visual_lines.rows(rows.clone()).zip(rows.clone())
+            {
+                let composed = splice_preedit(visual_line_idx, &vl.text);
+                let line_text = if let Some(composed) = composed.as_deref() {
+                    composed
+                } else if vl.text.is_empty() {
                     " "
                 } else {
                     vl.text.as_str()
@@ -1953,10 +2773,14 @@ impl TextArea {
                 let state_for_line = Arc::clone(&shared_state);
 
                 // Each visual line has fixed height and a click handler
-                text_content = text_content.child(
+                // Unwrapped lines keep their natural width so they scroll horizontally
+                let row = if config.wrap {
+                    div().w(text_area_width)
+                } else {
                     div()
-                        .h(line_height)
-                        .w(text_area_width)
+                };
+                text_content = text_content.child(
+                    row.h(line_height)
                         .flex_row()
                         .items_center()
                         .on_mouse_down(move |_ctx| {
@@ -1974,12 +2798,28 @@ impl TextArea {
                         ),
                 );
             }
+            let rows_after = data.visual_lines.len_rows() - rows.end;
+            if rows_after > 0 {
+                text_content = text_content.child(div().h(rows_after as f32 * line_height));
+            }
         } else if config.wrap {
             // Wrapping mode fallback: use natural text wrapping
             // This path is used when visual lines not yet computed
             // In this mode, line_idx corresponds to logical line (visual line not computed)
-            for (line_idx, line) in data.lines.iter().enumerate() {
-                let line_text = if line.is_empty() { " " } else { line.as_str() };
+            // Only the lines in the render window are built, counting one row per line
+            if rows.start > 0 {
+                text_content = text_content.child(div().h(rows.start as f32 * line_height));
+            }
+            for line_idx in rows.clone() {
+                let line = data.buffer.line(line_idx);
+                let composed = splice_preedit(line_idx, &line);
+                let line_text = if let Some(composed) = composed.as_deref() {
+                    composed
+                } else if line.is_empty() {
+                    " "
+                } else {
+                    line.as_str()
+                };
                 let state_for_line = Arc::clone(&shared_state);
 
                 // Don't use fixed height - let it grow based on wrapped content
@@ -2003,11 +2843,27 @@ impl TextArea {
                         ),
                 );
             }
+            let rows_after = data.line_count() - rows.end;
+            if rows_after > 0 {
+                text_content = text_content.child(div().h(rows_after as f32 * line_height));
+            }
         } else {
             // No-wrap mode: each line stays on single line, horizontally scrollable
             // In this mode, line_idx corresponds to both logical and visual line
-            for (line_idx, line) in data.lines.iter().enumerate() {
-                let line_text = if line.is_empty() { " " } else { line.as_str() };
+            // Only the lines in the render window are built
+            if rows.start > 0 {
+                text_content = text_content.child(div().h(rows.start as f32 * line_height));
+            }
+            for line_idx in rows.clone() {
+                let line = data.buffer.line(line_idx);
+                let composed = splice_preedit(line_idx, &line);
+                let line_text = if let Some(composed) = composed.as_deref() {
+                    composed
+                } else if line.is_empty() {
+                    " "
+                } else {
+                    line.as_str()
+                };
                 let state_for_line = Arc::clone(&shared_state);
 
                 text_content = text_content.child(
@@ -2030,6 +2886,10 @@ impl TextArea {
                         ),
                 );
             }
+            let rows_after = data.line_count() - rows.end;
+            if rows_after > 0 {
+                text_content = text_content.child(div().h(rows_after as f32 * line_height));
+            }
         }
 
         // Add cursor inside text_content so it scrolls with the text
@@ -2050,6 +2910,21 @@ impl TextArea {
             .direction(ScrollDirection::Vertical)
             .no_bounce()
             .flex_grow() // Take remaining space
+            .on_scroll(move |_| {
+                // Rebuild once rows outside the rendered window scroll into view
+                let stateful = {
+                    let Ok(d) = shared_state.lock() else {
+                        return;
+                    };
+                    if !d.needs_render_window_update() {
+                        return;
+                    }
+                    d.stateful_state.clone()
+                };
+                if let Some(stateful) = stateful {
+                    refresh_stateful(&stateful);
+                }
+            })
             .child(text_content);
 
         // Main content wrapper - uses explicit sizing to ensure proper intrinsic dimensions
@@ -2174,6 +3049,22 @@ impl TextArea {
         self
     }
 
+    /// Keep indentation on Enter, indenting one level after an opening bracket
+    pub fn auto_indent(self, enabled: bool) -> Self {
+        if let Ok(mut s) = self.state.lock() {
+            s.auto_indent = enabled;
+        }
+        self
+    }
+
+    /// Auto-close brackets and quotes, and wrap selections in them
+    pub fn bracket_pairing(self, enabled: bool) -> Self {
+        if let Ok(mut s) = self.state.lock() {
+            s.bracket_pairing = enabled;
+        }
+        self
+    }
+
     /// Enable or disable text wrapping
     ///
     /// When wrapping is enabled (default), long lines wrap to the next visual line.
@@ -2607,6 +3498,14 @@ impl ElementBuilder for TextArea {
         self.inner.layout_style()
     }
 
+    fn element_id(&self) -> Option<&str> {
+        self.inner.element_id()
+    }
+
+    fn element_classes(&self) -> &[String] {
+        self.inner.element_classes()
+    }
+
     fn layout_bounds_storage(&self) -> Option<crate::renderer::LayoutBoundsStorage> {
         // Return the layout bounds storage from the state so it gets updated after layout
         if let Ok(data) = self.state.lock() {
@@ -2734,4 +3633,200 @@ mod tests {
         assert_eq!(state.value(), "new");
         assert_eq!(state.line_count(), 1);
     }
+
+    #[test]
+    fn test_text_area_visual_arrow_keys_in_rtl_text() {
+        // "ab " then a Hebrew word, which is displayed reversed
+        let mut state = TextAreaState::with_value("ab אב");
+        state.cursor = TextPosition::new(0, 2);
+
+        // Right arrow from the space jumps to the visual right of the LTR
+        // run, which is logically the end of the line
+        state.move_visual(true, false);
+        assert_eq!(state.cursor.column, 5);
+        state.move_visual(true, false);
+        assert_eq!(state.cursor.column, 4);
+
+        // LTR lines keep logical movement
+        let mut state = TextAreaState::with_value("abc");
+        state.cursor = TextPosition::new(0, 0);
+        state.move_visual(true, false);
+        assert_eq!(state.cursor.column, 1);
+    }
+
+    #[test]
+    fn test_text_area_undo_redo() {
+        let mut state = TextAreaState::new();
+        for c in "hello".chars() {
+            state.type_char(c);
+        }
+        state.insert_newline();
+        state.break_undo_group();
+        state.insert("world");
+        assert_eq!(state.value(), "hello\nworld");
+
+        // Typing is grouped, newlines are their own step
+        assert!(state.undo());
+        assert_eq!(state.value(), "hello\n");
+        assert!(state.undo());
+        assert_eq!(state.value(), "hello");
+        assert!(state.undo());
+        assert_eq!(state.value(), "");
+        assert!(!state.undo());
+
+        assert!(state.redo());
+        assert!(state.redo());
+        assert_eq!(state.value(), "hello\n");
+        assert_eq!(state.cursor, TextPosition::new(1, 0));
+    }
+
+    #[test]
+    fn test_text_area_word_navigation() {
+        let mut state = TextAreaState::with_value("foo bar\nbaz");
+        state.cursor = TextPosition::new(0, 0);
+
+        state.move_word_right(false);
+        assert_eq!(state.cursor, TextPosition::new(0, 3));
+        state.move_word_right(false);
+        assert_eq!(state.cursor, TextPosition::new(0, 7));
+        // Crosses the line break
+        state.move_word_right(false);
+        assert_eq!(state.cursor, TextPosition::new(1, 0));
+        state.move_word_left(false);
+        assert_eq!(state.cursor, TextPosition::new(0, 7));
+
+        state.delete_word_backward();
+        assert_eq!(state.value(), "foo \nbaz");
+        state.cursor = TextPosition::new(0, 0);
+        state.delete_word_forward();
+        assert_eq!(state.value(), " \nbaz");
+    }
+
+    #[test]
+    fn test_text_area_multi_click_selection() {
+        let mut state = TextAreaState::with_value("one two\nthree");
+        state.select_word_at(TextPosition::new(0, 5));
+        assert_eq!(state.selected_text(), Some("two".to_string()));
+
+        state.select_line(0);
+        assert_eq!(state.selected_text(), Some("one two\n".to_string()));
+        state.select_line(1);
+        assert_eq!(state.selected_text(), Some("three".to_string()));
+    }
+
+    #[test]
+    fn test_text_area_auto_indent_and_pairing() {
+        let mut state = TextAreaState::with_value("  fn main() ");
+        state.auto_indent = true;
+        state.bracket_pairing = true;
+
+        state.type_char('{');
+        assert_eq!(state.value(), "  fn main() {}");
+        state.insert_newline();
+        assert_eq!(state.value(), "  fn main() {\n      \n  }");
+        assert_eq!(state.cursor, TextPosition::new(1, 6));
+
+        // Typing the closer steps over an existing one
+        state.type_char('(');
+        state.type_char(')');
+        assert_eq!(state.get_line(1).as_deref(), Some("      ()"));
+        assert_eq!(state.cursor.column, 8);
+
+        // Backspace inside an empty pair removes both
+        state.type_char('[');
+        state.delete_backward();
+        assert_eq!(state.get_line(1).as_deref(), Some("      ()"));
+
+        // Quotes wrap a selection
+        state.set_value("say hi");
+        state.select_word_at(TextPosition::new(0, 4));
+        state.type_char('"');
+        assert_eq!(state.value(), "say \"hi\"");
+        assert_eq!(state.selected_text(), Some("hi".to_string()));
+
+        // No pairing after a word character
+        state.set_value("don");
+        state.type_char('\'');
+        assert_eq!(state.value(), "don'");
+    }
+
+    #[test]
+    fn test_text_area_move_selection() {
+        let mut state = TextAreaState::with_value("one two\nthree");
+        state.select_word_at(TextPosition::new(0, 0));
+        assert!(!state.move_selection_to(TextPosition::new(0, 2)));
+
+        assert!(state.move_selection_to(TextPosition::new(1, 5)));
+        assert_eq!(state.value(), " two\nthreeone");
+        assert_eq!(state.selected_text(), Some("one".to_string()));
+
+        assert!(state.undo());
+        assert_eq!(state.value(), "one two\nthree");
+    }
+
+    fn wrapped_rows(state: &TextAreaState) -> Vec<(usize, usize, usize, String)> {
+        state
+            .visual_lines
+            .iter()
+            .map(|(line, vl)| (line, vl.start_char, vl.end_char, vl.text.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_incremental_rewrap_matches_full_wrap() {
+        let mut state = TextAreaState::with_value(
+            "the quick brown fox jumps over the lazy dog\nshort\n\nanother fairly long line",
+        );
+        state.font_size = 14.0;
+        state.available_width = 80.0;
+        state.wrap_enabled = true;
+        state.compute_visual_lines();
+
+        state.cursor = TextPosition::new(1, 5);
+        state.insert(" line that now wraps around\nand splits");
+        state.cursor = TextPosition::new(0, 3);
+        state.delete_forward();
+        state.cursor = TextPosition::new(3, 0);
+        state.delete_backward();
+        let incremental = wrapped_rows(&state);
+
+        state.invalidate_visual_lines();
+        state.compute_visual_lines();
+        assert_eq!(incremental, wrapped_rows(&state));
+    }
+
+    #[test]
+    fn test_large_document_wraps_lazily() {
+        let text: String = (0..50_000).map(|i| format!("line {i}\n")).collect();
+        let mut state = TextAreaState::with_value(text);
+        state.line_height = 20.0;
+        state.viewport_height = 200.0;
+        state.available_width = 300.0;
+        state.wrap_enabled = true;
+        state.cursor = TextPosition::new(0, 0);
+        // Before the first layout the fallback renders a window of logical lines
+        assert!(state.render_rows().len() < 50);
+        state.compute_visual_lines();
+
+        // Only the rows around the viewport are wrapped and rendered
+        let pending =
+            |state: &TextAreaState, row: usize| state.visual_lines.row(row).unwrap().1.pending;
+        assert_eq!(state.visual_lines.len_rows(), 50_001);
+        assert!((0..20).all(|row| !pending(&state, row)));
+        assert!(pending(&state, 10_000));
+        assert!(state.render_rows().len() < 50);
+
+        // Edits shift the rows after them without re-wrapping
+        state.insert("x\ny");
+        assert_eq!(state.visual_lines.len_rows(), 50_002);
+        assert_eq!(state.visual_lines.locate(50_001), Some((50_001, 0)));
+        assert_eq!(state.get_line(1).as_deref(), Some("yline 0"));
+        assert!(pending(&state, 10_000));
+
+        // Moving down into a pending paragraph wraps it
+        state.cursor = TextPosition::new(29_999, 0);
+        state.move_down(false);
+        assert_eq!(state.cursor, TextPosition::new(30_000, 0));
+        assert!(!pending(&state, state.visual_line_for_cursor()));
+    }
 }
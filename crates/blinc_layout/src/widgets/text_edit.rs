@@ -0,0 +1,509 @@
+//! Editing helpers shared by text widgets
+//!
+//! [`TextInput`] and [`TextArea`] build their editing behavior from the
+//! pieces in this module:
+//!
+//! - word boundaries for Ctrl/Alt+arrow movement, Ctrl+Backspace and
+//!   double-click selection
+//! - [`EditHistory`], an undo/redo stack that coalesces runs of typing
+//! - [`ClickCounter`], which turns presses into single/double/triple clicks
+//! - bracket pairing and indentation helpers
+//! - [`wrap_ranges`], the greedy word wrapping used for visual rows
+//!
+//! # Word boundaries
+//!
+//! Boundaries are the Unicode word boundaries (UAX #29) from
+//! `unicode-segmentation`, so `don't`, `example.com` and `1,000.5` are single
+//! words, each CJK ideograph is a word of its own, and combining marks and
+//! ZWJ emoji sequences never split. On top of that:
+//!
+//! - runs of whitespace are skipped as a unit
+//! - runs of ASCII punctuation (`...`, `->`) are treated as one word
+//!
+//! All positions are char offsets.
+//!
+//! [`TextInput`]: crate::widgets::TextInput
+//! [`TextArea`]: crate::widgets::TextArea
+
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Edits of the same kind within this window are undone together
+pub const UNDO_COALESCE_MS: u64 = 1000;
+
+/// Default number of undo steps kept
+pub const DEFAULT_UNDO_LIMIT: usize = 200;
+
+/// Maximum time between presses that count as one multi-click
+pub const MULTI_CLICK_MS: u64 = 500;
+
+/// Maximum pointer movement between presses that count as one multi-click
+const MULTI_CLICK_SLOP: f32 = 4.0;
+
+// =============================================================================
+// Word boundaries
+// =============================================================================
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SegmentKind {
+    Space,
+    Word,
+    Punct,
+}
+
+/// Split text into UAX #29 word segments as char ranges
+///
+/// Adjacent whitespace segments, and adjacent single ASCII punctuation
+/// characters, are merged into one segment.
+fn segments(text: &str) -> Vec<(Range<usize>, SegmentKind)> {
+    let mut out: Vec<(Range<usize>, SegmentKind)> = Vec::new();
+    let mut pos = 0;
+    for (_, segment) in text.split_word_bound_indices() {
+        let len = segment.chars().count();
+        let range = pos..pos + len;
+        pos += len;
+
+        let (kind, mergeable) = if segment.chars().all(char::is_whitespace) {
+            (SegmentKind::Space, true)
+        } else if len == 1 && segment.chars().all(|c| c.is_ascii_punctuation()) {
+            (SegmentKind::Punct, true)
+        } else {
+            (SegmentKind::Word, false)
+        };
+
+        match out.last_mut() {
+            Some((last, last_kind)) if mergeable && *last_kind == kind => last.end = range.end,
+            _ => out.push((range, kind)),
+        }
+    }
+    out
+}
+
+/// Start of the word before `pos`, skipping whitespace first
+///
+/// Used for Ctrl/Alt+Left and Ctrl+Backspace. `pos` and the result are char
+/// offsets; a position inside a word moves to that word's start.
+pub fn prev_word_boundary(text: &str, pos: usize) -> usize {
+    segments(text)
+        .into_iter()
+        .rev()
+        .filter(|(range, _)| range.start < pos)
+        .find(|(_, kind)| *kind != SegmentKind::Space)
+        .map_or(0, |(range, _)| range.start)
+}
+
+/// End of the word after `pos`, skipping whitespace first
+///
+/// Used for Ctrl/Alt+Right and Ctrl+Delete. `pos` and the result are char
+/// offsets; a position inside a word moves to that word's end.
+pub fn next_word_boundary(text: &str, pos: usize) -> usize {
+    segments(text)
+        .into_iter()
+        .filter(|(range, _)| range.end > pos)
+        .find(|(_, kind)| *kind != SegmentKind::Space)
+        .map_or_else(|| text.chars().count(), |(range, _)| range.end)
+}
+
+/// Char range `(start, end)` of the word, whitespace or punctuation run at `pos`
+///
+/// Used for double-click selection. A position just after a word selects that
+/// word rather than the whitespace following it.
+pub fn word_range_at(text: &str, pos: usize) -> (usize, usize) {
+    let segments = segments(text);
+    let Some(last) = segments.last() else {
+        return (0, 0);
+    };
+    let idx = pos.min(last.0.end - 1);
+    let mut i = segments
+        .iter()
+        .position(|(range, _)| range.contains(&idx))
+        .unwrap_or(segments.len() - 1);
+    if i > 0
+        && segments[i].1 == SegmentKind::Space
+        && segments[i].0.start == idx
+        && segments[i - 1].1 != SegmentKind::Space
+    {
+        i -= 1;
+    }
+    (segments[i].0.start, segments[i].0.end)
+}
+
+// =============================================================================
+// Undo history
+// =============================================================================
+
+/// What an edit did, for grouping undo steps
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditKind {
+    /// Inserting typed characters
+    Typing,
+    /// Deleting with Backspace/Delete
+    Deletion,
+    /// Anything else (newlines, pastes, moves); always its own undo step
+    Other,
+}
+
+/// Undo/redo stack of editor snapshots
+///
+/// Call [`record`](Self::record) with the state *before* each edit.
+/// Consecutive edits of the same [`EditKind`] (typing or deleting) made
+/// within [`UNDO_COALESCE_MS`] of each other form a single undo step;
+/// [`break_group`](Self::break_group) ends the current step early, e.g.
+/// when the caret is moved.
+#[derive(Clone, Debug)]
+pub struct EditHistory<S> {
+    undo: Vec<S>,
+    redo: Vec<S>,
+    last_kind: Option<EditKind>,
+    last_time_ms: u64,
+    limit: usize,
+}
+
+impl<S> Default for EditHistory<S> {
+    fn default() -> Self {
+        Self::with_limit(DEFAULT_UNDO_LIMIT)
+    }
+}
+
+impl<S> EditHistory<S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// History keeping at most `limit` undo steps
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            last_kind: None,
+            last_time_ms: 0,
+            limit: limit.max(1),
+        }
+    }
+
+    /// Record an edit, given the state before it
+    pub fn record(&mut self, before: S, kind: EditKind, now_ms: u64) {
+        let coalesce = kind != EditKind::Other
+            && self.last_kind == Some(kind)
+            && now_ms.saturating_sub(self.last_time_ms) <= UNDO_COALESCE_MS
+            && !self.undo.is_empty();
+        if !coalesce {
+            self.undo.push(before);
+            if self.undo.len() > self.limit {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.last_kind = Some(kind);
+        self.last_time_ms = now_ms;
+    }
+
+    /// Start a new undo step with the next edit
+    pub fn break_group(&mut self) {
+        self.last_kind = None;
+    }
+
+    /// Step back, given the current state; returns the state to restore
+    pub fn undo(&mut self, current: S) -> Option<S> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        self.break_group();
+        Some(previous)
+    }
+
+    /// Step forward again after an undo; returns the state to restore
+    pub fn redo(&mut self, current: S) -> Option<S> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        self.break_group();
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forget all history
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.break_group();
+    }
+}
+
+// =============================================================================
+// Multi-click detection
+// =============================================================================
+
+/// Counts consecutive presses at the same spot
+///
+/// Returns 1, 2 or 3 for single, double and triple clicks; a fourth press
+/// starts over at 1.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClickCounter {
+    last_time_ms: Option<u64>,
+    last_pos: (f32, f32),
+    count: u32,
+}
+
+impl ClickCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a press at window position `(x, y)`, returning the click count
+    pub fn register(&mut self, x: f32, y: f32, now_ms: u64) -> u32 {
+        let repeat = self
+            .last_time_ms
+            .is_some_and(|t| now_ms.saturating_sub(t) <= MULTI_CLICK_MS)
+            && (x - self.last_pos.0).abs() <= MULTI_CLICK_SLOP
+            && (y - self.last_pos.1).abs() <= MULTI_CLICK_SLOP
+            && self.count < 3;
+        self.count = if repeat { self.count + 1 } else { 1 };
+        self.last_time_ms = Some(now_ms);
+        self.last_pos = (x, y);
+        self.count
+    }
+}
+
+/// Selection drag-to-move in progress
+///
+/// Set when a press lands inside the selection; `drop` follows the pointer
+/// while dragging. If the button is released without dragging, the caret is
+/// placed at `press` instead.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DragMove<P> {
+    pub press: P,
+    pub drop: Option<P>,
+}
+
+// =============================================================================
+// Bracket pairing and indentation
+// =============================================================================
+
+/// Closing counterpart of an auto-paired opening character
+pub fn closing_pair(open: char) -> Option<char> {
+    match open {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        '"' => Some('"'),
+        '\'' => Some('\''),
+        '`' => Some('`'),
+        _ => None,
+    }
+}
+
+/// Whether `c` closes an auto-paired character
+pub fn is_closing_pair(c: char) -> bool {
+    matches!(c, ')' | ']' | '}' | '"' | '\'' | '`')
+}
+
+/// Whether typing `open` between `prev` and `next` should insert its pair
+///
+/// Pairs are only inserted before whitespace, a closing bracket or the end
+/// of the line, and quotes are not paired directly after a word character
+/// (so `don't` stays intact).
+pub fn should_auto_pair(open: char, prev: Option<char>, next: Option<char>) -> bool {
+    if closing_pair(open).is_none() {
+        return false;
+    }
+    let next_ok = next.map_or(true, |c| c.is_whitespace() || matches!(c, ')' | ']' | '}'));
+    let is_quote = matches!(open, '"' | '\'' | '`');
+    let prev_ok = !is_quote || prev.map_or(true, |c| !c.is_alphanumeric() && c != open);
+    next_ok && prev_ok
+}
+
+/// Leading whitespace of `line`
+pub fn leading_indent(line: &str) -> &str {
+    let end = line
+        .find(|c: char| c != ' ' && c != '\t')
+        .unwrap_or(line.len());
+    &line[..end]
+}
+
+/// One extra indentation level matching the style of `indent`
+pub fn indent_unit(indent: &str) -> &'static str {
+    if indent.contains('\t') {
+        "\t"
+    } else {
+        "    "
+    }
+}
+
+// =============================================================================
+// Wrapping
+// =============================================================================
+
+/// Split `text` into rows no wider than `available_width`, as char ranges
+///
+/// Rows break after whitespace where possible and always hold at least one
+/// character, so a word wider than the row is split. Empty text gives a
+/// single empty row.
+pub fn wrap_ranges(text: &str, font_size: f32, available_width: f32) -> Vec<Range<usize>> {
+    let chars: Vec<char> = text.chars().collect();
+    let char_count = chars.len();
+    let fits = |range: Range<usize>| {
+        let row = String::from_iter(&chars[range]);
+        crate::text_measure::measure_text(&row, font_size).width <= available_width
+    };
+
+    let mut rows = Vec::new();
+    let mut start_char = 0;
+    while start_char < char_count {
+        // Widest prefix that fits (always at least one character)
+        let (mut lo, mut hi) = (start_char + 1, char_count);
+        while lo < hi {
+            let mid = (lo + hi).div_ceil(2);
+            if fits(start_char..mid) {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+
+        // Back off to the last word boundary if the line continues
+        let mut end_char = lo;
+        if end_char < char_count {
+            if let Some(word_break) = (start_char + 1..=end_char)
+                .rev()
+                .find(|&k| chars[k - 1].is_whitespace())
+            {
+                end_char = word_break;
+            }
+        }
+
+        rows.push(start_char..end_char);
+        start_char = end_char;
+    }
+
+    if rows.is_empty() {
+        rows.push(0..0);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_boundaries() {
+        let text = "hello,  world foo_bar";
+        assert_eq!(next_word_boundary(text, 0), 5);
+        assert_eq!(next_word_boundary(text, 5), 6);
+        assert_eq!(next_word_boundary(text, 6), 13);
+        assert_eq!(prev_word_boundary(text, 13), 8);
+        assert_eq!(prev_word_boundary(text, 8), 5);
+        assert_eq!(prev_word_boundary(text, text.chars().count()), 14);
+
+        // Mid-word punctuation and numbers
+        assert_eq!(next_word_boundary("don't stop", 0), 5);
+        assert_eq!(next_word_boundary("1,000.50 total", 0), 8);
+        assert_eq!(next_word_boundary("end. Next", 0), 3);
+
+        // Ideographs are words of their own; Hangul and Katakana join
+        assert_eq!(next_word_boundary("日本語", 0), 1);
+        assert_eq!(prev_word_boundary("日本語", 3), 2);
+        assert_eq!(next_word_boundary("한국어 텍스트", 0), 3);
+        assert_eq!(next_word_boundary("カタカナ", 0), 4);
+
+        // Combining marks stay with their base letter
+        assert_eq!(next_word_boundary("cafe\u{301} au", 0), 5);
+
+        // Typographic apostrophes join, other punctuation runs are one step
+        assert_eq!(next_word_boundary("it\u{2019}s fine", 0), 4);
+        assert_eq!(next_word_boundary("wait... what", 4), 7);
+
+        // ZWJ emoji sequences are never split
+        let family = "hi \u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467} there";
+        assert_eq!(next_word_boundary(family, 2), 8);
+        assert_eq!(prev_word_boundary(family, 8), 3);
+        assert_eq!(word_range_at(family, 5), (3, 8));
+
+        // Mixed CJK and Latin
+        assert_eq!(next_word_boundary("東京tower", 0), 1);
+        assert_eq!(next_word_boundary("東京tower", 2), 7);
+    }
+
+    #[test]
+    fn test_word_range_at() {
+        let text = "foo bar  baz";
+        assert_eq!(word_range_at(text, 5), (4, 7));
+        assert_eq!(word_range_at(text, 7), (4, 7));
+        assert_eq!(word_range_at(text, 8), (7, 9));
+        assert_eq!(word_range_at(text, 12), (9, 12));
+        assert_eq!(word_range_at("", 0), (0, 0));
+    }
+
+    #[test]
+    fn test_edit_history_coalescing() {
+        let mut history = EditHistory::new();
+        history.record("", EditKind::Typing, 0);
+        history.record("a", EditKind::Typing, 100);
+        history.record("ab", EditKind::Typing, 200);
+        // Too late to join the group
+        history.record("abc", EditKind::Typing, 2000);
+        history.record("abcd", EditKind::Deletion, 2100);
+
+        assert_eq!(history.undo("abc"), Some("abcd"));
+        assert_eq!(history.undo("abcd"), Some("abc"));
+        assert_eq!(history.undo("abc"), Some(""));
+        assert!(!history.can_undo());
+        assert_eq!(history.redo(""), Some("abc"));
+        assert!(history.can_redo());
+
+        // A new edit drops the redo branch
+        history.record("abc", EditKind::Other, 3000);
+        assert!(!history.can_redo());
+
+        history.break_group();
+        history.record("x", EditKind::Typing, 3100);
+        history.record("xy", EditKind::Typing, 3150);
+        history.break_group();
+        history.record("xyz", EditKind::Typing, 3200);
+        assert_eq!(history.undo("xyzw"), Some("xyz"));
+        assert_eq!(history.undo("xyz"), Some("x"));
+    }
+
+    #[test]
+    fn test_edit_history_limit() {
+        let mut history = EditHistory::with_limit(2);
+        for (i, state) in ["a", "b", "c"].into_iter().enumerate() {
+            history.record(state, EditKind::Other, i as u64);
+        }
+        assert_eq!(history.undo("d"), Some("c"));
+        assert_eq!(history.undo("c"), Some("b"));
+        assert_eq!(history.undo("b"), None);
+    }
+
+    #[test]
+    fn test_click_counter() {
+        let mut clicks = ClickCounter::new();
+        assert_eq!(clicks.register(10.0, 10.0, 0), 1);
+        assert_eq!(clicks.register(11.0, 10.0, 200), 2);
+        assert_eq!(clicks.register(11.0, 11.0, 400), 3);
+        assert_eq!(clicks.register(11.0, 11.0, 500), 1);
+        // Too slow, or too far away
+        assert_eq!(clicks.register(11.0, 11.0, 1200), 1);
+        assert_eq!(clicks.register(40.0, 11.0, 1300), 1);
+    }
+
+    #[test]
+    fn test_auto_pair_rules() {
+        assert!(should_auto_pair('(', Some('a'), None));
+        assert!(should_auto_pair('[', None, Some(')')));
+        assert!(!should_auto_pair('(', None, Some('x')));
+        assert!(!should_auto_pair('\'', Some('n'), None));
+        assert!(should_auto_pair('"', Some(' '), Some(' ')));
+        assert!(!should_auto_pair('a', None, None));
+        assert_eq!(leading_indent("\t  foo"), "\t  ");
+        assert_eq!(indent_unit("  "), "    ");
+        assert_eq!(indent_unit("\t"), "\t");
+    }
+}
@@ -29,9 +29,14 @@ use crate::stateful::{
     refresh_stateful, SharedState, StateTransitions, Stateful, StatefulInner, TextFieldState,
 };
 use crate::text::text;
-use crate::text_selection::{clear_selection, set_selection, SelectionSource};
+use crate::text_selection::{clear_selection, set_selection, BidiCaretMap, SelectionSource};
 use crate::tree::{LayoutNodeId, LayoutTree};
 use crate::widgets::cursor::{cursor_state, CursorAnimation, SharedCursorState};
+use crate::widgets::ime::{notify_ime_caret, Composition, ImeCaretRect, ImeTarget};
+use crate::widgets::text_edit::{
+    next_word_boundary, prev_word_boundary, word_range_at, ClickCounter, DragMove, EditHistory,
+    EditKind,
+};
 
 /// Get elapsed time in milliseconds since app start (for cursor blinking)
 pub fn elapsed_ms() -> u64 {
@@ -162,6 +167,7 @@ pub(crate) fn set_focused_text_input(state: &SharedTextInputData) {
                         s.visual = new_state;
                         decrement_focus_count();
                     }
+                    s.composition = None;
                 }
             }
         }
@@ -194,6 +200,7 @@ pub(crate) fn set_focused_text_area(state: &crate::widgets::text_area::SharedTex
                         s.visual = new_state;
                         decrement_focus_count();
                     }
+                    s.composition = None;
                 }
             }
         }
@@ -209,6 +216,7 @@ pub(crate) fn set_focused_text_area(state: &crate::widgets::text_area::SharedTex
                             s.visual = new_state;
                             decrement_focus_count();
                         }
+                        s.composition = None;
                     }
                 }
             }
@@ -239,6 +247,7 @@ fn blur_focused_text_area() {
                     s.visual = new_state;
                     decrement_focus_count();
                 }
+                s.composition = None;
             }
         }
     }
@@ -261,6 +270,8 @@ pub fn blur_all_text_inputs() {
                             s.visual = new_state;
                             decrement_focus_count();
                         }
+                        // An unfinished composition is discarded on blur
+                        s.composition = None;
                         // Also update the FSM state to keep in sync
                         let stateful_ref = s.stateful_state.clone();
                         if let Some(ref stateful) = stateful_ref {
@@ -293,6 +304,8 @@ pub fn blur_all_text_inputs() {
                             s.visual = new_state;
                             decrement_focus_count();
                         }
+                        // An unfinished composition is discarded on blur
+                        s.composition = None;
                         // Also update the FSM state to keep in sync
                         let stateful_ref = s.stateful_state.clone();
                         if let Some(ref stateful) = stateful_ref {
@@ -313,6 +326,8 @@ pub fn blur_all_text_inputs() {
             }
         }
     }
+
+    notify_ime_caret(None);
 }
 
 /// Get the layout node ID of the currently focused TextInput, if any.
@@ -401,6 +416,14 @@ impl InputConstraints {
 /// Shared text input data handle
 pub type SharedTextInputData = Arc<Mutex<TextInputData>>;
 
+/// Text and caret of a [`TextInputData`], kept by its undo history
+#[derive(Clone, Debug)]
+pub(crate) struct TextInputSnapshot {
+    value: String,
+    cursor: usize,
+    selection_start: Option<usize>,
+}
+
 /// Text input data (content, cursor, validation)
 ///
 /// This is the EXTERNAL state that persists across rebuilds.
@@ -434,6 +457,16 @@ pub struct TextInputData {
     pub(crate) on_change_callback: Option<OnChangeCallback>,
     /// CSS element ID for stylesheet matching (set via TextInput::id())
     pub(crate) css_element_id: Option<String>,
+    /// In-progress IME composition, shown at the cursor but not yet in `value`
+    pub composition: Option<Composition>,
+    /// Window position of the text origin, captured on focus, for IME caret reporting
+    pub(crate) ime_origin: Option<(f32, f32)>,
+    /// Undo/redo history
+    pub(crate) history: EditHistory<TextInputSnapshot>,
+    /// Detects double and triple clicks
+    pub(crate) click_counter: ClickCounter,
+    /// Pending drag-to-move of the selection
+    pub(crate) drag_move: Option<DragMove<usize>>,
 }
 
 impl std::fmt::Debug for TextInputData {
@@ -450,6 +483,7 @@ impl std::fmt::Debug for TextInputData {
             .field("is_valid", &self.is_valid)
             .field("visual", &self.visual)
             .field("focus_time_ms", &self.focus_time_ms)
+            .field("composition", &self.composition)
             // Skip stateful_state since StatefulInner doesn't implement Debug
             .finish()
     }
@@ -482,6 +516,11 @@ impl TextInputData {
             stateful_state: None,
             on_change_callback: None,
             css_element_id: None,
+            composition: None,
+            ime_origin: None,
+            history: EditHistory::new(),
+            click_counter: ClickCounter::new(),
+            drag_move: None,
         }
     }
 
@@ -513,6 +552,10 @@ impl TextInputData {
 
     /// Insert text at cursor, respecting input type constraints
     pub fn insert(&mut self, text: &str) {
+        self.edit(EditKind::Typing, |d| d.insert_internal(text));
+    }
+
+    fn insert_internal(&mut self, text: &str) {
         // Delete selection first if any
         if let Some(start) = self.selection_start {
             let (from, to) = if start < self.cursor {
@@ -566,7 +609,63 @@ impl TextInputData {
         // after releasing the lock to avoid deadlock
     }
 
+    /// Whether an IME composition is in progress
+    pub fn is_composing(&self) -> bool {
+        self.composition.is_some()
+    }
+
+    /// Display text with the preedit spliced in at the cursor
+    ///
+    /// Returns the text and the preedit's char range within it, if composing.
+    pub fn composed_text(&self) -> (String, Option<(usize, usize)>) {
+        let display = self.display_text();
+        let Some(comp) = &self.composition else {
+            return (display, None);
+        };
+        let preedit = if self.masked {
+            "•".repeat(comp.len())
+        } else {
+            comp.text.clone()
+        };
+        let before: String = display.chars().take(self.cursor).collect();
+        let after: String = display.chars().skip(self.cursor).collect();
+        let start = before.chars().count();
+        (
+            before + &preedit + &after,
+            Some((start, start + comp.len())),
+        )
+    }
+
+    /// Caret rectangle in window coordinates for positioning the IME candidate window
+    pub fn ime_caret_rect(&self, font_size: f32) -> Option<ImeCaretRect> {
+        let (origin_x, origin_y) = self.ime_origin?;
+        let (display, range) = self.composed_text();
+        let caret = match (&self.composition, range) {
+            (Some(comp), Some((start, _))) => start + comp.caret(),
+            _ => self.cursor,
+        };
+        let caret_x = match BidiCaretMap::new(&display, |s| {
+            crate::text_measure::measure_text(s, font_size).width
+        }) {
+            Some(map) => map.caret_x(caret),
+            None => {
+                let before: String = display.chars().take(caret).collect();
+                crate::text_measure::measure_text(&before, font_size).width
+            }
+        };
+        Some(ImeCaretRect {
+            x: origin_x + caret_x - self.scroll_offset_x,
+            y: origin_y,
+            width: 2.0,
+            height: font_size * 1.2,
+        })
+    }
+
     pub fn delete_backward(&mut self) {
+        self.edit(EditKind::Deletion, |d| d.delete_backward_internal());
+    }
+
+    fn delete_backward_internal(&mut self) {
         if let Some(start) = self.selection_start {
             let (from, to) = if start < self.cursor {
                 (start, self.cursor)
@@ -590,6 +689,10 @@ impl TextInputData {
     }
 
     pub fn delete_forward(&mut self) {
+        self.edit(EditKind::Deletion, |d| d.delete_forward_internal());
+    }
+
+    fn delete_forward_internal(&mut self) {
         if let Some(start) = self.selection_start {
             let (from, to) = if start < self.cursor {
                 (start, self.cursor)
@@ -611,6 +714,169 @@ impl TextInputData {
         // after releasing the lock to avoid deadlock
     }
 
+    /// Delete from the cursor back to the previous word boundary (Ctrl+Backspace)
+    pub fn delete_word_backward(&mut self) {
+        if self.selection_start.is_none() {
+            self.move_word_left(true);
+        }
+        self.delete_backward();
+    }
+
+    /// Delete from the cursor to the next word boundary (Ctrl+Delete)
+    pub fn delete_word_forward(&mut self) {
+        if self.selection_start.is_none() {
+            self.move_word_right(true);
+        }
+        self.delete_forward();
+    }
+
+    /// Apply an edit, recording the previous state in the undo history if the value changed
+    fn edit(&mut self, kind: EditKind, f: impl FnOnce(&mut Self)) {
+        let before = self.snapshot();
+        f(self);
+        if self.value != before.value {
+            self.history.record(before, kind, elapsed_ms());
+        }
+    }
+
+    fn snapshot(&self) -> TextInputSnapshot {
+        TextInputSnapshot {
+            value: self.value.clone(),
+            cursor: self.cursor,
+            selection_start: self.selection_start,
+        }
+    }
+
+    fn restore(&mut self, snapshot: TextInputSnapshot) {
+        self.value = snapshot.value;
+        self.cursor = snapshot.cursor;
+        self.selection_start = snapshot.selection_start;
+        self.composition = None;
+        self.validate();
+    }
+
+    /// Undo the last edit group; returns whether anything changed
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo(self.snapshot()) {
+            Some(snapshot) => {
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Redo the last undone edit group; returns whether anything changed
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo(self.snapshot()) {
+            Some(snapshot) => {
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// End the current undo group, so the next edit is undone separately
+    pub fn break_undo_group(&mut self) {
+        self.history.break_group();
+    }
+
+    /// Forget the undo history
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Move to the previous word boundary
+    ///
+    /// Masked (password) inputs don't reveal word structure, so the cursor
+    /// jumps to the start instead.
+    pub fn move_word_left(&mut self, shift: bool) {
+        let target = if self.masked {
+            0
+        } else {
+            prev_word_boundary(&self.value, self.cursor)
+        };
+        self.move_to(target, shift);
+    }
+
+    /// Move to the next word boundary (the end for masked inputs)
+    pub fn move_word_right(&mut self, shift: bool) {
+        let target = if self.masked {
+            self.value.chars().count()
+        } else {
+            next_word_boundary(&self.value, self.cursor)
+        };
+        self.move_to(target, shift);
+    }
+
+    fn move_to(&mut self, target: usize, shift: bool) {
+        if shift {
+            if self.selection_start.is_none() {
+                self.selection_start = Some(self.cursor);
+            }
+        } else {
+            self.selection_start = None;
+        }
+        self.cursor = target;
+    }
+
+    /// Select the word at `pos` (double-click); selects everything when masked
+    pub fn select_word_at(&mut self, pos: usize) {
+        if self.masked {
+            return self.select_all();
+        }
+        let (start, end) = word_range_at(&self.value, pos);
+        self.selection_start = Some(start);
+        self.cursor = end;
+    }
+
+    /// Whether `pos` lies strictly inside the selection
+    pub fn selection_contains(&self, pos: usize) -> bool {
+        self.selection_start.is_some_and(|start| {
+            let (from, to) = (start.min(self.cursor), start.max(self.cursor));
+            from < pos && pos < to
+        })
+    }
+
+    /// Move the selected text to `target` (drag-to-move)
+    ///
+    /// The moved text stays selected. Returns `false` if nothing is selected
+    /// or `target` lies within the selection.
+    pub fn move_selection_to(&mut self, target: usize) -> bool {
+        let Some(start) = self.selection_start else {
+            return false;
+        };
+        let (from, to) = (start.min(self.cursor), start.max(self.cursor));
+        if from == to || (from..=to).contains(&target) {
+            return false;
+        }
+        let Some(text) = self.selected_text() else {
+            return false;
+        };
+
+        self.edit(EditKind::Other, |d| {
+            d.delete_backward_internal();
+            let at = if target > to {
+                target - (to - from)
+            } else {
+                target
+            };
+            d.cursor = at;
+            d.insert_internal(&text);
+            d.selection_start = Some(at);
+        });
+        true
+    }
+
     pub fn move_left(&mut self, shift: bool) {
         if shift {
             if self.selection_start.is_none() {
@@ -637,6 +903,37 @@ impl TextInputData {
         }
     }
 
+    /// Move the cursor one caret position visually left or right
+    ///
+    /// Same as `move_left`/`move_right` for left-to-right text; when the text
+    /// contains right-to-left runs the arrow keys follow screen order.
+    pub fn move_visual(&mut self, right: bool, shift: bool, font_size: f32) {
+        let Some(map) = self.caret_map(font_size) else {
+            if right {
+                self.move_right(shift);
+            } else {
+                self.move_left(shift);
+            }
+            return;
+        };
+
+        if shift {
+            if self.selection_start.is_none() {
+                self.selection_start = Some(self.cursor);
+            }
+        } else {
+            self.selection_start = None;
+        }
+        self.cursor = map.move_visual(self.cursor, right);
+    }
+
+    /// Bidi caret geometry for the displayed text (`None` for pure LTR text)
+    fn caret_map(&self, font_size: f32) -> Option<BidiCaretMap> {
+        BidiCaretMap::new(&self.display_text(), |s| {
+            crate::text_measure::measure_text(s, font_size).width
+        })
+    }
+
     pub fn move_to_start(&mut self, shift: bool) {
         if shift {
             if self.selection_start.is_none() {
@@ -739,6 +1036,10 @@ impl TextInputData {
         // so add scroll_offset to get position in text space
         let text_x = x + self.scroll_offset_x;
 
+        if let Some(map) = self.caret_map(font_size) {
+            return map.index_at_x(text_x);
+        }
+
         // Binary search would be more efficient, but for typical text input lengths,
         // linear search is fast enough
         let char_count = display.chars().count();
@@ -791,7 +1092,9 @@ impl TextInputData {
         };
 
         // Calculate cursor x position (where cursor is in the full text)
-        let cursor_x = if self.cursor > 0 && !display.is_empty() {
+        let cursor_x = if let Some(map) = self.caret_map(config.font_size) {
+            map.caret_x(self.cursor)
+        } else if self.cursor > 0 && !display.is_empty() {
             let text_before: String = display.chars().take(self.cursor).collect();
             crate::text_measure::measure_text(&text_before, config.font_size).width
         } else {
@@ -826,6 +1129,35 @@ impl TextInputData {
     }
 }
 
+impl ImeTarget for TextInputData {
+    fn ime_start(&mut self) {
+        // The composition replaces the selection, like typing would
+        if self.selection_start.is_some() {
+            self.delete_backward();
+        }
+        self.composition = Some(Composition::default());
+    }
+
+    fn ime_update(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        if self.composition.is_none() {
+            self.ime_start();
+        }
+        self.composition = Some(Composition {
+            text: text.to_string(),
+            cursor,
+        });
+    }
+
+    fn ime_commit(&mut self, text: &str) {
+        self.composition = None;
+        self.insert(text);
+    }
+
+    fn ime_cancel(&mut self) {
+        self.composition = None;
+    }
+}
+
 /// Create a shared text input data
 pub fn text_input_data() -> SharedTextInputData {
     Arc::new(Mutex::new(TextInputData::new()))
@@ -1286,12 +1618,23 @@ impl TextInput {
         let data_for_click = Arc::clone(&data);
         let data_for_text = Arc::clone(&data);
         let data_for_key = Arc::clone(&data);
+        let data_for_drag = Arc::clone(&data);
+        let data_for_drop = Arc::clone(&data);
+        let data_for_up = Arc::clone(&data);
         let config_for_click = Arc::clone(&config);
+        let config_for_key = Arc::clone(&config);
+        let config_for_drag = Arc::clone(&config);
         let stateful_for_click = Arc::clone(&stateful_state);
         let stateful_for_text = Arc::clone(&stateful_state);
         let stateful_for_key = Arc::clone(&stateful_state);
-
-        Stateful::with_shared_state(stateful_state)
+        let stateful_for_drop = Arc::clone(&stateful_state);
+        let stateful_for_up = Arc::clone(&stateful_state);
+        let stateful_for_ime = Arc::clone(&stateful_state);
+        let data_for_focus = Arc::clone(&data);
+        let config_for_focus = Arc::clone(&config);
+        let stateful_for_focus = Arc::clone(&stateful_state);
+
+        let inner = Stateful::with_shared_state(stateful_state)
             .w_full()
             // Handle mouse down to focus and position cursor
             .on_mouse_down(move |ctx| {
@@ -1339,6 +1682,9 @@ impl TextInput {
                         d.computed_width = Some(ctx.bounds_width);
                     }
 
+                    // Window position of the hit element, for placing the IME candidate window
+                    d.ime_origin = Some((ctx.mouse_x - ctx.local_x, ctx.mouse_y - ctx.local_y));
+
                     // Calculate cursor position from click x position
                     // local_x is relative to the hit element (text inside the wrapper).
                     // Since the text element is positioned after padding/border via layout,
@@ -1346,18 +1692,73 @@ impl TextInput {
                     // cursor_position_from_x handles scroll offset internally.
                     let text_x = ctx.local_x.max(0.0);
                     let cursor_pos = d.cursor_position_from_x(text_x, font_size);
-                    d.cursor = cursor_pos;
-                    d.selection_start = None;
+
+                    // Double-click selects a word, triple-click everything. A press
+                    // inside the selection may start dragging it elsewhere.
+                    let clicks = d
+                        .click_counter
+                        .register(ctx.mouse_x, ctx.mouse_y, elapsed_ms());
+                    d.break_undo_group();
+                    d.drag_move = None;
+                    match clicks {
+                        2 => d.select_word_at(cursor_pos),
+                        3 => d.select_all(),
+                        _ if d.selection_contains(cursor_pos) => {
+                            d.drag_move = Some(DragMove {
+                                press: cursor_pos,
+                                drop: None,
+                            });
+                        }
+                        _ => {
+                            d.cursor = cursor_pos;
+                            d.selection_start = None;
+                        }
+                    }
                     d.reset_cursor_blink();
 
-                    true // needs refresh
+                    // Enable IME at the new caret
+                    Some(d.ime_caret_rect(font_size))
                 }; // Lock released here
 
                 // Trigger incremental refresh AFTER releasing the data lock
-                if needs_refresh {
+                if let Some(caret) = needs_refresh {
                     refresh_stateful(&stateful_for_click);
+                    notify_ime_caret(caret);
                 }
             })
+            // Handle focus from the event router (keyboard navigation,
+            // ElementHandle::focus/type_text) - keeps the cursor where it was
+            .on_event(event_types::FOCUS, move |_ctx| {
+                let font_size = config_for_focus.lock().unwrap().font_size;
+                let caret = {
+                    let mut d = match data_for_focus.lock() {
+                        Ok(d) => d,
+                        Err(_) => return,
+                    };
+                    if d.disabled || d.visual.is_focused() {
+                        return;
+                    }
+
+                    {
+                        let mut shared = stateful_for_focus.lock().unwrap();
+                        if let Some(new_state) = shared.state.on_event(event_types::FOCUS) {
+                            shared.state = new_state;
+                            shared.needs_visual_update = true;
+                        }
+                    }
+
+                    d.visual = TextFieldState::Focused;
+                    d.focus_time_ms = elapsed_ms();
+                    d.reset_cursor_blink();
+                    increment_focus_count();
+                    set_focused_text_input(&data_for_focus);
+                    request_continuous_redraw();
+                    d.ime_caret_rect(font_size)
+                }; // Lock released here
+
+                refresh_stateful(&stateful_for_focus);
+                notify_ime_caret(caret);
+            })
             // Handle text input
             .on_event(event_types::TEXT_INPUT, move |ctx| {
                 let (needs_refresh, callback_info) = {
@@ -1366,7 +1767,8 @@ impl TextInput {
                         Err(_) => return,
                     };
 
-                    if d.disabled || !d.visual.is_focused() {
+                    // Keystrokes belong to the IME while composing
+                    if d.disabled || !d.visual.is_focused() || d.is_composing() {
                         return;
                     }
 
@@ -1397,20 +1799,39 @@ impl TextInput {
             })
             // Handle key down for navigation and deletion
             .on_key_down(move |ctx| {
+                let font_size = config_for_key.lock().unwrap().font_size;
                 let (needs_refresh, callback_info) = {
                     let mut d = match data_for_key.lock() {
                         Ok(d) => d,
                         Err(_) => return,
                     };
 
-                    if d.disabled || !d.visual.is_focused() {
+                    if d.disabled || !d.visual.is_focused() || d.is_composing() {
                         return;
                     }
 
                     let mut changed = true;
                     let mut should_blur = false;
                     let mut value_changed = false; // Track if text content actually changed
+                                                   // Ctrl (or Alt/Option on macOS) acts on whole words
+                    let by_word = ctx.ctrl || ctx.alt;
+                    let shortcut = ctx.ctrl || ctx.meta;
                     match ctx.key_code {
+                        90 if shortcut => {
+                            // Ctrl/Cmd+Z undo, with Shift redo
+                            value_changed = if ctx.shift { d.redo() } else { d.undo() };
+                        }
+                        89 if shortcut => value_changed = d.redo(), // Ctrl/Cmd+Y redo
+                        8 if by_word => {
+                            d.delete_word_backward(); // Ctrl+Backspace
+                            value_changed = true;
+                        }
+                        127 if by_word => {
+                            d.delete_word_forward(); // Ctrl+Delete
+                            value_changed = true;
+                        }
+                        37 if by_word => d.move_word_left(ctx.shift),
+                        39 if by_word => d.move_word_right(ctx.shift),
                         8 => {
                             d.delete_backward(); // Backspace
                             value_changed = true;
@@ -1419,11 +1840,11 @@ impl TextInput {
                             d.delete_forward(); // Delete
                             value_changed = true;
                         }
-                        37 => d.move_left(ctx.shift),     // Left arrow
-                        39 => d.move_right(ctx.shift),    // Right arrow
-                        36 => d.move_to_start(ctx.shift), // Home
-                        35 => d.move_to_end(ctx.shift),   // End
-                        65 if ctx.meta || ctx.ctrl => d.select_all(), // Ctrl/Cmd+A
+                        37 => d.move_visual(false, ctx.shift, font_size), // Left arrow
+                        39 => d.move_visual(true, ctx.shift, font_size),  // Right arrow
+                        36 => d.move_to_start(ctx.shift),                 // Home
+                        35 => d.move_to_end(ctx.shift),                   // End
+                        65 if ctx.meta || ctx.ctrl => d.select_all(),     // Ctrl/Cmd+A
                         27 => {
                             // Escape - blur the input
                             should_blur = true;
@@ -1435,6 +1856,10 @@ impl TextInput {
                     if changed && !should_blur {
                         d.reset_cursor_blink();
                         d.sync_global_selection();
+                        if !value_changed {
+                            // Moving the caret ends the current undo group
+                            d.break_undo_group();
+                        }
                     }
 
                     // Extract callback info if value changed
@@ -1462,8 +1887,131 @@ impl TextInput {
                     callback(&new_value);
                 }
             })
+            // Track the drop position while dragging a selection
+            .on_drag(move |ctx| {
+                let font_size = config_for_drag.lock().unwrap().font_size;
+                let Ok(mut d) = data_for_drag.lock() else {
+                    return;
+                };
+                let (Some(_), Some((origin_x, _))) = (d.drag_move, d.ime_origin) else {
+                    return;
+                };
+                let drop = d.cursor_position_from_x((ctx.mouse_x - origin_x).max(0.0), font_size);
+                if let Some(drag) = d.drag_move.as_mut() {
+                    drag.drop = Some(drop);
+                }
+            })
+            // Drop a dragged selection at the pointer
+            .on_drag_end(move |_ctx| {
+                let callback_info = {
+                    let mut d = match data_for_drop.lock() {
+                        Ok(d) => d,
+                        Err(_) => return,
+                    };
+                    let Some(drop) = d.drag_move.take().and_then(|drag| drag.drop) else {
+                        return;
+                    };
+                    if !d.move_selection_to(drop) {
+                        return;
+                    }
+                    d.reset_cursor_blink();
+                    d.sync_global_selection();
+                    d.on_change_callback
+                        .as_ref()
+                        .map(|cb| (Arc::clone(cb), d.value.clone()))
+                }; // Lock released here
+
+                refresh_stateful(&stateful_for_drop);
+                if let Some((callback, new_value)) = callback_info {
+                    callback(&new_value);
+                }
+            })
+            // A press inside the selection without dragging places the caret
+            .on_mouse_up(move |_ctx| {
+                {
+                    let mut d = match data_for_up.lock() {
+                        Ok(d) => d,
+                        Err(_) => return,
+                    };
+                    let Some(drag) = d.drag_move.take() else {
+                        return;
+                    };
+                    d.cursor = drag.press;
+                    d.selection_start = None;
+                    d.reset_cursor_blink();
+                } // Lock released here
+
+                refresh_stateful(&stateful_for_up);
+            })
             // Set text cursor (I-beam) for text input
-            .cursor_text()
+            .cursor_text();
+
+        Self::with_composition_handlers(inner, data, stateful_for_ime, config)
+    }
+
+    /// Attach IME composition handlers (start/update/commit/cancel)
+    fn with_composition_handlers(
+        mut inner: Stateful<TextFieldState>,
+        data: SharedTextInputData,
+        stateful_state: SharedState<TextFieldState>,
+        config: Arc<Mutex<TextInputConfig>>,
+    ) -> Stateful<TextFieldState> {
+        use blinc_core::events::event_types;
+
+        for event_type in [
+            event_types::COMPOSITION_START,
+            event_types::COMPOSITION_UPDATE,
+            event_types::COMPOSITION_COMMIT,
+            event_types::COMPOSITION_CANCEL,
+        ] {
+            let data = Arc::clone(&data);
+            let stateful = Arc::clone(&stateful_state);
+            let config = Arc::clone(&config);
+            inner = inner.on_event(event_type, move |ctx| {
+                let font_size = config.lock().unwrap().font_size;
+                let (caret, callback_info) = {
+                    let mut d = match data.lock() {
+                        Ok(d) => d,
+                        Err(_) => return,
+                    };
+
+                    if d.disabled || !d.visual.is_focused() {
+                        return;
+                    }
+
+                    let old_value = d.value.clone();
+                    let text = ctx.composition_text.as_deref().unwrap_or("");
+                    match ctx.event_type {
+                        event_types::COMPOSITION_START => d.ime_start(),
+                        event_types::COMPOSITION_UPDATE => {
+                            d.ime_update(text, ctx.composition_cursor)
+                        }
+                        event_types::COMPOSITION_COMMIT => d.ime_commit(text),
+                        _ => d.ime_cancel(),
+                    }
+                    d.reset_cursor_blink();
+
+                    let cb_info = if d.value != old_value {
+                        d.on_change_callback
+                            .as_ref()
+                            .map(|cb| (Arc::clone(cb), d.value.clone()))
+                    } else {
+                        None
+                    };
+                    (d.ime_caret_rect(font_size), cb_info)
+                }; // Lock released here
+
+                refresh_stateful(&stateful);
+                if caret.is_some() {
+                    notify_ime_caret(caret);
+                }
+
+                if let Some((callback, new_value)) = callback_info {
+                    callback(&new_value);
+                }
+            });
+        }
+        inner
     }
 
     /// Build the content div based on current visual state and data
@@ -1476,17 +2024,20 @@ impl TextInput {
         data: &TextInputData,
         config: &TextInputConfig,
     ) -> Div {
-        let display = if data.value.is_empty() {
-            if !data.placeholder.is_empty() {
+        // While composing, the preedit is shown spliced in at the cursor
+        let show_placeholder = data.value.is_empty() && !data.is_composing();
+        let (display, composition_range) = if show_placeholder {
+            let placeholder = if !data.placeholder.is_empty() {
                 data.placeholder.clone()
             } else {
                 config.placeholder.clone()
-            }
+            };
+            (placeholder, None)
         } else {
-            data.display_text()
+            data.composed_text()
         };
 
-        let text_color = if data.value.is_empty() {
+        let text_color = if show_placeholder {
             config.placeholder_color
         } else if data.disabled {
             Color::rgba(0.4, 0.4, 0.4, 1.0)
@@ -1498,6 +2049,11 @@ impl TextInput {
         let cursor_color = config.cursor_color;
         let selection_color = config.selection_color;
         let cursor_pos = data.cursor;
+        // Caret index in the displayed text, inside the preedit while composing
+        let caret_pos = match &data.composition {
+            Some(comp) => cursor_pos + comp.caret(),
+            None => cursor_pos,
+        };
         let cursor_height = config.font_size * 1.2;
         let scroll_offset = data.scroll_offset_x;
 
@@ -1511,8 +2067,20 @@ impl TextInput {
 
         let cursor_state_for_canvas = Arc::clone(&data.cursor_state);
 
-        let cursor_x = if cursor_pos > 0 && !display.is_empty() {
-            let text_before: String = display.chars().take(cursor_pos).collect();
+        // Bidi geometry only applies to the value, never the placeholder
+        let font_size = config.font_size;
+        let caret_map = if show_placeholder {
+            None
+        } else {
+            BidiCaretMap::new(&display, |s| {
+                crate::text_measure::measure_text(s, font_size).width
+            })
+        };
+
+        let cursor_x = if let Some(map) = &caret_map {
+            map.caret_x(caret_pos)
+        } else if caret_pos > 0 && !display.is_empty() {
+            let text_before: String = display.chars().take(caret_pos).collect();
             crate::text_measure::measure_text(&text_before, config.font_size).width
         } else {
             0.0
@@ -1555,8 +2123,49 @@ impl TextInput {
             .flex_row()
             .items_center();
 
+        // Underline the preedit text below the baseline
+        if let (Some((comp_start, comp_end)), Some(map)) = (composition_range, &caret_map) {
+            let underline_top = inner_height / 2.0 + config.font_size * 0.55;
+            for (left, right) in map.selection_ranges(comp_start, comp_end) {
+                text_wrapper = text_wrapper.child(
+                    div()
+                        .absolute()
+                        .left(left)
+                        .top(underline_top)
+                        .w(right - left)
+                        .h(1.0)
+                        .bg(text_color),
+                );
+            }
+        }
+
         if !display.is_empty() {
-            if let Some((sel_start, sel_end)) = selection_range {
+            if let (Some((sel_start, sel_end)), Some(map)) = (selection_range, &caret_map) {
+                // Bidi selections can be visually discontiguous, so draw the
+                // highlight spans behind a single text run
+                let highlight_height = config.font_size * 1.2;
+                let highlight_top = (inner_height - highlight_height) / 2.0;
+                for (left, right) in map.selection_ranges(sel_start, sel_end) {
+                    text_wrapper = text_wrapper.child(
+                        div()
+                            .absolute()
+                            .left(left)
+                            .top(highlight_top)
+                            .w(right - left)
+                            .h(highlight_height)
+                            .bg(selection_color)
+                            .rounded(config.corner_radius),
+                    );
+                }
+                text_wrapper = text_wrapper.child(
+                    text(&display)
+                        .size(config.font_size)
+                        .color(text_color)
+                        .text_left()
+                        .no_wrap()
+                        .v_center(),
+                );
+            } else if let Some((sel_start, sel_end)) = selection_range {
                 let mut text_container = div().flex_row().items_center();
 
                 let before_sel: String = display.chars().take(sel_start).collect();
@@ -1984,6 +2593,14 @@ impl ElementBuilder for TextInput {
         self.inner.layout_style()
     }
 
+    fn element_id(&self) -> Option<&str> {
+        self.inner.element_id()
+    }
+
+    fn element_classes(&self) -> &[String] {
+        self.inner.element_classes()
+    }
+
     fn layout_bounds_storage(&self) -> Option<crate::renderer::LayoutBoundsStorage> {
         // Return the layout bounds storage from the data so it gets updated after layout
         if let Ok(data) = self.data.lock() {
@@ -2050,4 +2667,40 @@ mod tests {
         data.insert("abc123");
         assert_eq!(data.value, "123");
     }
+
+    #[test]
+    fn test_text_input_undo_and_word_editing() {
+        let mut data = TextInputData::new();
+        data.insert("hello");
+        data.insert(" ");
+        data.break_undo_group();
+        data.insert("world");
+
+        data.move_word_left(false);
+        assert_eq!(data.cursor, 6);
+        data.move_word_left(true);
+        assert_eq!(data.selected_text(), Some("hello ".to_string()));
+
+        data.move_to_end(false);
+        data.delete_word_backward();
+        assert_eq!(data.value, "hello ");
+
+        assert!(data.undo());
+        assert_eq!(data.value, "hello world");
+        assert!(data.undo());
+        assert_eq!(data.value, "hello ");
+        assert!(data.redo());
+        assert_eq!(data.value, "hello world");
+
+        data.select_word_at(8);
+        assert_eq!(data.selected_text(), Some("world".to_string()));
+        assert!(data.move_selection_to(0));
+        assert_eq!(data.value, "worldhello ");
+
+        // Password fields don't expose word structure
+        let mut data = TextInputData::with_value("secret words");
+        data.masked = true;
+        data.move_word_left(false);
+        assert_eq!(data.cursor, 0);
+    }
 }
@@ -21,6 +21,8 @@ pub enum InputEvent {
     Mouse(MouseEvent),
     /// Keyboard event
     Keyboard(KeyboardEvent),
+    /// IME composition event (CJK and other input methods)
+    Ime(ImeEvent),
     /// Touch event (mobile/touchscreen)
     Touch(TouchEvent),
     /// Scroll/wheel event
@@ -265,6 +267,32 @@ pub enum Key {
     Unknown,
 }
 
+// ============================================================================
+// IME Events
+// ============================================================================
+
+/// Input method composition events
+///
+/// A composition starts with [`ImeEvent::Start`], receives any number of
+/// [`ImeEvent::Update`]s while the user edits the preedit text, and ends with
+/// either [`ImeEvent::Commit`] or [`ImeEvent::Cancel`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImeEvent {
+    /// Composition started
+    Start,
+    /// Preedit text changed
+    Update {
+        /// Uncommitted preedit text
+        text: String,
+        /// Caret or selection inside the preedit, in chars (`None` if the IME doesn't report one)
+        cursor: Option<(usize, usize)>,
+    },
+    /// Composition finished with final text
+    Commit(String),
+    /// Composition aborted; the preedit text is discarded
+    Cancel,
+}
+
 // ============================================================================
 // Touch Events
 // ============================================================================
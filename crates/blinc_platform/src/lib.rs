@@ -53,8 +53,8 @@ mod window;
 pub use error::{PlatformError, Result};
 pub use event::{ControlFlow, Event, EventLoop, LifecycleEvent, WindowEvent};
 pub use input::{
-    InputEvent, Key, KeyState, KeyboardEvent, Modifiers, MouseButton, MouseEvent, ScrollPhase,
-    TouchEvent,
+    ImeEvent, InputEvent, Key, KeyState, KeyboardEvent, Modifiers, MouseButton, MouseEvent,
+    ScrollPhase, TouchEvent,
 };
 pub use platform::Platform;
 pub use window::{Cursor, Window, WindowConfig};
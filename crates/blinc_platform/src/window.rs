@@ -116,6 +116,17 @@ pub trait Window: Send {
 
     /// Check if the window is visible
     fn is_visible(&self) -> bool;
+
+    /// Enable or disable IME composition for this window
+    ///
+    /// Text widgets enable IME while focused. The default does nothing.
+    fn set_ime_allowed(&self, _allowed: bool) {}
+
+    /// Position the IME candidate window next to the caret
+    ///
+    /// The rectangle is in logical pixels relative to the window's content area.
+    /// The default does nothing.
+    fn set_ime_cursor_area(&self, _x: f32, _y: f32, _width: f32, _height: f32) {}
 }
 
 /// Cursor icons
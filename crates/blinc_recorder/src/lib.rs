@@ -47,8 +47,8 @@ pub use server::{
     ServerHandle, ServerMessage,
 };
 pub use session::{
-    RecordingConfig, RecordingExport, RecordingSession, SessionState, SessionStats,
-    SharedRecordingSession,
+    GlyphAtlasStats, GlyphAtlasStatsUpdate, RecordingConfig, RecordingExport, RecordingSession,
+    SessionState, SessionStats, SharedRecordingSession,
 };
 pub use testing::{
     compare_frames, CapturedFrame, FrameSequence, HeadlessConfig, HeadlessContext,
@@ -99,6 +99,13 @@ pub fn record_event(event: RecordedEvent) {
     }
 }
 
+/// Record glyph atlas statistics if a recorder is installed and recording.
+pub fn record_atlas_stats(update: GlyphAtlasStatsUpdate) {
+    if let Some(recorder) = get_recorder() {
+        recorder.record_atlas_stats(update);
+    }
+}
+
 /// Record a tree snapshot if a recorder is installed and recording.
 pub fn record_snapshot(snapshot: TreeSnapshot) -> Option<TreeDiff> {
     get_recorder().and_then(|r| r.record_snapshot(snapshot))
@@ -168,6 +175,8 @@ pub fn install_hooks() {
             Arc::new(|event_any: blinc_core::RecordedEventAny| {
                 if let Some(event) = event_any.downcast_ref::<RecordedEvent>() {
                     record_event(event.clone());
+                } else if let Some(update) = event_any.downcast_ref::<GlyphAtlasStatsUpdate>() {
+                    record_atlas_stats(*update);
                 }
             });
         ctx.set_recorder_event_callback(event_callback);
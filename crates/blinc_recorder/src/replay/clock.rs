@@ -4,6 +4,8 @@
 //! and played at different speeds.
 
 use crate::Timestamp;
+use blinc_animation::{ManualClock, SharedClock};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// A virtual clock for controlling replay timing.
@@ -12,6 +14,9 @@ use std::time::{Duration, Instant};
 /// - Paused/resumed
 /// - Seeked to any position
 /// - Played at different speeds (0.5x, 1x, 2x, etc.)
+///
+/// Its position also drives an animation clock (see [`VirtualClock::animation_clock`]),
+/// so animations in the replayed UI follow virtual time.
 #[derive(Debug)]
 pub struct VirtualClock {
     /// Current virtual time position.
@@ -24,6 +29,8 @@ pub struct VirtualClock {
     last_update: Option<Instant>,
     /// Duration of the recording (for bounds checking).
     duration: Timestamp,
+    /// Animation clock kept at `position`.
+    animation_clock: Arc<ManualClock>,
 }
 
 impl VirtualClock {
@@ -35,9 +42,25 @@ impl VirtualClock {
             speed: 1.0,
             last_update: None,
             duration,
+            animation_clock: Arc::new(ManualClock::new()),
         }
     }
 
+    /// Get an animation clock that follows this clock's position.
+    ///
+    /// Pass it to `AnimationScheduler::with_clock` so springs and keyframes
+    /// advance with virtual time, including speed changes and pauses.
+    pub fn animation_clock(&self) -> SharedClock {
+        self.animation_clock.clone()
+    }
+
+    /// Set the position and keep the animation clock in step.
+    fn set_position(&mut self, position: Timestamp) {
+        self.position = position;
+        self.animation_clock
+            .set(Duration::from_micros(position.as_micros()));
+    }
+
     /// Get the current virtual time position.
     pub fn position(&self) -> Timestamp {
         self.position
@@ -95,7 +118,7 @@ impl VirtualClock {
 
     /// Seek to a specific position.
     pub fn seek(&mut self, position: Timestamp) {
-        self.position = position.clamp(Timestamp::zero(), self.duration);
+        self.set_position(position.clamp(Timestamp::zero(), self.duration));
         self.last_update = if self.running {
             Some(Instant::now())
         } else {
@@ -145,11 +168,11 @@ impl VirtualClock {
             let scaled_micros = scaled_elapsed.as_micros() as u64;
 
             let new_pos = self.position.as_micros().saturating_add(scaled_micros);
-            self.position = Timestamp::from_micros(new_pos);
+            self.set_position(Timestamp::from_micros(new_pos));
 
             // Check if we've reached the end
             if self.position >= self.duration {
-                self.position = self.duration;
+                self.set_position(self.duration);
                 self.running = false;
                 self.last_update = None;
                 return true;
@@ -181,7 +204,7 @@ impl VirtualClock {
 
     /// Reset the clock to the beginning and stop.
     pub fn reset(&mut self) {
-        self.position = Timestamp::zero();
+        self.set_position(Timestamp::zero());
         self.running = false;
         self.last_update = None;
     }
@@ -196,6 +219,7 @@ impl Default for VirtualClock {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use blinc_animation::Clock;
 
     #[test]
     fn test_clock_creation() {
@@ -236,6 +260,21 @@ mod tests {
         assert_eq!(clock.position().as_micros(), 0);
     }
 
+    #[test]
+    fn test_animation_clock_follows_position() {
+        let mut clock = VirtualClock::new(Timestamp::from_micros(1_000_000));
+        let animation_clock = clock.animation_clock();
+
+        clock.seek(Timestamp::from_micros(250_000));
+        assert_eq!(animation_clock.now(), Duration::from_millis(250));
+
+        clock.step_forward();
+        assert_eq!(animation_clock.now(), Duration::from_micros(266_667));
+
+        clock.reset();
+        assert_eq!(animation_clock.now(), Duration::ZERO);
+    }
+
     #[test]
     fn test_progress() {
         let mut clock = VirtualClock::new(Timestamp::from_micros(1_000_000));
@@ -58,6 +58,42 @@ pub struct SessionStats {
     pub last_event_time: Option<Timestamp>,
     /// Last snapshot timestamp.
     pub last_snapshot_time: Option<Timestamp>,
+    /// Latest grayscale glyph atlas statistics.
+    #[serde(default)]
+    pub glyph_atlas: Option<GlyphAtlasStats>,
+    /// Latest color (emoji) glyph atlas statistics.
+    #[serde(default)]
+    pub color_glyph_atlas: Option<GlyphAtlasStats>,
+}
+
+/// Glyph atlas usage statistics reported by the renderer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GlyphAtlasStats {
+    /// Allocated atlas pages.
+    pub pages: u32,
+    /// Maximum number of pages.
+    pub max_pages: u32,
+    /// Cached glyphs.
+    pub glyphs: u64,
+    /// Fraction of allocated page area in use (0.0 to 1.0).
+    pub utilization: f32,
+    /// Glyphs evicted so far.
+    pub evictions: u64,
+    /// Defragmentation passes so far.
+    pub defragmentations: u64,
+    /// Glyph allocations that failed because the atlas was full.
+    pub allocation_failures: u64,
+    /// Bytes uploaded to the GPU so far.
+    pub uploaded_bytes: u64,
+}
+
+/// Glyph atlas statistics for both atlases, sent once per frame when changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GlyphAtlasStatsUpdate {
+    /// Grayscale text atlas.
+    pub grayscale: GlyphAtlasStats,
+    /// Color (emoji) atlas.
+    pub color: GlyphAtlasStats,
 }
 
 impl RecordingSession {
@@ -202,6 +238,15 @@ impl RecordingSession {
         diff
     }
 
+    /// Record the latest glyph atlas statistics.
+    pub fn record_atlas_stats(&mut self, update: GlyphAtlasStatsUpdate) {
+        if self.state != SessionState::Recording {
+            return;
+        }
+        self.stats.glyph_atlas = Some(update.grayscale);
+        self.stats.color_glyph_atlas = Some(update.color);
+    }
+
     /// Get recorded events.
     pub fn events(&self) -> &VecDeque<TimestampedEvent> {
         &self.events
@@ -306,6 +351,10 @@ impl SharedRecordingSession {
         self.inner.write().record_snapshot(snapshot)
     }
 
+    pub fn record_atlas_stats(&self, update: GlyphAtlasStatsUpdate) {
+        self.inner.write().record_atlas_stats(update);
+    }
+
     pub fn stats(&self) -> SessionStats {
         self.inner.read().stats().clone()
     }
@@ -379,4 +428,32 @@ mod tests {
         assert_eq!(session.stats().events_dropped, 2);
         assert_eq!(session.stats().total_events, 7);
     }
+
+    #[test]
+    fn test_atlas_stats_recording() {
+        let mut session = RecordingSession::new(RecordingConfig::minimal());
+        let update = GlyphAtlasStatsUpdate {
+            grayscale: GlyphAtlasStats {
+                pages: 2,
+                max_pages: 4,
+                glyphs: 300,
+                ..Default::default()
+            },
+            color: GlyphAtlasStats::default(),
+        };
+
+        // Ignored while idle
+        session.record_atlas_stats(update);
+        assert!(session.stats().glyph_atlas.is_none());
+
+        session.start();
+        session.record_atlas_stats(update);
+        assert_eq!(session.stats().glyph_atlas.unwrap().pages, 2);
+
+        // Older exports without atlas stats still deserialize
+        let json = r#"{"total_events":0,"total_snapshots":0,"events_dropped":0,
+            "snapshots_dropped":0,"last_event_time":null,"last_snapshot_time":null}"#;
+        let stats: SessionStats = serde_json::from_str(json).unwrap();
+        assert!(stats.color_glyph_atlas.is_none());
+    }
 }
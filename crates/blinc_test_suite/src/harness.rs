@@ -533,6 +533,7 @@ impl TestHarness {
                 atlas_view,
                 color_atlas_view,
                 text_ctx.sampler(),
+                text_ctx.atlas_generation(),
             );
         }
     }
@@ -1,17 +1,28 @@
 //! Glyph atlas management
 //!
-//! Manages a texture atlas for caching rendered glyphs. Uses a skyline/shelf
-//! packing algorithm for efficient space utilization.
+//! Manages paged texture atlases for caching rendered glyphs. Each page is
+//! packed with a skyline/shelf algorithm; when every page is full the atlas
+//! grows by a page (up to a limit), then reclaims the slots of glyphs that
+//! haven't been used for a number of frames.
 //!
 //! Provides two atlas types:
 //! - `GlyphAtlas`: Grayscale atlas for regular text glyphs
 //! - `ColorGlyphAtlas`: RGBA atlas for color emoji
+//!
+//! Pages map to layers of a GPU texture array. Modified areas are tracked
+//! per page so uploads only cover what changed.
 
 use crate::{Result, TextError};
 use rustc_hash::FxHashMap;
 
+/// Default maximum number of pages per atlas
+pub const DEFAULT_MAX_PAGES: u32 = 4;
+
+/// Default number of frames a glyph may go unused before it can be evicted
+pub const DEFAULT_EVICT_AFTER_FRAMES: u64 = 120;
+
 /// Region in the atlas texture
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct AtlasRegion {
     /// X position in atlas (pixels)
     pub x: u32,
@@ -32,13 +43,27 @@ impl AtlasRegion {
         let v_max = (self.y + self.height) as f32 / atlas_height as f32;
         [u_min, v_min, u_max, v_max]
     }
+
+    /// Smallest region covering both regions
+    fn union(&self, other: &AtlasRegion) -> AtlasRegion {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        AtlasRegion {
+            x,
+            y,
+            width: (self.x + self.width).max(other.x + other.width) - x,
+            height: (self.y + self.height).max(other.y + other.height) - y,
+        }
+    }
 }
 
 /// Information about a cached glyph
 #[derive(Debug, Clone, Copy)]
 pub struct GlyphInfo {
-    /// Region in the atlas texture
+    /// Region in the atlas page
     pub region: AtlasRegion,
+    /// Atlas page (texture array layer) holding the glyph
+    pub page: u32,
     /// Horizontal bearing (offset from origin to left edge)
     pub bearing_x: i16,
     /// Vertical bearing (offset from baseline to top edge)
@@ -49,6 +74,38 @@ pub struct GlyphInfo {
     pub font_size: f32,
 }
 
+/// A modified area of one atlas page that needs uploading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirtyRegion {
+    /// Page (texture array layer)
+    pub page: u32,
+    /// Modified area within the page
+    pub region: AtlasRegion,
+}
+
+/// Atlas usage statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AtlasStats {
+    /// Allocated pages
+    pub pages: u32,
+    /// Maximum number of pages
+    pub max_pages: u32,
+    /// Page dimensions in pixels
+    pub page_size: (u32, u32),
+    /// Cached glyphs
+    pub glyphs: usize,
+    /// Fraction of allocated page area covered by glyphs (0.0 to 1.0)
+    pub utilization: f32,
+    /// Glyphs evicted since creation
+    pub evictions: u64,
+    /// Defragmentation passes since creation
+    pub defragmentations: u64,
+    /// Allocations that failed because every page was full
+    pub allocation_failures: u64,
+    /// Bytes handed to the GPU through [`GlyphAtlas::mark_clean`]
+    pub uploaded_bytes: u64,
+}
+
 /// Key for glyph cache lookup
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct GlyphKey {
@@ -81,123 +138,428 @@ struct Shelf {
     height: u32,
     /// Current X position (next free space)
     x: u32,
+    /// Slots freed by eviction, as (x, padded width)
+    free: Vec<(u32, u32)>,
+    /// Number of glyphs living on this shelf
+    glyphs: u32,
 }
 
-/// Glyph atlas for caching rendered glyphs
-pub struct GlyphAtlas {
-    /// Atlas width in pixels
-    width: u32,
-    /// Atlas height in pixels
-    height: u32,
-    /// Pixel data (single channel, 8-bit grayscale or SDF values)
+/// One page (texture array layer) of an atlas
+#[derive(Debug)]
+struct Page {
+    /// Pixel data, `bytes_per_pixel` bytes per pixel
     pixels: Vec<u8>,
-    /// Cached glyph information
-    glyphs: FxHashMap<GlyphKey, GlyphInfo>,
     /// Shelves for skyline packing
     shelves: Vec<Shelf>,
+    /// Area modified since the last upload
+    dirty: Option<AtlasRegion>,
+}
+
+/// A cached glyph and its bookkeeping
+#[derive(Debug)]
+struct Entry {
+    info: GlyphInfo,
+    /// Index of the shelf holding the glyph
+    shelf: usize,
+    /// Frame the glyph was last looked up or inserted in
+    last_used: u64,
+}
+
+/// Paged glyph atlas storing `BPP` bytes per pixel
+///
+/// Use the [`GlyphAtlas`] (grayscale) and [`ColorGlyphAtlas`] (RGBA) aliases.
+pub struct PagedGlyphAtlas<const BPP: usize> {
+    /// Page width in pixels
+    width: u32,
+    /// Page height in pixels
+    height: u32,
+    /// Allocated pages
+    pages: Vec<Page>,
+    /// Maximum number of pages
+    max_pages: u32,
+    /// Frames a glyph may go unused before its slot can be reclaimed
+    evict_after: u64,
+    /// Cached glyph information
+    glyphs: FxHashMap<GlyphKey, Entry>,
     /// Padding between glyphs
     padding: u32,
-    /// Whether atlas data has been modified since last upload
-    dirty: bool,
+    /// Current frame number
+    frame: u64,
+    /// Set when an allocation failed; the next frame repacks the pages
+    needs_defragment: bool,
+    /// Most pages the atlas has ever held
+    peak_pages: usize,
+    /// Bumped whenever the page count exceeds its previous peak
+    generation: u64,
+    /// Cumulative statistics
+    evictions: u64,
+    defragmentations: u64,
+    allocation_failures: u64,
+    uploaded_bytes: u64,
 }
 
-impl GlyphAtlas {
-    /// Create a new glyph atlas
+/// Glyph atlas for caching rendered glyphs (single channel, 8-bit coverage)
+pub type GlyphAtlas = PagedGlyphAtlas<1>;
+
+/// Color glyph atlas for RGBA emoji (4 bytes per pixel)
+pub type ColorGlyphAtlas = PagedGlyphAtlas<4>;
+
+impl<const BPP: usize> PagedGlyphAtlas<BPP> {
+    /// Create a new atlas with one page of the given size
     pub fn new(width: u32, height: u32) -> Self {
-        Self {
+        let mut atlas = Self {
             width,
             height,
-            pixels: vec![0; (width * height) as usize],
+            pages: Vec::new(),
+            max_pages: DEFAULT_MAX_PAGES,
+            evict_after: DEFAULT_EVICT_AFTER_FRAMES,
             glyphs: FxHashMap::default(),
-            shelves: Vec::new(),
             padding: 2, // 2 pixel padding between glyphs
-            dirty: true,
-        }
+            frame: 0,
+            needs_defragment: false,
+            peak_pages: 0,
+            generation: 0,
+            evictions: 0,
+            defragmentations: 0,
+            allocation_failures: 0,
+            uploaded_bytes: 0,
+        };
+        atlas.add_page();
+        atlas
     }
 
-    /// Get atlas dimensions
+    /// Set the maximum number of pages the atlas may grow to
+    pub fn with_max_pages(mut self, max_pages: u32) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
+    /// Set how many frames a glyph may go unused before it can be evicted
+    ///
+    /// Glyphs used in the current frame are never evicted, so the minimum is 1.
+    pub fn with_evict_after_frames(mut self, frames: u64) -> Self {
+        self.evict_after = frames.max(1);
+        self
+    }
+
+    /// Get page dimensions
     pub fn dimensions(&self) -> (u32, u32) {
         (self.width, self.height)
     }
 
-    /// Get raw pixel data
+    /// Bytes per pixel (1 for grayscale, 4 for RGBA)
+    pub fn bytes_per_pixel(&self) -> u32 {
+        BPP as u32
+    }
+
+    /// Number of allocated pages
+    pub fn page_count(&self) -> u32 {
+        self.pages.len() as u32
+    }
+
+    /// Maximum number of pages
+    pub fn max_pages(&self) -> u32 {
+        self.max_pages
+    }
+
+    /// Counter that changes whenever the atlas needs more pages than ever before
+    ///
+    /// GPU textures are sized to the page count, so a new generation means the
+    /// texture (and its view) may have been recreated and anything bound to
+    /// the old one must be rebuilt.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Get raw pixel data of the first page
     pub fn pixels(&self) -> &[u8] {
-        &self.pixels
+        &self.pages[0].pixels
+    }
+
+    /// Get raw pixel data of a page
+    pub fn page_pixels(&self, page: u32) -> Option<&[u8]> {
+        self.pages.get(page as usize).map(|p| p.pixels.as_slice())
     }
 
     /// Check if atlas has been modified
     pub fn is_dirty(&self) -> bool {
-        self.dirty
+        self.pages.iter().any(|p| p.dirty.is_some())
+    }
+
+    /// Areas modified since the last upload, one per dirty page
+    pub fn dirty_regions(&self) -> Vec<DirtyRegion> {
+        self.pages
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| {
+                p.dirty.map(|region| DirtyRegion {
+                    page: i as u32,
+                    region,
+                })
+            })
+            .collect()
     }
 
     /// Mark atlas as clean (after GPU upload)
     pub fn mark_clean(&mut self) {
-        self.dirty = false;
+        for page in &mut self.pages {
+            if let Some(region) = page.dirty.take() {
+                self.uploaded_bytes += region.width as u64 * region.height as u64 * BPP as u64;
+            }
+        }
+    }
+
+    /// Advance the frame counter used for eviction
+    ///
+    /// Call once per frame, before preparing text. If an allocation failed
+    /// during the previous frame, stale glyphs are evicted and the pages are
+    /// repacked here, while no prepared glyph refers to them.
+    pub fn begin_frame(&mut self) {
+        self.frame += 1;
+        if self.needs_defragment {
+            self.needs_defragment = false;
+            self.evict_stale();
+            self.defragment();
+        }
     }
 
-    /// Look up a cached glyph
+    /// Look up a cached glyph without marking it as used
     pub fn get_glyph(&self, font_id: u32, glyph_id: u16, font_size: f32) -> Option<&GlyphInfo> {
         let key = GlyphKey::new(font_id, glyph_id, font_size);
-        self.glyphs.get(&key)
+        self.glyphs.get(&key).map(|e| &e.info)
     }
 
-    /// Allocate space for a glyph using skyline packing
-    fn allocate(&mut self, width: u32, height: u32) -> Result<AtlasRegion> {
+    /// Look up a cached glyph and mark it as used in the current frame
+    pub fn lookup(&mut self, font_id: u32, glyph_id: u16, font_size: f32) -> Option<GlyphInfo> {
+        let key = GlyphKey::new(font_id, glyph_id, font_size);
+        let frame = self.frame;
+        self.glyphs.get_mut(&key).map(|e| {
+            e.last_used = frame;
+            e.info
+        })
+    }
+
+    fn add_page(&mut self) {
+        let size = (self.width * self.height) as usize * BPP;
+        self.pages.push(Page {
+            pixels: vec![0; size],
+            shelves: Vec::new(),
+            dirty: Some(self.full_region()),
+        });
+        if self.pages.len() > self.peak_pages {
+            self.peak_pages = self.pages.len();
+            self.generation += 1;
+        }
+    }
+
+    fn full_region(&self) -> AtlasRegion {
+        AtlasRegion {
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    /// Allocate space on one page using skyline packing, reusing freed slots
+    fn allocate_on_page(
+        &mut self,
+        page: usize,
+        width: u32,
+        height: u32,
+    ) -> Option<(AtlasRegion, usize)> {
         let padded_width = width + self.padding;
         let padded_height = height + self.padding;
+        let atlas_width = self.width;
+        let shelves = &mut self.pages[page].shelves;
 
-        // Find best shelf (smallest height that fits)
-        let mut best_shelf = None;
+        // Find best shelf (smallest y that fits, in a freed slot or at the end)
+        let mut best: Option<(usize, Option<usize>)> = None;
         let mut best_y = u32::MAX;
-
-        for (i, shelf) in self.shelves.iter().enumerate() {
-            // Check if glyph fits in this shelf
-            if shelf.height >= padded_height
-                && shelf.x + padded_width <= self.width
-                && shelf.y < best_y
-            {
+        for (i, shelf) in shelves.iter().enumerate() {
+            if shelf.height < padded_height || shelf.y >= best_y {
+                continue;
+            }
+            let slot = shelf.free.iter().position(|&(_, w)| w >= padded_width);
+            if slot.is_some() || shelf.x + padded_width <= atlas_width {
                 best_y = shelf.y;
-                best_shelf = Some(i);
+                best = Some((i, slot));
             }
         }
 
-        if let Some(shelf_idx) = best_shelf {
-            // Use existing shelf
-            let shelf = &mut self.shelves[shelf_idx];
+        if let Some((shelf_idx, slot)) = best {
+            let shelf = &mut shelves[shelf_idx];
+            let x = match slot {
+                Some(slot) => {
+                    let (x, w) = shelf.free[slot];
+                    if w > padded_width {
+                        shelf.free[slot] = (x + padded_width, w - padded_width);
+                    } else {
+                        shelf.free.swap_remove(slot);
+                    }
+                    x
+                }
+                None => {
+                    let x = shelf.x;
+                    shelf.x += padded_width;
+                    x
+                }
+            };
+            shelf.glyphs += 1;
             let region = AtlasRegion {
-                x: shelf.x,
+                x,
                 y: shelf.y,
                 width,
                 height,
             };
-            shelf.x += padded_width;
-            return Ok(region);
+            return Some((region, shelf_idx));
         }
 
         // Create new shelf
-        let new_y = self.shelves.last().map(|s| s.y + s.height).unwrap_or(0);
-
-        if new_y + padded_height > self.height {
-            return Err(TextError::AtlasFull);
+        let new_y = shelves.last().map(|s| s.y + s.height).unwrap_or(0);
+        if new_y + padded_height > self.height || padded_width > atlas_width {
+            return None;
         }
 
+        shelves.push(Shelf {
+            y: new_y,
+            height: padded_height,
+            x: padded_width,
+            free: Vec::new(),
+            glyphs: 1,
+        });
+
         let region = AtlasRegion {
             x: 0,
             y: new_y,
             width,
             height,
         };
+        Some((region, shelves.len() - 1))
+    }
 
-        self.shelves.push(Shelf {
-            y: new_y,
-            height: padded_height,
-            x: padded_width,
+    /// Allocate space on any page, growing or evicting as needed
+    fn allocate(&mut self, width: u32, height: u32) -> Result<(AtlasRegion, u32, usize)> {
+        for pass in 0..2 {
+            for page in 0..self.pages.len() {
+                if let Some((region, shelf)) = self.allocate_on_page(page, width, height) {
+                    return Ok((region, page as u32, shelf));
+                }
+            }
+
+            if (self.pages.len() as u32) < self.max_pages {
+                self.add_page();
+                let page = self.pages.len() - 1;
+                if let Some((region, shelf)) = self.allocate_on_page(page, width, height) {
+                    return Ok((region, page as u32, shelf));
+                }
+                // Larger than a whole page
+                break;
+            }
+
+            if pass == 0 && self.evict_stale() == 0 {
+                break;
+            }
+        }
+
+        self.allocation_failures += 1;
+        self.needs_defragment = true;
+        Err(TextError::AtlasFull)
+    }
+
+    /// Evict glyphs unused for `evict_after` frames, freeing their slots
+    ///
+    /// Returns the number of evicted glyphs.
+    fn evict_stale(&mut self) -> usize {
+        let frame = self.frame;
+        let evict_after = self.evict_after;
+        let padding = self.padding;
+        let pages = &mut self.pages;
+        let before = self.glyphs.len();
+
+        self.glyphs.retain(|_, entry| {
+            if frame.saturating_sub(entry.last_used) < evict_after {
+                return true;
+            }
+            let region = entry.info.region;
+            if let Some(shelf) = pages
+                .get_mut(entry.info.page as usize)
+                .and_then(|p| p.shelves.get_mut(entry.shelf))
+            {
+                shelf.free.push((region.x, region.width + padding));
+                shelf.glyphs = shelf.glyphs.saturating_sub(1);
+            }
+            false
+        });
+
+        for page in pages.iter_mut() {
+            for shelf in &mut page.shelves {
+                if shelf.glyphs == 0 {
+                    // Whole shelf is free again
+                    shelf.x = 0;
+                    shelf.free.clear();
+                }
+            }
+            // Trailing empty shelves give their height back to the page
+            // (shelf indices of live glyphs are all below them)
+            while page.shelves.last().is_some_and(|s| s.glyphs == 0) {
+                page.shelves.pop();
+            }
+        }
+
+        let evicted = before - self.glyphs.len();
+        self.evictions += evicted as u64;
+        evicted
+    }
+
+    /// Repack all cached glyphs, tallest first, to remove fragmentation
+    ///
+    /// Glyphs move, so glyph instances prepared before this call have stale
+    /// UVs; call it between frames. All pages are marked dirty.
+    pub fn defragment(&mut self) {
+        let width = self.width;
+        let old_pages = std::mem::take(&mut self.pages);
+        let mut entries: Vec<(GlyphKey, Entry)> = self.glyphs.drain().collect();
+        entries.sort_by(|a, b| {
+            b.1.info
+                .region
+                .height
+                .cmp(&a.1.info.region.height)
+                .then(b.1.info.region.width.cmp(&a.1.info.region.width))
         });
 
-        Ok(region)
+        self.add_page();
+        for (key, mut entry) in entries {
+            let old = entry.info;
+            let src = &old_pages[old.page as usize].pixels;
+            let Ok((region, page, shelf)) = self.allocate(old.region.width, old.region.height)
+            else {
+                // Can't happen with the same page budget; drop the glyph
+                continue;
+            };
+            let dst = &mut self.pages[page as usize].pixels;
+            let row_bytes = old.region.width as usize * BPP;
+            for y in 0..old.region.height {
+                let s = ((old.region.y + y) * width + old.region.x) as usize * BPP;
+                let d = ((region.y + y) * width + region.x) as usize * BPP;
+                dst[d..d + row_bytes].copy_from_slice(&src[s..s + row_bytes]);
+            }
+            entry.info.region = region;
+            entry.info.page = page;
+            entry.shelf = shelf;
+            self.glyphs.insert(key, entry);
+        }
+
+        // Keep the page count so GPU texture layers stay valid
+        while self.pages.len() < old_pages.len() {
+            self.add_page();
+        }
+        self.defragmentations += 1;
     }
 
     /// Insert a rasterized glyph into the atlas
+    ///
+    /// `bitmap` holds `width * height` pixels of `bytes_per_pixel()` bytes.
     #[allow(clippy::too_many_arguments)]
     pub fn insert_glyph(
         &mut self,
@@ -211,48 +573,68 @@ impl GlyphAtlas {
         advance: u16,
         bitmap: &[u8],
     ) -> Result<GlyphInfo> {
-        let key = GlyphKey::new(font_id, glyph_id, font_size);
-
         // Check if already cached
-        if let Some(info) = self.glyphs.get(&key) {
-            return Ok(*info);
+        if let Some(info) = self.lookup(font_id, glyph_id, font_size) {
+            return Ok(info);
         }
 
         // Allocate region
-        let region = self.allocate(width, height)?;
-
-        // Copy bitmap to atlas
-        for y in 0..height {
-            let src_offset = (y * width) as usize;
-            let dst_offset = ((region.y + y) * self.width + region.x) as usize;
-            let row_end = src_offset + width as usize;
-
-            if row_end <= bitmap.len() && dst_offset + width as usize <= self.pixels.len() {
-                self.pixels[dst_offset..dst_offset + width as usize]
-                    .copy_from_slice(&bitmap[src_offset..row_end]);
+        let (region, page, shelf) = self.allocate(width, height)?;
+
+        // Copy bitmap to atlas, clearing the padding of reused slots
+        let atlas_width = self.width;
+        let page_data = &mut self.pages[page as usize];
+        let row_bytes = width as usize * BPP;
+        let padded_row_bytes = (width + self.padding).min(atlas_width - region.x) as usize * BPP;
+        let padded_height = (height + self.padding).min(self.height - region.y);
+        for y in 0..padded_height {
+            let dst_offset = ((region.y + y) * atlas_width + region.x) as usize * BPP;
+            let row = &mut page_data.pixels[dst_offset..dst_offset + padded_row_bytes];
+            row.fill(0);
+
+            let src_offset = (y * width) as usize * BPP;
+            if y < height && src_offset + row_bytes <= bitmap.len() {
+                row[..row_bytes].copy_from_slice(&bitmap[src_offset..src_offset + row_bytes]);
             }
         }
+        page_data.dirty = Some(match page_data.dirty {
+            Some(dirty) => dirty.union(&region),
+            None => region,
+        });
 
         let info = GlyphInfo {
             region,
+            page,
             bearing_x,
             bearing_y,
             advance,
             font_size,
         };
 
-        self.glyphs.insert(key, info);
-        self.dirty = true;
+        let key = GlyphKey::new(font_id, glyph_id, font_size);
+        self.glyphs.insert(
+            key,
+            Entry {
+                info,
+                shelf,
+                last_used: self.frame,
+            },
+        );
 
         Ok(info)
     }
 
-    /// Clear all cached glyphs
+    /// Clear all cached glyphs, keeping a single page
     pub fn clear(&mut self) {
         self.glyphs.clear();
-        self.shelves.clear();
-        self.pixels.fill(0);
-        self.dirty = true;
+        self.pages.truncate(1);
+        let full = self.full_region();
+        for page in &mut self.pages {
+            page.shelves.clear();
+            page.pixels.fill(0);
+            page.dirty = Some(full);
+        }
+        self.needs_defragment = false;
     }
 
     /// Get number of cached glyphs
@@ -260,239 +642,204 @@ impl GlyphAtlas {
         self.glyphs.len()
     }
 
-    /// Calculate atlas utilization (0.0 to 1.0)
+    /// Calculate atlas utilization across allocated pages (0.0 to 1.0)
     pub fn utilization(&self) -> f32 {
-        let used_height = self.shelves.last().map(|s| s.y + s.height).unwrap_or(0);
-        used_height as f32 / self.height as f32
+        let used: u64 = self
+            .glyphs
+            .values()
+            .map(|e| {
+                (e.info.region.width + self.padding) as u64
+                    * (e.info.region.height + self.padding) as u64
+            })
+            .sum();
+        let total = self.width as u64 * self.height as u64 * self.pages.len() as u64;
+        (used as f32 / total as f32).min(1.0)
+    }
+
+    /// Usage statistics
+    pub fn stats(&self) -> AtlasStats {
+        AtlasStats {
+            pages: self.page_count(),
+            max_pages: self.max_pages,
+            page_size: (self.width, self.height),
+            glyphs: self.glyphs.len(),
+            utilization: self.utilization(),
+            evictions: self.evictions,
+            defragmentations: self.defragmentations,
+            allocation_failures: self.allocation_failures,
+            uploaded_bytes: self.uploaded_bytes,
+        }
     }
 }
 
 impl Default for GlyphAtlas {
     fn default() -> Self {
-        // Default to 1024x1024 atlas (1 MB)
+        // Default to 1024x1024 pages (1 MB each)
         // This supports multiple fonts at various sizes for typical UI usage
         Self::new(1024, 1024)
     }
 }
 
-impl std::fmt::Debug for GlyphAtlas {
+impl Default for ColorGlyphAtlas {
+    fn default() -> Self {
+        // Default to 512x512 pages (1 MB each for RGBA)
+        // Color emoji are typically larger so we use a smaller atlas
+        Self::new(512, 512)
+    }
+}
+
+impl<const BPP: usize> std::fmt::Debug for PagedGlyphAtlas<BPP> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("GlyphAtlas")
+        let name = if BPP == 4 {
+            "ColorGlyphAtlas"
+        } else {
+            "GlyphAtlas"
+        };
+        f.debug_struct(name)
             .field("dimensions", &(self.width, self.height))
+            .field("pages", &(self.pages.len(), self.max_pages))
             .field("glyph_count", &self.glyphs.len())
             .field(
                 "utilization",
                 &format!("{:.1}%", self.utilization() * 100.0),
             )
-            .field("dirty", &self.dirty)
+            .field("dirty", &self.is_dirty())
             .finish()
     }
 }
 
-/// Color glyph atlas for RGBA emoji
-///
-/// Similar to GlyphAtlas but stores RGBA pixel data (4 bytes per pixel)
-/// for color emoji and other color glyphs.
-pub struct ColorGlyphAtlas {
-    /// Atlas width in pixels
-    width: u32,
-    /// Atlas height in pixels
-    height: u32,
-    /// Pixel data (RGBA, 4 bytes per pixel)
-    pixels: Vec<u8>,
-    /// Cached glyph information
-    glyphs: FxHashMap<GlyphKey, GlyphInfo>,
-    /// Shelves for skyline packing
-    shelves: Vec<Shelf>,
-    /// Padding between glyphs
-    padding: u32,
-    /// Whether atlas data has been modified since last upload
-    dirty: bool,
-}
-
-impl ColorGlyphAtlas {
-    /// Create a new color glyph atlas
-    pub fn new(width: u32, height: u32) -> Self {
-        Self {
-            width,
-            height,
-            pixels: vec![0; (width * height * 4) as usize], // RGBA = 4 bytes per pixel
-            glyphs: FxHashMap::default(),
-            shelves: Vec::new(),
-            padding: 2,
-            dirty: true,
-        }
-    }
-
-    /// Get atlas dimensions
-    pub fn dimensions(&self) -> (u32, u32) {
-        (self.width, self.height)
-    }
-
-    /// Get raw pixel data (RGBA format)
-    pub fn pixels(&self) -> &[u8] {
-        &self.pixels
-    }
-
-    /// Check if atlas has been modified
-    pub fn is_dirty(&self) -> bool {
-        self.dirty
-    }
-
-    /// Mark atlas as clean (after GPU upload)
-    pub fn mark_clean(&mut self) {
-        self.dirty = false;
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    /// Look up a cached glyph
-    pub fn get_glyph(&self, font_id: u32, glyph_id: u16, font_size: f32) -> Option<&GlyphInfo> {
-        let key = GlyphKey::new(font_id, glyph_id, font_size);
-        self.glyphs.get(&key)
+    fn insert(atlas: &mut GlyphAtlas, glyph_id: u16, size: u32) -> Result<GlyphInfo> {
+        let bitmap = vec![glyph_id as u8; (size * size) as usize];
+        atlas.insert_glyph(0, glyph_id, 16.0, size, size, 0, 0, size as u16, &bitmap)
     }
 
-    /// Allocate space for a glyph using skyline packing
-    fn allocate(&mut self, width: u32, height: u32) -> Result<AtlasRegion> {
-        let padded_width = width + self.padding;
-        let padded_height = height + self.padding;
-
-        // Find best shelf (smallest height that fits)
-        let mut best_shelf = None;
-        let mut best_y = u32::MAX;
-
-        for (i, shelf) in self.shelves.iter().enumerate() {
-            if shelf.height >= padded_height
-                && shelf.x + padded_width <= self.width
-                && shelf.y < best_y
-            {
-                best_y = shelf.y;
-                best_shelf = Some(i);
-            }
+    #[test]
+    fn test_grows_pages_then_fails() {
+        // 64x64 pages hold four 30px glyphs (32px padded)
+        let mut atlas = GlyphAtlas::new(64, 64).with_max_pages(2);
+        let generation = atlas.generation();
+        for id in 0..8 {
+            let info = insert(&mut atlas, id, 30).unwrap();
+            assert_eq!(info.page, id as u32 / 4);
         }
-
-        if let Some(shelf_idx) = best_shelf {
-            let shelf = &mut self.shelves[shelf_idx];
-            let region = AtlasRegion {
-                x: shelf.x,
-                y: shelf.y,
-                width,
-                height,
-            };
-            shelf.x += padded_width;
-            return Ok(region);
-        }
-
-        // Create new shelf
-        let new_y = self.shelves.last().map(|s| s.y + s.height).unwrap_or(0);
-
-        if new_y + padded_height > self.height {
-            return Err(TextError::AtlasFull);
-        }
-
-        let region = AtlasRegion {
-            x: 0,
-            y: new_y,
-            width,
-            height,
-        };
-
-        self.shelves.push(Shelf {
-            y: new_y,
-            height: padded_height,
-            x: padded_width,
-        });
-
-        Ok(region)
+        assert_eq!(atlas.page_count(), 2);
+        assert_eq!(atlas.generation(), generation + 1);
+        assert!(matches!(
+            insert(&mut atlas, 8, 30),
+            Err(TextError::AtlasFull)
+        ));
+        assert_eq!(atlas.stats().allocation_failures, 1);
+
+        // Already-cached glyphs are still found
+        assert_eq!(insert(&mut atlas, 3, 30).unwrap().page, 0);
     }
 
-    /// Insert a rasterized color glyph (RGBA) into the atlas
-    #[allow(clippy::too_many_arguments)]
-    pub fn insert_glyph(
-        &mut self,
-        font_id: u32,
-        glyph_id: u16,
-        font_size: f32,
-        width: u32,
-        height: u32,
-        bearing_x: i16,
-        bearing_y: i16,
-        advance: u16,
-        bitmap: &[u8],
-    ) -> Result<GlyphInfo> {
-        let key = GlyphKey::new(font_id, glyph_id, font_size);
-
-        // Check if already cached
-        if let Some(info) = self.glyphs.get(&key) {
-            return Ok(*info);
+    #[test]
+    fn test_lru_eviction_reuses_slots() {
+        let mut atlas = GlyphAtlas::new(64, 64)
+            .with_max_pages(1)
+            .with_evict_after_frames(2);
+        for id in 0..4 {
+            insert(&mut atlas, id, 30).unwrap();
         }
 
-        // Allocate region
-        let region = self.allocate(width, height)?;
-
-        // Copy RGBA bitmap to atlas (4 bytes per pixel)
-        for y in 0..height {
-            let src_offset = (y * width * 4) as usize;
-            let dst_offset = ((region.y + y) * self.width * 4 + region.x * 4) as usize;
-            let row_bytes = (width * 4) as usize;
-
-            if src_offset + row_bytes <= bitmap.len() && dst_offset + row_bytes <= self.pixels.len()
-            {
-                self.pixels[dst_offset..dst_offset + row_bytes]
-                    .copy_from_slice(&bitmap[src_offset..src_offset + row_bytes]);
-            }
-        }
-
-        let info = GlyphInfo {
-            region,
-            bearing_x,
-            bearing_y,
-            advance,
-            font_size,
-        };
-
-        self.glyphs.insert(key, info);
-        self.dirty = true;
-
-        Ok(info)
+        // Keep glyph 0 alive; the others go stale
+        atlas.begin_frame();
+        atlas.begin_frame();
+        assert!(atlas.lookup(0, 0, 16.0).is_some());
+
+        let info = insert(&mut atlas, 10, 30).unwrap();
+        assert_eq!(info.page, 0);
+        assert_eq!(atlas.glyph_count(), 2);
+        assert_eq!(atlas.stats().evictions, 3);
+        assert!(atlas.get_glyph(0, 0, 16.0).is_some());
+        assert!(atlas.get_glyph(0, 1, 16.0).is_none());
+
+        // The reused slot was overwritten with the new bitmap
+        let (w, _) = atlas.dimensions();
+        let p = (info.region.y * w + info.region.x) as usize;
+        assert_eq!(atlas.pixels()[p], 10);
     }
 
-    /// Clear all cached glyphs
-    pub fn clear(&mut self) {
-        self.glyphs.clear();
-        self.shelves.clear();
-        self.pixels.fill(0);
-        self.dirty = true;
+    #[test]
+    fn test_glyphs_used_this_frame_are_not_evicted() {
+        let mut atlas = GlyphAtlas::new(64, 64)
+            .with_max_pages(1)
+            .with_evict_after_frames(1);
+        for id in 0..4 {
+            insert(&mut atlas, id, 30).unwrap();
+        }
+        assert!(insert(&mut atlas, 4, 30).is_err());
+        assert_eq!(atlas.glyph_count(), 4);
     }
 
-    /// Get number of cached glyphs
-    pub fn glyph_count(&self) -> usize {
-        self.glyphs.len()
+    #[test]
+    fn test_defragment_preserves_pixels() {
+        let mut atlas = GlyphAtlas::new(64, 64).with_evict_after_frames(1);
+        insert(&mut atlas, 1, 10).unwrap();
+        insert(&mut atlas, 2, 20).unwrap();
+        insert(&mut atlas, 3, 30).unwrap();
+        atlas.mark_clean();
+
+        let generation = atlas.generation();
+        atlas.defragment();
+        assert_eq!(atlas.stats().defragmentations, 1);
+        assert!(atlas.is_dirty());
+        // Same page count, so GPU textures can be kept
+        assert_eq!(atlas.generation(), generation);
+
+        // Tallest glyph is packed first
+        let tall = *atlas.get_glyph(0, 3, 16.0).unwrap();
+        assert_eq!((tall.region.x, tall.region.y), (0, 0));
+        for id in 1..=3u16 {
+            let info = *atlas.get_glyph(0, id, 16.0).unwrap();
+            let (w, _) = atlas.dimensions();
+            let last =
+                ((info.region.y + info.region.height - 1) * w + info.region.x + info.region.width
+                    - 1) as usize;
+            assert_eq!(atlas.pixels()[last], id as u8);
+        }
     }
 
-    /// Calculate atlas utilization (0.0 to 1.0)
-    pub fn utilization(&self) -> f32 {
-        let used_height = self.shelves.last().map(|s| s.y + s.height).unwrap_or(0);
-        used_height as f32 / self.height as f32
-    }
-}
+    #[test]
+    fn test_dirty_regions_are_per_page() {
+        let mut atlas = GlyphAtlas::new(64, 64);
+        atlas.mark_clean();
+        assert!(!atlas.is_dirty());
+
+        insert(&mut atlas, 1, 10).unwrap();
+        insert(&mut atlas, 2, 10).unwrap();
+        let dirty = atlas.dirty_regions();
+        assert_eq!(dirty.len(), 1);
+        assert_eq!(dirty[0].page, 0);
+        assert_eq!(
+            dirty[0].region,
+            AtlasRegion {
+                x: 0,
+                y: 0,
+                width: 22,
+                height: 10
+            }
+        );
 
-impl Default for ColorGlyphAtlas {
-    fn default() -> Self {
-        // Default to 512x512 atlas (1 MB for RGBA)
-        // Color emoji are typically larger so we use a smaller atlas
-        Self::new(512, 512)
+        let uploaded = atlas.stats().uploaded_bytes;
+        atlas.mark_clean();
+        assert_eq!(atlas.stats().uploaded_bytes, uploaded + 22 * 10);
     }
-}
 
-impl std::fmt::Debug for ColorGlyphAtlas {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("ColorGlyphAtlas")
-            .field("dimensions", &(self.width, self.height))
-            .field("glyph_count", &self.glyphs.len())
-            .field(
-                "utilization",
-                &format!("{:.1}%", self.utilization() * 100.0),
-            )
-            .field("dirty", &self.dirty)
-            .finish()
+    #[test]
+    fn test_color_atlas_rgba() {
+        let mut atlas = ColorGlyphAtlas::new(32, 32);
+        let bitmap = [255u8, 0, 0, 255].repeat(4);
+        let info = atlas
+            .insert_glyph(0, 1, 16.0, 2, 2, 0, 0, 2, &bitmap)
+            .unwrap();
+        assert_eq!(atlas.bytes_per_pixel(), 4);
+        let p = ((info.region.y * 32 + info.region.x) * 4) as usize;
+        assert_eq!(&atlas.pixels()[p..p + 4], &[255, 0, 0, 255]);
     }
 }
@@ -0,0 +1,313 @@
+//! Bidirectional text support
+//!
+//! Applies the Unicode Bidirectional Algorithm (UAX #9) via `unicode-bidi`:
+//! embedding levels are resolved per paragraph, text is split into
+//! directional runs for shaping, and the glyphs of each laid-out line are
+//! reordered into visual order.
+
+use std::ops::Range;
+
+use unicode_bidi::{bidi_class, BidiClass, BidiInfo, Level};
+
+/// Base direction of a paragraph or run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextDirection {
+    /// Left-to-right (Latin, CJK, ...)
+    #[default]
+    Ltr,
+    /// Right-to-left (Arabic, Hebrew, ...)
+    Rtl,
+}
+
+impl TextDirection {
+    /// Whether this is right-to-left
+    pub fn is_rtl(self) -> bool {
+        self == TextDirection::Rtl
+    }
+
+    /// Direction of an embedding level (odd levels are right-to-left)
+    pub fn from_level(level: u8) -> Self {
+        if level % 2 == 1 {
+            TextDirection::Rtl
+        } else {
+            TextDirection::Ltr
+        }
+    }
+
+    fn level(self) -> Level {
+        match self {
+            TextDirection::Ltr => Level::ltr(),
+            TextDirection::Rtl => Level::rtl(),
+        }
+    }
+}
+
+/// A maximal run of text at a single embedding level, in logical order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BidiRun {
+    /// Byte range into the analyzed text
+    pub range: Range<usize>,
+    /// Resolved embedding level
+    pub level: u8,
+}
+
+impl BidiRun {
+    /// Direction the run is shaped in
+    pub fn direction(&self) -> TextDirection {
+        TextDirection::from_level(self.level)
+    }
+}
+
+/// Check whether text contains any right-to-left or explicit bidi characters
+///
+/// Text without them lays out identically with or without the bidi
+/// algorithm, so callers use this to keep the fast left-to-right path.
+pub fn contains_rtl(text: &str) -> bool {
+    text.chars().any(|c| {
+        matches!(
+            bidi_class(c),
+            BidiClass::R
+                | BidiClass::AL
+                | BidiClass::AN
+                | BidiClass::RLE
+                | BidiClass::RLO
+                | BidiClass::RLI
+        )
+    })
+}
+
+/// Detect the base direction of text from its first strong character
+///
+/// Returns `None` when the text has no strongly directional characters
+/// (e.g. only digits and punctuation).
+pub fn detect_direction(text: &str) -> Option<TextDirection> {
+    text.chars().find_map(|c| match bidi_class(c) {
+        BidiClass::L => Some(TextDirection::Ltr),
+        BidiClass::R | BidiClass::AL => Some(TextDirection::Rtl),
+        _ => None,
+    })
+}
+
+/// Resolved bidi levels for a text string
+#[derive(Debug, Clone)]
+pub struct BidiText {
+    /// Embedding level of every byte
+    levels: Vec<u8>,
+    /// Original bidi class of every byte (needed for the per-line L1 reset)
+    classes: Vec<BidiClass>,
+    /// Paragraph byte ranges and their base directions
+    paragraphs: Vec<(Range<usize>, TextDirection)>,
+}
+
+impl BidiText {
+    /// Analyze text, splitting it into paragraphs at paragraph separators
+    ///
+    /// With `base: None` each paragraph takes its direction from its first
+    /// strong character.
+    pub fn new(text: &str, base: Option<TextDirection>) -> Self {
+        let info = BidiInfo::new(text, base.map(TextDirection::level));
+        Self {
+            levels: info.levels.iter().map(|l| l.number()).collect(),
+            classes: info.original_classes.clone(),
+            paragraphs: info
+                .paragraphs
+                .iter()
+                .map(|p| {
+                    let direction = if p.level.is_rtl() {
+                        TextDirection::Rtl
+                    } else {
+                        TextDirection::Ltr
+                    };
+                    (p.range.clone(), direction)
+                })
+                .collect(),
+        }
+    }
+
+    /// Resolved embedding level at a byte offset, before line breaking
+    ///
+    /// This does not include rule L1, which depends on where lines end; use
+    /// [`BidiText::line_levels`] when placing glyphs or carets on a line.
+    pub fn level_at(&self, byte: usize) -> u8 {
+        self.levels.get(byte).copied().unwrap_or_else(|| {
+            self.paragraphs
+                .last()
+                .map(|(_, d)| d.is_rtl() as u8)
+                .unwrap_or(0)
+        })
+    }
+
+    /// Embedding levels of the bytes in a line, with rule L1 applied
+    ///
+    /// Segment and paragraph separators, and any whitespace, isolate or
+    /// formatting characters before them or at the end of the line, are reset
+    /// to the paragraph level.
+    pub fn line_levels(&self, line: Range<usize>) -> Vec<u8> {
+        let end = line.end.min(self.levels.len());
+        let start = line.start.min(end);
+        let base = self.paragraph_direction(start).is_rtl() as u8;
+        let mut levels = self.levels[start..end].to_vec();
+
+        let mut trailing = true;
+        for (level, class) in levels.iter_mut().zip(&self.classes[start..end]).rev() {
+            match class {
+                BidiClass::B | BidiClass::S => {
+                    *level = base;
+                    trailing = true;
+                }
+                BidiClass::WS
+                | BidiClass::FSI
+                | BidiClass::LRI
+                | BidiClass::RLI
+                | BidiClass::PDI
+                | BidiClass::BN
+                | BidiClass::LRE
+                | BidiClass::RLE
+                | BidiClass::LRO
+                | BidiClass::RLO
+                | BidiClass::PDF => {
+                    if trailing {
+                        *level = base;
+                    }
+                }
+                _ => trailing = false,
+            }
+        }
+        levels
+    }
+
+    /// Base direction of the paragraph containing a byte offset
+    pub fn paragraph_direction(&self, byte: usize) -> TextDirection {
+        self.paragraphs
+            .iter()
+            .find(|(range, _)| range.contains(&byte))
+            .or(self.paragraphs.last())
+            .map(|(_, d)| *d)
+            .unwrap_or_default()
+    }
+
+    /// Level runs in logical order, split at paragraph boundaries
+    pub fn runs(&self) -> Vec<BidiRun> {
+        let mut runs = Vec::new();
+        for (range, _) in &self.paragraphs {
+            let mut start = range.start;
+            for i in range.clone() {
+                if self.levels[i] != self.levels[start] {
+                    runs.push(BidiRun {
+                        range: start..i,
+                        level: self.levels[start],
+                    });
+                    start = i;
+                }
+            }
+            if start < range.end {
+                runs.push(BidiRun {
+                    range: start..range.end,
+                    level: self.levels[start],
+                });
+            }
+        }
+        runs
+    }
+}
+
+/// Visual order of items from their embedding levels (UAX #9 rule L2)
+///
+/// From the highest level down to the lowest odd level, every maximal
+/// sequence at that level or higher is reversed. Returns logical indices
+/// in visual (left-to-right) order.
+pub fn visual_order(levels: &[u8]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..levels.len()).collect();
+    let Some(&highest) = levels.iter().max() else {
+        return order;
+    };
+    let lowest_odd = levels
+        .iter()
+        .copied()
+        .filter(|l| l % 2 == 1)
+        .min()
+        .unwrap_or(highest + 1);
+
+    let mut level = highest;
+    while level >= lowest_odd && level > 0 {
+        let mut i = 0;
+        while i < order.len() {
+            if levels[order[i]] >= level {
+                let start = i;
+                while i < order.len() && levels[order[i]] >= level {
+                    i += 1;
+                }
+                order[start..i].reverse();
+            } else {
+                i += 1;
+            }
+        }
+        level -= 1;
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_direction() {
+        assert_eq!(detect_direction("hello"), Some(TextDirection::Ltr));
+        assert_eq!(detect_direction("123 שלום"), Some(TextDirection::Rtl));
+        assert_eq!(detect_direction("123 !"), None);
+        assert!(contains_rtl("abc مرحبا"));
+        assert!(!contains_rtl("abc 123"));
+    }
+
+    #[test]
+    fn test_runs_and_levels() {
+        // Hebrew paragraph with an embedded Latin word
+        let text = "שלום abc עולם";
+        let bidi = BidiText::new(text, None);
+        assert_eq!(bidi.paragraph_direction(0), TextDirection::Rtl);
+
+        let runs = bidi.runs();
+        let directions: Vec<_> = runs.iter().map(|r| r.direction()).collect();
+        assert_eq!(
+            directions,
+            vec![TextDirection::Rtl, TextDirection::Ltr, TextDirection::Rtl]
+        );
+        assert_eq!(&text[runs[1].range.clone()], "abc");
+    }
+
+    #[test]
+    fn test_line_levels_reset_trailing_whitespace() {
+        // LTR word inside an RTL paragraph, broken after "abc "
+        let text = "שלום abc \tdef";
+        let bidi = BidiText::new(text, None);
+        let line_end = text.find("def").unwrap();
+        let levels = bidi.line_levels(0..line_end);
+
+        // Before L1 the space and tab after "abc" sit at the embedded level
+        let space = text.find(" \t").unwrap();
+        assert_eq!(bidi.level_at(space), 2);
+        // At the end of the line they take the paragraph level
+        assert_eq!(levels[space], 1);
+        assert_eq!(levels[space + 1], 1);
+        assert_eq!(levels[text.find("abc").unwrap()], 2);
+
+        // The tab is a segment separator: the spaces before it reset too,
+        // even mid-line
+        let text = "abc  \tשלום";
+        let bidi = BidiText::new(text, Some(TextDirection::Rtl));
+        let levels = bidi.line_levels(0..text.len());
+        assert_eq!(&levels[3..6], &[1, 1, 1]);
+        assert_eq!(levels[0], 2);
+    }
+
+    #[test]
+    fn test_visual_order() {
+        assert_eq!(visual_order(&[0, 0, 0]), vec![0, 1, 2]);
+        assert_eq!(visual_order(&[1, 1, 1]), vec![2, 1, 0]);
+        // LTR paragraph with an RTL run in the middle
+        assert_eq!(visual_order(&[0, 1, 1, 0]), vec![0, 2, 1, 3]);
+        // Numbers (level 2) inside RTL text keep their own order
+        assert_eq!(visual_order(&[1, 2, 2, 1]), vec![3, 1, 2, 0]);
+    }
+}
@@ -148,6 +148,19 @@ impl FontMetrics {
     }
 }
 
+/// A variation axis of a variable font
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VariationAxis {
+    /// Axis tag (e.g. `wght`, `wdth`)
+    pub tag: [u8; 4],
+    /// Minimum axis value
+    pub min: f32,
+    /// Default axis value
+    pub default: f32,
+    /// Maximum axis value
+    pub max: f32,
+}
+
 /// A parsed font face
 pub struct FontFace {
     /// Raw font data (kept alive for ttf-parser) - can be owned or memory-mapped
@@ -299,6 +312,64 @@ impl FontFace {
         self.glyph_id(c).map(|id| id != 0).unwrap_or(false)
     }
 
+    /// Variation axes of a variable font (empty for static fonts)
+    pub fn variation_axes(&self) -> Vec<VariationAxis> {
+        self.as_ttf_face()
+            .map(|face| {
+                face.variation_axes()
+                    .into_iter()
+                    .map(|axis| VariationAxis {
+                        tag: axis.tag.to_bytes(),
+                        min: axis.min_value,
+                        default: axis.def_value,
+                        max: axis.max_value,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether this is a variable font
+    pub fn is_variable(&self) -> bool {
+        self.as_ttf_face().is_some_and(|face| face.is_variable())
+    }
+
+    /// Resolve variation settings against this font's axes
+    ///
+    /// Explicit settings are kept (clamped to the axis range). When the
+    /// font has a `wght` axis and none is set, the requested CSS weight is
+    /// applied, so one variable font file serves every weight.
+    pub fn resolve_variations(
+        &self,
+        settings: &blinc_core::FontSettings,
+        weight: u16,
+    ) -> Vec<blinc_core::FontVariation> {
+        let axes = self.variation_axes();
+        if axes.is_empty() {
+            return Vec::new();
+        }
+        let mut resolved: Vec<_> = settings
+            .variations
+            .iter()
+            .filter_map(|v| {
+                let axis = axes.iter().find(|a| a.tag == v.tag)?;
+                Some(blinc_core::FontVariation {
+                    tag: v.tag,
+                    value: v.value.clamp(axis.min, axis.max),
+                })
+            })
+            .collect();
+        if let Some(axis) = axes.iter().find(|a| &a.tag == b"wght") {
+            if !resolved.iter().any(|v| &v.tag == b"wght") && weight as f32 != axis.default {
+                resolved.push(blinc_core::FontVariation {
+                    tag: *b"wght",
+                    value: (weight as f32).clamp(axis.min, axis.max),
+                });
+            }
+        }
+        resolved
+    }
+
     /// Get horizontal advance width for a glyph in font units
     pub fn glyph_advance(&self, glyph_id: u16) -> Option<u16> {
         self.as_ttf_face()
@@ -0,0 +1,230 @@
+//! Automatic hyphenation
+//!
+//! Implements Frank Liang's pattern-based hyphenation, the algorithm used by
+//! TeX. Patterns are registered per language; the TeX `hyph-en-us` patterns
+//! and exceptions are built in, and other pattern files from the
+//! `hyph-utf8` project (`hyph-*.pat.txt`) can be registered at runtime with
+//! [`register_hyphenator`].
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// US English patterns (Liang's `hyphen.tex`, as shipped in `hyph-utf8`; see `patterns/NOTICE`)
+const ENGLISH_PATTERNS: &str = include_str!("patterns/hyph-en-us.pat.txt");
+
+/// US English whole-word exceptions that accompany [`ENGLISH_PATTERNS`]
+const ENGLISH_EXCEPTIONS: &str = include_str!("patterns/hyph-en-us.hyp.txt");
+
+/// A hyphenation dictionary for one language
+#[derive(Debug, Clone)]
+pub struct Hyphenator {
+    /// Pattern letters -> inter-letter values (one more value than letters)
+    patterns: HashMap<String, Vec<u8>>,
+    /// Whole-word exceptions -> allowed break positions (char indices)
+    exceptions: HashMap<String, Vec<usize>>,
+    /// Longest pattern, in characters
+    max_pattern_len: usize,
+    /// Minimum characters before the first hyphen
+    left_min: usize,
+    /// Minimum characters after the last hyphen
+    right_min: usize,
+}
+
+impl Hyphenator {
+    /// Build from whitespace-separated TeX patterns (e.g. `"hy3ph he2n"`)
+    pub fn from_patterns(patterns: &str) -> Self {
+        let mut table = HashMap::new();
+        let mut max_pattern_len = 0;
+
+        for pattern in patterns.split_whitespace() {
+            let mut letters = String::new();
+            let mut values = vec![0u8];
+            for c in pattern.chars() {
+                match c.to_digit(10) {
+                    Some(d) => *values.last_mut().unwrap() = d as u8,
+                    None => {
+                        letters.push(c);
+                        values.push(0);
+                    }
+                }
+            }
+            max_pattern_len = max_pattern_len.max(letters.chars().count());
+            // Repeated patterns combine, keeping the highest value per gap
+            let entry = table
+                .entry(letters)
+                .or_insert_with(|| vec![0; values.len()]);
+            for (current, value) in entry.iter_mut().zip(values) {
+                *current = (*current).max(value);
+            }
+        }
+
+        Self {
+            patterns: table,
+            exceptions: HashMap::new(),
+            max_pattern_len,
+            left_min: 2,
+            right_min: 3,
+        }
+    }
+
+    /// Add whole-word exceptions written with explicit hyphens (`"ta-ble"`)
+    pub fn with_exceptions(mut self, exceptions: &str) -> Self {
+        for word in exceptions.split_whitespace() {
+            let mut positions = Vec::new();
+            let mut letters = String::new();
+            for c in word.chars() {
+                if c == '-' {
+                    positions.push(letters.chars().count());
+                } else {
+                    letters.extend(c.to_lowercase());
+                }
+            }
+            self.exceptions.insert(letters, positions);
+        }
+        self
+    }
+
+    /// Set the minimum number of characters kept before/after a hyphen
+    pub fn with_min(mut self, left: usize, right: usize) -> Self {
+        self.left_min = left.max(1);
+        self.right_min = right.max(1);
+        self
+    }
+
+    /// Byte offsets within `word` where a hyphen may be inserted
+    pub fn hyphenate(&self, word: &str) -> Vec<usize> {
+        let chars: Vec<char> = word.chars().collect();
+        let n = chars.len();
+        if n < self.left_min + self.right_min {
+            return Vec::new();
+        }
+
+        let lower: String = chars.iter().flat_map(|c| c.to_lowercase()).collect();
+        let positions: Vec<usize> = match self.exceptions.get(&lower) {
+            Some(positions) => positions.clone(),
+            None => self.pattern_positions(&lower),
+        };
+
+        let offsets: Vec<usize> = word.char_indices().map(|(i, _)| i).collect();
+        positions
+            .into_iter()
+            .filter(|&p| p >= self.left_min && p + self.right_min <= n)
+            .filter_map(|p| offsets.get(p).copied())
+            .collect()
+    }
+
+    /// Char positions with an odd inter-letter value (a break before that char)
+    fn pattern_positions(&self, word: &str) -> Vec<usize> {
+        // Word boundaries are matched with '.' as in TeX patterns
+        let padded: Vec<char> = std::iter::once('.')
+            .chain(word.chars())
+            .chain(std::iter::once('.'))
+            .collect();
+        let mut values = vec![0u8; padded.len() + 1];
+
+        for start in 0..padded.len() {
+            let mut key = String::new();
+            for &c in padded[start..].iter().take(self.max_pattern_len) {
+                key.push(c);
+                if let Some(pattern) = self.patterns.get(&key) {
+                    for (k, &v) in pattern.iter().enumerate() {
+                        values[start + k] = values[start + k].max(v);
+                    }
+                }
+            }
+        }
+
+        // values[m] is the gap before padded[m]; char p of the word is padded[p + 1]
+        (1..word.chars().count())
+            .filter(|&p| values[p + 1] % 2 == 1)
+            .collect()
+    }
+}
+
+fn registry() -> &'static RwLock<HashMap<String, Arc<Hyphenator>>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, Arc<Hyphenator>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let mut map = HashMap::new();
+        map.insert(
+            "en".to_string(),
+            Arc::new(
+                Hyphenator::from_patterns(ENGLISH_PATTERNS).with_exceptions(ENGLISH_EXCEPTIONS),
+            ),
+        );
+        RwLock::new(map)
+    })
+}
+
+/// Register (or replace) the hyphenator for a language tag (e.g. `"de"`, `"en-gb"`)
+pub fn register_hyphenator(language: &str, hyphenator: Hyphenator) {
+    if let Ok(mut map) = registry().write() {
+        map.insert(language.to_ascii_lowercase(), Arc::new(hyphenator));
+    }
+}
+
+/// Look up the hyphenator for a language tag
+///
+/// Falls back from a full tag to its primary subtag (`"en-US"` -> `"en"`).
+pub fn hyphenator_for(language: &str) -> Option<Arc<Hyphenator>> {
+    let tag = language.to_ascii_lowercase().replace('_', "-");
+    let map = registry().read().ok()?;
+    map.get(&tag)
+        .or_else(|| map.get(tag.split('-').next().unwrap_or_default()))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(word: &str, hyphenator: &Hyphenator) -> String {
+        let mut out = String::new();
+        let points = hyphenator.hyphenate(word);
+        for (i, c) in word.char_indices() {
+            if points.contains(&i) {
+                out.push('-');
+            }
+            out.push(c);
+        }
+        out
+    }
+
+    #[test]
+    fn test_liang_patterns() {
+        let en = hyphenator_for("en-US").unwrap();
+        // Reference hyphenations produced by TeX with the same patterns
+        for expected in [
+            "hy-phen-ation",
+            "con-cate-na-tion",
+            "math-e-mat-ics",
+            "ty-pog-ra-phy",
+            "in-de-pen-dent",
+            "al-go-rithm",
+            "com-puter",
+            "ex-am-ple",
+            "su-per-cal-ifrag-ilis-tic-ex-pi-ali-do-cious",
+        ] {
+            assert_eq!(split(&expected.replace('-', ""), &en), expected);
+        }
+        // Too short to hyphenate with the default 2/3 minimums
+        assert_eq!(split("bless", &en), "bless");
+    }
+
+    #[test]
+    fn test_builtin_exceptions() {
+        let en = hyphenator_for("en").unwrap();
+        assert_eq!(split("table", &en), "ta-ble");
+        assert_eq!(split("present", &en), "present");
+        assert_eq!(split("associate", &en), "as-so-ciate");
+    }
+
+    #[test]
+    fn test_exceptions_and_registry() {
+        let custom = Hyphenator::from_patterns("").with_exceptions("ta-ble");
+        assert_eq!(split("Table", &custom), "Ta-ble");
+
+        register_hyphenator("xx", custom);
+        assert!(hyphenator_for("xx-YY").is_some());
+        assert!(hyphenator_for("zz").is_none());
+    }
+}
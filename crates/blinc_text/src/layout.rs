@@ -2,9 +2,18 @@
 //!
 //! Handles line breaking, text measurement, and multi-line layout.
 
+use std::collections::HashSet;
+
+use blinc_core::FontSettings;
+
+use crate::bidi::{contains_rtl, visual_order, BidiText, TextDirection};
 use crate::font::FontFace;
+use crate::hyphenation::hyphenator_for;
 use crate::shaper::{ShapedGlyph, ShapedText, TextShaper};
 
+/// Soft hyphen (U+00AD): an invisible break opportunity inside a word
+const SOFT_HYPHEN: char = '\u{AD}';
+
 /// Text alignment options (horizontal)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum TextAlignment {
@@ -12,6 +21,39 @@ pub enum TextAlignment {
     Left,
     Center,
     Right,
+    /// Start edge of the paragraph direction (left in LTR, right in RTL)
+    Start,
+    /// End edge of the paragraph direction (right in LTR, left in RTL)
+    End,
+    /// Stretch wrapped lines to fill the width; the last line of each
+    /// paragraph is start-aligned
+    Justify,
+}
+
+impl TextAlignment {
+    /// Resolve `Start`/`End` against a paragraph direction
+    pub fn resolve(self, direction: TextDirection) -> TextAlignment {
+        match (self, direction) {
+            (TextAlignment::Start, TextDirection::Ltr)
+            | (TextAlignment::End, TextDirection::Rtl) => TextAlignment::Left,
+            (TextAlignment::Start, TextDirection::Rtl)
+            | (TextAlignment::End, TextDirection::Ltr) => TextAlignment::Right,
+            (other, _) => other,
+        }
+    }
+
+    /// Horizontal offset of a line of `line_width` within `available` pixels
+    fn offset(self, direction: TextDirection, available: f32, line_width: f32) -> f32 {
+        let alignment = match self {
+            TextAlignment::Justify => TextAlignment::Start,
+            other => other,
+        };
+        match alignment.resolve(direction) {
+            TextAlignment::Center => (available - line_width) / 2.0,
+            TextAlignment::Right => available - line_width,
+            _ => 0.0,
+        }
+    }
 }
 
 /// Vertical anchor point for text positioning
@@ -38,6 +80,77 @@ pub enum LineBreakMode {
     None,
 }
 
+/// How justified lines distribute extra space
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextJustify {
+    /// Widen the spaces between words
+    #[default]
+    InterWord,
+    /// Spread space between every character (suits CJK text)
+    InterCharacter,
+}
+
+/// Hyphenation mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Hyphens {
+    /// Never hyphenate, not even at soft hyphens
+    None,
+    /// Break only at soft hyphens (U+00AD)
+    #[default]
+    Manual,
+    /// Hyphenate words automatically using the language's patterns
+    Auto,
+}
+
+/// White-space handling, as in CSS `white-space`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WhiteSpace {
+    /// Collapse spaces and newlines, wrap lines
+    Normal,
+    /// Collapse spaces and newlines, never wrap
+    NoWrap,
+    /// Preserve spaces and newlines, never wrap
+    Pre,
+    /// Preserve spaces and newlines, wrap lines
+    #[default]
+    PreWrap,
+    /// Collapse spaces but keep newlines, wrap lines
+    PreLine,
+}
+
+impl WhiteSpace {
+    /// Whether runs of spaces collapse into one
+    pub fn collapses_spaces(self) -> bool {
+        matches!(
+            self,
+            WhiteSpace::Normal | WhiteSpace::NoWrap | WhiteSpace::PreLine
+        )
+    }
+
+    /// Whether newlines in the source force line breaks
+    pub fn preserves_newlines(self) -> bool {
+        matches!(
+            self,
+            WhiteSpace::Pre | WhiteSpace::PreWrap | WhiteSpace::PreLine
+        )
+    }
+
+    /// Whether lines wrap at the available width
+    pub fn wraps(self) -> bool {
+        !matches!(self, WhiteSpace::NoWrap | WhiteSpace::Pre)
+    }
+}
+
+/// How text that overflows its line is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextOverflow {
+    /// Overflowing text is left to the renderer's clip
+    #[default]
+    Clip,
+    /// Overflowing lines end in an ellipsis ("…")
+    Ellipsis,
+}
+
 /// Options for text layout
 #[derive(Debug, Clone)]
 pub struct LayoutOptions {
@@ -53,6 +166,22 @@ pub struct LayoutOptions {
     pub line_height: f32,
     /// Letter spacing adjustment in pixels
     pub letter_spacing: f32,
+    /// Base paragraph direction (None = detect from the first strong character)
+    pub direction: Option<TextDirection>,
+    /// How `TextAlignment::Justify` distributes extra space
+    pub justify: TextJustify,
+    /// Hyphenation mode
+    pub hyphens: Hyphens,
+    /// Language tag used to pick hyphenation patterns (None = English)
+    pub language: Option<String>,
+    /// White-space collapsing and wrapping
+    pub white_space: WhiteSpace,
+    /// How lines wider than `max_width` are shown
+    pub overflow: TextOverflow,
+    /// Maximum number of lines; the last kept line ends in an ellipsis
+    pub max_lines: Option<usize>,
+    /// OpenType features and variable font axes used for shaping
+    pub font_settings: FontSettings,
 }
 
 impl Default for LayoutOptions {
@@ -64,6 +193,14 @@ impl Default for LayoutOptions {
             line_break: LineBreakMode::Word,
             line_height: 1.2,
             letter_spacing: 0.0,
+            direction: None,
+            justify: TextJustify::InterWord,
+            hyphens: Hyphens::Manual,
+            language: None,
+            white_space: WhiteSpace::PreWrap,
+            overflow: TextOverflow::Clip,
+            max_lines: None,
+            font_settings: FontSettings::default(),
         }
     }
 }
@@ -95,6 +232,8 @@ pub struct LayoutLine {
     pub width: f32,
     /// Baseline Y position
     pub baseline_y: f32,
+    /// Direction of the paragraph this line belongs to
+    pub direction: TextDirection,
 }
 
 /// Result of laying out text
@@ -156,186 +295,133 @@ impl TextLayoutEngine {
 
         let ascender = metrics.ascender_px(font_size);
 
-        // Check for explicit newlines - these are always respected regardless of wrap mode
-        let has_newlines = text.contains('\n');
-
-        // Shape the entire text first
-        let shaped = self.shaper.shape(text, font, font_size);
-
-        // If no wrapping AND no explicit newlines, return single line
-        if (options.max_width.is_none() || options.line_break == LineBreakMode::None)
-            && !has_newlines
-        {
-            let mut line = self.create_line(&shaped, 0.0, ascender, options);
-            let width = line.width;
-
-            // Apply alignment if max_width is set
-            if let Some(max_width) = options.max_width {
-                if options.alignment != TextAlignment::Left && width < max_width {
-                    let offset = match options.alignment {
-                        TextAlignment::Center => (max_width - width) / 2.0,
-                        TextAlignment::Right => max_width - width,
-                        TextAlignment::Left => 0.0,
-                    };
-                    for glyph in &mut line.glyphs {
-                        glyph.x += offset;
-                    }
-                }
-            }
+        // Resolve bidi levels only when the text needs them; pure LTR text
+        // keeps the single-pass shaping path
+        let bidi = (options.direction == Some(TextDirection::Rtl) || contains_rtl(text))
+            .then(|| BidiText::new(text, options.direction));
 
-            return TextLayout {
-                lines: vec![line],
-                width,
-                height: line_height,
-            };
-        }
+        // Shape the entire text first (per directional run for bidi text)
+        let mut shaped = match &bidi {
+            Some(bidi) => self.shape_bidi(text, bidi, font, font_size, options),
+            None => self.shape(text, font, font_size, options),
+        };
 
-        // Handle explicit newlines without word wrapping
-        if has_newlines
-            && (options.max_width.is_none() || options.line_break == LineBreakMode::None)
-        {
-            return self.layout_with_newlines_only(
-                text,
-                &shaped,
-                font,
-                font_size,
-                ascender,
-                line_height,
-                options,
+        if options.white_space.collapses_spaces() {
+            let space = self
+                .shape(" ", font, font_size, options)
+                .glyphs
+                .first()
+                .copied();
+            collapse_whitespace(
+                &mut shaped.glyphs,
+                options.white_space.preserves_newlines(),
+                space,
             );
         }
-
-        let max_width = options.max_width.unwrap();
-
-        // Break into lines
-        let lines = self.break_lines(text, &shaped, font, font_size, max_width, options);
-
-        // Position lines
-        let mut positioned_lines = Vec::with_capacity(lines.len());
-        let mut y = ascender;
-        let mut max_width_found = 0.0f32;
-
-        for line_glyphs in lines {
-            let shaped_line = ShapedText {
-                glyphs: line_glyphs,
-                total_advance: 0, // Will be recalculated
-                font_size,
-                units_per_em: metrics.units_per_em,
-            };
-
-            let line = self.create_line(&shaped_line, 0.0, y, options);
-            max_width_found = max_width_found.max(line.width);
-            positioned_lines.push(line);
-            y += line_height;
+        // Soft hyphens are invisible unless a line breaks at them
+        for glyph in &mut shaped.glyphs {
+            if glyph.codepoint == SOFT_HYPHEN {
+                glyph.x_advance = 0;
+            }
         }
 
-        // Apply alignment
-        if options.alignment != TextAlignment::Left {
-            for line in &mut positioned_lines {
-                let offset = match options.alignment {
-                    TextAlignment::Center => (max_width - line.width) / 2.0,
-                    TextAlignment::Right => max_width - line.width,
-                    TextAlignment::Left => 0.0,
+        // Break into lines: at the available width when wrapping, otherwise
+        // only at explicit newlines (which are always respected)
+        let wraps = options.line_break != LineBreakMode::None && options.white_space.wraps();
+        let mut lines = match options.max_width {
+            Some(max_width) if wraps => {
+                let hyphen_width = if options.hyphens == Hyphens::None {
+                    0.0
+                } else {
+                    self.marker_width("-", font, font_size, options)
                 };
-                for glyph in &mut line.glyphs {
-                    glyph.x += offset;
-                }
+                self.break_lines(text, &shaped, max_width, hyphen_width, options)
             }
-        }
-
-        // Height is number of lines * line_height (minimum 1 line)
-        let height = (positioned_lines.len().max(1) as f32) * line_height;
+            _ => split_hard_lines(&shaped.glyphs),
+        };
 
-        TextLayout {
-            lines: positioned_lines,
-            width: max_width_found,
-            height,
+        // Line clamping ends the last kept line with an ellipsis; with
+        // `TextOverflow::Ellipsis` so does any line that still overflows
+        let clamped = options.max_lines.is_some_and(|max| lines.len() > max);
+        if let Some(max) = options.max_lines {
+            lines.truncate(max.max(1));
         }
-    }
-
-    /// Layout text that contains explicit newlines but no word wrapping
-    #[allow(clippy::too_many_arguments)]
-    fn layout_with_newlines_only(
-        &self,
-        text: &str,
-        shaped: &ShapedText,
-        _font: &FontFace,
-        _font_size: f32,
-        ascender: f32,
-        line_height: f32,
-        options: &LayoutOptions,
-    ) -> TextLayout {
-        let mut lines = Vec::new();
-        let mut current_line: Vec<ShapedGlyph> = Vec::new();
-
-        // Split glyphs by newline character
-        for glyph in &shaped.glyphs {
-            if glyph.codepoint == '\n' {
-                // End current line, don't include the newline glyph
-                lines.push(std::mem::take(&mut current_line));
-            } else {
-                current_line.push(*glyph);
+        let limit = options.max_width.unwrap_or(f32::INFINITY);
+        let last = lines.len().saturating_sub(1);
+        let mut ellipsis = None;
+        for (i, line) in lines.iter_mut().enumerate() {
+            let overflows = options.overflow == TextOverflow::Ellipsis
+                && glyphs_width(&shaped, &line.glyphs, options.letter_spacing) > limit;
+            if overflows || (clamped && i == last) {
+                let marker =
+                    ellipsis.get_or_insert_with(|| self.ellipsis_glyphs(font, font_size, options));
+                ellipsize(line, marker, limit, &shaped, options.letter_spacing);
             }
         }
 
-        // Add remaining line
-        if !current_line.is_empty() {
-            lines.push(current_line);
-        }
-
-        // Handle case where text ends with newline
-        if text.ends_with('\n') {
-            lines.push(Vec::new());
+        // Drop soft hyphens and show a hyphen where a line broke inside a word
+        let mut hyphen = None;
+        for line in &mut lines {
+            line.glyphs.retain(|g| g.codepoint != SOFT_HYPHEN);
+            if line.hyphenated {
+                let marker =
+                    hyphen.get_or_insert_with(|| self.shape("-", font, font_size, options).glyphs);
+                let cluster = line.glyphs.last().map_or(0, |g| g.cluster);
+                line.glyphs
+                    .extend(marker.iter().map(|g| ShapedGlyph { cluster, ..*g }));
+            }
         }
 
         // Position lines
         let mut positioned_lines = Vec::with_capacity(lines.len());
         let mut y = ascender;
         let mut max_width_found = 0.0f32;
-        let metrics_units_per_em = shaped.units_per_em;
 
-        for line_glyphs in lines {
+        for line in lines {
+            let justify_to = options
+                .max_width
+                .filter(|_| options.alignment == TextAlignment::Justify && !line.hard_break);
             let shaped_line = ShapedText {
-                glyphs: line_glyphs,
-                total_advance: 0,
-                font_size: shaped.font_size,
-                units_per_em: metrics_units_per_em,
+                glyphs: line.glyphs,
+                total_advance: 0, // Will be recalculated
+                font_size,
+                units_per_em: metrics.units_per_em,
             };
 
-            let line = self.create_line(&shaped_line, 0.0, y, options);
-            max_width_found = max_width_found.max(line.width);
-            positioned_lines.push(line);
-            y += line_height;
-        }
+            let mut positioned =
+                self.position_line(shaped_line, y, options, bidi.as_ref(), justify_to);
 
-        // Apply alignment if max_width is set
-        if let Some(max_width) = options.max_width {
-            if options.alignment != TextAlignment::Left {
-                for line in &mut positioned_lines {
-                    if line.width < max_width {
-                        let offset = match options.alignment {
-                            TextAlignment::Center => (max_width - line.width) / 2.0,
-                            TextAlignment::Right => max_width - line.width,
-                            TextAlignment::Left => 0.0,
-                        };
-                        for glyph in &mut line.glyphs {
-                            glyph.x += offset;
-                        }
+            // Apply alignment if max_width is set; lines that overflow stay
+            // at the start edge
+            if let Some(max_width) = options.max_width {
+                let offset = if justify_to.is_some() {
+                    TextAlignment::Start.offset(positioned.direction, max_width, positioned.width)
+                } else if positioned.width < max_width {
+                    options
+                        .alignment
+                        .offset(positioned.direction, max_width, positioned.width)
+                } else {
+                    0.0
+                };
+                if offset != 0.0 {
+                    for glyph in &mut positioned.glyphs {
+                        glyph.x += offset;
                     }
                 }
             }
+
+            max_width_found = max_width_found.max(positioned.width);
+            positioned_lines.push(positioned);
+            y += line_height;
         }
 
-        let total_height = if positioned_lines.is_empty() {
-            line_height
-        } else {
-            (positioned_lines.len() as f32) * line_height
-        };
+        // Height is number of lines * line_height (minimum 1 line)
+        let height = (positioned_lines.len().max(1) as f32) * line_height;
 
         TextLayout {
             lines: positioned_lines,
             width: max_width_found,
-            height: total_height,
+            height,
         }
     }
 
@@ -369,19 +455,129 @@ impl TextLayoutEngine {
             glyphs,
             width: x - start_x,
             baseline_y,
+            direction: TextDirection::Ltr,
+        }
+    }
+
+    /// Shape text with the options' font features and variations
+    fn shape(
+        &self,
+        text: &str,
+        font: &FontFace,
+        font_size: f32,
+        options: &LayoutOptions,
+    ) -> ShapedText {
+        self.shaper
+            .shape_with_settings(text, font, font_size, None, &options.font_settings)
+    }
+
+    /// Shape bidi text run by run, returning glyphs in logical order
+    ///
+    /// Line breaking works on logical order; each line is reordered into
+    /// visual order afterwards by [`Self::position_line`].
+    fn shape_bidi(
+        &self,
+        text: &str,
+        bidi: &BidiText,
+        font: &FontFace,
+        font_size: f32,
+        options: &LayoutOptions,
+    ) -> ShapedText {
+        let mut glyphs = Vec::with_capacity(text.len());
+        let mut total_advance = 0;
+
+        for run in bidi.runs() {
+            let mut shaped = self.shaper.shape_with_settings(
+                &text[run.range.clone()],
+                font,
+                font_size,
+                Some(run.direction()),
+                &options.font_settings,
+            );
+            if run.direction().is_rtl() {
+                shaped.glyphs.reverse();
+            }
+            total_advance += shaped.total_advance;
+            glyphs.extend(shaped.glyphs.into_iter().map(|mut glyph| {
+                glyph.cluster += run.range.start as u32;
+                glyph
+            }));
+        }
+
+        ShapedText {
+            glyphs,
+            total_advance,
+            font_size,
+            units_per_em: font.metrics().units_per_em,
+        }
+    }
+
+    /// Position one line, reordering bidi glyphs into visual order first
+    ///
+    /// With `justify_to` set, the line is stretched to that width.
+    fn position_line(
+        &self,
+        mut shaped: ShapedText,
+        baseline_y: f32,
+        options: &LayoutOptions,
+        bidi: Option<&BidiText>,
+        justify_to: Option<f32>,
+    ) -> LayoutLine {
+        let mut direction = TextDirection::Ltr;
+        if let Some(bidi) = bidi {
+            if let Some(first) = shaped.glyphs.first() {
+                direction = bidi.paragraph_direction(first.cluster as usize);
+            }
+            reorder_visual(&mut shaped.glyphs, bidi);
+        }
+
+        let mut line = self.create_line(&shaped, 0.0, baseline_y, options);
+        line.direction = direction;
+        if let Some(target) = justify_to {
+            justify_line(&mut line, &shaped, target, options);
+        }
+        line
+    }
+
+    /// Width of a short marker string (hyphen, ellipsis) in pixels
+    fn marker_width(
+        &self,
+        marker: &str,
+        font: &FontFace,
+        font_size: f32,
+        options: &LayoutOptions,
+    ) -> f32 {
+        let shaped = self.shape(marker, font, font_size, options);
+        glyphs_width(&shaped, &shaped.glyphs, options.letter_spacing)
+    }
+
+    /// Ellipsis glyphs, falling back to "..." when the font lacks "…"
+    fn ellipsis_glyphs(
+        &self,
+        font: &FontFace,
+        font_size: f32,
+        options: &LayoutOptions,
+    ) -> Vec<ShapedGlyph> {
+        let glyphs = self.shape("\u{2026}", font, font_size, options).glyphs;
+        if glyphs.iter().any(|g| g.glyph_id == 0) {
+            self.shape("...", font, font_size, options).glyphs
+        } else {
+            glyphs
         }
     }
 
     /// Break text into lines based on max width
+    ///
+    /// `hyphen_width` is the width of the hyphen shown when a line breaks at
+    /// a soft hyphen or an automatic hyphenation point.
     fn break_lines(
         &self,
         text: &str,
         shaped: &ShapedText,
-        _font: &FontFace,
-        _font_size: f32,
         max_width: f32,
+        hyphen_width: f32,
         options: &LayoutOptions,
-    ) -> Vec<Vec<ShapedGlyph>> {
+    ) -> Vec<BrokenLine> {
         let mut lines = Vec::new();
         let mut current_line: Vec<ShapedGlyph> = Vec::new();
         let mut line_width = 0.0f32;
@@ -393,14 +589,22 @@ impl TextLayoutEngine {
             .map(|(i, _)| i)
             .collect();
 
+        // Positions where automatic hyphenation may break a word
+        let hyphen_points = hyphenation_points(text, options);
+
         let mut last_word_end = 0;
+        // Whether the break at last_word_end splits a word (needs a hyphen)
+        let mut last_break_hyphenated = false;
+        let mut prev_cluster = None;
 
         for glyph in shaped.glyphs.iter() {
             // Handle explicit newline - always force a line break
             if glyph.codepoint == '\n' {
-                lines.push(std::mem::take(&mut current_line));
+                lines.push(BrokenLine::hard(std::mem::take(&mut current_line)));
                 line_width = 0.0;
                 last_word_end = 0;
+                last_break_hyphenated = false;
+                prev_cluster = None;
                 continue; // Don't include the newline glyph itself
             }
 
@@ -409,6 +613,19 @@ impl TextLayoutEngine {
             // Check if this is a word boundary (at a whitespace character)
             let is_word_break = word_breaks.contains(&(glyph.cluster as usize));
 
+            // A hyphenation point allows a break before this glyph, provided
+            // the hyphen still fits on the line
+            let starts_cluster = prev_cluster != Some(glyph.cluster);
+            prev_cluster = Some(glyph.cluster);
+            if starts_cluster
+                && !current_line.is_empty()
+                && hyphen_points.contains(&(glyph.cluster as usize))
+                && line_width + hyphen_width <= max_width
+            {
+                last_word_end = current_line.len();
+                last_break_hyphenated = true;
+            }
+
             // Check if adding this glyph would overflow the line
             if line_width + advance > max_width && !current_line.is_empty() {
                 let mut broke_line = false;
@@ -419,7 +636,11 @@ impl TextLayoutEngine {
                             // Keep glyphs 0..last_word_end on current line
                             // Move glyphs last_word_end.. to next line
                             let remaining: Vec<_> = current_line.drain(last_word_end..).collect();
-                            lines.push(std::mem::take(&mut current_line));
+                            lines.push(BrokenLine {
+                                glyphs: std::mem::take(&mut current_line),
+                                hard_break: false,
+                                hyphenated: last_break_hyphenated,
+                            });
 
                             // Start new line with remaining glyphs (skip leading whitespace)
                             for g in remaining {
@@ -435,20 +656,23 @@ impl TextLayoutEngine {
                                 .map(|g| shaped.scale(g.x_advance) + options.letter_spacing)
                                 .sum();
                             last_word_end = 0;
+                            last_break_hyphenated = false;
                             broke_line = true;
                         } else {
                             // No word boundary found - break at current position (character break)
-                            lines.push(std::mem::take(&mut current_line));
+                            lines.push(BrokenLine::soft(std::mem::take(&mut current_line)));
                             line_width = 0.0;
                             last_word_end = 0;
+                            last_break_hyphenated = false;
                             broke_line = true;
                         }
                     }
                     LineBreakMode::Character => {
                         // Break at current position
-                        lines.push(std::mem::take(&mut current_line));
+                        lines.push(BrokenLine::soft(std::mem::take(&mut current_line)));
                         line_width = 0.0;
                         last_word_end = 0;
+                        last_break_hyphenated = false;
                         broke_line = true;
                     }
                     LineBreakMode::None => {
@@ -470,6 +694,7 @@ impl TextLayoutEngine {
                     // Update word boundary if this glyph is a word break
                     if is_word_break {
                         last_word_end = current_line.len();
+                        last_break_hyphenated = false;
                     }
                     continue; // Move to next glyph
                 }
@@ -490,12 +715,20 @@ impl TextLayoutEngine {
             if is_word_break {
                 // Mark position AFTER this whitespace as potential break point
                 last_word_end = current_line.len();
+                last_break_hyphenated = false;
+            } else if glyph.codepoint == SOFT_HYPHEN
+                && options.hyphens != Hyphens::None
+                && line_width + hyphen_width <= max_width
+            {
+                // Soft hyphen: break after it, showing a hyphen
+                last_word_end = current_line.len();
+                last_break_hyphenated = true;
             }
         }
 
         // Add remaining line
         if !current_line.is_empty() {
-            lines.push(current_line);
+            lines.push(BrokenLine::hard(current_line));
         }
 
         lines
@@ -514,6 +747,238 @@ impl TextLayoutEngine {
     }
 }
 
+/// Reorder a line's logical glyphs into visual order
+fn reorder_visual(glyphs: &mut Vec<ShapedGlyph>, bidi: &BidiText) {
+    let (Some(start), Some(end)) = (
+        glyphs.iter().map(|g| g.cluster as usize).min(),
+        glyphs
+            .iter()
+            .map(|g| g.cluster as usize + g.codepoint.len_utf8())
+            .max(),
+    ) else {
+        return;
+    };
+
+    // Line-level embedding levels, with trailing whitespace reset (rule L1)
+    let line_levels = bidi.line_levels(start..end);
+    let levels: Vec<u8> = glyphs
+        .iter()
+        .map(|g| {
+            line_levels
+                .get(g.cluster as usize - start)
+                .copied()
+                .unwrap_or_else(|| bidi.level_at(g.cluster as usize))
+        })
+        .collect();
+
+    let logical = std::mem::take(glyphs);
+    glyphs.extend(visual_order(&levels).into_iter().map(|i| logical[i]));
+}
+
+/// A line produced by line breaking, before positioning
+struct BrokenLine {
+    glyphs: Vec<ShapedGlyph>,
+    /// Ended by a newline or the end of the text (not justified)
+    hard_break: bool,
+    /// Broke inside a word, so a hyphen is shown at the end
+    hyphenated: bool,
+}
+
+impl BrokenLine {
+    fn hard(glyphs: Vec<ShapedGlyph>) -> Self {
+        Self {
+            glyphs,
+            hard_break: true,
+            hyphenated: false,
+        }
+    }
+
+    fn soft(glyphs: Vec<ShapedGlyph>) -> Self {
+        Self {
+            glyphs,
+            hard_break: false,
+            hyphenated: false,
+        }
+    }
+}
+
+/// Split glyphs at newlines only (the non-wrapping path)
+fn split_hard_lines(glyphs: &[ShapedGlyph]) -> Vec<BrokenLine> {
+    let mut lines = Vec::new();
+    let mut current = Vec::new();
+    for glyph in glyphs {
+        if glyph.codepoint == '\n' {
+            lines.push(BrokenLine::hard(std::mem::take(&mut current)));
+        } else {
+            current.push(*glyph);
+        }
+    }
+    lines.push(BrokenLine::hard(current));
+    lines
+}
+
+/// Width of a run of glyphs in pixels
+fn glyphs_width(shaped: &ShapedText, glyphs: &[ShapedGlyph], letter_spacing: f32) -> f32 {
+    glyphs
+        .iter()
+        .map(|g| shaped.scale(g.x_advance) + letter_spacing)
+        .sum()
+}
+
+/// Collapse white-space runs into single spaces
+///
+/// Tabs and (unless kept) newlines become spaces; leading and trailing
+/// white-space is dropped, as are spaces next to a kept newline.
+fn collapse_whitespace(
+    glyphs: &mut Vec<ShapedGlyph>,
+    keep_newlines: bool,
+    space: Option<ShapedGlyph>,
+) {
+    let mut out: Vec<ShapedGlyph> = Vec::with_capacity(glyphs.len());
+    for glyph in glyphs.drain(..) {
+        if keep_newlines && glyph.codepoint == '\n' {
+            while out.last().is_some_and(|g| g.codepoint == ' ') {
+                out.pop();
+            }
+            out.push(glyph);
+        } else if glyph.codepoint.is_whitespace() {
+            let after_break = out
+                .last()
+                .map_or(true, |g| g.codepoint == ' ' || g.codepoint == '\n');
+            if !after_break {
+                let mut collapsed = space.unwrap_or(glyph);
+                collapsed.cluster = glyph.cluster;
+                collapsed.codepoint = ' ';
+                out.push(collapsed);
+            }
+        } else {
+            out.push(glyph);
+        }
+    }
+    while out.last().is_some_and(|g| g.codepoint == ' ') {
+        out.pop();
+    }
+    *glyphs = out;
+}
+
+/// Byte offsets where automatic hyphenation may break a word
+fn hyphenation_points(text: &str, options: &LayoutOptions) -> HashSet<usize> {
+    let mut points = HashSet::new();
+    if options.hyphens != Hyphens::Auto {
+        return points;
+    }
+    let Some(hyphenator) = hyphenator_for(options.language.as_deref().unwrap_or("en")) else {
+        return points;
+    };
+
+    let mut word_start = None;
+    for (i, c) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        match (c.is_alphabetic(), word_start) {
+            (true, None) => word_start = Some(i),
+            (false, Some(start)) => {
+                points.extend(
+                    hyphenator
+                        .hyphenate(&text[start..i])
+                        .into_iter()
+                        .map(|p| start + p),
+                );
+                word_start = None;
+            }
+            _ => {}
+        }
+    }
+    points
+}
+
+/// Shorten a line until it fits `max_width` with the ellipsis appended
+///
+/// Whole clusters are removed, then trailing white-space, so the ellipsis
+/// follows the last visible character.
+fn ellipsize(
+    line: &mut BrokenLine,
+    ellipsis: &[ShapedGlyph],
+    max_width: f32,
+    shaped: &ShapedText,
+    letter_spacing: f32,
+) {
+    let ellipsis_width = glyphs_width(shaped, ellipsis, letter_spacing);
+    line.glyphs.retain(|g| g.codepoint != SOFT_HYPHEN);
+    while glyphs_width(shaped, &line.glyphs, letter_spacing) + ellipsis_width > max_width {
+        let Some(last) = line.glyphs.last().map(|g| g.cluster) else {
+            break;
+        };
+        while line.glyphs.last().is_some_and(|g| g.cluster == last) {
+            line.glyphs.pop();
+        }
+    }
+    while line
+        .glyphs
+        .last()
+        .is_some_and(|g| g.codepoint.is_whitespace())
+    {
+        line.glyphs.pop();
+    }
+
+    let cluster = line.glyphs.last().map_or(0, |g| g.cluster);
+    line.glyphs
+        .extend(ellipsis.iter().map(|g| ShapedGlyph { cluster, ..*g }));
+    line.hyphenated = false;
+    line.hard_break = true;
+}
+
+/// Stretch a positioned line to `target` pixels
+///
+/// Extra space goes after white-space (inter-word) or between clusters
+/// (inter-character); leading and trailing white-space is left alone.
+fn justify_line(line: &mut LayoutLine, shaped: &ShapedText, target: f32, options: &LayoutOptions) {
+    let glyphs = &shaped.glyphs;
+    let Some(first) = glyphs.iter().position(|g| !g.codepoint.is_whitespace()) else {
+        return;
+    };
+    let last = glyphs
+        .iter()
+        .rposition(|g| !g.codepoint.is_whitespace())
+        .unwrap_or(first);
+
+    let visible = glyphs_width(shaped, &glyphs[first..=last], options.letter_spacing);
+    let extra = target - visible;
+    if extra <= 0.0 {
+        return;
+    }
+
+    // Gap after glyph i, for i in first..last
+    let boundary = |i: usize| glyphs[i].cluster != glyphs[i + 1].cluster;
+    let mut gaps: Vec<usize> = match options.justify {
+        TextJustify::InterWord => (first..last)
+            .filter(|&i| glyphs[i].codepoint.is_whitespace() && boundary(i))
+            .collect(),
+        TextJustify::InterCharacter => Vec::new(),
+    };
+    if gaps.is_empty() {
+        gaps = (first..last).filter(|&i| boundary(i)).collect();
+    }
+    if gaps.is_empty() {
+        return;
+    }
+
+    let per_gap = extra / gaps.len() as f32;
+    let mut shift = 0.0;
+    let mut gap_iter = gaps.iter().peekable();
+    for (i, glyph) in line.glyphs.iter_mut().enumerate() {
+        glyph.x += shift;
+        if gap_iter.peek() == Some(&&i) {
+            gap_iter.next();
+            shift += per_gap;
+        }
+    }
+
+    let pen_before_first = glyphs_width(shaped, &glyphs[..first], options.letter_spacing);
+    line.width = pen_before_first + target;
+}
+
 impl Default for TextLayoutEngine {
     fn default() -> Self {
         Self::new()
@@ -910,4 +1375,163 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_alignment_resolves_against_direction() {
+        assert_eq!(
+            TextAlignment::Start.resolve(TextDirection::Ltr),
+            TextAlignment::Left
+        );
+        assert_eq!(
+            TextAlignment::Start.resolve(TextDirection::Rtl),
+            TextAlignment::Right
+        );
+        assert_eq!(
+            TextAlignment::End.resolve(TextDirection::Rtl),
+            TextAlignment::Left
+        );
+        assert_eq!(
+            TextAlignment::Center.resolve(TextDirection::Rtl),
+            TextAlignment::Center
+        );
+    }
+
+    #[test]
+    fn test_bidi_line_visual_order() {
+        let engine = TextLayoutEngine::new();
+        let options = LayoutOptions::default();
+
+        // LTR paragraph with an embedded Hebrew word
+        let text = "ab שלום";
+        let bidi = BidiText::new(text, None);
+        let line = engine.position_line(
+            create_mock_shaped_text(text),
+            0.0,
+            &options,
+            Some(&bidi),
+            None,
+        );
+        let visual: String = line.glyphs.iter().map(|g| g.codepoint).collect();
+        assert_eq!(line.direction, TextDirection::Ltr);
+        assert_eq!(visual, "ab םולש");
+        assert!(line.glyphs.windows(2).all(|w| w[0].x < w[1].x));
+
+        // RTL paragraph: the Latin word ends up on the left
+        let text = "שלום ab ";
+        let bidi = BidiText::new(text, None);
+        let line = engine.position_line(
+            create_mock_shaped_text(text),
+            0.0,
+            &options,
+            Some(&bidi),
+            None,
+        );
+        let visual: String = line.glyphs.iter().map(|g| g.codepoint).collect();
+        assert_eq!(line.direction, TextDirection::Rtl);
+        // Trailing whitespace stays at the paragraph end (visually leftmost)
+        assert_eq!(visual, " ab םולש");
+    }
+    fn line_text(line: &BrokenLine) -> String {
+        line.glyphs.iter().map(|g| g.codepoint).collect()
+    }
+
+    #[test]
+    fn test_collapse_whitespace() {
+        let mut shaped = create_mock_shaped_text("  a \t b \n c  ");
+        collapse_whitespace(&mut shaped.glyphs, false, None);
+        let text: String = shaped.glyphs.iter().map(|g| g.codepoint).collect();
+        assert_eq!(text, "a b c");
+
+        // pre-line keeps newlines but drops the spaces around them
+        let mut shaped = create_mock_shaped_text("a  \n  b");
+        collapse_whitespace(&mut shaped.glyphs, true, None);
+        let text: String = shaped.glyphs.iter().map(|g| g.codepoint).collect();
+        assert_eq!(text, "a\nb");
+    }
+
+    #[test]
+    fn test_break_lines_hyphenation() {
+        let engine = TextLayoutEngine::new();
+        let options = LayoutOptions {
+            hyphens: Hyphens::Auto,
+            ..Default::default()
+        };
+
+        // "hyphenation" is 110px; "hyphen" + hyphen fits in 75px
+        let text = "hyphenation";
+        let lines = engine.break_lines(text, &create_mock_shaped_text(text), 75.0, 5.0, &options);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(line_text(&lines[0]), "hyphen");
+        assert!(lines[0].hyphenated && !lines[0].hard_break);
+        assert!(lines[1].hard_break);
+
+        // Soft hyphens are break opportunities even without auto hyphenation
+        let text = "abc\u{AD}defgh";
+        let options = LayoutOptions::default();
+        let lines = engine.break_lines(text, &create_mock_shaped_text(text), 60.0, 5.0, &options);
+        assert_eq!(line_text(&lines[0]), "abc\u{AD}");
+        assert!(lines[0].hyphenated);
+        assert_eq!(line_text(&lines[1]), "defgh");
+    }
+
+    #[test]
+    fn test_ellipsize_line() {
+        let shaped = create_mock_shaped_text("hello world");
+        let ellipsis = create_mock_shaped_text("\u{2026}").glyphs;
+        let mut line = BrokenLine::soft(shaped.glyphs.clone());
+        line.hyphenated = true;
+
+        // 70px leaves room for "hello" (50px) plus the 10px ellipsis only
+        ellipsize(&mut line, &ellipsis, 70.0, &shaped, 0.0);
+        assert_eq!(line_text(&line), "hello\u{2026}");
+        assert!(line.hard_break && !line.hyphenated);
+        assert_eq!(line.glyphs.last().unwrap().cluster, 4);
+    }
+
+    #[test]
+    fn test_justify_line() {
+        let engine = TextLayoutEngine::new();
+        let options = LayoutOptions::default();
+
+        // "ab cd ef" is 70px wide with two 5px spaces
+        let line = engine.position_line(
+            create_mock_shaped_text("ab cd ef"),
+            0.0,
+            &options,
+            None,
+            Some(100.0),
+        );
+        assert!((line.width - 100.0).abs() < 0.01);
+        let last = line.glyphs.last().unwrap();
+        assert!((last.x - 90.0).abs() < 0.01);
+        // Letters within a word keep their spacing
+        assert!((line.glyphs[1].x - line.glyphs[0].x - 10.0).abs() < 0.01);
+
+        // Inter-character spreads space between every character
+        let options = LayoutOptions {
+            justify: TextJustify::InterCharacter,
+            ..Default::default()
+        };
+        let line = engine.position_line(
+            create_mock_shaped_text("abcd"),
+            0.0,
+            &options,
+            None,
+            Some(70.0),
+        );
+        let xs: Vec<f32> = line.glyphs.iter().map(|g| g.x).collect();
+        assert_eq!(xs, vec![0.0, 20.0, 40.0, 60.0]);
+    }
+
+    #[test]
+    fn test_white_space_modes() {
+        assert!(WhiteSpace::Normal.collapses_spaces() && WhiteSpace::Normal.wraps());
+        assert!(!WhiteSpace::NoWrap.wraps() && !WhiteSpace::NoWrap.preserves_newlines());
+        assert!(!WhiteSpace::Pre.collapses_spaces() && !WhiteSpace::Pre.wraps());
+        assert!(WhiteSpace::PreLine.collapses_spaces() && WhiteSpace::PreLine.preserves_newlines());
+        assert_eq!(
+            split_hard_lines(&create_mock_shaped_text("a\n\nb").glyphs).len(),
+            3
+        );
+    }
 }
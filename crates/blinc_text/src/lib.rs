@@ -21,9 +21,11 @@
 //! ```
 
 pub mod atlas;
+pub mod bidi;
 pub mod emoji;
 pub mod fallback;
 pub mod font;
+pub mod hyphenation;
 pub mod layout;
 pub mod rasterizer;
 pub mod registry;
@@ -32,10 +34,13 @@ pub mod shaper;
 
 use std::sync::{Arc, Mutex, OnceLock};
 
-pub use atlas::{AtlasRegion, ColorGlyphAtlas, GlyphAtlas, GlyphInfo};
+pub use atlas::{
+    AtlasRegion, AtlasStats, ColorGlyphAtlas, DirtyRegion, GlyphAtlas, GlyphInfo, PagedGlyphAtlas,
+};
+pub use bidi::{contains_rtl, detect_direction, visual_order, BidiRun, BidiText, TextDirection};
 pub use emoji::{contains_emoji, is_emoji, EmojiRenderer, EmojiSprite};
 pub use fallback::{fallback_bucket_key, FallbackCandidate, FallbackKind, FallbackResolver};
-pub use font::{Font, FontFace, FontMetrics, FontStyle, FontWeight};
+pub use font::{Font, FontFace, FontMetrics, FontStyle, FontWeight, VariationAxis};
 
 /// Global shared font registry singleton.
 ///
@@ -66,9 +71,10 @@ pub fn global_font_registry() -> Arc<Mutex<registry::FontRegistry>> {
 
 // Re-export html-escape for entity decoding
 pub use html_escape::decode_html_entities;
+pub use hyphenation::{hyphenator_for, register_hyphenator, Hyphenator};
 pub use layout::{
-    LayoutOptions, LineBreakMode, PositionedGlyph, TextAlignment, TextAnchor, TextLayout,
-    TextLayoutEngine,
+    Hyphens, LayoutOptions, LineBreakMode, PositionedGlyph, TextAlignment, TextAnchor, TextJustify,
+    TextLayout, TextLayoutEngine, TextOverflow, WhiteSpace,
 };
 pub use rasterizer::{GlyphFormat, GlyphRasterizer, RasterizedGlyph};
 pub use registry::{FontRegistry, GenericFont};
@@ -7,6 +7,7 @@
 
 use crate::font::FontFace;
 use crate::{Result, TextError};
+use blinc_core::FontVariation;
 use swash::scale::{Render, ScaleContext, Source, StrikeWith};
 use swash::zeno::Format;
 
@@ -58,6 +59,17 @@ impl GlyphRasterizer {
         font: &FontFace,
         glyph_id: u16,
         font_size: f32,
+    ) -> Result<RasterizedGlyph> {
+        self.rasterize_with_variations(font, glyph_id, font_size, &[])
+    }
+
+    /// Rasterize a glyph of a variable font at the given axis values
+    pub fn rasterize_with_variations(
+        &mut self,
+        font: &FontFace,
+        glyph_id: u16,
+        font_size: f32,
+        variations: &[FontVariation],
     ) -> Result<RasterizedGlyph> {
         // Get the raw font data and create a swash FontRef with correct face index
         let font_data = font.data();
@@ -69,6 +81,11 @@ impl GlyphRasterizer {
             .scale_context
             .builder(swash_font)
             .size(font_size)
+            .variations(
+                variations
+                    .iter()
+                    .map(|v| (u32::from_be_bytes(v.tag), v.value)),
+            )
             .build();
 
         // Get advance width from font metrics (scale from font units to pixels)
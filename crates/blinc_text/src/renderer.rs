@@ -6,7 +6,7 @@
 //! Supports automatic emoji font fallback - when the primary font doesn't
 //! have a glyph for an emoji character, the system emoji font is used.
 
-use crate::atlas::{ColorGlyphAtlas, GlyphAtlas, GlyphInfo};
+use crate::atlas::{AtlasStats, ColorGlyphAtlas, GlyphAtlas, GlyphInfo};
 use crate::emoji::is_emoji;
 use crate::font::{FontFace, FontStyle};
 use crate::layout::{LayoutOptions, PositionedGlyph, TextLayout, TextLayoutEngine};
@@ -14,6 +14,7 @@ use crate::rasterizer::GlyphRasterizer;
 use crate::registry::{FontRegistry, GenericFont};
 use crate::{Result, TextError};
 use lru::LruCache;
+use std::borrow::Cow;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 
@@ -34,6 +35,8 @@ pub struct GlyphInstance {
     pub color: [f32; 4],
     /// Whether this glyph is from the color atlas (emoji)
     pub is_color: bool,
+    /// Atlas page (texture array layer) holding the glyph
+    pub page: u32,
 }
 
 /// Result of preparing text for rendering
@@ -100,6 +103,7 @@ impl crate::fallback::FallbackWalkHandler for RenderFallbackWalker<'_> {
             self.font_id,
             glyph.glyph_id,
             self.font_size,
+            &self.options.font_settings.variations,
         )?;
         self.glyph_infos.push(Some(ResolvedGlyphData {
             info: glyph_info,
@@ -155,6 +159,7 @@ impl crate::fallback::FallbackWalkHandler for RenderFallbackWalker<'_> {
                 fallback_font_id,
                 fallback_gid,
                 self.font_size,
+                &[],
             )?;
             (info, false)
         };
@@ -178,6 +183,29 @@ impl crate::fallback::FallbackWalkHandler for RenderFallbackWalker<'_> {
     }
 }
 
+/// Resolve a variable font's axes for rendering
+///
+/// Returns the options with variations clamped to the font's axes (and
+/// `wght` taken from `weight` when unset), plus a font id that keys the
+/// glyph cache by those axis values.
+fn resolve_variations<'a>(
+    font: &FontFace,
+    font_id: u32,
+    options: &'a LayoutOptions,
+    weight: u16,
+) -> (Cow<'a, LayoutOptions>, u32) {
+    let variations = font.resolve_variations(&options.font_settings, weight);
+    if variations == options.font_settings.variations {
+        return (Cow::Borrowed(options), font_id);
+    }
+
+    let mut resolved = options.clone();
+    resolved.font_settings.variations = variations;
+    let key = resolved.font_settings.variation_key();
+    let font_id = font_id ^ (key as u32) ^ ((key >> 32) as u32);
+    (Cow::Owned(resolved), font_id)
+}
+
 /// Text renderer that manages fonts, atlas, and glyph rendering
 pub struct TextRenderer {
     /// Default font (legacy support)
@@ -387,12 +415,25 @@ impl TextRenderer {
         self.color_atlas.mark_clean();
     }
 
-    /// Get atlas pixel data for GPU upload (grayscale)
+    /// Advance both atlases to a new frame (drives glyph eviction)
+    ///
+    /// Call once per frame before preparing text.
+    pub fn begin_frame(&mut self) {
+        self.atlas.begin_frame();
+        self.color_atlas.begin_frame();
+    }
+
+    /// Usage statistics of the grayscale and color atlases
+    pub fn atlas_stats(&self) -> (AtlasStats, AtlasStats) {
+        (self.atlas.stats(), self.color_atlas.stats())
+    }
+
+    /// Get atlas pixel data of the first page for GPU upload (grayscale)
     pub fn atlas_pixels(&self) -> &[u8] {
         self.atlas.pixels()
     }
 
-    /// Get color atlas pixel data for GPU upload (RGBA)
+    /// Get color atlas pixel data of the first page for GPU upload (RGBA)
     pub fn color_atlas_pixels(&self) -> &[u8] {
         self.color_atlas.pixels()
     }
@@ -494,6 +535,8 @@ impl TextRenderer {
         // Resolve the font to use
         let font = self.resolve_font_with_style(font_name, generic, weight, italic)?;
         let font_id = self.font_id_with_style(font_name, generic, weight, italic);
+        let (options, font_id) = resolve_variations(&font, font_id, options, weight);
+        let options = options.as_ref();
         let resolved_weight = font.weight().to_number();
         let resolved_italic = matches!(font.style(), FontStyle::Italic | FontStyle::Oblique);
 
@@ -559,6 +602,7 @@ impl TextRenderer {
                 uv_bounds: uv,
                 color,
                 is_color: data.is_color,
+                page: data.info.page,
             });
         }
 
@@ -590,6 +634,8 @@ impl TextRenderer {
         let font = self.resolve_font(font_name, generic)?;
         let font_id = self.font_id(font_name, generic);
         let requested_weight = font.weight().to_number();
+        let (options, font_id) = resolve_variations(&font, font_id, options, requested_weight);
+        let options = options.as_ref();
         let requested_italic = matches!(font.style(), FontStyle::Italic | FontStyle::Oblique);
 
         // Get font metrics
@@ -670,6 +716,7 @@ impl TextRenderer {
                 uv_bounds: uv,
                 color,
                 is_color: data.is_color,
+                page: data.info.page,
             });
         }
 
@@ -793,18 +840,28 @@ impl TextRenderer {
         font_id: u32,
         glyph_id: u16,
         font_size: f32,
+        variations: &[blinc_core::FontVariation],
     ) -> Result<GlyphInfo> {
         // Quantize font size for cache key (0.5px granularity)
         let size_key = (font_size * 2.0).round() as u16;
         let cache_key = (font_id, glyph_id, size_key);
 
-        // Check cache first (LruCache::get promotes to most-recently-used)
-        if let Some(info) = self.glyph_cache.get(&cache_key) {
-            return Ok(*info);
+        // Check cache first (LruCache::get promotes to most-recently-used).
+        // Atlas glyphs can be evicted or moved, so re-resolve them there.
+        if let Some(info) = self.glyph_cache.get(&cache_key).copied() {
+            if info.region.width == 0 || info.region.height == 0 {
+                return Ok(info);
+            }
+            if let Some(info) = self.atlas.lookup(font_id, glyph_id, font_size) {
+                return Ok(info);
+            }
+            self.glyph_cache.pop(&cache_key);
         }
 
         // Rasterize the glyph
-        let rasterized = self.rasterizer.rasterize(font, glyph_id, font_size)?;
+        let rasterized = self
+            .rasterizer
+            .rasterize_with_variations(font, glyph_id, font_size, variations)?;
 
         // Handle empty glyphs (like space)
         if rasterized.width == 0 || rasterized.height == 0 {
@@ -815,6 +872,7 @@ impl TextRenderer {
                     width: 0,
                     height: 0,
                 },
+                page: 0,
                 bearing_x: rasterized.bearing_x,
                 bearing_y: rasterized.bearing_y,
                 advance: rasterized.advance,
@@ -855,8 +913,14 @@ impl TextRenderer {
         let cache_key = (font_id, glyph_id, size_key);
 
         // Check color cache first (LruCache::get promotes to most-recently-used)
-        if let Some(info) = self.color_glyph_cache.get(&cache_key) {
-            return Ok(*info);
+        if let Some(info) = self.color_glyph_cache.get(&cache_key).copied() {
+            if info.region.width == 0 || info.region.height == 0 {
+                return Ok(info);
+            }
+            if let Some(info) = self.color_atlas.lookup(font_id, glyph_id, font_size) {
+                return Ok(info);
+            }
+            self.color_glyph_cache.pop(&cache_key);
         }
 
         // Rasterize the glyph as color (RGBA)
@@ -871,6 +935,7 @@ impl TextRenderer {
                     width: 0,
                     height: 0,
                 },
+                page: 0,
                 bearing_x: rasterized.bearing_x,
                 bearing_y: rasterized.bearing_y,
                 advance: rasterized.advance,
@@ -905,7 +970,7 @@ impl TextRenderer {
             let mut registry = self.font_registry.lock().unwrap();
             registry.load_generic(GenericFont::SansSerif)?
         };
-        self.rasterize_glyph_for_font(&font, 0, glyph_id, font_size)
+        self.rasterize_glyph_for_font(&font, 0, glyph_id, font_size, &[])
     }
 
     /// Clear the glyph cache and atlas
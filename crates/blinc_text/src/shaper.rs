@@ -3,8 +3,11 @@
 //! Converts text strings into positioned glyph sequences with proper
 //! kerning, ligatures, and OpenType feature support.
 
+use crate::bidi::TextDirection;
 use crate::font::FontFace;
-use rustybuzz::{Face, UnicodeBuffer};
+use blinc_core::FontSettings;
+use rustybuzz::ttf_parser::Tag;
+use rustybuzz::{Direction, Face, Feature, GlyphBuffer, UnicodeBuffer, Variation};
 
 /// A shaped glyph with position information
 #[derive(Debug, Clone, Copy)]
@@ -79,41 +82,7 @@ impl TextShaper {
 
         // Shape the buffer
         let output = rustybuzz::shape(&face, &[], buffer);
-
-        // Extract glyph information
-        let glyph_infos = output.glyph_infos();
-        let glyph_positions = output.glyph_positions();
-
-        let mut glyphs = Vec::with_capacity(glyph_infos.len());
-        let mut total_advance = 0i32;
-
-        for (info, pos) in glyph_infos.iter().zip(glyph_positions.iter()) {
-            // Find the original character for this cluster
-            let codepoint = text
-                .char_indices()
-                .find(|(i, _)| *i as u32 == info.cluster)
-                .map(|(_, c)| c)
-                .unwrap_or('\u{FFFD}');
-
-            glyphs.push(ShapedGlyph {
-                glyph_id: info.glyph_id as u16,
-                codepoint,
-                x_offset: pos.x_offset,
-                y_offset: pos.y_offset,
-                x_advance: pos.x_advance,
-                y_advance: pos.y_advance,
-                cluster: info.cluster,
-            });
-
-            total_advance += pos.x_advance;
-        }
-
-        ShapedText {
-            glyphs,
-            total_advance,
-            font_size,
-            units_per_em: font_face.metrics().units_per_em,
-        }
+        Self::collect_glyphs(text, &output, font_face, font_size)
     }
 
     /// Fallback shaping when rustybuzz fails
@@ -163,7 +132,112 @@ impl TextShaper {
         buffer.push_str(text);
 
         let output = rustybuzz::shape(&face, features, buffer);
+        Self::collect_glyphs(text, &output, font_face, font_size)
+    }
 
+    /// Shape a single-direction bidi run
+    ///
+    /// The buffer direction is set explicitly and the script is inferred
+    /// from the run's content. Glyphs of right-to-left runs are returned in
+    /// visual order, as HarfBuzz produces them.
+    pub fn shape_run(
+        &self,
+        text: &str,
+        font_face: &FontFace,
+        font_size: f32,
+        direction: TextDirection,
+    ) -> ShapedText {
+        let face = match Face::from_slice(font_face.data(), font_face.face_index()) {
+            Some(f) => f,
+            None => {
+                let mut shaped = self.fallback_shape(text, font_face, font_size);
+                if direction.is_rtl() {
+                    shaped.glyphs.reverse();
+                }
+                return shaped;
+            }
+        };
+
+        let mut buffer = UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.set_direction(match direction {
+            TextDirection::Ltr => Direction::LeftToRight,
+            TextDirection::Rtl => Direction::RightToLeft,
+        });
+        buffer.guess_segment_properties();
+
+        let output = rustybuzz::shape(&face, &[], buffer);
+        Self::collect_glyphs(text, &output, font_face, font_size)
+    }
+
+    /// Shape with OpenType features and variable font axes applied
+    ///
+    /// With `direction: None` the direction and script are left for HarfBuzz
+    /// to guess, as in [`Self::shape`]; otherwise this behaves like
+    /// [`Self::shape_run`].
+    pub fn shape_with_settings(
+        &self,
+        text: &str,
+        font_face: &FontFace,
+        font_size: f32,
+        direction: Option<TextDirection>,
+        settings: &FontSettings,
+    ) -> ShapedText {
+        if settings.is_empty() {
+            return match direction {
+                Some(direction) => self.shape_run(text, font_face, font_size, direction),
+                None => self.shape(text, font_face, font_size),
+            };
+        }
+
+        let mut face = match Face::from_slice(font_face.data(), font_face.face_index()) {
+            Some(f) => f,
+            None => {
+                let mut shaped = self.fallback_shape(text, font_face, font_size);
+                if direction.is_some_and(TextDirection::is_rtl) {
+                    shaped.glyphs.reverse();
+                }
+                return shaped;
+            }
+        };
+        if !settings.variations.is_empty() {
+            let variations: Vec<Variation> = settings
+                .variations
+                .iter()
+                .map(|v| Variation {
+                    tag: Tag::from_bytes(&v.tag),
+                    value: v.value,
+                })
+                .collect();
+            face.set_variations(&variations);
+        }
+        let features: Vec<Feature> = settings
+            .features
+            .iter()
+            .map(|f| Feature::new(Tag::from_bytes(&f.tag), f.value, ..))
+            .collect();
+
+        let mut buffer = UnicodeBuffer::new();
+        buffer.push_str(text);
+        if let Some(direction) = direction {
+            buffer.set_direction(match direction {
+                TextDirection::Ltr => Direction::LeftToRight,
+                TextDirection::Rtl => Direction::RightToLeft,
+            });
+            buffer.guess_segment_properties();
+        }
+
+        let output = rustybuzz::shape(&face, &features, buffer);
+        Self::collect_glyphs(text, &output, font_face, font_size)
+    }
+
+    /// Convert a HarfBuzz glyph buffer into shaped glyphs
+    fn collect_glyphs(
+        text: &str,
+        output: &GlyphBuffer,
+        font_face: &FontFace,
+        font_size: f32,
+    ) -> ShapedText {
         let glyph_infos = output.glyph_infos();
         let glyph_positions = output.glyph_positions();
 
@@ -126,6 +126,7 @@ where
     mouse_position: (f32, f32),
     last_scroll_event_at: Option<Instant>,
     scroll_end_pending: bool,
+    ime_composition: input::ImeComposition,
     should_exit: bool,
 }
 
@@ -142,6 +143,7 @@ where
             mouse_position: (0.0, 0.0),
             last_scroll_event_at: None,
             scroll_end_pending: false,
+            ime_composition: input::ImeComposition::Idle,
             should_exit: false,
         }
     }
@@ -200,6 +202,19 @@ where
         _window_id: WindowId,
         event: WinitWindowEvent,
     ) {
+        // A cleared preedit is followed by its commit straight away; other
+        // input first means the composition was dismissed
+        if matches!(
+            event,
+            WinitWindowEvent::KeyboardInput { .. }
+                | WinitWindowEvent::MouseInput { .. }
+                | WinitWindowEvent::Focused(_)
+        ) {
+            if let Some(cancel) = input::finish_ime_composition(&mut self.ime_composition) {
+                self.handle_event(Event::Input(cancel));
+            }
+        }
+
         match event {
             WinitWindowEvent::CloseRequested => {
                 self.handle_event(Event::Window(WindowEvent::CloseRequested));
@@ -253,6 +268,15 @@ where
                 }
             }
 
+            WinitWindowEvent::Ime(ime) => {
+                for input_event in input::convert_ime_event(&ime, &mut self.ime_composition) {
+                    self.handle_event(Event::Input(input_event));
+                }
+                if let Some(ref window) = self.window {
+                    window.request_redraw();
+                }
+            }
+
             WinitWindowEvent::CursorMoved { position, .. } => {
                 self.mouse_position = (position.x as f32, position.y as f32);
                 let input_event = input::mouse_moved(self.mouse_position.0, self.mouse_position.1);
@@ -365,7 +389,98 @@ where
 #[cfg(test)]
 mod tests {
     use super::{scroll_end_deadline, should_emit_synthetic_scroll_end, SCROLL_END_DEBOUNCE};
+    use crate::input::{convert_ime_event, finish_ime_composition, ImeComposition};
+    use blinc_platform::{ImeEvent, InputEvent};
     use std::time::{Duration, Instant};
+    use winit::event::Ime;
+
+    fn ime_events(events: Vec<InputEvent>) -> Vec<ImeEvent> {
+        events
+            .into_iter()
+            .filter_map(|e| match e {
+                InputEvent::Ime(ime) => Some(ime),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn ime_preedit_opens_and_commit_closes_composition() {
+        let mut composition = ImeComposition::Idle;
+
+        // "日本" is 6 bytes; a caret at byte 3 is char 1
+        let events = ime_events(convert_ime_event(
+            &Ime::Preedit("日本".into(), Some((3, 3))),
+            &mut composition,
+        ));
+        assert_eq!(
+            events,
+            vec![
+                ImeEvent::Start,
+                ImeEvent::Update {
+                    text: "日本".into(),
+                    cursor: Some((1, 1)),
+                },
+            ]
+        );
+        assert_eq!(composition, ImeComposition::Composing);
+
+        // winit clears the preedit before committing; that only clears the text
+        let events = ime_events(convert_ime_event(
+            &Ime::Preedit(String::new(), None),
+            &mut composition,
+        ));
+        assert_eq!(
+            events,
+            vec![ImeEvent::Update {
+                text: String::new(),
+                cursor: None,
+            }]
+        );
+
+        let events = ime_events(convert_ime_event(
+            &Ime::Commit("日本".into()),
+            &mut composition,
+        ));
+        assert_eq!(events, vec![ImeEvent::Commit("日本".into())]);
+        assert_eq!(composition, ImeComposition::Idle);
+        assert!(finish_ime_composition(&mut composition).is_none());
+
+        // Disabling without an open composition emits nothing
+        assert!(convert_ime_event(&Ime::Disabled, &mut composition).is_empty());
+    }
+
+    #[test]
+    fn ime_composition_cancels_without_commit() {
+        let mut composition = ImeComposition::Idle;
+        convert_ime_event(&Ime::Preedit("か".into(), None), &mut composition);
+
+        // Disabling the IME mid-composition cancels it
+        let events = ime_events(convert_ime_event(&Ime::Disabled, &mut composition));
+        assert_eq!(events, vec![ImeEvent::Cancel]);
+
+        // A cleared preedit followed by other input was dismissed
+        convert_ime_event(&Ime::Preedit("か".into(), None), &mut composition);
+        convert_ime_event(&Ime::Preedit(String::new(), None), &mut composition);
+        assert_eq!(composition, ImeComposition::Cleared);
+        let events = ime_events(
+            finish_ime_composition(&mut composition)
+                .into_iter()
+                .collect(),
+        );
+        assert_eq!(events, vec![ImeEvent::Cancel]);
+        assert_eq!(composition, ImeComposition::Idle);
+
+        // Typing the preedit again after clearing it continues the composition
+        convert_ime_event(&Ime::Preedit("か".into(), None), &mut composition);
+        convert_ime_event(&Ime::Preedit(String::new(), None), &mut composition);
+        let events = ime_events(convert_ime_event(
+            &Ime::Preedit("き".into(), None),
+            &mut composition,
+        ));
+        assert!(!events.contains(&ImeEvent::Start));
+        assert_eq!(composition, ImeComposition::Composing);
+    }
 
     #[test]
     fn synthetic_scroll_end_requires_pending_and_elapsed_threshold() {
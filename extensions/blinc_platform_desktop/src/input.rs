@@ -1,10 +1,10 @@
 //! Desktop input conversion (winit -> blinc_platform)
 
 use blinc_platform::{
-    InputEvent, Key, KeyState, KeyboardEvent, Modifiers, MouseButton, MouseEvent, ScrollPhase,
-    TouchEvent,
+    ImeEvent, InputEvent, Key, KeyState, KeyboardEvent, Modifiers, MouseButton, MouseEvent,
+    ScrollPhase, TouchEvent,
 };
-use winit::event::{ElementState, MouseButton as WinitMouseButton, Touch, TouchPhase};
+use winit::event::{ElementState, Ime, MouseButton as WinitMouseButton, Touch, TouchPhase};
 use winit::keyboard::{Key as WinitKey, ModifiersState, NamedKey};
 
 /// Convert winit mouse button to blinc MouseButton
@@ -152,6 +152,77 @@ pub fn convert_keyboard_event(
     })
 }
 
+/// Where a winit IME composition stands between events
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ImeComposition {
+    /// No composition open
+    #[default]
+    Idle,
+    /// Preedit text is showing
+    Composing,
+    /// The preedit was emptied; winit does this right before every commit
+    Cleared,
+}
+
+/// Convert a winit IME event into blinc composition events
+///
+/// winit has no explicit start/cancel, so `composition` tracks whether one is
+/// open: the first non-empty preedit starts it and an empty preedit only
+/// clears the preedit text, since winit sends one before each commit.
+/// Disabling the IME cancels an open composition; so does other input after
+/// a cleared preedit (see [`finish_ime_composition`]). Preedit cursor byte
+/// offsets are converted to char offsets.
+pub fn convert_ime_event(ime: &Ime, composition: &mut ImeComposition) -> Vec<InputEvent> {
+    let mut events = Vec::new();
+    match ime {
+        Ime::Enabled => {}
+        Ime::Preedit(text, cursor) if !text.is_empty() => {
+            if *composition == ImeComposition::Idle {
+                events.push(ImeEvent::Start);
+            }
+            *composition = ImeComposition::Composing;
+            let to_chars = |byte: usize| text[..byte.min(text.len())].chars().count();
+            events.push(ImeEvent::Update {
+                text: text.clone(),
+                cursor: cursor.map(|(start, end)| (to_chars(start), to_chars(end))),
+            });
+        }
+        Ime::Preedit(..) => {
+            if *composition == ImeComposition::Composing {
+                *composition = ImeComposition::Cleared;
+                events.push(ImeEvent::Update {
+                    text: String::new(),
+                    cursor: None,
+                });
+            }
+        }
+        Ime::Disabled => {
+            if *composition != ImeComposition::Idle {
+                *composition = ImeComposition::Idle;
+                events.push(ImeEvent::Cancel);
+            }
+        }
+        Ime::Commit(text) => {
+            *composition = ImeComposition::Idle;
+            events.push(ImeEvent::Commit(text.clone()));
+        }
+    }
+    events.into_iter().map(InputEvent::Ime).collect()
+}
+
+/// Cancel a composition whose preedit was cleared without a commit
+///
+/// winit delivers the commit straight after the empty preedit, so when other
+/// input (a key press, click or focus change) arrives first the user
+/// dismissed the composition instead.
+pub fn finish_ime_composition(composition: &mut ImeComposition) -> Option<InputEvent> {
+    if *composition != ImeComposition::Cleared {
+        return None;
+    }
+    *composition = ImeComposition::Idle;
+    Some(InputEvent::Ime(ImeEvent::Cancel))
+}
+
 /// Convert winit touch event to blinc InputEvent
 pub fn convert_touch_event(touch: &Touch) -> InputEvent {
     let id = touch.id;
@@ -3,7 +3,7 @@
 use blinc_platform::{Cursor, Window, WindowConfig};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use winit::dpi::LogicalSize;
+use winit::dpi::{LogicalPosition, LogicalSize};
 use winit::event_loop::ActiveEventLoop;
 use winit::window::{Window as WinitWindow, WindowAttributes};
 
@@ -114,6 +114,15 @@ impl Window for DesktopWindow {
     fn is_visible(&self) -> bool {
         self.window.is_visible().unwrap_or(true)
     }
+
+    fn set_ime_allowed(&self, allowed: bool) {
+        self.window.set_ime_allowed(allowed);
+    }
+
+    fn set_ime_cursor_area(&self, x: f32, y: f32, width: f32, height: f32) {
+        self.window
+            .set_ime_cursor_area(LogicalPosition::new(x, y), LogicalSize::new(width, height));
+    }
 }
 
 // Safety: Window operations are thread-safe via winit's internal synchronization